@@ -0,0 +1,13 @@
+// Build script - compiles the protobuf definitions of the gRPC server and the published user events
+// A vendored protoc is used so no system installation is required
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::var_os("PROTOC").is_none() {
+        // SAFETY: build scripts are single-threaded at this point
+        unsafe { std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?) };
+    }
+
+    tonic_prost_build::compile_protos("proto/user_api.proto")?;
+    tonic_prost_build::compile_protos("proto/user_events.proto")?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+// Email rules shared by the frontend (raw input) and the backend (normalized):
+// normalizing is idempotent and never changes whether an email is accepted
+
+use libfuzzer_sys::fuzz_target;
+use shared::validation::{normalize_email, validate_email};
+
+fuzz_target!(|email: &str| {
+    let normalized = normalize_email(email);
+    assert_eq!(normalize_email(&normalized), normalized);
+    assert_eq!(validate_email(email).is_ok(), validate_email(&normalized).is_ok(), "{:?}", email);
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+// JSON body of POST /users: any bytes either fail to parse or give a user
+// that validates without panicking and that normalizing twice leaves as is
+
+use backend::models::User;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(user) = serde_json::from_slice::<User>(data) else {
+        return;
+    };
+    let normalized = user.normalized();
+    let _ = normalized.validate();
+    assert_eq!(normalized.clone().normalized(), normalized);
+});
@@ -0,0 +1,67 @@
+# Error messages of the API, keyed by message id.
+# These English messages are the reference: they must read exactly as the
+# backend writes them, so they can be recognized and translated.
+
+name-empty = Name cannot be empty
+email-empty = Email cannot be empty
+email-invalid = Invalid email format
+password-empty = Password cannot be empty
+password-too-short = Password must be at least { $min } characters
+password-breached = Password has appeared in a data breach
+password-reused = Password must differ from the last { $count } passwords
+user-not-found = User with id { $id } not found
+user-email-not-found = No user with email { $email }
+user-email-taken = A user with email { $email } already exists
+user-already-anonymized = User with id { $id } is already anonymized
+search-query-empty = Search query cannot be empty
+search-encrypted = Search is unavailable while names and emails are encrypted
+webhook-not-found = Webhook with id { $id } not found
+request-not-understood = The request could not be understood
+csrf-invalid = Missing or invalid CSRF token
+admin-disabled = The admin API is disabled
+admin-token-invalid = Missing or invalid admin token
+route-not-found = No route matches { $route }
+body-too-large = The request body is too large
+body-malformed = The request body is malformed
+too-many-requests = Too many requests, try again later
+verification-token-invalid = Invalid or expired verification token
+captcha-missing = CAPTCHA token is missing
+captcha-failed = CAPTCHA verification failed
+captcha-unavailable = CAPTCHA provider is unavailable
+query-timeout = The database did not answer in time
+audit-trail-broken = Audit trail is broken at entry { $id }
+notification-not-found = Notification with id { $id } not found
+client-error-message-empty = Error report message cannot be empty
+preference-theme-invalid = Theme must be light, dark or system
+preference-locale-invalid = Locale must be en, pt-BR or es
+preference-page-size-invalid = Page size must be between 1 and { $max }
+preference-column-unknown = Unknown column { $column }
+preference-sort-unknown = Unknown sort { $sort }
+preference-status-unknown = Unknown status { $status }
+preference-views-too-many = At most { $max } views can be saved
+preference-view-name-empty = View name cannot be empty
+preference-view-name-too-long = View names must be at most { $max } characters
+preference-view-search-too-long = View searches must be at most { $max } characters
+preference-view-duplicate = View { $name } is saved twice
+phone-invalid = Phone number must be in international format, e.g. +5511912345678
+address-street-empty = Street cannot be empty
+address-city-empty = City cannot be empty
+address-postal-code-invalid = Invalid postal code
+address-country-invalid = Country must be a two-letter ISO 3166 code, e.g. BR
+address-too-long = Address fields must be at most { $max } characters
+username-too-short = Username must be at least { $min } characters
+username-too-long = Username must be at most { $max } characters
+username-invalid = Username may only contain lowercase letters, digits and single dashes between them
+username-taken = Username { $username } is already taken
+username-immutable = Username cannot be changed
+user-username-not-found = No user with username { $username }
+tag-empty = Tag cannot be empty
+tag-too-long = Tags must be at most { $max } characters
+tag-invalid = Tags may only contain lowercase letters, digits and single dashes between them
+tags-too-many = A user can have at most { $max } tags
+bulk-no-ids = A bulk update needs at least one id
+bulk-too-many = A bulk update can change at most { $max } users
+bulk-empty = A bulk update must change at least one field
+export-no-ids = An export needs at least one id
+export-too-many = An export can include at most { $max } users
+maintenance-mode = The service is read-only for maintenance, try again later
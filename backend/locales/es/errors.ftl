@@ -0,0 +1,63 @@
+name-empty = El nombre no puede estar vacío
+email-empty = El correo electrónico no puede estar vacío
+email-invalid = Formato de correo electrónico no válido
+password-empty = La contraseña no puede estar vacía
+password-too-short = La contraseña debe tener al menos { $min } caracteres
+password-breached = La contraseña ha aparecido en una filtración de datos
+password-reused = La contraseña debe ser distinta de las últimas { $count } contraseñas
+user-not-found = No se encontró el usuario con id { $id }
+user-email-not-found = Ningún usuario con el correo { $email }
+user-email-taken = Ya existe un usuario con el correo { $email }
+user-already-anonymized = El usuario con id { $id } ya fue anonimizado
+search-query-empty = La búsqueda no puede estar vacía
+search-encrypted = La búsqueda no está disponible mientras los nombres y correos están cifrados
+webhook-not-found = No se encontró el webhook con id { $id }
+request-not-understood = No se pudo entender la solicitud
+csrf-invalid = Token CSRF ausente o no válido
+admin-disabled = La API de administración está desactivada
+admin-token-invalid = Token de administración ausente o no válido
+route-not-found = Ninguna ruta coincide con { $route }
+body-too-large = El cuerpo de la solicitud es demasiado grande
+body-malformed = El cuerpo de la solicitud está mal formado
+too-many-requests = Demasiadas solicitudes, inténtalo de nuevo más tarde
+verification-token-invalid = Token de verificación inválido o caducado
+captcha-missing = Falta el token del CAPTCHA
+captcha-failed = La verificación del CAPTCHA falló
+captcha-unavailable = El proveedor de CAPTCHA no está disponible
+query-timeout = La base de datos no respondió a tiempo
+audit-trail-broken = El registro de auditoría está roto en la entrada { $id }
+notification-not-found = Notificación con id { $id } no encontrada
+client-error-message-empty = El mensaje del informe de error no puede estar vacío
+preference-theme-invalid = El tema debe ser light, dark o system
+preference-locale-invalid = El idioma debe ser en, pt-BR o es
+preference-page-size-invalid = El tamaño de página debe estar entre 1 y { $max }
+preference-column-unknown = Columna desconocida { $column }
+preference-sort-unknown = Orden desconocido { $sort }
+preference-status-unknown = Estado desconocido { $status }
+preference-views-too-many = Se pueden guardar como máximo { $max } vistas
+preference-view-name-empty = El nombre de la vista no puede estar vacío
+preference-view-name-too-long = Los nombres de vista deben tener como máximo { $max } caracteres
+preference-view-search-too-long = Las búsquedas de vista deben tener como máximo { $max } caracteres
+preference-view-duplicate = La vista { $name } está guardada dos veces
+phone-invalid = El teléfono debe estar en formato internacional, p. ej. +5511912345678
+address-street-empty = La calle no puede estar vacía
+address-city-empty = La ciudad no puede estar vacía
+address-postal-code-invalid = Código postal no válido
+address-country-invalid = El país debe ser un código ISO 3166 de dos letras, p. ej. BR
+address-too-long = Los campos de la dirección deben tener como máximo { $max } caracteres
+username-too-short = El nombre de usuario debe tener al menos { $min } caracteres
+username-too-long = El nombre de usuario debe tener como máximo { $max } caracteres
+username-invalid = El nombre de usuario solo puede contener letras minúsculas, dígitos y guiones simples entre ellos
+username-taken = El nombre de usuario { $username } ya está en uso
+username-immutable = El nombre de usuario no se puede cambiar
+user-username-not-found = Ningún usuario con el nombre de usuario { $username }
+tag-empty = La etiqueta no puede estar vacía
+tag-too-long = Las etiquetas deben tener como máximo { $max } caracteres
+tag-invalid = Las etiquetas solo pueden contener letras minúsculas, dígitos y guiones simples entre ellos
+tags-too-many = Un usuario puede tener como máximo { $max } etiquetas
+bulk-no-ids = Una actualización masiva necesita al menos un id
+bulk-too-many = Una actualización masiva puede cambiar como máximo { $max } usuarios
+bulk-empty = Una actualización masiva debe cambiar al menos un campo
+export-no-ids = Una exportación necesita al menos un id
+export-too-many = Una exportación puede incluir como máximo { $max } usuarios
+maintenance-mode = El servicio está en modo de solo lectura por mantenimiento, inténtalo más tarde
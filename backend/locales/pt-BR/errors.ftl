@@ -0,0 +1,63 @@
+name-empty = O nome não pode ficar vazio
+email-empty = O e-mail não pode ficar vazio
+email-invalid = Formato de e-mail inválido
+password-empty = A senha não pode ficar vazia
+password-too-short = A senha deve ter pelo menos { $min } caracteres
+password-breached = A senha apareceu em um vazamento de dados
+password-reused = A senha deve ser diferente das últimas { $count } senhas
+user-not-found = Usuário com id { $id } não encontrado
+user-email-not-found = Nenhum usuário com o e-mail { $email }
+user-email-taken = Já existe um usuário com o e-mail { $email }
+user-already-anonymized = O usuário com id { $id } já foi anonimizado
+search-query-empty = O termo de busca não pode ficar vazio
+search-encrypted = A busca não está disponível enquanto nomes e e-mails estão criptografados
+webhook-not-found = Webhook com id { $id } não encontrado
+request-not-understood = A requisição não pôde ser entendida
+csrf-invalid = Token CSRF ausente ou inválido
+admin-disabled = A API de administração está desativada
+admin-token-invalid = Token de administração ausente ou inválido
+route-not-found = Nenhuma rota corresponde a { $route }
+body-too-large = O corpo da requisição é grande demais
+body-malformed = O corpo da requisição está malformado
+too-many-requests = Muitas requisições, tente novamente mais tarde
+verification-token-invalid = Token de verificação inválido ou expirado
+captcha-missing = O token do CAPTCHA está ausente
+captcha-failed = A verificação do CAPTCHA falhou
+captcha-unavailable = O provedor de CAPTCHA está indisponível
+query-timeout = O banco de dados não respondeu a tempo
+audit-trail-broken = A trilha de auditoria está quebrada na entrada { $id }
+notification-not-found = Notificação com id { $id } não encontrada
+client-error-message-empty = A mensagem do relatório de erro não pode estar vazia
+preference-theme-invalid = O tema deve ser light, dark ou system
+preference-locale-invalid = O idioma deve ser en, pt-BR ou es
+preference-page-size-invalid = O tamanho da página deve estar entre 1 e { $max }
+preference-column-unknown = Coluna desconhecida { $column }
+preference-sort-unknown = Ordenação desconhecida { $sort }
+preference-status-unknown = Status desconhecido { $status }
+preference-views-too-many = É possível salvar no máximo { $max } visualizações
+preference-view-name-empty = O nome da visualização não pode ficar vazio
+preference-view-name-too-long = Nomes de visualização devem ter no máximo { $max } caracteres
+preference-view-search-too-long = Buscas de visualização devem ter no máximo { $max } caracteres
+preference-view-duplicate = A visualização { $name } foi salva duas vezes
+phone-invalid = O telefone deve estar no formato internacional, ex.: +5511912345678
+address-street-empty = O logradouro não pode ficar vazio
+address-city-empty = A cidade não pode ficar vazia
+address-postal-code-invalid = CEP inválido
+address-country-invalid = O país deve ser um código ISO 3166 de duas letras, ex.: BR
+address-too-long = Os campos do endereço devem ter no máximo { $max } caracteres
+username-too-short = O nome de usuário deve ter pelo menos { $min } caracteres
+username-too-long = O nome de usuário deve ter no máximo { $max } caracteres
+username-invalid = O nome de usuário só pode conter letras minúsculas, dígitos e hifens simples entre eles
+username-taken = O nome de usuário { $username } já está em uso
+username-immutable = O nome de usuário não pode ser alterado
+user-username-not-found = Nenhum usuário com o nome de usuário { $username }
+tag-empty = A tag não pode ficar vazia
+tag-too-long = As tags devem ter no máximo { $max } caracteres
+tag-invalid = As tags só podem conter letras minúsculas, dígitos e hifens simples entre eles
+tags-too-many = Um usuário pode ter no máximo { $max } tags
+bulk-no-ids = Uma atualização em massa precisa de pelo menos um id
+bulk-too-many = Uma atualização em massa pode alterar no máximo { $max } usuários
+bulk-empty = Uma atualização em massa deve alterar pelo menos um campo
+export-no-ids = Uma exportação precisa de pelo menos um id
+export-too-many = Uma exportação pode incluir no máximo { $max } usuários
+maintenance-mode = O serviço está somente leitura para manutenção, tente novamente mais tarde
@@ -0,0 +1,12 @@
+-- Migration: Add timestamps and email verification flag to users table
+-- Date: 2026-10-17
+-- Description: Tracks when users sign up / change and whether their email is verified,
+-- needed by the admin statistics endpoint
+
+-- Existing rows get the migration time as their creation date
+ALTER TABLE users ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE users ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Speeds up the signups-per-day aggregate
+CREATE INDEX IF NOT EXISTS users_created_at_idx ON users (created_at);
@@ -0,0 +1,15 @@
+-- Migration: Create webhooks table
+-- Date: 2026-10-17
+-- Description: Endpoints notified of user lifecycle events (user.created,
+-- user.updated, user.deleted); payloads are signed with the per-webhook secret
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id SERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Speeds up looking up the webhooks subscribed to an event
+CREATE INDEX IF NOT EXISTS webhooks_events_idx ON webhooks USING GIN (events);
@@ -0,0 +1,22 @@
+-- Migration: Notify user changes
+-- Date: 2026-10-17
+-- Description: Publishes every insert/update/delete on users to the `user_changes`
+-- channel so all backend instances can forward them to their SSE clients
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    -- The password never leaves the database
+    PERFORM pg_notify('user_changes', json_build_object(
+        'op', lower(TG_OP), 'id', NEW.id, 'name', NEW.name, 'email', NEW.email
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER users_notify_change
+    AFTER INSERT OR UPDATE OR DELETE ON users
+    FOR EACH ROW EXECUTE FUNCTION notify_user_change();
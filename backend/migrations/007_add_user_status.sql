@@ -0,0 +1,22 @@
+-- Migration: Add account status to users table
+-- Date: 2026-10-17
+-- Description: Lets admins suspend and reactivate accounts; the status is also
+-- included in the user change notifications
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'active'
+    CHECK (status IN ('active', 'suspended'));
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    -- The password never leaves the database
+    PERFORM pg_notify('user_changes', json_build_object(
+        'op', lower(TG_OP), 'id', NEW.id, 'name', NEW.name, 'email', NEW.email,
+        'status', NEW.status
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
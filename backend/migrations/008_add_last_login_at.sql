@@ -0,0 +1,21 @@
+-- Migration: Track the last login of each user
+-- Date: 2026-10-17
+-- Description: NULL until the user logs in for the first time
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_login_at TIMESTAMPTZ;
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    -- The password never leaves the database
+    PERFORM pg_notify('user_changes', json_build_object(
+        'op', lower(TG_OP), 'id', NEW.id, 'name', NEW.name, 'email', NEW.email,
+        'status', NEW.status,
+        'last_login_at', to_char(NEW.last_login_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
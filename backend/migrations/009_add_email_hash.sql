@@ -0,0 +1,7 @@
+-- Migration: Lookup hash for encrypted emails
+-- Date: 2026-10-17
+-- Description: HMAC of the email, filled in when PII encryption is enabled.
+-- Encrypted emails are random, so uniqueness is enforced on the hash.
+-- Run `cargo run --bin admin -- rekey-pii` afterwards to encrypt existing rows
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_hash TEXT UNIQUE;
@@ -0,0 +1,10 @@
+-- Migration: Case-insensitive unique emails
+-- Date: 2026-10-17
+-- Description: Stores emails lowercased and makes `Email@Example.com` and
+-- `email@example.com` the same address for the unique index.
+-- Note: This fails if two users only differ by the case of their email.
+-- List them first with `cargo run --bin admin -- duplicate-emails`
+
+UPDATE users SET email = LOWER(TRIM(email)) WHERE email <> LOWER(TRIM(email));
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_email_lower_unique ON users (LOWER(email));
@@ -0,0 +1,10 @@
+-- Migration: Fuzzy user search
+-- Date: 2026-10-17
+-- Description: Trigram indexes on names and emails for `GET /api/v1/users/search`,
+-- which matches with pg_trgm word similarity and so tolerates typos.
+-- Note: Search is unavailable while PII encryption is enabled
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS users_name_trgm ON users USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS users_email_trgm ON users USING GIN (email gin_trgm_ops);
@@ -0,0 +1,14 @@
+-- Migration: Create audit_log table
+-- Date: 2026-10-17
+-- Description: Per-user history of lifecycle events, written by the audit-log
+-- event subscriber and served by `GET /api/v1/users/<id>/activity`.
+-- Note: user_id has no foreign key so entries outlive deleted users
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_user_time ON audit_log (user_id, occurred_at DESC);
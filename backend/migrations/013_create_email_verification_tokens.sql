@@ -0,0 +1,11 @@
+-- Migration: Create email_verification_tokens table
+-- Date: 2026-10-17
+-- Description: Single-use tokens of the confirmation links emailed on signup.
+-- `POST /api/v1/signup/verify` consumes a token and sets users.email_verified.
+-- Note: tokens are removed with their user
+
+CREATE TABLE IF NOT EXISTS email_verification_tokens (
+    token TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,23 @@
+-- Migration: Hash-chain the audit_log table
+-- Date: 2026-10-17
+-- Description: Every new entry stores the SHA-256 hash of the previous entry (`prev_hash`)
+-- and its own (`hash`), so `GET /api/v1/audit/verify` can detect altered, inserted or removed
+-- entries. The unique index on prev_hash stops concurrent writers from forking the chain,
+-- and a trigger rejects updates and deletes.
+-- Note: entries written before this migration keep NULL hashes and are reported as unchained
+
+ALTER TABLE audit_log
+    ADD COLUMN IF NOT EXISTS prev_hash TEXT,
+    ADD COLUMN IF NOT EXISTS hash TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS audit_log_prev_hash ON audit_log (prev_hash);
+
+CREATE OR REPLACE FUNCTION reject_audit_log_change() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_append_only ON audit_log;
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+FOR EACH ROW EXECUTE FUNCTION reject_audit_log_change();
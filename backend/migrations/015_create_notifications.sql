@@ -0,0 +1,16 @@
+-- Migration: Create notifications table
+-- Date: 2026-10-17
+-- Description: Per-user notification inbox, written by the notification event subscriber
+-- (welcome on signup, password changed) and served by `GET /api/v1/users/<id>/notifications`.
+-- A notification is unread while read_at is NULL.
+-- Note: notifications are removed with their user
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS notifications_user_time ON notifications (user_id, created_at DESC);
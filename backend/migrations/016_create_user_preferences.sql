@@ -0,0 +1,12 @@
+-- Migration: Create user_preferences table
+-- Date: 2026-10-17
+-- Description: UI preferences of each user (theme, locale, page size, user list columns),
+-- stored as one JSONB document and served by `GET/PUT /api/v1/users/<id>/preferences`
+-- so they roam across devices. Keys left out keep the frontend defaults.
+-- Note: preferences are removed with their user
+
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    preferences JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
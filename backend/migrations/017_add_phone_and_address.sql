@@ -0,0 +1,25 @@
+-- Migration: Add phone and address to users
+-- Date: 2026-10-17
+-- Description: Optional E.164 phone number and postal address of each user. The address
+-- is stored as JSON text rather than JSONB so that, like name and email, both columns can
+-- hold `enc:` values when PII_ENCRYPTION_KEY is set. NULL when not given
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS phone TEXT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS address TEXT;
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    -- The password never leaves the database
+    PERFORM pg_notify('user_changes', json_build_object(
+        'op', lower(TG_OP), 'id', NEW.id, 'name', NEW.name, 'email', NEW.email,
+        'status', NEW.status,
+        'last_login_at', to_char(NEW.last_login_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"'),
+        'phone', NEW.phone, 'address', NEW.address
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
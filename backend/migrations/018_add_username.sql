@@ -0,0 +1,26 @@
+-- Migration: Add username to users
+-- Date: 2026-10-17
+-- Description: Unique handle of the profile URL (`/users/@username`). Usernames are
+-- public, so unlike name and email they are never encrypted, and the unique index
+-- works on the column itself. Lowercase letters, digits and single dashes between
+-- them, 3 to 30 characters. NULL for users who have not picked one yet
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS username TEXT UNIQUE
+    CHECK (username ~ '^[a-z0-9]+(-[a-z0-9]+)*$' AND length(username) BETWEEN 3 AND 30);
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    -- The password never leaves the database
+    PERFORM pg_notify('user_changes', json_build_object(
+        'op', lower(TG_OP), 'id', NEW.id, 'name', NEW.name, 'email', NEW.email,
+        'status', NEW.status,
+        'last_login_at', to_char(NEW.last_login_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"'),
+        'phone', NEW.phone, 'address', NEW.address, 'username', NEW.username
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
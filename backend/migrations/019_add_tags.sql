@@ -0,0 +1,27 @@
+-- Migration: Add tags to users
+-- Date: 2026-10-17
+-- Description: Free-form labels for filtering the user list, e.g. `vip` or `beta`.
+-- Stored normalized (lowercase letters, digits and single dashes between them, sorted
+-- and without duplicates), so the GIN index answers `tags @> ARRAY['vip']` directly.
+-- The change feed payload now carries them too
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS users_tags ON users USING GIN (tags);
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    -- The password never leaves the database
+    PERFORM pg_notify('user_changes', json_build_object(
+        'op', lower(TG_OP), 'id', NEW.id, 'name', NEW.name, 'email', NEW.email,
+        'status', NEW.status,
+        'last_login_at', to_char(NEW.last_login_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"'),
+        'phone', NEW.phone, 'address', NEW.address, 'username', NEW.username, 'tags', NEW.tags
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
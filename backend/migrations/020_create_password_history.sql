@@ -0,0 +1,15 @@
+-- Migration: Create password_history table
+-- Date: 2026-10-17
+-- Description: Salted hashes of the passwords each user had, newest last by id, so
+-- a change or reset can reject the last `PASSWORD_HISTORY` passwords. Only that many
+-- rows are kept per user; older ones are deleted when a password is recorded.
+-- Note: the history is removed with its user and cleared on anonymization
+
+CREATE TABLE IF NOT EXISTS password_history (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS password_history_user ON password_history (user_id, id DESC);
@@ -0,0 +1,23 @@
+-- Migration: Notify user timestamps
+-- Date: 2026-10-17
+-- Description: The change feed payload now carries `created_at` and `updated_at`,
+-- so users pushed to the frontend keep the timestamps shown on their profile
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    -- The password never leaves the database
+    PERFORM pg_notify('user_changes', json_build_object(
+        'op', lower(TG_OP), 'id', NEW.id, 'name', NEW.name, 'email', NEW.email,
+        'status', NEW.status,
+        'last_login_at', to_char(NEW.last_login_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"'),
+        'created_at', to_char(NEW.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"'),
+        'updated_at', to_char(NEW.updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"'),
+        'phone', NEW.phone, 'address', NEW.address, 'username', NEW.username, 'tags', NEW.tags
+    )::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,17 @@
+-- Migration: Notify user ids only
+-- Date: 2026-10-17
+-- Description: The change feed payload carried the whole row, and NOTIFY fails the
+-- INSERT or UPDATE once a payload reaches 8000 bytes, e.g. for a long name or an
+-- encrypted address. It now only carries the operation and the id; the listener
+-- re-reads the row
+
+CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    PERFORM pg_notify('user_changes', json_build_object('op', lower(TG_OP), 'id', NEW.id)::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
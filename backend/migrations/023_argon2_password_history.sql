@@ -0,0 +1,8 @@
+-- Migration: Hash the password history with Argon2
+-- Date: 2026-10-17
+-- Description: The history held salted single-round SHA-256 hashes (`<salt>:<hex>`),
+-- which a leaked table gives up to brute force. New entries are Argon2id PHC
+-- strings; the old ones cannot be converted without the passwords, so they are
+-- dropped and the history of those users starts again with their current password
+
+DELETE FROM password_history WHERE hash NOT LIKE '$argon2%';
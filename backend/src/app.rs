@@ -0,0 +1,461 @@
+use crate::audit::{ActivityRepository, PostgresActivityRepository};
+use crate::breach::PasswordScreen;
+use crate::captcha::CaptchaSettings;
+use crate::change_feed::LiveEvents;
+use crate::client_errors::CLIENT_ERROR_LIMIT;
+use crate::config::{AppConfig, DatabaseConfig};
+use crate::crypto::FieldCipher;
+use crate::envelope::EnvelopeFairing;
+use crate::event_stream::EventStreamSubscriber;
+use crate::events::{AuditLogSubscriber, EventBus, ForwardingSubscriber};
+use crate::jobs::{JobQueue, JobRepository, PostgresJobRepository};
+use crate::mailer::{SendEmailJob, WelcomeEmailSubscriber};
+use crate::maintenance::MaintenanceMode;
+use crate::notifications::{NotificationRepository, NotificationSubscriber, PostgresNotificationRepository};
+use crate::preferences::{PostgresPreferencesRepository, PreferencesRepository, PreferencesService};
+use crate::query_retry::QueryRetry;
+use crate::query_timeout::{QueryMetrics, QueryTimeout};
+use crate::rate_limit::{ClientErrorRateLimiter, EmailCheckRateLimiter, SignupRateLimiter};
+use crate::replicas::{DatabasePool, PostgresPool, ReplicaRoutedUserRepository};
+use crate::repository::{PostgresUserRepository, UserRepository};
+use crate::request_id::RequestIdFairing;
+use crate::request_log::{RequestLogFairing, TimedUserRepository};
+use crate::scheduler::{PurgeFinishedJobsTask, RequeueStaleJobsTask, Scheduler};
+use crate::service::UserService;
+use crate::unit_of_work::{PostgresUnitOfWorkFactory, UnitOfWorkFactory};
+use crate::verification::{
+    EmailVerificationService, PostgresVerificationTokenRepository, VerificationTokenRepository,
+};
+use crate::versioning::DeprecationFairing;
+use crate::webhooks::{
+    HttpWebhookSender, PostgresWebhookRepository, WebhookDeliveryJob, WebhookDispatcher,
+    WebhookRepository, WebhookService,
+};
+use crate::{
+    cache, captcha, change_feed, errors, event_stream, grpc, handlers, mailer, security, storage, versioning,
+};
+use rocket::data::Limits;
+use rocket::{Build, Rocket};
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+// Application assembly - Dependency Inversion Principle
+// Wires configuration and data access into the services, background tasks
+// and Rocket instance. The API server, the integration tests and any other
+// entry point build the same application from here
+
+/// Data access the application is built on
+pub struct Repositories {
+    pub users: Arc<dyn UserRepository>,
+    pub units: Arc<dyn UnitOfWorkFactory>,
+    pub webhooks: Arc<dyn WebhookRepository>,
+    pub jobs: Arc<dyn JobRepository>,
+    pub activity: Arc<dyn ActivityRepository>,
+    pub notifications: Arc<dyn NotificationRepository>,
+    pub preferences: Arc<dyn PreferencesRepository>,
+    pub verification_tokens: Arc<dyn VerificationTokenRepository>,
+    /// Database connections reported by the readiness probe
+    pub pools: Vec<Arc<dyn DatabasePool>>,
+    /// Timeouts of user repository queries
+    pub query_metrics: Arc<QueryMetrics>,
+}
+
+impl Repositories {
+    /// Postgres repositories sharing `client`; units of work open their own connections.
+    /// User reads go to the `replicas` when there are any. Their user queries
+    /// time out as `config` says, once `db::limit_statements` ran on the
+    /// connections, and are retried after transient errors
+    pub fn postgres(
+        client: Arc<Client>,
+        replicas: Vec<Arc<Client>>,
+        config: &DatabaseConfig,
+        cipher: Option<Arc<FieldCipher>>,
+    ) -> Self {
+        let query_metrics = Arc::new(QueryMetrics::new(config.statement_timeout));
+        let timeout = QueryTimeout::after_statement_timeout(query_metrics.clone());
+        let retry = QueryRetry::new(config.query_retry.clone());
+        let user_repository = |client: Arc<Client>| {
+            let users = PostgresUserRepository::new(client)
+                .with_timeout(timeout.clone())
+                .with_retry(retry.clone());
+            match &cipher {
+                Some(cipher) => users.with_cipher(cipher.clone()),
+                None => users,
+            }
+        };
+        let mut units = PostgresUnitOfWorkFactory::new(config.connection_string.clone())
+            .with_timeout(timeout.clone())
+            .with_retry(retry.clone());
+        if let Some(cipher) = &cipher {
+            units = units.with_cipher(cipher.clone());
+        }
+
+        let mut pools: Vec<Arc<dyn DatabasePool>> = vec![Arc::new(PostgresPool::primary(client.clone()))];
+        let mut users: Arc<dyn UserRepository> = Arc::new(user_repository(client.clone()));
+        if !replicas.is_empty() {
+            let mut replica_users: Vec<Arc<dyn UserRepository>> = Vec::new();
+            for (index, replica) in replicas.into_iter().enumerate() {
+                pools.push(Arc::new(PostgresPool::replica(index + 1, replica.clone())));
+                replica_users.push(Arc::new(user_repository(replica)));
+            }
+            users = Arc::new(ReplicaRoutedUserRepository::new(users, replica_users));
+        }
+        Repositories {
+            users,
+            units: Arc::new(units),
+            webhooks: Arc::new(PostgresWebhookRepository::new(client.clone())),
+            jobs: Arc::new(PostgresJobRepository::new(client.clone())),
+            activity: Arc::new(PostgresActivityRepository::new(client.clone())),
+            notifications: Arc::new(PostgresNotificationRepository::new(client.clone())),
+            preferences: Arc::new(PostgresPreferencesRepository::new(client.clone())),
+            verification_tokens: Arc::new(PostgresVerificationTokenRepository::new(client)),
+            pools,
+            query_metrics,
+        }
+    }
+}
+
+/// Build the Rocket application on top of `repositories`
+///
+/// Also starts the background work the configuration asks for: the job
+/// worker, the scheduler, the change feed listener and the gRPC server.
+/// Fails if part of the configuration is invalid
+pub async fn build_rocket(config: AppConfig, repositories: Repositories) -> Result<Rocket<Build>, String> {
+    // CORS restricted to the configured origins, validated before anything starts
+    let cors = security::cors(&config.cors, config.profile)
+        .map_err(|e| format!("Invalid CORS configuration: {}", e))?;
+    let cipher = FieldCipher::from_config(&config.encryption)
+        .map_err(|e| format!("Invalid PII encryption configuration: {}", e))?
+        .map(Arc::new);
+
+    // Timing of user repository calls; cache hits below are not queries, so it goes first
+    let users: Arc<dyn UserRepository> = Arc::new(TimedUserRepository::new(repositories.users, &config.logging));
+
+    // Optional read cache decorating the user repository and units of work
+    let cache::CachedLayers {
+        repository,
+        units,
+        metrics: cache_metrics,
+    } = cache::with_cache(users, repositories.units, &config.cache)
+        .await
+        .map_err(|e| format!("Failed to initialize cache: {}", e))?;
+
+    // File storage (local directory or S3) selected by configuration
+    let file_store = storage::build_file_store(&config.storage)
+        .map_err(|e| format!("Invalid storage configuration: {}", e))?;
+
+    // CAPTCHA check of signups (hCaptcha, reCAPTCHA, Turnstile or none)
+    let captcha = captcha::build_captcha_verifier(&config.captcha)
+        .map_err(|e| format!("Invalid CAPTCHA configuration: {}", e))?;
+
+    // Outgoing email transport (SMTP or log) selected by configuration
+    let mailer = mailer::build_mailer(&config.mailer)
+        .map_err(|e| format!("Invalid mailer configuration: {}", e))?;
+
+    // Background job queue with a handler per job type, drained by a worker task
+    let jobs = Arc::new(
+        JobQueue::new(repositories.jobs.clone())
+            .with_handler(Arc::new(WebhookDeliveryJob::new(
+                Arc::new(HttpWebhookSender::new(&config.webhooks)),
+                &config.webhooks,
+            )))
+            .with_handler(Arc::new(SendEmailJob::new(mailer))),
+    );
+    jobs.clone().spawn_worker(config.jobs.poll_interval);
+
+    // Recurring maintenance tasks
+    let scheduler = Arc::new(
+        Scheduler::new()
+            .with_task(
+                Arc::new(PurgeFinishedJobsTask::new(
+                    repositories.jobs.clone(),
+                    config.scheduler.job_retention,
+                )),
+                config.scheduler.purge_finished_jobs_every,
+            )
+            .with_task(
+                Arc::new(RequeueStaleJobsTask::new(
+                    repositories.jobs,
+                    config.scheduler.job_stale_after,
+                )),
+                config.scheduler.requeue_stale_jobs_every,
+            ),
+    );
+    if config.scheduler.enabled {
+        scheduler.clone().spawn();
+    }
+
+    // Event bus with its subscribers (side effects of user changes)
+    let activity = repositories.activity;
+    let events = EventBus::default();
+    events.spawn_subscriber(Arc::new(AuditLogSubscriber::new(activity.clone())));
+    events.spawn_subscriber(Arc::new(WebhookDispatcher::new(
+        repositories.webhooks.clone(),
+        jobs.clone(),
+    )));
+    events.spawn_subscriber(Arc::new(WelcomeEmailSubscriber::new(
+        jobs.clone(),
+        config.mailer.app_url.clone(),
+    )));
+    events.spawn_subscriber(Arc::new(NotificationSubscriber::new(
+        repositories.notifications.clone(),
+    )));
+    // Kafka or NATS, for downstream services
+    if let Some(publisher) = event_stream::build_event_publisher(&config.event_stream)
+        .await
+        .map_err(|e| format!("Invalid event stream configuration: {}", e))?
+    {
+        events.spawn_subscriber(Arc::new(EventStreamSubscriber::new(
+            publisher,
+            config.event_stream.format,
+            config.event_stream.retry.clone(),
+        )));
+    }
+
+    // Live updates (SSE) come from Postgres notifications so changes made on
+    // any instance reach every client; without the feed only local changes are seen
+    let live = LiveEvents::default();
+    if config.database.change_feed {
+        change_feed::spawn_listener(config.database.clone(), live.clone(), cipher);
+    } else {
+        events.spawn_subscriber(Arc::new(ForwardingSubscriber::new(live.0.clone())));
+    }
+
+    // Service layer (business logic)
+    let service = Arc::new(
+        UserService::new(repository, events)
+            .with_unit_of_work(units)
+            .with_password_history(config.password_policy.history),
+    );
+    let webhook_service = Arc::new(WebhookService::new(repositories.webhooks));
+    let preferences_service = Arc::new(PreferencesService::new(repositories.preferences));
+    // Confirmation links emailed after self-service signup
+    let verification = Arc::new(EmailVerificationService::new(
+        repositories.verification_tokens,
+        jobs.clone(),
+        config.mailer.app_url.clone(),
+    ));
+
+    // Read-only switch shared by the REST API and the gRPC server
+    let maintenance = Arc::new(MaintenanceMode::from_config(&config.maintenance));
+
+    // Internal gRPC server shares the same service layer on its own port
+    if config.grpc.enabled {
+        let service = service.clone();
+        let maintenance = maintenance.clone();
+        let address = config.grpc.address;
+        tokio::spawn(async move {
+            if let Err(e) = grpc::serve(address, service, maintenance).await {
+                eprintln!("gRPC server error: {}", e);
+            }
+        });
+    }
+
+    // Explicit request body limits on top of Rocket's own configuration
+    let figment = rocket::Config::figment()
+        .merge((
+            "limits",
+            Limits::default()
+                .limit("json", config.limits.json)
+                .limit(CLIENT_ERROR_LIMIT, config.limits.client_error),
+        ));
+
+    // Build Rocket application with injected dependencies
+    let rocket = rocket::custom(figment)
+        .manage(service)
+        .manage(webhook_service)
+        .manage(preferences_service)
+        .manage(jobs)
+        .manage(scheduler)
+        .manage(cache_metrics)
+        .manage(live)
+        .manage(verification)
+        .manage(SignupRateLimiter::from_config(&config.signup))
+        .manage(ClientErrorRateLimiter::from_config(&config.client_errors))
+        .manage(EmailCheckRateLimiter::from_config(&config.signup))
+        .manage(captcha)
+        .manage(CaptchaSettings::from_config(&config.captcha))
+        .manage(Arc::new(PasswordScreen::from_config(&config.password_policy)))
+        .manage(repositories.pools)
+        .manage(repositories.query_metrics)
+        .manage(activity)
+        .manage(file_store)
+        .manage(config.features.clone())
+        .manage(config.admin.clone())
+        .manage(maintenance);
+
+    // Each API version mounts its own routes; /api stays a deprecated alias of v1
+    let rocket = versioning::mount_v1(rocket, handlers::v1_routes())
+        .mount("/", handlers::root_routes())
+        .register("/", errors::catchers())
+        .attach(cors)
+        .attach(RequestIdFairing)
+        .attach(EnvelopeFairing { always: config.envelope.always })
+        .attach(DeprecationFairing)
+        .attach(security::shield())
+        .attach(security::SecurityHeaders);
+    Ok(if config.logging.requests {
+        rocket.attach(RequestLogFairing::new(config.logging.slow_request))
+    } else {
+        rocket
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::tests::MockActivityRepository;
+    use crate::replicas::tests::MockDatabasePool;
+    use crate::jobs::tests::MockJobRepository;
+    use crate::models::User;
+    use crate::notifications::tests::MockNotificationRepository;
+    use crate::preferences::tests::MockPreferencesRepository;
+    use crate::repository::tests::MockUserRepository;
+    use crate::unit_of_work::tests::MockUnitOfWorkFactory;
+    use crate::verification::tests::MockVerificationTokenRepository;
+    use crate::webhooks::tests::MockWebhookRepository;
+    use rocket::http::{Header, Method, Status};
+    use rocket::local::asynchronous::Client as TestClient;
+    use std::time::Duration;
+    use shared::routes::{self, users};
+
+    fn mock_repositories() -> Repositories {
+        let users = Arc::new(MockUserRepository::new());
+        Repositories {
+            users: users.clone(),
+            units: Arc::new(MockUnitOfWorkFactory::new(users)),
+            webhooks: Arc::new(MockWebhookRepository::new()),
+            jobs: Arc::new(MockJobRepository::new()),
+            activity: Arc::new(MockActivityRepository::default()),
+            notifications: Arc::new(MockNotificationRepository::default()),
+            preferences: Arc::new(MockPreferencesRepository::default()),
+            verification_tokens: Arc::new(MockVerificationTokenRepository::default()),
+            pools: vec![Arc::new(MockDatabasePool::new("primary"))],
+            query_metrics: Arc::new(QueryMetrics::new(Duration::from_secs(10))),
+        }
+    }
+
+    fn test_config() -> AppConfig {
+        let mut config = AppConfig::from_env().unwrap();
+        config.database.change_feed = false;
+        config.grpc.enabled = false;
+        config.scheduler.enabled = false;
+        config.admin.token = Some(ADMIN_TOKEN.into());
+        config
+    }
+
+    const ADMIN_TOKEN: &str = "q8Zt-31xLw";
+
+    #[tokio::test]
+    async fn test_build_rocket_serves_the_api() {
+        let rocket = build_rocket(test_config(), mock_repositories()).await.unwrap();
+        let client = TestClient::tracked(rocket).await.unwrap();
+
+        let response = client
+            .post("/api/v1/users")
+            .header(Header::new("Authorization", format!("Bearer {}", ADMIN_TOKEN)))
+            .json(&User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string()))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/api/v1/users/1").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let response = client.get("/api/v1/users/999").dispatch().await;
+        assert_eq!(response.status(), Status::NotFound);
+
+        // The heartbeat lives outside the versioned API
+        let response = client.get("/healthz").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().await.unwrap(), r#"{"status":"ok"}"#);
+        let response = client.get("/readyz").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.into_string().await.unwrap(),
+            r#"{"status":"ready","pools":[{"name":"primary","healthy":true}]}"#
+        );
+    }
+
+    // Whether a mounted route template such as `/api/v1/users/<id>` serves `path`
+    fn template_matches(template: &str, path: &str) -> bool {
+        let template: Vec<&str> = template.split('/').collect();
+        let path: Vec<&str> = path.split('/').collect();
+        template.len() == path.len()
+            && template
+                .iter()
+                .zip(&path)
+                .all(|(segment, part)| (segment.starts_with('<') && segment.ends_with('>')) || segment == part)
+    }
+
+    #[tokio::test]
+    async fn test_shared_routes_are_mounted() {
+        let rocket = build_rocket(test_config(), mock_repositories()).await.unwrap();
+        let mounted: Vec<(Method, String)> =
+            rocket.routes().map(|route| (route.method, route.uri.path().to_string())).collect();
+
+        // The requests the frontend builds from `shared::routes`
+        let v1 = |path: &str| format!("{}{}", routes::API_V1_PREFIX, path);
+        let requests = [
+            (Method::Get, v1(users::ALL)),
+            (Method::Post, v1(users::ALL)),
+            (Method::Get, v1(&users::by_id(1))),
+            (Method::Put, v1(&users::by_id(1))),
+            (Method::Delete, v1(&users::by_id(1))),
+            (Method::Get, v1(&users::by_username("ada"))),
+            (Method::Get, v1(&users::activity(1))),
+            (Method::Get, v1(&users::preferences(1))),
+            (Method::Put, v1(&users::preferences(1))),
+            (Method::Put, v1(&users::tags(1))),
+            (Method::Post, v1(&users::suspend(1))),
+            (Method::Post, v1(&users::activate(1))),
+            (Method::Get, v1(users::EXISTS)),
+            (Method::Get, v1(users::EXPORT)),
+            (Method::Post, v1(users::EXPORT)),
+            (Method::Patch, v1(users::BULK)),
+            (Method::Post, v1(routes::SIGNUP)),
+            (Method::Post, v1(routes::SIGNUP_VERIFY)),
+            (Method::Get, v1(routes::CAPTCHA)),
+            (Method::Post, v1(routes::CLIENT_ERRORS)),
+            (Method::Get, v1(routes::USERNAMES_AVAILABLE)),
+            (Method::Get, v1(routes::STATS)),
+            (Method::Get, v1(routes::EVENTS)),
+            (Method::Get, v1(routes::FLAGS)),
+            (Method::Get, v1(routes::STATUS)),
+            (Method::Get, routes::HEALTHZ.to_string()),
+        ];
+        for (method, path) in requests {
+            let served = mounted
+                .iter()
+                .any(|(mounted_method, template)| *mounted_method == method && template_matches(template, &path));
+            assert!(served, "no route serves {} {}", method, path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_allows_patch() {
+        let rocket = build_rocket(test_config(), mock_repositories()).await.unwrap();
+        let client = TestClient::tracked(rocket).await.unwrap();
+
+        // The bulk edit of the frontend on :8080
+        let response = client
+            .options("/api/v1/users/bulk")
+            .header(Header::new("Origin", "http://localhost:8080"))
+            .header(Header::new("Access-Control-Request-Method", "PATCH"))
+            .header(Header::new("Access-Control-Request-Headers", "Content-Type"))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::NoContent);
+        let headers = response.headers();
+        assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("http://localhost:8080"));
+        let methods = headers.get_one("Access-Control-Allow-Methods").unwrap_or_default();
+        assert!(methods.split(',').any(|method| method.trim() == "PATCH"), "{}", methods);
+    }
+
+    #[tokio::test]
+    async fn test_build_rocket_rejects_invalid_configuration() {
+        let mut config = test_config();
+        config.cors.allowed_origins = vec!["not a url".to_string()];
+        let error = build_rocket(config, mock_repositories()).await.err().unwrap();
+        assert!(error.starts_with("Invalid CORS configuration"));
+    }
+}
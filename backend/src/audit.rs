@@ -0,0 +1,457 @@
+use crate::domain::{DomainError, RepositoryError};
+use async_trait::async_trait;
+use rocket::http::Header;
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use rocket::Request;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::Client;
+
+// Audit log - Single Responsibility Principle
+// Keeps the history of what happened to each user; entries are written by
+// the AuditLogSubscriber from domain events and read back per user.
+// Each entry stores the hash of the one before it, so editing, inserting or
+// removing a past entry breaks the chain and is detected on verification
+
+/// Entries returned when no limit is given
+pub const DEFAULT_ACTIVITY_LIMIT: i64 = 20;
+/// Upper bound for the `limit` parameter
+pub const MAX_ACTIVITY_LIMIT: i64 = 100;
+/// Previous hash of the first chained entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// Entries read at a time while walking the chain
+const TRAIL_PAGE_SIZE: i64 = 500;
+/// Tries to append an entry when other instances keep moving the head
+const APPEND_ATTEMPTS: usize = 5;
+
+/// Something that happened to a user, e.g. `user.updated`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ActivityEntry {
+    pub event: String,
+    /// RFC 3339 UTC time of the event
+    pub occurred_at: String,
+}
+
+/// An entry of the audit trail with its place in the hash chain
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AuditRecord {
+    pub id: i64,
+    pub user_id: i32,
+    pub event: String,
+    /// RFC 3339 UTC time of the event, with microseconds
+    pub occurred_at: String,
+    /// None for entries written before the chain was introduced
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+}
+
+/// Outcome of walking the whole audit trail
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AuditVerification {
+    pub valid: bool,
+    /// Chained entries checked
+    pub entries: u64,
+    /// Entries written before the chain was introduced, not covered by it
+    pub unchained: u64,
+    /// Id of the first entry that does not match the chain
+    pub broken_at: Option<i64>,
+}
+
+/// Storage of the audit log
+#[async_trait]
+pub trait ActivityRepository: Send + Sync {
+    /// Append an entry chained to the latest one
+    async fn record(&self, user_id: i32, event: &str) -> Result<(), RepositoryError>;
+    /// The latest `limit` entries of a user, newest first
+    async fn for_user(&self, user_id: i32, limit: i64) -> Result<Vec<ActivityEntry>, RepositoryError>;
+    /// Up to `limit` entries with an id above `after_id`, oldest first
+    async fn trail(&self, after_id: i64, limit: i64) -> Result<Vec<AuditRecord>, RepositoryError>;
+}
+
+pub struct PostgresActivityRepository {
+    client: Arc<Client>,
+}
+
+impl PostgresActivityRepository {
+    pub fn new(client: Arc<Client>) -> Self {
+        PostgresActivityRepository { client }
+    }
+}
+
+#[async_trait]
+impl ActivityRepository for PostgresActivityRepository {
+    async fn record(&self, user_id: i32, event: &str) -> Result<(), RepositoryError> {
+        // prev_hash is unique: when another writer appended first, the insert
+        // fails instead of forking the chain and is retried on the new head
+        let mut attempt = 1;
+        loop {
+            let prev_hash = self
+                .client
+                .query_opt(
+                    "SELECT hash FROM audit_log WHERE hash IS NOT NULL ORDER BY id DESC LIMIT 1",
+                    &[],
+                )
+                .await?
+                .map(|row| row.get::<_, String>(0))
+                .unwrap_or_else(|| GENESIS_HASH.to_string());
+            let occurred_at = chain_timestamp(OffsetDateTime::now_utc());
+            let hash = entry_hash(&prev_hash, user_id, event, &occurred_at);
+
+            let inserted = self
+                .client
+                .execute(
+                    "INSERT INTO audit_log (user_id, event, occurred_at, prev_hash, hash)
+                     VALUES ($1, $2, $3::TEXT::TIMESTAMPTZ, $4, $5)",
+                    &[&user_id, &event, &occurred_at, &prev_hash, &hash],
+                )
+                .await;
+            match inserted {
+                Ok(_) => return Ok(()),
+                Err(e) if e.code() == Some(&SqlState::UNIQUE_VIOLATION) && attempt < APPEND_ATTEMPTS => {
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn for_user(&self, user_id: i32, limit: i64) -> Result<Vec<ActivityEntry>, RepositoryError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT event,
+                    to_char(occurred_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"')
+                 FROM audit_log WHERE user_id = $1
+                 ORDER BY occurred_at DESC, id DESC LIMIT $2",
+                &[&user_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ActivityEntry {
+                event: row.get(0),
+                occurred_at: row.get(1),
+            })
+            .collect())
+    }
+
+    async fn trail(&self, after_id: i64, limit: i64) -> Result<Vec<AuditRecord>, RepositoryError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, user_id, event,
+                    to_char(occurred_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.US\"Z\"'),
+                    prev_hash, hash
+                 FROM audit_log WHERE id > $1
+                 ORDER BY id LIMIT $2",
+                &[&after_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| AuditRecord {
+                id: row.get(0),
+                user_id: row.get(1),
+                event: row.get(2),
+                occurred_at: row.get(3),
+                prev_hash: row.get(4),
+                hash: row.get(5),
+            })
+            .collect())
+    }
+}
+
+/// `YYYY-MM-DD'T'HH:MM:SS.ffffff'Z'` time of an entry, as Postgres stores
+/// it, so the hashed value can be read back unchanged
+pub fn chain_timestamp(now: OffsetDateTime) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        now.microsecond()
+    )
+}
+
+/// Hex SHA-256 of an entry and the hash of the entry before it
+pub fn entry_hash(prev_hash: &str, user_id: i32, event: &str, occurred_at: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}\n{}\n{}\n{}", prev_hash, user_id, event, occurred_at));
+    hex::encode(hasher.finalize())
+}
+
+/// Checks entries one by one, in id order
+#[derive(Debug, Default)]
+pub struct ChainVerifier {
+    last_hash: Option<String>,
+    entries: u64,
+    unchained: u64,
+    broken_at: Option<i64>,
+}
+
+impl ChainVerifier {
+    /// Check the next entry; false from the first one breaking the chain on
+    pub fn check(&mut self, record: &AuditRecord) -> bool {
+        if self.broken_at.is_some() {
+            return false;
+        }
+        let intact = match (&record.prev_hash, &record.hash) {
+            // Entries older than the chain may only come before it
+            (None, None) if self.last_hash.is_none() => {
+                self.unchained += 1;
+                return true;
+            }
+            (Some(prev_hash), Some(hash)) => {
+                let expected_prev = self.last_hash.as_deref().unwrap_or(GENESIS_HASH);
+                prev_hash == expected_prev
+                    && *hash == entry_hash(prev_hash, record.user_id, &record.event, &record.occurred_at)
+            }
+            _ => false,
+        };
+        if intact {
+            self.entries += 1;
+            self.last_hash = record.hash.clone();
+        } else {
+            self.broken_at = Some(record.id);
+        }
+        intact
+    }
+
+    pub fn finish(self) -> AuditVerification {
+        AuditVerification {
+            valid: self.broken_at.is_none(),
+            entries: self.entries,
+            unchained: self.unchained,
+            broken_at: self.broken_at,
+        }
+    }
+}
+
+/// Walk the whole trail; the verification and, when `keep` is set, the entries
+async fn walk_trail(
+    repository: &dyn ActivityRepository,
+    keep: bool,
+) -> Result<(AuditVerification, Vec<AuditRecord>), RepositoryError> {
+    let mut verifier = ChainVerifier::default();
+    let mut kept = Vec::new();
+    let mut after_id = 0;
+    loop {
+        let page = repository.trail(after_id, TRAIL_PAGE_SIZE).await?;
+        let Some(last) = page.last() else { break };
+        after_id = last.id;
+        for record in page {
+            if !verifier.check(&record) {
+                return Ok((verifier.finish(), Vec::new()));
+            }
+            if keep {
+                kept.push(record);
+            }
+        }
+    }
+    Ok((verifier.finish(), kept))
+}
+
+/// Check that no entry of the trail was altered, inserted or removed
+pub async fn verify_trail(repository: &dyn ActivityRepository) -> Result<AuditVerification, RepositoryError> {
+    Ok(walk_trail(repository, false).await?.0)
+}
+
+/// The whole trail, oldest first, once it has been verified; 409 when it is broken
+pub async fn verified_trail(repository: &dyn ActivityRepository) -> Result<Vec<AuditRecord>, DomainError> {
+    let (verification, trail) = walk_trail(repository, true).await?;
+    match verification.broken_at {
+        Some(id) => Err(DomainError::Conflict(format!("Audit trail is broken at entry {}", id))),
+        None => Ok(trail),
+    }
+}
+
+/// Verified trail sent as a JSON file download
+pub struct AuditTrailExport(pub Vec<AuditRecord>);
+
+impl<'r> Responder<'r, 'static> for AuditTrailExport {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Json(self.0).respond_to(request)?;
+        response.set_header(Header::new(
+            "Content-Disposition",
+            "attachment; filename=\"audit-trail.json\"",
+        ));
+        Ok(response)
+    }
+}
+
+/// `limit` bounded to 1..=MAX_ACTIVITY_LIMIT, DEFAULT_ACTIVITY_LIMIT when absent
+pub fn activity_limit(limit: Option<i64>) -> i64 {
+    limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .clamp(1, MAX_ACTIVITY_LIMIT)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // In-memory hash-chained audit log for tests
+    #[derive(Default)]
+    pub struct MockActivityRepository {
+        pub entries: Mutex<Vec<AuditRecord>>,
+    }
+
+    #[async_trait]
+    impl ActivityRepository for MockActivityRepository {
+        async fn record(&self, user_id: i32, event: &str) -> Result<(), RepositoryError> {
+            let mut entries = self.entries.lock().unwrap();
+            let occurred_at = format!("2026-10-17T12:00:{:02}.000000Z", entries.len());
+            let prev_hash = entries
+                .last()
+                .and_then(|entry| entry.hash.clone())
+                .unwrap_or_else(|| GENESIS_HASH.to_string());
+            let hash = entry_hash(&prev_hash, user_id, event, &occurred_at);
+            let id = entries.len() as i64 + 1;
+            entries.push(AuditRecord {
+                id,
+                user_id,
+                event: event.to_string(),
+                occurred_at,
+                prev_hash: Some(prev_hash),
+                hash: Some(hash),
+            });
+            Ok(())
+        }
+
+        async fn for_user(&self, user_id: i32, limit: i64) -> Result<Vec<ActivityEntry>, RepositoryError> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .iter()
+                .rev()
+                .filter(|entry| entry.user_id == user_id)
+                .take(limit as usize)
+                .map(|entry| ActivityEntry {
+                    event: entry.event.clone(),
+                    occurred_at: entry.occurred_at.clone(),
+                })
+                .collect())
+        }
+
+        async fn trail(&self, after_id: i64, limit: i64) -> Result<Vec<AuditRecord>, RepositoryError> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .iter()
+                .filter(|entry| entry.id > after_id)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_activity_limit_bounds() {
+        assert_eq!(activity_limit(None), DEFAULT_ACTIVITY_LIMIT);
+        assert_eq!(activity_limit(Some(0)), 1);
+        assert_eq!(activity_limit(Some(5)), 5);
+        assert_eq!(activity_limit(Some(1_000)), MAX_ACTIVITY_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_mock_returns_newest_entries_of_user() {
+        let repository = MockActivityRepository::default();
+        repository.record(1, "user.created").await.unwrap();
+        repository.record(2, "user.created").await.unwrap();
+        repository.record(1, "user.updated").await.unwrap();
+
+        let entries = repository.for_user(1, 10).await.unwrap();
+        let events: Vec<&str> = entries.iter().map(|entry| entry.event.as_str()).collect();
+        assert_eq!(events, vec!["user.updated", "user.created"]);
+        assert_eq!(repository.for_user(1, 1).await.unwrap().len(), 1);
+    }
+
+    async fn chained(events: usize) -> MockActivityRepository {
+        let repository = MockActivityRepository::default();
+        for index in 0..events {
+            repository.record(index as i32 % 3, "user.updated").await.unwrap();
+        }
+        repository
+    }
+
+    #[test]
+    fn test_chain_timestamp_has_microseconds() {
+        let time = OffsetDateTime::from_unix_timestamp_nanos(1_792_238_400_123_456_789).unwrap();
+        assert_eq!(chain_timestamp(time), "2026-10-17T12:00:00.123456Z");
+    }
+
+    #[tokio::test]
+    async fn test_intact_trail_verifies() {
+        let repository = chained(1_200).await;
+        let verification = verify_trail(&repository).await.unwrap();
+        assert_eq!(
+            verification,
+            AuditVerification {
+                valid: true,
+                entries: 1_200,
+                unchained: 0,
+                broken_at: None,
+            }
+        );
+        assert_eq!(verified_trail(&repository).await.unwrap().len(), 1_200);
+        assert!(verify_trail(&MockActivityRepository::default()).await.unwrap().valid);
+    }
+
+    #[tokio::test]
+    async fn test_edited_entry_breaks_the_chain() {
+        let repository = chained(5).await;
+        repository.entries.lock().unwrap()[2].event = "user.created".to_string();
+
+        let verification = verify_trail(&repository).await.unwrap();
+        assert!(!verification.valid);
+        assert_eq!(verification.broken_at, Some(3));
+        assert_eq!(verification.entries, 2);
+
+        let error = verified_trail(&repository).await.unwrap_err();
+        assert_eq!(error, DomainError::Conflict("Audit trail is broken at entry 3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_removed_entry_breaks_the_chain() {
+        let repository = chained(5).await;
+        repository.entries.lock().unwrap().remove(1);
+        assert_eq!(verify_trail(&repository).await.unwrap().broken_at, Some(3));
+    }
+
+    #[test]
+    fn test_entries_older_than_the_chain_may_only_come_first() {
+        let legacy = |id| AuditRecord {
+            id,
+            user_id: 1,
+            event: "user.created".to_string(),
+            occurred_at: "2026-10-17T12:00:00.000000Z".to_string(),
+            prev_hash: None,
+            hash: None,
+        };
+        let occurred_at = "2026-10-17T12:00:01.000000Z".to_string();
+        let chained = AuditRecord {
+            id: 2,
+            prev_hash: Some(GENESIS_HASH.to_string()),
+            hash: Some(entry_hash(GENESIS_HASH, 1, "user.updated", &occurred_at)),
+            event: "user.updated".to_string(),
+            occurred_at,
+            ..legacy(2)
+        };
+
+        let mut verifier = ChainVerifier::default();
+        assert!(verifier.check(&legacy(1)));
+        assert!(verifier.check(&chained));
+        assert!(!verifier.check(&legacy(3)));
+        let verification = verifier.finish();
+        assert_eq!((verification.entries, verification.unchained), (1, 1));
+        assert_eq!(verification.broken_at, Some(3));
+    }
+}
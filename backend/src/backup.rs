@@ -0,0 +1,366 @@
+use crate::models::{Address, User, UserStatus};
+use crate::preferences::{PreferencesRepository, UserPreferences};
+use crate::repository::UserRepository;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rocket::serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+// Backup and restore - Single Responsibility Principle
+// App-level backups for deployments without database tooling: users and
+// their preferences are read through the repositories, serialized to JSON
+// and sealed with AES-256-GCM, so an archive is useless without its key.
+// Restoring goes through the repositories too, which re-encrypts PII with
+// the key of the target deployment
+
+/// First bytes of every archive, naming the format and its version
+const ARCHIVE_MAGIC: &[u8] = b"RUSTAPP-BACKUP-1\n";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// One user with the rows that belong to it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct BackupUser {
+    /// Id in the source database; restored users get new ids
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+    pub password: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    pub status: UserStatus,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub last_login_at: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub address: Option<Address>,
+    #[serde(default)]
+    pub preferences: Option<UserPreferences>,
+}
+
+/// Contents of an archive
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct Backup {
+    /// RFC 3339 time the backup was taken
+    pub created_at: String,
+    pub users: Vec<BackupUser>,
+}
+
+impl Backup {
+    /// Read every user and their preferences
+    pub async fn collect(
+        users: &dyn UserRepository,
+        preferences: &dyn PreferencesRepository,
+    ) -> Result<Backup, String> {
+        let mut backup = Backup {
+            created_at: OffsetDateTime::now_utc().format(&Rfc3339).map_err(|e| e.to_string())?,
+            users: Vec::new(),
+        };
+        for user in users.find_all().await.map_err(|e| e.to_string())? {
+            let id = user.id.unwrap_or_default();
+            backup.users.push(BackupUser {
+                id,
+                preferences: preferences.find(id).await.map_err(|e| e.to_string())?,
+                name: user.name,
+                email: user.email,
+                password: user.password,
+                username: user.username,
+                status: user.status,
+                tags: user.tags,
+                last_login_at: user.last_login_at,
+                phone: user.phone,
+                address: user.address,
+            });
+        }
+        Ok(backup)
+    }
+}
+
+/// Key sealing and opening archives
+pub struct BackupKey(Aes256Gcm);
+
+impl BackupKey {
+    pub fn from_hex(value: &str) -> Result<Self, String> {
+        let bytes = hex::decode(value.trim()).map_err(|_| "The backup key must be hex encoded".to_string())?;
+        if bytes.len() != KEY_LEN {
+            return Err(format!(
+                "The backup key must be {} bytes ({} hex characters)",
+                KEY_LEN,
+                KEY_LEN * 2
+            ));
+        }
+        Ok(BackupKey(Aes256Gcm::new_from_slice(&bytes).expect("key length checked above")))
+    }
+
+    /// Serialize and encrypt `backup` with a random nonce
+    pub fn seal(&self, backup: &Backup) -> Result<Vec<u8>, String> {
+        let plaintext = serde_json::to_vec(backup).map_err(|e| e.to_string())?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt the backup".to_string())?;
+
+        let mut archive = ARCHIVE_MAGIC.to_vec();
+        archive.extend_from_slice(&nonce);
+        archive.extend_from_slice(&ciphertext);
+        Ok(archive)
+    }
+
+    /// Decrypt and parse an archive written by `seal`
+    pub fn open(&self, archive: &[u8]) -> Result<Backup, String> {
+        let payload = archive
+            .strip_prefix(ARCHIVE_MAGIC)
+            .filter(|payload| payload.len() > NONCE_LEN)
+            .ok_or_else(|| "Not a backup archive".to_string())?;
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .0
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "Failed to decrypt the backup: wrong key or damaged archive".to_string())?;
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Malformed backup: {}", e))
+    }
+}
+
+/// What to do with a backed up user whose email is already taken
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ConflictStrategy {
+    /// Keep the existing user
+    #[default]
+    Skip,
+    /// Replace the existing user's data with the backed up one
+    Overwrite,
+    /// Restore nothing
+    Fail,
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "skip" => Ok(ConflictStrategy::Skip),
+            "overwrite" => Ok(ConflictStrategy::Overwrite),
+            "fail" => Ok(ConflictStrategy::Fail),
+            other => Err(format!("Unknown conflict strategy '{}', expected skip, overwrite or fail", other)),
+        }
+    }
+}
+
+/// What a restore did, or would do in a dry run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestoreReport {
+    pub created: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Restore the users of `backup`, matched to existing users by email.
+/// Conflicts are all looked up before anything is written, so the `fail`
+/// strategy and dry runs leave the database untouched
+pub async fn restore(
+    backup: &Backup,
+    users: &dyn UserRepository,
+    preferences: &dyn PreferencesRepository,
+    strategy: ConflictStrategy,
+    dry_run: bool,
+) -> Result<RestoreReport, String> {
+    let mut plan = Vec::with_capacity(backup.users.len());
+    for backed_up in &backup.users {
+        let existing = users.find_by_email(&backed_up.email).await.map_err(|e| e.to_string())?;
+        plan.push((backed_up, existing.and_then(|user| user.id)));
+    }
+
+    let conflicts: Vec<&str> = plan
+        .iter()
+        .filter(|(_, existing)| existing.is_some())
+        .map(|(backed_up, _)| backed_up.email.as_str())
+        .collect();
+    if strategy == ConflictStrategy::Fail && !conflicts.is_empty() {
+        return Err(format!("Users of the backup already exist: {}", conflicts.join(", ")));
+    }
+
+    let mut report = RestoreReport::default();
+    for (backed_up, existing) in plan {
+        let email = backed_up.email.clone();
+        let id = match existing {
+            Some(_) if strategy == ConflictStrategy::Skip => {
+                report.skipped.push(email);
+                continue;
+            }
+            Some(id) => {
+                report.overwritten.push(email);
+                id
+            }
+            None => {
+                report.created.push(email);
+                if dry_run {
+                    continue;
+                }
+                let user = User::new(backed_up.name.clone(), backed_up.email.clone(), backed_up.password.clone())
+                    .with_username(backed_up.username.clone())
+                    .with_contact(backed_up.phone.clone(), backed_up.address.clone());
+                users
+                    .create(&user)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .id
+                    .unwrap_or_default()
+            }
+        };
+        if dry_run {
+            continue;
+        }
+        if existing.is_some() {
+            let user = User::with_id(id, backed_up.name.clone(), backed_up.email.clone(), backed_up.password.clone())
+                .with_username(backed_up.username.clone())
+                .with_contact(backed_up.phone.clone(), backed_up.address.clone());
+            users.update(id, &user).await.map_err(|e| e.to_string())?;
+        }
+        users.set_status(id, backed_up.status).await.map_err(|e| e.to_string())?;
+        users.set_tags(id, &backed_up.tags).await.map_err(|e| e.to_string())?;
+        if let Some(saved) = &backed_up.preferences {
+            preferences.save(id, saved).await.map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preferences::tests::MockPreferencesRepository;
+    use crate::repository::tests::MockUserRepository;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    const OTHER_KEY: &str = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100";
+
+    fn backed_up(id: i32, email: &str) -> BackupUser {
+        BackupUser {
+            id,
+            name: "John Doe".to_string(),
+            email: email.to_string(),
+            password: "password123".to_string(),
+            username: None,
+            status: UserStatus::Suspended,
+            tags: vec!["vip".to_string()],
+            last_login_at: None,
+            phone: Some("+5511912345678".to_string()),
+            address: None,
+            preferences: Some(UserPreferences {
+                theme: Some("dark".to_string()),
+                ..UserPreferences::default()
+            }),
+        }
+    }
+
+    fn backup() -> Backup {
+        Backup {
+            created_at: "2026-10-17T12:00:00Z".to_string(),
+            users: vec![backed_up(1, "john@example.com"), backed_up(2, "jane@example.com")],
+        }
+    }
+
+    async fn existing_john(users: &MockUserRepository) -> i32 {
+        let user = User::new("Johnny".to_string(), "john@example.com".to_string(), "old-password".to_string());
+        users.create(&user).await.unwrap().id.unwrap()
+    }
+
+    #[test]
+    fn test_archives_round_trip_and_need_the_key() {
+        let key = BackupKey::from_hex(KEY).unwrap();
+        let archive = key.seal(&backup()).unwrap();
+
+        assert!(archive.starts_with(ARCHIVE_MAGIC));
+        assert!(!String::from_utf8_lossy(&archive).contains("john@example.com"));
+        assert_eq!(key.open(&archive).unwrap(), backup());
+
+        let other = BackupKey::from_hex(OTHER_KEY).unwrap();
+        assert!(other.open(&archive).is_err());
+        assert_eq!(key.open(b"not an archive"), Err("Not a backup archive".to_string()));
+        assert!(BackupKey::from_hex("abcd").is_err());
+    }
+
+    #[test]
+    fn test_conflict_strategy_from_str() {
+        assert_eq!("skip".parse(), Ok(ConflictStrategy::Skip));
+        assert_eq!("overwrite".parse(), Ok(ConflictStrategy::Overwrite));
+        assert_eq!("fail".parse(), Ok(ConflictStrategy::Fail));
+        assert!("merge".parse::<ConflictStrategy>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_collect_includes_preferences() {
+        let users = MockUserRepository::new();
+        let preferences = MockPreferencesRepository::default();
+        let id = existing_john(&users).await;
+        preferences.save(id, &backed_up(id, "").preferences.unwrap()).await.unwrap();
+
+        let backup = Backup::collect(&users, &preferences).await.unwrap();
+        assert_eq!(backup.users.len(), 1);
+        assert_eq!(backup.users[0].email, "john@example.com");
+        assert_eq!(backup.users[0].preferences.as_ref().unwrap().theme.as_deref(), Some("dark"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_skips_existing_users() {
+        let users = MockUserRepository::new();
+        let preferences = MockPreferencesRepository::default();
+        let john = existing_john(&users).await;
+
+        let report = restore(&backup(), &users, &preferences, ConflictStrategy::Skip, false)
+            .await
+            .unwrap();
+        assert_eq!(report.created, vec!["jane@example.com"]);
+        assert_eq!(report.skipped, vec!["john@example.com"]);
+        assert_eq!(users.find_by_id(john).await.unwrap().unwrap().name, "Johnny");
+
+        let jane = users.find_by_email("jane@example.com").await.unwrap().unwrap();
+        assert_eq!(jane.status, UserStatus::Suspended);
+        assert_eq!(jane.tags, vec!["vip".to_string()]);
+        assert_eq!(jane.phone.as_deref(), Some("+5511912345678"));
+        assert!(preferences.find(jane.id.unwrap()).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_restore_overwrites_existing_users() {
+        let users = MockUserRepository::new();
+        let preferences = MockPreferencesRepository::default();
+        let john = existing_john(&users).await;
+
+        let report = restore(&backup(), &users, &preferences, ConflictStrategy::Overwrite, false)
+            .await
+            .unwrap();
+        assert_eq!(report.overwritten, vec!["john@example.com"]);
+        let restored = users.find_by_id(john).await.unwrap().unwrap();
+        assert_eq!(restored.name, "John Doe");
+        assert_eq!(restored.password, "password123");
+    }
+
+    #[tokio::test]
+    async fn test_failed_and_dry_run_restores_write_nothing() {
+        let users = MockUserRepository::new();
+        let preferences = MockPreferencesRepository::default();
+        existing_john(&users).await;
+
+        let error = restore(&backup(), &users, &preferences, ConflictStrategy::Fail, false)
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Users of the backup already exist: john@example.com");
+
+        let report = restore(&backup(), &users, &preferences, ConflictStrategy::Overwrite, true)
+            .await
+            .unwrap();
+        assert_eq!(report.created, vec!["jane@example.com"]);
+        assert_eq!(report.overwritten, vec!["john@example.com"]);
+        assert_eq!(users.count().await.unwrap(), 1);
+        assert!(preferences.preferences.lock().unwrap().is_empty());
+    }
+}
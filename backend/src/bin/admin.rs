@@ -0,0 +1,279 @@
+use aes_gcm::aead::OsRng;
+use aes_gcm::aead::rand_core::RngCore;
+use backend::backup::{self, Backup, BackupKey, ConflictStrategy};
+use backend::config::{AppConfig, Profile};
+use backend::crypto::FieldCipher;
+use backend::db;
+use backend::events::EventBus;
+use backend::models::User;
+use backend::preferences::PostgresPreferencesRepository;
+use backend::repository::{DEFAULT_BULK_CHUNK_SIZE, PostgresUserRepository, UserRepository};
+use backend::seed::{self, DEFAULT_SEED_COUNT};
+use backend::service::UserService;
+use clap::{Parser, Subcommand};
+use shared::password::GENERATED_PASSWORD_LENGTH;
+use shared::{generate_password, normalize_email};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// Admin CLI - Single Responsibility Principle
+// Operational tasks run against the database configured in the environment,
+// through the same repository and service layers as the API. Changes are
+// not published to webhook or email subscribers, which only run in the server
+
+#[derive(Parser)]
+#[command(name = "admin", about = "User management for operators")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create an account for an administrator (a regular user until roles exist)
+    CreateAdmin {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        /// Generated and printed when omitted
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Print every user
+    ListUsers,
+    /// Set a new password for the user with this email
+    ResetPassword {
+        email: String,
+        /// Generated and printed when omitted
+        #[arg(long)]
+        password: Option<String>,
+    },
+    /// Apply the SQL migrations that were not applied yet
+    RunMigrations {
+        #[arg(long, default_value = "migrations")]
+        dir: PathBuf,
+        /// Only record the migrations as applied (database migrated by hand)
+        #[arg(long)]
+        baseline: bool,
+    },
+    /// Encrypt stored names and emails with the current PII encryption key
+    RekeyPii,
+    /// Report users whose emails only differ by case
+    DuplicateEmails,
+    /// Insert fake users for local development (refused in the prod profile)
+    Seed {
+        #[arg(long, default_value_t = DEFAULT_SEED_COUNT)]
+        count: usize,
+        /// Users inserted per statement
+        #[arg(long, default_value_t = DEFAULT_BULK_CHUNK_SIZE)]
+        batch_size: usize,
+    },
+    /// Write users and their preferences to an archive encrypted with BACKUP_ENCRYPTION_KEY
+    Backup {
+        output: PathBuf,
+    },
+    /// Restore users and their preferences from a backup archive
+    Restore {
+        input: PathBuf,
+        /// Report what would be restored without writing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// What to do with users whose email already exists: skip, overwrite or fail
+        #[arg(long, default_value = "skip")]
+        on_conflict: ConflictStrategy,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli.command).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(command: Command) -> Result<(), String> {
+    let config = AppConfig::from_env()?;
+
+    if let Command::RunMigrations { dir, baseline } = &command {
+        let mut client = db::connect_with_retry(&config.database)
+            .await
+            .map_err(|e| e.to_string())?;
+        let applied = db::run_migrations(&mut client, dir, *baseline)
+            .await
+            .map_err(|e| e.to_string())?;
+        let verb = if *baseline { "Recorded" } else { "Applied" };
+        for version in &applied {
+            println!("{} {}", verb, version);
+        }
+        println!("{} {} migrations", verb, applied.len());
+        return Ok(());
+    }
+
+    let client = db::init_database(&config.database)
+        .await
+        .map_err(|e| e.to_string())?;
+    let preferences = PostgresPreferencesRepository::new(client.clone());
+    let mut repository = PostgresUserRepository::new(client);
+    if let Some(cipher) = FieldCipher::from_config(&config.encryption)? {
+        repository = repository.with_cipher(Arc::new(cipher));
+    }
+
+    if let Command::RekeyPii = command {
+        let count = repository.rekey().await.map_err(|e| e.to_string())?;
+        println!("Re-keyed {} users", count);
+        return Ok(());
+    }
+
+    if let Command::Seed { count, batch_size } = command {
+        if config.profile == Profile::Prod {
+            return Err("Refusing to seed fake users in the prod profile".to_string());
+        }
+        // Numbering continues after the existing users, so reruns add new emails
+        let start = repository.count().await.map_err(|e| e.to_string())? as usize;
+        let mut rng = fake::rand::rng();
+        let users: Vec<User> = (start..start + count)
+            .map(|number| seed::fake_user(&mut rng, number))
+            .collect();
+
+        let report = repository.create_many(&users, batch_size).await;
+        println!("Seeded {} users", report.inserted());
+        let failed: Vec<_> = report.failed().collect();
+        for chunk in &failed {
+            eprintln!(
+                "Users {}..{} not inserted: {}",
+                chunk.offset,
+                chunk.offset + chunk.len,
+                chunk.error.as_deref().unwrap_or_default()
+            );
+        }
+        if !failed.is_empty() {
+            return Err(format!("{} of {} batches failed", failed.len(), report.chunks.len()));
+        }
+        return Ok(());
+    }
+
+    if let Command::Backup { output } = &command {
+        let key = backup_key(&config)?;
+        let backup = Backup::collect(&repository, &preferences).await?;
+        let archive = key.seal(&backup)?;
+        std::fs::write(output, archive).map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+        println!("Backed up {} users to {}", backup.users.len(), output.display());
+        return Ok(());
+    }
+
+    if let Command::Restore { input, dry_run, on_conflict } = &command {
+        let key = backup_key(&config)?;
+        let archive = std::fs::read(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+        let backup = key.open(&archive)?;
+        println!("Backup of {} with {} users", backup.created_at, backup.users.len());
+        let report = backup::restore(&backup, &repository, &preferences, *on_conflict, *dry_run).await?;
+        for email in &report.created {
+            println!("Create    {}", email);
+        }
+        for email in &report.overwritten {
+            println!("Overwrite {}", email);
+        }
+        for email in &report.skipped {
+            println!("Skip      {}", email);
+        }
+        let verb = if *dry_run { "Would restore" } else { "Restored" };
+        println!(
+            "{} {} users ({} created, {} overwritten), skipped {}",
+            verb,
+            report.created.len() + report.overwritten.len(),
+            report.created.len(),
+            report.overwritten.len(),
+            report.skipped.len()
+        );
+        return Ok(());
+    }
+
+    let service = UserService::new(Arc::new(repository), EventBus::default())
+        .with_password_history(config.password_policy.history);
+    match command {
+        Command::CreateAdmin { name, email, password } => {
+            let email = normalize_email(&email);
+            let (password, generated) = password_or_generated(password);
+            service
+                .create_user(User::new(name, email.clone(), password.clone()))
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("Created admin {}", email);
+            if generated {
+                println!("Password: {}", password);
+            }
+        }
+        Command::ListUsers => {
+            let users = service.get_all_users().await.map_err(|e| e.to_string())?;
+            println!("{:<6} {:<24} {:<32} {:<10} LAST SEEN", "ID", "NAME", "EMAIL", "STATUS");
+            for user in &users {
+                println!(
+                    "{:<6} {:<24} {:<32} {:<10} {}",
+                    user.id.unwrap_or_default(),
+                    user.name,
+                    user.email,
+                    user.status.as_str(),
+                    user.last_login_at.as_deref().unwrap_or("never")
+                );
+            }
+            println!("{} users", users.len());
+        }
+        Command::ResetPassword { email, password } => {
+            let email = normalize_email(&email);
+            let user = service.get_user_by_email(&email).await.map_err(|e| e.to_string())?;
+            let (password, generated) = password_or_generated(password);
+            let id = user.id.unwrap_or_default();
+            service
+                .update_user(id, User { password: password.clone(), ..user })
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("Password of {} reset", email);
+            if generated {
+                println!("Password: {}", password);
+            }
+        }
+        Command::DuplicateEmails => {
+            let duplicates = service.duplicate_emails().await.map_err(|e| e.to_string())?;
+            for (email, users) in &duplicates {
+                println!("{}", email);
+                for user in users {
+                    println!("  {:<6} {:<24} {}", user.id.unwrap_or_default(), user.name, user.email);
+                }
+            }
+            println!("{} emails used by more than one user", duplicates.len());
+        }
+        Command::RunMigrations { .. }
+        | Command::RekeyPii
+        | Command::Seed { .. }
+        | Command::Backup { .. }
+        | Command::Restore { .. } => {
+            unreachable!("handled above")
+        }
+    }
+    Ok(())
+}
+
+/// Key of the backup archives, required by backup and restore
+fn backup_key(config: &AppConfig) -> Result<BackupKey, String> {
+    let key = config
+        .encryption
+        .backup_key
+        .as_ref()
+        .ok_or_else(|| "BACKUP_ENCRYPTION_KEY must be set to back up or restore".to_string())?;
+    BackupKey::from_hex(key.expose())
+}
+
+/// The given password, or a strong random one (flagged so it can be shown)
+fn password_or_generated(password: Option<String>) -> (String, bool) {
+    match password {
+        Some(password) => (password, false),
+        None => {
+            let mut bytes = [0u8; GENERATED_PASSWORD_LENGTH];
+            OsRng.fill_bytes(&mut bytes);
+            (generate_password(&bytes), true)
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::errors::{AppError, FieldError};
+use rocket::data::{FromData, Limits, Outcome};
+use rocket::serde::DeserializeOwned;
+use rocket::{Data, Request};
+
+// Request bodies - Single Responsibility Principle
+// JsonBody replaces Rocket's Json data guard for incoming payloads: it
+// enforces the configured size limit and turns deserialization failures
+// into AppErrors naming the offending field, which the catchers render
+
+/// Failure of the body guard, kept for the catcher of the request
+#[derive(Debug, Clone, Default)]
+pub struct BodyError(pub Option<AppError>);
+
+impl BodyError {
+    /// The body error recorded for `request`, if any
+    pub fn of(request: &Request<'_>) -> Option<AppError> {
+        request.local_cache(BodyError::default).0.clone()
+    }
+}
+
+/// JSON request body deserialized with field-level error reporting
+#[derive(Debug)]
+pub struct JsonBody<T>(pub T);
+
+impl<T> JsonBody<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Map a deserialization failure to the error returned to the client
+pub fn parse_error(error: serde_path_to_error::Error<serde_json::Error>) -> AppError {
+    let inner = error.inner();
+    // serde_json appends " at line X column Y" to every message
+    let message = inner.to_string();
+    let message = message
+        .rsplit_once(" at line ")
+        .map_or(message.as_str(), |(message, _)| message)
+        .to_string();
+
+    if inner.is_syntax() || inner.is_eof() {
+        return AppError::BadRequest(format!(
+            "Malformed JSON at line {} column {}: {}",
+            inner.line(),
+            inner.column(),
+            message
+        ));
+    }
+
+    let path = error.path().to_string();
+    // A missing field is reported on its parent, so append the field name
+    let field = match message.strip_prefix("missing field `") {
+        Some(rest) => {
+            let name = rest.trim_end_matches('`');
+            if path == "." { name.to_string() } else { format!("{}.{}", path, name) }
+        }
+        None => path,
+    };
+    let expected = message
+        .split_once(", expected ")
+        .map(|(_, expected)| expected.to_string());
+
+    AppError::InvalidField(FieldError {
+        field,
+        expected,
+        message,
+    })
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for JsonBody<T> {
+    type Error = AppError;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        read_json(request, data, "json").await.map(JsonBody)
+    }
+}
+
+/// Deserialize a JSON body capped by the Rocket limit named `limit_name`
+/// (Rocket's JSON default when it is not configured). Failures are recorded
+/// for the catcher, like those of JsonBody
+pub async fn read_json<'r, T: DeserializeOwned>(
+    request: &'r Request<'_>,
+    data: Data<'r>,
+    limit_name: &str,
+) -> Outcome<'r, T, AppError> {
+    let limit = request.limits().get(limit_name).unwrap_or(Limits::JSON);
+    let result = match data.open(limit).into_string().await {
+        Ok(body) if body.is_complete() => {
+            let deserializer = &mut serde_json::Deserializer::from_str(&body);
+            serde_path_to_error::deserialize(deserializer).map_err(parse_error)
+        }
+        Ok(_) => Err(AppError::PayloadTooLarge(format!(
+            "Request body exceeds the {} limit",
+            limit
+        ))),
+        Err(e) => Err(AppError::BadRequest(format!("Failed to read request body: {}", e))),
+    };
+
+    match result {
+        Ok(value) => Outcome::Success(value),
+        Err(error) => {
+            request.local_cache(|| BodyError(Some(error.clone())));
+            Outcome::Error((error.status(), error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Status;
+    use rocket::serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(crate = "rocket::serde")]
+    #[allow(dead_code)]
+    struct Payload {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    fn error_for(body: &str) -> AppError {
+        let deserializer = &mut serde_json::Deserializer::from_str(body);
+        parse_error(serde_path_to_error::deserialize::<_, Payload>(deserializer).unwrap_err())
+    }
+
+    #[test]
+    fn test_invalid_type_names_field_and_expected_type() {
+        let AppError::InvalidField(error) = error_for(r#"{"name": 5, "tags": []}"#) else {
+            panic!("expected a field error");
+        };
+        assert_eq!(error.field, "name");
+        assert_eq!(error.expected.as_deref(), Some("a string"));
+        assert_eq!(error.message, "invalid type: integer `5`, expected a string");
+    }
+
+    #[test]
+    fn test_nested_and_missing_fields() {
+        let AppError::InvalidField(error) = error_for(r#"{"name": "a", "tags": ["x", 1]}"#) else {
+            panic!("expected a field error");
+        };
+        assert_eq!(error.field, "tags[1]");
+
+        let AppError::InvalidField(error) = error_for(r#"{"tags": []}"#) else {
+            panic!("expected a field error");
+        };
+        assert_eq!(error.field, "name");
+        assert_eq!(error.expected, None);
+    }
+
+    #[test]
+    fn test_syntax_errors_are_bad_requests() {
+        let error = error_for(r#"{"name": "#);
+        assert_eq!(error.status(), Status::BadRequest);
+        assert!(error.message().starts_with("Malformed JSON at line 1"));
+    }
+}
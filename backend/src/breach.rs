@@ -0,0 +1,282 @@
+use crate::config::{BreachCheckMode, PasswordPolicyConfig};
+use crate::domain::DomainError;
+use crate::versioning::WARNING_HEADER;
+use async_trait::async_trait;
+use rocket::http::Header;
+use rocket::response::{self, Responder};
+use rocket::Request;
+use shared::password::validate_password;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Breached passwords - Dependency Inversion Principle
+// Signups and password changes ask a BreachedPasswordChecker whether the new
+// password is known from data breaches. The Pwned Passwords range API uses
+// k-anonymity: only the first five hex digits of the password's SHA-1 leave
+// the server, and the matching suffixes come back. SHA-1 is merely the lookup
+// key that API defines, so it is computed here instead of adding a crate.
+// The check is off by default, which keeps development and tests offline
+
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Hex digits of the hash sent to the range API
+const PREFIX_LEN: usize = 5;
+
+pub const BREACHED_PASSWORD_MESSAGE: &str = "Password has appeared in a data breach";
+
+/// SHA-1 of `input` as uppercase hex, the form the range API answers with
+pub fn sha1_hex(input: &[u8]) -> String {
+    let mut state: [u32; 5] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476, 0xC3D2_E1F0];
+    let mut message = input.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((input.len() as u64).wrapping_mul(8)).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut words = [0u32; 80];
+        for (word, bytes) in words.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6),
+            };
+            let next = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = next;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+    state.iter().map(|word| format!("{:08X}", word)).collect()
+}
+
+/// Count of `suffix` in a range response made of `SUFFIX:COUNT` lines;
+/// 0 when it is missing or only one of the padding entries
+pub fn count_in_range(body: &str, suffix: &str) -> u64 {
+    body.lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Tells how often a password appears in known data breaches
+#[async_trait]
+pub trait BreachedPasswordChecker: Send + Sync {
+    async fn breach_count(&self, password: &str) -> Result<u64, String>;
+}
+
+/// Knows no breaches; used while the check is off
+pub struct NoopBreachedPasswordChecker;
+
+#[async_trait]
+impl BreachedPasswordChecker for NoopBreachedPasswordChecker {
+    async fn breach_count(&self, _password: &str) -> Result<u64, String> {
+        Ok(0)
+    }
+}
+
+/// Looks passwords up in the Pwned Passwords range API
+pub struct PwnedPasswordsChecker {
+    client: reqwest::Client,
+    range_url: String,
+}
+
+impl PwnedPasswordsChecker {
+    pub fn new(range_url: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(LOOKUP_TIMEOUT)
+            // The API refuses requests without a user agent
+            .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("Failed to build breach check HTTP client");
+        PwnedPasswordsChecker {
+            client,
+            range_url: range_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl BreachedPasswordChecker for PwnedPasswordsChecker {
+    async fn breach_count(&self, password: &str) -> Result<u64, String> {
+        let hash = sha1_hex(password.as_bytes());
+        let (prefix, suffix) = hash.split_at(PREFIX_LEN);
+        let body = self
+            .client
+            .get(format!("{}/{}", self.range_url, prefix))
+            // Padded answers do not reveal the prefix through their size
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(count_in_range(&body, suffix))
+    }
+}
+
+/// Checks new passwords as the password policy asks; managed by Rocket
+pub struct PasswordScreen {
+    checker: Arc<dyn BreachedPasswordChecker>,
+    mode: BreachCheckMode,
+}
+
+impl PasswordScreen {
+    pub fn new(checker: Arc<dyn BreachedPasswordChecker>, mode: BreachCheckMode) -> Self {
+        PasswordScreen { checker, mode }
+    }
+
+    pub fn from_config(config: &PasswordPolicyConfig) -> Self {
+        let checker: Arc<dyn BreachedPasswordChecker> = match config.breach_check {
+            BreachCheckMode::Off => Arc::new(NoopBreachedPasswordChecker),
+            BreachCheckMode::Warn | BreachCheckMode::Reject => {
+                Arc::new(PwnedPasswordsChecker::new(&config.breach_api_url))
+            }
+        };
+        PasswordScreen::new(checker, config.breach_check)
+    }
+
+    /// Check a password about to be set: an error when it is breached and the
+    /// mode rejects it, the warning to send when the mode only warns. A failed
+    /// lookup lets the password through, so an outage never blocks signups
+    pub async fn screen(&self, password: &str) -> Result<Option<String>, DomainError> {
+        // Passwords failing validation are rejected before they are stored anyway
+        if self.mode == BreachCheckMode::Off || validate_password(password).is_err() {
+            return Ok(None);
+        }
+        let count = match self.checker.breach_count(password).await {
+            Ok(count) => count,
+            Err(e) => {
+                eprintln!("Password breach lookup failed: {}", e);
+                return Ok(None);
+            }
+        };
+        match (self.mode, count) {
+            (_, 0) => Ok(None),
+            (BreachCheckMode::Reject, _) => Err(DomainError::Invalid(BREACHED_PASSWORD_MESSAGE.to_string())),
+            _ => Ok(Some(BREACHED_PASSWORD_MESSAGE.to_string())),
+        }
+    }
+}
+
+/// Responder that adds a `Warning: 299` header when there is something to warn about
+pub struct WithWarning<R>(pub Option<String>, pub R);
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for WithWarning<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = self.1.respond_to(request)?;
+        if let Some(warning) = self.0 {
+            response.set_header(Header::new(WARNING_HEADER, format!("299 - \"{}\"", warning)));
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use rocket::local::blocking::Client;
+
+    /// Password MockBreachedPasswordChecker reports as breached
+    pub const BREACHED_PASSWORD: &str = "breached-password";
+
+    // Knows BREACHED_PASSWORD only; "unreachable" fails the lookup
+    pub struct MockBreachedPasswordChecker;
+
+    #[async_trait]
+    impl BreachedPasswordChecker for MockBreachedPasswordChecker {
+        async fn breach_count(&self, password: &str) -> Result<u64, String> {
+            match password {
+                BREACHED_PASSWORD => Ok(42),
+                "unreachable" => Err("connection refused".to_string()),
+                _ => Ok(0),
+            }
+        }
+    }
+
+    fn screen(mode: BreachCheckMode) -> PasswordScreen {
+        PasswordScreen::new(Arc::new(MockBreachedPasswordChecker), mode)
+    }
+
+    #[test]
+    fn test_sha1_hex() {
+        assert_eq!(sha1_hex(b""), "DA39A3EE5E6B4B0D3255BFEF95601890AFD80709");
+        assert_eq!(sha1_hex(b"abc"), "A9993E364706816ABA3E25717850C26C9CD0D89D");
+        assert_eq!(sha1_hex(b"password"), "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8");
+        // Two blocks
+        assert_eq!(
+            sha1_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "84983E441C3BD26EBAAE4AA1F95129E5E54670F1"
+        );
+    }
+
+    #[test]
+    fn test_count_in_range() {
+        let body = "003D68EB55068C33ACE09247EE4C639306B:3\r\n1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n\
+                    0018A45C4D1DEF81644B54AB7F969B88D65:0\r\n";
+        assert_eq!(count_in_range(body, "1E4C9B93F3F0682250B6CF8331B7EE68FD8"), 9545824);
+        assert_eq!(count_in_range(body, "1e4c9b93f3f0682250b6cf8331b7ee68fd8"), 9545824);
+        assert_eq!(count_in_range(body, "0018A45C4D1DEF81644B54AB7F969B88D65"), 0);
+        assert_eq!(count_in_range(body, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_screen_modes() {
+        let breached = Err(DomainError::Invalid(BREACHED_PASSWORD_MESSAGE.to_string()));
+        assert_eq!(screen(BreachCheckMode::Reject).screen(BREACHED_PASSWORD).await, breached);
+        assert_eq!(
+            screen(BreachCheckMode::Warn).screen(BREACHED_PASSWORD).await,
+            Ok(Some(BREACHED_PASSWORD_MESSAGE.to_string()))
+        );
+        assert_eq!(screen(BreachCheckMode::Off).screen(BREACHED_PASSWORD).await, Ok(None));
+        assert_eq!(screen(BreachCheckMode::Reject).screen("password123").await, Ok(None));
+    }
+
+    #[get("/warned")]
+    fn warned() -> WithWarning<&'static str> {
+        WithWarning(Some(BREACHED_PASSWORD_MESSAGE.to_string()), "ok")
+    }
+
+    #[get("/plain")]
+    fn plain() -> WithWarning<&'static str> {
+        WithWarning(None, "ok")
+    }
+
+    #[test]
+    fn test_with_warning_sets_the_warning_header() {
+        let client = Client::tracked(rocket::build().mount("/", routes![warned, plain])).unwrap();
+        let response = client.get("/warned").dispatch();
+        assert_eq!(
+            response.headers().get_one(WARNING_HEADER),
+            Some("299 - \"Password has appeared in a data breach\"")
+        );
+        assert_eq!(client.get("/plain").dispatch().headers().get_one(WARNING_HEADER), None);
+    }
+
+    #[tokio::test]
+    async fn test_screen_lets_passwords_through_when_the_lookup_fails() {
+        assert_eq!(screen(BreachCheckMode::Reject).screen("unreachable").await, Ok(None));
+    }
+}
@@ -0,0 +1,606 @@
+use crate::config::{CacheBackend, CacheConfig};
+use crate::domain::RepositoryError;
+use crate::models::{TagCount, User, UserMatch, UserStats, UserStatus};
+use crate::repository::{BulkInsertReport, UserRepository};
+use crate::unit_of_work::{UnitOfWork, UnitOfWorkFactory};
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use lru::LruCache;
+use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
+use rocket::serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Read cache - Open/Closed Principle
+// CachedUserRepository decorates any UserRepository, serving list and
+// get-by-id reads from a Cache and invalidating entries on writes.
+// The service layer and the Postgres repository are unaware of it.
+// Inside a unit of work reads bypass the cache and invalidations wait for
+// the commit, so uncommitted data is never cached
+
+const ALL_USERS_KEY: &str = "users:all";
+
+fn user_key(id: i32) -> String {
+    format!("users:id:{}", id)
+}
+
+/// A cached user; the fields a User never takes from request bodies are
+/// skipped when it is deserialized, so they are stored beside it
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct CachedUser {
+    user: User,
+    last_login_at: Option<String>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+impl From<&User> for CachedUser {
+    fn from(user: &User) -> Self {
+        CachedUser {
+            user: user.clone(),
+            last_login_at: user.last_login_at.clone(),
+            created_at: user.created_at.clone(),
+            updated_at: user.updated_at.clone(),
+        }
+    }
+}
+
+impl From<CachedUser> for User {
+    fn from(cached: CachedUser) -> Self {
+        User {
+            last_login_at: cached.last_login_at,
+            created_at: cached.created_at,
+            updated_at: cached.updated_at,
+            ..cached.user
+        }
+    }
+}
+
+/// Key/value store for serialized entries; failures behave like misses
+#[async_trait]
+pub trait Cache: Send + Sync {
+    async fn get(&self, key: &str) -> Option<Vec<u8>>;
+    async fn set(&self, key: &str, value: Vec<u8>);
+    async fn delete(&self, keys: &[String]);
+}
+
+/// Bounded in-process LRU cache with a time-to-live per entry
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, (Instant, Vec<u8>)>>,
+    ttl: Duration,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        MemoryCache {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((expires_at, value)) if *expires_at > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>) {
+        let expires_at = Instant::now() + self.ttl;
+        self.entries
+            .lock()
+            .unwrap()
+            .put(key.to_string(), (expires_at, value));
+    }
+
+    async fn delete(&self, keys: &[String]) {
+        let mut entries = self.entries.lock().unwrap();
+        for key in keys {
+            entries.pop(key);
+        }
+    }
+}
+
+/// Redis-backed cache shared by every backend instance
+pub struct RedisCache {
+    connection: ConnectionManager,
+    ttl: Duration,
+}
+
+impl RedisCache {
+    pub async fn connect(url: &str, ttl: Duration) -> Result<Self, String> {
+        let client = redis::Client::open(url).map_err(|e| e.to_string())?;
+        let connection = ConnectionManager::new(client)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(RedisCache { connection, ttl })
+    }
+}
+
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let mut connection = self.connection.clone();
+        connection
+            .get::<_, Option<Vec<u8>>>(key)
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Redis GET {} failed: {}", key, e);
+                None
+            })
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>) {
+        let mut connection = self.connection.clone();
+        let ttl = self.ttl.as_secs().max(1);
+        if let Err(e) = connection.set_ex::<_, _, ()>(key, value, ttl).await {
+            eprintln!("Redis SET {} failed: {}", key, e);
+        }
+    }
+
+    async fn delete(&self, keys: &[String]) {
+        let mut connection = self.connection.clone();
+        if let Err(e) = connection.del::<_, ()>(keys).await {
+            eprintln!("Redis DEL failed: {}", e);
+        }
+    }
+}
+
+/// Hit/miss counters reported by the admin endpoint
+#[derive(Debug, Default)]
+pub struct CacheMetrics {
+    backend: &'static str,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Snapshot of the cache metrics
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct CacheStats {
+    pub backend: String,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheMetrics {
+    pub fn new(backend: &'static str) -> Self {
+        CacheMetrics {
+            backend,
+            ..CacheMetrics::default()
+        }
+    }
+
+    fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> CacheStats {
+        CacheStats {
+            backend: self.backend.to_string(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// UserRepository decorator caching `find_all` and `find_by_id`
+pub struct CachedUserRepository {
+    inner: Arc<dyn UserRepository>,
+    cache: Arc<dyn Cache>,
+    metrics: Arc<CacheMetrics>,
+    /// Keys to invalidate on commit, when decorating a unit of work
+    pending: Option<Mutex<Vec<String>>>,
+}
+
+impl CachedUserRepository {
+    pub fn new(
+        inner: Arc<dyn UserRepository>,
+        cache: Arc<dyn Cache>,
+        metrics: Arc<CacheMetrics>,
+    ) -> Self {
+        CachedUserRepository {
+            inner,
+            cache,
+            metrics,
+            pending: None,
+        }
+    }
+
+    /// Decorator of the repository of a unit of work: reads go to `inner`
+    /// and invalidations are deferred until `flush`
+    pub fn in_unit_of_work(
+        inner: Arc<dyn UserRepository>,
+        cache: Arc<dyn Cache>,
+        metrics: Arc<CacheMetrics>,
+    ) -> Self {
+        CachedUserRepository {
+            pending: Some(Mutex::new(Vec::new())),
+            ..CachedUserRepository::new(inner, cache, metrics)
+        }
+    }
+
+    async fn invalidate(&self, keys: Vec<String>) {
+        match &self.pending {
+            Some(pending) => pending.lock().unwrap().extend(keys),
+            None => self.cache.delete(&keys).await,
+        }
+    }
+
+    /// Apply the invalidations deferred by a unit of work
+    pub async fn flush(&self) {
+        let Some(pending) = &self.pending else {
+            return;
+        };
+        let mut keys = std::mem::take(&mut *pending.lock().unwrap());
+        keys.sort();
+        keys.dedup();
+        if !keys.is_empty() {
+            self.cache.delete(&keys).await;
+        }
+    }
+
+    async fn cached<T: Serialize + for<'de> Deserialize<'de>>(&self, key: &str) -> Option<T> {
+        if self.pending.is_some() {
+            return None;
+        }
+        let value = self
+            .cache
+            .get(key)
+            .await
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+        self.metrics.record(value.is_some());
+        value
+    }
+
+    async fn store<T: Serialize>(&self, key: &str, value: &T) {
+        if self.pending.is_some() {
+            return;
+        }
+        if let Ok(bytes) = serde_json::to_vec(value) {
+            self.cache.set(key, bytes).await;
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for CachedUserRepository {
+    async fn create(&self, user: &User) -> Result<User, RepositoryError> {
+        let created = self.inner.create(user).await?;
+        self.invalidate(vec![ALL_USERS_KEY.to_string()]).await;
+        Ok(created)
+    }
+
+    async fn create_many(&self, users: &[User], chunk_size: usize) -> BulkInsertReport {
+        let report = self.inner.create_many(users, chunk_size).await;
+        self.invalidate(vec![ALL_USERS_KEY.to_string()]).await;
+        report
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        if let Some(users) = self.cached::<Vec<CachedUser>>(ALL_USERS_KEY).await {
+            return Ok(users.into_iter().map(User::from).collect());
+        }
+        let users = self.inner.find_all().await?;
+        let cached: Vec<CachedUser> = users.iter().map(CachedUser::from).collect();
+        self.store(ALL_USERS_KEY, &cached).await;
+        Ok(users)
+    }
+
+    async fn find_by_id(&self, id: i32) -> Result<Option<User>, RepositoryError> {
+        let key = user_key(id);
+        if let Some(user) = self.cached::<CachedUser>(&key).await {
+            return Ok(Some(user.into()));
+        }
+        let user = self.inner.find_by_id(id).await?;
+        // Misses are not cached so a user created right after is found immediately
+        if let Some(user) = &user {
+            self.store(&key, &CachedUser::from(user)).await;
+        }
+        Ok(user)
+    }
+
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<User>, RepositoryError> {
+        self.inner.find_by_ids(ids).await
+    }
+
+    // Email lookups guard writes, so they always read the database
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, RepositoryError> {
+        self.inner.find_by_email(email).await
+    }
+
+    async fn exists_by_email(&self, email: &str) -> Result<bool, RepositoryError> {
+        self.inner.exists_by_email(email).await
+    }
+
+    // Username lookups guard writes too
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+        self.inner.find_by_username(username).await
+    }
+
+    async fn find_page(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        self.inner.find_page(limit, offset).await
+    }
+
+    async fn find_page_by_tag(&self, tag: &str, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        self.inner.find_page_by_tag(tag, limit, offset).await
+    }
+
+    async fn count_by_tag(&self, tag: &str) -> Result<i64, RepositoryError> {
+        self.inner.count_by_tag(tag).await
+    }
+
+    async fn tag_counts(&self) -> Result<Vec<TagCount>, RepositoryError> {
+        self.inner.tag_counts().await
+    }
+
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<UserMatch>, RepositoryError> {
+        self.inner.search(query, limit).await
+    }
+
+    // Exports always read the database; caching them would defeat the streaming
+    fn find_all_stream(&self, batch_size: i64) -> BoxStream<'static, Result<User, RepositoryError>> {
+        self.inner.find_all_stream(batch_size)
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        self.inner.count().await
+    }
+
+    async fn stats(&self, days: i32) -> Result<UserStats, RepositoryError> {
+        self.inner.stats(days).await
+    }
+
+    async fn update(&self, id: i32, user: &User) -> Result<(), RepositoryError> {
+        self.inner.update(id, user).await?;
+        self.invalidate(vec![ALL_USERS_KEY.to_string(), user_key(id)]).await;
+        Ok(())
+    }
+
+    async fn delete(&self, id: i32) -> Result<(), RepositoryError> {
+        self.inner.delete(id).await?;
+        self.invalidate(vec![ALL_USERS_KEY.to_string(), user_key(id)]).await;
+        Ok(())
+    }
+
+    async fn set_status(&self, id: i32, status: UserStatus) -> Result<(), RepositoryError> {
+        self.inner.set_status(id, status).await?;
+        self.invalidate(vec![ALL_USERS_KEY.to_string(), user_key(id)]).await;
+        Ok(())
+    }
+
+    async fn set_tags(&self, id: i32, tags: &[String]) -> Result<(), RepositoryError> {
+        self.inner.set_tags(id, tags).await?;
+        self.invalidate(vec![ALL_USERS_KEY.to_string(), user_key(id)]).await;
+        Ok(())
+    }
+
+    async fn record_login(&self, id: i32) -> Result<(), RepositoryError> {
+        self.inner.record_login(id).await?;
+        self.invalidate(vec![ALL_USERS_KEY.to_string(), user_key(id)]).await;
+        Ok(())
+    }
+
+    // Password hashes are never cached
+    async fn password_history(&self, id: i32, limit: i64) -> Result<Vec<String>, RepositoryError> {
+        self.inner.password_history(id, limit).await
+    }
+
+    async fn record_password(&self, id: i32, hash: &str, keep: i64) -> Result<(), RepositoryError> {
+        self.inner.record_password(id, hash, keep).await
+    }
+
+    async fn anonymize(&self, id: i32, anonymized: &User) -> Result<(), RepositoryError> {
+        self.inner.anonymize(id, anonymized).await?;
+        self.invalidate(vec![ALL_USERS_KEY.to_string(), user_key(id)]).await;
+        Ok(())
+    }
+}
+
+/// UnitOfWorkFactory decorator invalidating the cache entries written by a
+/// unit of work once it commits
+pub struct CachedUnitOfWorkFactory {
+    inner: Arc<dyn UnitOfWorkFactory>,
+    cache: Arc<dyn Cache>,
+    metrics: Arc<CacheMetrics>,
+}
+
+struct CachedUnitOfWork {
+    inner: Box<dyn UnitOfWork>,
+    users: Arc<CachedUserRepository>,
+}
+
+#[async_trait]
+impl UnitOfWork for CachedUnitOfWork {
+    fn users(&self) -> Arc<dyn UserRepository> {
+        self.users.clone()
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+        self.inner.commit().await?;
+        self.users.flush().await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UnitOfWorkFactory for CachedUnitOfWorkFactory {
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork>, RepositoryError> {
+        let inner = self.inner.begin().await?;
+        let users = Arc::new(CachedUserRepository::in_unit_of_work(
+            inner.users(),
+            self.cache.clone(),
+            self.metrics.clone(),
+        ));
+        Ok(Box::new(CachedUnitOfWork { inner, users }))
+    }
+}
+
+/// Users repository and units of work, decorated with the cache when enabled
+pub struct CachedLayers {
+    pub repository: Arc<dyn UserRepository>,
+    pub units: Arc<dyn UnitOfWorkFactory>,
+    pub metrics: Arc<CacheMetrics>,
+}
+
+/// Wrap `repository` and `units` with the configured cache, if any
+pub async fn with_cache(
+    repository: Arc<dyn UserRepository>,
+    units: Arc<dyn UnitOfWorkFactory>,
+    config: &CacheConfig,
+) -> Result<CachedLayers, String> {
+    let cache: Arc<dyn Cache> = match config.backend {
+        CacheBackend::None => {
+            return Ok(CachedLayers {
+                repository,
+                units,
+                metrics: Arc::new(CacheMetrics::new("none")),
+            });
+        }
+        CacheBackend::Memory => Arc::new(MemoryCache::new(config.capacity, config.ttl)),
+        CacheBackend::Redis => Arc::new(RedisCache::connect(config.redis_url.expose(), config.ttl).await?),
+    };
+    let metrics = Arc::new(CacheMetrics::new(config.backend.as_str()));
+    Ok(CachedLayers {
+        repository: Arc::new(CachedUserRepository::new(repository, cache.clone(), metrics.clone())),
+        units: Arc::new(CachedUnitOfWorkFactory {
+            inner: units,
+            cache,
+            metrics: metrics.clone(),
+        }),
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::tests::MockUserRepository;
+    use crate::unit_of_work::tests::MockUnitOfWorkFactory;
+
+    fn cached_repository() -> (Arc<MockUserRepository>, CachedUserRepository, Arc<CacheMetrics>) {
+        let inner = Arc::new(MockUserRepository::new());
+        let metrics = Arc::new(CacheMetrics::new("memory"));
+        let repository = CachedUserRepository::new(
+            inner.clone(),
+            Arc::new(MemoryCache::new(16, Duration::from_secs(60))),
+            metrics.clone(),
+        );
+        (inner, repository, metrics)
+    }
+
+    fn user(name: &str) -> User {
+        User::new(
+            name.to_string(),
+            format!("{}@example.com", name.to_lowercase()),
+            "password123".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_evicts_least_recently_used() {
+        let cache = MemoryCache::new(2, Duration::from_secs(60));
+        cache.set("a", b"1".to_vec()).await;
+        cache.set("b", b"2".to_vec()).await;
+        cache.get("a").await;
+        cache.set("c", b"3".to_vec()).await;
+
+        assert_eq!(cache.get("a").await, Some(b"1".to_vec()));
+        assert_eq!(cache.get("b").await, None);
+        assert_eq!(cache.get("c").await, Some(b"3".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_expires_entries() {
+        let cache = MemoryCache::new(2, Duration::ZERO);
+        cache.set("a", b"1".to_vec()).await;
+        assert_eq!(cache.get("a").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_find_all_is_served_from_cache() {
+        let (inner, repository, metrics) = cached_repository();
+        repository.create(&user("John")).await.unwrap();
+
+        assert_eq!(repository.find_all().await.unwrap().len(), 1);
+        // Bypass the decorator: the cached list does not see this user
+        inner.create(&user("Jane")).await.unwrap();
+        assert_eq!(repository.find_all().await.unwrap().len(), 1);
+
+        let stats = metrics.snapshot();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_cached_users_keep_server_set_fields() {
+        let (_, repository, metrics) = cached_repository();
+        repository.create(&user("John")).await.unwrap();
+        repository.record_login(1).await.unwrap();
+        let fresh = repository.find_by_id(1).await.unwrap().unwrap();
+        assert!(fresh.updated_at.is_some());
+
+        assert_eq!(repository.find_by_id(1).await.unwrap(), Some(fresh.clone()));
+        repository.find_all().await.unwrap();
+        assert_eq!(repository.find_all().await.unwrap(), vec![fresh]);
+        assert_eq!(metrics.snapshot().hits, 2);
+    }
+
+    #[tokio::test]
+    async fn test_writes_invalidate_cached_entries() {
+        let (_, repository, _) = cached_repository();
+        repository.create(&user("John")).await.unwrap();
+        repository.find_all().await.unwrap();
+        repository.find_by_id(1).await.unwrap();
+
+        repository.update(1, &user("Johnny")).await.unwrap();
+        assert_eq!(repository.find_all().await.unwrap()[0].name, "Johnny");
+        assert_eq!(repository.find_by_id(1).await.unwrap().unwrap().name, "Johnny");
+
+        repository.create(&user("Jane")).await.unwrap();
+        assert_eq!(repository.find_all().await.unwrap().len(), 2);
+
+        repository.delete(1).await.unwrap();
+        assert!(repository.find_by_id(1).await.unwrap().is_none());
+        assert_eq!(repository.find_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unit_of_work_invalidates_after_commit() {
+        let (inner, repository, _) = cached_repository();
+        repository.create(&user("John")).await.unwrap();
+        repository.find_all().await.unwrap();
+
+        let factory = CachedUnitOfWorkFactory {
+            inner: Arc::new(MockUnitOfWorkFactory::new(inner.clone())),
+            cache: repository.cache.clone(),
+            metrics: repository.metrics.clone(),
+        };
+
+        // Rolled back: the cached list stays valid
+        let unit = factory.begin().await.unwrap();
+        unit.users().create(&user("Jane")).await.unwrap();
+        assert_eq!(unit.users().find_all().await.unwrap().len(), 2);
+        drop(unit);
+        assert_eq!(repository.find_all().await.unwrap().len(), 1);
+
+        let unit = factory.begin().await.unwrap();
+        unit.users().create(&user("Jane")).await.unwrap();
+        assert_eq!(repository.find_all().await.unwrap().len(), 1);
+        unit.commit().await.unwrap();
+        assert_eq!(repository.find_all().await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,220 @@
+use rocket::Request;
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::{self, Responder, Response};
+use rocket::serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::sync::LazyLock;
+use time::format_description::well_known::Rfc3339;
+use time::format_description::{self, BorrowedFormatItem};
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+// HTTP caching - Single Responsibility Principle
+// Weak ETags let clients revalidate cached lists with `If-None-Match`
+// and receive an empty 304 instead of the full body. Single users are
+// revalidated by date instead: `Last-Modified` comes from their `updated_at`
+// and `If-Modified-Since` answers 304 while it has not moved on
+
+/// Format of `Last-Modified` and `If-Modified-Since`, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+static HTTP_DATE: LazyLock<Vec<BorrowedFormatItem<'static>>> = LazyLock::new(|| {
+    format_description::parse("[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT")
+        .expect("valid HTTP date format")
+});
+
+/// `time` as an HTTP date, in UTC
+pub fn http_date(time: OffsetDateTime) -> String {
+    time.to_offset(UtcOffset::UTC)
+        .format(&*HTTP_DATE)
+        .expect("HTTP dates format every UTC time")
+}
+
+/// Parse an HTTP date; None when malformed
+pub fn parse_http_date(value: &str) -> Option<OffsetDateTime> {
+    PrimitiveDateTime::parse(value.trim(), &*HTTP_DATE)
+        .ok()
+        .map(PrimitiveDateTime::assume_utc)
+}
+
+/// Compute a weak ETag from the number of items and a hash of their content
+///
+/// The hash is the first 8 bytes of a SHA-256, so tags stay the same across
+/// builds and instances and clients keep revalidating after a deploy
+pub fn weak_etag<T: Serialize>(items: &[T]) -> String {
+    let digest = Sha256::digest(serde_json::to_vec(items).unwrap_or_default());
+    format!("W/\"{}-{}\"", items.len(), hex::encode(&digest[..8]))
+}
+
+/// Strip the weak indicator so tags can be compared with weak comparison
+fn opaque_tag(tag: &str) -> &str {
+    let tag = tag.trim();
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+/// The `If-None-Match` request header, if present
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IfNoneMatch(pub Option<String>);
+
+impl IfNoneMatch {
+    /// Whether the client's cached representation matches `etag`
+    pub fn matches(&self, etag: &str) -> bool {
+        match &self.0 {
+            Some(header) => header
+                .split(',')
+                .any(|tag| tag.trim() == "*" || opaque_tag(tag) == opaque_tag(etag)),
+            None => false,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let value = request
+            .headers()
+            .get_one("If-None-Match")
+            .map(str::to_string);
+        Outcome::Success(IfNoneMatch(value))
+    }
+}
+
+/// Responder that sets the `ETag` header, or answers 304 when the client is up to date
+pub enum Cached<R> {
+    NotModified(String),
+    Fresh(String, R),
+}
+
+impl<R> Cached<R> {
+    pub fn new(etag: String, if_none_match: &IfNoneMatch, body: R) -> Self {
+        if if_none_match.matches(&etag) {
+            Cached::NotModified(etag)
+        } else {
+            Cached::Fresh(etag, body)
+        }
+    }
+}
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for Cached<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let (etag, mut response) = match self {
+            Cached::NotModified(etag) => (
+                etag,
+                Response::build().status(Status::NotModified).finalize(),
+            ),
+            Cached::Fresh(etag, body) => (etag, body.respond_to(request)?),
+        };
+        response.set_header(Header::new("ETag", etag));
+        response.set_header(Header::new("Vary", "Accept"));
+        Ok(response)
+    }
+}
+
+/// The `If-Modified-Since` request header; a malformed date is ignored, as if absent
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IfModifiedSince(pub Option<OffsetDateTime>);
+
+impl IfModifiedSince {
+    /// Whether the client's copy is at least as recent as `last_modified`.
+    /// HTTP dates have whole seconds, so fractions are dropped before comparing
+    pub fn is_current(&self, last_modified: OffsetDateTime) -> bool {
+        self.0
+            .is_some_and(|since| last_modified.unix_timestamp() <= since.unix_timestamp())
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfModifiedSince {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let value = request
+            .headers()
+            .get_one("If-Modified-Since")
+            .and_then(parse_http_date);
+        Outcome::Success(IfModifiedSince(value))
+    }
+}
+
+/// Responder that sets the `Last-Modified` header, or answers 304 when the client is up to date
+pub enum LastModified<R> {
+    NotModified(String),
+    /// Sent without `Last-Modified` when the time of the last change is unknown
+    Fresh(Option<String>, R),
+}
+
+impl<R> LastModified<R> {
+    /// `updated_at` is the RFC 3339 time of the last change
+    pub fn new(updated_at: Option<&str>, if_modified_since: &IfModifiedSince, body: R) -> Self {
+        match updated_at.and_then(|updated_at| OffsetDateTime::parse(updated_at, &Rfc3339).ok()) {
+            Some(time) if if_modified_since.is_current(time) => LastModified::NotModified(http_date(time)),
+            Some(time) => LastModified::Fresh(Some(http_date(time)), body),
+            None => LastModified::Fresh(None, body),
+        }
+    }
+}
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for LastModified<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let (date, mut response) = match self {
+            LastModified::NotModified(date) => (
+                Some(date),
+                Response::build().status(Status::NotModified).finalize(),
+            ),
+            LastModified::Fresh(date, body) => (date, body.respond_to(request)?),
+        };
+        if let Some(date) = date {
+            response.set_header(Header::new("Last-Modified", date));
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weak_etag_is_stable_and_content_sensitive() {
+        let first = weak_etag(&["a", "b"]);
+        assert_eq!(first, weak_etag(&["a", "b"]));
+        assert_eq!(first, "W/\"2-0473ef2dc0d324ab\"");
+        assert_ne!(first, weak_etag(&["a", "c"]));
+        assert_ne!(first, weak_etag(&["a"]));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = "W/\"2-abc\"";
+        assert!(!IfNoneMatch(None).matches(etag));
+        assert!(IfNoneMatch(Some(etag.to_string())).matches(etag));
+        assert!(IfNoneMatch(Some("\"2-abc\"".to_string())).matches(etag));
+        assert!(IfNoneMatch(Some("W/\"1-def\", W/\"2-abc\"".to_string())).matches(etag));
+        assert!(IfNoneMatch(Some("*".to_string())).matches(etag));
+        assert!(!IfNoneMatch(Some("W/\"1-def\"".to_string())).matches(etag));
+    }
+
+    #[test]
+    fn test_http_date_round_trip() {
+        let time = OffsetDateTime::parse("1994-11-06T08:49:37Z", &Rfc3339).unwrap();
+        assert_eq!(http_date(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(time));
+        assert_eq!(parse_http_date("1994-11-06"), None);
+    }
+
+    #[test]
+    fn test_last_modified() {
+        let updated_at = Some("2026-10-17T12:00:00.250Z");
+        let since = |value: &str| IfModifiedSince(parse_http_date(value));
+
+        let response = LastModified::new(updated_at, &IfModifiedSince(None), ());
+        assert!(matches!(response, LastModified::Fresh(Some(date), ()) if date == "Sat, 17 Oct 2026 12:00:00 GMT"));
+        let response = LastModified::new(updated_at, &since("Sat, 17 Oct 2026 12:00:00 GMT"), ());
+        assert!(matches!(response, LastModified::NotModified(_)));
+        let response = LastModified::new(updated_at, &since("Sat, 17 Oct 2026 11:59:59 GMT"), ());
+        assert!(matches!(response, LastModified::Fresh(Some(_), ())));
+        let response = LastModified::new(None, &since("Sat, 17 Oct 2026 12:00:00 GMT"), ());
+        assert!(matches!(response, LastModified::Fresh(None, ())));
+    }
+}
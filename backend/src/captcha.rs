@@ -0,0 +1,226 @@
+use crate::config::{CaptchaConfig, CaptchaProvider};
+use async_trait::async_trait;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+// CAPTCHA - Dependency Inversion Principle
+// The signup handler asks a CaptchaVerifier whether the widget token sent by
+// the browser is valid; hCaptcha, reCAPTCHA and Turnstile share the same
+// siteverify protocol, and the no-op verifier accepts everything in development
+
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+const HCAPTCHA_VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+const RECAPTCHA_VERIFY_URL: &str = "https://www.google.com/recaptcha/api/siteverify";
+const TURNSTILE_VERIFY_URL: &str = "https://challenges.cloudflare.com/turnstile/v0/siteverify";
+
+/// What the frontend needs to render the widget
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct CaptchaSettings {
+    /// `none`, `hcaptcha`, `recaptcha` or `turnstile`
+    pub provider: String,
+    pub site_key: Option<String>,
+}
+
+impl CaptchaSettings {
+    pub fn from_config(config: &CaptchaConfig) -> Self {
+        let enabled = config.provider != CaptchaProvider::None;
+        CaptchaSettings {
+            provider: config.provider.as_str().to_string(),
+            site_key: config.site_key.clone().filter(|_| enabled),
+        }
+    }
+}
+
+/// Checks the token produced by a solved CAPTCHA widget
+#[async_trait]
+pub trait CaptchaVerifier: Send + Sync {
+    async fn verify(&self, token: Option<&str>, remote_ip: Option<IpAddr>) -> Result<(), Custom<String>>;
+}
+
+/// Accepts every request; for development and tests
+pub struct NoopCaptchaVerifier;
+
+#[async_trait]
+impl CaptchaVerifier for NoopCaptchaVerifier {
+    async fn verify(&self, _token: Option<&str>, _remote_ip: Option<IpAddr>) -> Result<(), Custom<String>> {
+        Ok(())
+    }
+}
+
+/// Answer of a siteverify endpoint
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// Verifies tokens against the siteverify endpoint of hCaptcha, reCAPTCHA or Turnstile
+pub struct SiteVerifyCaptchaVerifier {
+    client: reqwest::Client,
+    verify_url: String,
+    secret_key: String,
+}
+
+impl SiteVerifyCaptchaVerifier {
+    pub fn new(verify_url: &str, secret_key: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(VERIFY_TIMEOUT)
+            .build()
+            .expect("Failed to build CAPTCHA HTTP client");
+        SiteVerifyCaptchaVerifier {
+            client,
+            verify_url: verify_url.to_string(),
+            secret_key: secret_key.to_string(),
+        }
+    }
+
+    /// Form body of a verification request
+    fn form(&self, token: &str, remote_ip: Option<IpAddr>) -> String {
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+        form.append_pair("secret", &self.secret_key);
+        form.append_pair("response", token);
+        if let Some(ip) = remote_ip {
+            form.append_pair("remoteip", &ip.to_string());
+        }
+        form.finish()
+    }
+}
+
+#[async_trait]
+impl CaptchaVerifier for SiteVerifyCaptchaVerifier {
+    async fn verify(&self, token: Option<&str>, remote_ip: Option<IpAddr>) -> Result<(), Custom<String>> {
+        let token = token.map(str::trim).filter(|token| !token.is_empty()).ok_or_else(|| {
+            Custom(Status::BadRequest, "CAPTCHA token is missing".to_string())
+        })?;
+
+        let unavailable = |e: String| {
+            eprintln!("CAPTCHA verification request failed: {}", e);
+            Custom(
+                Status::InternalServerError,
+                "CAPTCHA provider is unavailable".to_string(),
+            )
+        };
+        let body = self
+            .client
+            .post(&self.verify_url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(self.form(token, remote_ip))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| unavailable(e.to_string()))?
+            .bytes()
+            .await
+            .map_err(|e| unavailable(e.to_string()))?;
+        let response: SiteVerifyResponse =
+            serde_json::from_slice(&body).map_err(|e| unavailable(e.to_string()))?;
+
+        if response.success {
+            Ok(())
+        } else {
+            if !response.error_codes.is_empty() {
+                eprintln!("CAPTCHA rejected: {}", response.error_codes.join(", "));
+            }
+            Err(Custom(
+                Status::BadRequest,
+                "CAPTCHA verification failed".to_string(),
+            ))
+        }
+    }
+}
+
+/// Build the CAPTCHA verifier selected in the configuration
+pub fn build_captcha_verifier(config: &CaptchaConfig) -> Result<Arc<dyn CaptchaVerifier>, String> {
+    let verify_url = match config.provider {
+        CaptchaProvider::None => return Ok(Arc::new(NoopCaptchaVerifier)),
+        CaptchaProvider::HCaptcha => HCAPTCHA_VERIFY_URL,
+        CaptchaProvider::ReCaptcha => RECAPTCHA_VERIFY_URL,
+        CaptchaProvider::Turnstile => TURNSTILE_VERIFY_URL,
+    };
+    let (Some(_), Some(secret_key)) = (&config.site_key, &config.secret_key) else {
+        return Err(format!(
+            "CAPTCHA_SITE_KEY and CAPTCHA_SECRET_KEY are required by the {} provider",
+            config.provider.as_str()
+        ));
+    };
+    Ok(Arc::new(SiteVerifyCaptchaVerifier::new(verify_url, secret_key.expose())))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::secrets::SecretString;
+
+    /// Token accepted by MockCaptchaVerifier
+    pub const SOLVED_CAPTCHA: &str = "solved";
+
+    // Accepts only SOLVED_CAPTCHA
+    pub struct MockCaptchaVerifier;
+
+    #[async_trait]
+    impl CaptchaVerifier for MockCaptchaVerifier {
+        async fn verify(&self, token: Option<&str>, _remote_ip: Option<IpAddr>) -> Result<(), Custom<String>> {
+            match token {
+                Some(SOLVED_CAPTCHA) => Ok(()),
+                _ => Err(Custom(Status::BadRequest, "CAPTCHA verification failed".to_string())),
+            }
+        }
+    }
+
+    fn config(provider: CaptchaProvider) -> CaptchaConfig {
+        CaptchaConfig {
+            provider,
+            site_key: Some("site".to_string()),
+            secret_key: Some(SecretString::from("secret")),
+        }
+    }
+
+    #[test]
+    fn test_settings_only_expose_site_key_when_enabled() {
+        let settings = CaptchaSettings::from_config(&config(CaptchaProvider::Turnstile));
+        assert_eq!(settings.provider, "turnstile");
+        assert_eq!(settings.site_key.as_deref(), Some("site"));
+
+        let settings = CaptchaSettings::from_config(&config(CaptchaProvider::None));
+        assert_eq!(settings.provider, "none");
+        assert_eq!(settings.site_key, None);
+    }
+
+    #[test]
+    fn test_build_requires_keys() {
+        assert!(build_captcha_verifier(&CaptchaConfig::default()).is_ok());
+        assert!(build_captcha_verifier(&config(CaptchaProvider::HCaptcha)).is_ok());
+
+        let missing_secret = CaptchaConfig {
+            secret_key: None,
+            ..config(CaptchaProvider::ReCaptcha)
+        };
+        let error = build_captcha_verifier(&missing_secret).err().unwrap();
+        assert!(error.contains("recaptcha"));
+    }
+
+    #[test]
+    fn test_form_body() {
+        let verifier = SiteVerifyCaptchaVerifier::new(HCAPTCHA_VERIFY_URL, "s&cret");
+        assert_eq!(verifier.form("tok", None), "secret=s%26cret&response=tok");
+        assert_eq!(
+            verifier.form("tok", Some(IpAddr::from([10, 0, 0, 1]))),
+            "secret=s%26cret&response=tok&remoteip=10.0.0.1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_is_rejected_without_a_request() {
+        let verifier = SiteVerifyCaptchaVerifier::new(TURNSTILE_VERIFY_URL, "secret");
+        let error = verifier.verify(Some(" "), None).await.unwrap_err();
+        assert_eq!(error, Custom(Status::BadRequest, "CAPTCHA token is missing".to_string()));
+        assert!(NoopCaptchaVerifier.verify(None, None).await.is_ok());
+    }
+}
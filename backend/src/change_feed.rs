@@ -0,0 +1,198 @@
+use crate::config::DatabaseConfig;
+use crate::crypto::FieldCipher;
+use crate::domain::RepositoryError;
+use crate::events::{EventBus, UserEvent};
+use crate::repository::{PostgresUserRepository, UserRepository};
+use futures_util::{StreamExt, stream};
+use rocket::serde::Deserialize;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio_postgres::{AsyncMessage, NoTls};
+
+// Change feed - Single Responsibility Principle
+// A trigger on the users table NOTIFYs `user_changes` on every write; this
+// module LISTENs on a dedicated connection and republishes the notifications
+// on the live-update bus, so SSE clients of every backend instance see
+// changes made through any of them (or directly in the database).
+// Notifications only name the operation and the user: the row is read again
+// on a second connection to the primary, which also decrypts its PII
+
+pub const CHANNEL: &str = "user_changes";
+
+/// Events for live updates (SSE); fed by the change feed rather than the
+/// service bus, so notifications never re-trigger local side effects
+#[derive(Clone, Default)]
+pub struct LiveEvents(pub EventBus);
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// JSON payload built by the `notify_user_change` trigger
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct Change {
+    pub op: ChangeOp,
+    pub id: i32,
+}
+
+/// Read a `user_changes` notification payload
+pub fn parse_notification(payload: &str) -> Result<Change, String> {
+    serde_json::from_str(payload).map_err(|e| e.to_string())
+}
+
+/// The live event of `change`, with the user as `users` has it now. None when
+/// the user is already gone: its delete notification follows
+pub async fn change_event(change: Change, users: &dyn UserRepository) -> Result<Option<UserEvent>, RepositoryError> {
+    if change.op == ChangeOp::Delete {
+        return Ok(Some(UserEvent::Deleted { id: change.id }));
+    }
+    let Some(user) = users.find_by_id(change.id).await? else {
+        return Ok(None);
+    };
+    let user = user.into();
+    Ok(Some(match change.op {
+        ChangeOp::Insert => UserEvent::Created { user },
+        _ => UserEvent::Updated { user },
+    }))
+}
+
+/// LISTEN on `user_changes` and publish every notification on `live`,
+/// reconnecting with backoff whenever the connection drops
+pub fn spawn_listener(
+    config: DatabaseConfig,
+    live: LiveEvents,
+    cipher: Option<Arc<FieldCipher>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut attempt = 0;
+        loop {
+            match listen(&config, &live, cipher.clone(), &mut attempt).await {
+                Ok(()) => eprintln!("Change feed connection closed"),
+                Err(e) => eprintln!("Change feed error: {}", e),
+            }
+            let delay = config.retry.delay_for_attempt(attempt);
+            attempt = attempt.saturating_add(1);
+            eprintln!("Reconnecting change feed in {:?}", delay);
+            tokio::time::sleep(delay).await;
+        }
+    })
+}
+
+async fn listen(
+    config: &DatabaseConfig,
+    live: &LiveEvents,
+    cipher: Option<Arc<FieldCipher>>,
+    attempt: &mut u32,
+) -> Result<(), tokio_postgres::Error> {
+    let (client, mut connection) = tokio_postgres::connect(config.connection_string.expose(), NoTls).await?;
+    // Reads run on their own connection, since this one is only polled between notifications
+    let (reader, reader_connection) = tokio_postgres::connect(config.connection_string.expose(), NoTls).await?;
+    let reads = tokio::spawn(reader_connection);
+    let mut users = PostgresUserRepository::new(Arc::new(reader));
+    if let Some(cipher) = cipher {
+        users = users.with_cipher(cipher);
+    }
+    // Notifications only arrive while the connection is polled
+    let mut messages = stream::poll_fn(move |cx| connection.poll_message(cx));
+
+    let statement = format!("LISTEN {}", CHANNEL);
+    let subscribe = client.batch_execute(&statement);
+    tokio::pin!(subscribe);
+    loop {
+        tokio::select! {
+            result = &mut subscribe => {
+                result?;
+                break;
+            }
+            message = messages.next() => match message {
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()),
+            },
+        }
+    }
+    println!("Listening for '{}' notifications", CHANNEL);
+    *attempt = 0;
+
+    while let Some(message) = messages.next().await {
+        let AsyncMessage::Notification(notification) = message? else {
+            continue;
+        };
+        let change = match parse_notification(notification.payload()) {
+            Ok(change) => change,
+            Err(e) => {
+                eprintln!("Ignoring malformed change notification: {}", e);
+                continue;
+            }
+        };
+        match change_event(change, &users).await {
+            Ok(Some(event)) => live.0.publish(event),
+            Ok(None) => {}
+            Err(e) => eprintln!("Change of user {} not published: {}", change.id, e),
+        }
+    }
+    reads.abort();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{User, UserStatus};
+    use crate::repository::tests::MockUserRepository;
+
+    #[test]
+    fn test_parse_notification() {
+        assert_eq!(
+            parse_notification(r#"{"op":"insert","id":1}"#),
+            Ok(Change { op: ChangeOp::Insert, id: 1 })
+        );
+        assert_eq!(
+            parse_notification(r#"{"op":"delete","id":7}"#),
+            Ok(Change { op: ChangeOp::Delete, id: 7 })
+        );
+        // Payloads of the earlier trigger still carry the row, which is ignored
+        assert_eq!(
+            parse_notification(r#"{"op":"update","id":1,"name":"John","email":"j@x.com"}"#),
+            Ok(Change { op: ChangeOp::Update, id: 1 })
+        );
+    }
+
+    #[test]
+    fn test_parse_notification_rejects_bad_payloads() {
+        assert!(parse_notification("not json").is_err());
+        assert!(parse_notification(r#"{"op":"truncate","id":1}"#).is_err());
+        assert!(parse_notification(r#"{"op":"insert"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_change_event_reads_the_current_user() {
+        let users = MockUserRepository::new();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        let id = users.create(&user).await.unwrap().id.unwrap();
+        users.set_status(id, UserStatus::Suspended).await.unwrap();
+
+        let event = change_event(Change { op: ChangeOp::Insert, id }, &users).await.unwrap();
+        let Some(UserEvent::Created { user }) = event else { panic!("expected a creation") };
+        assert_eq!((user.id, user.name.as_str()), (id, "John Doe"));
+        assert_eq!(user.status, UserStatus::Suspended);
+
+        let event = change_event(Change { op: ChangeOp::Update, id }, &users).await.unwrap();
+        assert_eq!(event.map(|event| event.name()), Some("user.updated"));
+    }
+
+    #[tokio::test]
+    async fn test_change_event_of_deleted_users() {
+        let users = MockUserRepository::new();
+        let event = change_event(Change { op: ChangeOp::Delete, id: 7 }, &users).await.unwrap();
+        assert_eq!(event, Some(UserEvent::Deleted { id: 7 }));
+        // Deleted before the update was read: the delete notification follows
+        let event = change_event(Change { op: ChangeOp::Update, id: 7 }, &users).await.unwrap();
+        assert_eq!(event, None);
+    }
+}
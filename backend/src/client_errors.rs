@@ -0,0 +1,108 @@
+use crate::body::read_json;
+use crate::errors::AppError;
+use rocket::data::{FromData, Outcome};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{Data, Request};
+use std::net::IpAddr;
+
+// Client errors - Single Responsibility Principle
+// Receives the crashes and failed requests the frontend reports on its own,
+// so problems users never mention still show up in the backend log
+
+/// Rocket limit capping the body of a report
+pub const CLIENT_ERROR_LIMIT: &str = "client-error";
+
+/// What went wrong in the browser
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum ClientErrorKind {
+    /// The WASM app panicked
+    Panic,
+    /// An API request failed
+    Request,
+}
+
+/// Error report sent by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct ClientErrorReport {
+    pub kind: ClientErrorKind,
+    pub message: String,
+    /// Frontend route the user was on, e.g. `/users/7`
+    #[serde(default)]
+    pub route: Option<String>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub app_version: Option<String>,
+    /// Failed requests only: the URL called and the status it answered, if any
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub status: Option<u16>,
+}
+
+impl ClientErrorReport {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.message.trim().is_empty() {
+            return Err("Error report message cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// One log line with the report as JSON
+    pub fn log_line(&self, client_ip: Option<IpAddr>) -> String {
+        let client = client_ip.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+        format!(
+            "[client-error] client={} {}",
+            client,
+            serde_json::to_string(self).unwrap_or_default()
+        )
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromData<'r> for ClientErrorReport {
+    type Error = AppError;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        read_json(request, data, CLIENT_ERROR_LIMIT).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(message: &str) -> ClientErrorReport {
+        ClientErrorReport {
+            kind: ClientErrorKind::Panic,
+            message: message.to_string(),
+            route: Some("/users/7".to_string()),
+            user_agent: None,
+            app_version: Some("0.1.0".to_string()),
+            url: None,
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_report_format() {
+        let parsed: ClientErrorReport =
+            serde_json::from_str(r#"{"kind":"request","message":"Server returned an error","url":"/api/v1/users","status":500}"#)
+                .unwrap();
+        assert_eq!(parsed.kind, ClientErrorKind::Request);
+        assert_eq!(parsed.status, Some(500));
+        assert_eq!(parsed.route, None);
+    }
+
+    #[test]
+    fn test_validate_and_log_line() {
+        assert!(report("panicked at src/pages.rs:10:5").validate().is_ok());
+        assert!(report(" ").validate().is_err());
+
+        let line = report("boom").log_line(Some(IpAddr::from([10, 0, 0, 1])));
+        assert!(line.starts_with("[client-error] client=10.0.0.1 {\"kind\":\"panic\""));
+        assert!(line.contains("\"route\":\"/users/7\""));
+    }
+}
@@ -0,0 +1,1419 @@
+use crate::db::DB_CONNECTION_STRING;
+use crate::secrets::{env_secret, is_placeholder, SecretString};
+use rocket::data::ByteUnit;
+use std::collections::BTreeMap;
+use std::env;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+// Application configuration - Single Responsibility Principle
+// This module only knows how to read settings from the environment,
+// falling back to defaults that match the local docker-compose setup
+
+const DEFAULT_DB_CONNECT_RETRIES: u32 = 5;
+const DEFAULT_DB_RETRY_INITIAL_DELAY_MS: u64 = 500;
+const DEFAULT_DB_RETRY_MAX_DELAY_MS: u64 = 10_000;
+const DEFAULT_DB_STATEMENT_TIMEOUT_MS: u64 = 10_000;
+const DEFAULT_DB_QUERY_RETRIES: u32 = 3;
+const DEFAULT_DB_QUERY_RETRY_INITIAL_DELAY_MS: u64 = 50;
+const DEFAULT_DB_QUERY_RETRY_MAX_DELAY_MS: u64 = 1_000;
+const DEFAULT_GRPC_ADDRESS: &str = "127.0.0.1:50051";
+const DEFAULT_WEBHOOK_RETRIES: u32 = 5;
+const DEFAULT_WEBHOOK_RETRY_INITIAL_DELAY_MS: u64 = 1_000;
+const DEFAULT_WEBHOOK_RETRY_MAX_DELAY_MS: u64 = 60_000;
+const DEFAULT_WEBHOOK_TIMEOUT_MS: u64 = 10_000;
+const DEFAULT_JOB_POLL_INTERVAL_MS: u64 = 1_000;
+const DEFAULT_PURGE_FINISHED_JOBS_EVERY: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_JOB_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const DEFAULT_REQUEUE_STALE_JOBS_EVERY: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_JOB_STALE_AFTER: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_MAIL_FROM: &str = "Rust App <no-reply@localhost>";
+const DEFAULT_SMTP_HOST: &str = "localhost";
+const DEFAULT_SMTP_PORT: u16 = 587;
+const DEFAULT_APP_URL: &str = "http://localhost:8080";
+const DEFAULT_REDIS_URL: &str = "redis://127.0.0.1:6379";
+const DEFAULT_CACHE_CAPACITY: usize = 1_000;
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_JSON_BODY_LIMIT: ByteUnit = ByteUnit::Mebibyte(1);
+const DEFAULT_STORAGE_DIR: &str = "storage";
+const DEFAULT_S3_REGION: &str = "us-east-1";
+const DEFAULT_KAFKA_REST_URL: &str = "http://127.0.0.1:8082";
+const DEFAULT_KAFKA_TOPIC: &str = "user-events";
+const DEFAULT_NATS_URL: &str = "nats://127.0.0.1:4222";
+const DEFAULT_EVENT_PUBLISH_RETRIES: u32 = 5;
+const DEFAULT_EVENT_PUBLISH_RETRY_INITIAL_DELAY_MS: u64 = 500;
+const DEFAULT_EVENT_PUBLISH_RETRY_MAX_DELAY_MS: u64 = 10_000;
+const DEFAULT_SIGNUP_RATE_LIMIT: u32 = 5;
+const DEFAULT_SIGNUP_RATE_WINDOW: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_EMAIL_CHECK_RATE_LIMIT: u32 = 30;
+const DEFAULT_EMAIL_CHECK_RATE_WINDOW: Duration = Duration::from_secs(60);
+const DEFAULT_PASSWORD_HISTORY: usize = 5;
+const DEFAULT_BREACH_API_URL: &str = "https://api.pwnedpasswords.com/range";
+const DEFAULT_CLIENT_ERROR_BODY_LIMIT: ByteUnit = ByteUnit::Kibibyte(16);
+const DEFAULT_CLIENT_ERROR_RATE_LIMIT: u32 = 10;
+const DEFAULT_CLIENT_ERROR_RATE_WINDOW: Duration = Duration::from_secs(60);
+const DEFAULT_SLOW_REQUEST_MS: u64 = 500;
+const DEFAULT_SLOW_QUERY_MS: u64 = 100;
+const DEV_CORS_ORIGINS: [&str; 2] = ["http://localhost:8080", "http://127.0.0.1:8080"];
+const DEFAULT_CORS_METHODS: [&str; 6] = ["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+const DEFAULT_CORS_HEADERS: [&str; 6] = [
+    "Accept",
+    "Content-Type",
+    "If-None-Match",
+    "If-Modified-Since",
+    "X-Request-Id",
+    "X-CSRF-Token",
+];
+
+/// Exponential backoff policy used while waiting for a dependency to come up
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryConfig {
+    /// Delay to wait before retry number `attempt` (starting at 0),
+    /// doubling every time and capped at `max_delay`
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: DEFAULT_DB_CONNECT_RETRIES,
+            initial_delay: Duration::from_millis(DEFAULT_DB_RETRY_INITIAL_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_DB_RETRY_MAX_DELAY_MS),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseConfig {
+    pub connection_string: SecretString,
+    pub retry: RetryConfig,
+    /// LISTEN for `user_changes` notifications to share live updates across instances
+    pub change_feed: bool,
+    /// Read replicas serving the user list, lookups by id and search
+    pub replica_urls: Vec<SecretString>,
+    /// Longest a statement of the API server may run before Postgres cancels it
+    pub statement_timeout: Duration,
+    /// Backoff of user queries rolled back by a serialization failure or deadlock
+    pub query_retry: RetryConfig,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    pub address: SocketAddr,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        GrpcConfig {
+            enabled: true,
+            address: DEFAULT_GRPC_ADDRESS.parse().expect("valid default gRPC address"),
+        }
+    }
+}
+
+/// Outbound webhook delivery settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookConfig {
+    pub retry: RetryConfig,
+    pub timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig {
+            retry: RetryConfig {
+                max_retries: DEFAULT_WEBHOOK_RETRIES,
+                initial_delay: Duration::from_millis(DEFAULT_WEBHOOK_RETRY_INITIAL_DELAY_MS),
+                max_delay: Duration::from_millis(DEFAULT_WEBHOOK_RETRY_MAX_DELAY_MS),
+            },
+            timeout: Duration::from_millis(DEFAULT_WEBHOOK_TIMEOUT_MS),
+        }
+    }
+}
+
+/// Background job worker settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobConfig {
+    pub poll_interval: Duration,
+}
+
+impl Default for JobConfig {
+    fn default() -> Self {
+        JobConfig {
+            poll_interval: Duration::from_millis(DEFAULT_JOB_POLL_INTERVAL_MS),
+        }
+    }
+}
+
+/// Schedules of the recurring maintenance tasks
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    pub purge_finished_jobs_every: Duration,
+    pub job_retention: Duration,
+    pub requeue_stale_jobs_every: Duration,
+    pub job_stale_after: Duration,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            enabled: true,
+            purge_finished_jobs_every: DEFAULT_PURGE_FINISHED_JOBS_EVERY,
+            job_retention: DEFAULT_JOB_RETENTION,
+            requeue_stale_jobs_every: DEFAULT_REQUEUE_STALE_JOBS_EVERY,
+            job_stale_after: DEFAULT_JOB_STALE_AFTER,
+        }
+    }
+}
+
+/// Which Mailer implementation sends emails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailTransport {
+    /// Print emails to the application log (development default)
+    Log,
+    Smtp,
+}
+
+impl FromStr for MailTransport {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "log" => Ok(MailTransport::Log),
+            "smtp" => Ok(MailTransport::Smtp),
+            other => Err(format!("Unknown mail transport '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<SecretString>,
+}
+
+/// Outgoing email settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailerConfig {
+    pub transport: MailTransport,
+    pub from: String,
+    pub smtp: SmtpConfig,
+    /// Public URL of the frontend, used for links in emails
+    pub app_url: String,
+}
+
+impl Default for MailerConfig {
+    fn default() -> Self {
+        MailerConfig {
+            transport: MailTransport::Log,
+            from: DEFAULT_MAIL_FROM.to_string(),
+            smtp: SmtpConfig {
+                host: DEFAULT_SMTP_HOST.to_string(),
+                port: DEFAULT_SMTP_PORT,
+                username: None,
+                password: None,
+            },
+            app_url: DEFAULT_APP_URL.to_string(),
+        }
+    }
+}
+
+/// Where user reads are cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheBackend {
+    None,
+    Memory,
+    Redis,
+}
+
+impl CacheBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheBackend::None => "none",
+            CacheBackend::Memory => "memory",
+            CacheBackend::Redis => "redis",
+        }
+    }
+}
+
+impl FromStr for CacheBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Ok(CacheBackend::None),
+            "memory" => Ok(CacheBackend::Memory),
+            "redis" => Ok(CacheBackend::Redis),
+            other => Err(format!("Unknown cache backend '{}'", other)),
+        }
+    }
+}
+
+/// Read cache in front of the user repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheConfig {
+    pub backend: CacheBackend,
+    /// May carry the Redis password, hence a secret
+    pub redis_url: SecretString,
+    /// Maximum number of entries of the in-memory cache
+    pub capacity: usize,
+    pub ttl: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            backend: CacheBackend::None,
+            redis_url: SecretString::from(DEFAULT_REDIS_URL),
+            capacity: DEFAULT_CACHE_CAPACITY,
+            ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+}
+
+/// Where files (avatars, export archives) are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// A directory on the local filesystem (development default)
+    Local,
+    /// An S3-compatible bucket
+    S3,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "local" => Ok(StorageBackend::Local),
+            "s3" => Ok(StorageBackend::S3),
+            other => Err(format!("Unknown storage backend '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct S3Config {
+    pub bucket: String,
+    pub region: String,
+    /// Base URL of an S3-compatible service; AWS when unset
+    pub endpoint: Option<String>,
+    /// Address the bucket in the path instead of the host name (MinIO and most S3-compatible services)
+    pub path_style: bool,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<SecretString>,
+}
+
+/// File storage settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// Root directory of the `local` backend
+    pub local_dir: PathBuf,
+    pub s3: S3Config,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            backend: StorageBackend::Local,
+            local_dir: PathBuf::from(DEFAULT_STORAGE_DIR),
+            s3: S3Config {
+                bucket: String::new(),
+                region: DEFAULT_S3_REGION.to_string(),
+                endpoint: None,
+                path_style: false,
+                access_key_id: None,
+                secret_access_key: None,
+            },
+        }
+    }
+}
+
+/// Which service checks the CAPTCHA of signups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaProvider {
+    /// Accept every signup (development default)
+    None,
+    HCaptcha,
+    ReCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptchaProvider::None => "none",
+            CaptchaProvider::HCaptcha => "hcaptcha",
+            CaptchaProvider::ReCaptcha => "recaptcha",
+            CaptchaProvider::Turnstile => "turnstile",
+        }
+    }
+}
+
+impl FromStr for CaptchaProvider {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Ok(CaptchaProvider::None),
+            "hcaptcha" => Ok(CaptchaProvider::HCaptcha),
+            "recaptcha" => Ok(CaptchaProvider::ReCaptcha),
+            "turnstile" => Ok(CaptchaProvider::Turnstile),
+            other => Err(format!("Unknown CAPTCHA provider '{}'", other)),
+        }
+    }
+}
+
+/// CAPTCHA settings; the site key is public, the secret key stays on the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptchaConfig {
+    pub provider: CaptchaProvider,
+    pub site_key: Option<String>,
+    pub secret_key: Option<SecretString>,
+}
+
+impl Default for CaptchaConfig {
+    fn default() -> Self {
+        CaptchaConfig {
+            provider: CaptchaProvider::None,
+            site_key: None,
+            secret_key: None,
+        }
+    }
+}
+
+/// Message broker user events are published to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventBroker {
+    /// Publish nothing (development default)
+    None,
+    /// A Kafka topic, written through a Kafka REST Proxy
+    Kafka,
+    Nats,
+}
+
+impl EventBroker {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventBroker::None => "none",
+            EventBroker::Kafka => "kafka",
+            EventBroker::Nats => "nats",
+        }
+    }
+}
+
+impl FromStr for EventBroker {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Ok(EventBroker::None),
+            "kafka" => Ok(EventBroker::Kafka),
+            "nats" => Ok(EventBroker::Nats),
+            other => Err(format!("Unknown event broker '{}'", other)),
+        }
+    }
+}
+
+/// Encoding of published user events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFormat {
+    Json,
+    /// `userevents.UserEvent` of `proto/user_events.proto`
+    Protobuf,
+}
+
+impl FromStr for EventFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(EventFormat::Json),
+            "protobuf" | "proto" => Ok(EventFormat::Protobuf),
+            other => Err(format!("Unknown event format '{}'", other)),
+        }
+    }
+}
+
+/// Publishing of user events to a message broker for downstream services
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStreamConfig {
+    pub broker: EventBroker,
+    pub format: EventFormat,
+    /// Base URL of the Kafka REST Proxy
+    pub kafka_rest_url: String,
+    pub kafka_topic: String,
+    /// May carry NATS credentials, hence a secret
+    pub nats_url: SecretString,
+    /// Prepended to the subjects, e.g. `app` publishes on `app.user.created`
+    pub nats_subject_prefix: Option<String>,
+    /// Backoff of events the broker did not accept
+    pub retry: RetryConfig,
+}
+
+impl Default for EventStreamConfig {
+    fn default() -> Self {
+        EventStreamConfig {
+            broker: EventBroker::None,
+            format: EventFormat::Json,
+            kafka_rest_url: DEFAULT_KAFKA_REST_URL.to_string(),
+            kafka_topic: DEFAULT_KAFKA_TOPIC.to_string(),
+            nats_url: SecretString::from(DEFAULT_NATS_URL),
+            nats_subject_prefix: None,
+            retry: RetryConfig {
+                max_retries: DEFAULT_EVENT_PUBLISH_RETRIES,
+                initial_delay: Duration::from_millis(DEFAULT_EVENT_PUBLISH_RETRY_INITIAL_DELAY_MS),
+                max_delay: Duration::from_millis(DEFAULT_EVENT_PUBLISH_RETRY_MAX_DELAY_MS),
+            },
+        }
+    }
+}
+
+/// Self-service signup settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignupConfig {
+    /// Signups allowed per client address within `rate_window`
+    pub rate_limit: u32,
+    pub rate_window: Duration,
+    /// Email availability checks allowed per client address within `email_check_rate_window`
+    pub email_check_rate_limit: u32,
+    pub email_check_rate_window: Duration,
+}
+
+impl Default for SignupConfig {
+    fn default() -> Self {
+        SignupConfig {
+            rate_limit: DEFAULT_SIGNUP_RATE_LIMIT,
+            rate_window: DEFAULT_SIGNUP_RATE_WINDOW,
+            email_check_rate_limit: DEFAULT_EMAIL_CHECK_RATE_LIMIT,
+            email_check_rate_window: DEFAULT_EMAIL_CHECK_RATE_WINDOW,
+        }
+    }
+}
+
+/// What signups and password changes do with a password known from data breaches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BreachCheckMode {
+    /// Never look passwords up (default, works offline)
+    #[default]
+    Off,
+    /// Accept the password but answer with a `Warning` header
+    Warn,
+    Reject,
+}
+
+impl BreachCheckMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreachCheckMode::Off => "off",
+            BreachCheckMode::Warn => "warn",
+            BreachCheckMode::Reject => "reject",
+        }
+    }
+}
+
+impl FromStr for BreachCheckMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" => Ok(BreachCheckMode::Off),
+            "warn" => Ok(BreachCheckMode::Warn),
+            "reject" => Ok(BreachCheckMode::Reject),
+            other => Err(format!("Unknown password breach check mode '{}'", other)),
+        }
+    }
+}
+
+/// Password rules on top of the validation shared with the frontend
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordPolicyConfig {
+    /// Last passwords of a user, the current one included, that a change or
+    /// reset may not reuse; 0 turns the history off
+    pub history: usize,
+    pub breach_check: BreachCheckMode,
+    /// Range endpoint of the Pwned Passwords API; the first five hex digits
+    /// of the password's SHA-1 are appended
+    pub breach_api_url: String,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        PasswordPolicyConfig {
+            history: DEFAULT_PASSWORD_HISTORY,
+            breach_check: BreachCheckMode::Off,
+            breach_api_url: DEFAULT_BREACH_API_URL.to_string(),
+        }
+    }
+}
+
+/// Frontend error reporting settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientErrorsConfig {
+    /// Reports accepted per client address within `rate_window`
+    pub rate_limit: u32,
+    pub rate_window: Duration,
+}
+
+impl Default for ClientErrorsConfig {
+    fn default() -> Self {
+        ClientErrorsConfig {
+            rate_limit: DEFAULT_CLIENT_ERROR_RATE_LIMIT,
+            rate_window: DEFAULT_CLIENT_ERROR_RATE_WINDOW,
+        }
+    }
+}
+
+/// Maximum sizes of incoming request bodies
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitsConfig {
+    pub json: ByteUnit,
+    /// Body of a frontend error report
+    pub client_error: ByteUnit,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        LimitsConfig {
+            json: DEFAULT_JSON_BODY_LIMIT,
+            client_error: DEFAULT_CLIENT_ERROR_BODY_LIMIT,
+        }
+    }
+}
+
+/// Request and repository query logging
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggingConfig {
+    /// Log a line per request
+    pub requests: bool,
+    /// Requests taking longer are logged as slow
+    pub slow_request: Duration,
+    /// Log every user repository call, not only the slow ones
+    pub queries: bool,
+    pub slow_query: Duration,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            requests: true,
+            slow_request: Duration::from_millis(DEFAULT_SLOW_REQUEST_MS),
+            queries: false,
+            slow_query: Duration::from_millis(DEFAULT_SLOW_QUERY_MS),
+        }
+    }
+}
+
+/// Shape of API response bodies
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvelopeConfig {
+    /// Wrap every JSON body in `{ data, meta, errors }`, not only for clients asking for it
+    pub always: bool,
+}
+
+/// Read-only maintenance mode at startup; switchable at runtime afterwards
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MaintenanceConfig {
+    pub enabled: bool,
+    /// Shown instead of the default maintenance message
+    pub message: Option<String>,
+}
+
+/// Access to the `/admin` endpoints; disabled while no token is set
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdminConfig {
+    /// Bearer token operators send in the `Authorization` header
+    pub token: Option<SecretString>,
+}
+
+/// Encryption at rest of PII columns; disabled while no key is set
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EncryptionConfig {
+    /// Hex-encoded 256-bit key used for new values
+    pub key: Option<SecretString>,
+    /// Retired keys still needed to read values that were not re-keyed yet
+    pub previous_keys: Vec<SecretString>,
+    /// Hex-encoded 256-bit key of the admin CLI's backup archives
+    pub backup_key: Option<SecretString>,
+}
+
+/// Flags the frontend knows about; reported as disabled unless enabled
+pub const KNOWN_FEATURE_FLAGS: [&str; 3] = ["bulk_delete", "debug_panel", "org_management"];
+
+/// Dark-launched features, switched on per deployment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeatureFlagsConfig {
+    pub enabled: Vec<String>,
+}
+
+impl FeatureFlagsConfig {
+    /// Every known or enabled flag with its state
+    pub fn flags(&self) -> BTreeMap<String, bool> {
+        let mut flags: BTreeMap<String, bool> = KNOWN_FEATURE_FLAGS
+            .iter()
+            .map(|flag| (flag.to_string(), false))
+            .collect();
+        for flag in &self.enabled {
+            flags.insert(flag.clone(), true);
+        }
+        flags
+    }
+}
+
+/// Deployment profile selecting defaults that differ between environments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    Dev,
+    Prod,
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "dev" | "development" => Ok(Profile::Dev),
+            "prod" | "production" => Ok(Profile::Prod),
+            other => Err(format!("Unknown profile '{}'", other)),
+        }
+    }
+}
+
+/// Cross-origin access to the REST API
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorsConfig {
+    /// Exact origins such as `https://app.example.com`
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub allow_credentials: bool,
+}
+
+impl CorsConfig {
+    /// Defaults of a profile: the local frontend in dev, nothing in prod
+    pub fn for_profile(profile: Profile) -> Self {
+        let allowed_origins = match profile {
+            Profile::Dev => DEV_CORS_ORIGINS.iter().map(|o| o.to_string()).collect(),
+            Profile::Prod => Vec::new(),
+        };
+        CorsConfig {
+            allowed_origins,
+            allowed_methods: DEFAULT_CORS_METHODS.iter().map(|m| m.to_string()).collect(),
+            allowed_headers: DEFAULT_CORS_HEADERS.iter().map(|h| h.to_string()).collect(),
+            allow_credentials: profile == Profile::Dev,
+        }
+    }
+
+    /// Reject settings that would silently open or break cross-origin access
+    pub fn validate(&self, profile: Profile) -> Result<(), String> {
+        if profile == Profile::Prod && self.allowed_origins.is_empty() {
+            return Err("CORS_ALLOWED_ORIGINS must be set in the prod profile".to_string());
+        }
+        for origin in &self.allowed_origins {
+            validate_origin(origin)?;
+        }
+        if self.allowed_methods.is_empty() {
+            return Err("CORS_ALLOWED_METHODS cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// An origin is `scheme://host[:port]` with an http(s) scheme and nothing after the authority
+pub fn validate_origin(origin: &str) -> Result<(), String> {
+    let invalid = |reason: &str| Err(format!("Invalid CORS origin '{}': {}", origin, reason));
+
+    let Some((scheme, authority)) = origin.split_once("://") else {
+        return invalid("expected scheme://host[:port]");
+    };
+    if scheme != "http" && scheme != "https" {
+        return invalid("scheme must be http or https");
+    }
+    if authority.is_empty()
+        || authority.contains(['/', '?', '#', '*'])
+        || authority.contains(char::is_whitespace)
+    {
+        return invalid("expected only a host and optional port after the scheme");
+    }
+    // IPv6 hosts are bracketed, so the port separator is the last ':' after ']'
+    let port_separator = authority.rfind(':').filter(|&i| i > authority.rfind(']').unwrap_or(0));
+    let (host, port) = match port_separator {
+        Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+        None => (authority, None),
+    };
+    if port.is_some_and(|port| port.parse::<u16>().is_err()) {
+        return invalid("port must be a number");
+    }
+    if host.is_empty() {
+        return invalid("host cannot be empty");
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfig {
+    pub profile: Profile,
+    pub database: DatabaseConfig,
+    pub grpc: GrpcConfig,
+    pub webhooks: WebhookConfig,
+    pub jobs: JobConfig,
+    pub scheduler: SchedulerConfig,
+    pub mailer: MailerConfig,
+    pub cache: CacheConfig,
+    pub storage: StorageConfig,
+    pub signup: SignupConfig,
+    pub password_policy: PasswordPolicyConfig,
+    pub captcha: CaptchaConfig,
+    pub client_errors: ClientErrorsConfig,
+    pub cors: CorsConfig,
+    pub limits: LimitsConfig,
+    pub encryption: EncryptionConfig,
+    pub features: FeatureFlagsConfig,
+    pub logging: LoggingConfig,
+    pub maintenance: MaintenanceConfig,
+    pub envelope: EnvelopeConfig,
+    pub event_stream: EventStreamConfig,
+    pub admin: AdminConfig,
+}
+
+impl AppConfig {
+    /// Build the configuration from environment variables
+    ///
+    /// - `APP_PROFILE`: `dev` or `prod`, selects the defaults below that differ per environment
+    /// - `DATABASE_URL`: Postgres connection string
+    /// - `DB_CONNECT_RETRIES`: how many times to retry the initial connection
+    /// - `DB_RETRY_INITIAL_DELAY_MS`: delay before the first retry
+    /// - `DB_RETRY_MAX_DELAY_MS`: upper bound for the backoff delay
+    /// - `CHANGE_FEED_ENABLED`: whether to LISTEN for user changes made by other instances
+    /// - `DATABASE_REPLICA_URLS`: comma-separated connection strings of read replicas
+    /// - `DB_STATEMENT_TIMEOUT_MS`: how long a query may run before it is cancelled
+    /// - `DB_QUERY_RETRIES`: how many times a query rolled back by a serialization failure or deadlock is retried
+    /// - `DB_QUERY_RETRY_INITIAL_DELAY_MS`: delay before the first query retry, before jitter
+    /// - `DB_QUERY_RETRY_MAX_DELAY_MS`: upper bound for the query retry delay
+    /// - `GRPC_ENABLED`: whether to start the internal gRPC server
+    /// - `GRPC_ADDRESS`: socket address the gRPC server listens on
+    /// - `WEBHOOK_RETRIES`: how many times a failed webhook delivery is retried
+    /// - `WEBHOOK_RETRY_INITIAL_DELAY_MS`: delay before the first delivery retry
+    /// - `WEBHOOK_RETRY_MAX_DELAY_MS`: upper bound for the delivery backoff delay
+    /// - `WEBHOOK_TIMEOUT_MS`: timeout of a single delivery request
+    /// - `JOB_POLL_INTERVAL_MS`: how often the idle job worker checks for due jobs
+    /// - `SCHEDULER_ENABLED`: whether to run the recurring maintenance tasks
+    /// - `SCHEDULE_PURGE_FINISHED_JOBS`: interval of the finished-jobs purge, e.g. `1d`
+    /// - `JOB_RETENTION`: how long completed and failed jobs are kept
+    /// - `SCHEDULE_REQUEUE_STALE_JOBS`: interval of the stale-jobs check, e.g. `5m`
+    /// - `JOB_STALE_AFTER`: how long a job may stay `running` before it is requeued
+    /// - `MAIL_TRANSPORT`: `log` or `smtp`
+    /// - `MAIL_FROM`: sender address of outgoing emails
+    /// - `SMTP_HOST` / `SMTP_PORT`: SMTP relay (STARTTLS)
+    /// - `SMTP_USERNAME` / `SMTP_PASSWORD`: optional SMTP credentials
+    /// - `APP_URL`: public frontend URL used for links in emails
+    /// - `CACHE_BACKEND`: `none`, `memory` or `redis`
+    /// - `REDIS_URL`: Redis server used by the `redis` cache backend
+    /// - `CACHE_CAPACITY`: maximum entries of the `memory` cache backend
+    /// - `CACHE_TTL`: how long cached reads are served, e.g. `60s`
+    /// - `STORAGE_BACKEND`: `local` or `s3`
+    /// - `STORAGE_LOCAL_DIR`: root directory of the `local` storage backend
+    /// - `S3_BUCKET` / `S3_REGION`: bucket of the `s3` storage backend
+    /// - `S3_ENDPOINT`: base URL of an S3-compatible service, AWS when unset
+    /// - `S3_PATH_STYLE`: whether the bucket goes in the URL path instead of the host name
+    /// - `S3_ACCESS_KEY_ID` / `S3_SECRET_ACCESS_KEY`: S3 credentials
+    /// - `SIGNUP_RATE_LIMIT`: signups allowed per client address within the window
+    /// - `SIGNUP_RATE_WINDOW`: window of the signup rate limit, e.g. `1h`
+    /// - `EMAIL_CHECK_RATE_LIMIT`: email availability checks allowed per client address within the window
+    /// - `EMAIL_CHECK_RATE_WINDOW`: window of the email check rate limit, e.g. `1m`
+    /// - `PASSWORD_HISTORY`: last passwords a user may not reuse, 0 to allow any
+    /// - `PASSWORD_BREACH_CHECK`: `off`, `warn` or `reject` passwords found in data breaches
+    /// - `PASSWORD_BREACH_API_URL`: range endpoint of the Pwned Passwords API
+    /// - `CAPTCHA_PROVIDER`: `none`, `hcaptcha`, `recaptcha` or `turnstile`
+    /// - `CAPTCHA_SITE_KEY` / `CAPTCHA_SECRET_KEY`: keys issued by the CAPTCHA provider
+    /// - `CLIENT_ERROR_RATE_LIMIT`: frontend error reports accepted per client address within the window
+    /// - `CLIENT_ERROR_RATE_WINDOW`: window of the error report rate limit, e.g. `1m`
+    /// - `CORS_ALLOWED_ORIGINS`: comma-separated origins allowed to call the API
+    /// - `CORS_ALLOWED_METHODS` / `CORS_ALLOWED_HEADERS`: comma-separated lists
+    /// - `CORS_ALLOW_CREDENTIALS`: whether browsers may send cookies cross-origin
+    /// - `JSON_BODY_LIMIT`: maximum size of a JSON request body, e.g. `1MiB` or `256KiB`
+    /// - `CLIENT_ERROR_BODY_LIMIT`: maximum size of a frontend error report
+    /// - `PII_ENCRYPTION_KEY`: hex-encoded 32-byte key encrypting user names and emails
+    /// - `PII_ENCRYPTION_PREVIOUS_KEYS`: comma-separated retired keys, kept until re-keyed
+    /// - `BACKUP_ENCRYPTION_KEY`: hex-encoded 32-byte key of `admin backup`/`restore` archives
+    /// - `FEATURE_FLAGS`: comma-separated feature flags to enable, e.g. `bulk_delete`
+    /// - `LOG_REQUESTS`: whether to log a line per request
+    /// - `SLOW_REQUEST_MS`: requests taking longer are flagged `SLOW`
+    /// - `LOG_QUERIES`: whether to log every user repository call (on in the dev profile)
+    /// - `SLOW_QUERY_MS`: repository calls taking longer are always logged, flagged `SLOW`
+    /// - `MAINTENANCE_MODE`: whether to start read-only, answering 503 to every write
+    /// - `MAINTENANCE_MESSAGE`: message shown during maintenance instead of the default one
+    /// - `API_ENVELOPE`: whether to wrap every JSON response in `{ data, meta, errors }`
+    /// - `EVENT_BROKER`: `none`, `kafka` or `nats`, where user changes are published
+    /// - `EVENT_FORMAT`: `json` or `protobuf`, encoding of the published events
+    /// - `KAFKA_REST_URL` / `KAFKA_TOPIC`: Kafka REST Proxy and topic of the `kafka` broker
+    /// - `NATS_URL`: server of the `nats` broker
+    /// - `NATS_SUBJECT_PREFIX`: prepended to the NATS subjects, e.g. `app` for `app.user.created`
+    /// - `EVENT_PUBLISH_RETRIES`: how many times an event the broker did not accept is published again
+    /// - `EVENT_PUBLISH_RETRY_INITIAL_DELAY_MS`: delay before the first publish retry, before jitter
+    /// - `EVENT_PUBLISH_RETRY_MAX_DELAY_MS`: upper bound for the publish retry delay
+    /// - `ADMIN_TOKEN`: bearer token of the `/admin` endpoints, which answer 403 while it is unset
+    ///
+    /// The credentials among them (`DATABASE_URL`, `DATABASE_REPLICA_URLS`,
+    /// `REDIS_URL`, `NATS_URL`, `SMTP_PASSWORD`, `S3_SECRET_ACCESS_KEY`, `CAPTCHA_SECRET_KEY`,
+    /// `ADMIN_TOKEN` and the encryption keys) can instead be read from the file named by the
+    /// same variable with a `_FILE` suffix. Fails if such a file cannot be read, or if the prod profile still
+    /// uses the development or a placeholder credential
+    pub fn from_env() -> Result<Self, String> {
+        let profile = env_or("APP_PROFILE", Profile::Dev);
+        let cors = CorsConfig::for_profile(profile);
+        let grpc = GrpcConfig::default();
+        let webhooks = WebhookConfig::default();
+        let scheduler = SchedulerConfig::default();
+        let mailer = MailerConfig::default();
+        let cache = CacheConfig::default();
+        let storage = StorageConfig::default();
+        let signup = SignupConfig::default();
+        let client_errors = ClientErrorsConfig::default();
+        let event_stream = EventStreamConfig::default();
+
+        let config = AppConfig {
+            profile,
+            database: DatabaseConfig {
+                connection_string: env_secret("DATABASE_URL")?
+                    .unwrap_or_else(|| SecretString::from(DB_CONNECTION_STRING)),
+                retry: RetryConfig {
+                    max_retries: env_or("DB_CONNECT_RETRIES", DEFAULT_DB_CONNECT_RETRIES),
+                    initial_delay: Duration::from_millis(env_or(
+                        "DB_RETRY_INITIAL_DELAY_MS",
+                        DEFAULT_DB_RETRY_INITIAL_DELAY_MS,
+                    )),
+                    max_delay: Duration::from_millis(env_or(
+                        "DB_RETRY_MAX_DELAY_MS",
+                        DEFAULT_DB_RETRY_MAX_DELAY_MS,
+                    )),
+                },
+                change_feed: env_or("CHANGE_FEED_ENABLED", true),
+                replica_urls: env_secret_list("DATABASE_REPLICA_URLS")?,
+                statement_timeout: Duration::from_millis(env_or(
+                    "DB_STATEMENT_TIMEOUT_MS",
+                    DEFAULT_DB_STATEMENT_TIMEOUT_MS,
+                )),
+                query_retry: RetryConfig {
+                    max_retries: env_or("DB_QUERY_RETRIES", DEFAULT_DB_QUERY_RETRIES),
+                    initial_delay: Duration::from_millis(env_or(
+                        "DB_QUERY_RETRY_INITIAL_DELAY_MS",
+                        DEFAULT_DB_QUERY_RETRY_INITIAL_DELAY_MS,
+                    )),
+                    max_delay: Duration::from_millis(env_or(
+                        "DB_QUERY_RETRY_MAX_DELAY_MS",
+                        DEFAULT_DB_QUERY_RETRY_MAX_DELAY_MS,
+                    )),
+                },
+            },
+            grpc: GrpcConfig {
+                enabled: env_or("GRPC_ENABLED", grpc.enabled),
+                address: env_or("GRPC_ADDRESS", grpc.address),
+            },
+            webhooks: WebhookConfig {
+                retry: RetryConfig {
+                    max_retries: env_or("WEBHOOK_RETRIES", webhooks.retry.max_retries),
+                    initial_delay: Duration::from_millis(env_or(
+                        "WEBHOOK_RETRY_INITIAL_DELAY_MS",
+                        DEFAULT_WEBHOOK_RETRY_INITIAL_DELAY_MS,
+                    )),
+                    max_delay: Duration::from_millis(env_or(
+                        "WEBHOOK_RETRY_MAX_DELAY_MS",
+                        DEFAULT_WEBHOOK_RETRY_MAX_DELAY_MS,
+                    )),
+                },
+                timeout: Duration::from_millis(env_or(
+                    "WEBHOOK_TIMEOUT_MS",
+                    DEFAULT_WEBHOOK_TIMEOUT_MS,
+                )),
+            },
+            jobs: JobConfig {
+                poll_interval: Duration::from_millis(env_or(
+                    "JOB_POLL_INTERVAL_MS",
+                    DEFAULT_JOB_POLL_INTERVAL_MS,
+                )),
+            },
+            scheduler: SchedulerConfig {
+                enabled: env_or("SCHEDULER_ENABLED", scheduler.enabled),
+                purge_finished_jobs_every: env_interval(
+                    "SCHEDULE_PURGE_FINISHED_JOBS",
+                    scheduler.purge_finished_jobs_every,
+                ),
+                job_retention: env_interval("JOB_RETENTION", scheduler.job_retention),
+                requeue_stale_jobs_every: env_interval(
+                    "SCHEDULE_REQUEUE_STALE_JOBS",
+                    scheduler.requeue_stale_jobs_every,
+                ),
+                job_stale_after: env_interval("JOB_STALE_AFTER", scheduler.job_stale_after),
+            },
+            mailer: MailerConfig {
+                transport: env_or("MAIL_TRANSPORT", mailer.transport),
+                from: env::var("MAIL_FROM").unwrap_or(mailer.from),
+                smtp: SmtpConfig {
+                    host: env::var("SMTP_HOST").unwrap_or(mailer.smtp.host),
+                    port: env_or("SMTP_PORT", mailer.smtp.port),
+                    username: env::var("SMTP_USERNAME").ok(),
+                    password: env_secret("SMTP_PASSWORD")?,
+                },
+                app_url: env::var("APP_URL").unwrap_or(mailer.app_url),
+            },
+            cache: CacheConfig {
+                backend: env_or("CACHE_BACKEND", cache.backend),
+                redis_url: env_secret("REDIS_URL")?.unwrap_or(cache.redis_url),
+                capacity: env_or("CACHE_CAPACITY", cache.capacity),
+                ttl: env_interval("CACHE_TTL", cache.ttl),
+            },
+            storage: StorageConfig {
+                backend: env_or("STORAGE_BACKEND", storage.backend),
+                local_dir: env::var("STORAGE_LOCAL_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or(storage.local_dir),
+                s3: S3Config {
+                    bucket: env::var("S3_BUCKET").unwrap_or(storage.s3.bucket),
+                    region: env::var("S3_REGION").unwrap_or(storage.s3.region),
+                    endpoint: env::var("S3_ENDPOINT").ok(),
+                    path_style: env_or("S3_PATH_STYLE", storage.s3.path_style),
+                    access_key_id: env::var("S3_ACCESS_KEY_ID").ok(),
+                    secret_access_key: env_secret("S3_SECRET_ACCESS_KEY")?,
+                },
+            },
+            signup: SignupConfig {
+                rate_limit: env_or("SIGNUP_RATE_LIMIT", signup.rate_limit),
+                rate_window: env_interval("SIGNUP_RATE_WINDOW", signup.rate_window),
+                email_check_rate_limit: env_or("EMAIL_CHECK_RATE_LIMIT", signup.email_check_rate_limit),
+                email_check_rate_window: env_interval("EMAIL_CHECK_RATE_WINDOW", signup.email_check_rate_window),
+            },
+            password_policy: PasswordPolicyConfig {
+                history: env_or("PASSWORD_HISTORY", DEFAULT_PASSWORD_HISTORY),
+                breach_check: env_or("PASSWORD_BREACH_CHECK", BreachCheckMode::Off),
+                breach_api_url: env::var("PASSWORD_BREACH_API_URL")
+                    .unwrap_or_else(|_| DEFAULT_BREACH_API_URL.to_string()),
+            },
+            captcha: CaptchaConfig {
+                provider: env_or("CAPTCHA_PROVIDER", CaptchaProvider::None),
+                site_key: env::var("CAPTCHA_SITE_KEY").ok(),
+                secret_key: env_secret("CAPTCHA_SECRET_KEY")?,
+            },
+            client_errors: ClientErrorsConfig {
+                rate_limit: env_or("CLIENT_ERROR_RATE_LIMIT", client_errors.rate_limit),
+                rate_window: env_interval("CLIENT_ERROR_RATE_WINDOW", client_errors.rate_window),
+            },
+            cors: CorsConfig {
+                allowed_origins: env_list("CORS_ALLOWED_ORIGINS", cors.allowed_origins),
+                allowed_methods: env_list("CORS_ALLOWED_METHODS", cors.allowed_methods),
+                allowed_headers: env_list("CORS_ALLOWED_HEADERS", cors.allowed_headers),
+                allow_credentials: env_or("CORS_ALLOW_CREDENTIALS", cors.allow_credentials),
+            },
+            limits: LimitsConfig {
+                json: env_or("JSON_BODY_LIMIT", DEFAULT_JSON_BODY_LIMIT),
+                client_error: env_or("CLIENT_ERROR_BODY_LIMIT", DEFAULT_CLIENT_ERROR_BODY_LIMIT),
+            },
+            encryption: EncryptionConfig {
+                key: env_secret("PII_ENCRYPTION_KEY")?,
+                previous_keys: env_secret_list("PII_ENCRYPTION_PREVIOUS_KEYS")?,
+                backup_key: env_secret("BACKUP_ENCRYPTION_KEY")?,
+            },
+            features: FeatureFlagsConfig {
+                enabled: env_list("FEATURE_FLAGS", Vec::new()),
+            },
+            logging: LoggingConfig {
+                requests: env_or("LOG_REQUESTS", true),
+                slow_request: Duration::from_millis(env_or("SLOW_REQUEST_MS", DEFAULT_SLOW_REQUEST_MS)),
+                queries: env_or("LOG_QUERIES", profile == Profile::Dev),
+                slow_query: Duration::from_millis(env_or("SLOW_QUERY_MS", DEFAULT_SLOW_QUERY_MS)),
+            },
+            maintenance: MaintenanceConfig {
+                enabled: env_or("MAINTENANCE_MODE", false),
+                message: env::var("MAINTENANCE_MESSAGE").ok().filter(|message| !message.trim().is_empty()),
+            },
+            envelope: EnvelopeConfig {
+                always: env_or("API_ENVELOPE", false),
+            },
+            event_stream: EventStreamConfig {
+                broker: env_or("EVENT_BROKER", event_stream.broker),
+                format: env_or("EVENT_FORMAT", event_stream.format),
+                kafka_rest_url: env::var("KAFKA_REST_URL").unwrap_or(event_stream.kafka_rest_url),
+                kafka_topic: env::var("KAFKA_TOPIC").unwrap_or(event_stream.kafka_topic),
+                nats_url: env_secret("NATS_URL")?.unwrap_or(event_stream.nats_url),
+                nats_subject_prefix: env::var("NATS_SUBJECT_PREFIX")
+                    .ok()
+                    .filter(|prefix| !prefix.trim().is_empty()),
+                retry: RetryConfig {
+                    max_retries: env_or("EVENT_PUBLISH_RETRIES", DEFAULT_EVENT_PUBLISH_RETRIES),
+                    initial_delay: Duration::from_millis(env_or(
+                        "EVENT_PUBLISH_RETRY_INITIAL_DELAY_MS",
+                        DEFAULT_EVENT_PUBLISH_RETRY_INITIAL_DELAY_MS,
+                    )),
+                    max_delay: Duration::from_millis(env_or(
+                        "EVENT_PUBLISH_RETRY_MAX_DELAY_MS",
+                        DEFAULT_EVENT_PUBLISH_RETRY_MAX_DELAY_MS,
+                    )),
+                },
+            },
+            admin: AdminConfig {
+                token: env_secret("ADMIN_TOKEN")?,
+            },
+        };
+
+        let insecure = config.insecure_credentials();
+        if config.profile == Profile::Prod && !insecure.is_empty() {
+            return Err(format!(
+                "Refusing to start the prod profile with default or placeholder credentials: {}",
+                insecure.join(", ")
+            ));
+        }
+        Ok(config)
+    }
+
+    /// Variables whose credential is the development default or a placeholder
+    pub fn insecure_credentials(&self) -> Vec<&'static str> {
+        let dev_database_password = connection_password(DB_CONNECTION_STRING);
+        let database_password = connection_password(self.database.connection_string.expose());
+        let mut insecure = Vec::new();
+        // Without a password the server authenticates some other way, e.g. with certificates
+        let default_password = |password: &str| is_placeholder(password) || Some(password) == dev_database_password;
+        if database_password.is_some_and(default_password) {
+            insecure.push("DATABASE_URL");
+        }
+        let replicas = &self.database.replica_urls;
+        if replicas.iter().any(|url| connection_password(url.expose()).is_some_and(default_password)) {
+            insecure.push("DATABASE_REPLICA_URLS");
+        }
+        if connection_password(self.cache.redis_url.expose()).is_some_and(is_placeholder) {
+            insecure.push("REDIS_URL");
+        }
+        if connection_password(self.event_stream.nats_url.expose()).is_some_and(is_placeholder) {
+            insecure.push("NATS_URL");
+        }
+        let secrets = [
+            ("SMTP_PASSWORD", self.mailer.smtp.password.as_ref()),
+            ("S3_SECRET_ACCESS_KEY", self.storage.s3.secret_access_key.as_ref()),
+            ("CAPTCHA_SECRET_KEY", self.captcha.secret_key.as_ref()),
+            ("ADMIN_TOKEN", self.admin.token.as_ref()),
+            ("PII_ENCRYPTION_KEY", self.encryption.key.as_ref()),
+            ("BACKUP_ENCRYPTION_KEY", self.encryption.backup_key.as_ref()),
+        ];
+        for (key, secret) in secrets {
+            if secret.is_some_and(|secret| is_placeholder(secret.expose())) {
+                insecure.push(key);
+            }
+        }
+        insecure
+    }
+}
+
+/// Password of a Postgres (`key=value` or URL) or Redis connection string
+fn connection_password(connection_string: &str) -> Option<&str> {
+    if let Some((_, rest)) = connection_string.split_once("://") {
+        let userinfo = rest.split_once('@')?.0;
+        return userinfo.split_once(':').map(|(_, password)| password);
+    }
+    connection_string
+        .split_whitespace()
+        .find_map(|pair| pair.strip_prefix("password="))
+        .map(|password| password.trim_matches('\''))
+}
+
+/// Read an environment variable and parse it, using `default` when it is
+/// missing or cannot be parsed
+fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+/// Read a comma-separated environment variable, using `default` when it is missing
+fn env_list(key: &str, default: Vec<String>) -> Vec<String> {
+    match env::var(key) {
+        Ok(value) => split_list(&value),
+        Err(_) => default,
+    }
+}
+
+/// Read a comma-separated list of secrets, empty when it is missing
+fn env_secret_list(key: &str) -> Result<Vec<SecretString>, String> {
+    Ok(env_secret(key)?
+        .map(|list| split_list(list.expose()).into_iter().map(SecretString::from).collect())
+        .unwrap_or_default())
+}
+
+/// Items of a comma-separated list, trimmed, without empty ones
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse an interval such as `30s`, `15m`, `6h` or `7d`
+pub fn parse_interval(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value.len().checked_sub(1)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.checked_mul(unit_secs)?)).filter(|d| !d.is_zero())
+}
+
+/// Read an interval environment variable, using `default` when it is
+/// missing or invalid
+fn env_interval(key: &str, default: Duration) -> Duration {
+    env::var(key)
+        .ok()
+        .and_then(|value| parse_interval(&value))
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_retry_config() {
+        let retry = RetryConfig::default();
+        assert_eq!(retry.max_retries, DEFAULT_DB_CONNECT_RETRIES);
+        assert_eq!(retry.initial_delay, Duration::from_millis(500));
+        assert_eq!(retry.max_delay, Duration::from_millis(10_000));
+    }
+
+    #[test]
+    fn test_default_query_limits() {
+        let config = AppConfig::from_env().unwrap();
+        assert_eq!(config.database.statement_timeout, Duration::from_secs(10));
+        assert_eq!(config.database.query_retry.max_retries, DEFAULT_DB_QUERY_RETRIES);
+        assert_eq!(config.database.query_retry.delay_for_attempt(0), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_delay_doubles_each_attempt() {
+        let retry = RetryConfig::default();
+        assert_eq!(retry.delay_for_attempt(0), Duration::from_millis(500));
+        assert_eq!(retry.delay_for_attempt(1), Duration::from_millis(1000));
+        assert_eq!(retry.delay_for_attempt(2), Duration::from_millis(2000));
+        assert_eq!(retry.delay_for_attempt(3), Duration::from_millis(4000));
+    }
+
+    #[test]
+    fn test_delay_is_capped() {
+        let retry = RetryConfig::default();
+        assert_eq!(retry.delay_for_attempt(5), Duration::from_millis(10_000));
+        assert_eq!(retry.delay_for_attempt(100), Duration::from_millis(10_000));
+    }
+
+    #[test]
+    fn test_default_grpc_config() {
+        let grpc = GrpcConfig::default();
+        assert!(grpc.enabled);
+        assert_eq!(grpc.address.port(), 50051);
+    }
+
+    #[test]
+    fn test_default_logging_config() {
+        let logging = LoggingConfig::default();
+        assert!(logging.requests);
+        assert!(!logging.queries);
+        assert_eq!(logging.slow_request, Duration::from_millis(500));
+        assert_eq!(logging.slow_query, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_default_password_policy() {
+        let policy = PasswordPolicyConfig::default();
+        assert_eq!(policy.history, 5);
+        assert_eq!(policy.breach_check, BreachCheckMode::Off);
+    }
+
+    #[test]
+    fn test_breach_check_mode_from_str() {
+        assert_eq!("Reject".parse::<BreachCheckMode>(), Ok(BreachCheckMode::Reject));
+        assert_eq!("warn".parse::<BreachCheckMode>().map(|mode| mode.as_str()), Ok("warn"));
+        assert!("block".parse::<BreachCheckMode>().is_err());
+    }
+
+    #[test]
+    fn test_default_webhook_config() {
+        let webhooks = WebhookConfig::default();
+        assert_eq!(webhooks.retry.max_retries, 5);
+        assert_eq!(webhooks.retry.delay_for_attempt(0), Duration::from_secs(1));
+        assert_eq!(webhooks.retry.delay_for_attempt(10), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_mail_transport_from_str() {
+        assert_eq!("log".parse(), Ok(MailTransport::Log));
+        assert_eq!("SMTP".parse(), Ok(MailTransport::Smtp));
+        assert!("sendmail".parse::<MailTransport>().is_err());
+    }
+
+    #[test]
+    fn test_cache_backend_round_trips() {
+        for backend in [CacheBackend::None, CacheBackend::Memory, CacheBackend::Redis] {
+            assert_eq!(backend.as_str().parse(), Ok(backend));
+        }
+        assert_eq!(CacheConfig::default().backend, CacheBackend::None);
+    }
+
+    #[test]
+    fn test_storage_backend_from_str() {
+        assert_eq!("local".parse(), Ok(StorageBackend::Local));
+        assert_eq!("S3".parse(), Ok(StorageBackend::S3));
+        assert!("gcs".parse::<StorageBackend>().is_err());
+        assert_eq!(StorageConfig::default().backend, StorageBackend::Local);
+    }
+
+    #[test]
+    fn test_captcha_provider_from_str() {
+        assert_eq!("hCaptcha".parse(), Ok(CaptchaProvider::HCaptcha));
+        assert_eq!("turnstile".parse(), Ok(CaptchaProvider::Turnstile));
+        assert!("recaptcha-v2".parse::<CaptchaProvider>().is_err());
+        assert_eq!(CaptchaConfig::default().provider.as_str(), "none");
+    }
+
+    #[test]
+    fn test_event_stream_settings_from_str() {
+        assert_eq!("Kafka".parse(), Ok(EventBroker::Kafka));
+        assert_eq!("nats".parse::<EventBroker>().map(|broker| broker.as_str()), Ok("nats"));
+        assert!("rabbitmq".parse::<EventBroker>().is_err());
+        assert_eq!("proto".parse(), Ok(EventFormat::Protobuf));
+        assert!("avro".parse::<EventFormat>().is_err());
+        let config = EventStreamConfig::default();
+        assert_eq!((config.broker, config.format), (EventBroker::None, EventFormat::Json));
+        assert_eq!(config.retry.max_retries, DEFAULT_EVENT_PUBLISH_RETRIES);
+        assert_eq!(config.retry.delay_for_attempt(0), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_interval("15m"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_interval(" 6h "), Some(Duration::from_secs(21_600)));
+        assert_eq!(parse_interval("7d"), Some(Duration::from_secs(604_800)));
+        assert_eq!(parse_interval("0m"), None);
+        assert_eq!(parse_interval("10"), None);
+        assert_eq!(parse_interval("m"), None);
+        assert_eq!(parse_interval(""), None);
+    }
+
+    #[test]
+    fn test_env_or_falls_back_to_default() {
+        assert_eq!(env_or("RUST_APP_TEST_UNSET_VARIABLE", 42u32), 42);
+    }
+
+    #[test]
+    fn test_cors_profiles() {
+        let dev = CorsConfig::for_profile(Profile::Dev);
+        assert!(dev.allowed_origins.contains(&"http://localhost:8080".to_string()));
+        assert!(dev.validate(Profile::Dev).is_ok());
+
+        let prod = CorsConfig::for_profile(Profile::Prod);
+        assert!(!prod.allow_credentials);
+        assert!(prod.validate(Profile::Prod).is_err());
+
+        let prod = CorsConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            ..prod
+        };
+        assert!(prod.validate(Profile::Prod).is_ok());
+        assert_eq!("production".parse(), Ok(Profile::Prod));
+    }
+
+    #[test]
+    fn test_validate_origin() {
+        assert!(validate_origin("https://app.example.com").is_ok());
+        assert!(validate_origin("http://localhost:8080").is_ok());
+        assert!(validate_origin("http://[::1]:8080").is_ok());
+        assert!(validate_origin("*").is_err());
+        assert!(validate_origin("app.example.com").is_err());
+        assert!(validate_origin("ftp://example.com").is_err());
+        assert!(validate_origin("https://example.com/").is_err());
+        assert!(validate_origin("https://*.example.com").is_err());
+        assert!(validate_origin("http://localhost:port").is_err());
+        assert!(validate_origin("https://").is_err());
+    }
+
+    #[test]
+    fn test_json_body_limit_parses_byte_units() {
+        assert_eq!(LimitsConfig::default().json, ByteUnit::Mebibyte(1));
+        assert_eq!(LimitsConfig::default().client_error, ByteUnit::Kibibyte(16));
+        assert_eq!("256KiB".parse::<ByteUnit>().unwrap(), ByteUnit::Kibibyte(256));
+    }
+
+    #[test]
+    fn test_feature_flags_report_known_flags() {
+        let config = FeatureFlagsConfig::default();
+        assert_eq!(config.flags().get("bulk_delete"), Some(&false));
+
+        let config = FeatureFlagsConfig {
+            enabled: vec!["bulk_delete".to_string(), "beta_search".to_string()],
+        };
+        let flags = config.flags();
+        assert_eq!(flags.get("bulk_delete"), Some(&true));
+        assert_eq!(flags.get("org_management"), Some(&false));
+        assert_eq!(flags.get("debug_panel"), Some(&false));
+        assert_eq!(flags.get("beta_search"), Some(&true));
+    }
+
+    #[test]
+    fn test_connection_password() {
+        assert_eq!(connection_password(DB_CONNECTION_STRING), Some("postGr3s1245xSDI"));
+        assert_eq!(connection_password("postgres://app:s3cr3t@db:5432/app"), Some("s3cr3t"));
+        assert_eq!(connection_password("host=db password='quoted' user=app"), Some("quoted"));
+        assert_eq!(connection_password("redis://127.0.0.1:6379"), None);
+        assert_eq!(connection_password("host=db user=app sslmode=verify-full"), None);
+    }
+
+    #[test]
+    fn test_insecure_credentials() {
+        let mut config = AppConfig::from_env().unwrap();
+        config.database.connection_string = SecretString::from(DB_CONNECTION_STRING);
+        config.cache.redis_url = SecretString::from("redis://:changeme@cache:6379");
+        config.mailer.smtp.password = Some(SecretString::from("password"));
+        config.storage.s3.secret_access_key = Some(SecretString::from("wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY"));
+        config.captcha.secret_key = None;
+        config.encryption.key = Some(SecretString::from("0".repeat(64)));
+        config.encryption.backup_key = None;
+        config.admin.token = Some(SecretString::from("admin"));
+        assert_eq!(
+            config.insecure_credentials(),
+            ["DATABASE_URL", "REDIS_URL", "SMTP_PASSWORD", "S3_SECRET_ACCESS_KEY", "ADMIN_TOKEN", "PII_ENCRYPTION_KEY"]
+        );
+
+        config.database.connection_string = SecretString::from("postgres://app:Xk29a-7fQ@db/app");
+        config.cache.redis_url = SecretString::from(DEFAULT_REDIS_URL);
+        config.mailer.smtp.password = Some(SecretString::from("Xk29a-7fQ"));
+        config.storage.s3.secret_access_key = None;
+        config.admin.token = Some(SecretString::from("q8Zt-31xLw"));
+        config.encryption.key = Some(SecretString::from("9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"));
+        assert!(config.insecure_credentials().is_empty());
+        // Secrets stay out of a dumped config
+        assert!(!format!("{:?}", config).contains("Xk29a-7fQ"));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::config::EncryptionConfig;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+// Field encryption - Single Responsibility Principle
+// Encrypts individual PII columns with AES-256-GCM. Every ciphertext names
+// the key that produced it, so keys can be rotated: old keys stay available
+// for decryption until the re-keying command has rewritten every row.
+// Encrypted values are random, so equality lookups use a keyed HMAC instead,
+// computed with every key until the re-keying command has rewritten the hashes
+
+/// Prefix of every encrypted value: `enc:<key id>:<hex(nonce || ciphertext)>`
+pub const ENCRYPTED_PREFIX: &str = "enc:";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+// KeyInit is in scope for AES-GCM, so name the Mac constructor explicitly
+fn hmac_sha256(key: &[u8]) -> Hmac<Sha256> {
+    <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+struct FieldKey {
+    /// Short fingerprint of the key, stored with every ciphertext
+    id: String,
+    cipher: Aes256Gcm,
+    /// Key of the lookup HMAC, derived from the encryption key
+    lookup: Vec<u8>,
+}
+
+impl FieldKey {
+    fn from_hex(value: &str) -> Result<Self, String> {
+        let bytes = hex::decode(value.trim())
+            .map_err(|_| "PII encryption keys must be hex encoded".to_string())?;
+        if bytes.len() != KEY_LEN {
+            return Err(format!(
+                "PII encryption keys must be {} bytes ({} hex characters)",
+                KEY_LEN,
+                KEY_LEN * 2
+            ));
+        }
+
+        let fingerprint = Sha256::digest(&bytes);
+        let mut mac = hmac_sha256(&bytes);
+        mac.update(b"pii-lookup");
+        Ok(FieldKey {
+            id: hex::encode(&fingerprint[..4]),
+            cipher: Aes256Gcm::new_from_slice(&bytes).expect("key length checked above"),
+            lookup: mac.finalize().into_bytes().to_vec(),
+        })
+    }
+
+    fn lookup_hash(&self, value: &str) -> String {
+        let mut mac = hmac_sha256(&self.lookup);
+        mac.update(value.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// Encrypts and decrypts PII columns
+pub struct FieldCipher {
+    current: FieldKey,
+    /// Retired keys, only used to read values that were not re-keyed yet
+    previous: Vec<FieldKey>,
+}
+
+impl FieldCipher {
+    pub fn new(current: &str, previous: &[String]) -> Result<Self, String> {
+        Ok(FieldCipher {
+            current: FieldKey::from_hex(current)?,
+            previous: previous
+                .iter()
+                .map(|key| FieldKey::from_hex(key))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The configured cipher, or None when encryption is disabled
+    pub fn from_config(config: &EncryptionConfig) -> Result<Option<Self>, String> {
+        config
+            .key
+            .as_ref()
+            .map(|key| {
+                let previous: Vec<String> = config.previous_keys.iter().map(|key| key.expose().to_string()).collect();
+                FieldCipher::new(key.expose(), &previous)
+            })
+            .transpose()
+    }
+
+    /// Encrypt `plaintext` with the current key and a random nonce
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .current
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("AES-GCM encryption of an in-memory buffer cannot fail");
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        format!("{}{}:{}", ENCRYPTED_PREFIX, self.current.id, hex::encode(payload))
+    }
+
+    /// Decrypt a stored value. Values without the `enc:` prefix were written
+    /// before encryption was enabled and are returned unchanged
+    pub fn decrypt(&self, value: &str) -> Result<String, String> {
+        let Some(rest) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let (key_id, payload) = rest
+            .split_once(':')
+            .ok_or_else(|| "Malformed encrypted value".to_string())?;
+        let key = std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|key| key.id == key_id)
+            .ok_or_else(|| format!("No PII encryption key with id '{}'", key_id))?;
+
+        let payload = hex::decode(payload).map_err(|_| "Malformed encrypted value".to_string())?;
+        if payload.len() < NONCE_LEN {
+            return Err("Malformed encrypted value".to_string());
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = key
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| format!("Failed to decrypt value with key '{}'", key_id))?;
+        String::from_utf8(plaintext).map_err(|e| e.to_string())
+    }
+
+    /// Whether `value` is already encrypted with the current key
+    pub fn is_current(&self, value: &str) -> bool {
+        value
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .and_then(|rest| rest.split_once(':'))
+            .is_some_and(|(key_id, _)| key_id == self.current.id)
+    }
+
+    /// Deterministic keyed hash of `value` with the current key, as stored on writes
+    pub fn lookup_hash(&self, value: &str) -> String {
+        self.current.lookup_hash(value)
+    }
+
+    /// Hashes of `value` with the current key and every previous key, so
+    /// lookups still match rows whose hash was not re-keyed yet
+    pub fn lookup_hashes(&self, value: &str) -> Vec<String> {
+        std::iter::once(&self.current)
+            .chain(&self.previous)
+            .map(|key| key.lookup_hash(value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    const OTHER_KEY: &str = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100";
+
+    #[test]
+    fn test_encrypt_round_trips_with_random_nonces() {
+        let cipher = FieldCipher::new(KEY, &[]).unwrap();
+        let first = cipher.encrypt("john@example.com");
+        let second = cipher.encrypt("john@example.com");
+
+        assert!(first.starts_with(ENCRYPTED_PREFIX));
+        assert!(!first.contains("john"));
+        assert_ne!(first, second);
+        assert!(cipher.is_current(&first));
+        assert_eq!(cipher.decrypt(&first).unwrap(), "john@example.com");
+        assert_eq!(cipher.decrypt(&second).unwrap(), "john@example.com");
+    }
+
+    #[test]
+    fn test_plaintext_passes_through_decrypt() {
+        let cipher = FieldCipher::new(KEY, &[]).unwrap();
+        assert_eq!(cipher.decrypt("John Doe").unwrap(), "John Doe");
+        assert!(!cipher.is_current("John Doe"));
+    }
+
+    #[test]
+    fn test_previous_keys_still_decrypt() {
+        let old = FieldCipher::new(OTHER_KEY, &[]).unwrap();
+        let encrypted = old.encrypt("John Doe");
+
+        let rotated = FieldCipher::new(KEY, &[OTHER_KEY.to_string()]).unwrap();
+        assert!(!rotated.is_current(&encrypted));
+        assert_eq!(rotated.decrypt(&encrypted).unwrap(), "John Doe");
+
+        let without_old_key = FieldCipher::new(KEY, &[]).unwrap();
+        assert!(without_old_key.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_tampered_values_are_rejected() {
+        let cipher = FieldCipher::new(KEY, &[]).unwrap();
+        let mut encrypted = cipher.encrypt("John Doe");
+        let last = encrypted.pop().unwrap();
+        encrypted.push(if last == '0' { '1' } else { '0' });
+
+        assert!(cipher.decrypt(&encrypted).is_err());
+        assert!(cipher.decrypt("enc:nokey").is_err());
+    }
+
+    #[test]
+    fn test_lookup_hash_is_deterministic_per_key() {
+        let cipher = FieldCipher::new(KEY, &[]).unwrap();
+        let other = FieldCipher::new(OTHER_KEY, &[]).unwrap();
+
+        assert_eq!(cipher.lookup_hash("john@example.com"), cipher.lookup_hash("john@example.com"));
+        assert_ne!(cipher.lookup_hash("john@example.com"), cipher.lookup_hash("jane@example.com"));
+        assert_ne!(cipher.lookup_hash("john@example.com"), other.lookup_hash("john@example.com"));
+    }
+
+    #[test]
+    fn test_lookup_hashes_match_rows_hashed_before_rotation() {
+        let old = FieldCipher::new(OTHER_KEY, &[]).unwrap();
+        let stored = old.lookup_hash("john@example.com");
+
+        let rotated = FieldCipher::new(KEY, &[OTHER_KEY.to_string()]).unwrap();
+        let candidates = rotated.lookup_hashes("john@example.com");
+        assert_eq!(candidates[0], rotated.lookup_hash("john@example.com"));
+        assert!(candidates.contains(&stored));
+        assert!(!rotated.lookup_hashes("jane@example.com").contains(&stored));
+
+        // Once the old key is dropped the re-keyed hash is the only one left
+        let rekeyed = FieldCipher::new(KEY, &[]).unwrap();
+        assert_eq!(rekeyed.lookup_hashes("john@example.com"), vec![rotated.lookup_hash("john@example.com")]);
+    }
+
+    #[test]
+    fn test_invalid_keys_are_rejected() {
+        assert!(FieldCipher::new("not hex", &[]).is_err());
+        assert!(FieldCipher::new("abcd", &[]).is_err());
+        assert!(FieldCipher::new(KEY, &["abcd".to_string()]).is_err());
+
+        let disabled = EncryptionConfig::default();
+        assert!(FieldCipher::from_config(&disabled).unwrap().is_none());
+    }
+}
@@ -1,33 +1,154 @@
+use crate::config::DatabaseConfig;
+use crate::query_timeout::statement_timeout_sql;
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 use tokio_postgres::{Client, NoTls};
 
-/// Database configuration and initialization module
-/// Following Single Responsibility Principle - this module only handles database setup
+// Database configuration and initialization module
+// Following Single Responsibility Principle - this module only handles database setup
 
 // Use 127.0.0.1 instead of localhost to ensure TCP connection to Docker container
 // localhost might try Unix socket which could connect to local PostgreSQL if running
-const DB_CONNECTION_STRING: &str =
+pub const DB_CONNECTION_STRING: &str =
     "host=127.0.0.1 user=postgres password=postGr3s1245xSDI dbname=rust_app_db port=5431";
 
 const SCHEMA_INIT_SQL: &str = "CREATE TABLE IF NOT EXISTS users (
     id SERIAL PRIMARY KEY,
     name TEXT NOT NULL,
     email TEXT NOT NULL UNIQUE,
-    password TEXT NOT NULL
+    password TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    email_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'suspended')),
+    last_login_at TIMESTAMPTZ,
+    email_hash TEXT UNIQUE,
+    phone TEXT,
+    address TEXT,
+    username TEXT UNIQUE CHECK (username ~ '^[a-z0-9]+(-[a-z0-9]+)*$' AND length(username) BETWEEN 3 AND 30),
+    tags TEXT[] NOT NULL DEFAULT '{}'
+)";
+
+// Filtering the list by tag uses array containment (`tags @> ARRAY[...]`)
+const TAGS_INDEX_SQL: &str = "CREATE INDEX IF NOT EXISTS users_tags ON users USING GIN (tags)";
+
+// Emails differing only by case are the same address
+const EMAIL_INDEX_SQL: &str =
+    "CREATE UNIQUE INDEX IF NOT EXISTS users_email_lower_unique ON users (LOWER(email))";
+
+// Trigram indexes of the fuzzy user search
+const SEARCH_SCHEMA_SQL: &str = "CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE INDEX IF NOT EXISTS users_name_trgm ON users USING GIN (name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS users_email_trgm ON users USING GIN (email gin_trgm_ops);";
+
+const WEBHOOKS_SCHEMA_SQL: &str = "CREATE TABLE IF NOT EXISTS webhooks (
+    id SERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+)";
+
+const JOBS_SCHEMA_SQL: &str = "CREATE TABLE IF NOT EXISTS jobs (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 1,
+    last_error TEXT,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+)";
+
+// History of what happened to each user, written from domain events.
+// Entries are hash-chained and the table is append-only
+const AUDIT_LOG_SCHEMA_SQL: &str = "CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS audit_log_user_time ON audit_log (user_id, occurred_at DESC);
+ALTER TABLE audit_log ADD COLUMN IF NOT EXISTS prev_hash TEXT, ADD COLUMN IF NOT EXISTS hash TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS audit_log_prev_hash ON audit_log (prev_hash);
+CREATE OR REPLACE FUNCTION reject_audit_log_change() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+DROP TRIGGER IF EXISTS audit_log_append_only ON audit_log;
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+FOR EACH ROW EXECUTE FUNCTION reject_audit_log_change();";
+
+// Single-use tokens of the links sent to confirm email addresses
+const EMAIL_VERIFICATION_SCHEMA_SQL: &str = "CREATE TABLE IF NOT EXISTS email_verification_tokens (
+    token TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL
+)";
+
+// Per-user inbox of notifications, written from domain events
+const NOTIFICATIONS_SCHEMA_SQL: &str = "CREATE TABLE IF NOT EXISTS notifications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ
+);
+CREATE INDEX IF NOT EXISTS notifications_user_time ON notifications (user_id, created_at DESC);";
+
+// UI preferences of a user, one JSONB document so new keys need no migration
+const USER_PREFERENCES_SCHEMA_SQL: &str = "CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    preferences JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+)";
+
+// Argon2 hashes of the last passwords of each user, see password_history.rs
+const PASSWORD_HISTORY_SCHEMA_SQL: &str = "CREATE TABLE IF NOT EXISTS password_history (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS password_history_user ON password_history (user_id, id DESC);";
+
+// Every write to users is announced on the `user_changes` channel so all
+// backend instances can push live updates. Only the operation and the id are
+// sent: NOTIFY payloads must stay under 8000 bytes, so the listener re-reads the row
+const CHANGE_FEED_SQL: &str = "CREATE OR REPLACE FUNCTION notify_user_change() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('user_changes', json_build_object('op', 'delete', 'id', OLD.id)::text);
+        RETURN OLD;
+    END IF;
+    PERFORM pg_notify('user_changes', json_build_object('op', lower(TG_OP), 'id', NEW.id)::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER users_notify_change
+    AFTER INSERT OR UPDATE OR DELETE ON users
+    FOR EACH ROW EXECUTE FUNCTION notify_user_change();";
+
+// Applied SQL migrations, by file name
+const MIGRATIONS_TABLE_SQL: &str = "CREATE TABLE IF NOT EXISTS schema_migrations (
+    version TEXT PRIMARY KEY,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
 )";
 
 /// Initialize database connection and return the client
-/// Spawns a background task to handle the connection
-pub async fn init_database() -> Result<Arc<Client>, Box<dyn std::error::Error>> {
+/// Retries the connection with exponential backoff so the backend survives
+/// starting before Postgres is ready (e.g. docker-compose ordering)
+pub async fn init_database(
+    config: &DatabaseConfig,
+) -> Result<Arc<Client>, Box<dyn std::error::Error>> {
     // Establish database connection
-    let (client, connection) = tokio_postgres::connect(DB_CONNECTION_STRING, NoTls).await?;
-
-    // Spawn connection handler in background
-    tokio::spawn(async move {
-        if let Err(e) = connection.await {
-            eprintln!("Database connection error: {}", e);
-        }
-    });
+    let client = connect_with_retry(config).await?;
 
     // Initialize database schema
     initialize_schema(&client).await?;
@@ -35,12 +156,155 @@ pub async fn init_database() -> Result<Arc<Client>, Box<dyn std::error::Error>>
     Ok(Arc::new(client))
 }
 
+/// Connect to the database, retrying according to the configured backoff policy
+/// Spawns a background task to handle the connection; the schema is left untouched
+pub async fn connect_with_retry(config: &DatabaseConfig) -> Result<Client, tokio_postgres::Error> {
+    let retry = &config.retry;
+    let mut attempt = 0;
+
+    loop {
+        match tokio_postgres::connect(config.connection_string.expose(), NoTls).await {
+            Ok((client, connection)) => {
+                // Spawn connection handler in background
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        eprintln!("Database connection error: {}", e);
+                    }
+                });
+
+                if attempt > 0 {
+                    println!("Connected to database after {} retries", attempt);
+                }
+                return Ok(client);
+            }
+            Err(e) if attempt < retry.max_retries => {
+                let delay = retry.delay_for_attempt(attempt);
+                attempt += 1;
+                eprintln!(
+                    "Database not available yet ({}), retrying in {:?} (attempt {}/{})",
+                    e, delay, attempt, retry.max_retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Giving up connecting to database after {} attempts: {}",
+                    attempt + 1,
+                    e
+                );
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// Connect to the read replicas of `config` with the same retry policy;
+/// they receive their schema from the primary
+pub async fn connect_replicas(config: &DatabaseConfig) -> Result<Vec<Arc<Client>>, tokio_postgres::Error> {
+    let mut replicas = Vec::with_capacity(config.replica_urls.len());
+    for url in &config.replica_urls {
+        let replica = DatabaseConfig {
+            connection_string: url.clone(),
+            replica_urls: Vec::new(),
+            ..config.clone()
+        };
+        replicas.push(Arc::new(connect_with_retry(&replica).await?));
+    }
+    Ok(replicas)
+}
+
+/// Make Postgres cancel the statements of `client` that run longer than the
+/// configured statement timeout; schema setup and migrations run before this
+pub async fn limit_statements(client: &Client, config: &DatabaseConfig) -> Result<(), tokio_postgres::Error> {
+    client.batch_execute(&statement_timeout_sql(config.statement_timeout)).await
+}
+
+/// The server's message for database errors, which tokio-postgres displays as "db error"
+fn error_message(error: &tokio_postgres::Error) -> String {
+    match error.as_db_error() {
+        Some(db_error) => db_error.message().to_string(),
+        None => error.to_string(),
+    }
+}
+
 /// Initialize database schema by creating tables if they don't exist
 async fn initialize_schema(client: &Client) -> Result<(), tokio_postgres::Error> {
     client.execute(SCHEMA_INIT_SQL, &[]).await?;
+    // Existing duplicates must be resolved by hand, so they do not stop startup
+    if let Err(e) = client.execute(EMAIL_INDEX_SQL, &[]).await {
+        eprintln!(
+            "Case-insensitive email index not created ({}); list the duplicates with `admin duplicate-emails`",
+            error_message(&e)
+        );
+    }
+    // Databases created before tags need migration 019 for the column first
+    if let Err(e) = client.execute(TAGS_INDEX_SQL, &[]).await {
+        eprintln!("Tags index not created ({}); run the pending migrations", error_message(&e));
+    }
+    // Creating the extension may need privileges the application role lacks
+    if let Err(e) = client.batch_execute(SEARCH_SCHEMA_SQL).await {
+        eprintln!("Search indexes not created ({}); user search is unavailable", error_message(&e));
+    }
+    client.execute(WEBHOOKS_SCHEMA_SQL, &[]).await?;
+    client.execute(JOBS_SCHEMA_SQL, &[]).await?;
+    client.batch_execute(AUDIT_LOG_SCHEMA_SQL).await?;
+    client.execute(EMAIL_VERIFICATION_SCHEMA_SQL, &[]).await?;
+    client.batch_execute(NOTIFICATIONS_SCHEMA_SQL).await?;
+    client.execute(USER_PREFERENCES_SCHEMA_SQL, &[]).await?;
+    client.batch_execute(PASSWORD_HISTORY_SCHEMA_SQL).await?;
+    client.batch_execute(CHANGE_FEED_SQL).await?;
     Ok(())
 }
 
+/// The `.sql` files of `files` not in `applied`, in the order they must run
+pub fn pending_migrations(files: &[String], applied: &HashSet<String>) -> Vec<String> {
+    let mut pending: Vec<String> = files
+        .iter()
+        .filter(|file| file.ends_with(".sql") && !applied.contains(*file))
+        .cloned()
+        .collect();
+    pending.sort();
+    pending
+}
+
+/// Apply the migrations of `dir` that were not applied yet, each in its own
+/// transaction, and return their names. With `baseline` they are only
+/// recorded as applied, for databases that were migrated by hand
+pub async fn run_migrations(
+    client: &mut Client,
+    dir: &Path,
+    baseline: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    client.execute(MIGRATIONS_TABLE_SQL, &[]).await?;
+    let applied: HashSet<String> = client
+        .query("SELECT version FROM schema_migrations", &[])
+        .await?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+
+    let files = std::fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<String>, std::io::Error>>()?;
+
+    let pending = pending_migrations(&files, &applied);
+    for version in &pending {
+        let transaction = client.transaction().await?;
+        if !baseline {
+            let sql = std::fs::read_to_string(dir.join(version))?;
+            transaction
+                .batch_execute(&sql)
+                .await
+                .map_err(|e| format!("Migration {} failed: {}", version, error_message(&e)))?;
+        }
+        transaction
+            .execute("INSERT INTO schema_migrations (version) VALUES ($1)", &[version])
+            .await?;
+        transaction.commit().await?;
+    }
+    Ok(pending)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,6 +316,23 @@ mod tests {
         assert!(SCHEMA_INIT_SQL.contains("users"));
         assert!(SCHEMA_INIT_SQL.contains("email TEXT NOT NULL UNIQUE"));
         assert!(SCHEMA_INIT_SQL.contains("password TEXT NOT NULL"));
+        assert!(SCHEMA_INIT_SQL.contains("created_at TIMESTAMPTZ"));
+        assert!(SCHEMA_INIT_SQL.contains("email_verified BOOLEAN"));
+        assert!(SCHEMA_INIT_SQL.contains("status TEXT NOT NULL DEFAULT 'active'"));
+        assert!(SCHEMA_INIT_SQL.contains("email_hash TEXT UNIQUE"));
+        assert!(SCHEMA_INIT_SQL.contains("tags TEXT[] NOT NULL DEFAULT '{}'"));
+        assert!(TAGS_INDEX_SQL.contains("USING GIN (tags)"));
+        assert!(WEBHOOKS_SCHEMA_SQL.contains("events TEXT[]"));
+        assert!(JOBS_SCHEMA_SQL.contains("payload JSONB"));
+        assert!(CHANGE_FEED_SQL.contains("pg_notify('user_changes'"));
+        assert!(EMAIL_INDEX_SQL.contains("UNIQUE INDEX"));
+        assert!(EMAIL_INDEX_SQL.contains("LOWER(email)"));
+        assert!(SEARCH_SCHEMA_SQL.contains("pg_trgm"));
+        assert!(SEARCH_SCHEMA_SQL.contains("gin_trgm_ops"));
+        assert!(AUDIT_LOG_SCHEMA_SQL.contains("UNIQUE INDEX IF NOT EXISTS audit_log_prev_hash"));
+        assert!(AUDIT_LOG_SCHEMA_SQL.contains("BEFORE UPDATE OR DELETE ON audit_log"));
+        assert!(NOTIFICATIONS_SCHEMA_SQL.contains("read_at TIMESTAMPTZ"));
+        assert!(USER_PREFERENCES_SCHEMA_SQL.contains("preferences JSONB"));
     }
 
     #[test]
@@ -61,4 +342,16 @@ mod tests {
         assert!(DB_CONNECTION_STRING.contains("user="));
         assert!(DB_CONNECTION_STRING.contains("dbname="));
     }
+
+    #[test]
+    fn test_pending_migrations_are_sorted_and_skip_applied() {
+        let files: Vec<String> = ["002_b.sql", "README.md", "001_a.sql", "003_c.sql"]
+            .iter()
+            .map(|file| file.to_string())
+            .collect();
+        let applied = HashSet::from(["001_a.sql".to_string()]);
+
+        assert_eq!(pending_migrations(&files, &applied), vec!["002_b.sql", "003_c.sql"]);
+        assert_eq!(pending_migrations(&files, &HashSet::new()).len(), 3);
+    }
 }
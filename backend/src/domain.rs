@@ -0,0 +1,114 @@
+use std::fmt;
+
+// Domain errors - Dependency Inversion Principle
+// Failures of the repositories (users, preferences, webhooks, jobs,
+// notifications, audit log, verification tokens) and the services built
+// on them, free of any web framework so the data access and business
+// layers work the same from the REST API, the gRPC server and the admin
+// CLI. Only errors.rs decides which HTTP status a failure becomes
+
+/// Failure of a repository call
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepositoryError {
+    /// No row has the given key
+    NotFound(String),
+    /// A unique constraint rejected the write
+    Conflict(String),
+    /// The store cannot run this operation in its current configuration
+    Unsupported(String),
+    /// The database failed or returned data that cannot be read
+    Storage(String),
+    /// The database did not answer before the query timeout
+    Timeout(String),
+}
+
+impl RepositoryError {
+    pub fn message(&self) -> &str {
+        match self {
+            RepositoryError::NotFound(message)
+            | RepositoryError::Conflict(message)
+            | RepositoryError::Unsupported(message)
+            | RepositoryError::Storage(message)
+            | RepositoryError::Timeout(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for RepositoryError {}
+
+/// Failure of a business operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainError {
+    /// The input breaks a business rule
+    Invalid(String),
+    NotFound(String),
+    /// The operation clashes with the current state, e.g. a taken email
+    Conflict(String),
+    /// Storage or another dependency failed
+    Internal(String),
+    /// Storage did not answer in time
+    Timeout(String),
+}
+
+impl DomainError {
+    pub fn message(&self) -> &str {
+        match self {
+            DomainError::Invalid(message)
+            | DomainError::NotFound(message)
+            | DomainError::Conflict(message)
+            | DomainError::Internal(message)
+            | DomainError::Timeout(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for DomainError {}
+
+impl From<RepositoryError> for DomainError {
+    fn from(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::NotFound(message) => DomainError::NotFound(message),
+            RepositoryError::Conflict(message) => DomainError::Conflict(message),
+            RepositoryError::Unsupported(message) => DomainError::Invalid(message),
+            RepositoryError::Storage(message) => DomainError::Internal(message),
+            RepositoryError::Timeout(message) => DomainError::Timeout(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_errors_become_domain_errors() {
+        let cases = [
+            (RepositoryError::NotFound("gone".to_string()), DomainError::NotFound("gone".to_string())),
+            (RepositoryError::Conflict("taken".to_string()), DomainError::Conflict("taken".to_string())),
+            (RepositoryError::Unsupported("encrypted".to_string()), DomainError::Invalid("encrypted".to_string())),
+            (RepositoryError::Storage("down".to_string()), DomainError::Internal("down".to_string())),
+            (RepositoryError::Timeout("slow".to_string()), DomainError::Timeout("slow".to_string())),
+        ];
+        for (repository, domain) in cases {
+            assert_eq!(DomainError::from(repository), domain);
+        }
+    }
+
+    #[test]
+    fn test_display_is_the_message() {
+        assert_eq!(DomainError::Conflict("taken".to_string()).to_string(), "taken");
+        assert_eq!(RepositoryError::Storage("down".to_string()).to_string(), "down");
+    }
+}
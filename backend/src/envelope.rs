@@ -0,0 +1,258 @@
+use crate::pagination::{Pagination, TOTAL_COUNT_HEADER};
+use crate::request_id::RequestId;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Status;
+use rocket::serde::Serialize;
+use rocket::{Request, Response};
+use serde_json::Value;
+use shared::routes;
+use std::io::Cursor;
+
+// Response envelope - Open/Closed Principle
+// Wraps every JSON body of the API in `{ "data", "meta", "errors" }` for
+// tooling that expects one shape for all responses. Handlers are unchanged:
+// the fairing rewrites their bodies, either for every client (`API_ENVELOPE`)
+// or for those asking for it through the `Accept` header. Other formats, e.g.
+// MessagePack or CSV, empty bodies such as 304 answers, downloads and streamed
+// bodies such as the JSON export are left alone
+
+/// `Accept` media type asking for the envelope when it is not always on
+pub const ENVELOPE_MEDIA_TYPE: (&str, &str) = ("application", "vnd.envelope+json");
+
+/// Pagination of a list response, from its `X-Total-Count` header and the
+/// `page`/`per_page` query parameters
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct PageMeta {
+    pub total: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct Meta {
+    pub request_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<PageMeta>,
+}
+
+/// A response body in the envelope; errors leave `data` null
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct Envelope {
+    pub data: Value,
+    pub meta: Meta,
+    pub errors: Vec<Value>,
+}
+
+impl Envelope {
+    pub fn new(status: Status, body: Value, meta: Meta) -> Self {
+        if status.code >= 400 {
+            Envelope { data: Value::Null, meta, errors: vec![body] }
+        } else {
+            Envelope { data: body, meta, errors: Vec::new() }
+        }
+    }
+}
+
+/// Whether the client's `Accept` header lists the envelope media type
+pub fn requests_envelope(request: &Request<'_>) -> bool {
+    let (top, sub) = ENVELOPE_MEDIA_TYPE;
+    request.accept().is_some_and(|accept| {
+        accept
+            .media_types()
+            .any(|media_type| media_type.top() == top && media_type.sub() == sub)
+    })
+}
+
+fn is_api_path(request: &Request<'_>) -> bool {
+    let prefix = routes::LEGACY_API_PREFIX.trim_start_matches('/');
+    request.uri().path().segments().next() == Some(prefix)
+}
+
+/// Whether the body is a file download or streamed, so must not be buffered
+fn is_download(response: &Response<'_>) -> bool {
+    let attachment = response
+        .headers()
+        .get_one("Content-Disposition")
+        .is_some_and(|disposition| disposition.trim_start().starts_with("attachment"));
+    attachment || response.body().preset_size().is_none()
+}
+
+fn page_meta(request: &Request<'_>, response: &Response<'_>) -> Option<PageMeta> {
+    let total = response.headers().get_one(TOTAL_COUNT_HEADER)?.parse().ok()?;
+    let page = request.query_value::<u32>("page").and_then(Result::ok);
+    let per_page = request.query_value::<u32>("per_page").and_then(Result::ok);
+    // Echo the page actually served, after clamping
+    let served = (page.is_some() || per_page.is_some()).then(|| Pagination::from_query(page, per_page));
+    Some(PageMeta {
+        total,
+        page: served.map(|pagination| pagination.page),
+        per_page: served.map(|pagination| pagination.per_page),
+    })
+}
+
+/// Fairing wrapping JSON bodies of the API in the envelope
+pub struct EnvelopeFairing {
+    /// Wrap every response rather than only those asked for
+    pub always: bool,
+}
+
+#[rocket::async_trait]
+impl Fairing for EnvelopeFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Response envelope",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if !(self.always || requests_envelope(request)) || !is_api_path(request) {
+            return;
+        }
+        if !response.content_type().is_some_and(|content_type| content_type.is_json()) || is_download(response) {
+            return;
+        }
+        let Ok(body) = response.body_mut().to_bytes().await else {
+            return;
+        };
+        let Ok(value) = serde_json::from_slice::<Value>(&body) else {
+            // Not JSON after all; hand it on unchanged
+            response.set_sized_body(body.len(), Cursor::new(body));
+            return;
+        };
+
+        let meta = Meta {
+            request_id: RequestId::of(request),
+            pagination: page_meta(request, response),
+        };
+        let envelope = Envelope::new(response.status(), value, meta);
+        let body = serde_json::to_vec(&envelope).unwrap_or(body);
+        response.set_sized_body(body.len(), Cursor::new(body));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::AppError;
+    use crate::export::{Export, ExportFormat};
+    use crate::pagination::WithTotalCount;
+    use crate::request_id::RequestIdFairing;
+    use futures_util::stream::{self, Stream};
+    use rocket::http::{Accept, ContentType, Header, MediaType};
+    use rocket::local::blocking::Client;
+    use rocket::serde::json::Json;
+    use rocket::{Build, Rocket};
+
+    #[get("/users?<page>&<per_page>")]
+    fn users(page: Option<u32>, per_page: Option<u32>) -> WithTotalCount<Json<Vec<&'static str>>> {
+        let _ = (page, per_page);
+        WithTotalCount(42, Json(vec!["ada", "alan"]))
+    }
+
+    #[get("/users/<id>")]
+    fn user(id: i32) -> Result<Json<i32>, AppError> {
+        match id {
+            1 => Ok(Json(id)),
+            _ => Err(AppError::NotFound("User not found".to_string())),
+        }
+    }
+
+    #[get("/export")]
+    fn export() -> (ContentType, &'static str) {
+        (ContentType::CSV, "id,name\n")
+    }
+
+    #[get("/export.json")]
+    fn export_json() -> Export<impl Stream<Item = String>> {
+        let chunks = ["[", r#"{"id":1}"#, ",", r#"{"id":2}"#, "]"];
+        Export {
+            format: ExportFormat::Json,
+            body: stream::iter(chunks.map(str::to_string)),
+        }
+    }
+
+    fn rocket(always: bool) -> Rocket<Build> {
+        rocket::build()
+            .mount(routes::API_V1_PREFIX, routes![users, user, export, export_json])
+            .attach(RequestIdFairing)
+            .attach(EnvelopeFairing { always })
+    }
+
+    fn envelope_accept() -> Accept {
+        let (top, sub) = ENVELOPE_MEDIA_TYPE;
+        Accept::from(MediaType::new(top, sub))
+    }
+
+    #[test]
+    fn test_envelope_new() {
+        let meta = Meta { request_id: "r1".to_string(), pagination: None };
+        let envelope = Envelope::new(Status::Ok, serde_json::json!([1]), meta.clone());
+        assert_eq!(envelope.data, serde_json::json!([1]));
+        assert!(envelope.errors.is_empty());
+
+        let envelope = Envelope::new(Status::NotFound, serde_json::json!({"code": "not_found"}), meta);
+        assert_eq!(envelope.data, Value::Null);
+        assert_eq!(envelope.errors, vec![serde_json::json!({"code": "not_found"})]);
+    }
+
+    #[test]
+    fn test_bodies_are_unchanged_unless_asked_for() {
+        let client = Client::tracked(rocket(false)).expect("valid rocket instance");
+        let body = client.get("/api/v1/users").dispatch().into_string().unwrap();
+        assert_eq!(body, r#"["ada","alan"]"#);
+    }
+
+    #[test]
+    fn test_accept_header_asks_for_the_envelope() {
+        let client = Client::tracked(rocket(false)).expect("valid rocket instance");
+        let response = client
+            .get("/api/v1/users?page=2&per_page=1000")
+            .header(envelope_accept())
+            .header(Header::new("X-Request-Id", "r1"))
+            .dispatch();
+        let body: Value = response.into_json().unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "data": ["ada", "alan"],
+                "meta": {"request_id": "r1", "pagination": {"total": 42, "page": 2, "per_page": 100}},
+                "errors": []
+            })
+        );
+    }
+
+    #[test]
+    fn test_errors_go_in_the_envelope() {
+        let client = Client::tracked(rocket(true)).expect("valid rocket instance");
+        let response = client.get("/api/v1/users/9").header(Header::new("X-Request-Id", "r2")).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let body: Value = response.into_json().unwrap();
+        assert_eq!(body["data"], Value::Null);
+        assert_eq!(body["meta"], serde_json::json!({"request_id": "r2"}));
+        assert_eq!(body["errors"][0]["code"], "not_found");
+
+        let body: Value = client.get("/api/v1/users/1").dispatch().into_json().unwrap();
+        assert_eq!(body["data"], 1);
+    }
+
+    #[test]
+    fn test_other_formats_are_left_alone() {
+        let client = Client::tracked(rocket(true)).expect("valid rocket instance");
+        let body = client.get("/api/v1/export").dispatch().into_string().unwrap();
+        assert_eq!(body, "id,name\n");
+    }
+
+    #[test]
+    fn test_streamed_json_downloads_are_left_alone() {
+        let client = Client::tracked(rocket(true)).expect("valid rocket instance");
+        let response = client.get("/api/v1/export.json").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        assert_eq!(response.into_string().unwrap(), r#"[{"id":1},{"id":2}]"#);
+    }
+}
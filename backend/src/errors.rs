@@ -0,0 +1,385 @@
+use crate::body::BodyError;
+use crate::breach::BREACHED_PASSWORD_MESSAGE;
+use crate::domain::{DomainError, RepositoryError};
+use crate::i18n::LOCALIZER;
+use crate::maintenance::{MaintenanceMode, DEFAULT_MAINTENANCE_MESSAGE};
+use crate::request_id::RequestId;
+use crate::security::{AdminRefusal, ADMIN_TOKEN_MESSAGE};
+use rocket::http::{Header, Status};
+use rocket::response::status::Custom;
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{Catcher, Request};
+use std::sync::Arc;
+
+// Error handling - Single Responsibility Principle
+// Every error leaving the API (handler failures and Rocket catchers alike)
+// is rendered as the same JSON body so clients can always parse it.
+// `code` is stable; `message` is translated to the Accept-Language of the request
+
+/// JSON body returned for every API error
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct ErrorResponse {
+    pub code: String,
+    pub message: String,
+    pub request_id: String,
+    /// Offending field of an invalid request body, e.g. `email` or `tags[1]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Type the invalid field was expected to have
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+/// A request body field that could not be deserialized
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub expected: Option<String>,
+    pub message: String,
+}
+
+/// Application error - carries the HTTP semantics of a failure
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    PayloadTooLarge(String),
+    UnprocessableEntity(String),
+    InvalidField(FieldError),
+    TooManyRequests(String),
+    Internal(String),
+    ServiceUnavailable(String),
+    /// A dependency such as the database did not answer in time
+    Timeout(String),
+}
+
+impl AppError {
+    pub fn status(&self) -> Status {
+        match self {
+            AppError::BadRequest(_) => Status::BadRequest,
+            AppError::Unauthorized(_) => Status::Unauthorized,
+            AppError::Forbidden(_) => Status::Forbidden,
+            AppError::NotFound(_) => Status::NotFound,
+            AppError::Conflict(_) => Status::Conflict,
+            AppError::PayloadTooLarge(_) => Status::PayloadTooLarge,
+            AppError::UnprocessableEntity(_) | AppError::InvalidField(_) => {
+                Status::UnprocessableEntity
+            }
+            AppError::TooManyRequests(_) => Status::TooManyRequests,
+            AppError::Internal(_) => Status::InternalServerError,
+            AppError::ServiceUnavailable(_) => Status::ServiceUnavailable,
+            AppError::Timeout(_) => Status::GatewayTimeout,
+        }
+    }
+
+    /// Stable, machine readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::UnprocessableEntity(_) | AppError::InvalidField(_) => "unprocessable_entity",
+            AppError::TooManyRequests(_) => "too_many_requests",
+            AppError::Internal(_) => "internal_error",
+            AppError::ServiceUnavailable(_) => "service_unavailable",
+            AppError::Timeout(_) => "timeout",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::BadRequest(message)
+            | AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::PayloadTooLarge(message)
+            | AppError::UnprocessableEntity(message)
+            | AppError::TooManyRequests(message)
+            | AppError::Internal(message)
+            | AppError::ServiceUnavailable(message)
+            | AppError::Timeout(message) => message,
+            AppError::InvalidField(error) => &error.message,
+        }
+    }
+}
+
+impl From<Custom<String>> for AppError {
+    fn from(error: Custom<String>) -> Self {
+        let Custom(status, message) = error;
+        match status.code {
+            400 => AppError::BadRequest(message),
+            401 => AppError::Unauthorized(message),
+            403 => AppError::Forbidden(message),
+            404 => AppError::NotFound(message),
+            409 => AppError::Conflict(message),
+            413 => AppError::PayloadTooLarge(message),
+            422 => AppError::UnprocessableEntity(message),
+            429 => AppError::TooManyRequests(message),
+            504 => AppError::Timeout(message),
+            _ => AppError::Internal(message),
+        }
+    }
+}
+
+/// The only place domain failures get an HTTP status
+impl From<DomainError> for AppError {
+    fn from(error: DomainError) -> Self {
+        match error {
+            DomainError::Invalid(message) => AppError::BadRequest(message),
+            DomainError::NotFound(message) => AppError::NotFound(message),
+            DomainError::Conflict(message) => AppError::Conflict(message),
+            DomainError::Internal(message) => AppError::Internal(message),
+            DomainError::Timeout(message) => AppError::Timeout(message),
+        }
+    }
+}
+
+impl From<RepositoryError> for AppError {
+    fn from(error: RepositoryError) -> Self {
+        DomainError::from(error).into()
+    }
+}
+
+/// User field a validation message is about, so clients can show it next to the input
+pub fn validation_field(message: &str) -> Option<&'static str> {
+    use shared::password::PasswordError;
+    use shared::validation::{AddressError, EmailError, PhoneError, TagError, UsernameError};
+
+    let is_any = |errors: &[String]| errors.iter().any(|error| error == message);
+    if message == "Name cannot be empty" {
+        Some("name")
+    } else if is_any(&[EmailError::Empty.to_string(), EmailError::InvalidFormat.to_string()])
+        || (message.starts_with("A user with email ") && message.ends_with(" already exists"))
+    {
+        Some("email")
+    } else if is_any(&[PasswordError::Empty.to_string(), PasswordError::TooShort.to_string()])
+        || message == BREACHED_PASSWORD_MESSAGE
+        || (message.starts_with("Password must differ from the last ") && message.ends_with(" passwords"))
+    {
+        Some("password")
+    } else if is_any(&[
+        UsernameError::TooShort.to_string(),
+        UsernameError::TooLong.to_string(),
+        UsernameError::InvalidFormat.to_string(),
+        "Username cannot be changed".to_string(),
+    ]) || (message.starts_with("Username ") && message.ends_with(" is already taken"))
+    {
+        Some("username")
+    } else if is_any(&[
+        TagError::Empty.to_string(),
+        TagError::TooLong.to_string(),
+        TagError::InvalidFormat.to_string(),
+        TagError::TooMany.to_string(),
+    ]) {
+        Some("tags")
+    } else if message == "A bulk update needs at least one id"
+        || message == "An export needs at least one id"
+        || (message.starts_with("A bulk update can change at most ") && message.ends_with(" users"))
+        || (message.starts_with("An export can include at most ") && message.ends_with(" users"))
+    {
+        Some("ids")
+    } else if message == PhoneError::InvalidFormat.to_string() {
+        Some("phone")
+    } else if is_any(&[
+        AddressError::StreetEmpty.to_string(),
+        AddressError::CityEmpty.to_string(),
+        AddressError::InvalidPostalCode.to_string(),
+        AddressError::InvalidCountry.to_string(),
+        AddressError::TooLong.to_string(),
+    ]) {
+        Some("address")
+    } else {
+        None
+    }
+}
+
+impl<'r> Responder<'r, 'static> for AppError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let (field, expected) = match &self {
+            AppError::InvalidField(error) => (Some(error.field.clone()), error.expected.clone()),
+            AppError::BadRequest(message) | AppError::Conflict(message) => {
+                (validation_field(message).map(str::to_string), None)
+            }
+            _ => (None, None),
+        };
+        let message = match &self {
+            AppError::InvalidField(error) => {
+                format!("Invalid value for field '{}': {}", error.field, error.message)
+            }
+            _ => self.message().to_string(),
+        };
+        let language = LOCALIZER.negotiate(request.headers().get_one("Accept-Language"));
+        let body = ErrorResponse {
+            code: self.code().to_string(),
+            message: LOCALIZER.translate(&message, language),
+            request_id: RequestId::of(request),
+            field,
+            expected,
+        };
+        let mut response = Custom(self.status(), Json(body)).respond_to(request)?;
+        response.set_header(Header::new("Content-Language", language.to_string()));
+        Ok(response)
+    }
+}
+
+// Catchers - replace Rocket's default HTML error pages with JSON
+
+#[catch(400)]
+pub fn bad_request(request: &Request<'_>) -> AppError {
+    BodyError::of(request)
+        .unwrap_or_else(|| AppError::BadRequest("The request could not be understood".to_string()))
+}
+
+#[catch(401)]
+pub fn unauthorized(request: &Request<'_>) -> AppError {
+    AdminRefusal::of(request).unwrap_or_else(|| AppError::Unauthorized(ADMIN_TOKEN_MESSAGE.to_string()))
+}
+
+#[catch(403)]
+pub fn forbidden(request: &Request<'_>) -> AppError {
+    AdminRefusal::of(request).unwrap_or_else(|| AppError::Forbidden("Missing or invalid CSRF token".to_string()))
+}
+
+#[catch(404)]
+pub fn not_found(request: &Request<'_>) -> AppError {
+    AppError::NotFound(format!(
+        "No route matches {} {}",
+        request.method(),
+        request.uri()
+    ))
+}
+
+#[catch(413)]
+pub fn payload_too_large(request: &Request<'_>) -> AppError {
+    BodyError::of(request)
+        .unwrap_or_else(|| AppError::PayloadTooLarge("The request body is too large".to_string()))
+}
+
+#[catch(422)]
+pub fn unprocessable_entity(request: &Request<'_>) -> AppError {
+    BodyError::of(request).unwrap_or_else(|| {
+        AppError::UnprocessableEntity("The request body is malformed".to_string())
+    })
+}
+
+#[catch(429)]
+pub fn too_many_requests() -> AppError {
+    AppError::TooManyRequests("Too many requests, try again later".to_string())
+}
+
+#[catch(500)]
+pub fn internal_error() -> AppError {
+    AppError::Internal("Internal server error".to_string())
+}
+
+#[catch(503)]
+pub fn service_unavailable(request: &Request<'_>) -> AppError {
+    let message = request
+        .rocket()
+        .state::<Arc<MaintenanceMode>>()
+        .and_then(|mode| mode.refusal())
+        .unwrap_or_else(|| DEFAULT_MAINTENANCE_MESSAGE.to_string());
+    AppError::ServiceUnavailable(message)
+}
+
+/// All JSON catchers, ready to be registered on the Rocket instance
+pub fn catchers() -> Vec<Catcher> {
+    catchers![
+        bad_request,
+        unauthorized,
+        forbidden,
+        not_found,
+        payload_too_large,
+        unprocessable_entity,
+        too_many_requests,
+        internal_error,
+        service_unavailable
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_and_code_mapping() {
+        let error = AppError::NotFound("missing".to_string());
+        assert_eq!(error.status(), Status::NotFound);
+        assert_eq!(error.code(), "not_found");
+        assert_eq!(error.message(), "missing");
+    }
+
+    #[test]
+    fn test_from_custom_keeps_known_statuses() {
+        let error = AppError::from(Custom(Status::BadRequest, "Name cannot be empty".to_string()));
+        assert_eq!(error, AppError::BadRequest("Name cannot be empty".to_string()));
+
+        let error = AppError::from(Custom(Status::Conflict, "duplicate".to_string()));
+        assert_eq!(error.status(), Status::Conflict);
+
+        let error = AppError::from(Custom(Status::TooManyRequests, "slow down".to_string()));
+        assert_eq!(error.code(), "too_many_requests");
+    }
+
+    #[test]
+    fn test_from_custom_defaults_to_internal() {
+        let error = AppError::from(Custom(Status::ServiceUnavailable, "down".to_string()));
+        assert_eq!(error, AppError::Internal("down".to_string()));
+    }
+
+    #[test]
+    fn test_domain_errors_map_to_statuses() {
+        let cases = [
+            (DomainError::Invalid("bad".to_string()), Status::BadRequest),
+            (DomainError::NotFound("gone".to_string()), Status::NotFound),
+            (DomainError::Conflict("taken".to_string()), Status::Conflict),
+            (DomainError::Internal("down".to_string()), Status::InternalServerError),
+            (DomainError::Timeout("slow".to_string()), Status::GatewayTimeout),
+        ];
+        for (error, status) in cases {
+            let message = error.message().to_string();
+            let error = AppError::from(error);
+            assert_eq!(error.status(), status);
+            assert_eq!(error.message(), message);
+        }
+        let error = AppError::from(RepositoryError::Unsupported("encrypted".to_string()));
+        assert_eq!(error, AppError::BadRequest("encrypted".to_string()));
+    }
+
+    #[test]
+    fn test_validation_field_of_user_messages() {
+        assert_eq!(validation_field("Name cannot be empty"), Some("name"));
+        assert_eq!(validation_field("Invalid email format"), Some("email"));
+        assert_eq!(
+            validation_field("A user with email a@x.com already exists"),
+            Some("email")
+        );
+        assert_eq!(
+            validation_field(&shared::password::PasswordError::TooShort.to_string()),
+            Some("password")
+        );
+        assert_eq!(
+            validation_field(&shared::validation::PhoneError::InvalidFormat.to_string()),
+            Some("phone")
+        );
+        assert_eq!(validation_field("City cannot be empty"), Some("address"));
+        assert_eq!(validation_field("Username ana is already taken"), Some("username"));
+        assert_eq!(validation_field("Username cannot be changed"), Some("username"));
+        assert_eq!(validation_field("Password must differ from the last 5 passwords"), Some("password"));
+        assert_eq!(validation_field("Password has appeared in a data breach"), Some("password"));
+        assert_eq!(validation_field("A user can have at most 10 tags"), Some("tags"));
+        assert_eq!(validation_field("A bulk update can change at most 100 users"), Some("ids"));
+        assert_eq!(validation_field("An export can include at most 1000 users"), Some("ids"));
+        assert_eq!(validation_field("Search query cannot be empty"), None);
+    }
+}
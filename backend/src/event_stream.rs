@@ -0,0 +1,428 @@
+use crate::config::{EventBroker, EventFormat, EventStreamConfig, RetryConfig};
+use crate::events::{EventSubscriber, UserEvent};
+use crate::models::{Address, UserResponse};
+use crate::query_retry::jitter;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use fake::rand::Rng;
+use prost::Message;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Event streaming - Dependency Inversion Principle
+// Downstream services learn about user changes from a message broker. The
+// EventStreamSubscriber takes the created, updated, deleted and anonymized
+// events off the EventBus (the last so downstream copies of personal data
+// get scrubbed too), encodes them as JSON or protobuf and hands them to an
+// EventPublisher: Kafka, written through a Kafka REST Proxy so no native
+// client library is needed, or NATS. Messages are keyed by user id, which
+// keeps the changes of a user in order on a Kafka partition. A message the
+// broker did not accept is published again with jittered backoff before the
+// next one, so outages of a few seconds lose nothing. One still failing after
+// the last retry is logged and dropped; there is no outbox to replay it
+
+pub mod proto {
+    tonic::include_proto!("userevents");
+}
+
+const KAFKA_TIMEOUT: Duration = Duration::from_secs(10);
+const KAFKA_CONTENT_TYPE: &str = "application/vnd.kafka.binary.v2+json";
+
+/// Events published to the broker
+pub const STREAMED_EVENTS: [&str; 4] = ["user.created", "user.updated", "user.deleted", "user.anonymized"];
+
+/// An encoded user event, ready for the broker
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventMessage {
+    /// Event name, e.g. `user.created`
+    pub name: &'static str,
+    /// Id of the user, as partition key
+    pub key: String,
+    pub content_type: &'static str,
+    pub payload: Vec<u8>,
+}
+
+impl EventMessage {
+    /// Encode `event` as `format` says
+    pub fn encode(event: &UserEvent, format: EventFormat) -> Self {
+        let (content_type, payload) = match format {
+            EventFormat::Json => (
+                "application/json",
+                serde_json::to_vec(event).expect("user events serialize to JSON"),
+            ),
+            EventFormat::Protobuf => ("application/x-protobuf", to_proto(event).encode_to_vec()),
+        };
+        EventMessage {
+            name: event.name(),
+            key: event.user_id().to_string(),
+            content_type,
+            payload,
+        }
+    }
+}
+
+fn to_proto(event: &UserEvent) -> proto::UserEvent {
+    let user = match event {
+        UserEvent::Created { user } | UserEvent::Updated { user } => Some(user_to_proto(user)),
+        _ => None,
+    };
+    proto::UserEvent {
+        r#type: event.name().to_string(),
+        user_id: event.user_id(),
+        user,
+    }
+}
+
+fn user_to_proto(user: &UserResponse) -> proto::User {
+    proto::User {
+        id: user.id,
+        name: user.name.clone(),
+        email: user.email.clone(),
+        username: user.username.clone(),
+        status: user.status.as_str().to_string(),
+        tags: user.tags.clone(),
+        last_login_at: user.last_login_at.clone(),
+        phone: user.phone.clone(),
+        address: user.address.as_ref().map(address_to_proto),
+    }
+}
+
+fn address_to_proto(address: &Address) -> proto::Address {
+    proto::Address {
+        street: address.street.clone(),
+        complement: address.complement.clone(),
+        city: address.city.clone(),
+        region: address.region.clone(),
+        postal_code: address.postal_code.clone(),
+        country: address.country.clone(),
+    }
+}
+
+/// Sends encoded user events to a message broker
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn publish(&self, message: &EventMessage) -> Result<(), String>;
+}
+
+/// Produces to a Kafka topic through the REST Proxy's v2 API
+pub struct KafkaRestPublisher {
+    client: reqwest::Client,
+    /// `<rest url>/topics/<topic>`
+    topic_url: String,
+}
+
+impl KafkaRestPublisher {
+    pub fn new(rest_url: &str, topic: &str) -> Result<Self, String> {
+        if topic.trim().is_empty() {
+            return Err("KAFKA_TOPIC cannot be empty".to_string());
+        }
+        let client = reqwest::Client::builder()
+            .timeout(KAFKA_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(KafkaRestPublisher {
+            client,
+            topic_url: format!("{}/topics/{}", rest_url.trim_end_matches('/'), topic),
+        })
+    }
+}
+
+/// Body producing `message` as one record; the binary embedded format
+/// carries JSON and protobuf payloads alike
+pub fn kafka_records(message: &EventMessage) -> serde_json::Value {
+    json!({
+        "records": [{
+            "key": BASE64.encode(&message.key),
+            "value": BASE64.encode(&message.payload),
+        }]
+    })
+}
+
+#[async_trait]
+impl EventPublisher for KafkaRestPublisher {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn publish(&self, message: &EventMessage) -> Result<(), String> {
+        self.client
+            .post(&self.topic_url)
+            .header(reqwest::header::CONTENT_TYPE, KAFKA_CONTENT_TYPE)
+            .body(kafka_records(message).to_string())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Publishes on a NATS subject per event, e.g. `user.created`
+pub struct NatsPublisher {
+    client: async_nats::Client,
+    subject_prefix: Option<String>,
+}
+
+impl NatsPublisher {
+    /// Connect to `url`; while the server is unreachable the client keeps
+    /// reconnecting in the background instead of failing the startup
+    pub async fn connect(url: &str, subject_prefix: Option<String>) -> Result<Self, String> {
+        let client = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect(url)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(NatsPublisher { client, subject_prefix })
+    }
+}
+
+/// Subject of the event named `name`
+pub fn nats_subject(prefix: Option<&str>, name: &str) -> String {
+    match prefix {
+        Some(prefix) => format!("{}.{}", prefix.trim_end_matches('.'), name),
+        None => name.to_string(),
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NatsPublisher {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn publish(&self, message: &EventMessage) -> Result<(), String> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Content-Type", message.content_type);
+        headers.insert("User-Id", message.key.as_str());
+        self.client
+            .publish_with_headers(
+                nats_subject(self.subject_prefix.as_deref(), message.name),
+                headers,
+                message.payload.clone().into(),
+            )
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Publishes the streamed events of the bus
+pub struct EventStreamSubscriber {
+    publisher: Arc<dyn EventPublisher>,
+    format: EventFormat,
+    retry: RetryConfig,
+}
+
+impl EventStreamSubscriber {
+    /// Publish events as `format`, retrying failed ones as `retry` says
+    pub fn new(publisher: Arc<dyn EventPublisher>, format: EventFormat, retry: RetryConfig) -> Self {
+        EventStreamSubscriber { publisher, format, retry }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for EventStreamSubscriber {
+    fn name(&self) -> &'static str {
+        "event-stream"
+    }
+
+    async fn handle(&self, event: &UserEvent) {
+        if !STREAMED_EVENTS.contains(&event.name()) {
+            return;
+        }
+        let message = EventMessage::encode(event, self.format);
+        let mut attempt = 0;
+        loop {
+            match self.publisher.publish(&message).await {
+                Ok(()) => return,
+                Err(e) if attempt < self.retry.max_retries => {
+                    let delay = jitter(self.retry.delay_for_attempt(attempt), fake::rand::rng().random());
+                    attempt += 1;
+                    eprintln!(
+                        "{} for user {} not published to {} ({}), retrying in {:?} (attempt {}/{})",
+                        message.name,
+                        message.key,
+                        self.publisher.name(),
+                        e,
+                        delay,
+                        attempt,
+                        self.retry.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} for user {} dropped after {} attempts to publish to {}: {}",
+                        message.name,
+                        message.key,
+                        attempt + 1,
+                        self.publisher.name(),
+                        e
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The publisher of the configured broker; None when events are not published
+pub async fn build_event_publisher(config: &EventStreamConfig) -> Result<Option<Arc<dyn EventPublisher>>, String> {
+    Ok(match config.broker {
+        EventBroker::None => None,
+        EventBroker::Kafka => Some(Arc::new(KafkaRestPublisher::new(&config.kafka_rest_url, &config.kafka_topic)?)),
+        EventBroker::Nats => Some(Arc::new(
+            NatsPublisher::connect(config.nats_url.expose(), config.nats_subject_prefix.clone()).await?,
+        )),
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::models::UserStatus;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    /// Records the messages it publishes
+    #[derive(Default)]
+    pub struct MockEventPublisher {
+        pub messages: Mutex<Vec<EventMessage>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        async fn publish(&self, message: &EventMessage) -> Result<(), String> {
+            self.messages.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    /// Fails its first `failures` messages, then records them like MockEventPublisher
+    pub struct FailingEventPublisher {
+        failures: u32,
+        attempts: AtomicU32,
+        pub messages: Mutex<Vec<EventMessage>>,
+    }
+
+    impl FailingEventPublisher {
+        pub fn new(failures: u32) -> Self {
+            FailingEventPublisher {
+                failures,
+                attempts: AtomicU32::new(0),
+                messages: Mutex::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EventPublisher for FailingEventPublisher {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn publish(&self, message: &EventMessage) -> Result<(), String> {
+            if self.attempts.fetch_add(1, Ordering::Relaxed) < self.failures {
+                return Err("broker unavailable".to_string());
+            }
+            self.messages.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    fn retry(max_retries: u32) -> RetryConfig {
+        RetryConfig {
+            max_retries,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        }
+    }
+
+    fn created() -> UserEvent {
+        UserEvent::Created {
+            user: UserResponse {
+                id: 7,
+                name: "Ada".to_string(),
+                email: "ada@example.com".to_string(),
+                username: Some("ada".to_string()),
+                status: UserStatus::Active,
+                tags: vec!["vip".to_string()],
+                last_login_at: None,
+                created_at: None,
+                updated_at: None,
+                phone: None,
+                address: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_events_encode_as_json_or_protobuf() {
+        let message = EventMessage::encode(&created(), EventFormat::Json);
+        assert_eq!((message.name, message.key.as_str()), ("user.created", "7"));
+        let json: serde_json::Value = serde_json::from_slice(&message.payload).unwrap();
+        assert_eq!(json["type"], "created");
+        assert_eq!(json["user"]["email"], "ada@example.com");
+
+        let message = EventMessage::encode(&created(), EventFormat::Protobuf);
+        assert_eq!(message.content_type, "application/x-protobuf");
+        let decoded = proto::UserEvent::decode(message.payload.as_slice()).unwrap();
+        assert_eq!((decoded.r#type.as_str(), decoded.user_id), ("user.created", 7));
+        let user = decoded.user.unwrap();
+        assert_eq!((user.username.as_deref(), user.status.as_str()), (Some("ada"), "active"));
+
+        let message = EventMessage::encode(&UserEvent::Deleted { id: 7 }, EventFormat::Protobuf);
+        assert_eq!(proto::UserEvent::decode(message.payload.as_slice()).unwrap().user, None);
+    }
+
+    #[test]
+    fn test_kafka_records_and_nats_subjects() {
+        let message = EventMessage::encode(&UserEvent::Deleted { id: 7 }, EventFormat::Json);
+        assert_eq!(
+            kafka_records(&message),
+            json!({"records": [{"key": "Nw==", "value": BASE64.encode(r#"{"type":"deleted","id":7}"#)}]})
+        );
+        assert_eq!(nats_subject(None, "user.deleted"), "user.deleted");
+        assert_eq!(nats_subject(Some("app."), "user.deleted"), "app.user.deleted");
+        assert!(KafkaRestPublisher::new("http://localhost:8082", " ").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_publishes_only_streamed_events() {
+        let publisher = Arc::new(MockEventPublisher::default());
+        let subscriber = EventStreamSubscriber::new(publisher.clone(), EventFormat::Json, retry(0));
+        subscriber.handle(&created()).await;
+        subscriber.handle(&UserEvent::PasswordChanged { id: 7 }).await;
+        subscriber.handle(&UserEvent::Deleted { id: 7 }).await;
+
+        let names: Vec<&str> = publisher.messages.lock().unwrap().iter().map(|message| message.name).collect();
+        assert_eq!(names, ["user.created", "user.deleted"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_events_are_published_again() {
+        let publisher = Arc::new(FailingEventPublisher::new(2));
+        let subscriber = EventStreamSubscriber::new(publisher.clone(), EventFormat::Json, retry(3));
+        subscriber.handle(&UserEvent::Anonymized { id: 7 }).await;
+
+        assert_eq!(publisher.attempts.load(Ordering::Relaxed), 3);
+        let names: Vec<&str> = publisher.messages.lock().unwrap().iter().map(|message| message.name).collect();
+        assert_eq!(names, ["user.anonymized"]);
+    }
+
+    #[tokio::test]
+    async fn test_events_are_dropped_after_the_last_retry() {
+        let publisher = Arc::new(FailingEventPublisher::new(u32::MAX));
+        let subscriber = EventStreamSubscriber::new(publisher.clone(), EventFormat::Json, retry(2));
+        subscriber.handle(&UserEvent::Deleted { id: 7 }).await;
+
+        assert_eq!(publisher.attempts.load(Ordering::Relaxed), 3);
+        assert!(publisher.messages.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::audit::ActivityRepository;
+use crate::models::UserResponse;
+use async_trait::async_trait;
+use rocket::serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+// Domain events - Open/Closed Principle
+// The service layer publishes what happened; side effects (audit log,
+// SSE, webhooks, ...) subscribe to the bus instead of living in CRUD code
+
+const DEFAULT_CAPACITY: usize = 256;
+
+/// Something that happened to a user
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(crate = "rocket::serde", tag = "type", rename_all = "snake_case")]
+pub enum UserEvent {
+    Created { user: UserResponse },
+    Updated { user: UserResponse },
+    Deleted { id: i32 },
+    /// Personal data was scrubbed; carries no PII on purpose
+    Anonymized { id: i32 },
+    /// Published after the Updated event of an update that set a new password
+    PasswordChanged { id: i32 },
+}
+
+/// Every event name, e.g. for validating webhook subscriptions
+pub const EVENT_NAMES: [&str; 5] = [
+    "user.created",
+    "user.updated",
+    "user.deleted",
+    "user.anonymized",
+    "user.password_changed",
+];
+
+impl UserEvent {
+    /// Stable event name, e.g. `user.created`
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserEvent::Created { .. } => "user.created",
+            UserEvent::Updated { .. } => "user.updated",
+            UserEvent::Deleted { .. } => "user.deleted",
+            UserEvent::Anonymized { .. } => "user.anonymized",
+            UserEvent::PasswordChanged { .. } => "user.password_changed",
+        }
+    }
+
+    pub fn user_id(&self) -> i32 {
+        match self {
+            UserEvent::Created { user } | UserEvent::Updated { user } => user.id,
+            UserEvent::Deleted { id }
+            | UserEvent::Anonymized { id }
+            | UserEvent::PasswordChanged { id } => *id,
+        }
+    }
+}
+
+/// In-process broadcast channel for user events
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<UserEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        EventBus { sender }
+    }
+
+    /// Publish an event; having no subscribers is not an error
+    pub fn publish(&self, event: UserEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<UserEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Run a subscriber on its own task until the bus is dropped
+    pub fn spawn_subscriber(&self, subscriber: Arc<dyn EventSubscriber>) -> JoinHandle<()> {
+        let mut receiver = self.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => subscriber.handle(&event).await,
+                    Err(RecvError::Lagged(skipped)) => eprintln!(
+                        "Event subscriber '{}' lagged behind, {} events skipped",
+                        subscriber.name(),
+                        skipped
+                    ),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// A pluggable consumer of user events
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn handle(&self, event: &UserEvent);
+}
+
+/// Writes every event to the application log and records it in the audit log
+pub struct AuditLogSubscriber {
+    repository: Arc<dyn ActivityRepository>,
+}
+
+impl AuditLogSubscriber {
+    pub fn new(repository: Arc<dyn ActivityRepository>) -> Self {
+        AuditLogSubscriber { repository }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for AuditLogSubscriber {
+    fn name(&self) -> &'static str {
+        "audit-log"
+    }
+
+    async fn handle(&self, event: &UserEvent) {
+        println!("[audit] {} user_id={}", event.name(), event.user_id());
+        if let Err(e) = self.repository.record(event.user_id(), event.name()).await {
+            eprintln!("Audit log entry for user {} not recorded: {}", event.user_id(), e);
+        }
+    }
+}
+
+/// Republishes every event on another bus
+pub struct ForwardingSubscriber {
+    target: EventBus,
+}
+
+impl ForwardingSubscriber {
+    pub fn new(target: EventBus) -> Self {
+        ForwardingSubscriber { target }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for ForwardingSubscriber {
+    fn name(&self) -> &'static str {
+        "forwarder"
+    }
+
+    async fn handle(&self, event: &UserEvent) {
+        self.target.publish(event.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct RecordingSubscriber {
+        events: Mutex<Vec<UserEvent>>,
+    }
+
+    #[async_trait]
+    impl EventSubscriber for RecordingSubscriber {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn handle(&self, event: &UserEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn test_event_names_and_serialization() {
+        let event = UserEvent::Deleted { id: 7 };
+        assert_eq!(event.name(), "user.deleted");
+        assert_eq!(event.user_id(), 7);
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"type":"deleted","id":7}"#);
+    }
+
+    #[tokio::test]
+    async fn test_publish_without_subscribers_is_ignored() {
+        let bus = EventBus::default();
+        bus.publish(UserEvent::Deleted { id: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_spawned_subscriber_receives_events() {
+        let bus = EventBus::default();
+        let subscriber = Arc::new(RecordingSubscriber {
+            events: Mutex::new(Vec::new()),
+        });
+        let handle = bus.spawn_subscriber(subscriber.clone());
+
+        bus.publish(UserEvent::Deleted { id: 1 });
+        bus.publish(UserEvent::Deleted { id: 2 });
+        drop(bus);
+        handle.await.unwrap();
+
+        let events = subscriber.events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![UserEvent::Deleted { id: 1 }, UserEvent::Deleted { id: 2 }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forwarding_subscriber_republishes_events() {
+        let target = EventBus::default();
+        let mut receiver = target.subscribe();
+
+        ForwardingSubscriber::new(target)
+            .handle(&UserEvent::Deleted { id: 3 })
+            .await;
+
+        assert_eq!(receiver.recv().await.unwrap(), UserEvent::Deleted { id: 3 });
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_subscriber_records_events() {
+        let repository = Arc::new(crate::audit::tests::MockActivityRepository::default());
+        let subscriber = AuditLogSubscriber::new(repository.clone());
+
+        subscriber.handle(&UserEvent::Deleted { id: 3 }).await;
+
+        let entries = repository.for_user(3, 10).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].event, "user.deleted");
+    }
+}
@@ -0,0 +1,242 @@
+use crate::domain::DomainError;
+use crate::models::{User, UserResponse};
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use rocket::Request;
+use rocket::http::{ContentType, Header};
+use rocket::response::stream::TextStream;
+use rocket::response::{self, Responder};
+use std::str::FromStr;
+
+// User export - Single Responsibility Principle
+// Turns a stream of users into CSV or JSON text chunks, one user at a time,
+// so exports of any size are sent with bounded memory. The status line is
+// already sent when rows are read, so a failure mid-export ends the body
+// early (the JSON array is left unterminated) and is logged
+
+pub const CSV_HEADER: &str = "id,name,email,status,last_login_at\n";
+
+/// Formats of `GET /users/export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(format!("Unknown export format '{}', expected csv or json", other)),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> ContentType {
+        match self {
+            ExportFormat::Csv => ContentType::CSV,
+            ExportFormat::Json => ContentType::JSON,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Quote a CSV field when needed. Values starting like a formula are
+/// prefixed with `'` so spreadsheets do not evaluate them
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+pub fn csv_row(user: &UserResponse) -> String {
+    format!(
+        "{},{},{},{},{}\n",
+        user.id,
+        csv_field(&user.name),
+        csv_field(&user.email),
+        user.status.as_str(),
+        user.last_login_at.as_deref().unwrap_or_default()
+    )
+}
+
+/// Text chunks of the export of `users` in `format`
+pub fn export_body(
+    format: ExportFormat,
+    users: BoxStream<'static, Result<User, DomainError>>,
+) -> impl Stream<Item = String> + Send + 'static {
+    let (header, footer) = match format {
+        ExportFormat::Csv => (CSV_HEADER, ""),
+        ExportFormat::Json => ("[", "]"),
+    };
+
+    // `None` marks the end of the users; the first error ends the body
+    // before the footer, so a cut-short export is never well-formed
+    let rows = users
+        .map(Some)
+        .chain(stream::once(std::future::ready(None)))
+        .scan(0usize, move |index, item| {
+            let chunk = match item {
+                Some(Ok(user)) => {
+                    let user = UserResponse::from(user);
+                    *index += 1;
+                    Some(match format {
+                        ExportFormat::Csv => csv_row(&user),
+                        ExportFormat::Json => {
+                            let separator = if *index == 1 { "" } else { "," };
+                            format!("{}{}", separator, serde_json::to_string(&user).unwrap_or_default())
+                        }
+                    })
+                }
+                Some(Err(e)) => {
+                    eprintln!("User export failed after {} users: {}", index, e);
+                    None
+                }
+                None => Some(footer.to_string()),
+            };
+            std::future::ready(chunk)
+        });
+
+    stream::once(std::future::ready(header.to_string())).chain(rows)
+}
+
+/// Streamed export sent as a file download
+pub struct Export<S> {
+    pub format: ExportFormat,
+    pub body: S,
+}
+
+impl<'r, S: Stream<Item = String> + Send + 'r> Responder<'r, 'r> for Export<S> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'r> {
+        let mut response = TextStream(self.body).respond_to(request)?;
+        response.set_header(self.format.content_type());
+        response.set_header(Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"users.{}\"", self.format.extension()),
+        ));
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserStatus;
+
+    fn user(id: i32, name: &str) -> User {
+        User::with_id(id, name.to_string(), format!("user{}@example.com", id), "password123".to_string())
+    }
+
+    async fn collect(format: ExportFormat, users: Vec<Result<User, DomainError>>) -> String {
+        export_body(format, stream::iter(users).boxed())
+            .collect::<Vec<String>>()
+            .await
+            .concat()
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("John Doe"), "John Doe");
+        assert_eq!(csv_field("Doe, John"), "\"Doe, John\"");
+        assert_eq!(csv_field("The \"Boss\""), "\"The \"\"Boss\"\"\"");
+        assert_eq!(csv_field("=SUM(A1:A2)"), "'=SUM(A1:A2)");
+    }
+
+    #[test]
+    fn test_csv_row() {
+        let mut user = UserResponse::from(user(1, "Doe, John"));
+        user.status = UserStatus::Suspended;
+        user.last_login_at = Some("2026-10-17T12:00:00Z".to_string());
+        assert_eq!(
+            csv_row(&user),
+            "1,\"Doe, John\",user1@example.com,suspended,2026-10-17T12:00:00Z\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_body_csv_and_json() {
+        let csv = collect(ExportFormat::Csv, vec![Ok(user(1, "A")), Ok(user(2, "B"))]).await;
+        assert_eq!(
+            csv,
+            format!("{}1,A,user1@example.com,active,\n2,B,user2@example.com,active,\n", CSV_HEADER)
+        );
+
+        let json = collect(ExportFormat::Json, vec![Ok(user(1, "A")), Ok(user(2, "B"))]).await;
+        let parsed: Vec<UserResponse> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(!json.contains("password"));
+
+        let empty = collect(ExportFormat::Json, vec![]).await;
+        assert_eq!(empty, "[]");
+    }
+
+    #[tokio::test]
+    async fn test_export_body_stops_at_first_error() {
+        let users = vec![
+            Ok(user(1, "A")),
+            Err(DomainError::Internal("connection lost".to_string())),
+            Ok(user(2, "B")),
+        ];
+        let json = collect(ExportFormat::Json, users).await;
+        assert!(json.starts_with('['));
+        assert!(!json.ends_with(']'));
+        assert!(!json.contains("user2"));
+    }
+
+    // Reads one RFC 4180 field back, as a spreadsheet would
+    fn read_csv_field(field: &str) -> String {
+        match field.strip_prefix('"').and_then(|field| field.strip_suffix('"')) {
+            Some(quoted) => quoted.replace("\"\"", "\""),
+            None => field.to_string(),
+        }
+    }
+
+    proptest::proptest! {
+        #![proptest_config(crate::test_support::property_config())]
+
+        #[test]
+        fn test_csv_field_properties(value in crate::test_support::arbitrary_text(12)) {
+            let field = csv_field(&value);
+            let read = read_csv_field(&field);
+            let expected = if value.starts_with(['=', '+', '-', '@']) {
+                format!("'{}", value)
+            } else {
+                value.clone()
+            };
+            assert_eq!(read, expected, "{:?} was written as {:?}", value, field);
+            // Separators and line breaks only ever appear inside quotes
+            if !field.starts_with('"') {
+                assert!(!field.contains([',', '"', '\n', '\r']), "{:?}", field);
+            }
+        }
+
+        #[test]
+        fn test_export_format_parsing_properties(value in crate::test_support::arbitrary_text(6)) {
+            let parsed = value.parse::<ExportFormat>();
+            assert_eq!(parsed.is_ok(), ["csv", "json"].contains(&value.to_ascii_lowercase().as_str()), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_export_format_extension_round_trip() {
+        for format in [ExportFormat::Csv, ExportFormat::Json] {
+            assert_eq!(format.extension().to_uppercase().parse::<ExportFormat>(), Ok(format));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::domain::DomainError;
+use crate::maintenance::MaintenanceMode;
+use crate::models::User;
+use crate::service::UserService;
+use shared::validation::normalize_email;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+// gRPC API - Interface Segregation Principle
+// Exposes the same UserService used by the REST handlers to internal
+// gRPC consumers; this module only translates between protobuf and domain types
+
+pub mod proto {
+    tonic::include_proto!("userapi");
+}
+
+use proto::user_api_server::{UserApi, UserApiServer};
+use proto::{
+    CreateUserRequest, DeleteUserReply, DeleteUserRequest, GetUserRequest, ListUsersReply,
+    ListUsersRequest, UpdateUserRequest, UserReply,
+};
+
+pub struct UserApiService {
+    service: Arc<UserService>,
+    maintenance: Option<Arc<MaintenanceMode>>,
+}
+
+impl UserApiService {
+    pub fn new(service: Arc<UserService>) -> Self {
+        UserApiService {
+            service,
+            maintenance: None,
+        }
+    }
+
+    /// Refuse writes while `maintenance` is on
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = Some(maintenance);
+        self
+    }
+
+    fn check_writable(&self) -> Result<(), Status> {
+        match self.maintenance.as_ref().and_then(|mode| mode.refusal()) {
+            Some(message) => Err(Status::unavailable(message)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<User> for UserReply {
+    fn from(user: User) -> Self {
+        UserReply {
+            id: user.id.unwrap_or_default(),
+            name: user.name,
+            email: user.email,
+            status: user.status.as_str().to_string(),
+        }
+    }
+}
+
+/// Map a service error to the closest gRPC status
+fn to_status(error: DomainError) -> Status {
+    match error {
+        DomainError::Invalid(message) => Status::invalid_argument(message),
+        DomainError::NotFound(message) => Status::not_found(message),
+        DomainError::Conflict(message) => Status::already_exists(message),
+        DomainError::Internal(message) => Status::internal(message),
+        DomainError::Timeout(message) => Status::deadline_exceeded(message),
+    }
+}
+
+#[tonic::async_trait]
+impl UserApi for UserApiService {
+    async fn create_user(
+        &self,
+        request: Request<CreateUserRequest>,
+    ) -> Result<Response<UserReply>, Status> {
+        self.check_writable()?;
+        let request = request.into_inner();
+        let email = normalize_email(&request.email);
+        let users = self
+            .service
+            .create_user(User::new(request.name, request.email, request.password))
+            .await
+            .map_err(to_status)?;
+
+        // Emails are unique, so the created user is the one with the submitted email
+        users
+            .into_iter()
+            .find(|user| user.email == email)
+            .map(|user| Response::new(user.into()))
+            .ok_or_else(|| Status::internal("Created user could not be read back"))
+    }
+
+    async fn get_user(
+        &self,
+        request: Request<GetUserRequest>,
+    ) -> Result<Response<UserReply>, Status> {
+        let user = self
+            .service
+            .get_user(request.into_inner().id)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(user.into()))
+    }
+
+    async fn list_users(
+        &self,
+        _request: Request<ListUsersRequest>,
+    ) -> Result<Response<ListUsersReply>, Status> {
+        let users = self.service.get_all_users().await.map_err(to_status)?;
+        Ok(Response::new(ListUsersReply {
+            users: users.into_iter().map(UserReply::from).collect(),
+        }))
+    }
+
+    async fn update_user(
+        &self,
+        request: Request<UpdateUserRequest>,
+    ) -> Result<Response<UserReply>, Status> {
+        self.check_writable()?;
+        let request = request.into_inner();
+        let id = request.id;
+        self.service
+            .update_user(id, User::new(request.name, request.email, request.password))
+            .await
+            .map_err(to_status)?;
+
+        let user = self.service.get_user(id).await.map_err(to_status)?;
+        Ok(Response::new(user.into()))
+    }
+
+    async fn delete_user(
+        &self,
+        request: Request<DeleteUserRequest>,
+    ) -> Result<Response<DeleteUserReply>, Status> {
+        self.check_writable()?;
+        self.service
+            .delete_user(request.into_inner().id)
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(DeleteUserReply {}))
+    }
+}
+
+/// Run the gRPC server on its own port until the process exits
+pub async fn serve(
+    address: SocketAddr,
+    service: Arc<UserService>,
+    maintenance: Arc<MaintenanceMode>,
+) -> Result<(), tonic::transport::Error> {
+    println!("gRPC server listening on {}", address);
+    let api = UserApiService::new(service).with_maintenance(maintenance);
+    tonic::transport::Server::builder()
+        .add_service(UserApiServer::new(api))
+        .serve(address)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventBus;
+    use crate::maintenance::MaintenanceStatus;
+    use crate::repository::tests::MockUserRepository;
+    use tonic::Code;
+
+    fn create_test_api() -> UserApiService {
+        let repo = Arc::new(MockUserRepository::new());
+        UserApiService::new(Arc::new(UserService::new(repo, EventBus::default())))
+    }
+
+    fn create_request(name: &str, email: &str) -> Request<CreateUserRequest> {
+        Request::new(CreateUserRequest {
+            name: name.to_string(),
+            email: email.to_string(),
+            password: "password123".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_user() {
+        let api = create_test_api();
+        let created = api
+            .create_user(create_request("John Doe", "John@Example.com"))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(created.id, 1);
+        assert_eq!(created.email, "john@example.com");
+
+        let fetched = api
+            .get_user(Request::new(GetUserRequest { id: 1 }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(fetched, created);
+    }
+
+    #[tokio::test]
+    async fn test_writes_are_refused_during_maintenance() {
+        let maintenance = Arc::new(MaintenanceMode::default());
+        let api = create_test_api().with_maintenance(maintenance.clone());
+        api.create_user(create_request("John Doe", "john@example.com")).await.unwrap();
+
+        maintenance.set(MaintenanceStatus {
+            maintenance: true,
+            message: None,
+        });
+        let status = api
+            .create_user(create_request("Jane Doe", "jane@example.com"))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unavailable);
+        let status = api.delete_user(Request::new(DeleteUserRequest { id: 1 })).await.unwrap_err();
+        assert_eq!(status.code(), Code::Unavailable);
+        // Reads keep working
+        api.get_user(Request::new(GetUserRequest { id: 1 })).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_invalid_user() {
+        let api = create_test_api();
+        let status = api
+            .create_user(create_request("", "john@example.com"))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(status.message(), "Name cannot be empty");
+    }
+
+    #[tokio::test]
+    async fn test_list_update_and_delete_users() {
+        let api = create_test_api();
+        api.create_user(create_request("John Doe", "john@example.com"))
+            .await
+            .unwrap();
+        api.create_user(create_request("Jane Doe", "jane@example.com"))
+            .await
+            .unwrap();
+
+        let list = api
+            .list_users(Request::new(ListUsersRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(list.users.len(), 2);
+
+        let updated = api
+            .update_user(Request::new(UpdateUserRequest {
+                id: 1,
+                name: "John Smith".to_string(),
+                email: "johnsmith@example.com".to_string(),
+                password: "newpassword123".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(updated.name, "John Smith");
+
+        api.delete_user(Request::new(DeleteUserRequest { id: 1 }))
+            .await
+            .unwrap();
+        let status = api
+            .get_user(Request::new(GetUserRequest { id: 1 }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+}
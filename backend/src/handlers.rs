@@ -1,71 +1,739 @@
-use crate::models::User;
+use crate::audit::{self, ActivityEntry, ActivityRepository, AuditTrailExport, AuditVerification};
+use crate::body::JsonBody;
+use crate::breach::{PasswordScreen, WithWarning};
+use crate::cache::{CacheMetrics, CacheStats};
+use crate::caching::{weak_etag, Cached, IfModifiedSince, IfNoneMatch, LastModified};
+use crate::captcha::{CaptchaSettings, CaptchaVerifier};
+use crate::change_feed::LiveEvents;
+use crate::client_errors::ClientErrorReport;
+use crate::config::FeatureFlagsConfig;
+use crate::domain::DomainError;
+use crate::errors::AppError;
+use crate::export::{self, Export, ExportFormat};
+use crate::i18n::RequestLanguage;
+use crate::jobs::{JobQueue, JobResponse, JobStatus};
+use crate::maintenance::{MaintenanceMode, MaintenanceStatus, Writable};
+use crate::models::{
+    BulkUpdateRequest, BulkUpdateResult, CountResponse, EmailExistsResponse, ExportRequest, ProfileHandle,
+    SetTagsRequest, SignupRequest, TagCount, User, UserResponse, UserSearchResponse, UserStats, UserStatus,
+    UsernameAvailability, VerifyEmailRequest,
+};
+use crate::negotiation::Negotiated;
+use crate::pagination::{Pagination, WithTotalCount};
+use crate::preferences::{PreferencesService, UserPreferences};
+use crate::query_timeout::{QueryMetrics, QueryStats};
+use crate::rate_limit::{ClientErrorRateLimit, EmailCheckRateLimit, SignupRateLimit};
+use crate::replicas::{self, DatabasePool, Readiness};
+use crate::scheduler::{Scheduler, TaskStatus};
+use crate::security::{AdminAccess, CsrfProtected};
 use crate::service::UserService;
+use crate::verification::EmailVerificationService;
+use crate::webhooks::{Webhook, WebhookResponse, WebhookService};
+use futures_util::Stream;
 use rocket::http::Status;
-use rocket::response::status::Custom;
+use rocket::response::stream::{Event, EventStream};
 use rocket::serde::json::Json;
-use rocket::State;
+use rocket::serde::Serialize;
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{Route, Shutdown, State};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 
-/// Handlers/Controllers - Single Responsibility Principle
-/// These handlers are only responsible for HTTP request/response handling
-/// They delegate business logic to the service layer
+// Handlers/Controllers - Single Responsibility Principle
+// These handlers are only responsible for HTTP request/response handling
+// They delegate business logic to the service layer and render failures as AppError
 
-#[post("/api/users", data = "<user>")]
+/// Routes of the v1 API, relative to the version prefix
+pub fn v1_routes() -> Vec<Route> {
+    routes![
+        sign_up,
+        verify_email,
+        get_captcha_settings,
+        report_client_error,
+        add_user,
+        get_users,
+        count_users,
+        email_exists,
+        username_available,
+        search_users,
+        export_users,
+        export_selected_users,
+        get_stats,
+        user_events,
+        get_user,
+        get_user_by_username,
+        get_user_activity,
+        verify_audit_trail,
+        export_audit_trail,
+        get_preferences,
+        put_preferences,
+        update_user,
+        delete_user,
+        suspend_user,
+        activate_user,
+        set_user_tags,
+        get_tags,
+        bulk_update_users,
+        anonymize_user,
+        get_webhooks,
+        add_webhook,
+        update_webhook,
+        delete_webhook,
+        get_jobs,
+        get_scheduler_status,
+        get_cache_stats,
+        get_query_stats,
+        get_feature_flags,
+        get_status,
+        set_maintenance
+    ]
+}
+
+/// Routes outside the versioned API, at the root of the server
+pub fn root_routes() -> Vec<Route> {
+    routes![healthz, readyz]
+}
+
+/// Convert domain users into API responses (drops passwords)
+fn to_responses(users: Vec<User>) -> Negotiated<Vec<UserResponse>> {
+    Negotiated(users.into_iter().map(UserResponse::from).collect())
+}
+
+/// Self-service registration; rate limited per client and followed by a confirmation email
+#[post("/signup", data = "<request>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn sign_up(
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    _limit: SignupRateLimit,
+    client_ip: Option<IpAddr>,
+    service: &State<Arc<UserService>>,
+    captcha: &State<Arc<dyn CaptchaVerifier>>,
+    passwords: &State<Arc<PasswordScreen>>,
+    verification: &State<Arc<EmailVerificationService>>,
+    request: JsonBody<SignupRequest>,
+) -> Result<WithWarning<(Status, Json<UserResponse>)>, AppError> {
+    let request = request.into_inner();
+    captcha.verify(request.captcha_token.as_deref(), client_ip).await?;
+    let warning = passwords.screen(&request.password).await?;
+    let user = UserResponse::from(service.sign_up(request.into()).await?);
+    // The account exists either way; a new link can be requested later
+    if let Err(e) = verification.send(&user).await {
+        eprintln!("Failed to send verification email to {}: {}", user.email, e);
+    }
+    Ok(WithWarning(warning, (Status::Created, Json(user))))
+}
+
+/// CAPTCHA provider and site key the signup form renders its widget with
+#[get("/captcha")]
+pub fn get_captcha_settings(settings: &State<CaptchaSettings>) -> Json<CaptchaSettings> {
+    Json(settings.inner().clone())
+}
+
+/// Crash or failed request reported by the frontend, written to the log.
+/// Not CSRF protected: it changes no state
+#[post("/client-errors", data = "<report>")]
+pub fn report_client_error(
+    _limit: ClientErrorRateLimit,
+    client_ip: Option<IpAddr>,
+    report: ClientErrorReport,
+) -> Result<Status, AppError> {
+    report.validate().map_err(AppError::BadRequest)?;
+    eprintln!("{}", report.log_line(client_ip));
+    Ok(Status::Accepted)
+}
+
+/// Confirm an email address with the token of a verification link
+#[post("/signup/verify", data = "<request>")]
+pub async fn verify_email(
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    verification: &State<Arc<EmailVerificationService>>,
+    request: JsonBody<VerifyEmailRequest>,
+) -> Result<Status, AppError> {
+    verification.verify(&request.into_inner().token).await?;
+    Ok(Status::NoContent)
+}
+
+/// Create a user as an admin; people register themselves through `/signup`
+#[post("/users", data = "<user>")]
 pub async fn add_user(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
     service: &State<Arc<UserService>>,
-    user: Json<User>,
-) -> Result<Json<Vec<User>>, Custom<String>> {
-    service.create_user(user.into_inner()).await.map(Json)
+    user: JsonBody<User>,
+) -> Result<Negotiated<Vec<UserResponse>>, AppError> {
+    Ok(to_responses(service.create_user(user.into_inner()).await?))
 }
 
-#[get("/api/users")]
+/// Lists users; the whole list unless `page` or `per_page` is given, only
+/// the users tagged `tag` when it is
+#[get("/users?<page>&<per_page>&<tag>")]
 pub async fn get_users(
     service: &State<Arc<UserService>>,
-) -> Result<Json<Vec<User>>, Custom<String>> {
-    service.get_all_users().await.map(Json)
+    if_none_match: IfNoneMatch,
+    page: Option<u32>,
+    per_page: Option<u32>,
+    tag: Option<&str>,
+) -> Result<WithTotalCount<Cached<Negotiated<Vec<UserResponse>>>>, AppError> {
+    let pagination = (page.is_some() || per_page.is_some()).then(|| Pagination::from_query(page, per_page));
+    let (users, total) = match (tag, pagination) {
+        (Some(tag), pagination) => service.get_tagged_users(tag, pagination).await?,
+        (None, Some(pagination)) => (service.get_users_page(pagination).await?, service.count_users().await?),
+        (None, None) => (service.get_all_users().await?, service.count_users().await?),
+    };
+
+    let users = to_responses(users);
+    let etag = weak_etag(&users.0);
+    Ok(WithTotalCount(total, Cached::new(etag, &if_none_match, users)))
+}
+
+#[get("/users/count")]
+pub async fn count_users(
+    service: &State<Arc<UserService>>,
+) -> Result<Json<CountResponse>, AppError> {
+    let count = service.count_users().await?;
+    Ok(Json(CountResponse { count }))
+}
+
+/// Whether an email is already registered, so forms can warn before submitting;
+/// rate limited per client since it reveals which emails have accounts
+#[get("/users/exists?<email>")]
+pub async fn email_exists(
+    _limit: EmailCheckRateLimit,
+    service: &State<Arc<UserService>>,
+    email: &str,
+) -> Result<Json<EmailExistsResponse>, AppError> {
+    let exists = service.email_exists(email).await?;
+    Ok(Json(EmailExistsResponse { exists }))
+}
+
+/// Whether a username is still free, for the live check of the user form.
+/// Usernames are public anyway, so unlike the email check it is not rate limited
+#[get("/usernames/available?<u>")]
+pub async fn username_available(
+    service: &State<Arc<UserService>>,
+    u: &str,
+) -> Result<Json<UsernameAvailability>, AppError> {
+    let (username, available) = service.username_available(u).await?;
+    Ok(Json(UsernameAvailability { username, available }))
+}
+
+/// Fuzzy search on names and emails, best matches first with their `match_score`
+#[get("/users/search?<q>&<limit>")]
+pub async fn search_users(
+    service: &State<Arc<UserService>>,
+    q: &str,
+    limit: Option<i64>,
+) -> Result<Json<Vec<UserSearchResponse>>, AppError> {
+    let found = service.search_users(q, limit).await?;
+    Ok(Json(found.into_iter().map(UserSearchResponse::from).collect()))
+}
+
+/// Download every user as CSV (default) or JSON, streamed row by row
+#[get("/users/export?<format>")]
+pub fn export_users(
+    service: &State<Arc<UserService>>,
+    format: Option<&str>,
+) -> Result<Export<impl Stream<Item = String> + Send + 'static>, AppError> {
+    let format = export_format(format)?;
+    Ok(Export {
+        format,
+        body: export::export_body(format, service.export_users()),
+    })
+}
+
+/// Download only the users with the posted ids, e.g. the rows checked in the
+/// list. A read despite the POST, which only carries the id list
+#[post("/users/export?<format>", data = "<request>")]
+pub async fn export_selected_users(
+    service: &State<Arc<UserService>>,
+    format: Option<&str>,
+    request: JsonBody<ExportRequest>,
+) -> Result<Export<impl Stream<Item = String> + Send + 'static>, AppError> {
+    let format = export_format(format)?;
+    let users = service.export_selected_users(&request.into_inner().ids).await?;
+    Ok(Export {
+        format,
+        body: export::export_body(format, users),
+    })
+}
+
+fn export_format(format: Option<&str>) -> Result<ExportFormat, AppError> {
+    match format {
+        Some(format) => format.parse().map_err(AppError::BadRequest),
+        None => Ok(ExportFormat::Csv),
+    }
+}
+
+#[get("/stats")]
+pub async fn get_stats(service: &State<Arc<UserService>>) -> Result<Json<UserStats>, AppError> {
+    Ok(Json(service.get_stats().await?))
+}
+
+/// Server-Sent Events stream of user changes for live updates
+#[get("/events")]
+pub fn user_events(live: &State<LiveEvents>, mut shutdown: Shutdown) -> EventStream![] {
+    let mut receiver = live.0.subscribe();
+    EventStream! {
+        loop {
+            let event = select! {
+                message = receiver.recv() => match message {
+                    Ok(event) => event,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(_)) => continue,
+                },
+                _ = &mut shutdown => break,
+            };
+            yield Event::json(&event).event(event.name());
+        }
+    }
+}
+
+/// A user with its `Last-Modified` time, so polling clients can revalidate
+/// with `If-Modified-Since` and get an empty 304 while nothing changed
+#[get("/users/<id>")]
+pub async fn get_user(
+    service: &State<Arc<UserService>>,
+    id: i32,
+    if_modified_since: IfModifiedSince,
+) -> Result<LastModified<Json<UserResponse>>, AppError> {
+    let user = service.get_user(id).await?;
+    let updated_at = user.updated_at.clone();
+    Ok(LastModified::new(updated_at.as_deref(), &if_modified_since, Json(user.into())))
+}
+
+/// Profile URL of a user, `/users/@username`; ranked after `/users/<id>`,
+/// which forwards every segment that is not a number
+#[get("/users/<handle>", rank = 2)]
+pub async fn get_user_by_username(
+    service: &State<Arc<UserService>>,
+    handle: ProfileHandle<'_>,
+) -> Result<Json<UserResponse>, AppError> {
+    Ok(Json(service.get_user_by_username(handle.0).await?.into()))
+}
+
+/// Latest audit-log entries of a user, newest first
+#[get("/users/<id>/activity?<limit>")]
+pub async fn get_user_activity(
+    service: &State<Arc<UserService>>,
+    activity: &State<Arc<dyn ActivityRepository>>,
+    id: i32,
+    limit: Option<i64>,
+) -> Result<Json<Vec<ActivityEntry>>, AppError> {
+    service.get_user(id).await?;
+    let entries = activity.for_user(id, audit::activity_limit(limit)).await?;
+    Ok(Json(entries))
+}
+
+/// UI preferences of a user; empty when never saved, so the client keeps its defaults
+#[get("/users/<id>/preferences")]
+pub async fn get_preferences(
+    _admin: AdminAccess,
+    service: &State<Arc<UserService>>,
+    preferences: &State<Arc<PreferencesService>>,
+    id: i32,
+) -> Result<Json<UserPreferences>, AppError> {
+    service.get_user(id).await?;
+    Ok(Json(preferences.get(id).await?))
+}
+
+/// Replace the UI preferences of a user
+#[put("/users/<id>/preferences", data = "<body>")]
+pub async fn put_preferences(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<UserService>>,
+    preferences: &State<Arc<PreferencesService>>,
+    id: i32,
+    body: JsonBody<UserPreferences>,
+) -> Result<Json<UserPreferences>, AppError> {
+    service.get_user(id).await?;
+    Ok(Json(preferences.put(id, body.into_inner()).await?))
+}
+
+/// Check the hash chain of the whole audit trail
+#[get("/audit/verify")]
+pub async fn verify_audit_trail(
+    _admin: AdminAccess,
+    activity: &State<Arc<dyn ActivityRepository>>,
+) -> Result<Json<AuditVerification>, AppError> {
+    Ok(Json(audit::verify_trail(activity.inner().as_ref()).await?))
+}
+
+/// Download the whole audit trail with its hashes; 409 unless it verifies
+#[get("/audit/export")]
+pub async fn export_audit_trail(
+    _admin: AdminAccess,
+    activity: &State<Arc<dyn ActivityRepository>>,
+) -> Result<AuditTrailExport, AppError> {
+    Ok(AuditTrailExport(audit::verified_trail(activity.inner().as_ref()).await?))
 }
 
-#[put("/api/users/<id>", data = "<user>")]
+#[put("/users/<id>", data = "<user>")]
 pub async fn update_user(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
     service: &State<Arc<UserService>>,
+    passwords: &State<Arc<PasswordScreen>>,
     id: i32,
-    user: Json<User>,
-) -> Result<Json<Vec<User>>, Custom<String>> {
-    service.update_user(id, user.into_inner()).await.map(Json)
+    user: JsonBody<User>,
+) -> Result<WithWarning<Negotiated<Vec<UserResponse>>>, AppError> {
+    let user = user.into_inner();
+    // Only a new password is looked up
+    let warning = if service.get_user(id).await?.password != user.password {
+        passwords.screen(&user.password).await?
+    } else {
+        None
+    };
+    Ok(WithWarning(warning, to_responses(service.update_user(id, user).await?)))
 }
 
-#[delete("/api/users/<id>")]
+#[delete("/users/<id>")]
 pub async fn delete_user(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
     service: &State<Arc<UserService>>,
     id: i32,
-) -> Result<Status, Custom<String>> {
+) -> Result<Status, AppError> {
     service.delete_user(id).await?;
     Ok(Status::NoContent)
 }
 
+#[post("/users/<id>/suspend")]
+pub async fn suspend_user(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<UserService>>,
+    id: i32,
+) -> Result<Json<UserResponse>, AppError> {
+    let user = service.set_user_status(id, UserStatus::Suspended).await?;
+    Ok(Json(user.into()))
+}
+
+#[post("/users/<id>/activate")]
+pub async fn activate_user(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<UserService>>,
+    id: i32,
+) -> Result<Json<UserResponse>, AppError> {
+    let user = service.set_user_status(id, UserStatus::Active).await?;
+    Ok(Json(user.into()))
+}
+
+/// Replace every tag of a user
+#[put("/users/<id>/tags", data = "<request>")]
+pub async fn set_user_tags(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<UserService>>,
+    id: i32,
+    request: JsonBody<SetTagsRequest>,
+) -> Result<Json<UserResponse>, AppError> {
+    let user = service.set_user_tags(id, &request.into_inner().tags).await?;
+    Ok(Json(user.into()))
+}
+
+/// Every tag in use with how many users have it, most used first
+#[get("/tags")]
+pub async fn get_tags(service: &State<Arc<UserService>>) -> Result<Json<Vec<TagCount>>, AppError> {
+    Ok(Json(service.tag_counts().await?))
+}
+
+/// Apply one change to many users at once. Always `200` once the change is
+/// valid: each id gets its own result, with the updated user or an error
+#[patch("/users/bulk", data = "<request>")]
+pub async fn bulk_update_users(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<UserService>>,
+    language: RequestLanguage,
+    request: JsonBody<BulkUpdateRequest>,
+) -> Result<Json<Vec<BulkUpdateResult>>, AppError> {
+    let outcomes = service.bulk_update_users(&request.into_inner()).await?;
+    Ok(Json(
+        outcomes
+            .into_iter()
+            .map(|(id, outcome)| bulk_update_result(id, outcome, &language))
+            .collect(),
+    ))
+}
+
+fn bulk_update_result(id: i32, outcome: Result<User, DomainError>, language: &RequestLanguage) -> BulkUpdateResult {
+    match outcome {
+        Ok(user) => BulkUpdateResult {
+            id,
+            user: Some(user.into()),
+            code: None,
+            message: None,
+        },
+        Err(error) => {
+            let error = AppError::from(error);
+            BulkUpdateResult {
+                id,
+                user: None,
+                code: Some(error.code().to_string()),
+                message: Some(language.translate(error.message())),
+            }
+        }
+    }
+}
+
+/// GDPR right to erasure: scrub the user's personal data but keep the row
+#[post("/users/<id>/anonymize")]
+pub async fn anonymize_user(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<UserService>>,
+    id: i32,
+) -> Result<Json<UserResponse>, AppError> {
+    let user = service.anonymize_user(id).await?;
+    Ok(Json(user.into()))
+}
+
+#[get("/admin/webhooks")]
+pub async fn get_webhooks(
+    _admin: AdminAccess,
+    service: &State<Arc<WebhookService>>,
+) -> Result<Json<Vec<WebhookResponse>>, AppError> {
+    let webhooks = service.get_webhooks().await?;
+    Ok(Json(webhooks.into_iter().map(WebhookResponse::from).collect()))
+}
+
+#[post("/admin/webhooks", data = "<webhook>")]
+pub async fn add_webhook(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<WebhookService>>,
+    webhook: JsonBody<Webhook>,
+) -> Result<(Status, Json<WebhookResponse>), AppError> {
+    let webhook = service.create_webhook(webhook.into_inner()).await?;
+    Ok((Status::Created, Json(webhook.into())))
+}
+
+#[put("/admin/webhooks/<id>", data = "<webhook>")]
+pub async fn update_webhook(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<WebhookService>>,
+    id: i32,
+    webhook: JsonBody<Webhook>,
+) -> Result<Json<WebhookResponse>, AppError> {
+    let webhook = service.update_webhook(id, webhook.into_inner()).await?;
+    Ok(Json(webhook.into()))
+}
+
+#[delete("/admin/webhooks/<id>")]
+pub async fn delete_webhook(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    _writable: Writable,
+    service: &State<Arc<WebhookService>>,
+    id: i32,
+) -> Result<Status, AppError> {
+    service.delete_webhook(id).await?;
+    Ok(Status::NoContent)
+}
+
+/// Inspect background jobs, newest first, optionally filtered by `status`;
+/// payloads hold personal data and are not listed
+#[get("/admin/jobs?<status>&<page>&<per_page>")]
+pub async fn get_jobs(
+    _admin: AdminAccess,
+    jobs: &State<Arc<JobQueue>>,
+    status: Option<&str>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> Result<Json<Vec<JobResponse>>, AppError> {
+    let status = status
+        .map(str::parse::<JobStatus>)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+    let pagination = Pagination::from_query(page, per_page);
+    let jobs = jobs
+        .get_jobs(status, pagination.limit(), pagination.offset())
+        .await?;
+    Ok(Json(jobs.into_iter().map(JobResponse::from).collect()))
+}
+
+/// Status of the recurring maintenance tasks
+#[get("/admin/scheduler")]
+pub fn get_scheduler_status(_admin: AdminAccess, scheduler: &State<Arc<Scheduler>>) -> Json<Vec<TaskStatus>> {
+    Json(scheduler.statuses())
+}
+
+/// Hit/miss counters of the user read cache
+#[get("/admin/cache")]
+pub fn get_cache_stats(_admin: AdminAccess, metrics: &State<Arc<CacheMetrics>>) -> Json<CacheStats> {
+    Json(metrics.snapshot())
+}
+
+/// Statement timeout and timed out queries of the user repository
+#[get("/admin/queries")]
+pub fn get_query_stats(_admin: AdminAccess, metrics: &State<Arc<QueryMetrics>>) -> Json<QueryStats> {
+    Json(metrics.snapshot())
+}
+
+/// Feature flags the frontend uses to show dark-launched UI
+#[get("/flags")]
+pub fn get_feature_flags(features: &State<FeatureFlagsConfig>) -> Json<BTreeMap<String, bool>> {
+    Json(features.flags())
+}
+
+/// Whether the API is read-only for maintenance; polled by the frontend banner
+#[get("/status")]
+pub fn get_status(maintenance: Option<&State<Arc<MaintenanceMode>>>) -> Json<MaintenanceStatus> {
+    Json(maintenance.map(|mode| mode.status()).unwrap_or_default())
+}
+
+/// Body of `GET /healthz`
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct Health {
+    pub status: &'static str,
+}
+
+/// Liveness probe: answers as long as the server runs, without touching the
+/// database, so the frontend's heartbeat only measures whether it is reachable
+#[get("/healthz")]
+pub fn healthz() -> Json<Health> {
+    Json(Health { status: "ok" })
+}
+
+/// Readiness probe: pings the primary database and every read replica.
+/// Answers 503 while the primary is down; an unhealthy replica is only
+/// reported, since reads fall back to the primary
+#[get("/readyz")]
+pub async fn readyz(pools: &State<Vec<Arc<dyn DatabasePool>>>) -> (Status, Json<Readiness>) {
+    let readiness = replicas::check_readiness(pools).await;
+    let status = match readiness.status {
+        "ready" => Status::Ok,
+        _ => Status::ServiceUnavailable,
+    };
+    (status, Json(readiness))
+}
+
+/// Switch maintenance mode on or off; allowed during maintenance
+#[put("/admin/maintenance", data = "<status>")]
+pub fn set_maintenance(
+    _admin: AdminAccess,
+    _csrf: CsrfProtected,
+    maintenance: &State<Arc<MaintenanceMode>>,
+    status: JsonBody<MaintenanceStatus>,
+) -> Json<MaintenanceStatus> {
+    maintenance.set(status.into_inner());
+    Json(maintenance.status())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audit::tests::MockActivityRepository;
+    use crate::breach::tests::{MockBreachedPasswordChecker, BREACHED_PASSWORD};
+    use crate::captcha::tests::{MockCaptchaVerifier, SOLVED_CAPTCHA};
+    use crate::errors::{self, ErrorResponse};
+    use crate::events::EventBus;
+    use crate::security;
+    use crate::versioning;
     use crate::repository::tests::MockUserRepository;
+    use crate::jobs::tests::MockJobRepository;
+    use crate::mailer::{LogMailer, SendEmailJob};
+    use crate::preferences::tests::MockPreferencesRepository;
+    use crate::client_errors::CLIENT_ERROR_LIMIT;
+    use crate::config::{AdminConfig, BreachCheckMode};
+    use crate::rate_limit::{ClientErrorRateLimiter, EmailCheckRateLimiter, RateLimiter, SignupRateLimiter};
+    use rocket::data::{Limits, ToByteUnit};
+    use crate::verification::tests::MockVerificationTokenRepository;
+    use std::time::Duration;
+    use crate::webhooks::tests::MockWebhookRepository;
+    use rocket::http::{ContentType, Cookie, Header};
     use rocket::local::blocking::Client;
     use rocket::{Build, Rocket};
 
     fn rocket_with_mock_service() -> Rocket<Build> {
+        rocket_with_verification_tokens(Arc::new(MockVerificationTokenRepository::default()))
+    }
+
+    fn rocket_with_verification_tokens(tokens: Arc<MockVerificationTokenRepository>) -> Rocket<Build> {
         let repo = Arc::new(MockUserRepository::new());
-        let service = Arc::new(UserService::new(repo));
+        let service = Arc::new(UserService::new(repo, EventBus::default()));
+        let webhooks = Arc::new(WebhookService::new(Arc::new(MockWebhookRepository::new())));
+        let email_jobs = JobQueue::new(Arc::new(MockJobRepository::new()))
+            .with_handler(Arc::new(SendEmailJob::new(Arc::new(LogMailer))));
+        let verification = Arc::new(EmailVerificationService::new(
+            tokens,
+            Arc::new(email_jobs),
+            "http://localhost:8080".to_string(),
+        ));
 
-        rocket::build()
+        let limits = Limits::default().limit(CLIENT_ERROR_LIMIT, 1.kibibytes());
+        let rocket = rocket::build()
+            .configure(rocket::Config::figment().merge(("limits", limits)))
             .manage(service)
-            .mount("/", routes![add_user, get_users, update_user, delete_user])
+            .manage(webhooks)
+            .manage(Arc::new(PreferencesService::new(Arc::new(MockPreferencesRepository::default()))))
+            .manage(Arc::new(JobQueue::new(Arc::new(MockJobRepository::new()))))
+            .manage(Arc::new(Scheduler::new()))
+            .manage(Arc::new(CacheMetrics::new("none")))
+            .manage(Arc::new(QueryMetrics::new(Duration::from_secs(10))))
+            .manage(LiveEvents::default())
+            .manage(verification)
+            .manage(Arc::new(MockCaptchaVerifier) as Arc<dyn CaptchaVerifier>)
+            .manage(Arc::new(PasswordScreen::new(
+                Arc::new(MockBreachedPasswordChecker),
+                BreachCheckMode::Reject,
+            )))
+            .manage(CaptchaSettings {
+                provider: "turnstile".to_string(),
+                site_key: Some("site-key".to_string()),
+            })
+            .manage(SignupRateLimiter(RateLimiter::new(3, Duration::from_secs(60 * 60))))
+            .manage(ClientErrorRateLimiter(RateLimiter::new(2, Duration::from_secs(60))))
+            .manage(EmailCheckRateLimiter(RateLimiter::new(3, Duration::from_secs(60))))
+            .manage(Arc::new(MockActivityRepository::default()) as Arc<dyn ActivityRepository>)
+            .manage(FeatureFlagsConfig {
+                enabled: vec!["bulk_delete".to_string()],
+            })
+            .manage(Arc::new(MaintenanceMode::default()))
+            .manage(AdminConfig {
+                token: Some(ADMIN_TOKEN.into()),
+            });
+
+        versioning::mount_v1(rocket, v1_routes())
+            .register("/", errors::catchers())
+            .attach(versioning::DeprecationFairing)
+            .attach(security::shield())
+            .attach(security::SecurityHeaders)
+    }
+
+    const ADMIN_TOKEN: &str = "q8Zt-31xLw";
+
+    /// Authorization of the admin endpoints
+    fn admin() -> Header<'static> {
+        Header::new("Authorization", format!("Bearer {}", ADMIN_TOKEN))
     }
 
     #[test]
     fn test_get_users_empty() {
         let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
-        let response = client.get("/api/users").dispatch();
+        let response = client.get("/api/v1/users").dispatch();
 
         assert_eq!(response.status(), Status::Ok);
-        let users: Vec<User> = response.into_json().unwrap();
+        let users: Vec<UserResponse> = response.into_json().unwrap();
         assert_eq!(users.len(), 0);
     }
 
@@ -75,23 +743,65 @@ mod tests {
         let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
 
         let response = client
-            .post("/api/users")
+            .post("/api/v1/users")
+            .header(admin())
             .json(&user)
             .dispatch();
 
         assert_eq!(response.status(), Status::Ok);
-        let users: Vec<User> = response.into_json().unwrap();
+        let users: Vec<UserResponse> = response.into_json().unwrap();
         assert_eq!(users.len(), 1);
         assert_eq!(users[0].name, "John Doe");
     }
 
+    #[test]
+    fn test_add_user_requires_the_admin_token() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+
+        let response = client.post("/api/v1/users").json(&user).dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        let response = client
+            .post("/api/v1/users")
+            .header(Header::new("Authorization", "Bearer guess"))
+            .json(&user)
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let count: CountResponse = client.get("/api/v1/users/count").dispatch().into_json().unwrap();
+        assert_eq!(count.count, 0);
+    }
+
+    #[test]
+    fn test_user_changes_require_the_admin_token() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let changed = User { name: "John Smith".to_string(), ..user.clone() };
+        assert_eq!(client.put("/api/v1/users/1").json(&changed).dispatch().status(), Status::Unauthorized);
+        let response = client
+            .put("/api/v1/users/1/tags")
+            .header(ContentType::JSON)
+            .body(r#"{"tags":["vip"]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert_eq!(client.delete("/api/v1/users/1").dispatch().status(), Status::Unauthorized);
+
+        let response = client.get("/api/v1/users/1").dispatch();
+        let stored: UserResponse = response.into_json().unwrap();
+        assert_eq!(stored.name, "John Doe");
+        assert!(stored.tags.is_empty());
+    }
+
     #[test]
     fn test_add_user_invalid() {
         let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
         let user = User::new("".to_string(), "john@example.com".to_string(), "password123".to_string());
 
         let response = client
-            .post("/api/users")
+            .post("/api/v1/users")
+            .header(admin())
             .json(&user)
             .dispatch();
 
@@ -104,17 +814,18 @@ mod tests {
         
         // First create a user
         let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
-        client.post("/api/users").json(&user).dispatch();
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
 
         // Then update it
         let updated_user = User::new("John Smith".to_string(), "johnsmith@example.com".to_string(), "newpassword123".to_string());
         let response = client
-            .put("/api/users/1")
+            .put("/api/v1/users/1")
+            .header(admin())
             .json(&updated_user)
             .dispatch();
 
         assert_eq!(response.status(), Status::Ok);
-        let users: Vec<User> = response.into_json().unwrap();
+        let users: Vec<UserResponse> = response.into_json().unwrap();
         assert_eq!(users[0].name, "John Smith");
     }
 
@@ -124,15 +835,1167 @@ mod tests {
         
         // First create a user
         let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
-        client.post("/api/users").json(&user).dispatch();
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
 
         // Then delete it
-        let response = client.delete("/api/users/1").dispatch();
+        let response = client.delete("/api/v1/users/1").header(admin()).dispatch();
         assert_eq!(response.status(), Status::NoContent);
 
         // Verify it's deleted
-        let response = client.get("/api/users").dispatch();
-        let users: Vec<User> = response.into_json().unwrap();
+        let response = client.get("/api/v1/users").dispatch();
+        let users: Vec<UserResponse> = response.into_json().unwrap();
         assert_eq!(users.len(), 0);
     }
+
+    #[test]
+    fn test_add_user_invalid_returns_json_error() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("".to_string(), "john@example.com".to_string(), "password123".to_string());
+
+        let response = client
+            .post("/api/v1/users")
+            .header(admin())
+            .header(Header::new("X-Request-Id", "test-request"))
+            .json(&user)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "bad_request");
+        assert_eq!(error.message, "Name cannot be empty");
+        assert_eq!(error.field.as_deref(), Some("name"));
+        assert_eq!(error.request_id, "test-request");
+    }
+
+    #[test]
+    fn test_unknown_route_returns_json_404() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/unknown").dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "not_found");
+        assert!(!error.request_id.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_body_returns_json_422() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client
+            .post("/api/v1/users")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"name": "John"}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "unprocessable_entity");
+    }
+
+    #[test]
+    fn test_delete_nonexistent_user_returns_json_404() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.delete("/api/v1/users/999").header(admin()).dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, "User with id 999 not found");
+    }
+
+    #[test]
+    fn test_get_users_does_not_expose_passwords() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let body = client.get("/api/v1/users").dispatch().into_string().unwrap();
+        assert!(!body.contains("password"));
+    }
+
+    #[test]
+    fn test_get_users_as_msgpack() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client
+            .get("/api/v1/users")
+            .header(Header::new("Accept", "application/msgpack"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::MsgPack));
+        let users: Vec<UserResponse> = rmp_serde::from_slice(&response.into_bytes().unwrap()).unwrap();
+        assert_eq!(users[0].name, "John Doe");
+    }
+
+    #[test]
+    fn test_get_users_as_cbor() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client
+            .get("/api/v1/users")
+            .header(Header::new("Accept", "application/cbor"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let bytes = response.into_bytes().unwrap();
+        let users: Vec<UserResponse> = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(users[0].email, "john@example.com");
+    }
+
+    #[test]
+    fn test_get_users_returns_etag_and_304() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+        assert!(etag.starts_with("W/"));
+
+        let response = client
+            .get("/api/v1/users")
+            .header(Header::new("If-None-Match", etag.clone()))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.headers().get_one("ETag"), Some(etag.as_str()));
+        assert!(response.into_bytes().unwrap_or_default().is_empty());
+    }
+
+    #[test]
+    fn test_etag_changes_after_mutation() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/users").dispatch();
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client
+            .get("/api/v1/users")
+            .header(Header::new("If-None-Match", etag.clone()))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_ne!(response.headers().get_one("ETag"), Some(etag.as_str()));
+    }
+
+    #[test]
+    fn test_get_user_returns_last_modified_and_304() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users/1").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let last_modified = response.headers().get_one("Last-Modified").unwrap().to_string();
+        assert!(last_modified.ends_with(" GMT"));
+
+        let response = client
+            .get("/api/v1/users/1")
+            .header(Header::new("If-Modified-Since", last_modified.clone()))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.headers().get_one("Last-Modified"), Some(last_modified.as_str()));
+        assert!(response.into_bytes().unwrap_or_default().is_empty());
+
+        let response = client
+            .get("/api/v1/users/1")
+            .header(Header::new("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body: UserResponse = response.into_json().unwrap();
+        assert_eq!(body.name, "John Doe");
+    }
+
+    #[test]
+    fn test_count_users() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        for i in 1..=3 {
+            let user = User::new(format!("User {}", i), format!("user{}@example.com", i), "password123".to_string());
+            client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        }
+
+        let response = client.get("/api/v1/users/count").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body: CountResponse = response.into_json().unwrap();
+        assert_eq!(body.count, 3);
+    }
+
+    #[test]
+    fn test_get_users_paginated_with_total_count() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        for i in 1..=3 {
+            let user = User::new(format!("User {}", i), format!("user{}@example.com", i), "password123".to_string());
+            client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        }
+
+        let response = client.get("/api/v1/users?page=1&per_page=2").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("X-Total-Count"), Some("3"));
+        let users: Vec<UserResponse> = response.into_json().unwrap();
+        assert_eq!(users.len(), 2);
+
+        let response = client.get("/api/v1/users?page=2&per_page=2").dispatch();
+        let users: Vec<UserResponse> = response.into_json().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].name, "User 3");
+    }
+
+    #[test]
+    fn test_get_stats() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/stats").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let stats: UserStats = response.into_json().unwrap();
+        assert_eq!(stats.total, 1);
+        assert_eq!(stats.verified + stats.unverified, 1);
+    }
+
+    #[test]
+    fn test_webhook_crud() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let webhook = Webhook {
+            id: None,
+            url: "https://example.com/hooks".to_string(),
+            secret: "s3cret".to_string(),
+            events: vec!["user.created".to_string()],
+        };
+
+        let response = client.post("/api/v1/admin/webhooks").header(admin()).json(&webhook).dispatch();
+        assert_eq!(response.status(), Status::Created);
+        let body = response.into_string().unwrap();
+        assert!(!body.contains("s3cret"));
+
+        let updated = Webhook {
+            events: vec!["user.created".to_string(), "user.deleted".to_string()],
+            ..webhook
+        };
+        let response = client.put("/api/v1/admin/webhooks/1").header(admin()).json(&updated).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/api/v1/admin/webhooks").header(admin()).dispatch();
+        let webhooks: Vec<WebhookResponse> = response.into_json().unwrap();
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0].events.len(), 2);
+
+        let response = client.delete("/api/v1/admin/webhooks/1").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+        let response = client.delete("/api/v1/admin/webhooks/1").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_add_invalid_webhook() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let webhook = Webhook {
+            id: None,
+            url: "not-a-url".to_string(),
+            secret: "s3cret".to_string(),
+            events: vec!["user.created".to_string()],
+        };
+
+        let response = client.post("/api/v1/admin/webhooks").header(admin()).json(&webhook).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, "Webhook URL must be an http(s) URL");
+
+        let internal = Webhook {
+            url: "http://169.254.169.254/latest/meta-data".to_string(),
+            ..webhook
+        };
+        let response = client.post("/api/v1/admin/webhooks").header(admin()).json(&internal).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, "Webhook URL must not point to the internal address 169.254.169.254");
+    }
+
+    #[test]
+    fn test_webhooks_require_the_admin_token() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/admin/webhooks").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!((error.code.as_str(), error.message.as_str()), ("unauthorized", security::ADMIN_TOKEN_MESSAGE));
+
+        let response = client
+            .post("/api/v1/admin/webhooks")
+            .header(Header::new("Authorization", "Bearer guess"))
+            .json(&Webhook::with_id(1, "https://example.com".to_string(), "s3cret".to_string(), Vec::new()))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        // Without a configured token the admin API is off altogether
+        let rocket = rocket::build()
+            .manage(Arc::new(WebhookService::new(Arc::new(MockWebhookRepository::new()))))
+            .mount("/", routes![get_webhooks])
+            .register("/", errors::catchers());
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let response = client.get("/admin/webhooks").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, security::ADMIN_DISABLED_MESSAGE);
+    }
+
+    #[test]
+    fn test_get_jobs() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+
+        let response = client.get("/api/v1/admin/jobs").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.get("/api/v1/admin/jobs?status=pending").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let jobs: Vec<JobResponse> = response.into_json().unwrap();
+        assert!(jobs.is_empty());
+
+        let response = client.get("/api/v1/admin/jobs?status=done").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, "Unknown job status 'done'");
+    }
+
+    #[test]
+    fn test_get_scheduler_status() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/admin/scheduler").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.get("/api/v1/admin/scheduler").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let statuses: Vec<TaskStatus> = response.into_json().unwrap();
+        assert!(statuses.is_empty());
+    }
+
+    #[test]
+    fn test_get_cache_stats() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/admin/cache").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.get("/api/v1/admin/cache").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let stats: CacheStats = response.into_json().unwrap();
+        assert_eq!(stats.backend, "none");
+        assert_eq!(stats.hits + stats.misses, 0);
+    }
+
+    #[test]
+    fn test_get_query_stats() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/admin/queries").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.get("/api/v1/admin/queries").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let stats: QueryStats = response.into_json().unwrap();
+        assert_eq!(
+            stats,
+            QueryStats {
+                statement_timeout_ms: 10_000,
+                timeouts: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_legacy_api_prefix_is_deprecated_alias() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+
+        let response = client.get("/api/users/count").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("Deprecation"), Some("true"));
+        assert_eq!(
+            response.headers().get_one("Link"),
+            Some("</api/v1/users/count>; rel=\"successor-version\"")
+        );
+        assert!(response.headers().get_one("Warning").unwrap().starts_with("299"));
+
+        let response = client.get("/api/v1/users/count").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.headers().get_one("Deprecation").is_none());
+    }
+
+    #[test]
+    fn test_security_headers() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/users").dispatch();
+
+        let headers = response.headers();
+        assert_eq!(headers.get_one("X-Frame-Options"), Some("DENY"));
+        assert_eq!(headers.get_one("X-Content-Type-Options"), Some("nosniff"));
+        assert!(headers.get_one("Strict-Transport-Security").is_some());
+        assert_eq!(
+            headers.get_one("Content-Security-Policy"),
+            Some(security::CONTENT_SECURITY_POLICY)
+        );
+        assert!(response.cookies().get(security::CSRF_COOKIE).is_some());
+    }
+
+    #[test]
+    fn test_csrf_protects_cookie_authenticated_requests() {
+        let client = Client::untracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+
+        // No cookies: not cookie-authenticated, no token required
+        let response = client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client
+            .delete("/api/v1/users/1")
+            .header(admin())
+            .cookie(Cookie::new("session", "abc"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        let body: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(body.code, "forbidden");
+
+        let response = client
+            .delete("/api/v1/users/1")
+            .header(admin())
+            .cookie(Cookie::new("session", "abc"))
+            .cookie(Cookie::new(security::CSRF_COOKIE, "token"))
+            .header(Header::new(security::CSRF_HEADER, "other"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+
+        let response = client
+            .delete("/api/v1/users/1")
+            .header(admin())
+            .cookie(Cookie::new("session", "abc"))
+            .cookie(Cookie::new(security::CSRF_COOKIE, "token"))
+            .header(Header::new(security::CSRF_HEADER, "token"))
+            .dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+    }
+
+    #[test]
+    fn test_invalid_field_returns_structured_422() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client
+            .post("/api/v1/users")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"name": "John", "email": 42, "password": "password123"}"#)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "unprocessable_entity");
+        assert_eq!(error.field.as_deref(), Some("email"));
+        assert_eq!(error.expected.as_deref(), Some("a string"));
+        assert!(error.message.starts_with("Invalid value for field 'email'"));
+    }
+
+    #[test]
+    fn test_oversized_and_malformed_bodies() {
+        let rocket = rocket_with_mock_service()
+            .configure(rocket::Config::figment().merge(("limits.json", 64)));
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+
+        let name = "x".repeat(100);
+        let response = client
+            .post("/api/v1/users")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"name": "{}", "email": "a@b.co", "password": "password123"}}"#, name))
+            .dispatch();
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "payload_too_large");
+
+        let response = client
+            .post("/api/v1/users")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"name": "#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert!(error.message.starts_with("Malformed JSON"));
+    }
+
+    #[test]
+    fn test_suspend_and_activate_user() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.post("/api/v1/users/1/suspend").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let suspended: UserResponse = response.into_json().unwrap();
+        assert_eq!(suspended.status, UserStatus::Suspended);
+
+        let users: Vec<UserResponse> = client.get("/api/v1/users").dispatch().into_json().unwrap();
+        assert_eq!(users[0].status, UserStatus::Suspended);
+
+        let response = client.post("/api/v1/users/1/activate").header(admin()).dispatch();
+        let active: UserResponse = response.into_json().unwrap();
+        assert_eq!(active.status, UserStatus::Active);
+
+        let response = client.post("/api/v1/users/999/suspend").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_status_changes_require_the_admin_token() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.post("/api/v1/users/1/suspend").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, security::ADMIN_TOKEN_MESSAGE);
+        let response = client
+            .post("/api/v1/users/1/suspend")
+            .header(Header::new("Authorization", "Bearer guess"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert_eq!(client.post("/api/v1/users/1/activate").dispatch().status(), Status::Unauthorized);
+
+        let stored: UserResponse = client.get("/api/v1/users/1").dispatch().into_json().unwrap();
+        assert_eq!(stored.status, UserStatus::Active);
+    }
+
+    #[test]
+    fn test_anonymize_user() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.post("/api/v1/users/1/anonymize").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let anonymized: UserResponse = response.into_json().unwrap();
+        assert_eq!(anonymized.email, "user-1@anonymized.invalid");
+        assert_eq!(anonymized.status, UserStatus::Suspended);
+
+        let response = client.post("/api/v1/users/1/anonymize").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Conflict);
+    }
+
+    #[test]
+    fn test_anonymize_user_requires_the_admin_token() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.post("/api/v1/users/1/anonymize").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        let response = client
+            .post("/api/v1/users/1/anonymize")
+            .header(Header::new("Authorization", "Bearer guess"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        let stored: UserResponse = client.get("/api/v1/users/1").dispatch().into_json().unwrap();
+        assert_eq!(stored.email, "john@example.com");
+
+        // Without a configured token nobody can erase users
+        let service = Arc::new(UserService::new(Arc::new(MockUserRepository::new()), EventBus::default()));
+        let rocket = rocket::build()
+            .manage(service)
+            .mount("/", routes![anonymize_user])
+            .register("/", errors::catchers());
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let response = client.post("/users/1/anonymize").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, security::ADMIN_DISABLED_MESSAGE);
+    }
+
+    #[test]
+    fn test_export_users() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("Doe, John".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users/export").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::CSV));
+        assert_eq!(
+            response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"users.csv\"")
+        );
+        assert_eq!(
+            response.into_string().unwrap(),
+            "id,name,email,status,last_login_at\n1,\"Doe, John\",john@example.com,active,\n"
+        );
+
+        let response = client.get("/api/v1/users/export?format=json").dispatch();
+        assert_eq!(response.content_type(), Some(ContentType::JSON));
+        let users: Vec<UserResponse> = response.into_json().unwrap();
+        assert_eq!(users.len(), 1);
+
+        let response = client.get("/api/v1/users/export?format=xml").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_export_selected_users() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        for (name, email) in [("Ana", "ana@example.com"), ("Bia", "bia@example.com"), ("Caio", "caio@example.com")] {
+            let user = User::new(name.to_string(), email.to_string(), "password123".to_string());
+            client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        }
+
+        let response = client
+            .post("/api/v1/users/export")
+            .header(ContentType::JSON)
+            .body(r#"{"ids":[3,1,9]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::CSV));
+        assert_eq!(
+            response.into_string().unwrap(),
+            "id,name,email,status,last_login_at\n1,Ana,ana@example.com,active,\n3,Caio,caio@example.com,active,\n"
+        );
+
+        let response = client
+            .post("/api/v1/users/export?format=json")
+            .header(ContentType::JSON)
+            .body(r#"{"ids":[2]}"#)
+            .dispatch();
+        let users: Vec<UserResponse> = response.into_json().unwrap();
+        assert_eq!(users.iter().map(|u| u.id).collect::<Vec<_>>(), vec![2]);
+
+        let response = client
+            .post("/api/v1/users/export")
+            .header(ContentType::JSON)
+            .body(r#"{"ids":[]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let body: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(body["field"], "ids");
+    }
+
+    #[test]
+    fn test_search_users() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users/search?q=john").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string().unwrap();
+        assert!(!body.contains("password"));
+        let found: Vec<UserSearchResponse> = rocket::serde::json::from_str(&body).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].user.name, "John Doe");
+        assert_eq!(found[0].match_score, 1.0);
+
+        let response = client.get("/api/v1/users/search?q=%20").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_maintenance_mode_makes_the_api_read_only() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        let status: MaintenanceStatus = client.get("/api/v1/status").dispatch().into_json().unwrap();
+        assert!(!status.maintenance);
+
+        // Only operators may switch it
+        let on = MaintenanceStatus {
+            maintenance: true,
+            message: None,
+        };
+        let response = client.put("/api/v1/admin/maintenance").json(&on).dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        let status: MaintenanceStatus = client.get("/api/v1/status").dispatch().into_json().unwrap();
+        assert!(!status.maintenance);
+
+        let response = client.put("/api/v1/admin/maintenance").header(admin()).json(&on).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let status: MaintenanceStatus = client.get("/api/status").dispatch().into_json().unwrap();
+        assert!(status.maintenance);
+
+        let response = client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "service_unavailable");
+        assert_eq!(error.message, "The service is read-only for maintenance, try again later");
+
+        let response = client
+            .delete("/api/v1/users/1")
+            .header(admin())
+            .header(Header::new("Accept-Language", "pt-BR"))
+            .dispatch();
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.message, "O serviço está somente leitura para manutenção, tente novamente mais tarde");
+
+        // Reads keep working, and maintenance can be switched off again
+        assert_eq!(client.get("/api/v1/users").dispatch().status(), Status::Ok);
+        client
+            .put("/api/v1/admin/maintenance")
+            .header(admin())
+            .json(&MaintenanceStatus::default())
+            .dispatch();
+        let response = client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_get_feature_flags() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let response = client.get("/api/v1/flags").dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let flags: BTreeMap<String, bool> = response.into_json().unwrap();
+        assert_eq!(flags.get("bulk_delete"), Some(&true));
+        assert_eq!(flags.get("org_management"), Some(&false));
+    }
+
+    #[test]
+    fn test_error_messages_follow_accept_language() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("".to_string(), "john@example.com".to_string(), "password123".to_string());
+
+        let response = client
+            .post("/api/v1/users")
+            .header(admin())
+            .header(Header::new("Accept-Language", "pt-BR,pt;q=0.9"))
+            .json(&user)
+            .dispatch();
+        assert_eq!(response.headers().get_one("Content-Language"), Some("pt-BR"));
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "bad_request");
+        assert_eq!(error.message, "O nome não pode ficar vazio");
+
+        let response = client
+            .put("/api/v1/users/99")
+            .header(admin())
+            .header(Header::new("Accept-Language", "es"))
+            .json(&User::new("Jane".to_string(), "jane@example.com".to_string(), "password123".to_string()))
+            .dispatch();
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "not_found");
+        assert_eq!(error.message, "No se encontró el usuario con id 99");
+    }
+
+    #[test]
+    fn test_get_user_by_id() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users/1").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let found: UserResponse = response.into_json().unwrap();
+        assert_eq!(found.email, "john@example.com");
+        assert!(found.created_at.is_some());
+        assert!(found.updated_at.is_some());
+
+        let response = client.get("/api/v1/users/42").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_get_user_activity() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users/1/activity?limit=5").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let entries: Vec<serde_json::Value> = response.into_json().unwrap();
+        assert!(entries.is_empty());
+
+        let response = client.get("/api/v1/users/42/activity").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_notification_inbox_is_not_served() {
+        // Stays unrouted until there is a signed-in user to scope it to
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users/1/notifications").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        let response = client.post("/api/v1/users/1/notifications/read").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_email_exists() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client.get("/api/v1/users/exists?email=John%40Example.com").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), r#"{"exists":true}"#);
+        let response: serde_json::Value =
+            client.get("/api/v1/users/exists?email=jane%40example.com").dispatch().into_json().unwrap();
+        assert_eq!(response["exists"], false);
+        let response = client.get("/api/v1/users/exists?email=jane").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        // The fourth check within the window is refused
+        let response = client.get("/api/v1/users/exists?email=jane%40example.com").dispatch();
+        assert_eq!(response.status(), Status::TooManyRequests);
+    }
+
+    #[test]
+    fn test_usernames() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("Ana".to_string(), "ana@example.com".to_string(), "password123".to_string())
+            .with_username(Some("ana-silva".to_string()));
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response: UserResponse = client.get("/api/v1/users/@ana-silva").dispatch().into_json().unwrap();
+        assert_eq!(response.name, "Ana");
+        assert_eq!(response.username.as_deref(), Some("ana-silva"));
+        let response = client.get("/api/v1/users/1").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let response = client.get("/api/v1/users/@nobody").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client.get("/api/v1/usernames/available?u=Ana-Silva").dispatch();
+        assert_eq!(response.into_string().unwrap(), r#"{"username":"ana-silva","available":false}"#);
+        let response: UsernameAvailability =
+            client.get("/api/usernames/available?u=bia").dispatch().into_json().unwrap();
+        assert!(response.available);
+        let response = client.get("/api/v1/usernames/available?u=a_b").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let body: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(body["field"], "username");
+
+        let taken = User::new("Ana 2".to_string(), "ana2@example.com".to_string(), "password123".to_string())
+            .with_username(Some("ana-silva".to_string()));
+        let response = client.post("/api/v1/users").header(admin()).json(&taken).dispatch();
+        assert_eq!(response.status(), Status::Conflict);
+        let body: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(body["field"], "username");
+    }
+
+    #[test]
+    fn test_tags() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        for (name, email) in [("Ana", "ana@example.com"), ("Bia", "bia@example.com")] {
+            let user = User::new(name.to_string(), email.to_string(), "password123".to_string());
+            client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        }
+
+        let response = client
+            .put("/api/v1/users/1/tags")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"tags":["VIP","beta"]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let user: UserResponse = response.into_json().unwrap();
+        assert_eq!(user.tags, vec!["beta".to_string(), "vip".to_string()]);
+
+        let response = client.get("/api/v1/users?tag=vip").dispatch();
+        assert_eq!(response.headers().get_one("X-Total-Count"), Some("1"));
+        let users: Vec<UserResponse> = response.into_json().unwrap();
+        assert_eq!(users.iter().map(|u| u.id).collect::<Vec<_>>(), vec![1]);
+        let users: Vec<UserResponse> = client.get("/api/v1/users?tag=vip&page=2").dispatch().into_json().unwrap();
+        assert!(users.is_empty());
+
+        let response = client.get("/api/v1/tags").dispatch();
+        assert_eq!(response.into_string().unwrap(), r#"[{"tag":"beta","count":1},{"tag":"vip","count":1}]"#);
+
+        let response = client
+            .put("/api/v1/users/1/tags")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"tags":["not valid"]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let body: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(body["field"], "tags");
+        let response = client
+            .put("/api/v1/users/9/tags")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"tags":[]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_bulk_update_users() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        for (name, email) in [("Ana", "ana@example.com"), ("Bia", "bia@example.com")] {
+            let user = User::new(name.to_string(), email.to_string(), "password123".to_string());
+            client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        }
+
+        let response = client
+            .patch("/api/v1/users/bulk")
+            .header(admin())
+            .header(ContentType::JSON)
+            .header(Header::new("Accept-Language", "pt-BR"))
+            .body(r#"{"ids":[1,2,9],"status":"suspended","add_tags":["vip"]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let results: Vec<BulkUpdateResult> = response.into_json().unwrap();
+        assert_eq!(results.len(), 3);
+        let ana = results[0].user.as_ref().unwrap();
+        assert_eq!((ana.status, ana.tags.clone()), (UserStatus::Suspended, vec!["vip".to_string()]));
+        assert!(results[1].user.is_some());
+        assert_eq!(results[2].code.as_deref(), Some("not_found"));
+        assert_eq!(results[2].message.as_deref(), Some("Usuário com id 9 não encontrado"));
+
+        let response = client
+            .patch("/api/v1/users/bulk")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"ids":[],"status":"active"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let body: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(body["field"], "ids");
+        let response = client
+            .patch("/api/v1/users/bulk")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"ids":[1]}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_bulk_update_requires_the_admin_token() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("Ana".to_string(), "ana@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        let response = client
+            .patch("/api/v1/users/bulk")
+            .header(ContentType::JSON)
+            .body(r#"{"ids":[1],"status":"suspended"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        let stored: UserResponse = client.get("/api/v1/users/1").dispatch().into_json().unwrap();
+        assert_eq!(stored.status, UserStatus::Active);
+    }
+
+    #[test]
+    fn test_user_preferences() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+
+        // Any user's preferences could be read or replaced by id, so only the admin may
+        assert_eq!(client.get("/api/v1/users/1/preferences").dispatch().status(), Status::Unauthorized);
+        let response = client
+            .put("/api/v1/users/1/preferences")
+            .header(ContentType::JSON)
+            .body(r#"{"theme":"dark"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.get("/api/v1/users/1/preferences").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "{}");
+
+        let response = client
+            .put("/api/v1/users/1/preferences")
+            .header(admin())
+            .header(ContentType::JSON)
+            .body(r#"{"theme":"dark","page_size":50,"columns":{"hidden":["id"]}}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let preferences: serde_json::Value = client.get("/api/v1/users/1/preferences").header(admin()).dispatch().into_json().unwrap();
+        assert_eq!(preferences["theme"], "dark");
+        assert_eq!(preferences["page_size"], 50);
+        assert_eq!(preferences["columns"]["hidden"][0], "id");
+        assert!(preferences.get("locale").is_none());
+
+        let response = client
+            .put("/api/v1/users/1/preferences")
+            .header(admin())
+            .header(ContentType::JSON)
+            .header(Header::new("Accept-Language", "es"))
+            .body(r#"{"page_size":500}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let error: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(error["message"], "El tamaño de página debe estar entre 1 y 100");
+
+        let response = client.get("/api/v1/users/42/preferences").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_verify_and_export_audit_trail() {
+        let client = Client::tracked(rocket_with_mock_service()).expect("valid rocket instance");
+        assert_eq!(client.get("/api/v1/audit/verify").dispatch().status(), Status::Unauthorized);
+        assert_eq!(client.get("/api/v1/audit/export").dispatch().status(), Status::Unauthorized);
+
+        let response = client.get("/api/v1/audit/verify").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        let verification: serde_json::Value = response.into_json().unwrap();
+        assert_eq!(verification["valid"], true);
+        assert_eq!(verification["broken_at"], serde_json::Value::Null);
+
+        let response = client.get("/api/v1/audit/export").header(admin()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.headers().get_one("Content-Disposition"),
+            Some("attachment; filename=\"audit-trail.json\"")
+        );
+        let trail: Vec<serde_json::Value> = response.into_json().unwrap();
+        assert!(trail.is_empty());
+    }
+
+    fn signup(name: &str, email: &str) -> SignupRequest {
+        SignupRequest {
+            name: name.to_string(),
+            email: email.to_string(),
+            password: "password123".to_string(),
+            username: None,
+            captcha_token: Some(SOLVED_CAPTCHA.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sign_up_creates_user_and_verifies_email() {
+        let tokens = Arc::new(MockVerificationTokenRepository::default());
+        let client = Client::tracked(rocket_with_verification_tokens(tokens.clone())).unwrap();
+
+        let response = client
+            .post("/api/v1/signup")
+            .json(&signup("John Doe", "John@Example.com"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+        let user: UserResponse = response.into_json().unwrap();
+        assert_eq!(user.email, "john@example.com");
+
+        let token = tokens.tokens.lock().unwrap().keys().next().unwrap().clone();
+        let verify = |token: &str| {
+            client
+                .post("/api/v1/signup/verify")
+                .json(&VerifyEmailRequest {
+                    token: token.to_string(),
+                })
+                .dispatch()
+                .status()
+        };
+        assert_eq!(verify(&token), Status::NoContent);
+        assert_eq!(*tokens.verified.lock().unwrap(), vec![user.id]);
+        assert_eq!(verify(&token), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_sign_up_rejects_duplicate_email() {
+        let client = Client::tracked(rocket_with_mock_service()).unwrap();
+        client
+            .post("/api/v1/signup")
+            .json(&signup("John Doe", "john@example.com"))
+            .dispatch();
+
+        let response = client
+            .post("/api/v1/signup")
+            .json(&signup("John Again", "JOHN@example.com"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Conflict);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.field.as_deref(), Some("email"));
+    }
+
+    #[test]
+    fn test_sign_up_is_rate_limited() {
+        let client = Client::tracked(rocket_with_mock_service()).unwrap();
+        for i in 0..3 {
+            let response = client
+                .post("/api/v1/signup")
+                .json(&signup("John Doe", &format!("john{}@example.com", i)))
+                .dispatch();
+            assert_eq!(response.status(), Status::Created);
+        }
+
+        let response = client
+            .post("/api/v1/signup")
+            .json(&signup("John Doe", "john3@example.com"))
+            .dispatch();
+        assert_eq!(response.status(), Status::TooManyRequests);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.code, "too_many_requests");
+    }
+
+    #[test]
+    fn test_breached_passwords_are_rejected() {
+        let client = Client::tracked(rocket_with_mock_service()).unwrap();
+        let breached = SignupRequest {
+            password: BREACHED_PASSWORD.to_string(),
+            ..signup("John Doe", "john@example.com")
+        };
+        let response = client.post("/api/v1/signup").json(&breached).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        let error: ErrorResponse = response.into_json().unwrap();
+        assert_eq!(error.field.as_deref(), Some("password"));
+
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        client.post("/api/v1/users").header(admin()).json(&user).dispatch();
+        let changed = User { password: BREACHED_PASSWORD.to_string(), ..user.clone() };
+        let response = client.put("/api/v1/users/1").header(admin()).json(&changed).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(client.put("/api/v1/users/1").header(admin()).json(&user).dispatch().status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_report_client_error() {
+        let client = Client::tracked(rocket_with_mock_service()).unwrap();
+        let report = serde_json::json!({
+            "kind": "panic",
+            "message": "panicked at src/pages.rs:10:5",
+            "route": "/users/7",
+            "user_agent": "Mozilla/5.0",
+            "app_version": "0.1.0"
+        });
+        let response = client.post("/api/v1/client-errors").json(&report).dispatch();
+        assert_eq!(response.status(), Status::Accepted);
+
+        let too_large = serde_json::json!({"kind": "panic", "message": "x".repeat(2_000)});
+        let response = client.post("/api/v1/client-errors").json(&too_large).dispatch();
+        assert_eq!(response.status(), Status::PayloadTooLarge);
+
+        let response = client.post("/api/v1/client-errors").json(&report).dispatch();
+        assert_eq!(response.status(), Status::TooManyRequests);
+    }
+
+    #[test]
+    fn test_client_error_needs_a_message() {
+        let client = Client::tracked(rocket_with_mock_service()).unwrap();
+        let response = client
+            .post("/api/v1/client-errors")
+            .json(&serde_json::json!({"kind": "request", "message": ""}))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_sign_up_requires_solved_captcha() {
+        let client = Client::tracked(rocket_with_mock_service()).unwrap();
+        let settings: CaptchaSettings = client.get("/api/v1/captcha").dispatch().into_json().unwrap();
+        assert_eq!(settings.site_key.as_deref(), Some("site-key"));
+
+        let request = SignupRequest {
+            captcha_token: None,
+            ..signup("John Doe", "john@example.com")
+        };
+        let response = client.post("/api/v1/signup").json(&request).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        // Nothing was created, so the same email can still sign up
+        let response = client
+            .post("/api/v1/signup")
+            .json(&signup("John Doe", "john@example.com"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+    }
 }
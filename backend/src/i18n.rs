@@ -0,0 +1,270 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use fluent_langneg::{accepted_languages, negotiate_languages, NegotiationStrategy};
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, PatternElement};
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::LazyLock;
+use unic_langid::LanguageIdentifier;
+
+// Localization - Single Responsibility Principle
+// Translates API error messages with Fluent bundles keyed by message id.
+// Layers below the handlers keep raising English messages; the English
+// bundle is the reference used to recognize a message and its variable,
+// which is then rendered in the language negotiated from Accept-Language.
+// Messages missing from the catalog are returned unchanged
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Fluent resources by language; `en` must come first
+const RESOURCES: [(&str, &str); 3] = [
+    ("en", include_str!("../locales/en/errors.ftl")),
+    ("pt-BR", include_str!("../locales/pt-BR/errors.ftl")),
+    ("es", include_str!("../locales/es/errors.ftl")),
+];
+
+/// An English message split around its variable, if it has one
+#[derive(Debug, Clone, PartialEq)]
+struct Template {
+    id: String,
+    prefix: String,
+    /// Name of the variable and the text after it
+    variable: Option<(String, String)>,
+}
+
+impl Template {
+    /// The template of a message made of text and at most one variable
+    fn of(id: &str, elements: &[PatternElement<&str>]) -> Option<Self> {
+        let mut template = Template {
+            id: id.to_string(),
+            prefix: String::new(),
+            variable: None,
+        };
+        for element in elements {
+            match (element, &mut template.variable) {
+                (PatternElement::TextElement { value }, None) => template.prefix.push_str(value),
+                (PatternElement::TextElement { value }, Some((_, suffix))) => suffix.push_str(value),
+                (
+                    PatternElement::Placeable {
+                        expression: Expression::Inline(InlineExpression::VariableReference { id }),
+                    },
+                    None,
+                ) => template.variable = Some((id.name.to_string(), String::new())),
+                _ => return None,
+            }
+        }
+        Some(template)
+    }
+
+    /// The value of the variable when `message` was rendered from this template
+    fn matches<'m>(&self, message: &'m str) -> Option<Option<&'m str>> {
+        match &self.variable {
+            None => (message == self.prefix).then_some(None),
+            Some((_, suffix)) => message
+                .strip_prefix(self.prefix.as_str())?
+                .strip_suffix(suffix.as_str())
+                .filter(|value| !value.is_empty())
+                .map(Some),
+        }
+    }
+}
+
+/// Translates messages into the supported languages
+pub struct Localizer {
+    languages: Vec<LanguageIdentifier>,
+    bundles: HashMap<LanguageIdentifier, FluentBundle<FluentResource>>,
+    templates: Vec<Template>,
+}
+
+impl Localizer {
+    pub fn new() -> Self {
+        let mut languages = Vec::new();
+        let mut bundles = HashMap::new();
+        let mut templates = Vec::new();
+
+        for (tag, source) in RESOURCES {
+            let language: LanguageIdentifier = tag.parse().expect("valid language tag");
+            let resource = FluentResource::try_new(source.to_string())
+                .unwrap_or_else(|(_, errors)| panic!("Invalid {} messages: {:?}", tag, errors));
+            if tag == DEFAULT_LANGUAGE {
+                templates = resource
+                    .entries()
+                    .filter_map(|entry| match entry {
+                        Entry::Message(message) => {
+                            Template::of(message.id.name, &message.value.as_ref()?.elements)
+                        }
+                        _ => None,
+                    })
+                    .collect();
+            }
+
+            let mut bundle = FluentBundle::new_concurrent(vec![language.clone()]);
+            // Plain text responses; no Unicode isolation marks around variables
+            bundle.set_use_isolating(false);
+            bundle
+                .add_resource(resource)
+                .unwrap_or_else(|errors| panic!("Duplicate {} messages: {:?}", tag, errors));
+            languages.push(language.clone());
+            bundles.insert(language, bundle);
+        }
+
+        Localizer {
+            languages,
+            bundles,
+            templates,
+        }
+    }
+
+    /// The supported language best matching an `Accept-Language` header
+    pub fn negotiate(&self, accept_language: Option<&str>) -> &LanguageIdentifier {
+        let requested = accepted_languages::parse(accept_language.unwrap_or_default());
+        let default = &self.languages[0];
+        negotiate_languages(
+            &requested,
+            &self.languages,
+            Some(default),
+            NegotiationStrategy::Lookup,
+        )
+        .first()
+        .copied()
+        .unwrap_or(default)
+    }
+
+    /// `message` in `language`, or unchanged when it is not in the catalog
+    pub fn translate(&self, message: &str, language: &LanguageIdentifier) -> String {
+        self.try_translate(message, language)
+            .unwrap_or_else(|| message.to_string())
+    }
+
+    fn try_translate(&self, message: &str, language: &LanguageIdentifier) -> Option<String> {
+        let (template, value) = self
+            .templates
+            .iter()
+            .find_map(|template| Some((template, template.matches(message)?)))?;
+        let bundle = self.bundles.get(language)?;
+        let pattern = bundle.get_message(&template.id)?.value()?;
+
+        let mut args = FluentArgs::new();
+        if let (Some((name, _)), Some(value)) = (&template.variable, value) {
+            args.set(name.as_str(), value.to_string());
+        }
+        let mut errors = Vec::new();
+        let translated = bundle.format_pattern(pattern, Some(&args), &mut errors);
+        errors.is_empty().then(|| translated.into_owned())
+    }
+}
+
+impl Default for Localizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Localizer of the embedded message catalog
+pub static LOCALIZER: LazyLock<Localizer> = LazyLock::new(Localizer::new);
+
+/// Language negotiated from the request's Accept-Language, for handlers
+/// whose successful responses carry messages, e.g. per-item errors
+pub struct RequestLanguage(pub &'static LanguageIdentifier);
+
+impl RequestLanguage {
+    pub fn translate(&self, message: &str) -> String {
+        LOCALIZER.translate(message, self.0)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestLanguage {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RequestLanguage(LOCALIZER.negotiate(request.headers().get_one("Accept-Language"))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn language(tag: &str) -> LanguageIdentifier {
+        tag.parse().unwrap()
+    }
+
+    #[test]
+    fn test_negotiate_accept_language() {
+        let localizer = Localizer::new();
+        assert_eq!(localizer.negotiate(None), &language("en"));
+        assert_eq!(localizer.negotiate(Some("pt-BR,pt;q=0.9,en;q=0.8")), &language("pt-BR"));
+        assert_eq!(localizer.negotiate(Some("pt")), &language("pt-BR"));
+        assert_eq!(localizer.negotiate(Some("fr-FR, es;q=0.5")), &language("es"));
+        assert_eq!(localizer.negotiate(Some("de")), &language("en"));
+    }
+
+    #[test]
+    fn test_translate_messages_with_variables() {
+        let localizer = Localizer::new();
+        let pt = language("pt-BR");
+
+        assert_eq!(localizer.translate("Name cannot be empty", &pt), "O nome não pode ficar vazio");
+        assert_eq!(
+            localizer.translate("User with id 42 not found", &pt),
+            "Usuário com id 42 não encontrado"
+        );
+        assert_eq!(
+            localizer.translate("User with id 42 is already anonymized", &language("es")),
+            "El usuario con id 42 ya fue anonimizado"
+        );
+        assert_eq!(
+            localizer.translate("User with id 42 not found", &language("en")),
+            "User with id 42 not found"
+        );
+    }
+
+    #[test]
+    fn test_unknown_messages_are_unchanged() {
+        let localizer = Localizer::new();
+        let es = language("es");
+        assert_eq!(localizer.translate("connection refused", &es), "connection refused");
+        assert_eq!(localizer.translate("User with id  not found", &es), "User with id  not found");
+    }
+
+    #[test]
+    fn test_every_language_translates_every_message() {
+        let localizer = Localizer::new();
+        for language in &localizer.languages {
+            let bundle = &localizer.bundles[language];
+            for template in &localizer.templates {
+                assert!(bundle.has_message(&template.id), "{} lacks {}", language, template.id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalog_matches_shared_validation_messages() {
+        let localizer = Localizer::new();
+        let es = language("es");
+        for message in [
+            shared::validation::EmailError::Empty.to_string(),
+            shared::validation::EmailError::InvalidFormat.to_string(),
+            shared::password::PasswordError::Empty.to_string(),
+            shared::password::PasswordError::TooShort.to_string(),
+            shared::validation::PhoneError::InvalidFormat.to_string(),
+            shared::validation::AddressError::StreetEmpty.to_string(),
+            shared::validation::AddressError::CityEmpty.to_string(),
+            shared::validation::AddressError::InvalidPostalCode.to_string(),
+            shared::validation::AddressError::InvalidCountry.to_string(),
+            shared::validation::AddressError::TooLong.to_string(),
+            shared::validation::UsernameError::TooShort.to_string(),
+            shared::validation::UsernameError::TooLong.to_string(),
+            shared::validation::UsernameError::InvalidFormat.to_string(),
+            shared::validation::TagError::Empty.to_string(),
+            shared::validation::TagError::TooLong.to_string(),
+            shared::validation::TagError::InvalidFormat.to_string(),
+            shared::validation::TagError::TooMany.to_string(),
+        ] {
+            assert_ne!(localizer.translate(&message, &es), message);
+        }
+    }
+}
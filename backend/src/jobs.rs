@@ -0,0 +1,621 @@
+use crate::config::RetryConfig;
+use async_trait::async_trait;
+use crate::domain::{DomainError, RepositoryError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_postgres::{Client, Row};
+
+// Background jobs - Single Responsibility Principle
+// Work that should not run on the request path (webhook deliveries, emails,
+// cleanups, ...) is stored in the `jobs` table and executed by a worker loop.
+// New job types only need a JobHandler, the queue itself never changes
+
+/// Lifecycle of a job row
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(JobStatus::Pending),
+            "running" => Ok(JobStatus::Running),
+            "completed" => Ok(JobStatus::Completed),
+            "failed" => Ok(JobStatus::Failed),
+            other => Err(format!("Unknown job status '{}'", other)),
+        }
+    }
+}
+
+/// A unit of background work
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Job {
+    pub id: i64,
+    pub kind: String,
+    pub payload: Value,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    /// RFC 3339 timestamp of the earliest next run
+    pub run_at: String,
+    pub created_at: String,
+}
+
+/// A job as the admin API lists it. The payload is left out: emails and
+/// webhook deliveries carry addresses, user data and signed tokens
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobResponse {
+    pub id: i64,
+    pub kind: String,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub run_at: String,
+    pub created_at: String,
+}
+
+impl From<Job> for JobResponse {
+    fn from(job: Job) -> Self {
+        JobResponse {
+            id: job.id,
+            kind: job.kind,
+            status: job.status,
+            attempts: job.attempts,
+            max_attempts: job.max_attempts,
+            last_error: job.last_error,
+            run_at: job.run_at,
+            created_at: job.created_at,
+        }
+    }
+}
+
+/// Executes one kind of job - Open/Closed Principle
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    /// Stable job type, e.g. `webhook.deliver`
+    fn kind(&self) -> &'static str;
+
+    /// Backoff policy applied when `run` fails
+    fn retry(&self) -> RetryConfig {
+        RetryConfig::default()
+    }
+
+    async fn run(&self, payload: &Value) -> Result<(), String>;
+}
+
+/// Job storage - Dependency Inversion Principle
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    async fn enqueue(&self, kind: &str, payload: &Value, max_attempts: i32) -> Result<Job, RepositoryError>;
+    /// Atomically mark the oldest due pending job as running and return it
+    async fn claim_next(&self) -> Result<Option<Job>, RepositoryError>;
+    async fn complete(&self, id: i64) -> Result<(), RepositoryError>;
+    async fn retry_later(&self, id: i64, error: &str, delay: Duration) -> Result<(), RepositoryError>;
+    async fn fail(&self, id: i64, error: &str) -> Result<(), RepositoryError>;
+    /// Delete completed and failed jobs last touched more than `older_than` ago
+    async fn purge_finished(&self, older_than: Duration) -> Result<u64, RepositoryError>;
+    /// Return jobs stuck in `running` for more than `older_than` to the queue
+    async fn requeue_stale(&self, older_than: Duration) -> Result<u64, RepositoryError>;
+    async fn find_page(
+        &self,
+        status: Option<JobStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Job>, RepositoryError>;
+}
+
+const JOB_COLUMNS: &str = "id, kind, payload, status, attempts, max_attempts, last_error,
+    to_char(run_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"'),
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"')";
+
+/// PostgreSQL implementation of JobRepository
+pub struct PostgresJobRepository {
+    client: Arc<Client>,
+}
+
+impl PostgresJobRepository {
+    pub fn new(client: Arc<Client>) -> Self {
+        PostgresJobRepository { client }
+    }
+
+    fn to_job(row: &Row) -> Job {
+        let status: String = row.get(3);
+        Job {
+            id: row.get(0),
+            kind: row.get(1),
+            payload: row.get(2),
+            status: status.parse().unwrap_or(JobStatus::Failed),
+            attempts: row.get(4),
+            max_attempts: row.get(5),
+            last_error: row.get(6),
+            run_at: row.get(7),
+            created_at: row.get(8),
+        }
+    }
+
+    async fn execute_query(
+        &self,
+        query: &str,
+        params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    ) -> Result<u64, RepositoryError> {
+        self.client
+            .execute(query, params)
+            .await
+            .map_err(RepositoryError::from)
+    }
+}
+
+#[async_trait]
+impl JobRepository for PostgresJobRepository {
+    async fn enqueue(&self, kind: &str, payload: &Value, max_attempts: i32) -> Result<Job, RepositoryError> {
+        let row = self
+            .client
+            .query_one(
+                &format!(
+                    "INSERT INTO jobs (kind, payload, max_attempts) VALUES ($1, $2, $3) RETURNING {}",
+                    JOB_COLUMNS
+                ),
+                &[&kind, payload, &max_attempts],
+            )
+            .await?;
+
+        Ok(Self::to_job(&row))
+    }
+
+    // SKIP LOCKED lets several workers (or backend instances) poll the same table
+    async fn claim_next(&self) -> Result<Option<Job>, RepositoryError> {
+        let row = self
+            .client
+            .query_opt(
+                &format!(
+                    "UPDATE jobs SET status = 'running', attempts = attempts + 1, updated_at = NOW()
+                     WHERE id = (
+                         SELECT id FROM jobs
+                         WHERE status = 'pending' AND run_at <= NOW()
+                         ORDER BY run_at, id
+                         FOR UPDATE SKIP LOCKED
+                         LIMIT 1
+                     )
+                     RETURNING {}",
+                    JOB_COLUMNS
+                ),
+                &[],
+            )
+            .await?;
+
+        Ok(row.as_ref().map(Self::to_job))
+    }
+
+    async fn complete(&self, id: i64) -> Result<(), RepositoryError> {
+        self.execute_query(
+            "UPDATE jobs SET status = 'completed', last_error = NULL, updated_at = NOW() WHERE id = $1",
+            &[&id],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn retry_later(&self, id: i64, error: &str, delay: Duration) -> Result<(), RepositoryError> {
+        self.execute_query(
+            "UPDATE jobs SET status = 'pending', last_error = $2,
+                 run_at = NOW() + make_interval(secs => $3), updated_at = NOW()
+             WHERE id = $1",
+            &[&id, &error, &delay.as_secs_f64()],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn fail(&self, id: i64, error: &str) -> Result<(), RepositoryError> {
+        self.execute_query(
+            "UPDATE jobs SET status = 'failed', last_error = $2, updated_at = NOW() WHERE id = $1",
+            &[&id, &error],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn purge_finished(&self, older_than: Duration) -> Result<u64, RepositoryError> {
+        self.execute_query(
+            "DELETE FROM jobs
+             WHERE status IN ('completed', 'failed')
+               AND updated_at < NOW() - make_interval(secs => $1)",
+            &[&older_than.as_secs_f64()],
+        )
+        .await
+    }
+
+    async fn requeue_stale(&self, older_than: Duration) -> Result<u64, RepositoryError> {
+        self.execute_query(
+            "UPDATE jobs SET status = 'pending', last_error = 'requeued after stalling',
+                 run_at = NOW(), updated_at = NOW()
+             WHERE status = 'running' AND updated_at < NOW() - make_interval(secs => $1)",
+            &[&older_than.as_secs_f64()],
+        )
+        .await
+    }
+
+    async fn find_page(
+        &self,
+        status: Option<JobStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Job>, RepositoryError> {
+        let status = status.map(|status| status.as_str());
+        let jobs = self
+            .client
+            .query(
+                &format!(
+                    "SELECT {} FROM jobs
+                     WHERE $1::TEXT IS NULL OR status = $1
+                     ORDER BY id DESC LIMIT $2 OFFSET $3",
+                    JOB_COLUMNS
+                ),
+                &[&status, &limit, &offset],
+            )
+            .await?
+            .iter()
+            .map(Self::to_job)
+            .collect();
+
+        Ok(jobs)
+    }
+}
+
+/// The job queue: enqueues work and runs it through the registered handlers
+pub struct JobQueue {
+    repository: Arc<dyn JobRepository>,
+    handlers: HashMap<&'static str, Arc<dyn JobHandler>>,
+}
+
+impl JobQueue {
+    pub fn new(repository: Arc<dyn JobRepository>) -> Self {
+        JobQueue {
+            repository,
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Register the handler for one job kind
+    pub fn with_handler(mut self, handler: Arc<dyn JobHandler>) -> Self {
+        self.handlers.insert(handler.kind(), handler);
+        self
+    }
+
+    /// Store a job to be run by the worker as soon as possible
+    pub async fn enqueue(&self, kind: &str, payload: Value) -> Result<Job, DomainError> {
+        let handler = self
+            .handlers
+            .get(kind)
+            .ok_or_else(|| DomainError::Internal(format!("No handler registered for job kind '{}'", kind)))?;
+        let max_attempts = i32::try_from(handler.retry().max_retries)
+            .unwrap_or(i32::MAX)
+            .saturating_add(1);
+
+        Ok(self.repository.enqueue(kind, &payload, max_attempts).await?)
+    }
+
+    /// Jobs for the admin inspection endpoint, newest first
+    pub async fn get_jobs(
+        &self,
+        status: Option<JobStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Job>, DomainError> {
+        Ok(self.repository.find_page(status, limit, offset).await?)
+    }
+
+    /// Claim and run the next due job; returns whether there was one
+    pub async fn run_next(&self) -> Result<bool, DomainError> {
+        let Some(job) = self.repository.claim_next().await? else {
+            return Ok(false);
+        };
+
+        let Some(handler) = self.handlers.get(job.kind.as_str()) else {
+            let error = format!("No handler registered for job kind '{}'", job.kind);
+            eprintln!("Job {} failed: {}", job.id, error);
+            self.repository.fail(job.id, &error).await?;
+            return Ok(true);
+        };
+
+        match handler.run(&job.payload).await {
+            Ok(()) => self.repository.complete(job.id).await?,
+            Err(e) if job.attempts < job.max_attempts => {
+                let delay = handler
+                    .retry()
+                    .delay_for_attempt(u32::try_from(job.attempts - 1).unwrap_or_default());
+                eprintln!(
+                    "Job {} ({}) failed ({}), retrying in {:?} (attempt {}/{})",
+                    job.id, job.kind, e, delay, job.attempts, job.max_attempts
+                );
+                self.repository.retry_later(job.id, &e, delay).await?;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Giving up on job {} ({}) after {} attempts: {}",
+                    job.id, job.kind, job.attempts, e
+                );
+                self.repository.fail(job.id, &e).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Run jobs on a background task, polling every `poll_interval` while idle
+    pub fn spawn_worker(self: Arc<Self>, poll_interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match self.run_next().await {
+                    Ok(true) => continue,
+                    Ok(false) => {}
+                    Err(e) => eprintln!("Job worker error: {}", e),
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // In-memory job storage for tests; retry delays are not simulated
+    #[derive(Default)]
+    pub struct MockJobRepository {
+        pub jobs: Mutex<Vec<Job>>,
+    }
+
+    impl MockJobRepository {
+        pub fn new() -> Self {
+            MockJobRepository {
+                jobs: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn set_status(&self, id: i64, status: JobStatus, error: Option<&str>) {
+            let mut jobs = self.jobs.lock().unwrap();
+            if let Some(job) = jobs.iter_mut().find(|job| job.id == id) {
+                job.status = status;
+                job.last_error = error.map(str::to_string);
+            }
+        }
+    }
+
+    #[async_trait]
+    impl JobRepository for MockJobRepository {
+        async fn enqueue(&self, kind: &str, payload: &Value, max_attempts: i32) -> Result<Job, RepositoryError> {
+            let mut jobs = self.jobs.lock().unwrap();
+            let job = Job {
+                id: jobs.len() as i64 + 1,
+                kind: kind.to_string(),
+                payload: payload.clone(),
+                status: JobStatus::Pending,
+                attempts: 0,
+                max_attempts,
+                last_error: None,
+                run_at: String::new(),
+                created_at: String::new(),
+            };
+            jobs.push(job.clone());
+            Ok(job)
+        }
+
+        async fn claim_next(&self) -> Result<Option<Job>, RepositoryError> {
+            let mut jobs = self.jobs.lock().unwrap();
+            Ok(jobs
+                .iter_mut()
+                .find(|job| job.status == JobStatus::Pending)
+                .map(|job| {
+                    job.status = JobStatus::Running;
+                    job.attempts += 1;
+                    job.clone()
+                }))
+        }
+
+        async fn complete(&self, id: i64) -> Result<(), RepositoryError> {
+            self.set_status(id, JobStatus::Completed, None);
+            Ok(())
+        }
+
+        async fn retry_later(&self, id: i64, error: &str, _delay: Duration) -> Result<(), RepositoryError> {
+            self.set_status(id, JobStatus::Pending, Some(error));
+            Ok(())
+        }
+
+        async fn fail(&self, id: i64, error: &str) -> Result<(), RepositoryError> {
+            self.set_status(id, JobStatus::Failed, Some(error));
+            Ok(())
+        }
+
+        // The mock does not track timestamps, so every job counts as old enough
+        async fn purge_finished(&self, _older_than: Duration) -> Result<u64, RepositoryError> {
+            let mut jobs = self.jobs.lock().unwrap();
+            let before = jobs.len();
+            jobs.retain(|job| !matches!(job.status, JobStatus::Completed | JobStatus::Failed));
+            Ok((before - jobs.len()) as u64)
+        }
+
+        async fn requeue_stale(&self, _older_than: Duration) -> Result<u64, RepositoryError> {
+            let mut jobs = self.jobs.lock().unwrap();
+            let mut requeued = 0;
+            for job in jobs.iter_mut().filter(|job| job.status == JobStatus::Running) {
+                job.status = JobStatus::Pending;
+                requeued += 1;
+            }
+            Ok(requeued)
+        }
+
+        async fn find_page(
+            &self,
+            status: Option<JobStatus>,
+            limit: i64,
+            offset: i64,
+        ) -> Result<Vec<Job>, RepositoryError> {
+            let jobs = self.jobs.lock().unwrap();
+            Ok(jobs
+                .iter()
+                .rev()
+                .filter(|job| status.is_none_or(|status| job.status == status))
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+    }
+
+    /// Fails the first `failures` runs
+    struct FlakyHandler {
+        failures: Mutex<u32>,
+    }
+
+    #[async_trait]
+    impl JobHandler for FlakyHandler {
+        fn kind(&self) -> &'static str {
+            "test.flaky"
+        }
+
+        fn retry(&self) -> RetryConfig {
+            RetryConfig {
+                max_retries: 2,
+                initial_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(5),
+            }
+        }
+
+        async fn run(&self, _payload: &Value) -> Result<(), String> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err("temporary failure".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    fn queue_with_failures(failures: u32) -> (Arc<MockJobRepository>, JobQueue) {
+        let repository = Arc::new(MockJobRepository::new());
+        let queue = JobQueue::new(repository.clone()).with_handler(Arc::new(FlakyHandler {
+            failures: Mutex::new(failures),
+        }));
+        (repository, queue)
+    }
+
+    #[test]
+    fn test_job_status_round_trips() {
+        for status in [
+            JobStatus::Pending,
+            JobStatus::Running,
+            JobStatus::Completed,
+            JobStatus::Failed,
+        ] {
+            assert_eq!(status.as_str().parse::<JobStatus>(), Ok(status));
+        }
+        assert!("done".parse::<JobStatus>().is_err());
+    }
+
+    #[test]
+    fn test_response_leaves_the_payload_out() {
+        let job = Job {
+            id: 1,
+            kind: "email.send".to_string(),
+            payload: serde_json::json!({"to": "john@example.com", "subject": "Verify your email"}),
+            status: JobStatus::Pending,
+            attempts: 0,
+            max_attempts: 3,
+            last_error: None,
+            run_at: "2026-01-01T00:00:00Z".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let json = serde_json::to_string(&JobResponse::from(job)).unwrap();
+        assert!(json.contains(r#""kind":"email.send""#));
+        assert!(!json.contains("payload") && !json.contains("john@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_unknown_kind_is_rejected() {
+        let (_, queue) = queue_with_failures(0);
+        assert!(queue.enqueue("test.unknown", Value::Null).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_next_completes_job() {
+        let (repository, queue) = queue_with_failures(0);
+        let job = queue.enqueue("test.flaky", Value::Null).await.unwrap();
+        assert_eq!(job.max_attempts, 3);
+
+        assert!(queue.run_next().await.unwrap());
+        assert!(!queue.run_next().await.unwrap());
+        assert_eq!(repository.jobs.lock().unwrap()[0].status, JobStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_is_retried() {
+        let (repository, queue) = queue_with_failures(2);
+        queue.enqueue("test.flaky", Value::Null).await.unwrap();
+
+        queue.run_next().await.unwrap();
+        {
+            let jobs = repository.jobs.lock().unwrap();
+            assert_eq!(jobs[0].status, JobStatus::Pending);
+            assert_eq!(jobs[0].last_error.as_deref(), Some("temporary failure"));
+        }
+
+        queue.run_next().await.unwrap();
+        queue.run_next().await.unwrap();
+        let jobs = repository.jobs.lock().unwrap();
+        assert_eq!(jobs[0].status, JobStatus::Completed);
+        assert_eq!(jobs[0].attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_job_fails_after_max_attempts() {
+        let (repository, queue) = queue_with_failures(10);
+        queue.enqueue("test.flaky", Value::Null).await.unwrap();
+
+        while queue.run_next().await.unwrap() {}
+
+        let jobs = repository.jobs.lock().unwrap();
+        assert_eq!(jobs[0].status, JobStatus::Failed);
+        assert_eq!(jobs[0].attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_jobs_filters_by_status() {
+        let (_, queue) = queue_with_failures(0);
+        queue.enqueue("test.flaky", Value::Null).await.unwrap();
+        queue.enqueue("test.flaky", Value::Null).await.unwrap();
+        queue.run_next().await.unwrap();
+
+        let pending = queue.get_jobs(Some(JobStatus::Pending), 10, 0).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, 2);
+        assert_eq!(queue.get_jobs(None, 10, 0).await.unwrap().len(), 2);
+    }
+}
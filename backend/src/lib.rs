@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate rocket;
+
+// Backend library - Single Responsibility Principle
+// Declares the application modules so every binary (the API server in
+// main.rs, the admin CLI in bin/admin.rs) builds on the same layers
+
+pub mod app;
+pub mod audit;
+pub mod backup;
+pub mod body;
+pub mod breach;
+pub mod cache;
+pub mod caching;
+pub mod captcha;
+pub mod change_feed;
+pub mod client_errors;
+pub mod config;
+pub mod crypto;
+pub mod db;
+pub mod domain;
+pub mod envelope;
+pub mod errors;
+pub mod event_stream;
+pub mod events;
+pub mod export;
+pub mod grpc;
+pub mod i18n;
+pub mod handlers;
+pub mod jobs;
+pub mod mailer;
+pub mod maintenance;
+pub mod models;
+pub mod negotiation;
+pub mod notifications;
+pub mod pagination;
+pub mod password_history;
+pub mod preferences;
+pub mod query_retry;
+pub mod query_timeout;
+pub mod rate_limit;
+pub mod replicas;
+pub mod repository;
+pub mod request_id;
+pub mod request_log;
+pub mod scheduler;
+pub mod security;
+pub mod secrets;
+pub mod seed;
+pub mod service;
+pub mod storage;
+#[cfg(test)]
+mod test_support;
+pub mod unit_of_work;
+pub mod verification;
+pub mod versioning;
+pub mod webhooks;
@@ -0,0 +1,282 @@
+use crate::config::{MailTransport, MailerConfig};
+use crate::events::{EventSubscriber, UserEvent};
+use crate::jobs::{JobHandler, JobQueue};
+use askama::Template;
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rocket::serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+// Email - Dependency Inversion Principle
+// Features build an Email from a template and hand it to the job queue;
+// the configured Mailer (SMTP or log) does the actual sending
+
+pub const SEND_EMAIL_JOB_KIND: &str = "email.send";
+
+/// A rendered plain-text email
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Template)]
+#[template(path = "emails/welcome.txt")]
+struct WelcomeTemplate<'a> {
+    name: &'a str,
+    app_url: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "emails/verification.txt")]
+struct VerificationTemplate<'a> {
+    name: &'a str,
+    link: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "emails/password_reset.txt")]
+struct PasswordResetTemplate<'a> {
+    name: &'a str,
+    link: &'a str,
+}
+
+fn render(to: &str, subject: &str, template: &impl Template) -> Result<Email, String> {
+    Ok(Email {
+        to: to.to_string(),
+        subject: subject.to_string(),
+        body: template.render().map_err(|e| e.to_string())?,
+    })
+}
+
+pub fn welcome_email(to: &str, name: &str, app_url: &str) -> Result<Email, String> {
+    render(to, "Welcome!", &WelcomeTemplate { name, app_url })
+}
+
+pub fn verification_email(to: &str, name: &str, link: &str) -> Result<Email, String> {
+    render(to, "Confirm your email address", &VerificationTemplate { name, link })
+}
+
+// Password-reset links need tokens that do not exist yet
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn password_reset_email(to: &str, name: &str, link: &str) -> Result<Email, String> {
+    render(to, "Reset your password", &PasswordResetTemplate { name, link })
+}
+
+/// Sends emails - implementations are selected by `MAIL_TRANSPORT`
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> Result<(), String>;
+}
+
+/// Writes emails to the application log instead of sending them
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> Result<(), String> {
+        println!(
+            "[mail] to={} subject={:?}\n{}",
+            email.to, email.subject, email.body
+        );
+        Ok(())
+    }
+}
+
+/// Sends emails through an SMTP relay using STARTTLS
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &MailerConfig) -> Result<Self, String> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp.host)
+            .map_err(|e| e.to_string())?
+            .port(config.smtp.port);
+        if let (Some(username), Some(password)) = (&config.smtp.username, &config.smtp.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.expose().to_string()));
+        }
+
+        Ok(SmtpMailer {
+            transport: builder.build(),
+            from: config.from.parse().map_err(|e| format!("Invalid MAIL_FROM: {}", e))?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &Email) -> Result<(), String> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(email.to.parse().map_err(|e| format!("Invalid recipient: {}", e))?)
+            .subject(&email.subject)
+            .body(email.body.clone())
+            .map_err(|e| e.to_string())?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Build the mailer selected in the configuration
+pub fn build_mailer(config: &MailerConfig) -> Result<Arc<dyn Mailer>, String> {
+    Ok(match config.transport {
+        MailTransport::Log => Arc::new(LogMailer),
+        MailTransport::Smtp => Arc::new(SmtpMailer::new(config)?),
+    })
+}
+
+/// Job handler sending one queued email
+pub struct SendEmailJob {
+    mailer: Arc<dyn Mailer>,
+}
+
+impl SendEmailJob {
+    pub fn new(mailer: Arc<dyn Mailer>) -> Self {
+        SendEmailJob { mailer }
+    }
+}
+
+#[async_trait]
+impl JobHandler for SendEmailJob {
+    fn kind(&self) -> &'static str {
+        SEND_EMAIL_JOB_KIND
+    }
+
+    async fn run(&self, payload: &Value) -> Result<(), String> {
+        let email: Email = serde_json::from_value(payload.clone()).map_err(|e| e.to_string())?;
+        self.mailer.send(&email).await
+    }
+}
+
+/// Queues a welcome email for every new user
+pub struct WelcomeEmailSubscriber {
+    jobs: Arc<JobQueue>,
+    app_url: String,
+}
+
+impl WelcomeEmailSubscriber {
+    pub fn new(jobs: Arc<JobQueue>, app_url: String) -> Self {
+        WelcomeEmailSubscriber { jobs, app_url }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for WelcomeEmailSubscriber {
+    fn name(&self) -> &'static str {
+        "welcome-email"
+    }
+
+    async fn handle(&self, event: &UserEvent) {
+        let UserEvent::Created { user } = event else {
+            return;
+        };
+
+        let email = match welcome_email(&user.email, &user.name, &self.app_url) {
+            Ok(email) => email,
+            Err(e) => {
+                eprintln!("Failed to render welcome email: {}", e);
+                return;
+            }
+        };
+        let payload = serde_json::to_value(&email).unwrap_or_default();
+        if let Err(e) = self.jobs.enqueue(SEND_EMAIL_JOB_KIND, payload).await {
+            eprintln!("Failed to queue welcome email to {}: {}", user.email, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::tests::MockJobRepository;
+    use crate::models::{UserResponse, UserStatus};
+    use std::sync::Mutex;
+
+    struct RecordingMailer {
+        sent: Mutex<Vec<Email>>,
+    }
+
+    #[async_trait]
+    impl Mailer for RecordingMailer {
+        async fn send(&self, email: &Email) -> Result<(), String> {
+            self.sent.lock().unwrap().push(email.clone());
+            Ok(())
+        }
+    }
+
+    fn user() -> UserResponse {
+        UserResponse {
+            id: 1,
+            name: "John Doe".to_string(),
+            email: "john@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        }
+    }
+
+    #[test]
+    fn test_templates_render() {
+        let email = welcome_email("john@example.com", "John", "https://app.example.com").unwrap();
+        assert_eq!(email.subject, "Welcome!");
+        assert!(email.body.starts_with("Hi John,"));
+        assert!(email.body.contains("https://app.example.com"));
+
+        let email = verification_email("john@example.com", "John", "https://x/verify?t=1").unwrap();
+        assert!(email.body.contains("https://x/verify?t=1"));
+
+        let email = password_reset_email("john@example.com", "John", "https://x/reset?t=1").unwrap();
+        assert_eq!(email.subject, "Reset your password");
+        assert!(email.body.contains("https://x/reset?t=1"));
+    }
+
+    #[test]
+    fn test_build_mailer_rejects_invalid_sender() {
+        let config = MailerConfig {
+            transport: MailTransport::Smtp,
+            from: "not an address".to_string(),
+            ..MailerConfig::default()
+        };
+        assert!(build_mailer(&config).is_err());
+        assert!(build_mailer(&MailerConfig::default()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_welcome_email_is_queued_and_sent() {
+        let mailer = Arc::new(RecordingMailer {
+            sent: Mutex::new(Vec::new()),
+        });
+        let jobs = Arc::new(
+            JobQueue::new(Arc::new(MockJobRepository::new()))
+                .with_handler(Arc::new(SendEmailJob::new(mailer.clone()))),
+        );
+        let subscriber = WelcomeEmailSubscriber::new(jobs.clone(), "https://app.example.com".to_string());
+
+        subscriber.handle(&UserEvent::Deleted { id: 1 }).await;
+        assert!(!jobs.run_next().await.unwrap());
+
+        subscriber.handle(&UserEvent::Created { user: user() }).await;
+        assert!(jobs.run_next().await.unwrap());
+
+        let sent = mailer.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "john@example.com");
+        assert!(sent[0].body.starts_with("Hi John Doe,"));
+    }
+}
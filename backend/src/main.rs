@@ -1,15 +1,9 @@
 #[macro_use]
 extern crate rocket;
 
-mod db;
-mod handlers;
-mod models;
-mod repository;
-mod service;
-
-use repository::PostgresUserRepository;
-use rocket_cors::{AllowedOrigins, CorsOptions};
-use service::UserService;
+use backend::app::{self, Repositories};
+use backend::config::AppConfig;
+use backend::{crypto, db};
 use std::sync::Arc;
 
 /// Main entry point - follows Dependency Inversion Principle
@@ -23,35 +17,31 @@ use std::sync::Arc;
 /// - Dependency Inversion: High-level modules depend on abstractions (UserRepository trait)
 #[launch]
 async fn rocket() -> _ {
-    // Initialize database (connection + schema)
-    let client = db::init_database()
+    // Load configuration from the environment
+    let config = AppConfig::from_env().expect("Invalid configuration");
+    // User names and emails are encrypted at rest once a key is configured
+    let cipher = crypto::FieldCipher::from_config(&config.encryption)
+        .expect("Invalid PII encryption configuration")
+        .map(Arc::new);
+
+    // Initialize database (connection + schema), retrying while Postgres starts up
+    let client = db::init_database(&config.database)
         .await
         .expect("Failed to initialize database");
+    let replicas = db::connect_replicas(&config.database)
+        .await
+        .expect("Failed to connect to the read replicas");
+    // Stuck queries are cancelled from here on; the schema setup above has no limit
+    for connection in std::iter::once(&client).chain(&replicas) {
+        db::limit_statements(connection, &config.database)
+            .await
+            .expect("Failed to set the statement timeout");
+    }
 
-    // Dependency injection - building the application from the inside out
-    // Repository layer (data access)
-    let repository = Arc::new(PostgresUserRepository::new(client));
-
-    // Service layer (business logic)
-    let service = Arc::new(UserService::new(repository));
-
-    // CORS configuration
-    let cors = CorsOptions::default()
-        .allowed_origins(AllowedOrigins::all())
-        .to_cors()
-        .expect("Error while building CORS");
-
-    // Build Rocket application with injected dependencies
-    rocket::build()
-        .manage(service)
-        .mount(
-            "/",
-            routes![
-                handlers::add_user,
-                handlers::get_users,
-                handlers::update_user,
-                handlers::delete_user
-            ],
-        )
-        .attach(cors)
+    // Dependency injection - the repositories are the only Postgres-specific part,
+    // everything above them is assembled in app.rs
+    let repositories = Repositories::postgres(client, replicas, &config.database, cipher);
+    app::build_rocket(config, repositories)
+        .await
+        .expect("Failed to build the application")
 }
@@ -0,0 +1,115 @@
+use crate::config::MaintenanceConfig;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::Request;
+use std::sync::{Arc, RwLock};
+
+// Maintenance mode - Single Responsibility Principle
+// Runtime switch making the API read-only: while it is on, every mutating
+// endpoint answers 503 and reads keep working. Started from the
+// configuration and flipped through the admin endpoint without a restart
+
+/// Message of the 503 answered to writes when no custom one is set
+pub const DEFAULT_MAINTENANCE_MESSAGE: &str = "The service is read-only for maintenance, try again later";
+
+/// Whether maintenance mode is on, with the message shown to users
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct MaintenanceStatus {
+    pub maintenance: bool,
+    /// Custom explanation, e.g. when the service is expected back
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Current maintenance state, shared by the REST API and the gRPC server
+#[derive(Debug, Default)]
+pub struct MaintenanceMode(RwLock<MaintenanceStatus>);
+
+impl MaintenanceMode {
+    pub fn from_config(config: &MaintenanceConfig) -> Self {
+        MaintenanceMode(RwLock::new(MaintenanceStatus {
+            maintenance: config.enabled,
+            message: config.message.clone(),
+        }))
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn set(&self, status: MaintenanceStatus) {
+        let message = status.message.filter(|message| !message.trim().is_empty());
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = MaintenanceStatus { message, ..status };
+    }
+
+    /// Why writes are refused right now; None while the API is writable
+    pub fn refusal(&self) -> Option<String> {
+        let status = self.status();
+        status
+            .maintenance
+            .then(|| status.message.unwrap_or_else(|| DEFAULT_MAINTENANCE_MESSAGE.to_string()))
+    }
+}
+
+/// Request guard of mutating handlers, failing with 503 during maintenance;
+/// always passes when no MaintenanceMode is managed
+pub struct Writable;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Writable {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let refused = request
+            .rocket()
+            .state::<Arc<MaintenanceMode>>()
+            .is_some_and(|mode| mode.refusal().is_some());
+        if refused {
+            Outcome::Error((Status::ServiceUnavailable, ()))
+        } else {
+            Outcome::Success(Writable)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refusal_follows_the_status() {
+        let mode = MaintenanceMode::default();
+        assert_eq!(mode.refusal(), None);
+
+        mode.set(MaintenanceStatus {
+            maintenance: true,
+            message: None,
+        });
+        assert_eq!(mode.refusal().as_deref(), Some(DEFAULT_MAINTENANCE_MESSAGE));
+
+        mode.set(MaintenanceStatus {
+            maintenance: true,
+            message: Some("Back at 14:00 UTC".to_string()),
+        });
+        assert_eq!(mode.refusal().as_deref(), Some("Back at 14:00 UTC"));
+
+        mode.set(MaintenanceStatus::default());
+        assert_eq!(mode.refusal(), None);
+    }
+
+    #[test]
+    fn test_blank_messages_are_dropped() {
+        let mode = MaintenanceMode::from_config(&MaintenanceConfig {
+            enabled: true,
+            message: None,
+        });
+        mode.set(MaintenanceStatus {
+            maintenance: true,
+            message: Some("  ".to_string()),
+        });
+        assert_eq!(mode.status().message, None);
+        assert_eq!(mode.refusal().as_deref(), Some(DEFAULT_MAINTENANCE_MESSAGE));
+    }
+}
@@ -1,4 +1,93 @@
-use rocket::serde::{Deserialize, Serialize};
+use rocket::request::FromParam;
+use rocket::serde::{Deserialize, Deserializer, Serialize};
+use shared::password::validate_password;
+use shared::validation::{
+    normalize_email, normalize_phone, normalize_tags, normalize_username, validate_address, validate_email,
+    validate_phone, validate_tags, validate_username,
+};
+use std::str::FromStr;
+
+/// Account status - suspended users keep their data but are locked out
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    Suspended,
+}
+
+impl UserStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserStatus::Active => "active",
+            UserStatus::Suspended => "suspended",
+        }
+    }
+}
+
+impl FromStr for UserStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "active" => Ok(UserStatus::Active),
+            "suspended" => Ok(UserStatus::Suspended),
+            other => Err(format!("Unknown user status '{}'", other)),
+        }
+    }
+}
+
+/// Domain of the placeholder emails of anonymized users; `.invalid` is
+/// reserved (RFC 2606), so no real address can collide with them
+pub const ANONYMIZED_EMAIL_DOMAIN: &str = "anonymized.invalid";
+pub const ANONYMIZED_NAME: &str = "Anonymized user";
+
+/// Emails of request bodies are normalized as they are read, so two
+/// spellings of one address can never reach the database
+fn deserialize_email<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    String::deserialize(deserializer).map(|email| normalize_email(&email))
+}
+
+/// Usernames of request bodies are normalized like emails; a blank one means none
+fn deserialize_username<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(|username| {
+        username
+            .map(|username| normalize_username(&username))
+            .filter(|username| !username.is_empty())
+    })
+}
+
+/// Postal address of a user; `country` is an ISO 3166-1 alpha-2 code
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde", deny_unknown_fields)]
+pub struct Address {
+    pub street: String,
+    /// Apartment, suite, floor
+    #[serde(default)]
+    pub complement: Option<String>,
+    pub city: String,
+    /// State, province or region
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Empty for countries without postal codes
+    #[serde(default)]
+    pub postal_code: String,
+    pub country: String,
+}
+
+impl Address {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_address(
+            &self.street,
+            self.complement.as_deref(),
+            &self.city,
+            self.region.as_deref(),
+            &self.postal_code,
+            &self.country,
+        )
+        .map_err(|e| e.to_string())
+    }
+}
 
 /// User domain model - Single Responsibility Principle
 /// This struct is only responsible for representing a user entity
@@ -7,8 +96,33 @@ use rocket::serde::{Deserialize, Serialize};
 pub struct User {
     pub id: Option<i32>,
     pub name: String,
+    /// Always lowercase for new users; compared case-insensitively
+    #[serde(deserialize_with = "deserialize_email")]
     pub email: String,
     pub password: String,
+    /// Unique handle of the profile URL (`/users/@username`); once set it never changes
+    #[serde(default, deserialize_with = "deserialize_username")]
+    pub username: Option<String>,
+    /// Only changed through the suspend/activate endpoints, never by updates
+    #[serde(default)]
+    pub status: UserStatus,
+    /// Labels for filtering the list; only changed through the tags endpoint, never by updates
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// RFC 3339 time of the last successful login, never set from request bodies
+    #[serde(default, skip_deserializing)]
+    pub last_login_at: Option<String>,
+    /// RFC 3339 time of the signup, never set from request bodies
+    #[serde(default, skip_deserializing)]
+    pub created_at: Option<String>,
+    /// RFC 3339 time of the last change, sent as `Last-Modified`; never set from request bodies
+    #[serde(default, skip_deserializing)]
+    pub updated_at: Option<String>,
+    /// E.164 phone number, e.g. `+5511912345678`
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub address: Option<Address>,
 }
 
 impl User {
@@ -16,8 +130,16 @@ impl User {
         User {
             id: None,
             name,
-            email,
+            email: normalize_email(&email),
             password,
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
         }
     }
 
@@ -27,29 +149,295 @@ impl User {
             name,
             email,
             password,
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: UserStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// The user with every personal detail replaced by a placeholder.
+    /// The row (and its id) is kept so statistics and references stay valid
+    pub fn anonymized(&self) -> User {
+        let id = self.id.unwrap_or_default();
+        User {
+            id: self.id,
+            name: ANONYMIZED_NAME.to_string(),
+            email: format!("user-{}@{}", id, ANONYMIZED_EMAIL_DOMAIN),
+            // Random and never revealed, so nobody can log in as this user
+            password: uuid::Uuid::new_v4().to_string(),
+            username: None,
+            status: UserStatus::Suspended,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
         }
     }
 
+    pub fn is_anonymized(&self) -> bool {
+        self.email.ends_with(&format!("@{}", ANONYMIZED_EMAIL_DOMAIN))
+    }
+
+    pub fn with_username(mut self, username: Option<String>) -> Self {
+        self.username = username;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_contact(mut self, phone: Option<String>, address: Option<Address>) -> Self {
+        self.phone = phone;
+        self.address = address;
+        self
+    }
+
+    /// Return the user with its email, username and tags normalized (trimmed
+    /// and lowercased) and its phone number stripped of separators; a blank
+    /// username or phone is dropped
+    pub fn normalized(mut self) -> Self {
+        self.email = normalize_email(&self.email);
+        self.username = self
+            .username
+            .map(|username| normalize_username(&username))
+            .filter(|username| !username.is_empty());
+        self.tags = normalize_tags(&self.tags);
+        self.phone = self.phone.map(|phone| normalize_phone(&phone)).filter(|phone| !phone.is_empty());
+        self
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.name.trim().is_empty() {
             return Err("Name cannot be empty".to_string());
         }
-        if self.email.trim().is_empty() {
-            return Err("Email cannot be empty".to_string());
+        validate_email(&self.email).map_err(|e| e.to_string())?;
+        validate_password(&self.password).map_err(|e| e.to_string())?;
+        if let Some(username) = &self.username {
+            validate_username(username).map_err(|e| e.to_string())?;
         }
-        if !self.email.contains('@') {
-            return Err("Invalid email format".to_string());
+        validate_tags(&self.tags).map_err(|e| e.to_string())?;
+        if let Some(phone) = &self.phone {
+            validate_phone(phone).map_err(|e| e.to_string())?;
         }
-        if self.password.trim().is_empty() {
-            return Err("Password cannot be empty".to_string());
-        }
-        if self.password.len() < 6 {
-            return Err("Password must be at least 6 characters".to_string());
+        if let Some(address) = &self.address {
+            address.validate()?;
         }
         Ok(())
     }
 }
 
+/// Public representation of a user returned by the API - never exposes the password
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct UserResponse {
+    pub id: i32,
+    pub name: String,
+    pub email: String,
+    pub username: Option<String>,
+    pub status: UserStatus,
+    pub tags: Vec<String>,
+    pub last_login_at: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub phone: Option<String>,
+    pub address: Option<Address>,
+}
+
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        UserResponse {
+            id: user.id.unwrap_or_default(),
+            name: user.name,
+            email: user.email,
+            username: user.username,
+            status: user.status,
+            tags: user.tags,
+            last_login_at: user.last_login_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            phone: user.phone,
+            address: user.address,
+        }
+    }
+}
+
+/// Body of a self-service signup; unlike admin creation it cannot choose a status
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct SignupRequest {
+    pub name: String,
+    pub email: String,
+    pub password: String,
+    #[serde(default, deserialize_with = "deserialize_username")]
+    pub username: Option<String>,
+    /// Token of the solved CAPTCHA widget; not needed while CAPTCHA_PROVIDER is `none`
+    #[serde(default)]
+    pub captcha_token: Option<String>,
+}
+
+impl From<SignupRequest> for User {
+    fn from(request: SignupRequest) -> Self {
+        User::new(request.name, request.email, request.password).with_username(request.username)
+    }
+}
+
+/// Body of the email verification endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+/// A user found by a fuzzy search, with how closely it matched (0 to 1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserMatch {
+    pub user: User,
+    pub score: f32,
+}
+
+/// Search result returned by the API: the user plus its `match_score`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct UserSearchResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub match_score: f32,
+}
+
+impl From<UserMatch> for UserSearchResponse {
+    fn from(found: UserMatch) -> Self {
+        UserSearchResponse {
+            user: found.user.into(),
+            match_score: found.score,
+        }
+    }
+}
+
+/// Response body of the count endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct CountResponse {
+    pub count: i64,
+}
+
+/// Whether an email is already registered, for the signup form
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct EmailExistsResponse {
+    pub exists: bool,
+}
+
+/// `@username` segment of a profile URL; segments without the `@` are
+/// rejected so `/users/<id>` keeps numeric ids
+pub struct ProfileHandle<'a>(pub &'a str);
+
+impl<'a> FromParam<'a> for ProfileHandle<'a> {
+    type Error = &'a str;
+
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        param.strip_prefix('@').map(ProfileHandle).ok_or(param)
+    }
+}
+
+/// Whether a username can still be claimed, for the live check of the user form
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct UsernameAvailability {
+    /// The username as it would be stored
+    pub username: String,
+    pub available: bool,
+}
+
+/// Body of `PUT /users/<id>/tags`, which replaces every tag of the user
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct SetTagsRequest {
+    pub tags: Vec<String>,
+}
+
+/// A tag and how many users have it, for the filter bar of the list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+/// Body of `POST /users/export`, which exports only the given users
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct ExportRequest {
+    pub ids: Vec<i32>,
+}
+
+/// Body of `PATCH /users/bulk`: one partial change applied to every id.
+/// Omitted fields are left alone, and tags are added and removed rather than
+/// replaced so each user keeps the others
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct BulkUpdateRequest {
+    pub ids: Vec<i32>,
+    #[serde(default)]
+    pub status: Option<UserStatus>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+}
+
+impl BulkUpdateRequest {
+    /// Whether applying the request would change nothing
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.add_tags.is_empty() && self.remove_tags.is_empty()
+    }
+}
+
+/// Outcome of a bulk update for one of its ids: the updated user, or the
+/// error code and message of why it was skipped
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct BulkUpdateResult {
+    pub id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<UserResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Number of users who signed up on a given day (`YYYY-MM-DD`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct DailySignups {
+    pub day: String,
+    pub count: i64,
+}
+
+/// Aggregated user statistics for the admin dashboard
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct UserStats {
+    pub total: i64,
+    pub verified: i64,
+    pub unverified: i64,
+    pub signups_per_day: Vec<DailySignups>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,4 +536,215 @@ mod tests {
             "Password must be at least 6 characters"
         );
     }
+
+    #[test]
+    fn test_validate_rejects_multiple_at_signs() {
+        let user = User::new(
+            "John Doe".to_string(),
+            "a@b@c".to_string(),
+            "password123".to_string(),
+        );
+        assert_eq!(user.validate().unwrap_err(), "Invalid email format");
+
+        let user = User::new("John Doe".to_string(), "@@".to_string(), "password123".to_string());
+        assert_eq!(user.validate().unwrap_err(), "Invalid email format");
+    }
+
+    fn address() -> Address {
+        Address {
+            street: "Av. Paulista, 1000".to_string(),
+            complement: Some("Apto 12".to_string()),
+            city: "São Paulo".to_string(),
+            region: Some("SP".to_string()),
+            postal_code: "01310-100".to_string(),
+            country: "BR".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_phone_and_address() {
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        let valid = user.clone().with_contact(Some("+5511912345678".to_string()), Some(address()));
+        assert_eq!(valid.validate(), Ok(()));
+
+        let bad_phone = user.clone().with_contact(Some("11 91234-5678".to_string()), None);
+        assert_eq!(
+            bad_phone.validate().unwrap_err(),
+            "Phone number must be in international format, e.g. +5511912345678"
+        );
+
+        let bad_address = user.with_contact(
+            None,
+            Some(Address {
+                country: "Brazil".to_string(),
+                ..address()
+            }),
+        );
+        assert_eq!(
+            bad_address.validate().unwrap_err(),
+            "Country must be a two-letter ISO 3166 code, e.g. BR"
+        );
+    }
+
+    #[test]
+    fn test_every_address_field_is_length_checked() {
+        let long = "x".repeat(shared::validation::MAX_ADDRESS_FIELD_LENGTH + 1);
+        for address in [
+            Address { complement: Some(long.clone()), ..address() },
+            Address { region: Some(long.clone()), ..address() },
+        ] {
+            assert_eq!(
+                address.validate().unwrap_err(),
+                shared::validation::AddressError::TooLong.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalized_strips_phone_separators() {
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        let formatted = user.clone().with_contact(Some("+55 (11) 91234-5678".to_string()), None);
+        assert_eq!(formatted.normalized().phone.as_deref(), Some("+5511912345678"));
+
+        let blank = user.with_contact(Some("  ".to_string()), None);
+        assert_eq!(blank.normalized().phone, None);
+    }
+
+    #[test]
+    fn test_usernames_are_normalized_and_validated() {
+        let user: User = serde_json::from_str(
+            r#"{"id":null,"name":"Ana","email":"ana@example.com","password":"password123","username":" @Ana-Silva "}"#,
+        )
+        .unwrap();
+        assert_eq!(user.username.as_deref(), Some("ana-silva"));
+        assert_eq!(user.validate(), Ok(()));
+
+        let user: User = serde_json::from_str(
+            r#"{"id":null,"name":"Ana","email":"ana@example.com","password":"password123","username":""}"#,
+        )
+        .unwrap();
+        assert_eq!(user.username, None);
+
+        let user = user.with_username(Some("ana_silva".to_string()));
+        assert_eq!(
+            user.validate().unwrap_err(),
+            shared::validation::UsernameError::InvalidFormat.to_string()
+        );
+        assert_eq!(user.anonymized().username, None);
+    }
+
+    #[test]
+    fn test_address_rejects_unknown_fields() {
+        let json = r#"{"street":"1 Main St","city":"Dublin","country":"IE","planet":"Earth"}"#;
+        assert!(serde_json::from_str::<Address>(json).is_err());
+
+        let json = r#"{"street":"1 Main St","city":"Dublin","country":"IE"}"#;
+        let parsed: Address = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.postal_code, "");
+        assert_eq!(parsed.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_normalized_lowercases_email() {
+        let user = User::new(
+            "John Doe".to_string(),
+            "  John@Example.COM ".to_string(),
+            "password123".to_string(),
+        )
+        .normalized();
+        assert_eq!(user.email, "john@example.com");
+        assert_eq!(user.name, "John Doe");
+    }
+
+    #[test]
+    fn test_user_response_hides_password() {
+        let user = User::with_id(
+            1,
+            "John Doe".to_string(),
+            "john@example.com".to_string(),
+            "password123".to_string(),
+        );
+        let response = UserResponse::from(user);
+        assert_eq!(response.id, 1);
+        assert_eq!(response.name, "John Doe");
+
+        let json = rocket::serde::json::to_string(&response).unwrap();
+        assert!(!json.contains("password"));
+    }
+
+    #[test]
+    fn test_user_status_round_trips() {
+        for status in [UserStatus::Active, UserStatus::Suspended] {
+            assert_eq!(status.as_str().parse(), Ok(status));
+        }
+        assert!("banned".parse::<UserStatus>().is_err());
+
+        // Request bodies without a status deserialize as active
+        let user: User = serde_json::from_str(
+            r#"{"id": null, "name": "John", "email": "john@example.com", "password": "password123"}"#,
+        )
+        .unwrap();
+        assert_eq!(user.status, UserStatus::Active);
+    }
+
+    #[test]
+    fn test_anonymized_user_has_no_personal_data() {
+        let user = User::with_id(
+            7,
+            "John Doe".to_string(),
+            "john@example.com".to_string(),
+            "password123".to_string(),
+        );
+        assert!(!user.is_anonymized());
+
+        let anonymized = user.anonymized();
+        assert_eq!(anonymized.id, Some(7));
+        assert_eq!(anonymized.name, ANONYMIZED_NAME);
+        assert_eq!(anonymized.email, "user-7@anonymized.invalid");
+        assert_ne!(anonymized.password, user.password);
+        assert_eq!(anonymized.status, UserStatus::Suspended);
+        assert!(anonymized.is_anonymized());
+    }
+
+    #[test]
+    fn test_emails_are_normalized_when_read() {
+        let user: User = serde_json::from_str(
+            r#"{"id":null,"name":"John","email":" John@Example.COM ","password":"password123"}"#,
+        )
+        .unwrap();
+        assert_eq!(user.email, "john@example.com");
+
+        let user = User::new("John".to_string(), "JOHN@example.com".to_string(), "password123".to_string());
+        assert_eq!(user.email, "john@example.com");
+    }
+
+    proptest::proptest! {
+        #![proptest_config(crate::test_support::property_config())]
+
+        #[test]
+        fn test_validation_properties(
+            email in crate::test_support::arbitrary_email(),
+            name in crate::test_support::arbitrary_text(10),
+            password in crate::test_support::arbitrary_text(12),
+        ) {
+            let normalized = normalize_email(&email);
+            assert_eq!(normalize_email(&normalized), normalized, "normalizing twice changed {:?}", email);
+            // Normalizing never turns a rejected email into an accepted one or back,
+            // so the frontend (raw input) and the backend (normalized) agree
+            assert_eq!(
+                validate_email(&email).is_ok(),
+                validate_email(&normalized).is_ok(),
+                "normalizing changed the verdict on {:?}",
+                email
+            );
+
+            let user = User::new(name, email, password);
+            assert_eq!(&user.email, &normalized);
+            assert_eq!(user.clone().normalized(), user.clone());
+            if user.validate().is_ok() {
+                assert!(!user.name.trim().is_empty());
+                assert!(!user.email.chars().any(char::is_uppercase));
+            }
+        }
+    }
 }
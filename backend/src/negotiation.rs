@@ -0,0 +1,111 @@
+use rocket::http::{ContentType, MediaType, Status};
+use rocket::response::{self, Responder, Response};
+use rocket::serde::Serialize;
+use rocket::Request;
+use std::io::Cursor;
+
+// Content negotiation - Open/Closed Principle
+// Handlers return `Negotiated<T>`; the wire format is picked from the
+// `Accept` header so new formats can be added without touching handlers
+
+/// Serialization formats supported by the user endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl WireFormat {
+    /// Pick the format from the client's most preferred `Accept` media type,
+    /// defaulting to JSON
+    pub fn from_request(request: &Request<'_>) -> Self {
+        request
+            .accept()
+            .map(|accept| WireFormat::from_media_type(accept.preferred().media_type()))
+            .unwrap_or(WireFormat::Json)
+    }
+
+    pub fn from_media_type(media_type: &MediaType) -> Self {
+        match (media_type.top().as_str(), media_type.sub().as_str()) {
+            ("application", "msgpack") | ("application", "x-msgpack") => WireFormat::MessagePack,
+            ("application", "cbor") => WireFormat::Cbor,
+            _ => WireFormat::Json,
+        }
+    }
+
+    pub fn content_type(&self) -> ContentType {
+        match self {
+            WireFormat::Json => ContentType::JSON,
+            WireFormat::MessagePack => ContentType::MsgPack,
+            WireFormat::Cbor => ContentType::new("application", "cbor"),
+        }
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            WireFormat::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            WireFormat::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            WireFormat::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(value, &mut buffer).map_err(|e| e.to_string())?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+/// Responder that serializes its value in the format negotiated with the client
+#[derive(Debug, Clone, PartialEq)]
+pub struct Negotiated<T>(pub T);
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Negotiated<T> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let format = WireFormat::from_request(request);
+        let body = format.serialize(&self.0).map_err(|e| {
+            eprintln!("Failed to serialize response as {:?}: {}", format, e);
+            Status::InternalServerError
+        })?;
+
+        Response::build()
+            .header(format.content_type())
+            .sized_body(body.len(), Cursor::new(body))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_media_type() {
+        assert_eq!(WireFormat::from_media_type(&MediaType::JSON), WireFormat::Json);
+        assert_eq!(WireFormat::from_media_type(&MediaType::MsgPack), WireFormat::MessagePack);
+        assert_eq!(
+            WireFormat::from_media_type(&MediaType::new("application", "x-msgpack")),
+            WireFormat::MessagePack
+        );
+        assert_eq!(
+            WireFormat::from_media_type(&MediaType::new("application", "cbor")),
+            WireFormat::Cbor
+        );
+        assert_eq!(WireFormat::from_media_type(&MediaType::Any), WireFormat::Json);
+    }
+
+    #[test]
+    fn test_serialize_round_trips() {
+        let value = vec![1, 2, 3];
+
+        let json = WireFormat::Json.serialize(&value).unwrap();
+        assert_eq!(json, b"[1,2,3]");
+
+        let msgpack = WireFormat::MessagePack.serialize(&value).unwrap();
+        let decoded: Vec<i32> = rmp_serde::from_slice(&msgpack).unwrap();
+        assert_eq!(decoded, value);
+
+        let cbor = WireFormat::Cbor.serialize(&value).unwrap();
+        let decoded: Vec<i32> = ciborium::from_reader(cbor.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::domain::{DomainError, RepositoryError};
+use crate::events::{EventSubscriber, UserEvent};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+// Notifications - Single Responsibility Principle
+// Keeps a per-user inbox of things the user should know about; entries are
+// created by the NotificationSubscriber from domain events and marked read
+// by the user. The inbox is not served over HTTP yet: without authentication
+// a `/users/<id>/notifications` route would open any user's inbox to anyone,
+// so NotificationService gets its routes once there is a signed-in user
+
+/// Notifications returned when no limit is given
+pub const DEFAULT_NOTIFICATION_LIMIT: i64 = 20;
+/// Upper bound for the `limit` parameter
+pub const MAX_NOTIFICATION_LIMIT: i64 = 100;
+
+/// What a notification is about; the frontend picks the text from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Welcome,
+    PasswordChanged,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Welcome => "welcome",
+            NotificationKind::PasswordChanged => "password_changed",
+        }
+    }
+
+    /// The notification sent for `event`, if any
+    pub fn for_event(event: &UserEvent) -> Option<Self> {
+        match event {
+            UserEvent::Created { .. } => Some(NotificationKind::Welcome),
+            UserEvent::PasswordChanged { .. } => Some(NotificationKind::PasswordChanged),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Notification {
+    pub id: i64,
+    /// `welcome` or `password_changed`
+    pub kind: String,
+    pub read: bool,
+    /// RFC 3339 UTC creation time
+    pub created_at: String,
+}
+
+/// Latest notifications of a user with the number still unread
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct NotificationInbox {
+    pub unread: i64,
+    pub notifications: Vec<Notification>,
+}
+
+/// Storage of the notification inboxes
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    async fn create(&self, user_id: i32, kind: NotificationKind) -> Result<(), RepositoryError>;
+    /// The latest `limit` notifications of a user, newest first
+    async fn for_user(&self, user_id: i32, limit: i64) -> Result<Vec<Notification>, RepositoryError>;
+    async fn unread_count(&self, user_id: i32) -> Result<i64, RepositoryError>;
+    /// Mark one notification of a user read; false when the user has no such notification
+    async fn mark_read(&self, user_id: i32, id: i64) -> Result<bool, RepositoryError>;
+    async fn mark_all_read(&self, user_id: i32) -> Result<(), RepositoryError>;
+}
+
+pub struct PostgresNotificationRepository {
+    client: Arc<Client>,
+}
+
+impl PostgresNotificationRepository {
+    pub fn new(client: Arc<Client>) -> Self {
+        PostgresNotificationRepository { client }
+    }
+}
+
+#[async_trait]
+impl NotificationRepository for PostgresNotificationRepository {
+    async fn create(&self, user_id: i32, kind: NotificationKind) -> Result<(), RepositoryError> {
+        self.client
+            .execute(
+                "INSERT INTO notifications (user_id, kind) VALUES ($1, $2)",
+                &[&user_id, &kind.as_str()],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn for_user(&self, user_id: i32, limit: i64) -> Result<Vec<Notification>, RepositoryError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT id, kind, read_at IS NOT NULL,
+                    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"')
+                 FROM notifications WHERE user_id = $1
+                 ORDER BY created_at DESC, id DESC LIMIT $2",
+                &[&user_id, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| Notification {
+                id: row.get(0),
+                kind: row.get(1),
+                read: row.get(2),
+                created_at: row.get(3),
+            })
+            .collect())
+    }
+
+    async fn unread_count(&self, user_id: i32) -> Result<i64, RepositoryError> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+                &[&user_id],
+            )
+            .await?;
+        Ok(row.get(0))
+    }
+
+    async fn mark_read(&self, user_id: i32, id: i64) -> Result<bool, RepositoryError> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE notifications SET read_at = COALESCE(read_at, NOW())
+                 WHERE id = $1 AND user_id = $2",
+                &[&id, &user_id],
+            )
+            .await?;
+        Ok(updated > 0)
+    }
+
+    async fn mark_all_read(&self, user_id: i32) -> Result<(), RepositoryError> {
+        self.client
+            .execute(
+                "UPDATE notifications SET read_at = NOW() WHERE user_id = $1 AND read_at IS NULL",
+                &[&user_id],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Reads and updates the inbox of a user
+pub struct NotificationService {
+    repository: Arc<dyn NotificationRepository>,
+}
+
+impl NotificationService {
+    pub fn new(repository: Arc<dyn NotificationRepository>) -> Self {
+        NotificationService { repository }
+    }
+
+    /// `limit` bounded to 1..=MAX_NOTIFICATION_LIMIT, DEFAULT_NOTIFICATION_LIMIT when absent
+    pub async fn inbox(&self, user_id: i32, limit: Option<i64>) -> Result<NotificationInbox, DomainError> {
+        let limit = limit
+            .unwrap_or(DEFAULT_NOTIFICATION_LIMIT)
+            .clamp(1, MAX_NOTIFICATION_LIMIT);
+        Ok(NotificationInbox {
+            unread: self.repository.unread_count(user_id).await?,
+            notifications: self.repository.for_user(user_id, limit).await?,
+        })
+    }
+
+    pub async fn mark_read(&self, user_id: i32, id: i64) -> Result<(), DomainError> {
+        if self.repository.mark_read(user_id, id).await? {
+            Ok(())
+        } else {
+            Err(DomainError::NotFound(format!("Notification with id {} not found", id)))
+        }
+    }
+
+    pub async fn mark_all_read(&self, user_id: i32) -> Result<(), DomainError> {
+        Ok(self.repository.mark_all_read(user_id).await?)
+    }
+}
+
+/// Puts a notification in the inbox of the user an event is about
+pub struct NotificationSubscriber {
+    repository: Arc<dyn NotificationRepository>,
+}
+
+impl NotificationSubscriber {
+    pub fn new(repository: Arc<dyn NotificationRepository>) -> Self {
+        NotificationSubscriber { repository }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for NotificationSubscriber {
+    fn name(&self) -> &'static str {
+        "notifications"
+    }
+
+    async fn handle(&self, event: &UserEvent) {
+        let Some(kind) = NotificationKind::for_event(event) else {
+            return;
+        };
+        if let Err(e) = self.repository.create(event.user_id(), kind).await {
+            eprintln!("Notification for user {} not created: {}", event.user_id(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::models::{UserResponse, UserStatus};
+    use std::sync::Mutex;
+
+    // In-memory inboxes for tests
+    #[derive(Default)]
+    pub struct MockNotificationRepository {
+        pub notifications: Mutex<Vec<(i32, Notification)>>,
+    }
+
+    #[async_trait]
+    impl NotificationRepository for MockNotificationRepository {
+        async fn create(&self, user_id: i32, kind: NotificationKind) -> Result<(), RepositoryError> {
+            let mut notifications = self.notifications.lock().unwrap();
+            let id = notifications.len() as i64 + 1;
+            notifications.push((
+                user_id,
+                Notification {
+                    id,
+                    kind: kind.as_str().to_string(),
+                    read: false,
+                    created_at: format!("2026-10-17T12:00:{:02}Z", id),
+                },
+            ));
+            Ok(())
+        }
+
+        async fn for_user(&self, user_id: i32, limit: i64) -> Result<Vec<Notification>, RepositoryError> {
+            let notifications = self.notifications.lock().unwrap();
+            Ok(notifications
+                .iter()
+                .rev()
+                .filter(|(id, _)| *id == user_id)
+                .take(limit as usize)
+                .map(|(_, notification)| notification.clone())
+                .collect())
+        }
+
+        async fn unread_count(&self, user_id: i32) -> Result<i64, RepositoryError> {
+            let notifications = self.notifications.lock().unwrap();
+            Ok(notifications
+                .iter()
+                .filter(|(id, notification)| *id == user_id && !notification.read)
+                .count() as i64)
+        }
+
+        async fn mark_read(&self, user_id: i32, id: i64) -> Result<bool, RepositoryError> {
+            let mut notifications = self.notifications.lock().unwrap();
+            let found = notifications
+                .iter_mut()
+                .find(|(owner, notification)| *owner == user_id && notification.id == id);
+            Ok(found.map(|(_, notification)| notification.read = true).is_some())
+        }
+
+        async fn mark_all_read(&self, user_id: i32) -> Result<(), RepositoryError> {
+            let mut notifications = self.notifications.lock().unwrap();
+            for (_, notification) in notifications.iter_mut().filter(|(id, _)| *id == user_id) {
+                notification.read = true;
+            }
+            Ok(())
+        }
+    }
+
+    fn user(id: i32) -> UserResponse {
+        UserResponse {
+            id,
+            name: "John Doe".to_string(),
+            email: "john@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_notifies_on_signup_and_password_change() {
+        let repository = Arc::new(MockNotificationRepository::default());
+        let subscriber = NotificationSubscriber::new(repository.clone());
+
+        subscriber.handle(&UserEvent::Created { user: user(1) }).await;
+        subscriber.handle(&UserEvent::Updated { user: user(1) }).await;
+        subscriber.handle(&UserEvent::PasswordChanged { id: 1 }).await;
+
+        let notifications = repository.for_user(1, 10).await.unwrap();
+        let kinds: Vec<&str> = notifications.iter().map(|n| n.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["password_changed", "welcome"]);
+    }
+
+    #[tokio::test]
+    async fn test_inbox_counts_unread_and_marks_read() {
+        let repository = Arc::new(MockNotificationRepository::default());
+        repository.create(1, NotificationKind::Welcome).await.unwrap();
+        repository.create(1, NotificationKind::PasswordChanged).await.unwrap();
+        repository.create(2, NotificationKind::Welcome).await.unwrap();
+        let service = NotificationService::new(repository);
+
+        let inbox = service.inbox(1, None).await.unwrap();
+        assert_eq!(inbox.unread, 2);
+        assert_eq!(inbox.notifications.len(), 2);
+        assert_eq!(service.inbox(1, Some(0)).await.unwrap().notifications.len(), 1);
+
+        service.mark_read(1, 2).await.unwrap();
+        assert_eq!(service.inbox(1, None).await.unwrap().unread, 1);
+
+        // Notifications of other users cannot be marked
+        let error = service.mark_read(1, 3).await.unwrap_err();
+        assert_eq!(error, DomainError::NotFound("Notification with id 3 not found".to_string()));
+
+        service.mark_all_read(1).await.unwrap();
+        assert_eq!(service.inbox(1, None).await.unwrap().unread, 0);
+        assert_eq!(service.inbox(2, None).await.unwrap().unread, 1);
+    }
+}
@@ -0,0 +1,105 @@
+use rocket::http::Header;
+use rocket::response::{self, Responder};
+use rocket::Request;
+
+// Pagination - Single Responsibility Principle
+// Turns `?page=&per_page=` query parameters into a bounded limit/offset
+// and reports the total number of rows through the `X-Total-Count` header
+
+pub const TOTAL_COUNT_HEADER: &str = "X-Total-Count";
+pub const DEFAULT_PER_PAGE: u32 = 20;
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// A page request - `page` is 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl Pagination {
+    /// Build a page request from optional query parameters, clamping
+    /// out-of-range values instead of rejecting them
+    pub fn from_query(page: Option<u32>, per_page: Option<u32>) -> Self {
+        Pagination {
+            page: page.unwrap_or(1).max(1),
+            per_page: per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE),
+        }
+    }
+
+    pub fn limit(&self) -> i64 {
+        i64::from(self.per_page)
+    }
+
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page - 1) * i64::from(self.per_page)
+    }
+}
+
+/// Responder that adds the `X-Total-Count` header to an inner response
+pub struct WithTotalCount<R>(pub i64, pub R);
+
+impl<'r, R: Responder<'r, 'static>> Responder<'r, 'static> for WithTotalCount<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = self.1.respond_to(request)?;
+        response.set_header(Header::new(TOTAL_COUNT_HEADER, self.0.to_string()));
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let pagination = Pagination::from_query(None, None);
+        assert_eq!(pagination.page, 1);
+        assert_eq!(pagination.per_page, DEFAULT_PER_PAGE);
+        assert_eq!(pagination.offset(), 0);
+    }
+
+    #[test]
+    fn test_limit_and_offset() {
+        let pagination = Pagination::from_query(Some(3), Some(10));
+        assert_eq!(pagination.limit(), 10);
+        assert_eq!(pagination.offset(), 20);
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_clamped() {
+        let pagination = Pagination::from_query(Some(0), Some(0));
+        assert_eq!(pagination.page, 1);
+        assert_eq!(pagination.per_page, 1);
+
+        let pagination = Pagination::from_query(Some(2), Some(10_000));
+        assert_eq!(pagination.per_page, MAX_PER_PAGE);
+    }
+
+    // None, the edges of u32 or anything in between
+    fn arbitrary_query_value() -> impl proptest::strategy::Strategy<Value = Option<u32>> {
+        use proptest::prelude::*;
+
+        prop_oneof![
+            Just(None),
+            (0..3u32).prop_map(Some),
+            (0..3u32).prop_map(|below| Some(u32::MAX - below)),
+            any::<u32>().prop_map(Some),
+        ]
+    }
+
+    proptest::proptest! {
+        #![proptest_config(crate::test_support::property_config())]
+
+        #[test]
+        fn test_pagination_properties(page in arbitrary_query_value(), per_page in arbitrary_query_value()) {
+            let pagination = Pagination::from_query(page, per_page);
+            assert!(pagination.page >= 1);
+            assert!((1..=MAX_PER_PAGE).contains(&pagination.per_page));
+            assert!(pagination.offset() >= 0, "{:?}", pagination);
+            assert_eq!(pagination.offset() % pagination.limit(), 0);
+            // A page request read back from its own values is unchanged
+            assert_eq!(Pagination::from_query(Some(pagination.page), Some(pagination.per_page)), pagination);
+        }
+    }
+}
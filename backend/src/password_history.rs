@@ -0,0 +1,66 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+// Password history - Single Responsibility Principle
+// Users may not reuse any of their last `PASSWORD_HISTORY` passwords. Only
+// Argon2id hashes are kept, in PHC string format (`$argon2id$v=19$...`) with
+// their own random salt; a new password is verified against each stored
+// entry, so the history never reveals a password
+
+/// Argon2id hash of `password` for the history, as a PHC string
+pub fn hash_password(password: &str) -> String {
+    // A v4 UUID is 16 random bytes, the salt length Argon2 recommends
+    let salt = SaltString::encode_b64(uuid::Uuid::new_v4().as_bytes()).expect("16 bytes is a valid salt");
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("default Argon2 parameters hash any password")
+        .to_string()
+}
+
+/// Whether `hash` was made from `password`; malformed hashes, including the
+/// salted SHA-256 entries of earlier versions, match nothing
+pub fn matches(hash: &str, password: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
+
+/// Whether `password` is the stored password `stored`: verified like a
+/// history entry when `stored` is a hash, compared as is otherwise, since
+/// `users.password` holds the password itself
+pub fn is_current(stored: &str, password: &str) -> bool {
+    match PasswordHash::new(stored) {
+        Ok(_) => matches(stored, password),
+        Err(_) => stored == password,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashes_are_salted() {
+        let first = hash_password("password123");
+        let second = hash_password("password123");
+        assert_ne!(first, second);
+        assert!(first.starts_with("$argon2id$"));
+        assert!(!first.contains("password123"));
+
+        assert!(matches(&first, "password123"));
+        assert!(matches(&second, "password123"));
+        assert!(!matches(&first, "password124"));
+        assert!(!matches("not-a-hash", "password123"));
+        // Salted SHA-256 entries of earlier versions
+        assert!(!matches("0f1e:5994471abb01112afcc18159f6cc74b4f511b99806da59b3caf5a9c173cacfc5", "password123"));
+    }
+
+    #[test]
+    fn test_is_current_verifies_hashes() {
+        assert!(is_current("password123", "password123"));
+        assert!(!is_current("password123", "password124"));
+
+        let hash = hash_password("password123");
+        assert!(is_current(&hash, "password123"));
+        assert!(!is_current(&hash, "password124"));
+        assert!(!is_current(&hash, &hash));
+    }
+}
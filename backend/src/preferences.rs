@@ -0,0 +1,324 @@
+use crate::domain::{DomainError, RepositoryError};
+use crate::models::UserStatus;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shared::validation::validate_tag;
+use std::sync::Arc;
+use tokio_postgres::Client;
+
+// Preferences - Single Responsibility Principle
+// Stores the UI preferences of a user (theme, locale, page size, user list
+// columns and saved views) as one JSONB document, so they follow the user across devices.
+// Every field is optional: the frontend merges them over its local defaults
+
+/// Themes of the frontend
+pub const THEMES: [&str; 3] = ["light", "dark", "system"];
+/// Locales the frontend is translated to
+pub const LOCALES: [&str; 3] = ["en", "pt-BR", "es"];
+/// Columns of the frontend user list
+pub const COLUMNS: [&str; 5] = ["id", "name", "email", "last_seen", "status"];
+/// Largest page size a user may choose
+pub const MAX_PAGE_SIZE: u32 = 100;
+/// Sort orders of the frontend user list
+pub const SORTS: [&str; 3] = ["none", "most_recent", "least_recent"];
+/// Most views a user may save
+pub const MAX_VIEWS: usize = 20;
+pub const MAX_VIEW_NAME_LENGTH: usize = 40;
+pub const MAX_VIEW_SEARCH_LENGTH: usize = 100;
+
+/// Hidden and widened columns of the user list
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnPreferences {
+    #[serde(default)]
+    pub hidden: Vec<String>,
+    #[serde(default)]
+    pub wide: Vec<String>,
+}
+
+/// A named combination of search text, sort, tag and status of the user list
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SavedView {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub search: String,
+    /// One of SORTS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// `active` or `suspended`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl SavedView {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("View name cannot be empty".to_string());
+        }
+        if self.name.chars().count() > MAX_VIEW_NAME_LENGTH {
+            return Err(format!("View names must be at most {} characters", MAX_VIEW_NAME_LENGTH));
+        }
+        if self.search.chars().count() > MAX_VIEW_SEARCH_LENGTH {
+            return Err(format!("View searches must be at most {} characters", MAX_VIEW_SEARCH_LENGTH));
+        }
+        if let Some(sort) = self.sort.as_deref().filter(|sort| !SORTS.contains(sort)) {
+            return Err(format!("Unknown sort {}", sort));
+        }
+        if let Some(tag) = &self.tag {
+            validate_tag(tag).map_err(|e| e.to_string())?;
+        }
+        if let Some(status) = self.status.as_deref().filter(|status| status.parse::<UserStatus>().is_err()) {
+            return Err(format!("Unknown status {}", status));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UserPreferences {
+    /// `light`, `dark` or `system`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// `en`, `pt-BR` or `es`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Users per page, 1 to MAX_PAGE_SIZE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<ColumnPreferences>,
+    /// Saved views of the user list, at most MAX_VIEWS with distinct names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub views: Option<Vec<SavedView>>,
+}
+
+impl UserPreferences {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.theme.as_deref().is_some_and(|theme| !THEMES.contains(&theme)) {
+            return Err("Theme must be light, dark or system".to_string());
+        }
+        if self.locale.as_deref().is_some_and(|locale| !LOCALES.contains(&locale)) {
+            return Err("Locale must be en, pt-BR or es".to_string());
+        }
+        if self.page_size.is_some_and(|size| !(1..=MAX_PAGE_SIZE).contains(&size)) {
+            return Err(format!("Page size must be between 1 and {}", MAX_PAGE_SIZE));
+        }
+        let columns = self.columns.iter().flat_map(|columns| columns.hidden.iter().chain(&columns.wide));
+        for column in columns {
+            if !COLUMNS.contains(&column.as_str()) {
+                return Err(format!("Unknown column {}", column));
+            }
+        }
+        let views = self.views.as_deref().unwrap_or_default();
+        if views.len() > MAX_VIEWS {
+            return Err(format!("At most {} views can be saved", MAX_VIEWS));
+        }
+        for (index, view) in views.iter().enumerate() {
+            view.validate()?;
+            if views[..index].iter().any(|other| other.name == view.name) {
+                return Err(format!("View {} is saved twice", view.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Storage of the preference documents
+#[async_trait]
+pub trait PreferencesRepository: Send + Sync {
+    /// Saved preferences of a user, None when never saved
+    async fn find(&self, user_id: i32) -> Result<Option<UserPreferences>, RepositoryError>;
+    /// Replace the preferences of a user
+    async fn save(&self, user_id: i32, preferences: &UserPreferences) -> Result<(), RepositoryError>;
+}
+
+pub struct PostgresPreferencesRepository {
+    client: Arc<Client>,
+}
+
+impl PostgresPreferencesRepository {
+    pub fn new(client: Arc<Client>) -> Self {
+        PostgresPreferencesRepository { client }
+    }
+}
+
+#[async_trait]
+impl PreferencesRepository for PostgresPreferencesRepository {
+    async fn find(&self, user_id: i32) -> Result<Option<UserPreferences>, RepositoryError> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT preferences FROM user_preferences WHERE user_id = $1",
+                &[&user_id],
+            )
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        // Documents are validated on write; one that no longer parses is treated as unset
+        let document: Value = row.get(0);
+        Ok(serde_json::from_value(document).ok())
+    }
+
+    async fn save(&self, user_id: i32, preferences: &UserPreferences) -> Result<(), RepositoryError> {
+        let document =
+            serde_json::to_value(preferences).map_err(|e| RepositoryError::Storage(e.to_string()))?;
+        self.client
+            .execute(
+                "INSERT INTO user_preferences (user_id, preferences) VALUES ($1, $2)
+                 ON CONFLICT (user_id) DO UPDATE SET preferences = EXCLUDED.preferences, updated_at = NOW()",
+                &[&user_id, &document],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Reads and replaces the preferences of a user
+pub struct PreferencesService {
+    repository: Arc<dyn PreferencesRepository>,
+}
+
+impl PreferencesService {
+    pub fn new(repository: Arc<dyn PreferencesRepository>) -> Self {
+        PreferencesService { repository }
+    }
+
+    /// Saved preferences, empty when the user never saved any
+    pub async fn get(&self, user_id: i32) -> Result<UserPreferences, DomainError> {
+        Ok(self.repository.find(user_id).await?.unwrap_or_default())
+    }
+
+    pub async fn put(&self, user_id: i32, preferences: UserPreferences) -> Result<UserPreferences, DomainError> {
+        preferences.validate().map_err(DomainError::Invalid)?;
+        self.repository.save(user_id, &preferences).await?;
+        Ok(preferences)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    // In-memory preferences for tests
+    #[derive(Default)]
+    pub struct MockPreferencesRepository {
+        pub preferences: Mutex<HashMap<i32, UserPreferences>>,
+    }
+
+    #[async_trait]
+    impl PreferencesRepository for MockPreferencesRepository {
+        async fn find(&self, user_id: i32) -> Result<Option<UserPreferences>, RepositoryError> {
+            Ok(self.preferences.lock().unwrap().get(&user_id).cloned())
+        }
+
+        async fn save(&self, user_id: i32, preferences: &UserPreferences) -> Result<(), RepositoryError> {
+            self.preferences.lock().unwrap().insert(user_id, preferences.clone());
+            Ok(())
+        }
+    }
+
+    fn preferences() -> UserPreferences {
+        UserPreferences {
+            theme: Some("dark".to_string()),
+            locale: Some("pt-BR".to_string()),
+            page_size: Some(50),
+            columns: Some(ColumnPreferences {
+                hidden: vec!["id".to_string()],
+                wide: vec!["email".to_string()],
+            }),
+            views: Some(vec![SavedView {
+                name: "Suspended VIPs".to_string(),
+                sort: Some("most_recent".to_string()),
+                tag: Some("vip".to_string()),
+                status: Some("suspended".to_string()),
+                ..SavedView::default()
+            }]),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(preferences().validate().is_ok());
+        assert!(UserPreferences::default().validate().is_ok());
+
+        let invalid = |change: fn(&mut UserPreferences)| {
+            let mut preferences = preferences();
+            change(&mut preferences);
+            preferences.validate().unwrap_err()
+        };
+        assert_eq!(invalid(|p| p.theme = Some("blue".to_string())), "Theme must be light, dark or system");
+        assert_eq!(invalid(|p| p.locale = Some("fr".to_string())), "Locale must be en, pt-BR or es");
+        assert_eq!(invalid(|p| p.page_size = Some(0)), "Page size must be between 1 and 100");
+        assert_eq!(
+            invalid(|p| p.columns.as_mut().unwrap().wide.push("avatar".to_string())),
+            "Unknown column avatar"
+        );
+    }
+
+    #[test]
+    fn test_validate_views() {
+        let invalid = |change: fn(&mut SavedView)| {
+            let mut preferences = preferences();
+            change(&mut preferences.views.as_mut().unwrap()[0]);
+            preferences.validate().unwrap_err()
+        };
+        assert_eq!(invalid(|v| v.name = " ".to_string()), "View name cannot be empty");
+        assert_eq!(invalid(|v| v.name = "v".repeat(41)), "View names must be at most 40 characters");
+        assert_eq!(invalid(|v| v.search = "s".repeat(101)), "View searches must be at most 100 characters");
+        assert_eq!(invalid(|v| v.sort = Some("name".to_string())), "Unknown sort name");
+        assert_eq!(
+            invalid(|v| v.tag = Some("VIP!".to_string())),
+            "Tags may only contain lowercase letters, digits and single dashes between them"
+        );
+        assert_eq!(invalid(|v| v.status = Some("banned".to_string())), "Unknown status banned");
+
+        let mut preferences = preferences();
+        let views = preferences.views.as_mut().unwrap();
+        views.push(views[0].clone());
+        assert_eq!(preferences.validate().unwrap_err(), "View Suspended VIPs is saved twice");
+
+        let views = (0..=MAX_VIEWS)
+            .map(|i| SavedView { name: format!("View {}", i), ..SavedView::default() })
+            .collect();
+        let preferences = UserPreferences { views: Some(views), ..UserPreferences::default() };
+        assert_eq!(preferences.validate().unwrap_err(), "At most 20 views can be saved");
+    }
+
+    #[test]
+    fn test_document_format() {
+        let json = serde_json::to_value(UserPreferences {
+            theme: Some("light".to_string()),
+            ..UserPreferences::default()
+        })
+        .unwrap();
+        // Unset preferences are left out so the frontend keeps its defaults
+        assert_eq!(json, serde_json::json!({ "theme": "light" }));
+        assert!(serde_json::from_str::<UserPreferences>(r#"{"font":"large"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_and_put() {
+        let service = PreferencesService::new(Arc::new(MockPreferencesRepository::default()));
+        assert_eq!(service.get(1).await.unwrap(), UserPreferences::default());
+
+        service.put(1, preferences()).await.unwrap();
+        assert_eq!(service.get(1).await.unwrap(), preferences());
+        assert_eq!(service.get(2).await.unwrap(), UserPreferences::default());
+
+        let error = service
+            .put(1, UserPreferences { page_size: Some(500), ..UserPreferences::default() })
+            .await
+            .unwrap_err();
+        assert_eq!(error, DomainError::Invalid("Page size must be between 1 and 100".to_string()));
+        // A rejected document leaves the saved one untouched
+        assert_eq!(service.get(1).await.unwrap(), preferences());
+    }
+}
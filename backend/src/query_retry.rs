@@ -0,0 +1,211 @@
+use crate::config::RetryConfig;
+use fake::rand::Rng;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use tokio_postgres::error::SqlState;
+
+// Query retries - Single Responsibility Principle
+// Some database errors say nothing about the statement: a serialization
+// failure or deadlock rolled it back, so running it again is safe even for
+// writes. QueryRetry runs such statements again with exponential backoff;
+// any other error is the answer. After a connection error a write may have
+// been applied all the same, and the shared client does not reconnect, so
+// those are only retried while opening a new connection. The delays are
+// jittered so that requests failing together do not retry in lockstep
+
+/// Whether the server rolled a statement back for a reason that may be
+/// gone on the next attempt
+pub fn is_rolled_back_state(code: &SqlState) -> bool {
+    *code == SqlState::T_R_SERIALIZATION_FAILURE || *code == SqlState::T_R_DEADLOCK_DETECTED
+}
+
+/// Whether `error` rolled its statement back, so running it again is safe
+pub fn is_rolled_back(error: &tokio_postgres::Error) -> bool {
+    error
+        .as_db_error()
+        .is_some_and(|db_error| is_rolled_back_state(db_error.code()))
+}
+
+/// Whether the server refused a connection for a reason that may be gone
+/// on the next attempt
+pub fn is_transient_state(code: &SqlState) -> bool {
+    *code == SqlState::CANNOT_CONNECT_NOW
+        || *code == SqlState::TOO_MANY_CONNECTIONS
+        // Class 08: connection exceptions, of which only protocol violations are bugs
+        || (code.code().starts_with("08") && *code != SqlState::PROTOCOL_VIOLATION)
+}
+
+/// Whether connecting is worth another attempt: a transient server error,
+/// or an I/O error such as a reset while connecting
+pub fn is_transient(error: &tokio_postgres::Error) -> bool {
+    match error.as_db_error() {
+        Some(db_error) => is_transient_state(db_error.code()),
+        None => {
+            !error.is_closed()
+                && std::error::Error::source(error).is_some_and(|source| source.is::<std::io::Error>())
+        }
+    }
+}
+
+/// `delay` shortened by a random part of up to half, given `sample` in `[0, 1)`
+pub fn jitter(delay: Duration, sample: f64) -> Duration {
+    delay / 2 + delay.mul_f64(sample.clamp(0.0, 1.0) / 2.0)
+}
+
+/// Retries rolled back statements and refused connections as the policy says
+#[derive(Debug, Clone)]
+pub struct QueryRetry {
+    policy: RetryConfig,
+}
+
+impl QueryRetry {
+    pub fn new(policy: RetryConfig) -> Self {
+        QueryRetry { policy }
+    }
+
+    /// Run `statement` until it succeeds, fails for good or runs out of retries
+    pub async fn run<T, F>(&self, statement: impl Fn() -> F) -> Result<T, tokio_postgres::Error>
+    where
+        F: Future<Output = Result<T, tokio_postgres::Error>>,
+    {
+        self.retry(statement, is_rolled_back).await
+    }
+
+    /// Open a connection with `connect`, again while the server refuses it
+    pub async fn connect<T, F>(&self, connect: impl Fn() -> F) -> Result<T, tokio_postgres::Error>
+    where
+        F: Future<Output = Result<T, tokio_postgres::Error>>,
+    {
+        self.retry(connect, is_transient).await
+    }
+
+    async fn retry<T, E, F>(&self, statement: impl Fn() -> F, transient: impl Fn(&E) -> bool) -> Result<T, E>
+    where
+        E: fmt::Display,
+        F: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match statement().await {
+                Err(e) if attempt < self.policy.max_retries && transient(&e) => {
+                    let delay = jitter(self.policy.delay_for_attempt(attempt), fake::rand::rng().random());
+                    attempt += 1;
+                    eprintln!(
+                        "Transient database error ({}), retrying in {:?} (attempt {}/{})",
+                        e, delay, attempt, self.policy.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn retry(max_retries: u32) -> QueryRetry {
+        QueryRetry::new(RetryConfig {
+            max_retries,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        })
+    }
+
+    #[test]
+    fn test_rolled_back_states() {
+        for code in [SqlState::T_R_SERIALIZATION_FAILURE, SqlState::T_R_DEADLOCK_DETECTED] {
+            assert!(is_rolled_back_state(&code), "{:?}", code);
+        }
+        for code in [
+            SqlState::CONNECTION_FAILURE,
+            SqlState::CONNECTION_EXCEPTION,
+            SqlState::CANNOT_CONNECT_NOW,
+            SqlState::UNIQUE_VIOLATION,
+            SqlState::QUERY_CANCELED,
+        ] {
+            assert!(!is_rolled_back_state(&code), "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn test_transient_connect_states() {
+        for code in [
+            SqlState::CANNOT_CONNECT_NOW,
+            SqlState::TOO_MANY_CONNECTIONS,
+            SqlState::CONNECTION_FAILURE,
+        ] {
+            assert!(is_transient_state(&code), "{:?}", code);
+        }
+        for code in [
+            SqlState::UNIQUE_VIOLATION,
+            SqlState::SYNTAX_ERROR,
+            SqlState::PROTOCOL_VIOLATION,
+        ] {
+            assert!(!is_transient_state(&code), "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn test_jitter_stays_within_half_the_delay() {
+        let delay = Duration::from_millis(400);
+        assert_eq!(jitter(delay, 0.0), Duration::from_millis(200));
+        assert_eq!(jitter(delay, 0.5), Duration::from_millis(300));
+        assert!(jitter(delay, 0.999) <= delay);
+    }
+
+    #[tokio::test]
+    async fn test_transient_errors_are_retried() {
+        let attempts = AtomicU32::new(0);
+        let statement = || async {
+            match attempts.fetch_add(1, Ordering::Relaxed) {
+                0 | 1 => Err("serialization failure".to_string()),
+                _ => Ok(7),
+            }
+        };
+        assert_eq!(retry(3).retry(statement, |_| true).await, Ok(7));
+        assert_eq!(attempts.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_limited_and_permanent_errors_are_not_retried() {
+        let attempts = AtomicU32::new(0);
+        let statement = || async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err::<(), _>("down".to_string())
+        };
+        assert_eq!(retry(2).retry(statement, |_| true).await, Err("down".to_string()));
+        assert_eq!(attempts.load(Ordering::Relaxed), 3);
+
+        attempts.store(0, Ordering::Relaxed);
+        assert_eq!(retry(2).retry(statement, |_| false).await, Err("down".to_string()));
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_of_writes_are_not_retried() {
+        // The INSERT may have committed before the connection broke, so running it again could duplicate it
+        let attempts = AtomicU32::new(0);
+        let insert = || async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err::<u64, _>(SqlState::CONNECTION_FAILURE.code().to_string())
+        };
+        let rolled_back = |code: &String| is_rolled_back_state(&SqlState::from_code(code));
+        assert!(retry(3).retry(insert, rolled_back).await.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+
+        attempts.store(0, Ordering::Relaxed);
+        let deadlocked = || async {
+            match attempts.fetch_add(1, Ordering::Relaxed) {
+                0 => Err(SqlState::T_R_DEADLOCK_DETECTED.code().to_string()),
+                _ => Ok(1),
+            }
+        };
+        assert_eq!(retry(3).retry(deadlocked, rolled_back).await, Ok(1));
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+    }
+}
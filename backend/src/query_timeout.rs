@@ -0,0 +1,129 @@
+use crate::domain::RepositoryError;
+use rocket::serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Query timeouts - Single Responsibility Principle
+// Two limits keep a stuck query from holding a request forever. Postgres
+// cancels statements running longer than `statement_timeout`, which is set
+// on every connection of the API server. QueryTimeout additionally stops
+// waiting a little later, for when the server cannot answer at all, e.g.
+// over a dropped network link. Both end as RepositoryError::Timeout, which
+// the API answers with 504, and are counted for `GET /admin/queries`
+
+/// How much longer than the statement timeout the client waits, so that the
+/// server's cancellation, which leaves the connection usable, comes first
+pub const TIMEOUT_GRACE: Duration = Duration::from_secs(1);
+
+pub const QUERY_TIMEOUT_MESSAGE: &str = "The database did not answer in time";
+
+/// Timeout counter reported by the admin endpoint
+#[derive(Debug, Default)]
+pub struct QueryMetrics {
+    statement_timeout: Duration,
+    timeouts: AtomicU64,
+}
+
+/// Snapshot of the query metrics
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct QueryStats {
+    pub statement_timeout_ms: u64,
+    pub timeouts: u64,
+}
+
+impl QueryMetrics {
+    pub fn new(statement_timeout: Duration) -> Self {
+        QueryMetrics {
+            statement_timeout,
+            ..QueryMetrics::default()
+        }
+    }
+
+    fn record_timeout(&self) {
+        self.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> QueryStats {
+        QueryStats {
+            statement_timeout_ms: self.statement_timeout.as_millis() as u64,
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Longest wait for the answer to a query
+#[derive(Debug, Clone)]
+pub struct QueryTimeout {
+    deadline: Duration,
+    metrics: Arc<QueryMetrics>,
+}
+
+impl QueryTimeout {
+    pub fn new(deadline: Duration, metrics: Arc<QueryMetrics>) -> Self {
+        QueryTimeout { deadline, metrics }
+    }
+
+    /// Wait for queries on connections with the statement timeout of `metrics`
+    pub fn after_statement_timeout(metrics: Arc<QueryMetrics>) -> Self {
+        QueryTimeout::new(metrics.statement_timeout + TIMEOUT_GRACE, metrics)
+    }
+
+    /// Statement timeout of the connections the queries run on
+    pub fn statement_timeout(&self) -> Duration {
+        self.metrics.statement_timeout
+    }
+
+    /// Await `query` until the deadline. Giving up drops the query, and the
+    /// statement is then left to the server's statement timeout
+    pub async fn run<T>(
+        &self,
+        query: impl Future<Output = Result<T, tokio_postgres::Error>>,
+    ) -> Result<T, RepositoryError> {
+        let result = match tokio::time::timeout(self.deadline, query).await {
+            Ok(result) => result.map_err(RepositoryError::from),
+            Err(_) => Err(RepositoryError::Timeout(QUERY_TIMEOUT_MESSAGE.to_string())),
+        };
+        // Also counts the statements the server cancelled
+        if let Err(RepositoryError::Timeout(_)) = &result {
+            self.metrics.record_timeout();
+        }
+        result
+    }
+}
+
+/// SQL making the server cancel statements running longer than `timeout`
+pub fn statement_timeout_sql(timeout: Duration) -> String {
+    format!("SET statement_timeout = {}", timeout.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_gives_up_at_the_deadline() {
+        let metrics = Arc::new(QueryMetrics::new(Duration::from_millis(10)));
+        let timeout = QueryTimeout::new(Duration::from_millis(10), metrics.clone());
+
+        let result = timeout.run(std::future::pending::<Result<u64, tokio_postgres::Error>>()).await;
+        assert_eq!(result, Err(RepositoryError::Timeout(QUERY_TIMEOUT_MESSAGE.to_string())));
+        assert_eq!(timeout.run(async { Ok(3) }).await, Ok(3));
+        assert_eq!(
+            metrics.snapshot(),
+            QueryStats {
+                statement_timeout_ms: 10,
+                timeouts: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_deadline_follows_the_statement_timeout() {
+        let timeout = QueryTimeout::after_statement_timeout(Arc::new(QueryMetrics::new(Duration::from_secs(5))));
+        assert_eq!(timeout.deadline, Duration::from_secs(6));
+        assert_eq!(statement_timeout_sql(Duration::from_secs(5)), "SET statement_timeout = 5000");
+    }
+}
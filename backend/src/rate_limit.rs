@@ -0,0 +1,173 @@
+use crate::config::{ClientErrorsConfig, SignupConfig};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Rate limiting - Single Responsibility Principle
+// Counts requests per client address in fixed windows, in memory, for the
+// endpoints anonymous clients can hammer (signup, email checks, error
+// reports). Each instance keeps its own counters, so the effective limit
+// scales with the number of instances
+
+/// Windows tracked before expired ones are dropped
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Window {
+    started: Instant,
+    hits: u32,
+}
+
+/// Allows `limit` requests per client within each `window`
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    windows: Mutex<HashMap<Option<IpAddr>, Window>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        RateLimiter {
+            limit,
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a request of `client`; false once it went over the limit
+    pub fn check(&self, client: Option<IpAddr>) -> bool {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: Option<IpAddr>, now: Instant) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= PRUNE_THRESHOLD {
+            windows.retain(|_, window| now.duration_since(window.started) < self.window);
+        }
+
+        let window = windows.entry(client).or_insert(Window {
+            started: now,
+            hits: 0,
+        });
+        if now.duration_since(window.started) >= self.window {
+            *window = Window {
+                started: now,
+                hits: 0,
+            };
+        }
+        window.hits = window.hits.saturating_add(1);
+        window.hits <= self.limit
+    }
+}
+
+/// Rate limiter of `POST /signup`, managed by Rocket
+pub struct SignupRateLimiter(pub RateLimiter);
+
+impl SignupRateLimiter {
+    pub fn from_config(config: &SignupConfig) -> Self {
+        SignupRateLimiter(RateLimiter::new(config.rate_limit, config.rate_window))
+    }
+}
+
+/// Rate limiter of `GET /users/exists`, managed by Rocket; it keeps the
+/// endpoint from being used to enumerate registered emails
+pub struct EmailCheckRateLimiter(pub RateLimiter);
+
+impl EmailCheckRateLimiter {
+    pub fn from_config(config: &SignupConfig) -> Self {
+        EmailCheckRateLimiter(RateLimiter::new(
+            config.email_check_rate_limit,
+            config.email_check_rate_window,
+        ))
+    }
+}
+
+/// Rate limiter of `POST /client-errors`, managed by Rocket
+pub struct ClientErrorRateLimiter(pub RateLimiter);
+
+impl ClientErrorRateLimiter {
+    pub fn from_config(config: &ClientErrorsConfig) -> Self {
+        ClientErrorRateLimiter(RateLimiter::new(config.rate_limit, config.rate_window))
+    }
+}
+
+/// Count the request against `limiter`; 429 once the client went over it
+fn guard<T>(request: &Request<'_>, limiter: Option<&RateLimiter>, guard: T) -> Outcome<T, ()> {
+    match limiter {
+        Some(limiter) if !limiter.check(request.client_ip()) => Outcome::Error((Status::TooManyRequests, ())),
+        _ => Outcome::Success(guard),
+    }
+}
+
+/// Request guard failing with 429 once the client made too many signups;
+/// unlimited when no SignupRateLimiter is managed
+pub struct SignupRateLimit;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SignupRateLimit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let limiter = request.rocket().state::<SignupRateLimiter>().map(|limiter| &limiter.0);
+        guard(request, limiter, SignupRateLimit)
+    }
+}
+
+/// Request guard failing with 429 once the client checked too many emails;
+/// unlimited when no EmailCheckRateLimiter is managed
+pub struct EmailCheckRateLimit;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for EmailCheckRateLimit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let limiter = request.rocket().state::<EmailCheckRateLimiter>().map(|limiter| &limiter.0);
+        guard(request, limiter, EmailCheckRateLimit)
+    }
+}
+
+/// Request guard failing with 429 once the client sent too many error
+/// reports; unlimited when no ClientErrorRateLimiter is managed
+pub struct ClientErrorRateLimit;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientErrorRateLimit {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let limiter = request.rocket().state::<ClientErrorRateLimiter>().map(|limiter| &limiter.0);
+        guard(request, limiter, ClientErrorRateLimit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last: u8) -> Option<IpAddr> {
+        Some(IpAddr::from([10, 0, 0, last]))
+    }
+
+    #[test]
+    fn test_limit_is_per_client() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(limiter.check_at(ip(1), now));
+        assert!(limiter.check_at(ip(1), now));
+        assert!(!limiter.check_at(ip(1), now));
+        assert!(limiter.check_at(ip(2), now));
+    }
+
+    #[test]
+    fn test_limit_resets_after_window() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(limiter.check_at(ip(1), now));
+        assert!(!limiter.check_at(ip(1), now + Duration::from_secs(59)));
+        assert!(limiter.check_at(ip(1), now + Duration::from_secs(60)));
+    }
+}
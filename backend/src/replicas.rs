@@ -0,0 +1,391 @@
+use crate::domain::RepositoryError;
+use crate::models::{TagCount, User, UserMatch, UserStats, UserStatus};
+use crate::repository::{BulkInsertReport, UserRepository};
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use rocket::serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_postgres::Client;
+
+// Read replicas - Decorator Pattern
+// ReplicaRoutedUserRepository sends the reads behind the user list, the user
+// page and search to the read replicas in turn; every other call, writes
+// included, goes to the primary. Replicas lag behind the primary, so reads
+// that decide a write (email and username checks, password history) stay on
+// it. A replica failing a read is skipped for a while and the read falls back
+// to the next replica, then to the primary. The readiness probe pings every
+// pool through DatabasePool
+
+/// How long a replica that failed a read is left out before it is tried again
+const REPLICA_RETRY_AFTER: Duration = Duration::from_secs(30);
+/// Longest wait for a pool to answer the readiness ping
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+struct Replica {
+    repository: Arc<dyn UserRepository>,
+    failed_at: Mutex<Option<Instant>>,
+}
+
+impl Replica {
+    fn available(&self) -> bool {
+        let failed_at = self.failed_at.lock().unwrap();
+        failed_at.is_none_or(|failed_at| failed_at.elapsed() >= REPLICA_RETRY_AFTER)
+    }
+
+    fn set_failed(&self, failed: bool) {
+        *self.failed_at.lock().unwrap() = failed.then(Instant::now);
+    }
+}
+
+/// User repository reading from replicas, falling back to the primary
+pub struct ReplicaRoutedUserRepository {
+    primary: Arc<dyn UserRepository>,
+    replicas: Vec<Replica>,
+    /// Round-robin position among the replicas
+    next: AtomicUsize,
+}
+
+impl ReplicaRoutedUserRepository {
+    pub fn new(primary: Arc<dyn UserRepository>, replicas: Vec<Arc<dyn UserRepository>>) -> Self {
+        ReplicaRoutedUserRepository {
+            primary,
+            replicas: replicas
+                .into_iter()
+                .map(|repository| Replica {
+                    repository,
+                    failed_at: Mutex::new(None),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Run `query` on the available replicas, starting with the next in turn,
+    /// until one answers; the primary answers when none does. Only storage
+    /// errors fall back, the others are the answer
+    async fn read<T, Fut>(&self, query: impl Fn(Arc<dyn UserRepository>) -> Fut) -> Result<T, RepositoryError>
+    where
+        Fut: Future<Output = Result<T, RepositoryError>>,
+    {
+        let count = self.replicas.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..count {
+            let index = (start + offset) % count;
+            let replica = &self.replicas[index];
+            if !replica.available() {
+                continue;
+            }
+            match query(replica.repository.clone()).await {
+                Err(RepositoryError::Storage(e)) => {
+                    eprintln!("Read replica {} failed, falling back: {}", index + 1, e);
+                    replica.set_failed(true);
+                }
+                result => {
+                    replica.set_failed(false);
+                    return result;
+                }
+            }
+        }
+        query(self.primary.clone()).await
+    }
+}
+
+#[async_trait]
+impl UserRepository for ReplicaRoutedUserRepository {
+    async fn create(&self, user: &User) -> Result<User, RepositoryError> {
+        self.primary.create(user).await
+    }
+
+    async fn create_many(&self, users: &[User], chunk_size: usize) -> BulkInsertReport {
+        self.primary.create_many(users, chunk_size).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        self.read(|users| async move { users.find_all().await }).await
+    }
+
+    async fn find_by_id(&self, id: i32) -> Result<Option<User>, RepositoryError> {
+        self.read(|users| async move { users.find_by_id(id).await }).await
+    }
+
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<User>, RepositoryError> {
+        self.primary.find_by_ids(ids).await
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, RepositoryError> {
+        self.primary.find_by_email(email).await
+    }
+
+    async fn exists_by_email(&self, email: &str) -> Result<bool, RepositoryError> {
+        self.primary.exists_by_email(email).await
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+        self.primary.find_by_username(username).await
+    }
+
+    async fn find_page(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        self.read(|users| async move { users.find_page(limit, offset).await }).await
+    }
+
+    async fn find_page_by_tag(&self, tag: &str, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        self.read(|users| async move { users.find_page_by_tag(tag, limit, offset).await }).await
+    }
+
+    async fn count_by_tag(&self, tag: &str) -> Result<i64, RepositoryError> {
+        self.read(|users| async move { users.count_by_tag(tag).await }).await
+    }
+
+    async fn tag_counts(&self) -> Result<Vec<TagCount>, RepositoryError> {
+        self.read(|users| async move { users.tag_counts().await }).await
+    }
+
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<UserMatch>, RepositoryError> {
+        self.read(|users| async move { users.search(query, limit).await }).await
+    }
+
+    // A stream cannot fall back halfway through, so exports read the primary
+    fn find_all_stream(&self, batch_size: i64) -> BoxStream<'static, Result<User, RepositoryError>> {
+        self.primary.find_all_stream(batch_size)
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        self.read(|users| async move { users.count().await }).await
+    }
+
+    async fn stats(&self, days: i32) -> Result<UserStats, RepositoryError> {
+        self.read(|users| async move { users.stats(days).await }).await
+    }
+
+    async fn update(&self, id: i32, user: &User) -> Result<(), RepositoryError> {
+        self.primary.update(id, user).await
+    }
+
+    async fn delete(&self, id: i32) -> Result<(), RepositoryError> {
+        self.primary.delete(id).await
+    }
+
+    async fn set_status(&self, id: i32, status: UserStatus) -> Result<(), RepositoryError> {
+        self.primary.set_status(id, status).await
+    }
+
+    async fn set_tags(&self, id: i32, tags: &[String]) -> Result<(), RepositoryError> {
+        self.primary.set_tags(id, tags).await
+    }
+
+    async fn record_login(&self, id: i32) -> Result<(), RepositoryError> {
+        self.primary.record_login(id).await
+    }
+
+    async fn anonymize(&self, id: i32, anonymized: &User) -> Result<(), RepositoryError> {
+        self.primary.anonymize(id, anonymized).await
+    }
+
+    async fn password_history(&self, id: i32, limit: i64) -> Result<Vec<String>, RepositoryError> {
+        self.primary.password_history(id, limit).await
+    }
+
+    async fn record_password(&self, id: i32, hash: &str, keep: i64) -> Result<(), RepositoryError> {
+        self.primary.record_password(id, hash, keep).await
+    }
+}
+
+/// A database connection checked by the readiness probe
+#[async_trait]
+pub trait DatabasePool: Send + Sync {
+    /// `primary` or `replica-<n>`
+    fn name(&self) -> &str;
+    fn is_primary(&self) -> bool;
+    async fn ping(&self) -> Result<(), String>;
+}
+
+pub struct PostgresPool {
+    name: String,
+    client: Arc<Client>,
+}
+
+impl PostgresPool {
+    pub fn primary(client: Arc<Client>) -> Self {
+        PostgresPool {
+            name: "primary".to_string(),
+            client,
+        }
+    }
+
+    /// The replica numbered `number`, counting from 1 like `DATABASE_REPLICA_URLS`
+    pub fn replica(number: usize, client: Arc<Client>) -> Self {
+        PostgresPool {
+            name: format!("replica-{}", number),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl DatabasePool for PostgresPool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_primary(&self) -> bool {
+        self.name == "primary"
+    }
+
+    async fn ping(&self) -> Result<(), String> {
+        self.client.simple_query("SELECT 1").await.map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+/// Health of one pool in the readiness report
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct PoolHealth {
+    pub name: String,
+    pub healthy: bool,
+}
+
+/// Body of `GET /readyz`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct Readiness {
+    /// `ready`, or `unavailable` while the primary does not answer
+    pub status: &'static str,
+    pub pools: Vec<PoolHealth>,
+}
+
+/// Ping every pool; only the primary decides readiness, since reads fall
+/// back to it when a replica is down
+pub async fn check_readiness(pools: &[Arc<dyn DatabasePool>]) -> Readiness {
+    let mut health = Vec::with_capacity(pools.len());
+    let mut ready = true;
+    for pool in pools {
+        let healthy = match tokio::time::timeout(PING_TIMEOUT, pool.ping()).await {
+            Ok(Ok(())) => true,
+            Ok(Err(e)) => {
+                eprintln!("Database pool {} is unhealthy: {}", pool.name(), e);
+                false
+            }
+            Err(_) => {
+                eprintln!("Database pool {} did not answer within {:?}", pool.name(), PING_TIMEOUT);
+                false
+            }
+        };
+        ready &= healthy || !pool.is_primary();
+        health.push(PoolHealth {
+            name: pool.name().to_string(),
+            healthy,
+        });
+    }
+    Readiness {
+        status: if ready { "ready" } else { "unavailable" },
+        pools: health,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::repository::tests::MockUserRepository;
+    use std::sync::atomic::AtomicBool;
+
+    /// Pool whose ping fails while `down` is set
+    pub struct MockDatabasePool {
+        pub name: &'static str,
+        pub down: AtomicBool,
+    }
+
+    impl MockDatabasePool {
+        pub fn new(name: &'static str) -> Self {
+            MockDatabasePool {
+                name,
+                down: AtomicBool::new(false),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl DatabasePool for MockDatabasePool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn is_primary(&self) -> bool {
+            self.name == "primary"
+        }
+
+        async fn ping(&self) -> Result<(), String> {
+            match self.down.load(Ordering::Relaxed) {
+                true => Err("connection refused".to_string()),
+                false => Ok(()),
+            }
+        }
+    }
+
+    /// Repository holding one user named `name`, with id 1
+    fn store(name: &str) -> Arc<MockUserRepository> {
+        let store = Arc::new(MockUserRepository::new());
+        let user = User::new(name.to_string(), format!("{}@example.com", name), "password123".to_string());
+        store.users.lock().unwrap().push(User { id: Some(1), ..user });
+        store
+    }
+
+    async fn name_of_user_1(repository: &ReplicaRoutedUserRepository) -> String {
+        repository.find_by_id(1).await.unwrap().unwrap().name
+    }
+
+    #[tokio::test]
+    async fn test_reads_go_to_replicas_in_turn_and_writes_to_the_primary() {
+        let (primary, first, second) = (store("primary"), store("first"), store("second"));
+        let repository = ReplicaRoutedUserRepository::new(primary.clone(), vec![first.clone() as _, second as _]);
+
+        assert_eq!(name_of_user_1(&repository).await, "first");
+        assert_eq!(name_of_user_1(&repository).await, "second");
+        assert_eq!(name_of_user_1(&repository).await, "first");
+
+        let user = User::new("Ada".to_string(), "ada@example.com".to_string(), "password123".to_string());
+        repository.create(&user).await.unwrap();
+        assert!(primary.find_by_email("ada@example.com").await.unwrap().is_some());
+        assert!(first.find_by_email("ada@example.com").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failing_replicas_fall_back() {
+        let (first, second) = (store("first"), store("second"));
+        let repository = ReplicaRoutedUserRepository::new(store("primary"), vec![first.clone() as _, second.clone() as _]);
+
+        first.unavailable.store(true, Ordering::Relaxed);
+        assert_eq!(name_of_user_1(&repository).await, "second");
+        assert_eq!(name_of_user_1(&repository).await, "second");
+        // The failed replica is left out for a while, even once it is back
+        first.unavailable.store(false, Ordering::Relaxed);
+        assert_eq!(name_of_user_1(&repository).await, "second");
+
+        second.unavailable.store(true, Ordering::Relaxed);
+        assert_eq!(name_of_user_1(&repository).await, "primary");
+        assert_eq!(repository.find_all().await.unwrap()[0].name, "primary");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_depends_on_the_primary_only() {
+        let primary = Arc::new(MockDatabasePool::new("primary"));
+        let replica = Arc::new(MockDatabasePool::new("replica-1"));
+        let pools: Vec<Arc<dyn DatabasePool>> = vec![primary.clone(), replica.clone()];
+
+        replica.down.store(true, Ordering::Relaxed);
+        let readiness = check_readiness(&pools).await;
+        assert_eq!(readiness.status, "ready");
+        assert_eq!(
+            readiness.pools,
+            vec![
+                PoolHealth { name: "primary".to_string(), healthy: true },
+                PoolHealth { name: "replica-1".to_string(), healthy: false },
+            ]
+        );
+
+        primary.down.store(true, Ordering::Relaxed);
+        assert_eq!(check_readiness(&pools).await.status, "unavailable");
+    }
+}
@@ -1,86 +1,785 @@
-use crate::models::User;
+use crate::crypto::FieldCipher;
+use crate::domain::RepositoryError;
+use crate::models::{Address, DailySignups, TagCount, User, UserMatch, UserStats, UserStatus};
+use crate::query_retry::QueryRetry;
+use crate::query_timeout::{QueryTimeout, QUERY_TIMEOUT_MESSAGE};
 use async_trait::async_trait;
-use rocket::http::Status;
-use rocket::response::status::Custom;
+use shared::normalize_email;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use std::future::Future;
 use std::sync::Arc;
-use tokio_postgres::Client;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, Row};
+
+/// Rows per statement of a bulk insert
+pub const DEFAULT_BULK_CHUNK_SIZE: usize = 500;
+
+/// Result of one chunk of a bulk insert; a chunk is inserted entirely or not at all
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkResult {
+    /// Index of the first user of the chunk in the input
+    pub offset: usize,
+    pub len: usize,
+    pub error: Option<String>,
+}
+
+/// Outcome of `create_many`, chunk by chunk
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkInsertReport {
+    pub chunks: Vec<ChunkResult>,
+}
+
+impl BulkInsertReport {
+    pub fn inserted(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|chunk| chunk.error.is_none())
+            .map(|chunk| chunk.len)
+            .sum()
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &ChunkResult> {
+        self.chunks.iter().filter(|chunk| chunk.error.is_some())
+    }
+}
 
 /// Repository trait - Dependency Inversion Principle
 /// High-level modules (service layer) depend on this abstraction, not on concrete implementations
 #[async_trait]
 pub trait UserRepository: Send + Sync {
-    async fn create(&self, user: &User) -> Result<(), Custom<String>>;
-    async fn find_all(&self) -> Result<Vec<User>, Custom<String>>;
-    async fn update(&self, id: i32, user: &User) -> Result<(), Custom<String>>;
-    async fn delete(&self, id: i32) -> Result<(), Custom<String>>;
+    async fn create(&self, user: &User) -> Result<User, RepositoryError>;
+    /// Insert many users with one statement per chunk of `chunk_size`; a
+    /// failing chunk is reported and the following chunks are still inserted
+    async fn create_many(&self, users: &[User], chunk_size: usize) -> BulkInsertReport;
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError>;
+    async fn find_by_id(&self, id: i32) -> Result<Option<User>, RepositoryError>;
+    /// The users with any of `ids`, ordered by id; unknown ids are skipped
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<User>, RepositoryError>;
+    /// The user with `email`, compared case-insensitively
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, RepositoryError>;
+    async fn exists_by_email(&self, email: &str) -> Result<bool, RepositoryError>;
+    /// The user with `username`, which is stored normalized
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError>;
+    async fn find_page(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError>;
+    /// Like `find_page`, restricted to the users tagged `tag`
+    async fn find_page_by_tag(&self, tag: &str, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError>;
+    async fn count_by_tag(&self, tag: &str) -> Result<i64, RepositoryError>;
+    /// Every tag in use with the number of users having it, most used first
+    async fn tag_counts(&self) -> Result<Vec<TagCount>, RepositoryError>;
+    /// Users whose name or email resemble `query`, best matches first
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<UserMatch>, RepositoryError>;
+    /// Every user ordered by id, read `batch_size` rows at a time so memory
+    /// stays bounded however many users there are
+    fn find_all_stream(&self, batch_size: i64) -> BoxStream<'static, Result<User, RepositoryError>>;
+    async fn count(&self) -> Result<i64, RepositoryError>;
+    async fn stats(&self, days: i32) -> Result<UserStats, RepositoryError>;
+    async fn update(&self, id: i32, user: &User) -> Result<(), RepositoryError>;
+    async fn delete(&self, id: i32) -> Result<(), RepositoryError>;
+    async fn set_status(&self, id: i32, status: UserStatus) -> Result<(), RepositoryError>;
+    /// Replace every tag of a user; `tags` are already normalized
+    async fn set_tags(&self, id: i32, tags: &[String]) -> Result<(), RepositoryError>;
+    async fn record_login(&self, id: i32) -> Result<(), RepositoryError>;
+    /// Overwrite the personal data of a user with `anonymized`, username and tags
+    /// included; the password history is cleared
+    async fn anonymize(&self, id: i32, anonymized: &User) -> Result<(), RepositoryError>;
+    /// Hashes of the last `limit` passwords of a user, newest first
+    async fn password_history(&self, id: i32, limit: i64) -> Result<Vec<String>, RepositoryError>;
+    /// Add a password hash to the history of a user, keeping its `keep` newest entries
+    async fn record_password(&self, id: i32, hash: &str, keep: i64) -> Result<(), RepositoryError>;
 }
 
+const USER_COLUMNS: &str = "id, name, email, password, status,
+    to_char(last_login_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"'), phone, address, username, tags,
+    to_char(updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"'),
+    to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"')";
+
+/// Lowest pg_trgm word similarity counted as a search match; low enough
+/// for `jon` to find "John" (0.5) while "Jane" (0.25) is left out
+pub const SEARCH_MIN_SCORE: f32 = 0.3;
+
 /// PostgreSQL implementation of UserRepository
 /// This follows the Single Responsibility Principle - only handles database operations
+#[derive(Clone)]
 pub struct PostgresUserRepository {
     client: Arc<Client>,
+    /// Encrypts name, email, phone and address at rest when configured
+    cipher: Option<Arc<FieldCipher>>,
+    /// Longest wait for a query; unbounded when not set
+    timeout: Option<QueryTimeout>,
+    /// Backoff of statements rolled back by a serialization failure or deadlock; not retried when not set
+    retry: Option<QueryRetry>,
 }
 
 impl PostgresUserRepository {
     pub fn new(client: Arc<Client>) -> Self {
-        PostgresUserRepository { client }
+        PostgresUserRepository {
+            client,
+            cipher: None,
+            timeout: None,
+            retry: None,
+        }
+    }
+
+    /// Store names, emails, phones and addresses encrypted with `cipher`
+    pub fn with_cipher(mut self, cipher: Arc<FieldCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Stop waiting for queries as `timeout` says
+    pub fn with_timeout(mut self, timeout: QueryTimeout) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry statements rolled back by a serialization failure or deadlock as `retry` says. Only
+    /// for autocommit connections: in a transaction such an error aborts the
+    /// whole transaction, not just the statement
+    pub fn with_retry(mut self, retry: QueryRetry) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// The value as stored: encrypted when a cipher is configured
+    fn seal(&self, value: &str) -> String {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(value),
+            None => value.to_string(),
+        }
+    }
+
+    fn open(&self, value: String) -> Result<String, RepositoryError> {
+        match &self.cipher {
+            Some(cipher) => cipher
+                .decrypt(&value)
+                .map_err(RepositoryError::Storage),
+            None => Ok(value),
+        }
     }
 
-    async fn execute_query(
+    fn seal_phone(&self, user: &User) -> Option<String> {
+        user.phone.as_deref().map(|phone| self.seal(phone))
+    }
+
+    /// Addresses are stored as JSON text, so they can be sealed like the other columns
+    fn seal_address(&self, user: &User) -> Option<String> {
+        user.address.as_ref().map(|address| {
+            self.seal(&serde_json::to_string(address).expect("addresses serialize to JSON"))
+        })
+    }
+
+    fn open_address(&self, value: Option<String>) -> Result<Option<Address>, RepositoryError> {
+        value
+            .map(|value| {
+                serde_json::from_str(&self.open(value)?)
+                    .map_err(|e| RepositoryError::Storage(format!("Malformed stored address: {}", e)))
+            })
+            .transpose()
+    }
+
+    /// Lookup hash of an email, which keeps emails unique once encrypted
+    fn email_hash(&self, email: &str) -> Option<String> {
+        self.cipher.as_ref().map(|cipher| cipher.lookup_hash(email))
+    }
+
+    /// Every hash an email may be stored under: rows not re-keyed since the
+    /// last key rotation still carry the hash of a previous key
+    fn email_hashes(&self, email: &str) -> Vec<String> {
+        self.cipher
+            .as_ref()
+            .map(|cipher| cipher.lookup_hashes(email))
+            .unwrap_or_default()
+    }
+
+    /// Map a `SELECT USER_COLUMNS` row to a User
+    fn user_from_row(&self, row: &Row) -> Result<User, RepositoryError> {
+        let status: String = row.get(4);
+        let mut user = User::with_id(
+            row.get(0),
+            self.open(row.get(1))?,
+            self.open(row.get(2))?,
+            row.get(3),
+        )
+        .with_status(status.parse().unwrap_or_default())
+        .with_contact(
+            row.get::<_, Option<String>>(6).map(|phone| self.open(phone)).transpose()?,
+            self.open_address(row.get(7))?,
+        )
+        .with_username(row.get(8))
+        .with_tags(row.get(9));
+        user.last_login_at = row.get(5);
+        user.updated_at = row.get(10);
+        user.created_at = row.get(11);
+        Ok(user)
+    }
+
+    /// Up to `limit` users with an id above `after_id` (keyset pagination)
+    async fn find_after(&self, after_id: i32, limit: i64) -> Result<Vec<User>, RepositoryError> {
+        self
+            .query(
+                &format!(
+                    "SELECT {} FROM users WHERE id > $1 ORDER BY id LIMIT $2",
+                    USER_COLUMNS
+                ),
+                &[&after_id, &limit],
+            )
+            .await?
+            .iter()
+            .map(|row| self.user_from_row(row))
+            .collect()
+    }
+
+    /// Re-encrypt every name and email that is still plaintext or encrypted
+    /// with a previous key, returning how many users were rewritten
+    pub async fn rekey(&self) -> Result<u64, RepositoryError> {
+        let Some(cipher) = &self.cipher else {
+            return Err(RepositoryError::Unsupported(
+                "PII encryption is not configured".to_string(),
+            ));
+        };
+
+        let rows = self
+            .query("SELECT id, name, email, email_hash, phone, address FROM users", &[])
+            .await?;
+
+        let mut rekeyed = 0;
+        for row in rows {
+            let id: i32 = row.get(0);
+            let name: String = row.get(1);
+            let email: String = row.get(2);
+            let email_hash: Option<String> = row.get(3);
+            let phone: Option<String> = row.get(4);
+            let address: Option<String> = row.get(5);
+
+            let plain_email = self.open(email.clone())?;
+            let hash = cipher.lookup_hash(&plain_email);
+            let optional_current = |value: &Option<String>| value.as_deref().is_none_or(|value| cipher.is_current(value));
+            if cipher.is_current(&name)
+                && cipher.is_current(&email)
+                && optional_current(&phone)
+                && optional_current(&address)
+                && email_hash.as_ref() == Some(&hash)
+            {
+                continue;
+            }
+
+            let reseal = |value: Option<String>| -> Result<Option<String>, RepositoryError> {
+                value.map(|value| Ok(cipher.encrypt(&self.open(value)?))).transpose()
+            };
+            // Not an edit of the user, so updated_at is left alone
+            self.execute_query(
+                "UPDATE users SET name = $1, email = $2, email_hash = $3, phone = $4, address = $5 WHERE id = $6",
+                &[
+                    &cipher.encrypt(&self.open(name)?),
+                    &cipher.encrypt(&plain_email),
+                    &hash,
+                    &reseal(phone)?,
+                    &reseal(address)?,
+                    &id,
+                ],
+            )
+            .await?;
+            rekeyed += 1;
+        }
+        Ok(rekeyed)
+    }
+
+    /// Await a statement of the client, retries included, within the query timeout if there is one
+    async fn bounded<T>(
         &self,
-        query: &str,
-        params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
-    ) -> Result<u64, Custom<String>> {
-        self.client
-            .execute(query, params)
-            .await
-            .map_err(|e| Custom(Status::InternalServerError, e.to_string()))
+        statement: impl Future<Output = Result<T, tokio_postgres::Error>>,
+    ) -> Result<T, RepositoryError> {
+        match &self.timeout {
+            Some(timeout) => timeout.run(statement).await,
+            None => statement.await.map_err(RepositoryError::from),
+        }
+    }
+
+    /// Run a statement of the client, again after it was rolled back if retries are configured
+    async fn retried<T, F>(&self, statement: impl Fn() -> F) -> Result<T, tokio_postgres::Error>
+    where
+        F: Future<Output = Result<T, tokio_postgres::Error>>,
+    {
+        match &self.retry {
+            Some(retry) => retry.run(statement).await,
+            None => statement().await,
+        }
+    }
+
+    async fn query(&self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<Vec<Row>, RepositoryError> {
+        self.bounded(self.retried(|| self.client.query(query, params))).await
+    }
+
+    async fn query_one(&self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<Row, RepositoryError> {
+        self.bounded(self.retried(|| self.client.query_one(query, params))).await
+    }
+
+    async fn query_opt(&self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<Option<Row>, RepositoryError> {
+        self.bounded(self.retried(|| self.client.query_opt(query, params))).await
+    }
+
+    async fn execute_query(&self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<u64, RepositoryError> {
+        self.bounded(self.retried(|| self.client.execute(query, params))).await
+    }
+}
+
+impl From<tokio_postgres::Error> for RepositoryError {
+    /// Unique violations become conflicts, so a write racing a duplicate
+    /// check is reported like the check itself would have. Statements the
+    /// server cancelled after `statement_timeout` are timeouts
+    fn from(error: tokio_postgres::Error) -> Self {
+        match error.as_db_error() {
+            Some(db_error) if db_error.code() == &SqlState::UNIQUE_VIOLATION => {
+                RepositoryError::Conflict(db_error.message().to_string())
+            }
+            Some(db_error) if db_error.code() == &SqlState::QUERY_CANCELED => {
+                RepositoryError::Timeout(QUERY_TIMEOUT_MESSAGE.to_string())
+            }
+            _ => RepositoryError::Storage(error.to_string()),
+        }
     }
 }
 
 #[async_trait]
 impl UserRepository for PostgresUserRepository {
-    async fn create(&self, user: &User) -> Result<(), Custom<String>> {
-        self.execute_query(
-            "INSERT INTO users (name, email, password) VALUES ($1, $2, $3)",
-            &[&user.name, &user.email, &user.password],
+    async fn create(&self, user: &User) -> Result<User, RepositoryError> {
+        let row = self
+            .query_one(
+                "INSERT INTO users (name, email, password, email_hash, phone, address, username, tags)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+                &[
+                    &self.seal(&user.name),
+                    &self.seal(&user.email),
+                    &user.password,
+                    &self.email_hash(&user.email),
+                    &self.seal_phone(user),
+                    &self.seal_address(user),
+                    &user.username,
+                    &user.tags,
+                ],
+            )
+            .await?;
+
+        Ok(User::with_id(
+            row.get(0),
+            user.name.clone(),
+            user.email.clone(),
+            user.password.clone(),
         )
-        .await?;
-        Ok(())
+        .with_username(user.username.clone())
+        .with_tags(user.tags.clone())
+        .with_contact(user.phone.clone(), user.address.clone()))
+    }
+
+    async fn create_many(&self, users: &[User], chunk_size: usize) -> BulkInsertReport {
+        let mut report = BulkInsertReport::default();
+        for (index, chunk) in users.chunks(chunk_size.max(1)).enumerate() {
+            let mut names = Vec::with_capacity(chunk.len());
+            let mut emails = Vec::with_capacity(chunk.len());
+            let mut passwords = Vec::with_capacity(chunk.len());
+            let mut email_hashes = Vec::with_capacity(chunk.len());
+            let mut phones = Vec::with_capacity(chunk.len());
+            let mut addresses = Vec::with_capacity(chunk.len());
+            let mut usernames = Vec::with_capacity(chunk.len());
+            for user in chunk {
+                names.push(self.seal(&user.name));
+                emails.push(self.seal(&user.email));
+                passwords.push(user.password.clone());
+                email_hashes.push(self.email_hash(&user.email));
+                phones.push(self.seal_phone(user));
+                addresses.push(self.seal_address(user));
+                usernames.push(user.username.clone());
+            }
+
+            // One array per column keeps the statement at seven parameters whatever the chunk size.
+            // The inner result keeps the server's message, which the repository error would lose
+            let params: [&(dyn ToSql + Sync); 7] =
+                [&names, &emails, &passwords, &email_hashes, &phones, &addresses, &usernames];
+            let statement = async {
+                Ok(self
+                    .retried(|| {
+                        self.client.execute(
+                            "INSERT INTO users (name, email, password, email_hash, phone, address, username)
+                             SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::text[], $5::text[],
+                                                  $6::text[], $7::text[])",
+                            &params,
+                        )
+                    })
+                    .await)
+            };
+            let result = self.bounded(statement).await;
+            report.chunks.push(ChunkResult {
+                offset: index * chunk_size.max(1),
+                len: chunk.len(),
+                error: match result {
+                    Ok(Ok(_)) => None,
+                    Ok(Err(e)) => Some(match e.as_db_error() {
+                        Some(db_error) => db_error.message().to_string(),
+                        None => e.to_string(),
+                    }),
+                    Err(e) => Some(e.to_string()),
+                },
+            });
+        }
+        report
     }
 
-    async fn find_all(&self) -> Result<Vec<User>, Custom<String>> {
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
         let users = self
-            .client
-            .query("SELECT id, name, email, password FROM users", &[])
-            .await
-            .map_err(|e| Custom(Status::InternalServerError, e.to_string()))?
+            .query(&format!("SELECT {} FROM users", USER_COLUMNS), &[])
+            .await?
+            .iter()
+            .map(|row| self.user_from_row(row))
+            .collect::<Result<Vec<User>, _>>()?;
+
+        Ok(users)
+    }
+
+    async fn find_by_id(&self, id: i32) -> Result<Option<User>, RepositoryError> {
+        let user = self
+            .query_opt(
+                &format!("SELECT {} FROM users WHERE id = $1", USER_COLUMNS),
+                &[&id],
+            )
+            .await?
+            .map(|row| self.user_from_row(&row))
+            .transpose()?;
+
+        Ok(user)
+    }
+
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<User>, RepositoryError> {
+        self
+            .query(
+                &format!("SELECT {} FROM users WHERE id = ANY($1) ORDER BY id", USER_COLUMNS),
+                &[&ids],
+            )
+            .await?
             .iter()
-            .map(|row| User {
-                id: Some(row.get(0)),
-                name: row.get(1),
-                email: row.get(2),
-                password: row.get(3),
+            .map(|row| self.user_from_row(row))
+            .collect()
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, RepositoryError> {
+        let email = normalize_email(email);
+        // Encrypted emails are matched by their lookup hash under any key; rows written
+        // before encryption was enabled still match on the plaintext column
+        let user = self
+            .query_opt(
+                &format!(
+                    "SELECT {} FROM users WHERE email_hash = ANY($1) OR LOWER(email) = $2 LIMIT 1",
+                    USER_COLUMNS
+                ),
+                &[&self.email_hashes(&email), &email],
+            )
+            .await?
+            .map(|row| self.user_from_row(&row))
+            .transpose()?;
+
+        Ok(user)
+    }
+
+    async fn exists_by_email(&self, email: &str) -> Result<bool, RepositoryError> {
+        let email = normalize_email(email);
+        let row = self
+            .query_one(
+                "SELECT EXISTS(SELECT 1 FROM users WHERE email_hash = ANY($1) OR LOWER(email) = $2)",
+                &[&self.email_hashes(&email), &email],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+        let user = self
+            .query_opt(
+                &format!("SELECT {} FROM users WHERE username = $1", USER_COLUMNS),
+                &[&username],
+            )
+            .await?
+            .map(|row| self.user_from_row(&row))
+            .transpose()?;
+
+        Ok(user)
+    }
+
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<UserMatch>, RepositoryError> {
+        if self.cipher.is_some() {
+            return Err(RepositoryError::Unsupported(
+                "Search is unavailable while names and emails are encrypted".to_string(),
+            ));
+        }
+
+        // The `<%` operators use this threshold, which lets them use the trigram indexes
+        self
+            .execute_query(
+                "SELECT set_config('pg_trgm.word_similarity_threshold', $1, false)",
+                &[&SEARCH_MIN_SCORE.to_string()],
+            )
+            .await?;
+        let rows = self
+            .query(
+                &format!(
+                    "SELECT {}, GREATEST(word_similarity($1, name), word_similarity($1, email)) AS score
+                     FROM users WHERE $1 <% name OR $1 <% email
+                     ORDER BY score DESC, id LIMIT $2",
+                    USER_COLUMNS
+                ),
+                &[&query, &limit],
+            )
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(UserMatch {
+                    user: self.user_from_row(row)?,
+                    score: row.get("score"),
+                })
             })
-            .collect::<Vec<User>>();
+            .collect()
+    }
+
+    async fn find_page(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        let users = self
+            .query(
+                &format!("SELECT {} FROM users ORDER BY id LIMIT $1 OFFSET $2", USER_COLUMNS),
+                &[&limit, &offset],
+            )
+            .await?
+            .iter()
+            .map(|row| self.user_from_row(row))
+            .collect::<Result<Vec<User>, _>>()?;
 
         Ok(users)
     }
 
-    async fn update(&self, id: i32, user: &User) -> Result<(), Custom<String>> {
+    async fn find_page_by_tag(&self, tag: &str, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        self
+            .query(
+                &format!(
+                    "SELECT {} FROM users WHERE tags @> ARRAY[$1] ORDER BY id LIMIT $2 OFFSET $3",
+                    USER_COLUMNS
+                ),
+                &[&tag, &limit, &offset],
+            )
+            .await?
+            .iter()
+            .map(|row| self.user_from_row(row))
+            .collect()
+    }
+
+    async fn count_by_tag(&self, tag: &str) -> Result<i64, RepositoryError> {
+        let row = self
+            .query_one("SELECT COUNT(*) FROM users WHERE tags @> ARRAY[$1]", &[&tag])
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn tag_counts(&self) -> Result<Vec<TagCount>, RepositoryError> {
+        Ok(self
+            .query(
+                "SELECT tag, COUNT(*) FROM users, unnest(tags) AS tag GROUP BY tag ORDER BY COUNT(*) DESC, tag",
+                &[],
+            )
+            .await?
+            .iter()
+            .map(|row| TagCount {
+                tag: row.get(0),
+                count: row.get(1),
+            })
+            .collect())
+    }
+
+    // Keyset batches rather than a server-side cursor: a cursor needs a
+    // transaction, which would hold the shared client for the whole export
+    fn find_all_stream(&self, batch_size: i64) -> BoxStream<'static, Result<User, RepositoryError>> {
+        let repository = self.clone();
+        stream::try_unfold(Some(0), move |after_id| {
+            let repository = repository.clone();
+            async move {
+                let Some(after_id) = after_id else {
+                    return Ok::<_, RepositoryError>(None);
+                };
+                let batch = repository.find_after(after_id, batch_size).await?;
+                let next = match batch.last() {
+                    Some(last) if batch.len() as i64 == batch_size => last.id,
+                    _ => None,
+                };
+                Ok(Some((stream::iter(batch.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        let row = self
+            .query_one("SELECT COUNT(*) FROM users", &[])
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn stats(&self, days: i32) -> Result<UserStats, RepositoryError> {
+        let totals = self
+            .query_one(
+                "SELECT COUNT(*),
+                        COUNT(*) FILTER (WHERE email_verified),
+                        COUNT(*) FILTER (WHERE NOT email_verified)
+                 FROM users",
+                &[],
+            )
+            .await?;
+
+        // generate_series yields every day of the window, so days without signups report 0
+        let signups_per_day = self
+            .query(
+                "SELECT to_char(d.day, 'YYYY-MM-DD'), COUNT(u.id)
+                 FROM generate_series(
+                        date_trunc('day', NOW()) - make_interval(days => $1 - 1),
+                        date_trunc('day', NOW()),
+                        INTERVAL '1 day'
+                      ) AS d(day)
+                 LEFT JOIN users u ON date_trunc('day', u.created_at) = d.day
+                 GROUP BY d.day
+                 ORDER BY d.day",
+                &[&days],
+            )
+            .await?
+            .iter()
+            .map(|row| DailySignups {
+                day: row.get(0),
+                count: row.get(1),
+            })
+            .collect();
+
+        Ok(UserStats {
+            total: totals.get(0),
+            verified: totals.get(1),
+            unverified: totals.get(2),
+            signups_per_day,
+        })
+    }
+
+    // A username can be set once; COALESCE keeps the stored one
+    async fn update(&self, id: i32, user: &User) -> Result<(), RepositoryError> {
         self.execute_query(
-            "UPDATE users SET name = $1, email = $2, password = $3 WHERE id = $4",
-            &[&user.name, &user.email, &user.password, &id],
+            "UPDATE users
+             SET name = $1, email = $2, password = $3, email_hash = $4, phone = $5, address = $6,
+                 username = COALESCE(username, $7), updated_at = NOW()
+             WHERE id = $8",
+            &[
+                &self.seal(&user.name),
+                &self.seal(&user.email),
+                &user.password,
+                &self.email_hash(&user.email),
+                &self.seal_phone(user),
+                &self.seal_address(user),
+                &user.username,
+                &id,
+            ],
         )
         .await?;
         Ok(())
     }
 
-    async fn delete(&self, id: i32) -> Result<(), Custom<String>> {
-        self.execute_query("DELETE FROM users WHERE id = $1", &[&id])
+    async fn delete(&self, id: i32) -> Result<(), RepositoryError> {
+        let deleted = self
+            .execute_query("DELETE FROM users WHERE id = $1", &[&id])
+            .await?;
+        if deleted == 0 {
+            return Err(RepositoryError::NotFound(format!("User with id {} not found", id)));
+        }
+        Ok(())
+    }
+
+    async fn set_status(&self, id: i32, status: UserStatus) -> Result<(), RepositoryError> {
+        let updated = self
+            .execute_query(
+                "UPDATE users SET status = $1, updated_at = NOW() WHERE id = $2",
+                &[&status.as_str(), &id],
+            )
+            .await?;
+        if updated == 0 {
+            return Err(RepositoryError::NotFound(format!("User with id {} not found", id)));
+        }
+        Ok(())
+    }
+
+    async fn set_tags(&self, id: i32, tags: &[String]) -> Result<(), RepositoryError> {
+        let updated = self
+            .execute_query("UPDATE users SET tags = $1, updated_at = NOW() WHERE id = $2", &[&tags, &id])
+            .await?;
+        if updated == 0 {
+            return Err(RepositoryError::NotFound(format!("User with id {} not found", id)));
+        }
+        Ok(())
+    }
+
+    async fn record_login(&self, id: i32) -> Result<(), RepositoryError> {
+        // Not an edit of the user, so updated_at is left alone
+        self.execute_query("UPDATE users SET last_login_at = NOW() WHERE id = $1", &[&id])
             .await?;
         Ok(())
     }
+
+    async fn anonymize(&self, id: i32, anonymized: &User) -> Result<(), RepositoryError> {
+        let updated = self
+            .execute_query(
+                "UPDATE users
+                 SET name = $1, email = $2, password = $3, status = $4, email_hash = $5,
+                     phone = NULL, address = NULL, username = NULL, tags = '{}', last_login_at = NULL,
+                     updated_at = NOW()
+                 WHERE id = $6",
+                &[
+                    &self.seal(&anonymized.name),
+                    &self.seal(&anonymized.email),
+                    &anonymized.password,
+                    &anonymized.status.as_str(),
+                    &self.email_hash(&anonymized.email),
+                    &id,
+                ],
+            )
+            .await?;
+        if updated == 0 {
+            return Err(RepositoryError::NotFound(format!("User with id {} not found", id)));
+        }
+        self.execute_query("DELETE FROM password_history WHERE user_id = $1", &[&id])
+            .await?;
+        Ok(())
+    }
+
+    async fn password_history(&self, id: i32, limit: i64) -> Result<Vec<String>, RepositoryError> {
+        Ok(self
+            .query(
+                "SELECT hash FROM password_history WHERE user_id = $1 ORDER BY id DESC LIMIT $2",
+                &[&id, &limit],
+            )
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect())
+    }
+
+    async fn record_password(&self, id: i32, hash: &str, keep: i64) -> Result<(), RepositoryError> {
+        self.execute_query(
+            "INSERT INTO password_history (user_id, hash) VALUES ($1, $2)",
+            &[&id, &hash],
+        )
+        .await?;
+        self.execute_query(
+            "DELETE FROM password_history
+             WHERE user_id = $1 AND id NOT IN (
+                 SELECT id FROM password_history WHERE user_id = $1 ORDER BY id DESC LIMIT $2
+             )",
+            &[&id, &keep],
+        )
+        .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -89,61 +788,271 @@ pub mod tests {
     use crate::models::User;
 
     // Mock repository for testing - demonstrates Interface Segregation Principle
+    #[derive(Default)]
     pub struct MockUserRepository {
         pub users: std::sync::Mutex<Vec<User>>,
+        /// Password hashes by user id, oldest first
+        pub password_history: std::sync::Mutex<std::collections::HashMap<i32, Vec<String>>>,
+        /// Reads of the list and by id fail while set, like an unreachable database
+        pub unavailable: std::sync::atomic::AtomicBool,
+        /// Recording a password hash fails while set
+        pub history_unavailable: std::sync::atomic::AtomicBool,
     }
 
     impl MockUserRepository {
         pub fn new() -> Self {
-            MockUserRepository {
-                users: std::sync::Mutex::new(Vec::new()),
+            MockUserRepository::default()
+        }
+
+        fn check_available(&self) -> Result<(), RepositoryError> {
+            match self.unavailable.load(std::sync::atomic::Ordering::Relaxed) {
+                true => Err(RepositoryError::Storage("connection refused".to_string())),
+                false => Ok(()),
             }
         }
     }
 
+    /// `updated_at` of a row changed now, to the second like the real columns
+    fn now() -> Option<String> {
+        let now = time::OffsetDateTime::now_utc().replace_nanosecond(0).ok()?;
+        now.format(&time::format_description::well_known::Rfc3339).ok()
+    }
+
     #[async_trait]
     impl UserRepository for MockUserRepository {
-        async fn create(&self, user: &User) -> Result<(), Custom<String>> {
+        async fn create(&self, user: &User) -> Result<User, RepositoryError> {
             let mut users = self.users.lock().unwrap();
             let id = users.len() as i32 + 1;
-            let mut new_user = user.clone();
+            let mut new_user = user.clone().with_status(UserStatus::Active);
             new_user.id = Some(id);
-            users.push(new_user);
-            Ok(())
+            new_user.created_at = now();
+            new_user.updated_at = now();
+            users.push(new_user.clone());
+            Ok(new_user)
+        }
+
+        // Chunks fail as a whole when one of their emails is already taken
+        async fn create_many(&self, users: &[User], chunk_size: usize) -> BulkInsertReport {
+            let mut report = BulkInsertReport::default();
+            for (index, chunk) in users.chunks(chunk_size.max(1)).enumerate() {
+                let duplicate = {
+                    let stored = self.users.lock().unwrap();
+                    chunk.iter().find(|user| stored.iter().any(|u| u.email == user.email)).cloned()
+                };
+                if duplicate.is_none() {
+                    for user in chunk {
+                        self.create(user).await.unwrap();
+                    }
+                }
+                report.chunks.push(ChunkResult {
+                    offset: index * chunk_size.max(1),
+                    len: chunk.len(),
+                    error: duplicate.map(|user| format!("Email {} already exists", user.email)),
+                });
+            }
+            report
         }
 
-        async fn find_all(&self) -> Result<Vec<User>, Custom<String>> {
+        async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+            self.check_available()?;
             let users = self.users.lock().unwrap();
             Ok(users.clone())
         }
 
-        async fn update(&self, id: i32, user: &User) -> Result<(), Custom<String>> {
+        async fn find_by_id(&self, id: i32) -> Result<Option<User>, RepositoryError> {
+            self.check_available()?;
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.id == Some(id)).cloned())
+        }
+
+        async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<User>, RepositoryError> {
+            let users = self.users.lock().unwrap();
+            let mut found: Vec<User> = users.iter().filter(|u| u.id.is_some_and(|id| ids.contains(&id))).cloned().collect();
+            found.sort_by_key(|u| u.id);
+            Ok(found)
+        }
+
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, RepositoryError> {
+            let email = normalize_email(email);
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| normalize_email(&u.email) == email).cloned())
+        }
+
+        async fn exists_by_email(&self, email: &str) -> Result<bool, RepositoryError> {
+            Ok(self.find_by_email(email).await?.is_some())
+        }
+
+        async fn find_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.username.as_deref() == Some(username)).cloned())
+        }
+
+        // Substring matches only, all scored 1.0; similarity is left to pg_trgm
+        async fn search(&self, query: &str, limit: i64) -> Result<Vec<UserMatch>, RepositoryError> {
+            let query = query.to_lowercase();
+            let users = self.users.lock().unwrap();
+            Ok(users
+                .iter()
+                .filter(|u| u.name.to_lowercase().contains(&query) || u.email.contains(&query))
+                .take(limit as usize)
+                .map(|user| UserMatch {
+                    user: user.clone(),
+                    score: 1.0,
+                })
+                .collect())
+        }
+
+        async fn find_page(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+            let users = self.users.lock().unwrap();
+            Ok(users
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn find_page_by_tag(&self, tag: &str, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+            let users = self.users.lock().unwrap();
+            Ok(users
+                .iter()
+                .filter(|u| u.tags.iter().any(|t| t == tag))
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn count_by_tag(&self, tag: &str) -> Result<i64, RepositoryError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().filter(|u| u.tags.iter().any(|t| t == tag)).count() as i64)
+        }
+
+        async fn tag_counts(&self) -> Result<Vec<TagCount>, RepositoryError> {
+            let mut counts = std::collections::BTreeMap::<String, i64>::new();
+            for tag in self.users.lock().unwrap().iter().flat_map(|u| u.tags.clone()) {
+                *counts.entry(tag).or_default() += 1;
+            }
+            let mut counts: Vec<TagCount> = counts.into_iter().map(|(tag, count)| TagCount { tag, count }).collect();
+            counts.sort_by_key(|count| std::cmp::Reverse(count.count));
+            Ok(counts)
+        }
+
+        fn find_all_stream(&self, _batch_size: i64) -> BoxStream<'static, Result<User, RepositoryError>> {
+            let users = self.users.lock().unwrap().clone();
+            stream::iter(users.into_iter().map(Ok)).boxed()
+        }
+
+        async fn count(&self) -> Result<i64, RepositoryError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.len() as i64)
+        }
+
+        // The mock does not track dates or verification, so everyone is unverified
+        async fn stats(&self, _days: i32) -> Result<UserStats, RepositoryError> {
+            let total = self.users.lock().unwrap().len() as i64;
+            Ok(UserStats {
+                total,
+                verified: 0,
+                unverified: total,
+                signups_per_day: Vec::new(),
+            })
+        }
+
+        async fn update(&self, id: i32, user: &User) -> Result<(), RepositoryError> {
             let mut users = self.users.lock().unwrap();
             if let Some(existing_user) = users.iter_mut().find(|u| u.id == Some(id)) {
                 existing_user.name = user.name.clone();
                 existing_user.email = user.email.clone();
                 existing_user.password = user.password.clone();
+                if existing_user.username.is_none() {
+                    existing_user.username = user.username.clone();
+                }
+                existing_user.phone = user.phone.clone();
+                existing_user.address = user.address.clone();
+                existing_user.updated_at = now();
                 Ok(())
             } else {
-                Err(Custom(
-                    Status::NotFound,
-                    format!("User with id {} not found", id),
-                ))
+                Err(RepositoryError::NotFound(format!("User with id {} not found", id)))
             }
         }
 
-        async fn delete(&self, id: i32) -> Result<(), Custom<String>> {
+        async fn delete(&self, id: i32) -> Result<(), RepositoryError> {
             let mut users = self.users.lock().unwrap();
             if let Some(pos) = users.iter().position(|u| u.id == Some(id)) {
                 users.remove(pos);
                 Ok(())
             } else {
-                Err(Custom(
-                    Status::NotFound,
-                    format!("User with id {} not found", id),
-                ))
+                Err(RepositoryError::NotFound(format!("User with id {} not found", id)))
+            }
+        }
+
+        async fn set_status(&self, id: i32, status: UserStatus) -> Result<(), RepositoryError> {
+            let mut users = self.users.lock().unwrap();
+            match users.iter_mut().find(|u| u.id == Some(id)) {
+                Some(user) => {
+                    user.status = status;
+                    user.updated_at = now();
+                    Ok(())
+                }
+                None => Err(RepositoryError::NotFound(format!("User with id {} not found", id))),
             }
         }
+
+        async fn set_tags(&self, id: i32, tags: &[String]) -> Result<(), RepositoryError> {
+            let mut users = self.users.lock().unwrap();
+            match users.iter_mut().find(|u| u.id == Some(id)) {
+                Some(user) => {
+                    user.tags = tags.to_vec();
+                    user.updated_at = now();
+                    Ok(())
+                }
+                None => Err(RepositoryError::NotFound(format!("User with id {} not found", id))),
+            }
+        }
+
+        async fn record_login(&self, id: i32) -> Result<(), RepositoryError> {
+            let mut users = self.users.lock().unwrap();
+            if let Some(user) = users.iter_mut().find(|u| u.id == Some(id)) {
+                user.last_login_at = Some("2026-10-17T12:00:00Z".to_string());
+            }
+            Ok(())
+        }
+
+        async fn anonymize(&self, id: i32, anonymized: &User) -> Result<(), RepositoryError> {
+            let mut users = self.users.lock().unwrap();
+            match users.iter_mut().find(|u| u.id == Some(id)) {
+                Some(user) => {
+                    *user = User {
+                        id: Some(id),
+                        created_at: user.created_at.clone(),
+                        updated_at: now(),
+                        ..anonymized.clone()
+                    };
+                    self.password_history.lock().unwrap().remove(&id);
+                    Ok(())
+                }
+                None => Err(RepositoryError::NotFound(format!("User with id {} not found", id))),
+            }
+        }
+
+        async fn password_history(&self, id: i32, limit: i64) -> Result<Vec<String>, RepositoryError> {
+            let history = self.password_history.lock().unwrap();
+            let hashes = history.get(&id).map(Vec::as_slice).unwrap_or_default();
+            Ok(hashes.iter().rev().take(limit as usize).cloned().collect())
+        }
+
+        async fn record_password(&self, id: i32, hash: &str, keep: i64) -> Result<(), RepositoryError> {
+            if self.history_unavailable.load(std::sync::atomic::Ordering::Relaxed) {
+                return Err(RepositoryError::Storage("password_history is locked".to_string()));
+            }
+            let mut history = self.password_history.lock().unwrap();
+            let hashes = history.entry(id).or_default();
+            hashes.push(hash.to_string());
+            let excess = hashes.len().saturating_sub(keep as usize);
+            hashes.drain(..excess);
+            Ok(())
+        }
     }
 
     #[tokio::test]
@@ -184,6 +1093,43 @@ pub mod tests {
         assert_eq!(users.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_mock_repository_find_by_id() {
+        let repo = MockUserRepository::new();
+        let user = User::new(
+            "John Doe".to_string(),
+            "john@example.com".to_string(),
+            "password123".to_string(),
+        );
+        repo.create(&user).await.unwrap();
+
+        let found = repo.find_by_id(1).await.unwrap();
+        assert_eq!(found.map(|u| u.name), Some("John Doe".to_string()));
+
+        let missing = repo.find_by_id(999).await.unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mock_repository_find_page_and_count() {
+        let repo = MockUserRepository::new();
+        for i in 1..=5 {
+            let user = User::new(
+                format!("User {}", i),
+                format!("user{}@example.com", i),
+                "password123".to_string(),
+            );
+            repo.create(&user).await.unwrap();
+        }
+
+        assert_eq!(repo.count().await.unwrap(), 5);
+
+        let page = repo.find_page(2, 2).await.unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].name, "User 3");
+        assert_eq!(page[1].name, "User 4");
+    }
+
     #[tokio::test]
     async fn test_mock_repository_update() {
         let repo = MockUserRepository::new();
@@ -245,4 +1191,41 @@ pub mod tests {
         let result = repo.delete(999).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_mock_repository_create_many_reports_chunks() {
+        let repo = MockUserRepository::new();
+        let user = |n: usize| {
+            User::new(
+                format!("User {}", n),
+                format!("user{}@example.com", n),
+                "password123".to_string(),
+            )
+        };
+        repo.create(&user(3)).await.unwrap();
+
+        let users: Vec<User> = (0..5).map(user).collect();
+        let report = repo.create_many(&users, 2).await;
+
+        assert_eq!(report.chunks.len(), 3);
+        assert_eq!(report.inserted(), 3);
+        let failed: Vec<&ChunkResult> = report.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].offset, 2);
+        assert_eq!(failed[0].len, 2);
+        assert_eq!(repo.count().await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_mock_repository_find_by_email_ignores_case() {
+        let repo = MockUserRepository::new();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        repo.create(&user).await.unwrap();
+
+        let found = repo.find_by_email(" John@Example.COM ").await.unwrap().unwrap();
+        assert_eq!(found.id, Some(1));
+        assert!(repo.exists_by_email("JOHN@example.com").await.unwrap());
+        assert!(!repo.exists_by_email("jane@example.com").await.unwrap());
+        assert!(repo.find_by_email("jane@example.com").await.unwrap().is_none());
+    }
 }
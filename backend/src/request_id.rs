@@ -0,0 +1,60 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Request, Response};
+use std::convert::Infallible;
+use uuid::Uuid;
+
+// Request correlation - Single Responsibility Principle
+// Every request gets an id (taken from the incoming header or generated),
+// echoed back in the response headers and in error bodies
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Identifier of the current request, cached for the request lifetime
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Get (or lazily assign) the id of a request
+    pub fn of(request: &Request<'_>) -> String {
+        request
+            .local_cache(|| {
+                let id = request
+                    .headers()
+                    .get_one(REQUEST_ID_HEADER)
+                    .filter(|value| !value.trim().is_empty())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| Uuid::new_v4().to_string());
+                RequestId(id)
+            })
+            .0
+            .clone()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestId {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RequestId(RequestId::of(request)))
+    }
+}
+
+/// Fairing that adds the `X-Request-Id` header to every response
+pub struct RequestIdFairing;
+
+#[rocket::async_trait]
+impl Fairing for RequestIdFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request ID",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        response.set_header(Header::new(REQUEST_ID_HEADER, RequestId::of(request)));
+    }
+}
@@ -0,0 +1,342 @@
+use crate::config::LoggingConfig;
+use crate::domain::RepositoryError;
+use crate::models::{TagCount, User, UserMatch, UserStats, UserStatus};
+use crate::repository::{BulkInsertReport, UserRepository};
+use crate::request_id::RequestId;
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Status;
+use rocket::{Data, Request, Response};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Request and query logging - Single Responsibility Principle
+// One line per request with its outcome and latency, and one per user
+// repository call, so slow endpoints and handlers issuing more queries than
+// they should (such as the list reload after every create) show up in the log
+
+/// Id of the user a request was authenticated as; set by an authentication
+/// guard, logged as `-` while no user is known
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AuthenticatedUser(pub Option<i32>);
+
+impl AuthenticatedUser {
+    /// Remember the user for the rest of the request
+    pub fn set(request: &Request<'_>, id: i32) {
+        request.local_cache(|| AuthenticatedUser(Some(id)));
+    }
+
+    pub fn of(request: &Request<'_>) -> Option<i32> {
+        request.local_cache(AuthenticatedUser::default).0
+    }
+}
+
+/// Time the request reached the fairing
+struct RequestStart(Instant);
+
+/// Everything logged about one request
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLogEntry {
+    pub method: String,
+    pub path: String,
+    pub status: Status,
+    pub latency: Duration,
+    /// Body size when known up front; streamed bodies have none
+    pub size: Option<usize>,
+    pub user_id: Option<i32>,
+    pub request_id: String,
+}
+
+impl RequestLogEntry {
+    pub fn line(&self, slow: bool) -> String {
+        let size = self.size.map_or_else(|| "-".to_string(), |size| format!("{}B", size));
+        let user = self.user_id.map_or_else(|| "-".to_string(), |id| id.to_string());
+        format!(
+            "[request] {} {} {} {}ms {} user={} request_id={}{}",
+            self.method,
+            self.path,
+            self.status.code,
+            self.latency.as_millis(),
+            size,
+            user,
+            self.request_id,
+            if slow { " SLOW" } else { "" }
+        )
+    }
+}
+
+/// Fairing logging every response; requests slower than `slow_threshold`
+/// go to stderr, flagged `SLOW`
+pub struct RequestLogFairing {
+    slow_threshold: Duration,
+}
+
+impl RequestLogFairing {
+    pub fn new(slow_threshold: Duration) -> Self {
+        RequestLogFairing { slow_threshold }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RequestLogFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request log",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _data: &mut Data<'_>) {
+        request.local_cache(|| RequestStart(Instant::now()));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let started = request.local_cache(|| RequestStart(Instant::now())).0;
+        let entry = RequestLogEntry {
+            method: request.method().to_string(),
+            path: request.uri().path().to_string(),
+            status: response.status(),
+            latency: started.elapsed(),
+            size: response.body_mut().size().await,
+            user_id: AuthenticatedUser::of(request),
+            request_id: RequestId::of(request),
+        };
+        let slow = entry.latency > self.slow_threshold;
+        if slow {
+            eprintln!("{}", entry.line(true));
+        } else {
+            println!("{}", entry.line(false));
+        }
+    }
+}
+
+pub fn query_log_line(operation: &str, elapsed: Duration, slow: bool) -> String {
+    format!(
+        "[query] {} {}ms{}",
+        operation,
+        elapsed.as_millis(),
+        if slow { " SLOW" } else { "" }
+    )
+}
+
+/// UserRepository decorator logging how long each call takes. Slow calls
+/// are always logged, the others only when `log_all` is set
+pub struct TimedUserRepository {
+    inner: Arc<dyn UserRepository>,
+    log_all: bool,
+    slow_threshold: Duration,
+}
+
+impl TimedUserRepository {
+    pub fn new(inner: Arc<dyn UserRepository>, config: &LoggingConfig) -> Self {
+        TimedUserRepository {
+            inner,
+            log_all: config.queries,
+            slow_threshold: config.slow_query,
+        }
+    }
+
+    async fn timed<T>(&self, operation: &str, call: impl Future<Output = T>) -> T {
+        let started = Instant::now();
+        let result = call.await;
+        let elapsed = started.elapsed();
+        if elapsed > self.slow_threshold {
+            eprintln!("{}", query_log_line(operation, elapsed, true));
+        } else if self.log_all {
+            println!("{}", query_log_line(operation, elapsed, false));
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl UserRepository for TimedUserRepository {
+    async fn create(&self, user: &User) -> Result<User, RepositoryError> {
+        self.timed("create", self.inner.create(user)).await
+    }
+
+    async fn create_many(&self, users: &[User], chunk_size: usize) -> BulkInsertReport {
+        self.timed("create_many", self.inner.create_many(users, chunk_size)).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, RepositoryError> {
+        self.timed("find_all", self.inner.find_all()).await
+    }
+
+    async fn find_by_id(&self, id: i32) -> Result<Option<User>, RepositoryError> {
+        self.timed("find_by_id", self.inner.find_by_id(id)).await
+    }
+
+    async fn find_by_ids(&self, ids: &[i32]) -> Result<Vec<User>, RepositoryError> {
+        self.timed("find_by_ids", self.inner.find_by_ids(ids)).await
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, RepositoryError> {
+        self.timed("find_by_email", self.inner.find_by_email(email)).await
+    }
+
+    async fn exists_by_email(&self, email: &str) -> Result<bool, RepositoryError> {
+        self.timed("exists_by_email", self.inner.exists_by_email(email)).await
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+        self.timed("find_by_username", self.inner.find_by_username(username)).await
+    }
+
+    async fn find_page(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        self.timed("find_page", self.inner.find_page(limit, offset)).await
+    }
+
+    async fn find_page_by_tag(&self, tag: &str, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
+        self.timed("find_page_by_tag", self.inner.find_page_by_tag(tag, limit, offset)).await
+    }
+
+    async fn count_by_tag(&self, tag: &str) -> Result<i64, RepositoryError> {
+        self.timed("count_by_tag", self.inner.count_by_tag(tag)).await
+    }
+
+    async fn tag_counts(&self) -> Result<Vec<TagCount>, RepositoryError> {
+        self.timed("tag_counts", self.inner.tag_counts()).await
+    }
+
+    async fn search(&self, query: &str, limit: i64) -> Result<Vec<UserMatch>, RepositoryError> {
+        self.timed("search", self.inner.search(query, limit)).await
+    }
+
+    // A stream is read batch by batch for as long as the export runs, so
+    // there is no single query duration to report
+    fn find_all_stream(&self, batch_size: i64) -> BoxStream<'static, Result<User, RepositoryError>> {
+        self.inner.find_all_stream(batch_size)
+    }
+
+    async fn count(&self) -> Result<i64, RepositoryError> {
+        self.timed("count", self.inner.count()).await
+    }
+
+    async fn stats(&self, days: i32) -> Result<UserStats, RepositoryError> {
+        self.timed("stats", self.inner.stats(days)).await
+    }
+
+    async fn update(&self, id: i32, user: &User) -> Result<(), RepositoryError> {
+        self.timed("update", self.inner.update(id, user)).await
+    }
+
+    async fn delete(&self, id: i32) -> Result<(), RepositoryError> {
+        self.timed("delete", self.inner.delete(id)).await
+    }
+
+    async fn set_status(&self, id: i32, status: UserStatus) -> Result<(), RepositoryError> {
+        self.timed("set_status", self.inner.set_status(id, status)).await
+    }
+
+    async fn set_tags(&self, id: i32, tags: &[String]) -> Result<(), RepositoryError> {
+        self.timed("set_tags", self.inner.set_tags(id, tags)).await
+    }
+
+    async fn record_login(&self, id: i32) -> Result<(), RepositoryError> {
+        self.timed("record_login", self.inner.record_login(id)).await
+    }
+
+    async fn anonymize(&self, id: i32, anonymized: &User) -> Result<(), RepositoryError> {
+        self.timed("anonymize", self.inner.anonymize(id, anonymized)).await
+    }
+
+    async fn password_history(&self, id: i32, limit: i64) -> Result<Vec<String>, RepositoryError> {
+        self.timed("password_history", self.inner.password_history(id, limit)).await
+    }
+
+    async fn record_password(&self, id: i32, hash: &str, keep: i64) -> Result<(), RepositoryError> {
+        self.timed("record_password", self.inner.record_password(id, hash, keep)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::tests::MockUserRepository;
+    use rocket::local::blocking::Client;
+
+    fn entry() -> RequestLogEntry {
+        RequestLogEntry {
+            method: "GET".to_string(),
+            path: "/api/v1/users".to_string(),
+            status: Status::Ok,
+            latency: Duration::from_millis(42),
+            size: Some(1534),
+            user_id: None,
+            request_id: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_request_log_line() {
+        assert_eq!(
+            entry().line(false),
+            "[request] GET /api/v1/users 200 42ms 1534B user=- request_id=abc"
+        );
+        let entry = RequestLogEntry {
+            size: None,
+            user_id: Some(7),
+            ..entry()
+        };
+        assert_eq!(
+            entry.line(true),
+            "[request] GET /api/v1/users 200 42ms - user=7 request_id=abc SLOW"
+        );
+    }
+
+    #[test]
+    fn test_query_log_line() {
+        assert_eq!(query_log_line("find_all", Duration::from_millis(3), false), "[query] find_all 3ms");
+        assert_eq!(
+            query_log_line("find_all", Duration::from_millis(250), true),
+            "[query] find_all 250ms SLOW"
+        );
+    }
+
+    #[get("/whoami")]
+    fn whoami(request_user: RequestUser) -> String {
+        format!("{:?}", request_user.0)
+    }
+
+    // Stand-in for an authentication guard
+    struct RequestUser(Option<i32>);
+
+    #[rocket::async_trait]
+    impl<'r> rocket::request::FromRequest<'r> for RequestUser {
+        type Error = ();
+
+        async fn from_request(request: &'r Request<'_>) -> rocket::request::Outcome<Self, ()> {
+            AuthenticatedUser::set(request, 7);
+            rocket::request::Outcome::Success(RequestUser(AuthenticatedUser::of(request)))
+        }
+    }
+
+    #[test]
+    fn test_fairing_leaves_responses_unchanged() {
+        let rocket = rocket::build()
+            .mount("/", routes![whoami])
+            .attach(RequestLogFairing::new(Duration::from_secs(1)));
+        let client = Client::tracked(rocket).expect("valid rocket instance");
+        let response = client.get("/whoami").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "Some(7)");
+    }
+
+    #[tokio::test]
+    async fn test_timed_repository_passes_results_through() {
+        let config = LoggingConfig {
+            queries: true,
+            ..LoggingConfig::default()
+        };
+        let repository = TimedUserRepository::new(Arc::new(MockUserRepository::new()), &config);
+        let created = repository
+            .create(&User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(repository.find_all().await.unwrap(), vec![created.clone()]);
+        assert_eq!(repository.delete(99).await, Err(RepositoryError::NotFound("User with id 99 not found".to_string())));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::jobs::JobRepository;
+use async_trait::async_trait;
+use rocket::serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+// Scheduler - Open/Closed Principle
+// Recurring maintenance is a list of ScheduledTasks, each run on its own
+// interval; adding a task never touches the scheduling loop itself
+
+/// A recurring maintenance task
+#[async_trait]
+pub trait ScheduledTask: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Run the task once, returning a short summary of what was done
+    async fn run(&self) -> Result<String, String>;
+}
+
+/// Last known state of a scheduled task, reported by the admin endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "rocket::serde")]
+pub struct TaskStatus {
+    pub name: String,
+    pub interval_secs: u64,
+    pub runs: u64,
+    pub running: bool,
+    pub last_run_at: Option<String>,
+    pub last_result: Option<String>,
+    pub last_error: Option<String>,
+}
+
+struct ScheduledEntry {
+    task: Arc<dyn ScheduledTask>,
+    interval: Duration,
+}
+
+/// Runs registered tasks on fixed intervals and tracks their status
+pub struct Scheduler {
+    entries: Vec<ScheduledEntry>,
+    statuses: Mutex<Vec<TaskStatus>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Scheduler {
+            entries: Vec::new(),
+            statuses: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Register a task to run every `interval`
+    pub fn with_task(mut self, task: Arc<dyn ScheduledTask>, interval: Duration) -> Self {
+        self.statuses.get_mut().unwrap().push(TaskStatus {
+            name: task.name().to_string(),
+            interval_secs: interval.as_secs(),
+            runs: 0,
+            running: false,
+            last_run_at: None,
+            last_result: None,
+            last_error: None,
+        });
+        self.entries.push(ScheduledEntry { task, interval });
+        self
+    }
+
+    pub fn statuses(&self) -> Vec<TaskStatus> {
+        self.statuses.lock().unwrap().clone()
+    }
+
+    fn update_status(&self, index: usize, update: impl FnOnce(&mut TaskStatus)) {
+        if let Some(status) = self.statuses.lock().unwrap().get_mut(index) {
+            update(status);
+        }
+    }
+
+    /// Run the task at `index` once and record the outcome
+    pub async fn run_task(&self, index: usize) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        self.update_status(index, |status| status.running = true);
+
+        let result = entry.task.run().await;
+        if let Err(e) = &result {
+            eprintln!("Scheduled task '{}' failed: {}", entry.task.name(), e);
+        }
+
+        let now = OffsetDateTime::now_utc().format(&Rfc3339).ok();
+        self.update_status(index, |status| {
+            status.running = false;
+            status.runs += 1;
+            status.last_run_at = now;
+            match result {
+                Ok(summary) => {
+                    status.last_result = Some(summary);
+                    status.last_error = None;
+                }
+                Err(e) => status.last_error = Some(e),
+            }
+        });
+    }
+
+    /// Start one background loop per task; the first run happens immediately
+    pub fn spawn(self: Arc<Self>) -> Vec<JoinHandle<()>> {
+        (0..self.entries.len())
+            .map(|index| {
+                let scheduler = self.clone();
+                tokio::spawn(async move {
+                    let mut ticker = tokio::time::interval(scheduler.entries[index].interval);
+                    // A slow run should not cause a burst of catch-up runs
+                    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                    loop {
+                        ticker.tick().await;
+                        scheduler.run_task(index).await;
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+/// Deletes completed and failed jobs older than the retention period
+pub struct PurgeFinishedJobsTask {
+    repository: Arc<dyn JobRepository>,
+    retention: Duration,
+}
+
+impl PurgeFinishedJobsTask {
+    pub fn new(repository: Arc<dyn JobRepository>, retention: Duration) -> Self {
+        PurgeFinishedJobsTask {
+            repository,
+            retention,
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for PurgeFinishedJobsTask {
+    fn name(&self) -> &'static str {
+        "purge-finished-jobs"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let purged = self
+            .repository
+            .purge_finished(self.retention)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(format!("purged {} jobs", purged))
+    }
+}
+
+/// Puts jobs stuck in `running` (e.g. after a crash mid-job) back in the queue
+pub struct RequeueStaleJobsTask {
+    repository: Arc<dyn JobRepository>,
+    stale_after: Duration,
+}
+
+impl RequeueStaleJobsTask {
+    pub fn new(repository: Arc<dyn JobRepository>, stale_after: Duration) -> Self {
+        RequeueStaleJobsTask {
+            repository,
+            stale_after,
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for RequeueStaleJobsTask {
+    fn name(&self) -> &'static str {
+        "requeue-stale-jobs"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let requeued = self
+            .repository
+            .requeue_stale(self.stale_after)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(format!("requeued {} jobs", requeued))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::tests::MockJobRepository;
+    use crate::jobs::JobStatus;
+    use serde_json::Value;
+
+    struct FailingTask;
+
+    #[async_trait]
+    impl ScheduledTask for FailingTask {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn run(&self) -> Result<String, String> {
+            Err("boom".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_task_records_failure() {
+        let scheduler = Scheduler::new().with_task(Arc::new(FailingTask), Duration::from_secs(60));
+        assert_eq!(scheduler.statuses()[0].runs, 0);
+
+        scheduler.run_task(0).await;
+
+        let status = &scheduler.statuses()[0];
+        assert_eq!(status.name, "failing");
+        assert_eq!(status.interval_secs, 60);
+        assert_eq!(status.runs, 1);
+        assert!(!status.running);
+        assert!(status.last_run_at.is_some());
+        assert_eq!(status.last_error.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_purge_finished_jobs_task() {
+        let repository = Arc::new(MockJobRepository::new());
+        repository.enqueue("test", &Value::Null, 1).await.unwrap();
+        repository.enqueue("test", &Value::Null, 1).await.unwrap();
+        repository.complete(1).await.unwrap();
+
+        let scheduler = Scheduler::new().with_task(
+            Arc::new(PurgeFinishedJobsTask::new(repository.clone(), Duration::ZERO)),
+            Duration::from_secs(60),
+        );
+        scheduler.run_task(0).await;
+
+        assert_eq!(
+            scheduler.statuses()[0].last_result.as_deref(),
+            Some("purged 1 jobs")
+        );
+        let jobs = repository.jobs.lock().unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].status, JobStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_requeue_stale_jobs_task() {
+        let repository = Arc::new(MockJobRepository::new());
+        repository.enqueue("test", &Value::Null, 1).await.unwrap();
+        repository.claim_next().await.unwrap();
+
+        let task = RequeueStaleJobsTask::new(repository.clone(), Duration::ZERO);
+        assert_eq!(task.run().await.unwrap(), "requeued 1 jobs");
+        assert_eq!(repository.jobs.lock().unwrap()[0].status, JobStatus::Pending);
+    }
+}
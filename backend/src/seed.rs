@@ -0,0 +1,51 @@
+use crate::models::User;
+use fake::Fake;
+use fake::faker::name::en::{FirstName, LastName};
+use fake::rand::Rng;
+use shared::generate_password;
+use shared::password::GENERATED_PASSWORD_LENGTH;
+
+// Seed data - Single Responsibility Principle
+// Generates realistic fake users for local development, so pagination and
+// search can be exercised against more than a handful of rows
+
+pub const DEFAULT_SEED_COUNT: usize = 100;
+
+/// Reserved example domains, so seeded addresses can never reach anyone
+const SEED_EMAIL_DOMAINS: [&str; 3] = ["example.com", "example.org", "example.net"];
+
+/// A fake user; `number` keeps the emails of one run unique
+pub fn fake_user<R: Rng + ?Sized>(rng: &mut R, number: usize) -> User {
+    let first: String = FirstName().fake_with_rng(rng);
+    let last: String = LastName().fake_with_rng(rng);
+    let local_part: String = format!("{}.{}{}", first, last, number)
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '.')
+        .collect();
+    let domain = SEED_EMAIL_DOMAINS[rng.random_range(0..SEED_EMAIL_DOMAINS.len())];
+
+    let mut random_bytes = [0u8; GENERATED_PASSWORD_LENGTH];
+    rng.fill(&mut random_bytes);
+
+    User::new(
+        format!("{} {}", first, last),
+        format!("{}@{}", local_part, domain),
+        generate_password(&random_bytes),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_users_are_valid() {
+        let mut rng = fake::rand::rng();
+        for number in 0..50 {
+            let user = fake_user(&mut rng, number).normalized();
+            assert_eq!(user.validate(), Ok(()), "{:?}", user);
+            assert!(user.email.contains(&number.to_string()));
+        }
+    }
+}
@@ -1,58 +1,483 @@
-use crate::models::User;
+use crate::domain::DomainError;
+use crate::events::{EventBus, UserEvent};
+use crate::models::{BulkUpdateRequest, TagCount, User, UserMatch, UserStats, UserStatus};
+use crate::pagination::Pagination;
+use crate::password_history::{self, hash_password};
 use crate::repository::UserRepository;
-use rocket::http::Status;
-use rocket::response::status::Custom;
+use crate::unit_of_work::{DirectUnitOfWorkFactory, UnitOfWorkFactory};
+use futures_util::stream::{BoxStream, StreamExt, TryStreamExt};
+use shared::{
+    normalize_email, normalize_tag, normalize_tags, normalize_username, validate_email, validate_tag, validate_tags,
+    validate_username,
+};
+use std::collections::BTreeMap;
+use std::future::Future;
 use std::sync::Arc;
 
+/// Window used for the signups-per-day statistic
+pub const STATS_WINDOW_DAYS: i32 = 30;
+/// Users read from the database at a time while exporting
+pub const EXPORT_BATCH_SIZE: i64 = 500;
+/// Search results returned when no limit is given, and the most allowed
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 100;
+/// Most users a single bulk update may change
+pub const MAX_BULK_IDS: usize = 100;
+/// Most users an export of selected users may name
+pub const MAX_EXPORT_IDS: usize = 1000;
+
 /// UserService - Single Responsibility Principle
 /// This service is only responsible for business logic related to users
 /// It depends on UserRepository abstraction (Dependency Inversion Principle)
+/// Side effects are not performed here: every change is published as a UserEvent
+/// Operations with several writes run in a unit of work, and their events are
+/// only published once it has committed
 pub struct UserService {
     repository: Arc<dyn UserRepository>,
+    units: Arc<dyn UnitOfWorkFactory>,
+    events: EventBus,
+    /// Last passwords a user may not reuse; 0 keeps no history
+    password_history: usize,
 }
 
 impl UserService {
-    pub fn new(repository: Arc<dyn UserRepository>) -> Self {
-        UserService { repository }
+    pub fn new(repository: Arc<dyn UserRepository>, events: EventBus) -> Self {
+        let units = Arc::new(DirectUnitOfWorkFactory::new(repository.clone()));
+        UserService {
+            repository,
+            units,
+            events,
+            password_history: 0,
+        }
     }
 
-    /// Create a new user with validation
-    pub async fn create_user(&self, user: User) -> Result<Vec<User>, Custom<String>> {
-        // Validate user before creating
-        user.validate().map_err(|e| Custom(Status::BadRequest, e))?;
+    /// Run multi-step operations in transactions started by `units`
+    pub fn with_unit_of_work(mut self, units: Arc<dyn UnitOfWorkFactory>) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Keep the hashes of the last `count` passwords of every user and reject
+    /// changes back to any of them
+    pub fn with_password_history(mut self, count: usize) -> Self {
+        self.password_history = count;
+        self
+    }
+
+    /// Run `work` in a unit of work, committed when it succeeds. On error
+    /// the unit of work is dropped, which rolls back its writes
+    async fn transaction<T, F, Fut>(&self, work: F) -> Result<T, DomainError>
+    where
+        F: FnOnce(Arc<dyn UserRepository>) -> Fut,
+        Fut: Future<Output = Result<T, DomainError>>,
+    {
+        let unit = self.units.begin().await?;
+        let result = work(unit.users()).await?;
+        unit.commit().await?;
+        Ok(result)
+    }
 
-        self.repository.create(&user).await?;
+    /// Event bus the service publishes to
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Create a new user with validation
+    pub async fn create_user(&self, user: User) -> Result<Vec<User>, DomainError> {
+        self.sign_up(user).await?;
         self.get_all_users().await
     }
 
+    /// Create a user and return it; shared by admin creation and self-service signup
+    pub async fn sign_up(&self, user: User) -> Result<User, DomainError> {
+        // Normalize and validate user before creating
+        let user = user.normalized();
+        user.validate().map_err(DomainError::Invalid)?;
+        if self.repository.exists_by_email(&user.email).await? {
+            return Err(DomainError::Conflict(
+                format!("A user with email {} already exists", user.email),
+            ));
+        }
+        if let Some(username) = &user.username {
+            let owner = self.repository.find_by_username(username).await?;
+            if owner.is_some() {
+                return Err(username_taken(username));
+            }
+        }
+
+        // The user and the first entry of its password history are written together
+        let history = self.password_history;
+        let created = self
+            .transaction(|users| async move {
+                let created = users.create(&user).await?;
+                if history > 0 {
+                    let id = created.id.unwrap_or_default();
+                    users.record_password(id, &hash_password(&user.password), history as i64).await?;
+                }
+                Ok(created)
+            })
+            .await?;
+        self.events.publish(UserEvent::Created {
+            user: created.clone().into(),
+        });
+        Ok(created)
+    }
+
     /// Get all users
-    pub async fn get_all_users(&self) -> Result<Vec<User>, Custom<String>> {
-        self.repository.find_all().await
+    pub async fn get_all_users(&self) -> Result<Vec<User>, DomainError> {
+        Ok(self.repository.find_all().await?)
+    }
+
+    /// Get one page of users
+    pub async fn get_users_page(&self, pagination: Pagination) -> Result<Vec<User>, DomainError> {
+        Ok(self
+            .repository
+            .find_page(pagination.limit(), pagination.offset())
+            .await?)
+    }
+
+    /// Users tagged `tag`, all of them unless `pagination` is given, with how
+    /// many there are in total
+    pub async fn get_tagged_users(
+        &self,
+        tag: &str,
+        pagination: Option<Pagination>,
+    ) -> Result<(Vec<User>, i64), DomainError> {
+        let tag = normalize_tag(tag);
+        validate_tag(&tag).map_err(|e| DomainError::Invalid(e.to_string()))?;
+        let (limit, offset) = pagination.map_or((i64::MAX, 0), |page| (page.limit(), page.offset()));
+        let users = self.repository.find_page_by_tag(&tag, limit, offset).await?;
+        Ok((users, self.repository.count_by_tag(&tag).await?))
+    }
+
+    /// Every tag in use with how many users have it, for the list's filter bar
+    pub async fn tag_counts(&self) -> Result<Vec<TagCount>, DomainError> {
+        Ok(self.repository.tag_counts().await?)
+    }
+
+    /// Fuzzy search on names and emails, tolerating typos
+    pub async fn search_users(&self, query: &str, limit: Option<i64>) -> Result<Vec<UserMatch>, DomainError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(DomainError::Invalid("Search query cannot be empty".to_string()));
+        }
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+        Ok(self.repository.search(query, limit).await?)
+    }
+
+    /// Every user, streamed for exports
+    pub fn export_users(&self) -> BoxStream<'static, Result<User, DomainError>> {
+        self.repository
+            .find_all_stream(EXPORT_BATCH_SIZE)
+            .map_err(DomainError::from)
+            .boxed()
+    }
+
+    /// The users with the given ids, for exporting a selection. Unknown ids
+    /// are left out; the selection is read at once since it is bounded
+    pub async fn export_selected_users(
+        &self,
+        ids: &[i32],
+    ) -> Result<BoxStream<'static, Result<User, DomainError>>, DomainError> {
+        if ids.is_empty() {
+            return Err(DomainError::Invalid("An export needs at least one id".to_string()));
+        }
+        if ids.len() > MAX_EXPORT_IDS {
+            return Err(DomainError::Invalid(format!(
+                "An export can include at most {} users",
+                MAX_EXPORT_IDS
+            )));
+        }
+        let users = self.repository.find_by_ids(ids).await?;
+        Ok(futures_util::stream::iter(users.into_iter().map(Ok)).boxed())
+    }
+
+    /// Users sharing an email once case is ignored, grouped by normalized
+    /// email. Used to clean up data written before emails were normalized
+    pub async fn duplicate_emails(&self) -> Result<Vec<(String, Vec<User>)>, DomainError> {
+        let mut by_email: BTreeMap<String, Vec<User>> = BTreeMap::new();
+        let mut users = self.export_users();
+        while let Some(user) = users.try_next().await? {
+            by_email.entry(normalize_email(&user.email)).or_default().push(user);
+        }
+        Ok(by_email.into_iter().filter(|(_, users)| users.len() > 1).collect())
+    }
+
+    /// Count all users
+    pub async fn count_users(&self) -> Result<i64, DomainError> {
+        Ok(self.repository.count().await?)
+    }
+
+    /// Aggregated statistics for the admin dashboard
+    pub async fn get_stats(&self) -> Result<UserStats, DomainError> {
+        Ok(self.repository.stats(STATS_WINDOW_DAYS).await?)
+    }
+
+    /// Get a single user by id
+    pub async fn get_user(&self, id: i32) -> Result<User, DomainError> {
+        find_user(self.repository.as_ref(), id).await
+    }
+
+    /// Whether a user has this email, compared case-insensitively like signup does
+    pub async fn email_exists(&self, email: &str) -> Result<bool, DomainError> {
+        let email = normalize_email(email);
+        validate_email(&email).map_err(|e| DomainError::Invalid(e.to_string()))?;
+        Ok(self.repository.exists_by_email(&email).await?)
+    }
+
+    /// Whether a username is still free, normalized as it would be stored;
+    /// a username that breaks the format rules is Invalid rather than taken
+    pub async fn username_available(&self, username: &str) -> Result<(String, bool), DomainError> {
+        let username = normalize_username(username);
+        validate_username(&username).map_err(|e| DomainError::Invalid(e.to_string()))?;
+        let taken = self.repository.find_by_username(&username).await?.is_some();
+        Ok((username, !taken))
+    }
+
+    /// Get a single user by username, with or without the leading `@`
+    pub async fn get_user_by_username(&self, username: &str) -> Result<User, DomainError> {
+        let username = normalize_username(username);
+        self.repository.find_by_username(&username).await?.ok_or_else(|| {
+            DomainError::NotFound(format!("No user with username {}", username))
+        })
+    }
+
+    /// Get a single user by email, compared case-insensitively
+    pub async fn get_user_by_email(&self, email: &str) -> Result<User, DomainError> {
+        self.repository.find_by_email(email).await?.ok_or_else(|| {
+            DomainError::NotFound(format!("No user with email {}", email))
+        })
     }
 
     /// Update an existing user with validation
-    pub async fn update_user(&self, id: i32, user: User) -> Result<Vec<User>, Custom<String>> {
-        // Validate user before updating
-        user.validate().map_err(|e| Custom(Status::BadRequest, e))?;
+    pub async fn update_user(&self, id: i32, user: User) -> Result<Vec<User>, DomainError> {
+        // Normalize and validate user before updating
+        let user = user.normalized();
+        user.validate().map_err(DomainError::Invalid)?;
+        let history = self.password_history;
 
-        self.repository.update(id, &user).await?;
+        let (updated, password_changed) = self
+            .transaction(|users| async move {
+                let previous = find_user(users.as_ref(), id).await?;
+                let owner = users.find_by_email(&user.email).await?;
+                if owner.is_some_and(|owner| owner.id != Some(id)) {
+                    return Err(DomainError::Conflict(
+                        format!("A user with email {} already exists", user.email),
+                    ));
+                }
+                // Omitting the username keeps it; only users without one may pick one
+                match (&previous.username, &user.username) {
+                    (Some(current), Some(requested)) if current != requested => {
+                        return Err(DomainError::Invalid("Username cannot be changed".to_string()));
+                    }
+                    (None, Some(requested)) => {
+                        let owner = users.find_by_username(requested).await?;
+                        if owner.is_some_and(|owner| owner.id != Some(id)) {
+                            return Err(username_taken(requested));
+                        }
+                    }
+                    _ => {}
+                }
+                let password_changed = !password_history::is_current(&previous.password, &user.password);
+                if password_changed && history > 0 {
+                    let keep = history as i64;
+                    let hashes = users.password_history(id, keep).await?;
+                    if hashes.iter().any(|hash| password_history::matches(hash, &user.password)) {
+                        return Err(password_reused(history));
+                    }
+                    // Users created before the history have none; it starts with their current password
+                    if hashes.is_empty() {
+                        users.record_password(id, &hash_password(&previous.password), keep).await?;
+                    }
+                    users.record_password(id, &hash_password(&user.password), keep).await?;
+                }
+                users.update(id, &user).await?;
+                // Re-read the user so the event carries its stored status
+                let updated = find_user(users.as_ref(), id).await?;
+                Ok((updated, password_changed))
+            })
+            .await?;
+        self.events.publish(UserEvent::Updated {
+            user: updated.into(),
+        });
+        if password_changed {
+            self.events.publish(UserEvent::PasswordChanged { id });
+        }
         self.get_all_users().await
     }
 
+    /// Suspend or reactivate a user
+    pub async fn set_user_status(&self, id: i32, status: UserStatus) -> Result<User, DomainError> {
+        let user = self
+            .transaction(|users| async move {
+                users.set_status(id, status).await?;
+                find_user(users.as_ref(), id).await
+            })
+            .await?;
+        self.events.publish(UserEvent::Updated {
+            user: user.clone().into(),
+        });
+        Ok(user)
+    }
+
+    /// Replace the tags of a user, normalized like on creation
+    pub async fn set_user_tags(&self, id: i32, tags: &[String]) -> Result<User, DomainError> {
+        let tags = normalize_tags(tags);
+        validate_tags(&tags).map_err(|e| DomainError::Invalid(e.to_string()))?;
+        let user = self
+            .transaction(|users| async move {
+                users.set_tags(id, &tags).await?;
+                find_user(users.as_ref(), id).await
+            })
+            .await?;
+        self.events.publish(UserEvent::Updated {
+            user: user.clone().into(),
+        });
+        Ok(user)
+    }
+
+    /// Apply the change of `request` to each of its users in one transaction.
+    /// An invalid change rejects the whole request; a user that is missing,
+    /// or would end up with too many tags, gets an error in its own result
+    /// without stopping the others
+    pub async fn bulk_update_users(
+        &self,
+        request: &BulkUpdateRequest,
+    ) -> Result<Vec<(i32, Result<User, DomainError>)>, DomainError> {
+        let mut ids = request.ids.clone();
+        let mut seen = std::collections::HashSet::new();
+        ids.retain(|id| seen.insert(*id));
+        if ids.is_empty() {
+            return Err(DomainError::Invalid("A bulk update needs at least one id".to_string()));
+        }
+        if ids.len() > MAX_BULK_IDS {
+            return Err(DomainError::Invalid(format!(
+                "A bulk update can change at most {} users",
+                MAX_BULK_IDS
+            )));
+        }
+        if request.is_empty() {
+            return Err(DomainError::Invalid("A bulk update must change at least one field".to_string()));
+        }
+        let add_tags = normalize_tags(&request.add_tags);
+        for tag in &add_tags {
+            validate_tag(tag).map_err(|e| DomainError::Invalid(e.to_string()))?;
+        }
+        let remove_tags = normalize_tags(&request.remove_tags);
+        let status = request.status;
+
+        let outcomes = self
+            .transaction(|users| async move {
+                let mut outcomes = Vec::with_capacity(ids.len());
+                for id in ids {
+                    let outcome = bulk_update_user(users.as_ref(), id, status, &add_tags, &remove_tags).await;
+                    // Storage failures abort the transaction, so they fail the whole request
+                    if let Err(error @ (DomainError::Internal(_) | DomainError::Timeout(_))) = outcome {
+                        return Err(error);
+                    }
+                    outcomes.push((id, outcome));
+                }
+                Ok(outcomes)
+            })
+            .await?;
+        for user in outcomes.iter().filter_map(|(_, outcome)| outcome.as_ref().ok()) {
+            self.events.publish(UserEvent::Updated {
+                user: user.clone().into(),
+            });
+        }
+        Ok(outcomes)
+    }
+
+    /// Remember that a user just authenticated
+    // Called by the login flow, which does not exist yet
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub async fn record_login(&self, id: i32) -> Result<(), DomainError> {
+        Ok(self.repository.record_login(id).await?)
+    }
+
+    /// Irreversibly replace the personal data of a user (right to erasure).
+    /// Unlike deletion the row stays, so statistics remain accurate
+    pub async fn anonymize_user(&self, id: i32) -> Result<User, DomainError> {
+        let anonymized = self
+            .transaction(|users| async move {
+                let user = find_user(users.as_ref(), id).await?;
+                if user.is_anonymized() {
+                    return Err(DomainError::Conflict(
+                        format!("User with id {} is already anonymized", id),
+                    ));
+                }
+
+                let anonymized = user.anonymized();
+                users.anonymize(id, &anonymized).await?;
+                Ok(anonymized)
+            })
+            .await?;
+        self.events.publish(UserEvent::Anonymized { id });
+        Ok(anonymized)
+    }
+
     /// Delete a user
-    pub async fn delete_user(&self, id: i32) -> Result<(), Custom<String>> {
-        self.repository.delete(id).await
+    pub async fn delete_user(&self, id: i32) -> Result<(), DomainError> {
+        self.repository.delete(id).await?;
+        self.events.publish(UserEvent::Deleted { id });
+        Ok(())
     }
 }
 
+fn username_taken(username: &str) -> DomainError {
+    DomainError::Conflict(format!("Username {} is already taken", username))
+}
+
+fn password_reused(count: usize) -> DomainError {
+    DomainError::Invalid(format!("Password must differ from the last {} passwords", count))
+}
+
+/// The user with `id`, or NotFound
+async fn find_user(users: &dyn UserRepository, id: i32) -> Result<User, DomainError> {
+    users.find_by_id(id).await?.ok_or_else(|| {
+        DomainError::NotFound(format!("User with id {} not found", id))
+    })
+}
+
+/// One user of a bulk update; tags are merged into the current ones
+async fn bulk_update_user(
+    users: &dyn UserRepository,
+    id: i32,
+    status: Option<UserStatus>,
+    add_tags: &[String],
+    remove_tags: &[String],
+) -> Result<User, DomainError> {
+    let user = find_user(users, id).await?;
+    // Checked before any write so a rejected user is left untouched
+    let tags = if add_tags.is_empty() && remove_tags.is_empty() {
+        None
+    } else {
+        let mut tags = user.tags.clone();
+        tags.extend_from_slice(add_tags);
+        tags.retain(|tag| !remove_tags.contains(tag));
+        let tags = normalize_tags(&tags);
+        validate_tags(&tags).map_err(|e| DomainError::Invalid(e.to_string()))?;
+        Some(tags)
+    };
+    if let Some(status) = status {
+        users.set_status(id, status).await?;
+    }
+    if let Some(tags) = tags {
+        users.set_tags(id, &tags).await?;
+    }
+    find_user(users, id).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::repository::tests::MockUserRepository;
+    use crate::unit_of_work::tests::MockUnitOfWorkFactory;
 
     fn create_test_service() -> UserService {
         let repo = Arc::new(MockUserRepository::new());
-        UserService::new(repo)
+        UserService::new(repo, EventBus::default())
     }
 
     #[tokio::test]
@@ -68,6 +493,31 @@ mod tests {
         assert_eq!(users[0].name, "John Doe");
     }
 
+    #[tokio::test]
+    async fn test_sign_up_returns_created_user() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), " John@Example.com".to_string(), "password123".to_string());
+
+        let created = service.sign_up(user.clone()).await.unwrap();
+        assert!(created.id.is_some());
+        assert_eq!(created.email, "john@example.com");
+
+        let err = service.sign_up(user).await.unwrap_err();
+        assert!(matches!(err, DomainError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_email_exists() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.sign_up(user).await.unwrap();
+
+        assert!(service.email_exists(" JOHN@example.com ").await.unwrap());
+        assert!(!service.email_exists("jane@example.com").await.unwrap());
+        let err = service.email_exists("john@").await.unwrap_err();
+        assert!(matches!(err, DomainError::Invalid(_)));
+    }
+
     #[tokio::test]
     async fn test_create_user_invalid_name() {
         let service = create_test_service();
@@ -77,8 +527,7 @@ mod tests {
         assert!(result.is_err());
 
         let err = result.unwrap_err();
-        assert_eq!(err.0, Status::BadRequest);
-        assert_eq!(err.1, "Name cannot be empty");
+        assert_eq!(err, DomainError::Invalid("Name cannot be empty".to_string()));
     }
 
     #[tokio::test]
@@ -90,8 +539,7 @@ mod tests {
         assert!(result.is_err());
 
         let err = result.unwrap_err();
-        assert_eq!(err.0, Status::BadRequest);
-        assert_eq!(err.1, "Invalid email format");
+        assert_eq!(err, DomainError::Invalid("Invalid email format".to_string()));
     }
 
     #[tokio::test]
@@ -107,6 +555,36 @@ mod tests {
         assert_eq!(users.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_get_user() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user).await.unwrap();
+
+        let found = service.get_user(1).await.unwrap();
+        assert_eq!(found.name, "John Doe");
+
+        let err = service.get_user(999).await.unwrap_err();
+        assert!(matches!(err, DomainError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_get_users_page_and_count() {
+        let service = create_test_service();
+        for i in 1..=3 {
+            let user = User::new(format!("User {}", i), format!("user{}@example.com", i), "password123".to_string());
+            service.create_user(user).await.unwrap();
+        }
+
+        let page = service
+            .get_users_page(Pagination::from_query(Some(2), Some(2)))
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].name, "User 3");
+        assert_eq!(service.count_users().await.unwrap(), 3);
+    }
+
     #[tokio::test]
     async fn test_update_user_valid() {
         let service = create_test_service();
@@ -136,7 +614,7 @@ mod tests {
         assert!(result.is_err());
 
         let err = result.unwrap_err();
-        assert_eq!(err.0, Status::BadRequest);
+        assert!(matches!(err, DomainError::Invalid(_)));
     }
 
     #[tokio::test]
@@ -159,4 +637,413 @@ mod tests {
         let result = service.delete_user(999).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_create_user_normalizes_email() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "John@Example.com".to_string(), "password123".to_string());
+
+        let users = service.create_user(user).await.unwrap();
+        assert_eq!(users[0].email, "john@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_mutations_publish_events() {
+        let service = create_test_service();
+        let mut events = service.events().subscribe();
+
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user.clone()).await.unwrap();
+        service.update_user(1, user).await.unwrap();
+        service.delete_user(1).await.unwrap();
+
+        let created = events.try_recv().unwrap();
+        assert_eq!(created.name(), "user.created");
+        assert_eq!(created.user_id(), 1);
+        assert_eq!(events.try_recv().unwrap().name(), "user.updated");
+        assert_eq!(events.try_recv().unwrap(), UserEvent::Deleted { id: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_update_with_new_password_publishes_password_changed() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user.clone()).await.unwrap();
+        let mut events = service.events().subscribe();
+
+        service.update_user(1, user.clone()).await.unwrap();
+        assert_eq!(events.try_recv().unwrap().name(), "user.updated");
+        assert!(events.try_recv().is_err());
+
+        let user = User {
+            password: "new-password456".to_string(),
+            ..user
+        };
+        service.update_user(1, user).await.unwrap();
+        assert_eq!(events.try_recv().unwrap().name(), "user.updated");
+        assert_eq!(events.try_recv().unwrap(), UserEvent::PasswordChanged { id: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_password_history_rejects_reuse() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserService::new(repo.clone(), EventBus::default()).with_password_history(2);
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user.clone()).await.unwrap();
+        let with_password = |password: &str| User { password: password.to_string(), ..user.clone() };
+
+        service.update_user(1, with_password("password456")).await.unwrap();
+        let err = service.update_user(1, with_password("password123")).await.unwrap_err();
+        assert_eq!(err, DomainError::Invalid("Password must differ from the last 2 passwords".to_string()));
+        assert_eq!(service.get_user(1).await.unwrap().password, "password456");
+
+        // Only the last two are kept, so the first one is allowed again
+        service.update_user(1, with_password("password789")).await.unwrap();
+        service.update_user(1, with_password("password123")).await.unwrap();
+        assert_eq!(repo.password_history(1, 10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_password_history_starts_with_the_current_password() {
+        let repo = Arc::new(MockUserRepository::new());
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        // Created before the history was turned on
+        UserService::new(repo.clone(), EventBus::default()).create_user(user.clone()).await.unwrap();
+        let service = UserService::new(repo.clone(), EventBus::default()).with_password_history(5);
+
+        service.update_user(1, User { password: "password456".to_string(), ..user.clone() }).await.unwrap();
+        let result = service.update_user(1, User { password: "password123".to_string(), ..user }).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_mutations_do_not_publish_events() {
+        let service = create_test_service();
+        let mut events = service.events().subscribe();
+
+        let invalid = User::new("".to_string(), "john@example.com".to_string(), "password123".to_string());
+        assert!(service.create_user(invalid).await.is_err());
+        assert!(service.delete_user(999).await.is_err());
+
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_user_status() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user.clone()).await.unwrap();
+        let mut events = service.events().subscribe();
+
+        let suspended = service.set_user_status(1, UserStatus::Suspended).await.unwrap();
+        assert_eq!(suspended.status, UserStatus::Suspended);
+        let UserEvent::Updated { user: event_user } = events.try_recv().unwrap() else {
+            panic!("expected an update event");
+        };
+        assert_eq!(event_user.status, UserStatus::Suspended);
+
+        // Regular updates keep the stored status
+        let users = service.update_user(1, user).await.unwrap();
+        assert_eq!(users[0].status, UserStatus::Suspended);
+
+        let err = service.set_user_status(999, UserStatus::Active).await.unwrap_err();
+        assert!(matches!(err, DomainError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_record_login() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user).await.unwrap();
+        assert_eq!(service.get_user(1).await.unwrap().last_login_at, None);
+
+        service.record_login(1).await.unwrap();
+        assert!(service.get_user(1).await.unwrap().last_login_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_anonymize_user() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user).await.unwrap();
+        let mut events = service.events().subscribe();
+
+        let anonymized = service.anonymize_user(1).await.unwrap();
+        assert!(anonymized.is_anonymized());
+        assert_eq!(events.try_recv().unwrap(), UserEvent::Anonymized { id: 1 });
+
+        let stored = service.get_user(1).await.unwrap();
+        assert_eq!(stored.email, "user-1@anonymized.invalid");
+        assert_eq!(service.count_users().await.unwrap(), 1);
+
+        let err = service.anonymize_user(1).await.unwrap_err();
+        assert!(matches!(err, DomainError::Conflict(_)));
+        let err = service.anonymize_user(999).await.unwrap_err();
+        assert!(matches!(err, DomainError::NotFound(_)));
+    }
+
+    fn transactional_service() -> (Arc<MockUserRepository>, UserService) {
+        let repo = Arc::new(MockUserRepository::new());
+        let units = Arc::new(MockUnitOfWorkFactory::new(repo.clone()));
+        let service = UserService::new(repo.clone(), EventBus::default()).with_unit_of_work(units);
+        (repo, service)
+    }
+
+    #[tokio::test]
+    async fn test_unit_of_work_commits_writes() {
+        let (_, service) = transactional_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user).await.unwrap();
+
+        let updated = User::new("Johnny Doe".to_string(), "johnny@example.com".to_string(), "password123".to_string());
+        service.update_user(1, updated).await.unwrap();
+        service.set_user_status(1, UserStatus::Suspended).await.unwrap();
+
+        let stored = service.get_user(1).await.unwrap();
+        assert_eq!(stored.name, "Johnny Doe");
+        assert_eq!(stored.status, UserStatus::Suspended);
+    }
+
+    #[tokio::test]
+    async fn test_unit_of_work_rolls_back_on_error() {
+        let (repo, service) = transactional_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user).await.unwrap();
+        let mut events = service.events().subscribe();
+        let other = User::new("Jane Doe".to_string(), "jane@example.com".to_string(), "password123".to_string());
+
+        let result: Result<(), DomainError> = service
+            .transaction(|users| async move {
+                users.create(&other).await?;
+                users.set_status(1, UserStatus::Suspended).await?;
+                Err(DomainError::Internal("audit write failed".to_string()))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(repo.count().await.unwrap(), 1);
+        assert_eq!(service.get_user(1).await.unwrap().status, UserStatus::Active);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_user_rolls_back_when_the_history_fails() {
+        let (repo, service) = transactional_service();
+        let service = service.with_password_history(3);
+        let mut events = service.events().subscribe();
+        repo.history_unavailable.store(true, std::sync::atomic::Ordering::Relaxed);
+
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        assert!(service.create_user(user).await.is_err());
+        assert_eq!(repo.count().await.unwrap(), 0);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_user_rejects_duplicate_email() {
+        let service = create_test_service();
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+        service.create_user(user).await.unwrap();
+        let mut events = service.events().subscribe();
+
+        let duplicate = User::new("Johnny".to_string(), "John@Example.com".to_string(), "password123".to_string());
+        let err = service.create_user(duplicate).await.unwrap_err();
+        assert_eq!(err, DomainError::Conflict("A user with email john@example.com already exists".to_string()));
+        assert!(events.try_recv().is_err());
+        assert_eq!(service.count_users().await.unwrap(), 1);
+
+        let found = service.get_user_by_email("JOHN@example.com").await.unwrap();
+        assert_eq!(found.name, "John Doe");
+        let err = service.get_user_by_email("jane@example.com").await.unwrap_err();
+        assert!(matches!(err, DomainError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_update_user_rejects_email_of_another_user() {
+        let (_, service) = transactional_service();
+        for (name, email) in [("John Doe", "john@example.com"), ("Jane Doe", "jane@example.com")] {
+            let user = User::new(name.to_string(), email.to_string(), "password123".to_string());
+            service.create_user(user).await.unwrap();
+        }
+
+        let taken = User::new("John Doe".to_string(), "Jane@example.com".to_string(), "password123".to_string());
+        let err = service.update_user(1, taken).await.unwrap_err();
+        assert!(matches!(err, DomainError::Conflict(_)));
+        assert_eq!(service.get_user(1).await.unwrap().email, "john@example.com");
+
+        // Keeping one's own email is not a conflict
+        let renamed = User::new("John Smith".to_string(), "JOHN@example.com".to_string(), "password123".to_string());
+        service.update_user(1, renamed).await.unwrap();
+        assert_eq!(service.get_user(1).await.unwrap().name, "John Smith");
+    }
+
+    #[tokio::test]
+    async fn test_usernames_are_unique_and_immutable() {
+        let (_, service) = transactional_service();
+        let user = User::new("Ana".to_string(), "ana@example.com".to_string(), "password123".to_string())
+            .with_username(Some("ana".to_string()));
+        service.create_user(user).await.unwrap();
+
+        let taken = User::new("Ana Two".to_string(), "ana2@example.com".to_string(), "password123".to_string())
+            .with_username(Some("ANA".to_string()));
+        let err = service.create_user(taken).await.unwrap_err();
+        assert_eq!(err, DomainError::Conflict("Username ana is already taken".to_string()));
+
+        assert_eq!(service.username_available("@Ana").await.unwrap(), ("ana".to_string(), false));
+        assert_eq!(service.username_available("bia").await.unwrap(), ("bia".to_string(), true));
+        let err = service.username_available("a_b").await.unwrap_err();
+        assert!(matches!(err, DomainError::Invalid(_)));
+        assert_eq!(service.get_user_by_username("@ana").await.unwrap().id, Some(1));
+        let err = service.get_user_by_username("bia").await.unwrap_err();
+        assert!(matches!(err, DomainError::NotFound(_)));
+
+        // Updates without a username keep it; changing it is refused
+        let renamed = User::new("Ana Silva".to_string(), "ana@example.com".to_string(), "password123".to_string());
+        service.update_user(1, renamed.clone()).await.unwrap();
+        assert_eq!(service.get_user(1).await.unwrap().username.as_deref(), Some("ana"));
+        let err = service
+            .update_user(1, renamed.with_username(Some("ana-silva".to_string())))
+            .await
+            .unwrap_err();
+        assert_eq!(err, DomainError::Invalid("Username cannot be changed".to_string()));
+
+        // A user without one can pick a free username once
+        let user = User::new("Bia".to_string(), "bia@example.com".to_string(), "password123".to_string());
+        service.create_user(user.clone()).await.unwrap();
+        let err = service
+            .update_user(2, user.clone().with_username(Some("ana".to_string())))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Conflict(_)));
+        service.update_user(2, user.with_username(Some("bia".to_string()))).await.unwrap();
+        assert_eq!(service.get_user(2).await.unwrap().username.as_deref(), Some("bia"));
+    }
+
+    #[tokio::test]
+    async fn test_tags_filter_users() {
+        let service = create_test_service();
+        for (name, email) in [("Ana", "ana@example.com"), ("Bia", "bia@example.com"), ("Caio", "caio@example.com")] {
+            let user = User::new(name.to_string(), email.to_string(), "password123".to_string());
+            service.create_user(user).await.unwrap();
+        }
+
+        let tags = ["VIP", " beta", "vip"].map(String::from);
+        let ana = service.set_user_tags(1, &tags).await.unwrap();
+        assert_eq!(ana.tags, vec!["beta".to_string(), "vip".to_string()]);
+        service.set_user_tags(2, &["vip".to_string()]).await.unwrap();
+
+        let (users, total) = service.get_tagged_users("Vip", None).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(users.iter().map(|u| u.id).collect::<Vec<_>>(), vec![Some(1), Some(2)]);
+        let (users, total) = service
+            .get_tagged_users("vip", Some(Pagination::from_query(Some(2), Some(1))))
+            .await
+            .unwrap();
+        assert_eq!((users[0].id, total), (Some(2), 2));
+
+        let counts = service.tag_counts().await.unwrap();
+        assert_eq!(counts[0], TagCount { tag: "vip".to_string(), count: 2 });
+        assert_eq!(counts.len(), 2);
+
+        // Updates leave tags alone
+        let user = User::new("Ana".to_string(), "ana@example.com".to_string(), "password123".to_string());
+        service.update_user(1, user).await.unwrap();
+        assert_eq!(service.get_user(1).await.unwrap().tags.len(), 2);
+
+        assert!(matches!(service.get_tagged_users("not a tag", None).await, Err(DomainError::Invalid(_))));
+        let err = service.set_user_tags(1, &["no spaces".to_string()]).await.unwrap_err();
+        assert!(matches!(err, DomainError::Invalid(_)));
+        assert!(matches!(service.set_user_tags(9, &[]).await, Err(DomainError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_users() {
+        let service = create_test_service();
+        for (name, email) in [("Ana", "ana@example.com"), ("Bia", "bia@example.com")] {
+            let user = User::new(name.to_string(), email.to_string(), "password123".to_string());
+            service.create_user(user).await.unwrap();
+        }
+        service.set_user_tags(2, &["beta".to_string(), "old".to_string()]).await.unwrap();
+        let mut events = service.events().subscribe();
+
+        let request = BulkUpdateRequest {
+            ids: vec![1, 2, 9, 1],
+            status: Some(UserStatus::Suspended),
+            add_tags: vec!["VIP".to_string()],
+            remove_tags: vec!["old".to_string()],
+        };
+        let outcomes = service.bulk_update_users(&request).await.unwrap();
+        assert_eq!(outcomes.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![1, 2, 9]);
+        let bia = outcomes[1].1.as_ref().unwrap();
+        assert_eq!(bia.status, UserStatus::Suspended);
+        assert_eq!(bia.tags, vec!["beta".to_string(), "vip".to_string()]);
+        assert!(matches!(outcomes[2].1, Err(DomainError::NotFound(_))));
+        assert_eq!(events.try_recv().unwrap().name(), "user.updated");
+        assert_eq!(events.try_recv().unwrap().name(), "user.updated");
+        assert!(events.try_recv().is_err());
+
+        // A user that would exceed the tag limit is skipped, untouched
+        let many: Vec<String> = (0..10).map(|i| format!("tag-{}", i)).collect();
+        service.set_user_tags(1, &many).await.unwrap();
+        let request = BulkUpdateRequest {
+            ids: vec![1, 2],
+            status: Some(UserStatus::Active),
+            add_tags: vec!["new".to_string()],
+            remove_tags: Vec::new(),
+        };
+        let outcomes = service.bulk_update_users(&request).await.unwrap();
+        assert!(matches!(outcomes[0].1, Err(DomainError::Invalid(_))));
+        assert_eq!(service.get_user(1).await.unwrap().status, UserStatus::Suspended);
+        assert_eq!(outcomes[1].1.as_ref().unwrap().status, UserStatus::Active);
+
+        let invalid = |ids: Vec<i32>, add_tags: Vec<String>| BulkUpdateRequest {
+            ids,
+            status: None,
+            add_tags,
+            remove_tags: Vec::new(),
+        };
+        for request in [
+            invalid(Vec::new(), vec!["vip".to_string()]),
+            invalid((0..101).collect(), vec!["vip".to_string()]),
+            invalid(vec![1], Vec::new()),
+            invalid(vec![1], vec!["not a tag".to_string()]),
+        ] {
+            assert!(matches!(service.bulk_update_users(&request).await, Err(DomainError::Invalid(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_emails_ignore_case() {
+        let repo = Arc::new(MockUserRepository::new());
+        let service = UserService::new(repo.clone(), EventBus::default());
+        for (name, email) in [("John", "john@example.com"), ("Johnny", "John@Example.com "), ("Jane", "jane@example.com")] {
+            // Rows written before emails were normalized
+            let mut user = User::new(name.to_string(), String::new(), "password123".to_string());
+            user.email = email.to_string();
+            repo.create(&user).await.unwrap();
+        }
+
+        let duplicates = service.duplicate_emails().await.unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].0, "john@example.com");
+        let names: Vec<&str> = duplicates[0].1.iter().map(|user| user.name.as_str()).collect();
+        assert_eq!(names, ["John", "Johnny"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_users() {
+        let service = create_test_service();
+        for (name, email) in [("John Doe", "john@example.com"), ("Jane Roe", "jane@example.com")] {
+            let user = User::new(name.to_string(), email.to_string(), "password123".to_string());
+            service.create_user(user).await.unwrap();
+        }
+
+        let found = service.search_users(" JOHN ", None).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].user.name, "John Doe");
+        assert_eq!(service.search_users("example", Some(0)).await.unwrap().len(), 1);
+
+        let err = service.search_users("  ", None).await.unwrap_err();
+        assert!(matches!(err, DomainError::Invalid(_)));
+    }
 }
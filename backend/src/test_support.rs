@@ -0,0 +1,62 @@
+use proptest::prelude::*;
+use proptest::sample::select;
+
+// Test support - Single Responsibility Principle
+// proptest strategies shared by the property tests of the models,
+// pagination and export modules
+
+/// Inputs tried per property
+pub const PROPERTY_CASES: u32 = 2_000;
+
+/// Characters that sit on the edges of the validation and CSV rules:
+/// separators, quotes, whitespace, formula prefixes and non-ASCII
+/// letters whose case mapping changes their length or class
+const EDGE_CHARS: [char; 24] = [
+    '@', '.', '-', '+', '_', '\'', '"', ',', ' ', '\t', '\n', '\r', '=', '#', '\0',
+    'A', 'z', '9', 'é', 'ß', '\u{212A}', '\u{130}', '\u{feff}', '🦀',
+];
+
+/// proptest configuration of the property tests: PROPERTY_CASES inputs each
+pub fn property_config() -> ProptestConfig {
+    ProptestConfig::with_cases(PROPERTY_CASES)
+}
+
+/// Mostly EDGE_CHARS and printable ASCII, sometimes any char
+fn arbitrary_char() -> impl Strategy<Value = char> {
+    prop_oneof![
+        5 => select(&EDGE_CHARS[..]),
+        4 => proptest::char::range(' ', '~'),
+        1 => any::<char>(),
+    ]
+}
+
+/// Up to `max_len` characters from arbitrary_char
+pub fn arbitrary_text(max_len: usize) -> impl Strategy<Value = String> {
+    proptest::collection::vec(arbitrary_char(), 0..=max_len).prop_map(|chars| chars.into_iter().collect())
+}
+
+/// Mostly letters and digits with the odd edge character, so the
+/// result is often one character away from valid
+fn arbitrary_atom(max_len: usize) -> impl Strategy<Value = String> {
+    let char = prop_oneof![
+        1 => select(&EDGE_CHARS[..]),
+        9 => proptest::char::ranges(vec!['a'..='z', 'A'..='Z', '0'..='9'].into()),
+    ];
+    proptest::collection::vec(char, 1..=max_len).prop_map(|chars| chars.into_iter().collect())
+}
+
+/// `local@domain.tld` built from arbitrary atoms
+fn email_of_atoms() -> impl Strategy<Value = String> {
+    (arbitrary_atom(8), proptest::collection::vec(arbitrary_atom(6), 1..4))
+        .prop_map(|(local, labels)| format!("{}@{}", local, labels.join(".")))
+}
+
+/// Something shaped like `local@domain.tld` built from arbitrary parts,
+/// so both sides of the email rules are reached
+pub fn arbitrary_email() -> impl Strategy<Value = String> {
+    prop_oneof![
+        1 => email_of_atoms().prop_map(|email| format!(" {} ", email.to_uppercase())),
+        1 => arbitrary_text(12),
+        2 => email_of_atoms(),
+    ]
+}
@@ -0,0 +1,230 @@
+use crate::crypto::FieldCipher;
+use crate::domain::RepositoryError;
+use crate::query_retry::QueryRetry;
+use crate::query_timeout::{statement_timeout_sql, QueryTimeout};
+use crate::repository::{PostgresUserRepository, UserRepository};
+use crate::secrets::SecretString;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio_postgres::NoTls;
+
+// Unit of work - Dependency Inversion Principle
+// The service runs multi-step operations through a UnitOfWork so all their
+// writes commit or roll back together, without knowing how transactions
+// are implemented. Dropping a unit of work without committing rolls it back
+
+/// Writes of one operation, committed or rolled back together
+#[async_trait]
+pub trait UnitOfWork: Send {
+    /// Repository whose reads and writes belong to this unit of work
+    fn users(&self) -> Arc<dyn UserRepository>;
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError>;
+}
+
+/// Starts units of work
+#[async_trait]
+pub trait UnitOfWorkFactory: Send + Sync {
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork>, RepositoryError>;
+}
+
+/// Units of work that write straight to `repository`, one statement at a
+/// time; used where no transactional store is configured
+pub struct DirectUnitOfWorkFactory {
+    repository: Arc<dyn UserRepository>,
+}
+
+impl DirectUnitOfWorkFactory {
+    pub fn new(repository: Arc<dyn UserRepository>) -> Self {
+        DirectUnitOfWorkFactory { repository }
+    }
+}
+
+struct DirectUnitOfWork {
+    repository: Arc<dyn UserRepository>,
+}
+
+#[async_trait]
+impl UnitOfWork for DirectUnitOfWork {
+    fn users(&self) -> Arc<dyn UserRepository> {
+        self.repository.clone()
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UnitOfWorkFactory for DirectUnitOfWorkFactory {
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork>, RepositoryError> {
+        Ok(Box::new(DirectUnitOfWork {
+            repository: self.repository.clone(),
+        }))
+    }
+}
+
+/// Postgres transactions. The shared client cannot hold a transaction
+/// without mixing in the statements of concurrent requests, so every unit
+/// of work gets its own short-lived connection; closing it without COMMIT
+/// makes Postgres roll the transaction back
+pub struct PostgresUnitOfWorkFactory {
+    connection_string: SecretString,
+    cipher: Option<Arc<FieldCipher>>,
+    timeout: Option<QueryTimeout>,
+    retry: Option<QueryRetry>,
+}
+
+impl PostgresUnitOfWorkFactory {
+    pub fn new(connection_string: SecretString) -> Self {
+        PostgresUnitOfWorkFactory {
+            connection_string,
+            cipher: None,
+            timeout: None,
+            retry: None,
+        }
+    }
+
+    /// Encrypt PII like the main repository does
+    pub fn with_cipher(mut self, cipher: Arc<FieldCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Limit statements like on the shared connection
+    pub fn with_timeout(mut self, timeout: QueryTimeout) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Connect again after transient errors as `retry` says. The statements
+    /// of the transaction are not retried, since such an error aborts it
+    pub fn with_retry(mut self, retry: QueryRetry) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+}
+
+struct PostgresUnitOfWork {
+    client: Arc<tokio_postgres::Client>,
+    repository: Arc<PostgresUserRepository>,
+}
+
+#[async_trait]
+impl UnitOfWork for PostgresUnitOfWork {
+    fn users(&self) -> Arc<dyn UserRepository> {
+        self.repository.clone()
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+        self.client
+            .batch_execute("COMMIT")
+            .await
+            .map_err(RepositoryError::from)
+    }
+}
+
+#[async_trait]
+impl UnitOfWorkFactory for PostgresUnitOfWorkFactory {
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork>, RepositoryError> {
+        let connect = || tokio_postgres::connect(self.connection_string.expose(), NoTls);
+        let (client, connection) = match &self.retry {
+            Some(retry) => retry.connect(connect).await?,
+            None => connect().await?,
+        };
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("Unit of work connection error: {}", e);
+            }
+        });
+        if let Some(timeout) = &self.timeout {
+            client.batch_execute(&statement_timeout_sql(timeout.statement_timeout())).await?;
+        }
+        client.batch_execute("BEGIN").await?;
+
+        let client = Arc::new(client);
+        let mut repository = PostgresUserRepository::new(client.clone());
+        if let Some(cipher) = &self.cipher {
+            repository = repository.with_cipher(cipher.clone());
+        }
+        if let Some(timeout) = &self.timeout {
+            repository = repository.with_timeout(timeout.clone());
+        }
+        Ok(Box::new(PostgresUnitOfWork {
+            client,
+            repository: Arc::new(repository),
+        }))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::repository::tests::MockUserRepository;
+
+    /// Units of work over a copy of the mock's users, copied back on commit
+    pub struct MockUnitOfWorkFactory {
+        pub repository: Arc<MockUserRepository>,
+    }
+
+    impl MockUnitOfWorkFactory {
+        pub fn new(repository: Arc<MockUserRepository>) -> Self {
+            MockUnitOfWorkFactory { repository }
+        }
+    }
+
+    struct MockUnitOfWork {
+        target: Arc<MockUserRepository>,
+        work: Arc<MockUserRepository>,
+    }
+
+    #[async_trait]
+    impl UnitOfWork for MockUnitOfWork {
+        fn users(&self) -> Arc<dyn UserRepository> {
+            self.work.clone()
+        }
+
+        async fn commit(self: Box<Self>) -> Result<(), RepositoryError> {
+            let users = self.work.users.lock().unwrap().clone();
+            *self.target.users.lock().unwrap() = users;
+            let history = self.work.password_history.lock().unwrap().clone();
+            *self.target.password_history.lock().unwrap() = history;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl UnitOfWorkFactory for MockUnitOfWorkFactory {
+        async fn begin(&self) -> Result<Box<dyn UnitOfWork>, RepositoryError> {
+            let work = MockUserRepository::default();
+            *work.users.lock().unwrap() = self.repository.users.lock().unwrap().clone();
+            *work.password_history.lock().unwrap() = self.repository.password_history.lock().unwrap().clone();
+            work.history_unavailable.store(
+                self.repository.history_unavailable.load(std::sync::atomic::Ordering::Relaxed),
+                std::sync::atomic::Ordering::Relaxed,
+            );
+            Ok(Box::new(MockUnitOfWork {
+                target: self.repository.clone(),
+                work: Arc::new(work),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_unit_of_work_commits_or_discards() {
+        use crate::models::User;
+
+        let repository = Arc::new(MockUserRepository::new());
+        let factory = MockUnitOfWorkFactory::new(repository.clone());
+        let user = User::new("John Doe".to_string(), "john@example.com".to_string(), "password123".to_string());
+
+        let unit = factory.begin().await.unwrap();
+        unit.users().create(&user).await.unwrap();
+        drop(unit);
+        assert_eq!(repository.count().await.unwrap(), 0);
+
+        let unit = factory.begin().await.unwrap();
+        unit.users().create(&user).await.unwrap();
+        unit.commit().await.unwrap();
+        assert_eq!(repository.count().await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::domain::{DomainError, RepositoryError};
+use crate::jobs::JobQueue;
+use crate::mailer::{self, SEND_EMAIL_JOB_KIND};
+use crate::models::UserResponse;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_postgres::Client;
+
+// Email verification - Single Responsibility Principle
+// Issues the single-use tokens of the confirmation links emailed after
+// signup, and marks the address verified when a link is followed
+
+/// How long a confirmation link stays valid
+pub const VERIFICATION_TOKEN_TTL: Duration = Duration::from_secs(48 * 60 * 60);
+
+/// Storage of pending verification tokens
+#[async_trait]
+pub trait VerificationTokenRepository: Send + Sync {
+    async fn create(&self, user_id: i32, token: &str, ttl: Duration) -> Result<(), RepositoryError>;
+    /// Remove the token and mark its user's email verified; the user id,
+    /// or None when the token is unknown or expired
+    async fn consume(&self, token: &str) -> Result<Option<i32>, RepositoryError>;
+}
+
+pub struct PostgresVerificationTokenRepository {
+    client: Arc<Client>,
+}
+
+impl PostgresVerificationTokenRepository {
+    pub fn new(client: Arc<Client>) -> Self {
+        PostgresVerificationTokenRepository { client }
+    }
+}
+
+#[async_trait]
+impl VerificationTokenRepository for PostgresVerificationTokenRepository {
+    async fn create(&self, user_id: i32, token: &str, ttl: Duration) -> Result<(), RepositoryError> {
+        self.client
+            .execute(
+                "INSERT INTO email_verification_tokens (token, user_id, expires_at)
+                 VALUES ($1, $2, NOW() + $3 * INTERVAL '1 second')",
+                &[&token, &user_id, &ttl.as_secs_f64()],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn consume(&self, token: &str) -> Result<Option<i32>, RepositoryError> {
+        // Expired tokens are deleted as well, without verifying anything
+        let row = self
+            .client
+            .query_opt(
+                "WITH consumed AS (
+                    DELETE FROM email_verification_tokens WHERE token = $1
+                    RETURNING user_id, expires_at
+                 )
+                 UPDATE users SET email_verified = TRUE
+                 FROM consumed
+                 WHERE users.id = consumed.user_id AND consumed.expires_at > NOW()
+                 RETURNING users.id",
+                &[&token],
+            )
+            .await?;
+        Ok(row.map(|row| row.get(0)))
+    }
+}
+
+/// Sends confirmation links and verifies the tokens coming back
+pub struct EmailVerificationService {
+    repository: Arc<dyn VerificationTokenRepository>,
+    jobs: Arc<JobQueue>,
+    app_url: String,
+}
+
+impl EmailVerificationService {
+    pub fn new(repository: Arc<dyn VerificationTokenRepository>, jobs: Arc<JobQueue>, app_url: String) -> Self {
+        EmailVerificationService {
+            repository,
+            jobs,
+            app_url,
+        }
+    }
+
+    /// Queue an email with a fresh confirmation link for `user`
+    pub async fn send(&self, user: &UserResponse) -> Result<(), DomainError> {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        self.repository
+            .create(user.id, &token, VERIFICATION_TOKEN_TTL)
+            .await?;
+
+        let link = format!("{}/verify-email?token={}", self.app_url.trim_end_matches('/'), token);
+        let email = mailer::verification_email(&user.email, &user.name, &link).map_err(DomainError::Internal)?;
+        let payload = serde_json::to_value(&email).map_err(|e| DomainError::Internal(e.to_string()))?;
+        self.jobs.enqueue(SEND_EMAIL_JOB_KIND, payload).await?;
+        Ok(())
+    }
+
+    /// Mark the email of the token's user verified; the user id
+    pub async fn verify(&self, token: &str) -> Result<i32, DomainError> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(invalid_token());
+        }
+        self.repository.consume(token).await?.ok_or_else(invalid_token)
+    }
+}
+
+fn invalid_token() -> DomainError {
+    DomainError::Invalid("Invalid or expired verification token".to_string())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::jobs::tests::MockJobRepository;
+    use crate::mailer::{Email, LogMailer, SendEmailJob};
+    use crate::models::UserStatus;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    // In-memory tokens for tests; tokens never expire
+    #[derive(Default)]
+    pub struct MockVerificationTokenRepository {
+        pub tokens: Mutex<HashMap<String, i32>>,
+        pub verified: Mutex<Vec<i32>>,
+    }
+
+    #[async_trait]
+    impl VerificationTokenRepository for MockVerificationTokenRepository {
+        async fn create(&self, user_id: i32, token: &str, _ttl: Duration) -> Result<(), RepositoryError> {
+            self.tokens.lock().unwrap().insert(token.to_string(), user_id);
+            Ok(())
+        }
+
+        async fn consume(&self, token: &str) -> Result<Option<i32>, RepositoryError> {
+            let user_id = self.tokens.lock().unwrap().remove(token);
+            if let Some(user_id) = user_id {
+                self.verified.lock().unwrap().push(user_id);
+            }
+            Ok(user_id)
+        }
+    }
+
+    fn user() -> UserResponse {
+        UserResponse {
+            id: 7,
+            name: "John Doe".to_string(),
+            email: "john@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_queues_email_with_link_and_verify_consumes_it() {
+        let repository = Arc::new(MockVerificationTokenRepository::default());
+        let job_repository = Arc::new(MockJobRepository::new());
+        let jobs = Arc::new(
+            JobQueue::new(job_repository.clone()).with_handler(Arc::new(SendEmailJob::new(Arc::new(LogMailer)))),
+        );
+        let service = EmailVerificationService::new(repository.clone(), jobs, "https://app.example.com/".to_string());
+
+        service.send(&user()).await.unwrap();
+        let token = repository.tokens.lock().unwrap().keys().next().unwrap().clone();
+        let job = job_repository.jobs.lock().unwrap()[0].clone();
+        let email: Email = serde_json::from_value(job.payload).unwrap();
+        assert_eq!(job.kind, SEND_EMAIL_JOB_KIND);
+        assert_eq!(email.to, "john@example.com");
+        assert!(email
+            .body
+            .contains(&format!("https://app.example.com/verify-email?token={}", token)));
+
+        assert_eq!(service.verify(&token).await.unwrap(), 7);
+        assert_eq!(*repository.verified.lock().unwrap(), vec![7]);
+
+        // Tokens are single use
+        let error = service.verify(&token).await.unwrap_err();
+        assert!(matches!(error, DomainError::Invalid(_)));
+        assert!(matches!(service.verify(" ").await.unwrap_err(), DomainError::Invalid(_)));
+    }
+}
@@ -0,0 +1,87 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::{Build, Request, Response, Rocket, Route};
+use shared::routes;
+
+// API versioning - Open/Closed Principle
+// Each API version is a set of routes mounted under its own prefix; the
+// unversioned `/api` prefix is a deprecated alias of v1 kept for existing
+// consumers. A future v2 is one more `mount` with its own route list
+
+pub const LEGACY_PREFIX: &str = routes::LEGACY_API_PREFIX;
+pub const V1_PREFIX: &str = routes::API_V1_PREFIX;
+
+pub const DEPRECATION_HEADER: &str = "Deprecation";
+pub const LINK_HEADER: &str = "Link";
+pub const WARNING_HEADER: &str = "Warning";
+
+/// Mount the v1 routes under `/api/v1` and their deprecated `/api` alias
+pub fn mount_v1(rocket: Rocket<Build>, routes: Vec<Route>) -> Rocket<Build> {
+    rocket
+        .mount(V1_PREFIX, routes.clone())
+        .mount(LEGACY_PREFIX, routes)
+}
+
+/// Whether `path` uses the unversioned `/api` prefix
+pub fn is_legacy_path(path: &str) -> bool {
+    let Some(rest) = path.strip_prefix(LEGACY_PREFIX) else {
+        return false;
+    };
+    if !(rest.is_empty() || rest.starts_with('/')) {
+        return false;
+    }
+
+    let first_segment = rest.trim_start_matches('/').split('/').next().unwrap_or("");
+    let is_version = first_segment
+        .strip_prefix('v')
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+    !is_version
+}
+
+/// Marks responses served through the legacy `/api` alias as deprecated
+/// and points clients to the equivalent `/api/v1` URL
+pub struct DeprecationFairing;
+
+#[rocket::async_trait]
+impl Fairing for DeprecationFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Legacy API deprecation",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let path = request.uri().path();
+        if !is_legacy_path(path.as_str()) {
+            return;
+        }
+
+        let successor = format!("{}{}", V1_PREFIX, &path.as_str()[LEGACY_PREFIX.len()..]);
+        response.set_header(Header::new(DEPRECATION_HEADER, "true"));
+        response.set_header(Header::new(
+            LINK_HEADER,
+            format!("<{}>; rel=\"successor-version\"", successor),
+        ));
+        response.set_header(Header::new(
+            WARNING_HEADER,
+            format!("299 - \"Deprecated API, use {} instead\"", V1_PREFIX),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_legacy_path() {
+        assert!(is_legacy_path("/api"));
+        assert!(is_legacy_path("/api/users"));
+        assert!(is_legacy_path("/api/admin/jobs"));
+        assert!(!is_legacy_path("/api/v1/users"));
+        assert!(!is_legacy_path("/api/v2"));
+        assert!(!is_legacy_path("/apiary"));
+        assert!(!is_legacy_path("/health"));
+    }
+}
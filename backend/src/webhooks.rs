@@ -0,0 +1,767 @@
+use crate::config::{RetryConfig, WebhookConfig};
+use crate::domain::{DomainError, RepositoryError};
+use crate::events::{EVENT_NAMES, EventSubscriber, UserEvent};
+use crate::jobs::{JobHandler, JobQueue};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_postgres::Client;
+use url::{Host, Url};
+
+// Outbound webhooks - Open/Closed Principle
+// Admins register URLs that are notified of user lifecycle events; delivery is
+// just another EventSubscriber, so the user service knows nothing about it.
+// Each delivery is a background job, so retries survive restarts.
+// Deliveries carry personal data and are sent from inside the network, so
+// endpoints must be public: loopback, private, link-local and other internal
+// addresses are refused when a webhook is saved and again on every delivery,
+// whose resolver drops them, so a name re-pointed later gains nothing
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_JOB_KIND: &str = "webhook.deliver";
+
+/// A registered webhook endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Webhook {
+    pub id: Option<i32>,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+}
+
+impl Webhook {
+    pub fn with_id(id: i32, url: String, secret: String, events: Vec<String>) -> Self {
+        Webhook {
+            id: Some(id),
+            url,
+            secret,
+            events,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        check_url(&self.url)?;
+        if self.secret.trim().is_empty() {
+            return Err("Webhook secret cannot be empty".to_string());
+        }
+        if self.events.is_empty() {
+            return Err("Webhook must subscribe to at least one event".to_string());
+        }
+        if let Some(unknown) = self.events.iter().find(|e| !EVENT_NAMES.contains(&e.as_str())) {
+            return Err(format!("Unknown webhook event '{}'", unknown));
+        }
+        Ok(())
+    }
+}
+
+/// Parse a webhook URL, which must be http(s) and must not name an internal
+/// address literally; host names are checked once resolved
+pub fn check_url(url: &str) -> Result<Url, String> {
+    let invalid = || "Webhook URL must be an http(s) URL".to_string();
+    let parsed = Url::parse(url.trim()).map_err(|_| invalid())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid());
+    }
+    let ip = match parsed.host() {
+        None => return Err(invalid()),
+        Some(Host::Domain(_)) => None,
+        Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+    };
+    match ip {
+        Some(ip) if !is_public_ip(ip) => Err(internal_address(ip)),
+        _ => Ok(parsed),
+    }
+}
+
+fn internal_address(ip: IpAddr) -> String {
+    format!("Webhook URL must not point to the internal address {}", ip)
+}
+
+/// Whether `ip` is reachable on the internet, as opposed to loopback,
+/// private (RFC 1918, unique local), link-local, unspecified, shared (CGNAT),
+/// broadcast, documentation or multicast addresses
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8 "this network" and 100.64.0.0/10 shared address space
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)))
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7 unique local and fe80::/10 link-local
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        // 2001:db8::/32 documentation
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// Resolve the host of a webhook URL and fail if any of its addresses is
+/// internal. A host that cannot be resolved now is accepted: deliveries
+/// resolve it again through PublicResolver
+pub async fn check_destination(url: &str) -> Result<(), String> {
+    let parsed = check_url(url)?;
+    let Some(Host::Domain(domain)) = parsed.host() else {
+        return Ok(());
+    };
+    let port = parsed.port_or_known_default().unwrap_or_default();
+    let Ok(addresses) = tokio::net::lookup_host((domain, port)).await else {
+        return Ok(());
+    };
+    for address in addresses {
+        if !is_public_ip(address.ip()) {
+            return Err(internal_address(address.ip()));
+        }
+    }
+    Ok(())
+}
+
+/// DNS resolver of the delivery client that only hands out public addresses
+pub struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let public: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|address| is_public_ip(address.ip()))
+                .collect();
+            if public.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            let addresses: reqwest::dns::Addrs = Box::new(public.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Public representation of a webhook - the signing secret is write-only
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookResponse {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        WebhookResponse {
+            id: webhook.id.unwrap_or_default(),
+            url: webhook.url,
+            events: webhook.events,
+        }
+    }
+}
+
+/// Webhook storage - Dependency Inversion Principle
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    async fn create(&self, webhook: &Webhook) -> Result<Webhook, RepositoryError>;
+    async fn find_all(&self) -> Result<Vec<Webhook>, RepositoryError>;
+    async fn find_by_event(&self, event: &str) -> Result<Vec<Webhook>, RepositoryError>;
+    async fn update(&self, id: i32, webhook: &Webhook) -> Result<(), RepositoryError>;
+    async fn delete(&self, id: i32) -> Result<(), RepositoryError>;
+}
+
+/// PostgreSQL implementation of WebhookRepository
+pub struct PostgresWebhookRepository {
+    client: Arc<Client>,
+}
+
+impl PostgresWebhookRepository {
+    pub fn new(client: Arc<Client>) -> Self {
+        PostgresWebhookRepository { client }
+    }
+
+    async fn query_webhooks(
+        &self,
+        query: &str,
+        params: &[&(dyn tokio_postgres::types::ToSql + Sync)],
+    ) -> Result<Vec<Webhook>, RepositoryError> {
+        let webhooks = self
+            .client
+            .query(query, params)
+            .await?
+            .iter()
+            .map(|row| Webhook::with_id(row.get(0), row.get(1), row.get(2), row.get(3)))
+            .collect();
+
+        Ok(webhooks)
+    }
+
+    fn not_found(id: i32) -> RepositoryError {
+        RepositoryError::NotFound(format!("Webhook with id {} not found", id))
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for PostgresWebhookRepository {
+    async fn create(&self, webhook: &Webhook) -> Result<Webhook, RepositoryError> {
+        let row = self
+            .client
+            .query_one(
+                "INSERT INTO webhooks (url, secret, events) VALUES ($1, $2, $3) RETURNING id",
+                &[&webhook.url, &webhook.secret, &webhook.events],
+            )
+            .await?;
+
+        Ok(Webhook::with_id(
+            row.get(0),
+            webhook.url.clone(),
+            webhook.secret.clone(),
+            webhook.events.clone(),
+        ))
+    }
+
+    async fn find_all(&self) -> Result<Vec<Webhook>, RepositoryError> {
+        self.query_webhooks("SELECT id, url, secret, events FROM webhooks ORDER BY id", &[])
+            .await
+    }
+
+    async fn find_by_event(&self, event: &str) -> Result<Vec<Webhook>, RepositoryError> {
+        self.query_webhooks(
+            "SELECT id, url, secret, events FROM webhooks WHERE $1 = ANY(events) ORDER BY id",
+            &[&event],
+        )
+        .await
+    }
+
+    async fn update(&self, id: i32, webhook: &Webhook) -> Result<(), RepositoryError> {
+        let updated = self
+            .client
+            .execute(
+                "UPDATE webhooks SET url = $1, secret = $2, events = $3 WHERE id = $4",
+                &[&webhook.url, &webhook.secret, &webhook.events, &id],
+            )
+            .await?;
+
+        if updated == 0 {
+            return Err(Self::not_found(id));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: i32) -> Result<(), RepositoryError> {
+        let deleted = self
+            .client
+            .execute("DELETE FROM webhooks WHERE id = $1", &[&id])
+            .await?;
+
+        if deleted == 0 {
+            return Err(Self::not_found(id));
+        }
+        Ok(())
+    }
+}
+
+/// Business rules for managing webhooks through the admin API
+pub struct WebhookService {
+    repository: Arc<dyn WebhookRepository>,
+}
+
+impl WebhookService {
+    pub fn new(repository: Arc<dyn WebhookRepository>) -> Self {
+        WebhookService { repository }
+    }
+
+    async fn check(webhook: &Webhook) -> Result<(), DomainError> {
+        webhook.validate().map_err(DomainError::Invalid)?;
+        check_destination(&webhook.url).await.map_err(DomainError::Invalid)
+    }
+
+    pub async fn create_webhook(&self, webhook: Webhook) -> Result<Webhook, DomainError> {
+        Self::check(&webhook).await?;
+        Ok(self.repository.create(&webhook).await?)
+    }
+
+    pub async fn get_webhooks(&self) -> Result<Vec<Webhook>, DomainError> {
+        Ok(self.repository.find_all().await?)
+    }
+
+    pub async fn update_webhook(&self, id: i32, webhook: Webhook) -> Result<Webhook, DomainError> {
+        Self::check(&webhook).await?;
+        self.repository.update(id, &webhook).await?;
+        Ok(Webhook { id: Some(id), ..webhook })
+    }
+
+    pub async fn delete_webhook(&self, id: i32) -> Result<(), DomainError> {
+        Ok(self.repository.delete(id).await?)
+    }
+}
+
+/// Body POSTed to webhook endpoints
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WebhookPayload<'a> {
+    pub event: &'static str,
+    pub data: &'a UserEvent,
+    pub timestamp: u64,
+}
+
+/// Hex-encoded HMAC-SHA256 of `body`, sent as `X-Webhook-Signature: sha256=<hex>`
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// One signed delivery, stored as the payload of a `webhook.deliver` job
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookDelivery {
+    pub url: String,
+    pub event: String,
+    pub signature: String,
+    pub body: String,
+}
+
+/// Transport used to deliver webhooks, abstracted for testing
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    async fn send(&self, delivery: &WebhookDelivery) -> Result<(), String>;
+}
+
+/// Sends deliveries over HTTP to public addresses only; redirects are not
+/// followed, since they could lead anywhere. Any non-2xx response counts as a failure
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    pub fn new(config: &WebhookConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .dns_resolver(Arc::new(PublicResolver))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to build webhook HTTP client");
+        HttpWebhookSender { client }
+    }
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, delivery: &WebhookDelivery) -> Result<(), String> {
+        // Address literals bypass the resolver
+        let url = check_url(&delivery.url)?;
+        let response = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header(EVENT_HEADER, &delivery.event)
+            .header(SIGNATURE_HEADER, &delivery.signature)
+            .body(delivery.body.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("endpoint answered {}", response.status()))
+        }
+    }
+}
+
+/// Job handler performing one delivery; failures are retried by the job queue
+/// with the webhook backoff policy
+pub struct WebhookDeliveryJob {
+    sender: Arc<dyn WebhookSender>,
+    retry: RetryConfig,
+}
+
+impl WebhookDeliveryJob {
+    pub fn new(sender: Arc<dyn WebhookSender>, config: &WebhookConfig) -> Self {
+        WebhookDeliveryJob {
+            sender,
+            retry: config.retry.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for WebhookDeliveryJob {
+    fn kind(&self) -> &'static str {
+        DELIVERY_JOB_KIND
+    }
+
+    fn retry(&self) -> RetryConfig {
+        self.retry.clone()
+    }
+
+    async fn run(&self, payload: &Value) -> Result<(), String> {
+        let delivery: WebhookDelivery =
+            serde_json::from_value(payload.clone()).map_err(|e| e.to_string())?;
+        self.sender.send(&delivery).await
+    }
+}
+
+/// Event subscriber that queues a delivery for every matching webhook
+pub struct WebhookDispatcher {
+    repository: Arc<dyn WebhookRepository>,
+    jobs: Arc<JobQueue>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(repository: Arc<dyn WebhookRepository>, jobs: Arc<JobQueue>) -> Self {
+        WebhookDispatcher { repository, jobs }
+    }
+
+    /// Build the signed delivery of `event` for one webhook
+    pub fn delivery_for(webhook: &Webhook, event: &UserEvent) -> WebhookDelivery {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let body = serde_json::to_string(&WebhookPayload {
+            event: event.name(),
+            data: event,
+            timestamp,
+        })
+        .unwrap_or_default();
+
+        WebhookDelivery {
+            url: webhook.url.clone(),
+            event: event.name().to_string(),
+            signature: sign_payload(&webhook.secret, body.as_bytes()),
+            body,
+        }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for WebhookDispatcher {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    async fn handle(&self, event: &UserEvent) {
+        let webhooks = match self.repository.find_by_event(event.name()).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                eprintln!("Failed to load webhooks for {}: {}", event.name(), e);
+                return;
+            }
+        };
+
+        for webhook in webhooks {
+            let delivery = Self::delivery_for(&webhook, event);
+            let payload = serde_json::to_value(&delivery).unwrap_or_default();
+            if let Err(e) = self.jobs.enqueue(DELIVERY_JOB_KIND, payload).await {
+                eprintln!("Failed to queue webhook delivery to {}: {}", webhook.url, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::jobs::JobStatus;
+    use crate::jobs::tests::MockJobRepository;
+    use crate::models::{UserResponse, UserStatus};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    // In-memory webhook storage for tests
+    #[derive(Default)]
+    pub struct MockWebhookRepository {
+        pub webhooks: Mutex<Vec<Webhook>>,
+    }
+
+    impl MockWebhookRepository {
+        pub fn new() -> Self {
+            MockWebhookRepository {
+                webhooks: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WebhookRepository for MockWebhookRepository {
+        async fn create(&self, webhook: &Webhook) -> Result<Webhook, RepositoryError> {
+            let mut webhooks = self.webhooks.lock().unwrap();
+            let mut created = webhook.clone();
+            created.id = Some(webhooks.len() as i32 + 1);
+            webhooks.push(created.clone());
+            Ok(created)
+        }
+
+        async fn find_all(&self) -> Result<Vec<Webhook>, RepositoryError> {
+            Ok(self.webhooks.lock().unwrap().clone())
+        }
+
+        async fn find_by_event(&self, event: &str) -> Result<Vec<Webhook>, RepositoryError> {
+            let webhooks = self.webhooks.lock().unwrap();
+            Ok(webhooks
+                .iter()
+                .filter(|w| w.events.iter().any(|e| e == event))
+                .cloned()
+                .collect())
+        }
+
+        async fn update(&self, id: i32, webhook: &Webhook) -> Result<(), RepositoryError> {
+            let mut webhooks = self.webhooks.lock().unwrap();
+            let existing = webhooks
+                .iter_mut()
+                .find(|w| w.id == Some(id))
+                .ok_or_else(|| PostgresWebhookRepository::not_found(id))?;
+            *existing = Webhook {
+                id: Some(id),
+                ..webhook.clone()
+            };
+            Ok(())
+        }
+
+        async fn delete(&self, id: i32) -> Result<(), RepositoryError> {
+            let mut webhooks = self.webhooks.lock().unwrap();
+            let pos = webhooks
+                .iter()
+                .position(|w| w.id == Some(id))
+                .ok_or_else(|| PostgresWebhookRepository::not_found(id))?;
+            webhooks.remove(pos);
+            Ok(())
+        }
+    }
+
+    /// Fails the first `failures` sends, then records successful deliveries
+    struct FlakySender {
+        failures: Mutex<u32>,
+        delivered: Mutex<Vec<WebhookDelivery>>,
+    }
+
+    impl FlakySender {
+        fn new(failures: u32) -> Self {
+            FlakySender {
+                failures: Mutex::new(failures),
+                delivered: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl WebhookSender for FlakySender {
+        async fn send(&self, delivery: &WebhookDelivery) -> Result<(), String> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err("connection refused".to_string());
+            }
+            self.delivered.lock().unwrap().push(delivery.clone());
+            Ok(())
+        }
+    }
+
+    fn job_queue(sender: Arc<FlakySender>) -> (Arc<MockJobRepository>, Arc<JobQueue>) {
+        let config = WebhookConfig {
+            retry: RetryConfig {
+                max_retries: 2,
+                initial_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(5),
+            },
+            timeout: Duration::from_secs(1),
+        };
+        let repository = Arc::new(MockJobRepository::new());
+        let queue = JobQueue::new(repository.clone())
+            .with_handler(Arc::new(WebhookDeliveryJob::new(sender, &config)));
+        (repository, Arc::new(queue))
+    }
+
+    fn webhook(events: &[&str]) -> Webhook {
+        Webhook {
+            id: None,
+            url: "https://example.com/hooks".to_string(),
+            secret: "s3cret".to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    fn created_event() -> UserEvent {
+        UserEvent::Created {
+            user: UserResponse {
+                id: 1,
+                name: "John Doe".to_string(),
+                email: "john@example.com".to_string(),
+                username: None,
+                status: UserStatus::Active,
+                tags: Vec::new(),
+                last_login_at: None,
+                created_at: None,
+                updated_at: None,
+                phone: None,
+                address: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_validate_webhook() {
+        assert!(webhook(&["user.created"]).validate().is_ok());
+
+        let mut invalid = webhook(&["user.created"]);
+        invalid.url = "ftp://example.com".to_string();
+        assert_eq!(
+            invalid.validate().unwrap_err(),
+            "Webhook URL must be an http(s) URL"
+        );
+
+        assert_eq!(
+            webhook(&[]).validate().unwrap_err(),
+            "Webhook must subscribe to at least one event"
+        );
+        assert_eq!(
+            webhook(&["user.exploded"]).validate().unwrap_err(),
+            "Unknown webhook event 'user.exploded'"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_internal_addresses() {
+        for url in [
+            "http://127.0.0.1/hooks",
+            "http://10.0.0.5:8080/hooks",
+            "http://172.16.3.4/hooks",
+            "https://192.168.1.1/hooks",
+            "http://169.254.169.254/latest/meta-data",
+            "http://0.0.0.0:8000/hooks",
+            "http://100.64.0.1/hooks",
+            "http://[::1]/hooks",
+            "http://[fe80::1]/hooks",
+            "http://[fd00::1]/hooks",
+            "http://[::ffff:127.0.0.1]/hooks",
+            "http://2130706433/hooks",
+        ] {
+            let mut internal = webhook(&["user.created"]);
+            internal.url = url.to_string();
+            let error = internal.validate().unwrap_err();
+            assert!(error.starts_with("Webhook URL must not point to the internal address"), "{}: {}", url, error);
+        }
+
+        for url in ["https://93.184.215.14/hooks", "http://[2606:4700::1111]/hooks"] {
+            let mut public = webhook(&["user.created"]);
+            public.url = url.to_string();
+            assert!(public.validate().is_ok(), "{}", url);
+        }
+        assert_eq!(check_url("http://").unwrap_err(), "Webhook URL must be an http(s) URL");
+    }
+
+    #[tokio::test]
+    async fn test_names_resolving_to_internal_addresses_are_rejected() {
+        let error = check_destination("http://localhost:8000/hooks").await.unwrap_err();
+        assert!(error.starts_with("Webhook URL must not point to the internal address"), "{}", error);
+
+        let mut internal = webhook(&["user.created"]);
+        internal.url = "http://localhost/hooks".to_string();
+        let service = WebhookService::new(Arc::new(MockWebhookRepository::new()));
+        let error = service.create_webhook(internal).await.unwrap_err();
+        assert!(matches!(error, DomainError::Invalid(_)));
+
+        use reqwest::dns::Resolve;
+        let name = "localhost".parse().unwrap();
+        assert!(PublicResolver.resolve(name).await.is_err());
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_response_hides_secret() {
+        let response = WebhookResponse::from(Webhook::with_id(
+            1,
+            "https://example.com".to_string(),
+            "s3cret".to_string(),
+            vec!["user.created".to_string()],
+        ));
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(!json.contains("s3cret"));
+    }
+
+    #[tokio::test]
+    async fn test_service_rejects_invalid_webhook() {
+        let service = WebhookService::new(Arc::new(MockWebhookRepository::new()));
+        let error = service.create_webhook(webhook(&[])).await.unwrap_err();
+        assert!(matches!(error, DomainError::Invalid(_)));
+    }
+
+    #[test]
+    fn test_delivery_is_signed_json() {
+        let delivery =
+            WebhookDispatcher::delivery_for(&webhook(&["user.created"]), &created_event());
+
+        assert_eq!(delivery.event, "user.created");
+        assert_eq!(delivery.signature, sign_payload("s3cret", delivery.body.as_bytes()));
+        let body: Value = serde_json::from_str(&delivery.body).unwrap();
+        assert_eq!(body["event"], "user.created");
+        assert_eq!(body["data"]["user"]["email"], "john@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_dispatcher_queues_deliveries_for_subscribed_webhooks() {
+        let webhooks = Arc::new(MockWebhookRepository::new());
+        webhooks.create(&webhook(&["user.created"])).await.unwrap();
+        webhooks.create(&webhook(&["user.deleted"])).await.unwrap();
+        let sender = Arc::new(FlakySender::new(0));
+        let (jobs, queue) = job_queue(sender.clone());
+        let dispatcher = WebhookDispatcher::new(webhooks, queue.clone());
+
+        dispatcher.handle(&created_event()).await;
+        assert_eq!(jobs.jobs.lock().unwrap().len(), 1);
+
+        assert!(queue.run_next().await.unwrap());
+        let delivered = sender.delivered.lock().unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].event, "user.created");
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried_by_the_queue() {
+        let webhooks = Arc::new(MockWebhookRepository::new());
+        webhooks.create(&webhook(&["user.created"])).await.unwrap();
+        let sender = Arc::new(FlakySender::new(1));
+        let (jobs, queue) = job_queue(sender.clone());
+        WebhookDispatcher::new(webhooks, queue.clone())
+            .handle(&created_event())
+            .await;
+
+        while queue.run_next().await.unwrap() {}
+
+        let jobs = jobs.jobs.lock().unwrap();
+        assert_eq!(jobs[0].status, JobStatus::Completed);
+        assert_eq!(jobs[0].attempts, 2);
+        assert_eq!(jobs[0].max_attempts, 3);
+        assert_eq!(sender.delivered.lock().unwrap().len(), 1);
+    }
+}
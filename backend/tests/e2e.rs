@@ -0,0 +1,285 @@
+// End-to-end tests against a real Postgres
+// The unit tests run the handlers on MockUserRepository, so the SQL of the
+// Postgres repository is only exercised here: the application built by
+// app::build_rocket runs on a free port and is called over HTTP.
+// The database is a Postgres testcontainer, so Docker must be running; to use
+// an existing database instead, set E2E_DATABASE_URL, e.g.
+// `E2E_DATABASE_URL="host=localhost port=5431 user=postgres password=postGr3s1245xSDI dbname=rust_app_db" cargo test --test e2e`
+// with the Postgres of compose.yml
+
+use backend::app::{self, Repositories};
+use backend::config::AppConfig;
+use backend::db;
+use backend::secrets::SecretString;
+use reqwest::StatusCode;
+use rocket::Shutdown;
+use serde_json::{json, Value};
+use std::net::TcpListener;
+use std::time::Duration;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+
+const DATABASE_URL_VAR: &str = "E2E_DATABASE_URL";
+const ADMIN_TOKEN: &str = "e2e-admin-token";
+// The schema needs Postgres 14 or newer (CREATE OR REPLACE TRIGGER)
+const POSTGRES_TAG: &str = "16-alpine";
+
+// Connection string of E2E_DATABASE_URL, or of a fresh Postgres container that
+// lives as long as the returned handle
+async fn database() -> (String, Option<ContainerAsync<Postgres>>) {
+    if let Ok(connection_string) = std::env::var(DATABASE_URL_VAR) {
+        return (connection_string, None);
+    }
+    let container = Postgres::default()
+        .with_tag(POSTGRES_TAG)
+        .start()
+        .await
+        .expect("Postgres container started (is Docker running?)");
+    let host = container.get_host().await.expect("container host");
+    let port = container.get_host_port_ipv4(5432).await.expect("container port");
+    let connection_string = format!(
+        "host={} port={} user=postgres password=postgres dbname=postgres",
+        host, port
+    );
+    (connection_string, Some(container))
+}
+
+// Running server and the base URL of its v1 API
+struct TestServer {
+    base_url: String,
+    http: reqwest::Client,
+    shutdown: Shutdown,
+    _database: Option<ContainerAsync<Postgres>>,
+}
+
+impl TestServer {
+    async fn start() -> Self {
+        let (connection_string, database) = database().await;
+        // Only the HTTP API runs; no side servers or periodic tasks
+        let mut config = AppConfig::from_env().expect("valid configuration");
+        config.database.connection_string = SecretString::from(connection_string);
+        config.database.change_feed = false;
+        config.grpc.enabled = false;
+        config.scheduler.enabled = false;
+        config.admin.token = Some(ADMIN_TOKEN.into());
+        let client = db::init_database(&config.database).await.expect("database reachable");
+        let repositories = Repositories::postgres(client, Vec::new(), &config.database, None);
+        let rocket = app::build_rocket(config, repositories).await.expect("valid configuration");
+
+        // Port 0 is resolved by the OS first, so the URL is known before launch
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("free port")
+            .port();
+        let figment = rocket
+            .figment()
+            .clone()
+            .merge(("port", port))
+            .merge(("log_level", "off"));
+        let rocket = rocket
+            .configure(figment)
+            .ignite()
+            .await
+            .expect("valid rocket instance");
+        let shutdown = rocket.shutdown();
+        tokio::spawn(rocket.launch());
+
+        let server = TestServer {
+            base_url: format!("http://127.0.0.1:{}/api/v1", port),
+            http: reqwest::Client::new(),
+            shutdown,
+            _database: database,
+        };
+        server.wait_until_ready().await;
+        server
+    }
+
+    async fn wait_until_ready(&self) {
+        for _ in 0..50 {
+            if self.http.get(format!("{}/users/0", self.base_url)).send().await.is_ok() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("server did not start");
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> (StatusCode, Value) {
+        let response = request.send().await.expect("request sent");
+        let status = response.status();
+        let body = response.text().await.expect("body read");
+        (status, serde_json::from_str(&body).unwrap_or(Value::Null))
+    }
+
+    async fn get(&self, path: &str) -> (StatusCode, Value) {
+        self.send(self.http.get(format!("{}{}", self.base_url, path))).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        self.send(self.http.post(format!("{}{}", self.base_url, path)).json_body(&body)).await
+    }
+
+    // The admin variants send the admin token, which user changes require
+    async fn admin_post(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        let request = self.http.post(format!("{}{}", self.base_url, path)).json_body(&body);
+        self.send(request.bearer_auth(ADMIN_TOKEN)).await
+    }
+
+    async fn admin_put(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        let request = self.http.put(format!("{}{}", self.base_url, path)).json_body(&body);
+        self.send(request.bearer_auth(ADMIN_TOKEN)).await
+    }
+
+    async fn admin_patch(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        let request = self.http.patch(format!("{}{}", self.base_url, path)).json_body(&body);
+        self.send(request.bearer_auth(ADMIN_TOKEN)).await
+    }
+
+    async fn admin_delete(&self, path: &str) -> StatusCode {
+        let request = self.http.delete(format!("{}{}", self.base_url, path));
+        self.send(request.bearer_auth(ADMIN_TOKEN)).await.0
+    }
+}
+
+// reqwest is built without its `json` feature
+trait JsonBody {
+    fn json_body(self, body: &Value) -> Self;
+}
+
+impl JsonBody for reqwest::RequestBuilder {
+    fn json_body(self, body: &Value) -> Self {
+        self.header("Content-Type", "application/json").body(body.to_string())
+    }
+}
+
+// Email no other run used, so tests never clash with existing rows
+fn unique_email(name: &str) -> String {
+    format!("{}-{}@e2e.example.com", name, uuid::Uuid::new_v4().simple())
+}
+
+// Id of the user with `email` in a list response
+fn id_of(users: &Value, email: &str) -> i64 {
+    users
+        .as_array()
+        .and_then(|users| users.iter().find(|user| user["email"] == email))
+        .and_then(|user| user["id"].as_i64())
+        .unwrap_or_else(|| panic!("{} not listed", email))
+}
+
+#[tokio::test]
+async fn test_user_lifecycle_against_postgres() {
+    let server = TestServer::start().await;
+    let john = unique_email("john");
+    let jane = unique_email("jane");
+    let username = format!("john-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+
+    // Create, then find the user in the list
+    let (status, users) = server
+        .admin_post(
+            "/users",
+            json!({"name": "John Doe", "email": john, "password": "password123", "username": username}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let john_id = id_of(&users, &john);
+    let (status, users) = server.get("/users").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(id_of(&users, &john), john_id);
+
+    // The username is the profile URL and is no longer available
+    let (status, user) = server.get(&format!("/users/@{}", username)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["id"], john_id);
+    let (_, availability) = server.get(&format!("/usernames/available?u={}", username.to_uppercase())).await;
+    assert_eq!(availability["available"], false);
+
+    // Emails are unique regardless of case
+    let (status, error) = server
+        .admin_post("/users", json!({"name": "John Again", "email": john.to_uppercase(), "password": "password123"}))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(error["code"], "conflict");
+
+    // Update and read back
+    let (status, _) = server
+        .admin_put(
+            &format!("/users/{}", john_id),
+            json!({
+                "name": "John Smith",
+                "email": john,
+                "password": "password456",
+                "phone": "+55 (11) 91234-5678",
+                "address": {"street": "Av. Paulista, 1000", "city": "São Paulo", "postal_code": "01310-100", "country": "BR"}
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, user) = server.get(&format!("/users/{}", john_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["name"], "John Smith");
+    assert_eq!(user["phone"], "+5511912345678");
+    assert_eq!(user["address"]["city"], "São Paulo");
+    assert_eq!(user["username"], username.as_str());
+    assert!(user.get("password").is_none());
+
+    // Tags filter the list
+    let tag = format!("e2e-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+    let (status, user) = server.admin_put(&format!("/users/{}/tags", john_id), json!({"tags": [tag.to_uppercase()]})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(user["tags"], json!([tag]));
+    let (status, users) = server.get(&format!("/users?tag={}", tag)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(users.as_array().map(Vec::len), Some(1));
+    assert_eq!(id_of(&users, &john), john_id);
+
+    // Taking another user's email is a conflict
+    let (_, users) = server
+        .admin_post("/users", json!({"name": "Jane Doe", "email": jane, "password": "password123"}))
+        .await;
+    let jane_id = id_of(&users, &jane);
+    let (status, _) = server
+        .admin_put(
+            &format!("/users/{}", john_id),
+            json!({"name": "John Smith", "email": jane, "password": "password456"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Bulk updates report each id, including missing ones
+    let (status, results) = server
+        .admin_patch(
+            "/users/bulk",
+            json!({"ids": [john_id, jane_id, i32::MAX], "status": "suspended", "remove_tags": [tag]}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results[0]["user"]["status"], "suspended");
+    assert_eq!(results[0]["user"]["tags"], json!([]));
+    assert_eq!(results[1]["user"]["status"], "suspended");
+    assert_eq!(results[2]["code"], "not_found");
+
+    // Exporting a selection returns only those users
+    let (status, exported) = server
+        .post("/users/export?format=json", json!({"ids": [jane_id, i32::MAX]}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(exported.as_array().map(Vec::len), Some(1));
+    assert_eq!(exported[0]["id"], jane_id);
+
+    // Invalid input is rejected before reaching the database
+    let (status, error) = server
+        .admin_post("/users", json!({"name": "", "email": unique_email("nobody"), "password": "password123"}))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["message"], "Name cannot be empty");
+
+    // Delete
+    assert_eq!(server.admin_delete(&format!("/users/{}", john_id)).await, StatusCode::NO_CONTENT);
+    let (status, _) = server.get(&format!("/users/{}", john_id)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(server.admin_delete(&format!("/users/{}", john_id)).await, StatusCode::NOT_FOUND);
+    assert_eq!(server.admin_delete(&format!("/users/{}", jane_id)).await, StatusCode::NO_CONTENT);
+
+    server.shutdown.notify();
+}
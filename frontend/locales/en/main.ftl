@@ -0,0 +1,245 @@
+# Navigation
+nav-users = Users
+nav-dashboard = Dashboard
+nav-signup = Sign up
+language = Language
+page-not-found = Page not found
+theme-light = Light
+theme-dark = Dark
+theme-system = System
+
+# User form
+field-name = Name
+field-email = Email
+field-password = Password
+field-username = Username
+field-phone = Phone
+field-address = Address
+field-street = Street
+field-complement = Complement
+field-city = City
+field-region = State or region
+field-postal-code = Postal code
+field-country = Country
+email-invalid = Invalid email format
+email-taken = This email is already registered
+name-empty = Name cannot be empty
+email-empty = Email cannot be empty
+password-empty = Password cannot be empty
+password-too-short = Password must be at least { $min } characters
+password-help = At least { $min } characters
+phone-help = International format, e.g. +5511912345678
+phone-invalid = Phone number must be in international format, e.g. +5511912345678
+address-street-empty = Street cannot be empty
+address-city-empty = City cannot be empty
+address-postal-code-invalid = Invalid postal code
+address-country-invalid = Country must be a two-letter ISO 3166 code, e.g. BR
+address-too-long = Address fields must be at most { $max } characters
+username-help = Optional; your profile will be at /users/@username
+username-available = { $username } is available
+username-taken = Username { $username } is already taken
+username-locked = Usernames cannot be changed
+username-too-short = Username must be at least { $min } characters
+username-too-long = Username must be at most { $max } characters
+username-invalid = Use lowercase letters, digits and single dashes between them
+field-tags = Tags
+field-created-at = Created
+field-updated-at = Last updated
+field-admin-token = Admin token
+admin-token-help = Needed to manage users; forgotten when the tab is closed
+tags-help = Comma-separated, e.g. vip, pioneer
+tags-save = Save tags
+tags-saved = Tags saved
+tags-filter = Filter by tag
+tags-filter-clear = Show all
+tag-filter-by = Show only users tagged { $tag }
+tag-invalid = Tags may only use lowercase letters, digits and single dashes between them
+tag-too-long = Tags must be at most { $max } characters
+tags-too-many = A user can have at most { $max } tags
+users-filter-search = Search by name, email or username
+users-filter-status = Filter by status
+users-filter-status-all = All statuses
+views = Views
+views-none = All users
+view-name = View name
+views-save = Save view
+views-delete = Delete view
+view-name-empty = Give the view a name
+view-name-too-long = View names can be at most { $max } characters
+views-too-many = At most { $max } views can be saved
+pagination = Pages
+pagination-previous = Previous
+pagination-next = Next
+pagination-page = Page { $page } of { $pages }
+password-show = Show
+password-hide = Hide
+password-generate = Generate strong password
+user-create = Create User
+user-update = Update User
+password-strength = Password strength: { $strength ->
+    [weak] Weak
+    [fair] Fair
+   *[strong] Strong
+}
+
+# User list
+users-title = User Management
+users-fetch = Fetch User List
+users-export = Export CSV
+users-list = User List
+users-print = Print/PDF
+users-empty = No users yet
+users-empty-action = Create the first user
+users-cached = Showing cached data from your last visit, refreshing…
+users-cached-stale = Showing cached data: the server could not be reached.
+retry = Retry
+copied = Copied!
+copy-failed = Could not copy
+crash-title = Something went wrong
+crash-message = The page stopped working. The error was reported; reload to continue.
+crash-reload = Reload
+offline-banner = You are offline. { $count ->
+    [0] Showing the last saved data.
+    [one] 1 change will be sent when the connection returns.
+   *[other] { $count } changes will be sent when the connection returns.
+}
+offline-syncing = Back online, sending { $count ->
+    [one] 1 offline change
+   *[other] { $count } offline changes
+}…
+offline-unreachable = The server cannot be reached. { $count ->
+    [0] Showing the last saved data.
+    [one] 1 change will be sent when it is back.
+   *[other] { $count } changes will be sent when it is back.
+}
+connectivity-online = Connected
+connectivity-unreachable = Server unreachable
+connectivity-offline = Offline
+demo-banner = Demo mode: sample data, and your changes are saved in this browser only.
+demo-reset = Reset demo data
+column-id = ID
+column-last-seen = Last seen
+column-status = Status
+column-settings = Columns
+column-settings-visible = Visible columns
+column-settings-wide = Wide columns
+status-active = Active
+status-suspended = Suspended
+action-suspend = Suspend
+action-activate = Activate
+action-delete = Delete
+action-edit = Edit
+delete-selected = Delete selected ({ $count })
+export-selected = Export selected ({ $count })
+bulk-actions = Bulk actions
+bulk-selected = { $count ->
+    [one] 1 selected
+   *[other] { $count } selected
+}
+bulk-tag-label = Tag
+bulk-add-tag = Add tag
+bulk-remove-tag = Remove tag
+bulk-clear = Clear selection
+last-seen-never = Never
+last-seen-now = Just now
+last-seen-minutes = { $count ->
+    [one] 1 minute ago
+   *[other] { $count } minutes ago
+}
+last-seen-hours = { $count ->
+    [one] 1 hour ago
+   *[other] { $count } hours ago
+}
+last-seen-days = { $count ->
+    [one] 1 day ago
+   *[other] { $count } days ago
+}
+
+# Results
+user-created = User created successfully
+user-updated = User updated successfully
+user-deleted = User deleted successfully
+user-status-changed = { $status ->
+    [suspended] User suspended
+   *[active] User activated
+}
+bulk-deleted = { $total ->
+    [one] 1 user deleted successfully
+   *[other] { $total } users deleted successfully
+}
+bulk-delete-failed = { $failed } of { $total } users could not be deleted
+bulk-updated = { $total ->
+    [one] 1 user updated successfully
+   *[other] { $total } users updated successfully
+}
+bulk-update-failed = { $failed } of { $total } users could not be updated: { $reason }
+
+# User detail
+user-detail-back = ← Back to users
+user-detail-loading = Loading user...
+action-cancel = Cancel
+activity-title = Recent activity
+activity-empty = No activity recorded yet.
+activity-created = Account created
+activity-updated = Profile updated
+activity-deleted = Account deleted
+activity-anonymized = Personal data anonymized
+activity-password-changed = Password changed
+
+# Signup
+signup-title = Create your account
+signup-submit = Sign up
+signup-success = Account created. Check your email for a link to confirm your address.
+signup-step-account = Account details
+signup-step-password = Password
+signup-step-confirm = Confirmation
+signup-progress = Step { $step } of { $total }
+signup-back = Back
+signup-next = Next
+signup-review = Check your details, then create the account.
+signup-password-hidden = { $length } characters, hidden
+verify-email-title = Email confirmation
+verify-email-pending = Confirming your email address...
+verify-email-success = Your email address is confirmed.
+
+# Notifications
+desktop-notifications-enable = Enable desktop notifications
+desktop-notification-new-user = New user registered
+
+# Dashboard
+dashboard-title = Dashboard
+stats-total = Total users
+stats-verified = Verified
+stats-unverified = Unverified
+chart-signups = Signups per day (last 30 days)
+chart-verification = Email verification
+stats-loading = Loading statistics...
+
+# Accessible names
+a11y-select = Select
+a11y-actions = Actions
+a11y-select-user = Select { $name }
+a11y-suspend-user = Suspend { $name }
+a11y-activate-user = Activate { $name }
+a11y-delete-user = Delete { $name }
+a11y-edit-user = Edit { $name }
+a11y-copy-email = Copy the email of { $name }
+a11y-mail-user = Send an email to { $name }
+a11y-main-nav = Main navigation
+a11y-password-strength = Password strength
+dismiss = Dismiss
+maintenance-banner = The service is read-only for maintenance; changes cannot be saved right now.
+
+# Debug panel
+debug-panel = Debug ({ $count } calls)
+debug-clear-calls = Clear calls
+debug-clear-caches = Clear caches
+debug-use-mock = Use mock client
+debug-use-backend = Use backend
+debug-no-calls = No API calls recorded yet.
+debug-method = Method
+debug-url = URL
+debug-status = Status
+debug-latency-header = Latency
+debug-latency = { $ms } ms
+debug-payload = Payload
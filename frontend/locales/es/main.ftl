@@ -0,0 +1,245 @@
+# Navigation
+nav-users = Usuarios
+nav-dashboard = Panel
+nav-signup = Registrarse
+language = Idioma
+page-not-found = Página no encontrada
+theme-light = Claro
+theme-dark = Oscuro
+theme-system = Sistema
+
+# User form
+field-name = Nombre
+field-email = Correo electrónico
+field-password = Contraseña
+field-username = Nombre de usuario
+field-phone = Teléfono
+field-address = Dirección
+field-street = Calle
+field-complement = Complemento
+field-city = Ciudad
+field-region = Estado o región
+field-postal-code = Código postal
+field-country = País
+email-invalid = Formato de correo electrónico inválido
+email-taken = Este correo electrónico ya está registrado
+name-empty = El nombre no puede estar vacío
+email-empty = El correo electrónico no puede estar vacío
+password-empty = La contraseña no puede estar vacía
+password-too-short = La contraseña debe tener al menos { $min } caracteres
+password-help = Al menos { $min } caracteres
+phone-help = Formato internacional, p. ej. +5511912345678
+phone-invalid = El teléfono debe estar en formato internacional, p. ej. +5511912345678
+address-street-empty = La calle no puede estar vacía
+address-city-empty = La ciudad no puede estar vacía
+address-postal-code-invalid = Código postal no válido
+address-country-invalid = El país debe ser un código ISO 3166 de dos letras, p. ej. BR
+address-too-long = Los campos de la dirección deben tener como máximo { $max } caracteres
+username-help = Opcional; tu perfil estará en /users/@nombre
+username-available = { $username } está disponible
+username-taken = El nombre de usuario { $username } ya está en uso
+username-locked = Los nombres de usuario no se pueden cambiar
+username-too-short = El nombre de usuario debe tener al menos { $min } caracteres
+username-too-long = El nombre de usuario debe tener como máximo { $max } caracteres
+username-invalid = Usa letras minúsculas, dígitos y guiones simples entre ellos
+field-tags = Etiquetas
+field-created-at = Creado
+field-updated-at = Última actualización
+field-admin-token = Token de administrador
+admin-token-help = Necesario para administrar usuarios; se olvida al cerrar la pestaña
+tags-help = Separadas por comas, p. ej. vip, pioneer
+tags-save = Guardar etiquetas
+tags-saved = Etiquetas guardadas
+tags-filter = Filtrar por etiqueta
+tags-filter-clear = Mostrar todos
+tag-filter-by = Mostrar solo usuarios con la etiqueta { $tag }
+tag-invalid = Las etiquetas solo pueden usar letras minúsculas, dígitos y guiones simples entre ellos
+tag-too-long = Las etiquetas deben tener como máximo { $max } caracteres
+tags-too-many = Un usuario puede tener como máximo { $max } etiquetas
+users-filter-search = Buscar por nombre, correo o nombre de usuario
+users-filter-status = Filtrar por estado
+users-filter-status-all = Todos los estados
+views = Vistas
+views-none = Todos los usuarios
+view-name = Nombre de la vista
+views-save = Guardar vista
+views-delete = Eliminar vista
+view-name-empty = Ponle un nombre a la vista
+view-name-too-long = Los nombres de vista pueden tener como máximo { $max } caracteres
+views-too-many = Se pueden guardar como máximo { $max } vistas
+pagination = Páginas
+pagination-previous = Anterior
+pagination-next = Siguiente
+pagination-page = Página { $page } de { $pages }
+password-show = Mostrar
+password-hide = Ocultar
+password-generate = Generar contraseña segura
+user-create = Crear usuario
+user-update = Actualizar usuario
+password-strength = Seguridad de la contraseña: { $strength ->
+    [weak] Débil
+    [fair] Aceptable
+   *[strong] Fuerte
+}
+
+# User list
+users-title = Gestión de usuarios
+users-fetch = Cargar lista de usuarios
+users-export = Exportar CSV
+users-list = Lista de usuarios
+users-print = Imprimir/PDF
+users-empty = Todavía no hay usuarios
+users-empty-action = Crear el primer usuario
+users-cached = Mostrando datos en caché de tu última visita, actualizando…
+users-cached-stale = Mostrando datos en caché: no se pudo contactar con el servidor.
+retry = Reintentar
+copied = ¡Copiado!
+copy-failed = No se pudo copiar
+crash-title = Algo salió mal
+crash-message = La página dejó de funcionar. El error fue informado; recarga para continuar.
+crash-reload = Recargar
+offline-banner = Estás sin conexión. { $count ->
+    [0] Mostrando los últimos datos guardados.
+    [one] 1 cambio se enviará cuando vuelva la conexión.
+   *[other] { $count } cambios se enviarán cuando vuelva la conexión.
+}
+offline-syncing = De nuevo en línea, enviando { $count ->
+    [one] 1 cambio hecho sin conexión
+   *[other] { $count } cambios hechos sin conexión
+}…
+offline-unreachable = No se puede acceder al servidor. { $count ->
+    [0] Mostrando los últimos datos guardados.
+    [one] 1 cambio se enviará cuando vuelva.
+   *[other] { $count } cambios se enviarán cuando vuelva.
+}
+connectivity-online = Conectado
+connectivity-unreachable = Servidor inaccesible
+connectivity-offline = Sin conexión
+demo-banner = Modo demostración: datos de ejemplo, y tus cambios se guardan solo en este navegador.
+demo-reset = Restablecer datos de demostración
+column-id = ID
+column-last-seen = Última conexión
+column-status = Estado
+column-settings = Columnas
+column-settings-visible = Columnas visibles
+column-settings-wide = Columnas anchas
+status-active = Activo
+status-suspended = Suspendido
+action-suspend = Suspender
+action-activate = Activar
+action-delete = Eliminar
+action-edit = Editar
+delete-selected = Eliminar seleccionados ({ $count })
+export-selected = Exportar seleccionados ({ $count })
+bulk-actions = Acciones masivas
+bulk-selected = { $count ->
+    [one] 1 seleccionado
+   *[other] { $count } seleccionados
+}
+bulk-tag-label = Etiqueta
+bulk-add-tag = Añadir etiqueta
+bulk-remove-tag = Quitar etiqueta
+bulk-clear = Limpiar selección
+last-seen-never = Nunca
+last-seen-now = Ahora mismo
+last-seen-minutes = { $count ->
+    [one] hace 1 minuto
+   *[other] hace { $count } minutos
+}
+last-seen-hours = { $count ->
+    [one] hace 1 hora
+   *[other] hace { $count } horas
+}
+last-seen-days = { $count ->
+    [one] hace 1 día
+   *[other] hace { $count } días
+}
+
+# Results
+user-created = Usuario creado correctamente
+user-updated = Usuario actualizado correctamente
+user-deleted = Usuario eliminado correctamente
+user-status-changed = { $status ->
+    [suspended] Usuario suspendido
+   *[active] Usuario activado
+}
+bulk-deleted = { $total ->
+    [one] 1 usuario eliminado correctamente
+   *[other] { $total } usuarios eliminados correctamente
+}
+bulk-delete-failed = No se pudieron eliminar { $failed } de { $total } usuarios
+bulk-updated = { $total ->
+    [one] 1 usuario actualizado correctamente
+   *[other] { $total } usuarios actualizados correctamente
+}
+bulk-update-failed = No se pudieron actualizar { $failed } de { $total } usuarios: { $reason }
+
+# User detail
+user-detail-back = ← Volver a usuarios
+user-detail-loading = Cargando usuario...
+action-cancel = Cancelar
+activity-title = Actividad reciente
+activity-empty = Aún no hay actividad registrada.
+activity-created = Cuenta creada
+activity-updated = Perfil actualizado
+activity-deleted = Cuenta eliminada
+activity-anonymized = Datos personales anonimizados
+activity-password-changed = Contraseña cambiada
+
+# Signup
+signup-title = Crea tu cuenta
+signup-submit = Registrarse
+signup-success = Cuenta creada. Revisa tu correo para encontrar el enlace de confirmación de tu dirección.
+signup-step-account = Datos de la cuenta
+signup-step-password = Contraseña
+signup-step-confirm = Confirmación
+signup-progress = Paso { $step } de { $total }
+signup-back = Atrás
+signup-next = Siguiente
+signup-review = Revisa tus datos y crea la cuenta.
+signup-password-hidden = { $length } caracteres, oculta
+verify-email-title = Confirmación de correo
+verify-email-pending = Confirmando tu dirección de correo...
+verify-email-success = Tu dirección de correo está confirmada.
+
+# Notifications
+desktop-notifications-enable = Activar notificaciones de escritorio
+desktop-notification-new-user = Nuevo usuario registrado
+
+# Dashboard
+dashboard-title = Panel
+stats-total = Total de usuarios
+stats-verified = Verificados
+stats-unverified = Sin verificar
+chart-signups = Registros por día (últimos 30 días)
+chart-verification = Verificación de correo
+stats-loading = Cargando estadísticas...
+
+# Accessible names
+a11y-select = Seleccionar
+a11y-actions = Acciones
+a11y-select-user = Seleccionar a { $name }
+a11y-suspend-user = Suspender a { $name }
+a11y-activate-user = Activar a { $name }
+a11y-delete-user = Eliminar a { $name }
+a11y-edit-user = Editar a { $name }
+a11y-copy-email = Copiar el correo de { $name }
+a11y-mail-user = Enviar un correo a { $name }
+a11y-main-nav = Navegación principal
+a11y-password-strength = Seguridad de la contraseña
+dismiss = Cerrar
+maintenance-banner = El servicio está en modo de solo lectura por mantenimiento; no se pueden guardar cambios ahora.
+
+# Panel de depuración
+debug-panel = Depuración ({ $count } llamadas)
+debug-clear-calls = Borrar llamadas
+debug-clear-caches = Vaciar cachés
+debug-use-mock = Usar cliente simulado
+debug-use-backend = Usar servidor
+debug-no-calls = Todavía no se registraron llamadas a la API.
+debug-method = Método
+debug-url = URL
+debug-status = Estado
+debug-latency-header = Latencia
+debug-latency = { $ms } ms
+debug-payload = Contenido
@@ -0,0 +1,245 @@
+# Navigation
+nav-users = Usuários
+nav-dashboard = Painel
+nav-signup = Cadastre-se
+language = Idioma
+page-not-found = Página não encontrada
+theme-light = Claro
+theme-dark = Escuro
+theme-system = Sistema
+
+# User form
+field-name = Nome
+field-email = E-mail
+field-password = Senha
+field-username = Nome de usuário
+field-phone = Telefone
+field-address = Endereço
+field-street = Logradouro
+field-complement = Complemento
+field-city = Cidade
+field-region = Estado ou região
+field-postal-code = CEP
+field-country = País
+email-invalid = Formato de e-mail inválido
+email-taken = Este e-mail já está cadastrado
+name-empty = O nome não pode estar vazio
+email-empty = O e-mail não pode estar vazio
+password-empty = A senha não pode estar vazia
+password-too-short = A senha deve ter pelo menos { $min } caracteres
+password-help = Pelo menos { $min } caracteres
+phone-help = Formato internacional, ex.: +5511912345678
+phone-invalid = O telefone deve estar no formato internacional, ex.: +5511912345678
+address-street-empty = O logradouro não pode ficar vazio
+address-city-empty = A cidade não pode ficar vazia
+address-postal-code-invalid = CEP inválido
+address-country-invalid = O país deve ser um código ISO 3166 de duas letras, ex.: BR
+address-too-long = Os campos do endereço devem ter no máximo { $max } caracteres
+username-help = Opcional; seu perfil ficará em /users/@nome
+username-available = { $username } está disponível
+username-taken = O nome de usuário { $username } já está em uso
+username-locked = Nomes de usuário não podem ser alterados
+username-too-short = O nome de usuário deve ter pelo menos { $min } caracteres
+username-too-long = O nome de usuário deve ter no máximo { $max } caracteres
+username-invalid = Use letras minúsculas, dígitos e hífens simples entre eles
+field-tags = Tags
+field-created-at = Criado em
+field-updated-at = Última atualização
+field-admin-token = Token de administrador
+admin-token-help = Necessário para gerenciar usuários; esquecido ao fechar a aba
+tags-help = Separadas por vírgula, ex.: vip, pioneer
+tags-save = Salvar tags
+tags-saved = Tags salvas
+tags-filter = Filtrar por tag
+tags-filter-clear = Mostrar todos
+tag-filter-by = Mostrar apenas usuários com a tag { $tag }
+tag-invalid = Tags só podem usar letras minúsculas, dígitos e hífens simples entre eles
+tag-too-long = Tags devem ter no máximo { $max } caracteres
+tags-too-many = Um usuário pode ter no máximo { $max } tags
+users-filter-search = Buscar por nome, e-mail ou nome de usuário
+users-filter-status = Filtrar por status
+users-filter-status-all = Todos os status
+views = Visualizações
+views-none = Todos os usuários
+view-name = Nome da visualização
+views-save = Salvar visualização
+views-delete = Excluir visualização
+view-name-empty = Dê um nome à visualização
+view-name-too-long = Nomes de visualização podem ter no máximo { $max } caracteres
+views-too-many = É possível salvar no máximo { $max } visualizações
+pagination = Páginas
+pagination-previous = Anterior
+pagination-next = Próxima
+pagination-page = Página { $page } de { $pages }
+password-show = Mostrar
+password-hide = Ocultar
+password-generate = Gerar senha forte
+user-create = Criar usuário
+user-update = Atualizar usuário
+password-strength = Força da senha: { $strength ->
+    [weak] Fraca
+    [fair] Razoável
+   *[strong] Forte
+}
+
+# User list
+users-title = Gerenciamento de usuários
+users-fetch = Carregar lista de usuários
+users-export = Exportar CSV
+users-list = Lista de usuários
+users-print = Imprimir/PDF
+users-empty = Nenhum usuário ainda
+users-empty-action = Criar o primeiro usuário
+users-cached = Mostrando dados em cache da sua última visita, atualizando…
+users-cached-stale = Mostrando dados em cache: não foi possível acessar o servidor.
+retry = Tentar novamente
+copied = Copiado!
+copy-failed = Não foi possível copiar
+crash-title = Algo deu errado
+crash-message = A página parou de funcionar. O erro foi informado; recarregue para continuar.
+crash-reload = Recarregar
+offline-banner = Você está offline. { $count ->
+    [0] Mostrando os últimos dados salvos.
+    [one] 1 alteração será enviada quando a conexão voltar.
+   *[other] { $count } alterações serão enviadas quando a conexão voltar.
+}
+offline-syncing = De volta online, enviando { $count ->
+    [one] 1 alteração feita offline
+   *[other] { $count } alterações feitas offline
+}…
+offline-unreachable = Não foi possível alcançar o servidor. { $count ->
+    [0] Mostrando os últimos dados salvos.
+    [one] 1 alteração será enviada quando ele voltar.
+   *[other] { $count } alterações serão enviadas quando ele voltar.
+}
+connectivity-online = Conectado
+connectivity-unreachable = Servidor inacessível
+connectivity-offline = Offline
+demo-banner = Modo de demonstração: dados de exemplo, e suas alterações ficam salvas apenas neste navegador.
+demo-reset = Restaurar dados de demonstração
+column-id = ID
+column-last-seen = Último acesso
+column-status = Status
+column-settings = Colunas
+column-settings-visible = Colunas visíveis
+column-settings-wide = Colunas largas
+status-active = Ativo
+status-suspended = Suspenso
+action-suspend = Suspender
+action-activate = Ativar
+action-delete = Excluir
+action-edit = Editar
+delete-selected = Excluir selecionados ({ $count })
+export-selected = Exportar selecionados ({ $count })
+bulk-actions = Ações em massa
+bulk-selected = { $count ->
+    [one] 1 selecionado
+   *[other] { $count } selecionados
+}
+bulk-tag-label = Tag
+bulk-add-tag = Adicionar tag
+bulk-remove-tag = Remover tag
+bulk-clear = Limpar seleção
+last-seen-never = Nunca
+last-seen-now = Agora mesmo
+last-seen-minutes = { $count ->
+    [one] há 1 minuto
+   *[other] há { $count } minutos
+}
+last-seen-hours = { $count ->
+    [one] há 1 hora
+   *[other] há { $count } horas
+}
+last-seen-days = { $count ->
+    [one] há 1 dia
+   *[other] há { $count } dias
+}
+
+# Results
+user-created = Usuário criado com sucesso
+user-updated = Usuário atualizado com sucesso
+user-deleted = Usuário excluído com sucesso
+user-status-changed = { $status ->
+    [suspended] Usuário suspenso
+   *[active] Usuário ativado
+}
+bulk-deleted = { $total ->
+    [one] 1 usuário excluído com sucesso
+   *[other] { $total } usuários excluídos com sucesso
+}
+bulk-delete-failed = { $failed } de { $total } usuários não puderam ser excluídos
+bulk-updated = { $total ->
+    [one] 1 usuário atualizado com sucesso
+   *[other] { $total } usuários atualizados com sucesso
+}
+bulk-update-failed = { $failed } de { $total } usuários não puderam ser atualizados: { $reason }
+
+# User detail
+user-detail-back = ← Voltar para usuários
+user-detail-loading = Carregando usuário...
+action-cancel = Cancelar
+activity-title = Atividade recente
+activity-empty = Nenhuma atividade registrada ainda.
+activity-created = Conta criada
+activity-updated = Perfil atualizado
+activity-deleted = Conta excluída
+activity-anonymized = Dados pessoais anonimizados
+activity-password-changed = Senha alterada
+
+# Signup
+signup-title = Crie sua conta
+signup-submit = Cadastrar
+signup-success = Conta criada. Verifique seu email para encontrar o link de confirmação do endereço.
+signup-step-account = Dados da conta
+signup-step-password = Senha
+signup-step-confirm = Confirmação
+signup-progress = Etapa { $step } de { $total }
+signup-back = Voltar
+signup-next = Avançar
+signup-review = Confira seus dados e crie a conta.
+signup-password-hidden = { $length } caracteres, oculta
+verify-email-title = Confirmação de email
+verify-email-pending = Confirmando seu endereço de email...
+verify-email-success = Seu endereço de email foi confirmado.
+
+# Notifications
+desktop-notifications-enable = Ativar notificações na área de trabalho
+desktop-notification-new-user = Novo usuário cadastrado
+
+# Dashboard
+dashboard-title = Painel
+stats-total = Total de usuários
+stats-verified = Verificados
+stats-unverified = Não verificados
+chart-signups = Cadastros por dia (últimos 30 dias)
+chart-verification = Verificação de e-mail
+stats-loading = Carregando estatísticas...
+
+# Accessible names
+a11y-select = Selecionar
+a11y-actions = Ações
+a11y-select-user = Selecionar { $name }
+a11y-suspend-user = Suspender { $name }
+a11y-activate-user = Ativar { $name }
+a11y-delete-user = Excluir { $name }
+a11y-edit-user = Editar { $name }
+a11y-copy-email = Copiar o e-mail de { $name }
+a11y-mail-user = Enviar um e-mail para { $name }
+a11y-main-nav = Navegação principal
+a11y-password-strength = Força da senha
+dismiss = Fechar
+maintenance-banner = O serviço está somente leitura para manutenção; não é possível salvar alterações agora.
+
+# Painel de depuração
+debug-panel = Depuração ({ $count } chamadas)
+debug-clear-calls = Limpar chamadas
+debug-clear-caches = Limpar caches
+debug-use-mock = Usar cliente simulado
+debug-use-backend = Usar servidor
+debug-no-calls = Nenhuma chamada à API registrada ainda.
+debug-method = Método
+debug-url = URL
+debug-status = Status
+debug-latency-header = Latência
+debug-latency = { $ms } ms
+debug-payload = Conteúdo
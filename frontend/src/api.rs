@@ -1,18 +1,246 @@
 // API Client Module - Single Responsibility Principle
-// Handles all HTTP communication with the backend
+// Handles all HTTP communication with the backend; user changes made while
+// offline are queued by the offline module instead of failing
 
-use gloo::net::http::Request;
+use crate::debug::{self, ApiCall};
+use crate::local_cache;
+use crate::offline::{self, MutationMethod};
+use crate::pipeline::{ApiRequest, LocalBoxFuture, Next, Pipeline};
+use crate::preferences::UserPreferences;
+use crate::reporting;
+use crate::worker::{self, UsersResponse};
+use gloo::net::http::{Method, Request, Response};
+use gloo::storage::{SessionStorage, Storage};
 use serde::{Deserialize, Serialize};
+use shared::routes::{self, users};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use wasm_bindgen_futures::spawn_local;
+use web_sys::js_sys;
 use yew::Callback;
 
-const API_BASE_URL: &str = "http://127.0.0.1:8000/api";
+pub(crate) const SERVER_URL: &str = "http://127.0.0.1:8000";
+
+// Full URL of `path` in the v1 API, e.g. `api_url(routes::FLAGS)`
+pub(crate) fn api_url(path: &str) -> String {
+    format!("{}{}{}", SERVER_URL, routes::API_V1_PREFIX, path)
+}
+
+const ADMIN_TOKEN_STORAGE_KEY: &str = "admin-token";
+
+// Bearer token of the admin-only endpoints, e.g. creating users. Only kept in
+// sessionStorage, so it is forgotten once the tab is closed
+pub fn admin_token() -> Option<String> {
+    SessionStorage::get::<String>(ADMIN_TOKEN_STORAGE_KEY)
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+pub fn store_admin_token(token: &str) {
+    match token.trim() {
+        "" => SessionStorage::delete(ADMIN_TOKEN_STORAGE_KEY),
+        token => {
+            let _ = SessionStorage::set(ADMIN_TOKEN_STORAGE_KEY, token);
+        }
+    }
+}
+
+// `Authorization` header value presenting the admin token
+pub fn admin_authorization(token: &str) -> String {
+    format!("Bearer {}", token)
+}
+
+// Present the admin token of the session, when one was entered
+fn as_admin(request: ApiRequest) -> ApiRequest {
+    match admin_token() {
+        Some(token) => request.header("Authorization", &admin_authorization(&token)),
+        None => request,
+    }
+}
+
+// What the client's pipeline answers a request with
+pub type HttpResult = Result<Response, gloo::net::Error>;
+
+// End of the pipeline: hands the request to the browser
+fn transport(request: ApiRequest) -> LocalBoxFuture<HttpResult> {
+    Box::pin(async move {
+        let mut builder = Request::new(&request.url).method(request.method);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        builder.send().await
+    })
+}
+
+// Report network failures and server errors of a request to the backend,
+// then hand its result on unchanged
+fn reporting_layer(request: ApiRequest, next: Next<HttpResult>) -> LocalBoxFuture<HttpResult> {
+    Box::pin(async move {
+        let url = request.url.clone();
+        let result = next(request).await;
+        match &result {
+            Ok(resp) if reporting::is_reportable_status(resp.status()) => {
+                reporting::report_failed_request(&url, Some(resp.status()), &resp.status_text())
+            }
+            Err(e) => reporting::report_failed_request(&url, None, &e.to_string()),
+            Ok(_) => {}
+        }
+        result
+    })
+}
+
+// Record the call for the debug panel while it is open
+fn recording_layer(request: ApiRequest, next: Next<HttpResult>) -> LocalBoxFuture<HttpResult> {
+    Box::pin(async move {
+        if !debug::is_recording() {
+            return next(request).await;
+        }
+        let started = js_sys::Date::now();
+        let method = request.method.to_string();
+        let url = request.url.clone();
+        let body = request.body.as_deref().map(debug::preview);
+        let result = next(request).await;
+        let response = match &result {
+            Ok(resp) => response_preview(resp).await,
+            Err(_) => None,
+        };
+        debug::record(ApiCall {
+            method,
+            url,
+            status: result.as_ref().ok().map(Response::status),
+            latency_ms: (js_sys::Date::now() - started) as u32,
+            request: body,
+            response,
+        });
+        result
+    })
+}
+
+// Start of the response body, read from a copy so the caller can still read it
+async fn response_preview(resp: &Response) -> Option<String> {
+    let copy = web_sys::Response::clone(resp.as_raw()).ok()?;
+    let text = Response::from_raw(copy).text().await.ok()?;
+    (!text.is_empty()).then(|| debug::preview(&text))
+}
+
+// Layers every client starts with; recording comes first so the latency
+// shown in the debug panel covers the whole chain
+pub fn default_pipeline() -> Pipeline<HttpResult> {
+    Pipeline::new(transport).layer(recording_layer).layer(reporting_layer)
+}
+
+// Account status; suspended users are locked out
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    Suspended,
+}
+
+impl UserStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UserStatus::Active => "Active",
+            UserStatus::Suspended => "Suspended",
+        }
+    }
+
+    // Path segment of the endpoint switching a user to this status
+    pub fn action(&self) -> &'static str {
+        match self {
+            UserStatus::Active => "activate",
+            UserStatus::Suspended => "suspend",
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            UserStatus::Active => UserStatus::Suspended,
+            UserStatus::Suspended => UserStatus::Active,
+        }
+    }
+}
+
+// Postal address of a user; `country` is an ISO 3166-1 alpha-2 code
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Address {
+    pub street: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complement: Option<String>,
+    pub city: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub postal_code: String,
+    pub country: String,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct User {
     pub id: i32,
     pub name: String,
     pub email: String,
+    // Handle of the profile URL, `/users/@username`; None until the user picks one
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub status: UserStatus,
+    // Labels shown as chips in the list, normalized and sorted by the backend
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // RFC 3339 UTC time of the last login, None if the user never logged in
+    #[serde(default)]
+    pub last_login_at: Option<String>,
+    // RFC 3339 UTC times of the signup and of the last change
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    // E.164 phone number, e.g. `+5511912345678`
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub address: Option<Address>,
+}
+
+// Entry of a user's audit log, e.g. `user.updated`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ActivityEntry {
+    pub event: String,
+    // RFC 3339 UTC time of the event
+    pub occurred_at: String,
+}
+
+// Answer of the email availability check
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EmailExists {
+    pub exists: bool,
+}
+
+// Answer of the username availability check
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UsernameAvailability {
+    pub username: String,
+    pub available: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DailySignups {
+    pub day: String,
+    pub count: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UserStats {
+    pub total: i64,
+    pub verified: i64,
+    pub unverified: i64,
+    pub signups_per_day: Vec<DailySignups>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -20,6 +248,56 @@ pub struct CreateUserRequest {
     pub name: String,
     pub email: String,
     pub password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    // Token of the solved CAPTCHA widget, sent on signup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captcha_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+}
+
+// CAPTCHA service protecting the signup form
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaProvider {
+    #[default]
+    None,
+    HCaptcha,
+    ReCaptcha,
+    Turnstile,
+}
+
+impl CaptchaProvider {
+    // Script of the provider's widget; none while CAPTCHA is off
+    pub fn script_url(&self) -> Option<&'static str> {
+        match self {
+            CaptchaProvider::None => None,
+            CaptchaProvider::HCaptcha => Some("https://js.hcaptcha.com/1/api.js"),
+            CaptchaProvider::ReCaptcha => Some("https://www.google.com/recaptcha/api.js"),
+            CaptchaProvider::Turnstile => Some("https://challenges.cloudflare.com/turnstile/v0/api.js"),
+        }
+    }
+
+    // Global object the script defines; all three offer the same `render` function
+    pub fn js_global(&self) -> Option<&'static str> {
+        match self {
+            CaptchaProvider::None => None,
+            CaptchaProvider::HCaptcha => Some("hcaptcha"),
+            CaptchaProvider::ReCaptcha => Some("grecaptcha"),
+            CaptchaProvider::Turnstile => Some("turnstile"),
+        }
+    }
+}
+
+// Provider and public site key the CAPTCHA widget is rendered with
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CaptchaSettings {
+    pub provider: CaptchaProvider,
+    #[serde(default)]
+    pub site_key: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -28,35 +306,276 @@ pub struct UpdateUserRequest {
     pub name: String,
     pub email: String,
     pub password: String,
+    // Only taken by users who have none yet; usernames never change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
 }
 
 // Result type for API operations
 pub type ApiResult<T> = Result<T, String>;
 
+// JSON body the backend returns for every failed request
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ErrorResponse {
+    pub code: String,
+    pub message: String,
+    // Rejected field of the request, e.g. `email`
+    #[serde(default)]
+    pub field: Option<String>,
+}
+
+// Failure of a form submission; `field` names the input it is about, if any
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiError {
+    pub message: String,
+    pub field: Option<String>,
+}
+
+impl ApiError {
+    // An error not tied to any field
+    pub fn general(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            field: None,
+        }
+    }
+}
+
+impl From<ErrorResponse> for ApiError {
+    fn from(response: ErrorResponse) -> Self {
+        Self {
+            message: response.message,
+            field: response.field,
+        }
+    }
+}
+
+// Result of submitting the user form
+pub type SubmitResult = Result<(), ApiError>;
+
+// One change applied to several users at once; omitted parts are left alone
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct BulkUpdateRequest {
+    pub ids: Vec<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<UserStatus>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub add_tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remove_tags: Vec<String>,
+}
+
+// Outcome of a bulk update for one user: the updated user, or why it was skipped
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BulkUpdateResult {
+    pub id: i32,
+    #[serde(default)]
+    pub user: Option<User>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+// Error of a rejected submission, read from the response body when it has the backend's format
+async fn submit_error(response: gloo::net::http::Response, fallback: &str) -> ApiError {
+    match response.json::<ErrorResponse>().await {
+        Ok(body) => body.into(),
+        Err(_) => ApiError::general(fallback),
+    }
+}
+
+// Wire format used for list responses; MessagePack is more compact for large lists
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl WireFormat {
+    pub fn accept_header(&self) -> &'static str {
+        match self {
+            WireFormat::Json => "application/json",
+            WireFormat::MessagePack => "application/msgpack",
+        }
+    }
+
+    pub fn decode_users(&self, body: &[u8]) -> ApiResult<Vec<User>> {
+        match self {
+            WireFormat::Json => serde_json::from_slice(body).map_err(|e| e.to_string()),
+            WireFormat::MessagePack => rmp_serde::from_slice(body).map_err(|e| e.to_string()),
+        }
+    }
+}
+
 // Trait for API client (Dependency Inversion Principle)
 pub trait UserApiClient {
     fn fetch_users(&self, callback: Callback<ApiResult<Vec<User>>>);
-    fn create_user(&self, request: CreateUserRequest, callback: Callback<ApiResult<()>>);
-    fn update_user(&self, request: UpdateUserRequest, callback: Callback<ApiResult<()>>);
+    fn fetch_user(&self, id: i32, callback: Callback<ApiResult<User>>);
+    // The user of a profile URL, `/users/@username`
+    fn fetch_user_by_username(&self, username: String, callback: Callback<ApiResult<User>>);
+    fn fetch_activity(&self, id: i32, callback: Callback<ApiResult<Vec<ActivityEntry>>>);
+    fn fetch_preferences(&self, user_id: i32, callback: Callback<ApiResult<UserPreferences>>);
+    fn save_preferences(&self, user_id: i32, preferences: UserPreferences, callback: Callback<SubmitResult>);
+    fn create_user(&self, request: CreateUserRequest, callback: Callback<SubmitResult>);
+    fn sign_up(&self, request: CreateUserRequest, callback: Callback<SubmitResult>);
+    // Whether a user already has this email
+    fn email_exists(&self, email: String, callback: Callback<ApiResult<bool>>);
+    // Whether no user has this username yet
+    fn username_available(&self, username: String, callback: Callback<ApiResult<bool>>);
+    fn verify_email(&self, token: String, callback: Callback<ApiResult<()>>);
+    fn fetch_captcha_settings(&self, callback: Callback<ApiResult<CaptchaSettings>>);
+    fn update_user(&self, request: UpdateUserRequest, callback: Callback<SubmitResult>);
     fn delete_user(&self, id: i32, callback: Callback<ApiResult<()>>);
+    fn set_user_status(&self, id: i32, status: UserStatus, callback: Callback<ApiResult<()>>);
+    // Replace every tag of a user
+    fn set_user_tags(&self, id: i32, tags: Vec<String>, callback: Callback<SubmitResult>);
+    // Apply one change to many users, with a result for each of them
+    fn bulk_update_users(
+        &self,
+        request: BulkUpdateRequest,
+        callback: Callback<Result<Vec<BulkUpdateResult>, ApiError>>,
+    );
+    // CSV export of the users with `ids`, or of every user when it is empty
+    fn export_users(&self, ids: Vec<i32>, callback: Callback<ApiResult<String>>);
+    fn fetch_stats(&self, callback: Callback<ApiResult<UserStats>>);
+}
+
+// Last user list received from the server together with its ETag; also
+// persisted by the local cache between visits
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CachedUserList {
+    pub etag: String,
+    pub users: Vec<User>,
+}
+
+// Last version of one user received from the server with its Last-Modified
+// date, which the next fetch revalidates with If-Modified-Since
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedUser {
+    pub last_modified: String,
+    pub user: User,
 }
 
 // Concrete implementation of API client
+// Clones share the same list and user caches, so revalidation works across
+// callbacks. Requests pass the layers of its pipeline; the user list is
+// fetched by the API worker instead
 #[derive(Clone)]
 pub struct HttpUserApiClient {
     base_url: String,
+    format: WireFormat,
+    list_cache: Rc<RefCell<Option<CachedUserList>>>,
+    user_cache: Rc<RefCell<HashMap<i32, CachedUser>>>,
+    pipeline: Pipeline<HttpResult>,
 }
 
 impl HttpUserApiClient {
     pub fn new() -> Self {
         Self {
-            base_url: API_BASE_URL.to_string(),
+            base_url: api_url(""),
+            format: WireFormat::default(),
+            list_cache: Rc::default(),
+            user_cache: Rc::default(),
+            pipeline: default_pipeline(),
         }
     }
 
     #[cfg(test)]
     pub fn with_base_url(base_url: String) -> Self {
-        Self { base_url }
+        Self {
+            base_url,
+            format: WireFormat::default(),
+            list_cache: Rc::default(),
+            user_cache: Rc::default(),
+            pipeline: default_pipeline(),
+        }
+    }
+
+    // Add an interceptor inside the default ones
+    pub fn with_layer(
+        mut self,
+        layer: impl Fn(ApiRequest, Next<HttpResult>) -> LocalBoxFuture<HttpResult> + 'static,
+    ) -> Self {
+        self.pipeline = self.pipeline.layer(layer);
+        self
+    }
+
+    pub fn with_format(mut self, format: WireFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn format(&self) -> WireFormat {
+        self.format
+    }
+
+    // Full URL of an API path from `shared::routes`
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub fn cached_users(&self) -> Option<CachedUserList> {
+        self.list_cache.borrow().clone()
+    }
+
+    pub fn store_cached_users(&self, etag: String, users: Vec<User>) {
+        *self.list_cache.borrow_mut() = Some(CachedUserList { etag, users });
+    }
+
+    pub fn cached_user(&self, id: i32) -> Option<CachedUser> {
+        self.user_cache.borrow().get(&id).cloned()
+    }
+
+    // Keep `user` for revalidation; without a Last-Modified date there is
+    // nothing to revalidate with, so any older copy is dropped instead
+    pub fn store_cached_user(&self, id: i32, last_modified: Option<String>, user: User) {
+        let mut cache = self.user_cache.borrow_mut();
+        match last_modified {
+            Some(last_modified) => cache.insert(id, CachedUser { last_modified, user }),
+            None => cache.remove(&id),
+        };
+    }
+
+    // Drop the cached copy of a user this client is changing. Dates have whole
+    // seconds, so a change within the second of the last fetch could otherwise
+    // be answered with 304
+    pub fn forget_cached_user(&self, id: i32) {
+        self.user_cache.borrow_mut().remove(&id);
+    }
+
+    // Users of a list response from the worker; a fresh list with an ETag
+    // replaces the cached one
+    fn users_result(&self, url: &str, response: UsersResponse) -> ApiResult<Vec<User>> {
+        match response {
+            UsersResponse::Users { etag, users } => {
+                if let Some(etag) = etag {
+                    self.store_cached_users(etag, users.clone());
+                    if let Some(cached) = self.cached_users() {
+                        local_cache::store_user_list(&cached);
+                    }
+                }
+                Ok(users)
+            }
+            UsersResponse::NotModified => self
+                .cached_users()
+                .map(|cached| cached.users)
+                .ok_or_else(|| "Server returned an error".to_string()),
+            UsersResponse::Rejected { status } => {
+                if reporting::is_reportable_status(status) {
+                    reporting::report_failed_request(url, Some(status), "Server returned an error");
+                }
+                Err("Server returned an error".to_string())
+            }
+            UsersResponse::Malformed => Err("Failed to parse users".to_string()),
+            UsersResponse::Unreachable { error } => {
+                reporting::report_failed_request(url, None, &error);
+                Err("Failed to fetch users".to_string())
+            }
+        }
     }
 }
 
@@ -67,77 +586,396 @@ impl Default for HttpUserApiClient {
 }
 
 impl UserApiClient for HttpUserApiClient {
+    // Fetched and decoded in the API worker, so large lists do not block the UI
     fn fetch_users(&self, callback: Callback<ApiResult<Vec<User>>>) {
-        let url = format!("{}/users", self.base_url);
+        let url = self.url(users::ALL);
+        // A new page load starts from the list saved on the last visit
+        let saved = self.cached_users().is_none().then(local_cache::load_user_list).flatten();
+        if let Some(saved) = saved {
+            self.store_cached_users(saved.etag, saved.users);
+        }
+        let etag = self.cached_users().map(|cached| cached.etag);
+        let client = self.clone();
+        let reported_url = url.clone();
+        let started = js_sys::Date::now();
+        worker::fetch_users(
+            url,
+            self.format,
+            etag,
+            Callback::from(move |response: UsersResponse| {
+                if debug::is_recording() {
+                    debug::record(ApiCall {
+                        method: Method::GET.to_string(),
+                        url: reported_url.clone(),
+                        status: response.status(),
+                        latency_ms: (js_sys::Date::now() - started) as u32,
+                        request: None,
+                        response: None,
+                    });
+                }
+                let result = client.users_result(&reported_url, response);
+                callback.emit(result);
+            }),
+        );
+    }
+
+    // Revalidates the cached copy, if any; a 304 answers with it
+    fn fetch_user(&self, id: i32, callback: Callback<ApiResult<User>>) {
+        let url = self.url(&users::by_id(id));
+        let cached = self.cached_user(id);
+        let mut request = ApiRequest::new(Method::GET, &url);
+        if let Some(cached) = &cached {
+            request = request.header("If-Modified-Since", &cached.last_modified);
+        }
+        let client = self.clone();
+        let pipeline = self.pipeline.clone();
         spawn_local(async move {
-            match Request::get(&url).send().await {
+            match pipeline.send(request).await {
+                Ok(resp) if resp.status() == 304 => match cached {
+                    Some(cached) => callback.emit(Ok(cached.user)),
+                    None => callback.emit(Err("Server returned an error".to_string())),
+                },
                 Ok(resp) if resp.ok() => {
-                    match resp.json::<Vec<User>>().await {
-                        Ok(users) => callback.emit(Ok(users)),
-                        Err(_) => callback.emit(Err("Failed to parse users".to_string())),
+                    let last_modified = resp.headers().get("Last-Modified");
+                    match resp.json::<User>().await {
+                        Ok(user) => {
+                            client.store_cached_user(id, last_modified, user.clone());
+                            callback.emit(Ok(user))
+                        }
+                        Err(_) => callback.emit(Err("Failed to parse user".to_string())),
                     }
                 }
+                Ok(resp) if resp.status() == 404 => {
+                    client.forget_cached_user(id);
+                    callback.emit(Err("User not found".to_string()))
+                }
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to fetch user".to_string())),
+            }
+        });
+    }
+
+    fn fetch_user_by_username(&self, username: String, callback: Callback<ApiResult<User>>) {
+        let username = String::from(web_sys::js_sys::encode_uri_component(&username));
+        let url = self.url(&users::by_username(&username));
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(ApiRequest::new(Method::GET, &url)).await {
+                Ok(resp) if resp.ok() => match resp.json::<User>().await {
+                    Ok(user) => callback.emit(Ok(user)),
+                    Err(_) => callback.emit(Err("Failed to parse user".to_string())),
+                },
+                Ok(resp) if resp.status() == 404 => callback.emit(Err("User not found".to_string())),
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to fetch user".to_string())),
+            }
+        });
+    }
+
+    fn fetch_activity(&self, id: i32, callback: Callback<ApiResult<Vec<ActivityEntry>>>) {
+        let url = self.url(&users::activity(id));
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(ApiRequest::new(Method::GET, &url)).await {
+                Ok(resp) if resp.ok() => match resp.json::<Vec<ActivityEntry>>().await {
+                    Ok(entries) => callback.emit(Ok(entries)),
+                    Err(_) => callback.emit(Err("Failed to parse activity".to_string())),
+                },
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to fetch activity".to_string())),
+            }
+        });
+    }
+
+    fn fetch_preferences(&self, user_id: i32, callback: Callback<ApiResult<UserPreferences>>) {
+        let url = self.url(&users::preferences(user_id));
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(as_admin(ApiRequest::new(Method::GET, &url))).await {
+                Ok(resp) if resp.ok() => match resp.json::<UserPreferences>().await {
+                    Ok(preferences) => callback.emit(Ok(preferences)),
+                    Err(_) => callback.emit(Err("Failed to parse preferences".to_string())),
+                },
                 Ok(_) => callback.emit(Err("Server returned an error".to_string())),
-                Err(_) => callback.emit(Err("Failed to fetch users".to_string())),
+                Err(_) => callback.emit(Err("Failed to fetch preferences".to_string())),
+            }
+        });
+    }
+
+    fn save_preferences(&self, user_id: i32, preferences: UserPreferences, callback: Callback<SubmitResult>) {
+        let url = self.url(&users::preferences(user_id));
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            let body = match serde_json::to_string(&preferences) {
+                Ok(body) => body,
+                Err(_) => {
+                    callback.emit(Err(ApiError::general("Failed to encode preferences")));
+                    return;
+                }
+            };
+            if offline::queue_admin_if_offline(MutationMethod::Put, &url, Some(body.clone())) {
+                callback.emit(Ok(()));
+                return;
+            }
+
+            match pipeline.send(as_admin(ApiRequest::new(Method::PUT, &url).json(body))).await {
+                Ok(resp) if resp.ok() => callback.emit(Ok(())),
+                Ok(resp) => callback.emit(Err(submit_error(resp, "Failed to save preferences").await)),
+                Err(_) => callback.emit(Err(ApiError::general("Request failed"))),
+            }
+        });
+    }
+
+    fn create_user(&self, request: CreateUserRequest, callback: Callback<SubmitResult>) {
+        let url = self.url(users::ALL);
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            let user_data = serde_json::json!({
+                "name": request.name,
+                "email": request.email,
+                "password": request.password,
+                "username": request.username,
+                "phone": request.phone,
+                "address": request.address
+            });
+            if offline::queue_admin_if_offline(MutationMethod::Post, &url, Some(user_data.to_string())) {
+                callback.emit(Ok(()));
+                return;
+            }
+
+            // Only admins may create users; signups go through sign_up
+            let request = as_admin(ApiRequest::new(Method::POST, &url).json(user_data.to_string()));
+            match pipeline.send(request).await {
+                Ok(resp) if resp.ok() => callback.emit(Ok(())),
+                Ok(resp) => callback.emit(Err(submit_error(resp, "Failed to create user").await)),
+                Err(_) => callback.emit(Err(ApiError::general("Request failed"))),
             }
         });
     }
 
-    fn create_user(&self, request: CreateUserRequest, callback: Callback<ApiResult<()>>) {
-        let url = format!("{}/users", self.base_url);
+    fn sign_up(&self, request: CreateUserRequest, callback: Callback<SubmitResult>) {
+        let url = self.url(routes::SIGNUP);
+        let pipeline = self.pipeline.clone();
         spawn_local(async move {
             let user_data = serde_json::json!({
                 "name": request.name,
                 "email": request.email,
-                "password": request.password
+                "password": request.password,
+                "captcha_token": request.captcha_token
             });
 
-            match Request::post(&url)
-                .header("Content-Type", "application/json")
-                .body(user_data.to_string())
-                .send()
-                .await
-            {
+            match pipeline.send(ApiRequest::new(Method::POST, &url).json(user_data.to_string())).await {
                 Ok(resp) if resp.ok() => callback.emit(Ok(())),
-                Ok(_) => callback.emit(Err("Failed to create user".to_string())),
+                Ok(resp) => callback.emit(Err(submit_error(resp, "Failed to sign up").await)),
+                Err(_) => callback.emit(Err(ApiError::general("Request failed"))),
+            }
+        });
+    }
+
+    fn email_exists(&self, email: String, callback: Callback<ApiResult<bool>>) {
+        let email = String::from(web_sys::js_sys::encode_uri_component(&email));
+        let url = format!("{}?email={}", self.url(users::EXISTS), email);
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(ApiRequest::new(Method::GET, &url)).await {
+                Ok(resp) if resp.ok() => match resp.json::<EmailExists>().await {
+                    Ok(answer) => callback.emit(Ok(answer.exists)),
+                    Err(_) => callback.emit(Err("Failed to parse email check".to_string())),
+                },
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to check email".to_string())),
+            }
+        });
+    }
+
+    fn username_available(&self, username: String, callback: Callback<ApiResult<bool>>) {
+        let username = String::from(web_sys::js_sys::encode_uri_component(&username));
+        let url = format!("{}?u={}", self.url(routes::USERNAMES_AVAILABLE), username);
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(ApiRequest::new(Method::GET, &url)).await {
+                Ok(resp) if resp.ok() => match resp.json::<UsernameAvailability>().await {
+                    Ok(answer) => callback.emit(Ok(answer.available)),
+                    Err(_) => callback.emit(Err("Failed to parse username check".to_string())),
+                },
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to check username".to_string())),
+            }
+        });
+    }
+
+    fn verify_email(&self, token: String, callback: Callback<ApiResult<()>>) {
+        let url = self.url(routes::SIGNUP_VERIFY);
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            let body = serde_json::json!({ "token": token });
+            match pipeline.send(ApiRequest::new(Method::POST, &url).json(body.to_string())).await {
+                Ok(resp) if resp.ok() => callback.emit(Ok(())),
+                Ok(resp) => callback.emit(Err(submit_error(resp, "Failed to verify email").await.message)),
                 Err(_) => callback.emit(Err("Request failed".to_string())),
             }
         });
     }
 
-    fn update_user(&self, request: UpdateUserRequest, callback: Callback<ApiResult<()>>) {
-        let url = format!("{}/users/{}", self.base_url, request.id);
+    fn fetch_captcha_settings(&self, callback: Callback<ApiResult<CaptchaSettings>>) {
+        let url = self.url(routes::CAPTCHA);
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(ApiRequest::new(Method::GET, &url)).await {
+                Ok(resp) if resp.ok() => match resp.json::<CaptchaSettings>().await {
+                    Ok(settings) => callback.emit(Ok(settings)),
+                    Err(_) => callback.emit(Err("Failed to parse CAPTCHA settings".to_string())),
+                },
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to fetch CAPTCHA settings".to_string())),
+            }
+        });
+    }
+
+    fn update_user(&self, request: UpdateUserRequest, callback: Callback<SubmitResult>) {
+        let url = self.url(&users::by_id(request.id));
+        self.forget_cached_user(request.id);
+        let pipeline = self.pipeline.clone();
         spawn_local(async move {
             let user_data = serde_json::json!({
                 "id": request.id,
                 "name": request.name,
                 "email": request.email,
-                "password": request.password
+                "password": request.password,
+                "username": request.username,
+                "phone": request.phone,
+                "address": request.address
             });
-            
-            match Request::put(&url)
-                .header("Content-Type", "application/json")
-                .body(user_data.to_string())
-                .send()
-                .await
-            {
+            if offline::queue_admin_if_offline(MutationMethod::Put, &url, Some(user_data.to_string())) {
+                callback.emit(Ok(()));
+                return;
+            }
+
+            let request = as_admin(ApiRequest::new(Method::PUT, &url).json(user_data.to_string()));
+            match pipeline.send(request).await {
                 Ok(resp) if resp.ok() => callback.emit(Ok(())),
-                Ok(_) => callback.emit(Err("Failed to update user".to_string())),
-                Err(_) => callback.emit(Err("Request failed".to_string())),
+                Ok(resp) => callback.emit(Err(submit_error(resp, "Failed to update user").await)),
+                Err(_) => callback.emit(Err(ApiError::general("Request failed"))),
             }
         });
     }
 
     fn delete_user(&self, id: i32, callback: Callback<ApiResult<()>>) {
-        let url = format!("{}/users/{}", self.base_url, id);
+        let url = self.url(&users::by_id(id));
+        self.forget_cached_user(id);
+        if offline::queue_admin_if_offline(MutationMethod::Delete, &url, None) {
+            callback.emit(Ok(()));
+            return;
+        }
+        let pipeline = self.pipeline.clone();
         spawn_local(async move {
-            match Request::delete(&url).send().await {
+            match pipeline.send(as_admin(ApiRequest::new(Method::DELETE, &url))).await {
                 Ok(resp) if resp.ok() => callback.emit(Ok(())),
                 Ok(_) => callback.emit(Err("Failed to delete user".to_string())),
                 Err(_) => callback.emit(Err("Request failed".to_string())),
             }
         });
     }
+
+    fn set_user_status(&self, id: i32, status: UserStatus, callback: Callback<ApiResult<()>>) {
+        let url = self.url(&match status {
+            UserStatus::Active => users::activate(id),
+            UserStatus::Suspended => users::suspend(id),
+        });
+        self.forget_cached_user(id);
+        if offline::queue_admin_if_offline(MutationMethod::Post, &url, None) {
+            callback.emit(Ok(()));
+            return;
+        }
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(as_admin(ApiRequest::new(Method::POST, &url))).await {
+                Ok(resp) if resp.ok() => callback.emit(Ok(())),
+                Ok(_) => callback.emit(Err(format!("Failed to {} user", status.action()))),
+                Err(_) => callback.emit(Err("Request failed".to_string())),
+            }
+        });
+    }
+
+    fn set_user_tags(&self, id: i32, tags: Vec<String>, callback: Callback<SubmitResult>) {
+        let url = self.url(&users::tags(id));
+        self.forget_cached_user(id);
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            let body = serde_json::json!({ "tags": tags }).to_string();
+            if offline::queue_admin_if_offline(MutationMethod::Put, &url, Some(body.clone())) {
+                callback.emit(Ok(()));
+                return;
+            }
+
+            match pipeline.send(as_admin(ApiRequest::new(Method::PUT, &url).json(body))).await {
+                Ok(resp) if resp.ok() => callback.emit(Ok(())),
+                Ok(resp) => callback.emit(Err(submit_error(resp, "Failed to save tags").await)),
+                Err(_) => callback.emit(Err(ApiError::general("Request failed"))),
+            }
+        });
+    }
+
+    // Not queued while offline: the per-user results only come from the server
+    fn bulk_update_users(
+        &self,
+        request: BulkUpdateRequest,
+        callback: Callback<Result<Vec<BulkUpdateResult>, ApiError>>,
+    ) {
+        let url = self.url(users::BULK);
+        for id in &request.ids {
+            self.forget_cached_user(*id);
+        }
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            let body = serde_json::to_string(&request).unwrap_or_default();
+            match pipeline.send(as_admin(ApiRequest::new(Method::PATCH, &url).json(body))).await {
+                Ok(resp) if resp.ok() => match resp.json::<Vec<BulkUpdateResult>>().await {
+                    Ok(results) => callback.emit(Ok(results)),
+                    Err(_) => callback.emit(Err(ApiError::general("Failed to parse bulk update results"))),
+                },
+                Ok(resp) => callback.emit(Err(submit_error(resp, "Failed to update users").await)),
+                Err(_) => callback.emit(Err(ApiError::general("Request failed"))),
+            }
+        });
+    }
+
+    // A selection is posted as an id list; everything is a plain download
+    fn export_users(&self, ids: Vec<i32>, callback: Callback<ApiResult<String>>) {
+        let url = format!("{}?format=csv", self.url(users::EXPORT));
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            let response = if ids.is_empty() {
+                pipeline.send(ApiRequest::new(Method::GET, &url)).await
+            } else {
+                let body = serde_json::json!({ "ids": ids }).to_string();
+                pipeline.send(ApiRequest::new(Method::POST, &url).json(body)).await
+            };
+            match response {
+                Ok(resp) if resp.ok() => match resp.text().await {
+                    Ok(csv) => callback.emit(Ok(csv)),
+                    Err(_) => callback.emit(Err("Failed to read the export".to_string())),
+                },
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to export users".to_string())),
+            }
+        });
+    }
+
+    fn fetch_stats(&self, callback: Callback<ApiResult<UserStats>>) {
+        let url = self.url(routes::STATS);
+        let pipeline = self.pipeline.clone();
+        spawn_local(async move {
+            match pipeline.send(ApiRequest::new(Method::GET, &url)).await {
+                Ok(resp) if resp.ok() => match resp.json::<UserStats>().await {
+                    Ok(stats) => callback.emit(Ok(stats)),
+                    Err(_) => callback.emit(Err("Failed to parse statistics".to_string())),
+                },
+                Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+                Err(_) => callback.emit(Err("Failed to fetch statistics".to_string())),
+            }
+        });
+    }
 }
 
 #[cfg(test)]
@@ -150,6 +988,14 @@ mod tests {
             id: 1,
             name: "Test User".to_string(),
             email: "test@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
         };
         assert_eq!(user.id, 1);
         assert_eq!(user.name, "Test User");
@@ -162,6 +1008,14 @@ mod tests {
             id: 1,
             name: "Test User".to_string(),
             email: "test@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
         };
         let cloned = user.clone();
         assert_eq!(user, cloned);
@@ -173,6 +1027,10 @@ mod tests {
             name: "New User".to_string(),
             email: "new@example.com".to_string(),
             password: "password123".to_string(),
+            username: None,
+            captcha_token: None,
+            phone: None,
+            address: None,
         };
         assert_eq!(request.name, "New User");
         assert_eq!(request.email, "new@example.com");
@@ -186,6 +1044,9 @@ mod tests {
             name: "Updated User".to_string(),
             email: "updated@example.com".to_string(),
             password: "newpassword".to_string(),
+            username: None,
+            phone: None,
+            address: None,
         };
         assert_eq!(request.id, 1);
         assert_eq!(request.name, "Updated User");
@@ -193,16 +1054,177 @@ mod tests {
         assert_eq!(request.password, "newpassword");
     }
 
+    #[test]
+    fn test_user_contact_fields_from_backend_response() {
+        let user: User = serde_json::from_str(
+            r#"{"id":1,"name":"Ana","email":"ana@example.com","phone":"+5511912345678",
+                "address":{"street":"Rua A","city":"Recife","postal_code":"","country":"BR"}}"#,
+        )
+        .unwrap();
+        assert_eq!(user.phone.as_deref(), Some("+5511912345678"));
+        let address = user.address.unwrap();
+        assert_eq!(address.city, "Recife");
+        assert_eq!(address.complement, None);
+        assert_eq!(
+            serde_json::to_value(&address).unwrap(),
+            serde_json::json!({"street":"Rua A","city":"Recife","postal_code":"","country":"BR"})
+        );
+
+        let user: User =
+            serde_json::from_str(r#"{"id":2,"name":"Bo","email":"bo@example.com"}"#).unwrap();
+        assert_eq!(user.phone, None);
+        assert_eq!(user.address, None);
+    }
+
     #[test]
     fn test_http_client_creation() {
         let client = HttpUserApiClient::new();
-        assert_eq!(client.base_url, API_BASE_URL);
+        assert_eq!(client.base_url, api_url(""));
     }
 
     #[test]
     fn test_http_client_default() {
         let client = HttpUserApiClient::default();
-        assert_eq!(client.base_url, API_BASE_URL);
+        assert_eq!(client.base_url, api_url(""));
+    }
+
+    #[test]
+    fn test_admin_authorization() {
+        assert_eq!(admin_authorization("q8Zt-31xLw"), "Bearer q8Zt-31xLw");
+    }
+
+    #[test]
+    fn test_api_url() {
+        assert_eq!(api_url(routes::FLAGS), "http://127.0.0.1:8000/api/v1/flags");
+        assert_eq!(api_url(&users::by_id(7)), "http://127.0.0.1:8000/api/v1/users/7");
+    }
+
+    #[test]
+    fn test_http_client_with_format() {
+        let client = HttpUserApiClient::new();
+        assert_eq!(client.format(), WireFormat::Json);
+
+        let client = client.with_format(WireFormat::MessagePack);
+        assert_eq!(client.format(), WireFormat::MessagePack);
+    }
+
+    #[test]
+    fn test_list_cache_is_shared_between_clones() {
+        let client = HttpUserApiClient::new();
+        assert_eq!(client.cached_users(), None);
+
+        let clone = client.clone();
+        let users = vec![User {
+            id: 1,
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        }];
+        clone.store_cached_users("W/\"1-abc\"".to_string(), users.clone());
+
+        let cached = client.cached_users().unwrap();
+        assert_eq!(cached.etag, "W/\"1-abc\"");
+        assert_eq!(cached.users, users);
+    }
+
+    #[test]
+    fn test_user_cache() {
+        let client = HttpUserApiClient::new();
+        let user = User {
+            id: 7,
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+        let date = "Sat, 17 Oct 2026 12:00:00 GMT".to_string();
+        client.clone().store_cached_user(7, Some(date.clone()), user.clone());
+        assert_eq!(client.cached_user(7), Some(CachedUser { last_modified: date.clone(), user: user.clone() }));
+        assert_eq!(client.cached_user(8), None);
+
+        client.store_cached_user(7, None, user.clone());
+        assert_eq!(client.cached_user(7), None);
+
+        client.store_cached_user(7, Some(date), user);
+        client.forget_cached_user(7);
+        assert_eq!(client.cached_user(7), None);
+    }
+
+    #[test]
+    fn test_worker_responses_to_results() {
+        let client = HttpUserApiClient::new();
+        let url = "http://127.0.0.1:8000/api/v1/users";
+        assert_eq!(
+            client.users_result(url, UsersResponse::NotModified),
+            Err("Server returned an error".to_string())
+        );
+        assert_eq!(
+            client.users_result(url, UsersResponse::Malformed),
+            Err("Failed to parse users".to_string())
+        );
+
+        let users = vec![User {
+            id: 2,
+            name: "Jane".to_string(),
+            email: "jane@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        }];
+        let fresh = UsersResponse::Users { etag: None, users: users.clone() };
+        assert_eq!(client.users_result(url, fresh), Ok(users.clone()));
+
+        client.store_cached_users("W/\"1-abc\"".to_string(), users.clone());
+        assert_eq!(client.users_result(url, UsersResponse::NotModified), Ok(users));
+    }
+
+    #[test]
+    fn test_wire_format_accept_header() {
+        assert_eq!(WireFormat::Json.accept_header(), "application/json");
+        assert_eq!(WireFormat::MessagePack.accept_header(), "application/msgpack");
+    }
+
+    #[test]
+    fn test_decode_users() {
+        let users = vec![User {
+            id: 1,
+            name: "Test User".to_string(),
+            email: "test@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        }];
+
+        let json = serde_json::to_vec(&users).unwrap();
+        assert_eq!(WireFormat::Json.decode_users(&json), Ok(users.clone()));
+
+        let msgpack = rmp_serde::to_vec_named(&users).unwrap();
+        assert_eq!(WireFormat::MessagePack.decode_users(&msgpack), Ok(users.clone()));
+
+        assert!(WireFormat::MessagePack.decode_users(b"not msgpack").is_err());
     }
 
     #[test]
@@ -211,4 +1233,56 @@ mod tests {
         let client = HttpUserApiClient::with_base_url(custom_url.clone());
         assert_eq!(client.base_url, custom_url);
     }
+
+    #[test]
+    fn test_user_status() {
+        assert_eq!(UserStatus::default(), UserStatus::Active);
+        assert_eq!(UserStatus::Active.toggled(), UserStatus::Suspended);
+        assert_eq!(UserStatus::Suspended.action(), "suspend");
+        assert_eq!(UserStatus::Active.action(), "activate");
+
+        let user: User =
+            serde_json::from_str(r#"{"id":1,"name":"A","email":"a@x.com","status":"suspended"}"#)
+                .unwrap();
+        assert_eq!(user.status, UserStatus::Suspended);
+        let user: User = serde_json::from_str(r#"{"id":1,"name":"A","email":"a@x.com"}"#).unwrap();
+        assert_eq!(user.status, UserStatus::Active);
+    }
+
+    #[test]
+    fn test_api_error_from_error_response() {
+        let body: ErrorResponse = serde_json::from_str(
+            r#"{"code":"bad_request","message":"Invalid email format","request_id":"r1","field":"email"}"#,
+        )
+        .unwrap();
+        let error = ApiError::from(body);
+        assert_eq!(error.message, "Invalid email format");
+        assert_eq!(error.field.as_deref(), Some("email"));
+
+        let body: ErrorResponse =
+            serde_json::from_str(r#"{"code":"internal_error","message":"Internal server error","request_id":"r2"}"#)
+                .unwrap();
+        assert_eq!(ApiError::from(body), ApiError::general("Internal server error"));
+    }
+
+    #[test]
+    fn test_activity_entry_from_backend_response() {
+        let entries: Vec<ActivityEntry> = serde_json::from_str(
+            r#"[{"event":"user.updated","occurred_at":"2026-10-17T12:00:00Z"}]"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].event, "user.updated");
+        assert_eq!(entries[0].occurred_at, "2026-10-17T12:00:00Z");
+    }
+
+    #[test]
+    fn test_captcha_settings_format() {
+        let settings: CaptchaSettings =
+            serde_json::from_str(r#"{"provider":"hcaptcha","site_key":"10000000-ffff"}"#).unwrap();
+        assert_eq!(settings.provider, CaptchaProvider::HCaptcha);
+        assert_eq!(settings.site_key.as_deref(), Some("10000000-ffff"));
+
+        let settings: CaptchaSettings = serde_json::from_str(r#"{"provider":"none","site_key":null}"#).unwrap();
+        assert_eq!(settings, CaptchaSettings::default());
+    }
 }
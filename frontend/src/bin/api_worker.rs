@@ -0,0 +1,9 @@
+// Entry point of the API web worker; Trunk builds it as api_worker.js and
+// the app spawns it on the first user list request
+
+use frontend::worker::ApiWorker;
+use gloo::worker::PrivateWorker;
+
+fn main() {
+    ApiWorker::register();
+}
@@ -0,0 +1,133 @@
+// Error boundary Module - Single Responsibility Principle
+// A panic aborts the WASM instance, so Yew can no longer render anything and
+// the page would stay blank. The panic hook calls show_fallback, which swaps
+// the boundary's content for a static message built with plain DOM calls and
+// a Reload link that needs no WASM to work
+
+use crate::i18n::{Locale, Translator};
+use std::cell::RefCell;
+use web_sys::{Document, Element};
+use yew::prelude::*;
+
+thread_local! {
+    // Element of the mounted ErrorBoundary, replaced on panic
+    static CONTAINER: RefCell<Option<Element>> = const { RefCell::new(None) };
+}
+
+// Texts of the fallback, in the locale the user picked
+#[derive(Clone, Debug, PartialEq)]
+pub struct FallbackText {
+    pub title: String,
+    pub message: String,
+    pub reload: String,
+}
+
+impl FallbackText {
+    pub fn new(t: &Translator) -> Self {
+        Self {
+            title: t.t("crash-title"),
+            message: t.t("crash-message"),
+            reload: t.t("crash-reload"),
+        }
+    }
+}
+
+// Props for ErrorBoundary component
+#[derive(Properties, PartialEq)]
+pub struct ErrorBoundaryProps {
+    pub children: Html,
+}
+
+// Wraps the app; its content is replaced by the fallback after a panic
+#[function_component(ErrorBoundary)]
+pub fn error_boundary(props: &ErrorBoundaryProps) -> Html {
+    let node = use_node_ref();
+
+    {
+        let node = node.clone();
+        use_effect_with((), move |_| {
+            CONTAINER.with(|container| *container.borrow_mut() = node.cast::<Element>());
+            || {
+                CONTAINER.with(|container| container.borrow_mut().take());
+            }
+        });
+    }
+
+    html! {
+        <div ref={node}>
+            { props.children.clone() }
+        </div>
+    }
+}
+
+// Replace the boundary's content with the fallback; called by the panic hook
+pub fn show_fallback() {
+    // The panic may have happened while the container was borrowed
+    let container = CONTAINER.try_with(|container| container.try_borrow().ok().and_then(|c| c.clone()));
+    let Some(container) = container.ok().flatten() else {
+        return;
+    };
+    let Some(document) = container.owner_document() else {
+        return;
+    };
+    // The context is gone with the app, so the stored locale is read again
+    let text = FallbackText::new(&Translator::new(Locale::preferred()));
+    if let Some(fallback) = render_fallback(&document, &text) {
+        container.set_inner_html("");
+        let _ = container.append_child(&fallback);
+    }
+}
+
+fn element(document: &Document, tag: &str, class: &str, text: Option<&str>) -> Option<Element> {
+    let element = document.create_element(tag).ok()?;
+    element.set_class_name(class);
+    if let Some(text) = text {
+        element.set_text_content(Some(text));
+    }
+    Some(element)
+}
+
+fn render_fallback(document: &Document, text: &FallbackText) -> Option<Element> {
+    let fallback = element(
+        document,
+        "div",
+        "flex flex-col items-center m-10 p-10 border border-red-200 rounded bg-red-50 dark:bg-gray-800 dark:border-red-800",
+        None,
+    )?;
+    fallback.set_attribute("role", "alert").ok()?;
+    let title = element(document, "h1", "text-2xl font-bold mb-2 text-red-700 dark:text-red-400", Some(&text.title))?;
+    let message = element(document, "p", "mb-4 text-gray-700 dark:text-gray-300", Some(&text.message))?;
+    // A link to the current address reloads the page without running any WASM
+    let reload = element(
+        document,
+        "a",
+        "bg-gray-500 hover:bg-gray-700 text-white font-bold py-2 px-4 rounded",
+        Some(&text.reload),
+    )?;
+    let href = document.location().and_then(|location| location.href().ok()).unwrap_or_default();
+    reload.set_attribute("href", &href).ok()?;
+    for child in [title, message, reload] {
+        fallback.append_child(&child).ok()?;
+    }
+    Some(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_is_translated() {
+        let text = FallbackText::new(&Translator::new(Locale::En));
+        assert_eq!(text.reload, "Reload");
+
+        let text = FallbackText::new(&Translator::new(Locale::PtBr));
+        assert_eq!(text.reload, "Recarregar");
+        assert_ne!(text.title, "crash-title");
+    }
+
+    #[test]
+    fn test_fallback_without_boundary_is_a_no_op() {
+        show_fallback();
+    }
+}
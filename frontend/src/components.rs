@@ -1,162 +1,2473 @@
 // UI Components Module - Single Responsibility Principle & Open/Closed Principle
 // Reusable UI components separated by concern
 
-use crate::api::User;
-use web_sys::HtmlInputElement;
+use crate::api::{self, ActivityEntry, Address, ApiResult, CaptchaProvider, CaptchaSettings, User, UserStatus};
+use crate::desktop::DesktopNotifications;
+use crate::i18n::{use_translation, LanguageSwitcher, Translator};
+use crate::list_state::{page_count, page_of, ListState, UserListQuery, LIST_QUERY_DEBOUNCE_MS};
+use crate::preferences::{
+    find_view, with_view, without_view, Column, ColumnSettings, ListView, SavedView, MAX_SAVED_VIEWS,
+    MAX_VIEW_NAME_LENGTH,
+};
+use shared::password::{
+    generate_password, password_strength, PasswordStrength, GENERATED_PASSWORD_LENGTH,
+    MIN_PASSWORD_LENGTH,
+};
+use shared::validation::{
+    normalize_tag, validate_email, validate_tag, validate_username, EmailError, MAX_ADDRESS_FIELD_LENGTH, MAX_TAGS, MAX_TAG_LENGTH, MAX_USERNAME_LENGTH,
+    MIN_USERNAME_LENGTH,
+};
+use crate::router::{Route, UserRef};
+use crate::service::{DefaultUserService, UserService};
+use crate::state::{format_tags, mask_country, mask_phone, mask_username, tags_error_id, FieldErrors, HistoryStep, Notification, NotificationLevel, SignupStep, UserFormState};
+use crate::status::ConnectivityDot;
+use crate::theme::ThemeToggle;
+use gloo::timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{js_sys, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
+use yew_router::prelude::*;
+
+// Visible focus outline for keyboard users
+pub const FOCUS_RING: &str = "focus:outline-none focus-visible:ring-2 focus-visible:ring-blue-500";
+
+// Props for TextField component
+#[derive(Properties, PartialEq, Clone)]
+pub struct TextFieldProps {
+    // Id of the input, referenced by its label
+    pub id: AttrValue,
+    pub label: AttrValue,
+    pub value: String,
+    pub oninput: Callback<String>,
+    #[prop_or(AttrValue::Static("text"))]
+    pub input_type: AttrValue,
+    #[prop_or_default]
+    pub required: bool,
+    // Shown instead of the help text while set
+    #[prop_or_default]
+    pub error: Option<String>,
+    #[prop_or_default]
+    pub help: Option<String>,
+    #[prop_or_default]
+    pub placeholder: Option<AttrValue>,
+    // Shown but not editable, e.g. for values that never change
+    #[prop_or_default]
+    pub readonly: bool,
+}
+
+// Id of the element describing a field's input, if any
+pub fn field_description_id(props: &TextFieldProps) -> Option<String> {
+    match (&props.error, &props.help) {
+        (Some(_), _) => Some(format!("{}-error", props.id)),
+        (None, Some(_)) => Some(format!("{}-help", props.id)),
+        (None, None) => None,
+    }
+}
+
+// Labelled input with an optional error or help text below it
+#[function_component(TextField)]
+pub fn text_field(props: &TextFieldProps) -> Html {
+    let oninput = {
+        let callback = props.oninput.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_dyn_into::<HtmlInputElement>().unwrap();
+            callback.emit(input.value());
+        })
+    };
+    let border = if props.error.is_some() {
+        "border-red-500"
+    } else {
+        "dark:border-gray-600"
+    };
+
+    html! {
+        <div class="flex flex-col mr-2">
+            <label for={props.id.clone()} class="text-sm font-medium text-gray-700 dark:text-gray-200 mb-1">
+                { &props.label }
+                if props.required {
+                    <span class="text-red-500" aria-hidden="true">{ " *" }</span>
+                }
+            </label>
+            <input
+                id={props.id.clone()}
+                type={props.input_type.clone()}
+                value={props.value.clone()}
+                placeholder={props.placeholder.clone()}
+                required={props.required}
+                readonly={props.readonly}
+                aria-invalid={props.error.is_some().then_some("true")}
+                aria-describedby={field_description_id(props)}
+                oninput={oninput}
+                class={classes!("border", "rounded", "px-4", "py-2", "dark:bg-gray-800", border)}
+            />
+            if let Some(error) = &props.error {
+                <p id={format!("{}-error", props.id)} class="text-sm text-red-500 mt-1">{ error }</p>
+            } else if let Some(help) = &props.help {
+                <p id={format!("{}-help", props.id)} class="text-sm text-gray-500 dark:text-gray-400 mt-1">{ help }</p>
+            }
+        </div>
+    }
+}
+
+// Error shown under the email field; an empty field is only reported on submit
+pub fn email_field_error(t: &Translator, email: &str) -> Option<String> {
+    match validate_email(email) {
+        Err(EmailError::InvalidFormat) => Some(t.t("email-invalid")),
+        _ => None,
+    }
+}
+
+// Pause in typing after which the email or username is checked
+pub const EMAIL_CHECK_DEBOUNCE_MS: u32 = 400;
+
+// Service call answering whether a value is already in use
+type TakenCheck = fn(&DefaultUserService, &str, Callback<ApiResult<bool>>);
+
+// Whether `value` is already in use, checked with `check` once typing pauses;
+// None until a check answers, and while `enabled` is false or `value` is invalid
+#[hook]
+fn use_taken(value: &str, enabled: bool, is_valid: fn(&str) -> bool, check: TakenCheck) -> Option<bool> {
+    let taken = use_state(|| None);
+    // Value the latest check is for, so late answers for earlier input are ignored
+    let latest = use_mut_ref(String::new);
+
+    {
+        let taken = taken.clone();
+        use_effect_with((value.to_string(), enabled), move |(value, enabled)| {
+            taken.set(None);
+            *latest.borrow_mut() = value.clone();
+            let timeout = (*enabled && is_valid(value)).then(|| {
+                let value = value.clone();
+                Timeout::new(EMAIL_CHECK_DEBOUNCE_MS, move || {
+                    let checked = value.clone();
+                    check(
+                        &DefaultUserService::default(),
+                        &value,
+                        Callback::from(move |result: ApiResult<bool>| {
+                            // A failed check only means no early warning; submit still catches it
+                            if *latest.borrow() == checked {
+                                taken.set(result.ok());
+                            }
+                        }),
+                    );
+                })
+            });
+            move || drop(timeout)
+        });
+    }
+
+    *taken
+}
+
+// Whether `email` is already registered, checked once typing pauses so the
+// form can warn before submitting; always false while `enabled` is false
+#[hook]
+pub fn use_email_taken(email: &str, enabled: bool) -> bool {
+    let taken = use_taken(email, enabled, |email| validate_email(email).is_ok(), |service, email, callback| {
+        service.email_taken(email, callback)
+    });
+    taken.unwrap_or(false)
+}
+
+// Whether `username` is taken, for live feedback while it is picked; None
+// until the check answers, and while `enabled` is false
+#[hook]
+pub fn use_username_taken(username: &str, enabled: bool) -> Option<bool> {
+    use_taken(username, enabled, |username| validate_username(username).is_ok(), |service, username, callback| {
+        service.username_taken(username, callback)
+    })
+}
+
+// Feedback under the username field: (error, help). A locked username only
+// explains itself; a new one gets format errors or the availability answer
+pub fn username_field_feedback(
+    t: &Translator,
+    state: &UserFormState,
+    taken: Option<bool>,
+) -> (Option<String>, Option<String>) {
+    if state.username_locked {
+        return (None, Some(t.t("username-locked")));
+    }
+    if let Some(id) = state.username_error_id() {
+        let args = [("min", MIN_USERNAME_LENGTH.into()), ("max", MAX_USERNAME_LENGTH.into())];
+        return (Some(t.t_with(id, &args)), None);
+    }
+    match (state.username_request(), taken) {
+        (Some(username), Some(true)) => (Some(t.t_with("username-taken", &[("username", username.into())])), None),
+        (Some(username), Some(false)) => (None, Some(t.t_with("username-available", &[("username", username.into())]))),
+        _ => (None, Some(t.t("username-help"))),
+    }
+}
 
 // Props for UserForm component
 #[derive(Properties, PartialEq, Clone)]
 pub struct UserFormProps {
     pub name: String,
     pub email: String,
-    pub password: String,
-    pub is_editing: bool,
-    pub on_name_change: Callback<String>,
-    pub on_email_change: Callback<String>,
-    pub on_password_change: Callback<String>,
-    pub on_submit: Callback<()>,
-    pub message: String,
+    pub password: String,
+    pub is_editing: bool,
+    pub on_name_change: Callback<String>,
+    pub on_email_change: Callback<String>,
+    pub on_password_change: Callback<String>,
+    pub on_submit: Callback<()>,
+    // Outcome of the last action, shown below the form
+    #[prop_or_default]
+    pub notification: Option<Notification>,
+    #[prop_or_default]
+    pub on_dismiss: Callback<()>,
+    // Errors the backend reported for the form's fields
+    #[prop_or_default]
+    pub errors: FieldErrors,
+    // Text of the submit button; "Create User" or "Update User" when unset
+    #[prop_or_default]
+    pub submit_label: Option<String>,
+    // Optional contact details; their inputs are only shown with a change callback
+    #[prop_or_default]
+    pub phone: String,
+    #[prop_or_default]
+    pub address: Address,
+    #[prop_or_default]
+    pub on_phone_change: Option<Callback<String>>,
+    #[prop_or_default]
+    pub on_address_change: Option<Callback<Address>>,
+    // Optional username; its input is only shown with a change callback
+    #[prop_or_default]
+    pub username: String,
+    #[prop_or_default]
+    pub username_locked: bool,
+    #[prop_or_default]
+    pub on_username_change: Option<Callback<String>>,
+    // Undo and redo of the form's edits; the browser's own undo is kept without it
+    #[prop_or_default]
+    pub on_history: Option<Callback<HistoryStep>>,
+}
+
+// Key handler of a form emitting Ctrl+Z and Ctrl+Shift+Z to `on_history` in
+// place of the browser's undo, which only knows about one input
+fn history_keydown(on_history: Option<Callback<HistoryStep>>) -> Callback<KeyboardEvent> {
+    Callback::from(move |e: KeyboardEvent| {
+        let Some(on_history) = &on_history else {
+            return;
+        };
+        if let Some(step) = HistoryStep::from_key(&e.key(), e.ctrl_key() || e.meta_key(), e.shift_key()) {
+            e.prevent_default();
+            on_history.emit(step);
+        }
+    })
+}
+
+// Error shown under the phone field while it is filled in and invalid
+pub fn phone_field_error(t: &Translator, state: &UserFormState) -> Option<String> {
+    state.phone_error_id().map(|id| t.t(id))
+}
+
+// Error shown under the address fields; a blank address is valid
+pub fn address_field_error(t: &Translator, state: &UserFormState) -> Option<String> {
+    state
+        .address_error_id()
+        .map(|id| t.t_with(id, &[("max", MAX_ADDRESS_FIELD_LENGTH.into())]))
+}
+
+// Props for ContactFields component
+#[derive(Properties, PartialEq, Clone)]
+pub struct ContactFieldsProps {
+    pub phone: String,
+    pub address: Address,
+    pub on_phone_change: Callback<String>,
+    pub on_address_change: Callback<Address>,
+    #[prop_or_default]
+    pub errors: FieldErrors,
+}
+
+// Value of an optional text input, None while it is empty
+fn optional_text(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+// Optional phone (masked to E.164 while typing) and postal address inputs
+#[function_component(ContactFields)]
+pub fn contact_fields(props: &ContactFieldsProps) -> Html {
+    let t = use_translation();
+    let state = UserFormState {
+        phone: props.phone.clone(),
+        address: props.address.clone(),
+        ..UserFormState::new()
+    };
+
+    let on_phone = {
+        let callback = props.on_phone_change.clone();
+        Callback::from(move |value: String| callback.emit(mask_phone(&value)))
+    };
+    // Each address input replaces one field of the current address
+    let address_input = |update: fn(&mut Address, String)| {
+        let callback = props.on_address_change.clone();
+        let address = props.address.clone();
+        Callback::from(move |value: String| {
+            let mut address = address.clone();
+            update(&mut address, value);
+            callback.emit(address);
+        })
+    };
+    let address_error = props.errors.get("address").or_else(|| address_field_error(&t, &state));
+
+    html! {
+        <>
+            <div class="flex flex-wrap items-start mt-2">
+                <TextField
+                    id="user-phone"
+                    label={t.t("field-phone")}
+                    input_type="tel"
+                    value={props.phone.clone()}
+                    oninput={on_phone}
+                    placeholder="+5511912345678"
+                    error={props.errors.get("phone").or_else(|| phone_field_error(&t, &state))}
+                    help={t.t("phone-help")}
+                />
+            </div>
+            <fieldset class="mt-2" aria-describedby={address_error.is_some().then_some("user-address-error")}>
+                <legend class="text-sm font-medium text-gray-700 dark:text-gray-200 mb-1">{ t.t("field-address") }</legend>
+                <div class="flex flex-wrap items-start">
+                    <TextField
+                        id="user-street"
+                        label={t.t("field-street")}
+                        value={props.address.street.clone()}
+                        oninput={address_input(|address, value| address.street = value)}
+                    />
+                    <TextField
+                        id="user-complement"
+                        label={t.t("field-complement")}
+                        value={props.address.complement.clone().unwrap_or_default()}
+                        oninput={address_input(|address, value| address.complement = optional_text(value))}
+                    />
+                    <TextField
+                        id="user-city"
+                        label={t.t("field-city")}
+                        value={props.address.city.clone()}
+                        oninput={address_input(|address, value| address.city = value)}
+                    />
+                    <TextField
+                        id="user-region"
+                        label={t.t("field-region")}
+                        value={props.address.region.clone().unwrap_or_default()}
+                        oninput={address_input(|address, value| address.region = optional_text(value))}
+                    />
+                    <TextField
+                        id="user-postal-code"
+                        label={t.t("field-postal-code")}
+                        value={props.address.postal_code.clone()}
+                        oninput={address_input(|address, value| address.postal_code = value)}
+                    />
+                    <TextField
+                        id="user-country"
+                        label={t.t("field-country")}
+                        value={props.address.country.clone()}
+                        oninput={address_input(|address, value| address.country = mask_country(&value))}
+                        placeholder="BR"
+                    />
+                </div>
+                if let Some(error) = address_error {
+                    <p id="user-address-error" class="text-sm text-red-500 mt-1">{ error }</p>
+                }
+            </fieldset>
+        </>
+    }
+}
+
+// Props for UsernameField component
+#[derive(Properties, PartialEq, Clone)]
+pub struct UsernameFieldProps {
+    pub username: String,
+    pub locked: bool,
+    // Answer of the availability check, if any
+    pub taken: Option<bool>,
+    pub on_change: Callback<String>,
+    // Error the backend reported, shown over the live feedback
+    #[prop_or_default]
+    pub error: Option<String>,
+}
+
+// Username input (masked while typing) with live availability feedback;
+// read-only once the user has a username, since it never changes
+#[function_component(UsernameField)]
+pub fn username_field(props: &UsernameFieldProps) -> Html {
+    let t = use_translation();
+    let state = UserFormState {
+        username: props.username.clone(),
+        username_locked: props.locked,
+        ..UserFormState::new()
+    };
+    let (error, help) = username_field_feedback(&t, &state, props.taken);
+
+    let on_input = {
+        let callback = props.on_change.clone();
+        Callback::from(move |value: String| callback.emit(mask_username(&value)))
+    };
+
+    html! {
+        <div class="flex flex-wrap items-start mt-2">
+            <TextField
+                id="user-username"
+                label={t.t("field-username")}
+                value={props.username.clone()}
+                oninput={on_input}
+                placeholder="ada-lovelace"
+                readonly={props.locked}
+                error={props.error.clone().or(error)}
+                help={help}
+            />
+        </div>
+    }
+}
+
+#[function_component(UserForm)]
+pub fn user_form(props: &UserFormProps) -> Html {
+    let t = use_translation();
+    let show_password = use_state(|| false);
+    let email_taken = use_email_taken(&props.email, !props.is_editing);
+    let picks_username = props.on_username_change.is_some() && !props.username_locked;
+    let username_taken = use_username_taken(&props.username, picks_username);
+    let on_toggle_password = {
+        let show_password = show_password.clone();
+        Callback::from(move |_| show_password.set(!*show_password))
+    };
+
+    let on_generate_password = {
+        let on_password_change = props.on_password_change.clone();
+        let show_password = show_password.clone();
+        Callback::from(move |_| {
+            if let Some(password) = random_password() {
+                // Reveal the generated password so the user can note it down
+                show_password.set(true);
+                on_password_change.emit(password);
+            }
+        })
+    };
+
+    let on_submit = {
+        let callback = props.on_submit.clone();
+        Callback::from(move |_| callback.emit(()))
+    };
+
+    html! {
+        <div class="mb-4" onkeydown={history_keydown(props.on_history.clone())}>
+            <div class="flex flex-wrap items-start">
+                <TextField
+                    id="user-name"
+                    label={t.t("field-name")}
+                    value={props.name.clone()}
+                    oninput={props.on_name_change.clone()}
+                    required=true
+                    error={props.errors.get("name")}
+                />
+                <TextField
+                    id="user-email"
+                    label={t.t("field-email")}
+                    input_type="email"
+                    value={props.email.clone()}
+                    oninput={props.on_email_change.clone()}
+                    required=true
+                    error={props.errors.get("email")
+                        .or_else(|| email_field_error(&t, &props.email))
+                        .or_else(|| email_taken.then(|| t.t("email-taken")))}
+                />
+                <TextField
+                    id="user-password"
+                    label={t.t("field-password")}
+                    input_type={if *show_password { "text" } else { "password" }}
+                    value={props.password.clone()}
+                    oninput={props.on_password_change.clone()}
+                    required=true
+                    error={props.errors.get("password")}
+                    help={t.t_with("password-help", &[("min", MIN_PASSWORD_LENGTH.into())])}
+                />
+                <button
+                    type="button"
+                    onclick={on_toggle_password}
+                    aria-pressed={if *show_password { "true" } else { "false" }}
+                    aria-controls="user-password"
+                    class="mt-6 border rounded py-2 px-3 mr-2 text-gray-700 hover:bg-gray-100 dark:text-gray-200 dark:border-gray-600 dark:hover:bg-gray-700"
+                >
+                    { t.t(if *show_password { "password-hide" } else { "password-show" }) }
+                </button>
+                <button
+                    type="button"
+                    onclick={on_generate_password}
+                    class="mt-6 border rounded py-2 px-3 mr-2 text-gray-700 hover:bg-gray-100 dark:text-gray-200 dark:border-gray-600 dark:hover:bg-gray-700"
+                >
+                    { t.t("password-generate") }
+                </button>
+                <button
+                    type="button"
+                    onclick={on_submit}
+                    class="mt-6 bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded"
+                >
+                    { props.submit_label.clone().unwrap_or_else(|| {
+                        t.t(if props.is_editing { "user-update" } else { "user-create" })
+                    }) }
+                </button>
+            </div>
+            if let Some(on_username_change) = &props.on_username_change {
+                <UsernameField
+                    username={props.username.clone()}
+                    locked={props.username_locked}
+                    taken={username_taken}
+                    on_change={on_username_change.clone()}
+                    error={props.errors.get("username")}
+                />
+            }
+            if let (Some(on_phone_change), Some(on_address_change)) = (&props.on_phone_change, &props.on_address_change) {
+                <ContactFields
+                    phone={props.phone.clone()}
+                    address={props.address.clone()}
+                    on_phone_change={on_phone_change.clone()}
+                    on_address_change={on_address_change.clone()}
+                    errors={props.errors.clone()}
+                />
+            }
+            if !props.password.is_empty() {
+                <PasswordStrengthMeter password={props.password.clone()} />
+            }
+            <NotificationBanner
+                notification={props.notification.clone()}
+                on_dismiss={props.on_dismiss.clone()}
+            />
+        </div>
+    }
+}
+
+// Translated errors blocking the current wizard step, by field
+pub fn wizard_step_errors(t: &Translator, state: &UserFormState) -> FieldErrors {
+    let mut errors = FieldErrors::default();
+    for (field, message_id) in state.step_errors() {
+        errors.set(field, t.t_with(message_id, &[("min", MIN_PASSWORD_LENGTH.into())]));
+    }
+    errors
+}
+
+// Props for SignupProgress component
+#[derive(Properties, PartialEq)]
+pub struct SignupProgressProps {
+    pub step: SignupStep,
+}
+
+// Numbered steps of the signup wizard with the current one highlighted
+#[function_component(SignupProgress)]
+pub fn signup_progress(props: &SignupProgressProps) -> Html {
+    let t = use_translation();
+    let current = props.step.index();
+
+    html! {
+        <nav aria-label={t.t_with("signup-progress", &[("step", (current + 1).into()), ("total", SignupStep::ALL.len().into())])} class="mb-4">
+            <ol class="flex flex-wrap gap-4">
+                { for SignupStep::ALL.iter().enumerate().map(|(index, step)| {
+                    let class = match index.cmp(&current) {
+                        std::cmp::Ordering::Less => "text-green-600 dark:text-green-400",
+                        std::cmp::Ordering::Equal => "font-bold text-blue-600 dark:text-blue-400",
+                        std::cmp::Ordering::Greater => "text-gray-500 dark:text-gray-400",
+                    };
+                    html! {
+                        <li class={class} aria-current={(index == current).then_some("step")}>
+                            { format!("{}. {}", index + 1, t.t(step.label_id())) }
+                        </li>
+                    }
+                })}
+            </ol>
+        </nav>
+    }
+}
+
+// Props for SignupWizard component
+#[derive(Properties, PartialEq)]
+pub struct SignupWizardProps {
+    pub state: UserFormState,
+    pub on_name_change: Callback<String>,
+    pub on_email_change: Callback<String>,
+    pub on_password_change: Callback<String>,
+    pub on_back: Callback<()>,
+    pub on_next: Callback<()>,
+    pub on_submit: Callback<()>,
+    #[prop_or_default]
+    pub notification: Option<Notification>,
+    #[prop_or_default]
+    pub on_dismiss: Callback<()>,
+    // Errors of the current step, from the wizard or the backend
+    #[prop_or_default]
+    pub errors: FieldErrors,
+    #[prop_or_default]
+    pub on_history: Option<Callback<HistoryStep>>,
+    // Shown on the confirmation step above the submit button, e.g. the CAPTCHA
+    #[prop_or_default]
+    pub children: Html,
+}
+
+// Signup form split into account details, password and confirmation steps
+#[function_component(SignupWizard)]
+pub fn signup_wizard(props: &SignupWizardProps) -> Html {
+    let t = use_translation();
+    let show_password = use_state(|| false);
+    let state = &props.state;
+    let email_taken = use_email_taken(&state.email, state.step == SignupStep::Account);
+
+    let on_toggle_password = {
+        let show_password = show_password.clone();
+        Callback::from(move |_| show_password.set(!*show_password))
+    };
+    let on_generate_password = {
+        let on_password_change = props.on_password_change.clone();
+        let show_password = show_password.clone();
+        Callback::from(move |_| {
+            if let Some(password) = random_password() {
+                show_password.set(true);
+                on_password_change.emit(password);
+            }
+        })
+    };
+    let emit = |callback: &Callback<()>| {
+        let callback = callback.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+    let secondary = "border rounded py-2 px-3 mr-2 text-gray-700 hover:bg-gray-100 dark:text-gray-200 dark:border-gray-600 dark:hover:bg-gray-700";
+
+    let step = match state.step {
+        SignupStep::Account => html! {
+            <div class="flex flex-wrap items-start">
+                <TextField
+                    id="user-name"
+                    label={t.t("field-name")}
+                    value={state.name.clone()}
+                    oninput={props.on_name_change.clone()}
+                    required=true
+                    error={props.errors.get("name")}
+                />
+                <TextField
+                    id="user-email"
+                    label={t.t("field-email")}
+                    input_type="email"
+                    value={state.email.clone()}
+                    oninput={props.on_email_change.clone()}
+                    required=true
+                    error={props.errors.get("email")
+                        .or_else(|| email_field_error(&t, &state.email))
+                        .or_else(|| email_taken.then(|| t.t("email-taken")))}
+                />
+            </div>
+        },
+        SignupStep::Password => html! {
+            <>
+                <div class="flex flex-wrap items-start">
+                    <TextField
+                        id="user-password"
+                        label={t.t("field-password")}
+                        input_type={if *show_password { "text" } else { "password" }}
+                        value={state.password.clone()}
+                        oninput={props.on_password_change.clone()}
+                        required=true
+                        error={props.errors.get("password")}
+                        help={t.t_with("password-help", &[("min", MIN_PASSWORD_LENGTH.into())])}
+                    />
+                    <button
+                        type="button"
+                        onclick={on_toggle_password}
+                        aria-pressed={if *show_password { "true" } else { "false" }}
+                        aria-controls="user-password"
+                        class={classes!("mt-6", secondary)}
+                    >
+                        { t.t(if *show_password { "password-hide" } else { "password-show" }) }
+                    </button>
+                    <button type="button" onclick={on_generate_password} class={classes!("mt-6", secondary)}>
+                        { t.t("password-generate") }
+                    </button>
+                </div>
+                if !state.password.is_empty() {
+                    <PasswordStrengthMeter password={state.password.clone()} />
+                }
+            </>
+        },
+        SignupStep::Confirm => html! {
+            <>
+                <p class="mb-2 text-gray-700 dark:text-gray-300">{ t.t("signup-review") }</p>
+                <dl class="grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 mb-4">
+                    <dt class="font-semibold">{ t.t("field-name") }</dt>
+                    <dd>{ &state.name }</dd>
+                    <dt class="font-semibold">{ t.t("field-email") }</dt>
+                    <dd>{ &state.email }</dd>
+                    <dt class="font-semibold">{ t.t("field-password") }</dt>
+                    <dd>{ t.t_with("signup-password-hidden", &[("length", state.password.chars().count().into())]) }</dd>
+                </dl>
+                { props.children.clone() }
+            </>
+        },
+    };
+
+    html! {
+        <div class="mb-4" onkeydown={history_keydown(props.on_history.clone())}>
+            <SignupProgress step={state.step} />
+            <h2 class="text-2xl font-semibold mb-2">{ t.t(state.step.label_id()) }</h2>
+            { step }
+            <div class="mt-4">
+                if state.step.previous().is_some() {
+                    <button type="button" onclick={emit(&props.on_back)} class={secondary}>
+                        { t.t("signup-back") }
+                    </button>
+                }
+                if state.step.next().is_some() {
+                    <button
+                        type="button"
+                        onclick={emit(&props.on_next)}
+                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded"
+                    >
+                        { t.t("signup-next") }
+                    </button>
+                } else {
+                    <button
+                        type="button"
+                        onclick={emit(&props.on_submit)}
+                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded"
+                    >
+                        { t.t("signup-submit") }
+                    </button>
+                }
+            </div>
+            <NotificationBanner
+                notification={props.notification.clone()}
+                on_dismiss={props.on_dismiss.clone()}
+            />
+        </div>
+    }
+}
+
+// How long success notifications stay on screen
+pub const NOTIFICATION_DISMISS_MS: u32 = 4_000;
+
+// Tailwind colours of a notification level
+pub fn notification_classes(level: NotificationLevel) -> &'static str {
+    match level {
+        NotificationLevel::Success => "border-green-300 bg-green-50 text-green-700 dark:bg-gray-800 dark:border-green-800 dark:text-green-400",
+        NotificationLevel::Warning => "border-yellow-300 bg-yellow-50 text-yellow-800 dark:bg-gray-800 dark:border-yellow-700 dark:text-yellow-300",
+        NotificationLevel::Error => "border-red-300 bg-red-50 text-red-700 dark:bg-gray-800 dark:border-red-800 dark:text-red-400",
+    }
+}
+
+// Props for NotificationBanner component
+#[derive(Properties, PartialEq)]
+pub struct NotificationBannerProps {
+    pub notification: Option<Notification>,
+    pub on_dismiss: Callback<()>,
+}
+
+// Styled notification with a close button; successes dismiss themselves
+#[function_component(NotificationBanner)]
+pub fn notification_banner(props: &NotificationBannerProps) -> Html {
+    let t = use_translation();
+
+    {
+        let on_dismiss = props.on_dismiss.clone();
+        use_effect_with(props.notification.clone(), move |notification| {
+            let timeout = notification
+                .as_ref()
+                .filter(|notification| notification.auto_dismisses())
+                .map(|_| Timeout::new(NOTIFICATION_DISMISS_MS, move || on_dismiss.emit(())));
+            // A newer notification cancels the pending dismissal
+            move || drop(timeout)
+        });
+    }
+
+    let on_close = {
+        let callback = props.on_dismiss.clone();
+        Callback::from(move |_| callback.emit(()))
+    };
+
+    html! {
+        // Kept mounted so screen readers announce each new notification
+        <div aria-live="polite">
+            if let Some(notification) = &props.notification {
+                <div
+                    role={if notification.level == NotificationLevel::Error { "alert" } else { "status" }}
+                    class={classes!("flex", "items-center", "justify-between", "mt-2", "px-4", "py-2", "rounded", "border", notification_classes(notification.level))}
+                >
+                    <span>{ &notification.text }</span>
+                    <button
+                        type="button"
+                        onclick={on_close}
+                        aria-label={t.t("dismiss")}
+                        class={classes!("ml-4", "font-bold", FOCUS_RING)}
+                    >
+                        { "×" }
+                    </button>
+                </div>
+            }
+        </div>
+    }
+}
+
+// Generate a strong password using the browser's cryptographic random source
+fn random_password() -> Option<String> {
+    let crypto = web_sys::window()?.crypto().ok()?;
+    let mut bytes = [0u8; GENERATED_PASSWORD_LENGTH];
+    crypto.get_random_values_with_u8_array(&mut bytes).ok()?;
+    Some(generate_password(&bytes))
+}
+
+// Props for PasswordStrengthMeter component
+#[derive(Properties, PartialEq)]
+pub struct PasswordStrengthMeterProps {
+    pub password: String,
+}
+
+// Tailwind width and color classes for each strength level
+pub fn strength_bar_classes(strength: PasswordStrength) -> (&'static str, &'static str) {
+    match strength {
+        PasswordStrength::Weak => ("w-1/3", "bg-red-500"),
+        PasswordStrength::Fair => ("w-2/3", "bg-yellow-500"),
+        PasswordStrength::Strong => ("w-full", "bg-green-500"),
+    }
+}
+
+// Position of a strength on the meter, from 1 (weak) to 3 (strong)
+pub fn strength_level(strength: PasswordStrength) -> u8 {
+    match strength {
+        PasswordStrength::Weak => 1,
+        PasswordStrength::Fair => 2,
+        PasswordStrength::Strong => 3,
+    }
+}
+
+#[function_component(PasswordStrengthMeter)]
+pub fn password_strength_meter(props: &PasswordStrengthMeterProps) -> Html {
+    let t = use_translation();
+    let strength = password_strength(&props.password);
+    let (width, color) = strength_bar_classes(strength);
+
+    html! {
+        <div class="mt-2 w-64">
+            <div
+                role="meter"
+                aria-label={t.t("a11y-password-strength")}
+                aria-valuemin="1"
+                aria-valuemax="3"
+                aria-valuenow={strength_level(strength).to_string()}
+                aria-valuetext={strength.label()}
+                class="h-2 bg-gray-200 dark:bg-gray-700 rounded"
+            >
+                <div class={classes!("h-2", "rounded", width, color)}></div>
+            </div>
+            <p class="text-sm text-gray-600 dark:text-gray-400 mt-1">
+                { t.t_with("password-strength", &[("strength", strength.label().to_lowercase().into())]) }
+            </p>
+        </div>
+    }
+}
+
+// Props for UserList component
+#[derive(Properties, PartialEq)]
+pub struct UserListProps {
+    pub users: Vec<User>,
+    pub on_delete: Callback<i32>,
+    pub on_edit: Callback<i32>,
+    // Requests switching a user to the given status
+    pub on_set_status: Callback<(i32, UserStatus)>,
+    // Ids of the users selected for a bulk action
+    #[prop_or_default]
+    pub selected: Vec<i32>,
+    // Selection checkboxes are only shown when set
+    #[prop_or_default]
+    pub on_toggle_select: Option<Callback<i32>>,
+}
+
+// Props for ColumnSettingsMenu component
+#[derive(Properties, PartialEq)]
+pub struct ColumnSettingsMenuProps {
+    pub settings: ColumnSettings,
+    pub on_change: Callback<ColumnSettings>,
+}
+
+// Popover choosing which columns of the user list are shown and which are wide
+#[function_component(ColumnSettingsMenu)]
+pub fn column_settings_menu(props: &ColumnSettingsMenuProps) -> Html {
+    let t = use_translation();
+    let checkbox = |column: Column, checked: bool, toggled: ColumnSettings| {
+        let on_change = props.on_change.clone();
+        html! {
+            <label class="flex items-center gap-2 py-1">
+                <input
+                    type="checkbox"
+                    checked={checked}
+                    onchange={Callback::from(move |_: Event| on_change.emit(toggled.clone()))}
+                    class={FOCUS_RING}
+                />
+                { t.t(column.label_id()) }
+            </label>
+        }
+    };
+
+    html! {
+        <details class="relative">
+            <summary class={classes!("cursor-pointer", "border", "rounded", "px-2", "py-1", "text-gray-700", "dark:text-gray-200", "dark:border-gray-600", FOCUS_RING)}>
+                { t.t("column-settings") }
+            </summary>
+            <div class="absolute right-0 z-10 mt-1 w-56 p-3 border rounded shadow bg-white dark:bg-gray-800 dark:border-gray-700">
+                <fieldset class="mb-2">
+                    <legend class="font-semibold">{ t.t("column-settings-visible") }</legend>
+                    { for Column::HIDEABLE.into_iter().map(|column| {
+                        checkbox(column, props.settings.is_visible(column), props.settings.toggle_visible(column))
+                    })}
+                </fieldset>
+                <fieldset>
+                    <legend class="font-semibold">{ t.t("column-settings-wide") }</legend>
+                    { for Column::WIDENABLE.into_iter().map(|column| {
+                        checkbox(column, props.settings.is_wide(column), props.settings.toggle_wide(column))
+                    })}
+                </fieldset>
+            </div>
+        </details>
+    }
+}
+
+// Parse a `YYYY-MM-DDTHH:MM:SSZ` timestamp into seconds since the Unix epoch
+pub fn parse_utc_timestamp(value: &str) -> Option<i64> {
+    let field = |range: std::ops::Range<usize>| value.get(range)?.parse::<i64>().ok();
+    if value.len() != 20 || !value.ends_with('Z') {
+        return None;
+    }
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+
+    // Days from civil date (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+// Address on three lines: street, city with postal code and region, country
+pub fn format_address(address: &Address) -> String {
+    let join = |parts: [Option<&str>; 2], separator: &str| {
+        parts
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(separator)
+    };
+    let street = join([Some(&address.street), address.complement.as_deref()], ", ");
+    let city = join([Some(&address.postal_code), Some(&address.city)], " ");
+    let city = join([Some(&city), address.region.as_deref()], ", ");
+    [street, city, address.country.clone()].join("\n")
+}
+
+// Human friendly "Last seen" text relative to `now` (seconds since the epoch)
+pub fn format_last_seen(t: &Translator, last_login_at: Option<&str>, now: i64) -> String {
+    let Some(value) = last_login_at else {
+        return t.t("last-seen-never");
+    };
+    let Some(timestamp) = parse_utc_timestamp(value) else {
+        return value.to_string();
+    };
+
+    let ago = |id: &str, count: i64| t.t_with(id, &[("count", count.into())]);
+    match (now - timestamp).max(0) {
+        seconds if seconds < 60 => t.t("last-seen-now"),
+        seconds if seconds < 3_600 => ago("last-seen-minutes", seconds / 60),
+        seconds if seconds < 86_400 => ago("last-seen-hours", seconds / 3_600),
+        seconds if seconds < 30 * 86_400 => ago("last-seen-days", seconds / 86_400),
+        _ => value[..10].to_string(),
+    }
+}
+
+// Sort order of the "Last seen" column
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LastSeenSort {
+    #[default]
+    None,
+    MostRecent,
+    LeastRecent,
+}
+
+impl LastSeenSort {
+    pub fn is_none(&self) -> bool {
+        *self == LastSeenSort::None
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            LastSeenSort::None => LastSeenSort::MostRecent,
+            LastSeenSort::MostRecent => LastSeenSort::LeastRecent,
+            LastSeenSort::LeastRecent => LastSeenSort::None,
+        }
+    }
+
+    // Value of the column's `aria-sort` attribute
+    pub fn aria_sort(&self) -> &'static str {
+        match self {
+            LastSeenSort::None => "none",
+            LastSeenSort::MostRecent => "descending",
+            LastSeenSort::LeastRecent => "ascending",
+        }
+    }
+
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            LastSeenSort::None => "",
+            LastSeenSort::MostRecent => " ▼",
+            LastSeenSort::LeastRecent => " ▲",
+        }
+    }
+}
+
+// Users in the requested order; users who never logged in always come last
+pub fn sort_by_last_seen(users: &[User], order: LastSeenSort) -> Vec<User> {
+    let mut sorted = users.to_vec();
+    // RFC 3339 UTC timestamps of equal length sort chronologically as strings
+    match order {
+        LastSeenSort::None => {}
+        LastSeenSort::MostRecent => sorted.sort_by(|a, b| {
+            b.last_login_at.is_some().cmp(&a.last_login_at.is_some())
+                .then_with(|| b.last_login_at.cmp(&a.last_login_at))
+        }),
+        LastSeenSort::LeastRecent => sorted.sort_by(|a, b| {
+            b.last_login_at.is_some().cmp(&a.last_login_at.is_some())
+                .then_with(|| a.last_login_at.cmp(&b.last_login_at))
+        }),
+    }
+    sorted
+}
+
+// Users having `tag`, or all of them without one
+pub fn filter_by_tag(users: &[User], tag: Option<&str>) -> Vec<User> {
+    users
+        .iter()
+        .filter(|user| tag.is_none_or(|tag| user.tags.iter().any(|t| t == tag)))
+        .cloned()
+        .collect()
+}
+
+// Whether the name, email or username of `user` contains `search`, ignoring case
+pub fn matches_search(user: &User, search: &str) -> bool {
+    let search = search.trim().to_lowercase();
+    [Some(&user.name), Some(&user.email), user.username.as_ref()]
+        .into_iter()
+        .flatten()
+        .any(|value| value.to_lowercase().contains(&search))
+}
+
+// Users shown by `view`: those matching its search, tag and status, in its order
+pub fn apply_view(users: &[User], view: &ListView) -> Vec<User> {
+    filter_by_tag(&sort_by_last_seen(users, view.sort), view.tag.as_deref())
+        .into_iter()
+        .filter(|user| view.status.is_none_or(|status| user.status == status))
+        .filter(|user| matches_search(user, &view.search))
+        .collect()
+}
+
+// Every tag of `users` with how many have it, most used first
+pub fn count_tags(users: &[User]) -> Vec<(String, usize)> {
+    let mut counts = std::collections::BTreeMap::<&str, usize>::new();
+    for tag in users.iter().flat_map(|user| &user.tags) {
+        *counts.entry(tag).or_default() += 1;
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().map(|(tag, count)| (tag.to_string(), count)).collect();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+}
+
+// Props for TagChip component
+#[derive(Properties, PartialEq)]
+pub struct TagChipProps {
+    pub tag: String,
+    // Highlighted while the list is filtered by this tag
+    #[prop_or_default]
+    pub active: bool,
+    // Number of users with the tag, shown in the filter bar
+    #[prop_or_default]
+    pub count: Option<usize>,
+    // Chips are buttons when set, plain labels otherwise
+    #[prop_or_default]
+    pub on_click: Option<Callback<String>>,
+}
+
+// Small rounded label of one tag
+#[function_component(TagChip)]
+pub fn tag_chip(props: &TagChipProps) -> Html {
+    let t = use_translation();
+    let colors = if props.active {
+        classes!("bg-blue-600", "text-white")
+    } else {
+        classes!("bg-blue-100", "text-blue-800", "dark:bg-blue-900", "dark:text-blue-100")
+    };
+    let label = html! {
+        <>
+            { props.tag.clone() }
+            if let Some(count) = props.count {
+                <span class="ml-1 opacity-75">{ count }</span>
+            }
+        </>
+    };
+
+    match &props.on_click {
+        Some(on_click) => {
+            let onclick = {
+                let on_click = on_click.clone();
+                let tag = props.tag.clone();
+                Callback::from(move |_: MouseEvent| on_click.emit(tag.clone()))
+            };
+            html! {
+                <button
+                    type="button"
+                    {onclick}
+                    aria-pressed={if props.active { "true" } else { "false" }}
+                    title={t.t_with("tag-filter-by", &[("tag", props.tag.clone().into())])}
+                    class={classes!("text-xs", "font-semibold", "px-2", "py-0.5", "rounded-full", "hover:underline", colors, FOCUS_RING)}
+                >
+                    { label }
+                </button>
+            }
+        }
+        None => html! {
+            <span class={classes!("text-xs", "font-semibold", "px-2", "py-0.5", "rounded-full", colors)}>{ label }</span>
+        },
+    }
+}
+
+// Props for TagFilterBar component
+#[derive(Properties, PartialEq)]
+pub struct TagFilterBarProps {
+    // Tags of the listed users with their counts
+    pub tags: Vec<(String, usize)>,
+    pub active: Option<String>,
+    // Emits the clicked tag, or None to show every user again
+    pub on_change: Callback<Option<String>>,
+}
+
+// Chips of every tag in the list; clicking one narrows the list to its users,
+// clicking it again (or "Show all") lifts the filter
+#[function_component(TagFilterBar)]
+pub fn tag_filter_bar(props: &TagFilterBarProps) -> Html {
+    let t = use_translation();
+    if props.tags.is_empty() {
+        return html! {};
+    }
+    let on_click = {
+        let on_change = props.on_change.clone();
+        let active = props.active.clone();
+        Callback::from(move |tag: String| {
+            on_change.emit((active.as_deref() != Some(tag.as_str())).then_some(tag))
+        })
+    };
+    let on_clear = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |_: MouseEvent| on_change.emit(None))
+    };
+
+    html! {
+        <div role="group" aria-label={t.t("tags-filter")} class="flex flex-wrap items-center gap-2 mb-2 print:hidden">
+            <span class="text-sm text-gray-600 dark:text-gray-300">{ t.t("tags-filter") }</span>
+            { for props.tags.iter().map(|(tag, count)| html! {
+                <TagChip
+                    tag={tag.clone()}
+                    count={*count}
+                    active={props.active.as_deref() == Some(tag.as_str())}
+                    on_click={on_click.clone()}
+                />
+            })}
+            if props.active.is_some() {
+                <button
+                    type="button"
+                    onclick={on_clear}
+                    class={classes!("text-sm", "text-blue-600", "dark:text-blue-400", "hover:underline", FOCUS_RING)}
+                >
+                    { t.t("tags-filter-clear") }
+                </button>
+            }
+        </div>
+    }
+}
+
+// Props for ListFilters component
+#[derive(Properties, PartialEq)]
+pub struct ListFiltersProps {
+    pub view: ListView,
+    // Emits the view with the changed search text or status
+    pub on_change: Callback<ListView>,
+}
+
+// Search box and status filter of the user list
+#[function_component(ListFilters)]
+pub fn list_filters(props: &ListFiltersProps) -> Html {
+    let t = use_translation();
+    let oninput = {
+        let view = props.view.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_change.emit(ListView { search: input.value(), ..view.clone() });
+        })
+    };
+    let onchange = {
+        let view = props.view.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let status = match select.value().as_str() {
+                "active" => Some(UserStatus::Active),
+                "suspended" => Some(UserStatus::Suspended),
+                _ => None,
+            };
+            on_change.emit(ListView { status, ..view.clone() });
+        })
+    };
+    let option = |value: &'static str, status: Option<UserStatus>, label: String| html! {
+        <option value={value} selected={props.view.status == status}>{ label }</option>
+    };
+
+    html! {
+        <div class="flex flex-wrap items-center gap-2 mb-2 print:hidden">
+            <label for="user-filter-search" class="sr-only">{ t.t("users-filter-search") }</label>
+            <input
+                id="user-filter-search"
+                type="search"
+                value={props.view.search.clone()}
+                {oninput}
+                placeholder={t.t("users-filter-search")}
+                class={classes!("border", "rounded", "py-1", "px-2", "w-64", "text-gray-900", "dark:bg-gray-900", "dark:text-gray-100", "dark:border-gray-600", FOCUS_RING)}
+            />
+            <label for="user-filter-status" class="sr-only">{ t.t("users-filter-status") }</label>
+            <select
+                id="user-filter-status"
+                {onchange}
+                class={classes!("border", "rounded", "px-2", "py-1", "text-gray-700", "dark:bg-gray-800", "dark:text-gray-200", "dark:border-gray-600", FOCUS_RING)}
+            >
+                { option("", None, t.t("users-filter-status-all")) }
+                { option("active", Some(UserStatus::Active), t.t(status_label_id(UserStatus::Active))) }
+                { option("suspended", Some(UserStatus::Suspended), t.t(status_label_id(UserStatus::Suspended))) }
+            </select>
+        </div>
+    }
+}
+
+// Error shown when saving a view as `name`, if any; saving under the name of
+// an existing view replaces it, so only new names count towards the limit
+pub fn view_name_error(t: &Translator, views: &[SavedView], name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        Some(t.t("view-name-empty"))
+    } else if name.chars().count() > MAX_VIEW_NAME_LENGTH {
+        Some(t.t_with("view-name-too-long", &[("max", MAX_VIEW_NAME_LENGTH.into())]))
+    } else if find_view(views, name).is_none() && views.len() >= MAX_SAVED_VIEWS {
+        Some(t.t_with("views-too-many", &[("max", MAX_SAVED_VIEWS.into())]))
+    } else {
+        None
+    }
+}
+
+// Props for SavedViewsMenu component
+#[derive(Properties, PartialEq)]
+pub struct SavedViewsMenuProps {
+    pub views: Vec<SavedView>,
+    // Name of the view shown, None for the unsaved one
+    pub active: Option<String>,
+    // Emits the chosen view's name, or None for "All users"
+    pub on_select: Callback<Option<String>>,
+    // Emits the trimmed name to save the current search, sort and filters under
+    pub on_save: Callback<String>,
+    pub on_delete: Callback<String>,
+}
+
+// Dropdown switching between saved views, with a name box to save the current
+// one and a button deleting the view shown
+#[function_component(SavedViewsMenu)]
+pub fn saved_views_menu(props: &SavedViewsMenuProps) -> Html {
+    let t = use_translation();
+    let name = use_state(String::new);
+    let error = view_name_error(&t, &props.views, &name);
+    let onchange = {
+        let on_select = props.on_select.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            on_select.emit((!value.is_empty()).then_some(value));
+        })
+    };
+    let oninput = {
+        let name = name.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            name.set(input.value());
+        })
+    };
+    let on_save = {
+        let name = name.clone();
+        let on_save = props.on_save.clone();
+        let valid = error.is_none();
+        Callback::from(move |_: MouseEvent| {
+            if valid {
+                on_save.emit(name.trim().to_string());
+                name.set(String::new());
+            }
+        })
+    };
+
+    html! {
+        <div role="group" aria-label={t.t("views")} class="flex flex-wrap items-center gap-2 mb-2 print:hidden">
+            <label for="saved-view" class="text-sm text-gray-600 dark:text-gray-300">{ t.t("views") }</label>
+            <select
+                id="saved-view"
+                {onchange}
+                class={classes!("border", "rounded", "px-2", "py-1", "text-gray-700", "dark:bg-gray-800", "dark:text-gray-200", "dark:border-gray-600", FOCUS_RING)}
+            >
+                <option value="" selected={props.active.is_none()}>{ t.t("views-none") }</option>
+                { for props.views.iter().map(|view| html! {
+                    <option value={view.name.clone()} selected={props.active.as_deref() == Some(view.name.as_str())}>
+                        { view.name.clone() }
+                    </option>
+                })}
+            </select>
+            if let Some(active) = props.active.clone() {
+                <button
+                    type="button"
+                    onclick={props.on_delete.reform(move |_: MouseEvent| active.clone())}
+                    class={classes!("text-sm", "text-red-600", "dark:text-red-400", "hover:underline", FOCUS_RING)}
+                >
+                    { t.t("views-delete") }
+                </button>
+            }
+            <label for="view-name" class="sr-only">{ t.t("view-name") }</label>
+            <input
+                id="view-name"
+                type="text"
+                value={(*name).clone()}
+                {oninput}
+                placeholder={t.t("view-name")}
+                aria-invalid={if name.is_empty() || error.is_none() { "false" } else { "true" }}
+                aria-describedby={(!name.is_empty() && error.is_some()).then_some("view-name-error")}
+                class={classes!("border", "rounded", "py-1", "px-2", "w-40", "text-gray-900", "dark:bg-gray-900", "dark:text-gray-100", "dark:border-gray-600", FOCUS_RING)}
+            />
+            <button
+                type="button"
+                onclick={on_save}
+                disabled={error.is_some()}
+                class={classes!("border", "rounded", "py-1", "px-3", "text-gray-700", "hover:bg-gray-100", "disabled:opacity-50", "dark:text-gray-200", "dark:border-gray-600", "dark:hover:bg-gray-700", FOCUS_RING)}
+            >
+                { t.t("views-save") }
+            </button>
+            if let Some(error) = error.filter(|_| !name.is_empty()) {
+                <span id="view-name-error" class="text-sm text-red-600 dark:text-red-400">{ error }</span>
+            }
+        </div>
+    }
+}
+
+// Error shown by the tag editor for `input`, if any
+pub fn tags_error(t: &Translator, input: &str) -> Option<String> {
+    tags_error_id(input).map(|id| match id {
+        "tags-too-many" => t.t_with(id, &[("max", MAX_TAGS.into())]),
+        _ => t.t_with(id, &[("max", MAX_TAG_LENGTH.into())]),
+    })
+}
+
+// Props for TagEditor component
+#[derive(Properties, PartialEq)]
+pub struct TagEditorProps {
+    pub tags: Vec<String>,
+    // Emits the raw comma-separated input; only called while it is valid
+    pub on_save: Callback<String>,
+    #[prop_or_default]
+    pub saving: bool,
+}
+
+// Comma-separated tags input with a save button
+#[function_component(TagEditor)]
+pub fn tag_editor(props: &TagEditorProps) -> Html {
+    let t = use_translation();
+    let input = use_state(|| format_tags(&props.tags));
+    {
+        // Show the saved tags again once they come back normalized
+        let input = input.clone();
+        use_effect_with(props.tags.clone(), move |tags| {
+            input.set(format_tags(tags));
+            || ()
+        });
+    }
+    let error = tags_error(&t, &input);
+    let oninput = {
+        let input = input.clone();
+        Callback::from(move |value: String| input.set(value))
+    };
+    let onsubmit = {
+        let input = input.clone();
+        let on_save = props.on_save.clone();
+        let valid = error.is_none();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if valid {
+                on_save.emit((*input).clone());
+            }
+        })
+    };
+
+    html! {
+        <form {onsubmit} class="mt-4 print:hidden">
+            <TextField
+                id="user-tags"
+                label={t.t("field-tags")}
+                value={(*input).clone()}
+                {oninput}
+                help={Some(t.t("tags-help"))}
+                {error}
+                placeholder={AttrValue::from("vip, pioneer")}
+            />
+            <button
+                type="submit"
+                disabled={props.saving || tags_error_id(&input).is_some()}
+                class={classes!("bg-blue-600", "hover:bg-blue-700", "text-white", "font-bold", "py-1", "px-3", "rounded", "disabled:opacity-50", FOCUS_RING)}
+            >
+                { t.t("tags-save") }
+            </button>
+        </form>
+    }
+}
+
+// Admin token sent with user changes, kept for the session only
+#[function_component(AdminTokenField)]
+pub fn admin_token_field() -> Html {
+    let t = use_translation();
+    let token = use_state(|| api::admin_token().unwrap_or_default());
+    let oninput = {
+        let token = token.clone();
+        Callback::from(move |value: String| {
+            api::store_admin_token(&value);
+            token.set(value);
+        })
+    };
+
+    html! {
+        <div class="mb-4 print:hidden">
+            <TextField
+                id="admin-token"
+                label={t.t("field-admin-token")}
+                value={(*token).clone()}
+                {oninput}
+                input_type="password"
+                help={Some(t.t("admin-token-help"))}
+            />
+        </div>
+    }
+}
+
+// Props for BulkActionsToolbar component
+#[derive(Properties, PartialEq)]
+pub struct BulkActionsToolbarProps {
+    // Number of selected users; nothing is shown while it is zero
+    pub count: usize,
+    pub on_set_status: Callback<UserStatus>,
+    // Both emit the tag typed in the toolbar, normalized
+    pub on_add_tag: Callback<String>,
+    pub on_remove_tag: Callback<String>,
+    pub on_clear: Callback<()>,
+    // Set while a bulk update is in flight
+    #[prop_or_default]
+    pub busy: bool,
+    // Further actions on the selection, e.g. deleting it
+    #[prop_or_default]
+    pub children: Html,
+}
+
+// Actions applied to every selected user at once, shown above the list
+// as soon as a row is ticked
+#[function_component(BulkActionsToolbar)]
+pub fn bulk_actions_toolbar(props: &BulkActionsToolbarProps) -> Html {
+    let t = use_translation();
+    let tag = use_state(String::new);
+    if props.count == 0 {
+        return html! {};
+    }
+    let normalized = normalize_tag(&tag);
+    let tag_valid = validate_tag(&normalized).is_ok();
+
+    let oninput = {
+        let tag = tag.clone();
+        Callback::from(move |e: InputEvent| {
+            let input = e.target_dyn_into::<HtmlInputElement>().unwrap();
+            tag.set(input.value());
+        })
+    };
+    let status_button = |status: UserStatus, label: &str| {
+        let on_set_status = props.on_set_status.clone();
+        html! {
+            <button
+                type="button"
+                disabled={props.busy}
+                onclick={Callback::from(move |_: MouseEvent| on_set_status.emit(status))}
+                class={classes!("bg-gray-600", "hover:bg-gray-700", "text-white", "py-1", "px-3", "rounded", "disabled:opacity-50", FOCUS_RING)}
+            >
+                { t.t(label) }
+            </button>
+        }
+    };
+    let tag_button = |callback: &Callback<String>, label: &str| {
+        let callback = callback.clone();
+        let tag = tag.clone();
+        let normalized = normalized.clone();
+        html! {
+            <button
+                type="button"
+                disabled={props.busy || !tag_valid}
+                onclick={Callback::from(move |_: MouseEvent| {
+                    callback.emit(normalized.clone());
+                    tag.set(String::new());
+                })}
+                class={classes!("bg-blue-600", "hover:bg-blue-700", "text-white", "py-1", "px-3", "rounded", "disabled:opacity-50", FOCUS_RING)}
+            >
+                { t.t(label) }
+            </button>
+        }
+    };
+    let on_clear = {
+        let on_clear = props.on_clear.clone();
+        Callback::from(move |_: MouseEvent| on_clear.emit(()))
+    };
+
+    html! {
+        <div
+            role="toolbar"
+            aria-label={t.t("bulk-actions")}
+            class="flex flex-wrap items-center gap-2 mb-4 p-2 rounded bg-blue-50 dark:bg-gray-800 print:hidden"
+        >
+            <span role="status" class="font-semibold text-gray-900 dark:text-gray-100">
+                { t.t_with("bulk-selected", &[("count", props.count.into())]) }
+            </span>
+            { status_button(UserStatus::Suspended, "action-suspend") }
+            { status_button(UserStatus::Active, "action-activate") }
+            <label for="bulk-tag" class="sr-only">{ t.t("bulk-tag-label") }</label>
+            <input
+                id="bulk-tag"
+                type="text"
+                value={(*tag).clone()}
+                {oninput}
+                placeholder={t.t("bulk-tag-label")}
+                aria-invalid={if tag.is_empty() || tag_valid { "false" } else { "true" }}
+                class={classes!("border", "rounded", "py-1", "px-2", "w-32", "text-gray-900", "dark:bg-gray-900", "dark:text-gray-100", FOCUS_RING)}
+            />
+            { tag_button(&props.on_add_tag, "bulk-add-tag") }
+            { tag_button(&props.on_remove_tag, "bulk-remove-tag") }
+            { props.children.clone() }
+            <button
+                type="button"
+                onclick={on_clear}
+                class={classes!("ml-auto", "text-sm", "text-blue-600", "dark:text-blue-400", "hover:underline", FOCUS_RING)}
+            >
+                { t.t("bulk-clear") }
+            </button>
+        </div>
+    }
+}
+
+#[function_component(UserList)]
+pub fn user_list(props: &UserListProps) -> Html {
+    let t = use_translation();
+    let saved_views = use_state(SavedView::load_all);
+    // Search, sort, filters and page, read from the query string so shared
+    // links and the back and forward buttons restore them
+    let location = use_location();
+    let navigator = use_navigator();
+    let url_query = location
+        .and_then(|location| location.query::<UserListQuery>().ok())
+        .unwrap_or_default();
+    let url_state = url_query.to_state(&saved_views);
+    let list = use_state(|| url_state.clone());
+    {
+        let list = list.clone();
+        use_effect_with(url_state.clone(), move |url_state| {
+            if *list != *url_state {
+                list.set(url_state.clone());
+            }
+            || ()
+        });
+    }
+    // ...and written back once changes pause; a URL that already shows the
+    // same list, e.g. a bare `?view=<name>`, is replaced rather than stacked
+    {
+        let navigator = navigator.clone();
+        use_effect_with((*list).clone(), move |list| {
+            let query = UserListQuery::of(list);
+            let replace = url_state == *list;
+            let timeout = (query != url_query).then(|| {
+                Timeout::new(LIST_QUERY_DEBOUNCE_MS, move || {
+                    if let Some(navigator) = navigator {
+                        let _ = match replace {
+                            true => navigator.replace_with_query(&Route::Users, &query),
+                            false => navigator.push_with_query(&Route::Users, &query),
+                        };
+                    }
+                })
+            });
+            move || drop(timeout)
+        });
+    }
+    let view = list.view.clone();
+    let on_view_change = {
+        let list = list.clone();
+        Callback::from(move |changed: ListView| list.set(list.with_view(changed)))
+    };
+    let on_sort = {
+        let list = list.clone();
+        Callback::from(move |_| list.set(list.with_view(ListView { sort: list.view.sort.next(), ..list.view.clone() })))
+    };
+    // Tag the list is narrowed to, set by clicking a chip
+    let on_tag_filter = {
+        let list = list.clone();
+        Callback::from(move |tag: Option<String>| list.set(list.with_view(ListView { tag, ..list.view.clone() })))
+    };
+    let on_tag_click = {
+        let on_tag_filter = on_tag_filter.clone();
+        Callback::from(move |tag: String| on_tag_filter.emit(Some(tag)))
+    };
+    let on_page = {
+        let list = list.clone();
+        Callback::from(move |page: usize| list.set(list.with_page(page)))
+    };
+    let users = apply_view(&props.users, &view);
+    let pages = page_count(users.len());
+    let page = list.page.min(pages - 1);
+    // Saved views are kept like the column settings
+    let on_select_view = {
+        let list = list.clone();
+        let saved_views = saved_views.clone();
+        Callback::from(move |name: Option<String>| {
+            let saved = name.as_deref().and_then(|name| find_view(&saved_views, name));
+            list.set(saved.map(ListState::of_saved).unwrap_or_default());
+        })
+    };
+    let on_save_view = {
+        let list = list.clone();
+        let saved_views = saved_views.clone();
+        Callback::from(move |name: String| {
+            let views = with_view(&saved_views, SavedView { name: name.clone(), view: list.view.clone() });
+            SavedView::store_all(&views);
+            saved_views.set(views);
+            list.set(list.with_saved_view(Some(name)));
+        })
+    };
+    let on_delete_view = {
+        let list = list.clone();
+        let saved_views = saved_views.clone();
+        Callback::from(move |name: String| {
+            let views = without_view(&saved_views, &name);
+            SavedView::store_all(&views);
+            saved_views.set(views);
+            list.set(list.with_saved_view(None));
+        })
+    };
+    // Restored from the last visit; every change is saved right away
+    let columns = use_state(ColumnSettings::load);
+    let on_columns_change = {
+        let columns = columns.clone();
+        Callback::from(move |settings: ColumnSettings| {
+            settings.store();
+            columns.set(settings);
+        })
+    };
+    let grid = format!("grid-template-columns: {}", columns.grid_template(props.on_toggle_select.is_some()));
+    let header = |column: Column| match column {
+        Column::LastSeen => html! {
+            <div role="columnheader" aria-sort={view.sort.aria_sort()}>
+              <button type="button" onclick={on_sort.clone()} class={classes!("text-left", "font-bold", "hover:text-gray-900", "dark:hover:text-white", FOCUS_RING)}>
+                { t.t(column.label_id()) }
+                <span aria-hidden="true">{ view.sort.indicator() }</span>
+              </button>
+            </div>
+        },
+        _ => html! { <div role="columnheader">{ t.t(column.label_id()) }</div> },
+    };
+
+    html! {
+        <div class="p-6">
+            <div class="flex items-center justify-between mb-2">
+                <h2 id="user-list-title" class="text-2xl font-bold text-gray-700 dark:text-gray-200 print:text-black">{ t.t("users-list") }</h2>
+                <div class="flex items-center gap-2 print:hidden">
+                    <PrintButton />
+                    <ColumnSettingsMenu settings={(*columns).clone()} on_change={on_columns_change} />
+                </div>
+            </div>
+            <SavedViewsMenu
+                views={(*saved_views).clone()}
+                active={list.saved_view.clone()}
+                on_select={on_select_view}
+                on_save={on_save_view}
+                on_delete={on_delete_view}
+            />
+            <ListFilters view={view.clone()} on_change={on_view_change} />
+            <TagFilterBar tags={count_tags(&props.users)} active={view.tag.clone()} on_change={on_tag_filter} />
+            <PrintableUserList users={users.clone()} columns={(*columns).clone()} />
+            <div role="table" aria-labelledby="user-list-title" class="print:hidden">
+                <div role="row" style={grid} class={classes!("grid", "gap-4", "px-4", "py-2", "bg-gray-100", "dark:bg-gray-800", "font-bold", "text-gray-700", "dark:text-gray-200", "border-b", "dark:border-gray-700")}>
+                  if props.on_toggle_select.is_some() {
+                    <div role="columnheader"><span class="sr-only">{ t.t("a11y-select") }</span></div>
+                  }
+                  { for columns.visible_columns().into_iter().map(header) }
+                  <div role="columnheader" class="col-span-3"><span class="sr-only">{ t.t("a11y-actions") }</span></div>
+                </div>
+                <ul role="rowgroup" class="divide-y divide-gray-200 dark:divide-gray-700">
+                    { for page_of(&users, page).iter().map(|user| {
+                        html! { <UserListItem key={user.id} user={user.clone()} on_delete={props.on_delete.clone()} on_edit={props.on_edit.clone()} on_set_status={props.on_set_status.clone()} selected={props.selected.contains(&user.id)} on_toggle_select={props.on_toggle_select.clone()} columns={(*columns).clone()} on_tag_click={on_tag_click.clone()} /> }
+                    })}
+                </ul>
+            </div>
+            <Pagination page={page} pages={pages} on_change={on_page} />
+        </div>
+    }
+}
+
+// Props for Pagination component
+#[derive(Properties, PartialEq)]
+pub struct PaginationProps {
+    // Page shown, from 0
+    pub page: usize,
+    pub pages: usize,
+    pub on_change: Callback<usize>,
+}
+
+// Previous and next buttons around "Page X of Y"; hidden while everything fits on one page
+#[function_component(Pagination)]
+pub fn pagination(props: &PaginationProps) -> Html {
+    let t = use_translation();
+    if props.pages <= 1 {
+        return html! {};
+    }
+    let button = |target: Option<usize>, label_id: &str| {
+        let on_change = props.on_change.clone();
+        html! {
+            <button
+                type="button"
+                disabled={target.is_none()}
+                onclick={Callback::from(move |_: MouseEvent| {
+                    if let Some(page) = target {
+                        on_change.emit(page);
+                    }
+                })}
+                class={classes!("border", "rounded", "py-1", "px-3", "text-gray-700", "hover:bg-gray-100", "disabled:opacity-50", "dark:text-gray-200", "dark:border-gray-600", "dark:hover:bg-gray-700", FOCUS_RING)}
+            >
+                { t.t(label_id) }
+            </button>
+        }
+    };
+    let current = t.t_with("pagination-page", &[("page", (props.page + 1).into()), ("pages", props.pages.into())]);
+
+    html! {
+        <nav aria-label={t.t("pagination")} class="flex items-center justify-center gap-4 mt-4 print:hidden">
+            { button(props.page.checked_sub(1), "pagination-previous") }
+            <span aria-current="page" class="text-sm text-gray-700 dark:text-gray-200">{ current }</span>
+            { button((props.page + 1 < props.pages).then_some(props.page + 1), "pagination-next") }
+        </nav>
+    }
+}
+
+// Opens the browser's print dialog, which also saves the page as PDF
+#[function_component(PrintButton)]
+pub fn print_button() -> Html {
+    let t = use_translation();
+    let onclick = Callback::from(|_: MouseEvent| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    });
+
+    html! {
+        <button
+            type="button"
+            {onclick}
+            class={classes!("border", "rounded", "py-1", "px-3", "text-gray-700", "hover:bg-gray-100", "dark:text-gray-200", "dark:border-gray-600", "dark:hover:bg-gray-700", FOCUS_RING)}
+        >
+            { t.t("users-print") }
+        </button>
+    }
+}
+
+// Text of a user's cell in the printed list
+pub fn print_cell_text(t: &Translator, user: &User, column: Column, now: i64) -> String {
+    match column {
+        Column::Id => user.id.to_string(),
+        Column::Name => user.name.clone(),
+        Column::Email => user.email.clone(),
+        Column::LastSeen => format_last_seen(t, user.last_login_at.as_deref(), now),
+        Column::Status => t.t(status_label_id(user.status)),
+    }
+}
+
+// Props for PrintableUserList component
+#[derive(Properties, PartialEq)]
+pub struct PrintableUserListProps {
+    pub users: Vec<User>,
+    pub columns: ColumnSettings,
+}
+
+// Plain table of the listed users, in their current order and with the
+// visible columns, shown only when printing in place of the interactive list
+#[function_component(PrintableUserList)]
+pub fn printable_user_list(props: &PrintableUserListProps) -> Html {
+    let t = use_translation();
+    let columns = props.columns.visible_columns();
+    let now = (js_sys::Date::now() / 1000.0) as i64;
+
+    html! {
+        <table class="hidden print:table w-full text-sm text-black border-collapse">
+            <thead>
+                <tr>
+                    { for columns.iter().map(|column| html! {
+                        <th class="text-left border-b border-black py-1 pr-4">{ t.t(column.label_id()) }</th>
+                    })}
+                </tr>
+            </thead>
+            <tbody>
+                { for props.users.iter().map(|user| html! {
+                    <tr class="break-inside-avoid">
+                        { for columns.iter().map(|column| html! {
+                            <td class="border-b border-gray-300 py-1 pr-4">{ print_cell_text(&t, user, *column, now) }</td>
+                        })}
+                    </tr>
+                })}
+            </tbody>
+        </table>
+    }
+}
+
+// Props for EmptyState component
+#[derive(Properties, PartialEq)]
+pub struct EmptyStateProps {
+    pub message: String,
+    #[prop_or(AttrValue::Static("∅"))]
+    pub icon: AttrValue,
+    // Call-to-action button, shown when both are set
+    #[prop_or_default]
+    pub action_label: Option<String>,
+    #[prop_or_default]
+    pub on_action: Option<Callback<()>>,
+}
+
+// Placeholder for a list without items
+#[function_component(EmptyState)]
+pub fn empty_state(props: &EmptyStateProps) -> Html {
+    let action = props.action_label.clone().zip(props.on_action.clone());
+
+    html! {
+        <div class="flex flex-col items-center p-10 text-gray-500 dark:text-gray-400">
+            <span class="text-5xl mb-2" aria-hidden="true">{ &props.icon }</span>
+            <p class="mb-4">{ &props.message }</p>
+            if let Some((label, callback)) = action {
+                <button
+                    onclick={Callback::from(move |_| callback.emit(()))}
+                    class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded"
+                >
+                    { label }
+                </button>
+            }
+        </div>
+    }
+}
+
+// Props for ErrorState component
+#[derive(Properties, PartialEq)]
+pub struct ErrorStateProps {
+    pub message: String,
+    pub on_retry: Callback<()>,
+}
+
+// Failure to load data, with a button to try again
+#[function_component(ErrorState)]
+pub fn error_state(props: &ErrorStateProps) -> Html {
+    let t = use_translation();
+    let on_retry = {
+        let callback = props.on_retry.clone();
+        Callback::from(move |_| callback.emit(()))
+    };
+
+    html! {
+        <div role="alert" class="flex flex-col items-center p-10 border border-red-200 rounded bg-red-50 dark:bg-gray-800 dark:border-red-800">
+            <span class="text-5xl mb-2 text-red-500" aria-hidden="true">{ "⚠" }</span>
+            <p class="mb-4 text-red-700 dark:text-red-400">{ &props.message }</p>
+            <button
+                onclick={on_retry}
+                class="bg-gray-500 hover:bg-gray-700 text-white font-bold py-2 px-4 rounded"
+            >
+                { t.t("retry") }
+            </button>
+        </div>
+    }
+}
+
+// Props for UserListItem component
+#[derive(Properties, PartialEq)]
+pub struct UserListItemProps {
+    pub user: User,
+    pub on_delete: Callback<i32>,
+    pub on_edit: Callback<i32>,
+    // Requests switching a user to the given status
+    pub on_set_status: Callback<(i32, UserStatus)>,
+    #[prop_or_default]
+    pub selected: bool,
+    #[prop_or_default]
+    pub on_toggle_select: Option<Callback<i32>>,
+    // Columns shown and their widths
+    #[prop_or_default]
+    pub columns: ColumnSettings,
+    // Filters the list by the clicked tag chip
+    #[prop_or_default]
+    pub on_tag_click: Callback<String>,
+}
+
+// How long the "Copied!" tooltip stays visible
+pub const COPIED_TOOLTIP_MS: u32 = 2_000;
+
+// Outcome of the last copy, shown as a tooltip
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CopyOutcome {
+    Copied,
+    Failed,
+}
+
+// Props for CopyButton component
+#[derive(Properties, PartialEq)]
+pub struct CopyButtonProps {
+    // Text put on the clipboard
+    pub text: AttrValue,
+    // Accessible name of the button
+    pub label: AttrValue,
+}
+
+// Small icon button copying `text` with the Clipboard API
+#[function_component(CopyButton)]
+pub fn copy_button(props: &CopyButtonProps) -> Html {
+    let t = use_translation();
+    let outcome = use_state(|| None::<CopyOutcome>);
+    let hide = use_mut_ref(|| None::<Timeout>);
+
+    let onclick = {
+        let outcome = outcome.clone();
+        let text = props.text.clone();
+        Callback::from(move |_: MouseEvent| {
+            let outcome = outcome.clone();
+            let hide = hide.clone();
+            let text = text.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let copied = copy_to_clipboard(&text).await;
+                outcome.set(Some(if copied { CopyOutcome::Copied } else { CopyOutcome::Failed }));
+                // A new copy restarts the countdown
+                let reset = outcome.clone();
+                *hide.borrow_mut() = Some(Timeout::new(COPIED_TOOLTIP_MS, move || reset.set(None)));
+            });
+        })
+    };
+
+    html! {
+        <span class="relative inline-flex">
+            <button
+                type="button"
+                {onclick}
+                aria-label={props.label.clone()}
+                title={props.label.clone()}
+                class={classes!("text-gray-500", "hover:text-gray-800", "dark:text-gray-400", "dark:hover:text-gray-100", FOCUS_RING)}
+            >
+                { "⧉" }
+            </button>
+            <span role="status" class="absolute left-full ml-1 whitespace-nowrap text-xs px-1 rounded bg-gray-800 text-white empty:hidden">
+                { match *outcome {
+                    Some(CopyOutcome::Copied) => t.t("copied"),
+                    Some(CopyOutcome::Failed) => t.t("copy-failed"),
+                    None => String::new(),
+                } }
+            </span>
+        </span>
+    }
+}
+
+// Put `text` on the clipboard; false where the Clipboard API is missing
+// (plain-HTTP origins other than localhost) or the browser refused
+async fn copy_to_clipboard(text: &str) -> bool {
+    let Some(window) = web_sys::window() else {
+        return false;
+    };
+    let navigator = window.navigator();
+    if !js_sys::Reflect::has(&navigator, &"clipboard".into()).unwrap_or(false) {
+        return false;
+    }
+    wasm_bindgen_futures::JsFuture::from(navigator.clipboard().write_text(text))
+        .await
+        .is_ok()
+}
+
+// `mailto:` link of an email; characters with a meaning in URLs are escaped
+pub fn mailto_href(email: &str) -> String {
+    let mut href = String::from("mailto:");
+    for c in email.trim().chars() {
+        match c {
+            '%' | '?' | '#' | '&' | ' ' | '"' | '<' | '>' => href.push_str(&format!("%{:02X}", c as u32)),
+            _ => href.push(c),
+        }
+    }
+    href
+}
+
+#[function_component(UserListItem)]
+pub fn user_list_item(props: &UserListItemProps) -> Html {
+    let t = use_translation();
+    let user_id = props.user.id;
+    let on_delete = {
+        let callback = props.on_delete.clone();
+        Callback::from(move |_| callback.emit(user_id))
+    };
+
+    let on_edit = {
+        let callback = props.on_edit.clone();
+        Callback::from(move |_| callback.emit(user_id))
+    };
+
+    let next_status = props.user.status.toggled();
+    let on_set_status = {
+        let callback = props.on_set_status.clone();
+        Callback::from(move |_| callback.emit((user_id, next_status)))
+    };
+
+    let on_toggle_select = props.on_toggle_select.clone().map(|callback| {
+        Callback::from(move |_: Event| callback.emit(user_id))
+    });
+
+    let columns = &props.columns;
+    let grid = format!("grid-template-columns: {}", columns.grid_template(on_toggle_select.is_some()));
+
+    html! {
+        <li role="row" style={grid} class={classes!("grid", "gap-4", "px-4", "py-2", "hover:bg-gray-50", "dark:hover:bg-gray-800", "items-center")}>
+            if let Some(on_toggle_select) = on_toggle_select {
+                <span role="cell">
+                    <input
+                        type="checkbox"
+                        checked={props.selected}
+                        onchange={on_toggle_select}
+                        aria-label={user_action_label(&t, "a11y-select-user", &props.user)}
+                        class={FOCUS_RING}
+                    />
+                </span>
+            }
+            if columns.is_visible(Column::Id) {
+                <span role="cell" class="font-medium text-gray-900 dark:text-gray-100">
+                    { format!("{}", props.user.id) }
+                </span>
+            }
+            <span role="cell" class="flex items-center gap-2 font-medium text-gray-900 dark:text-gray-100 truncate">
+                <Avatar email={props.user.email.clone()} name={props.user.name.clone()} size={24} />
+                <Link<Route> to={Route::UserDetail { user: UserRef::of(&props.user) }} classes={classes!("hover:underline", FOCUS_RING)}>
+                    { format!("{}", props.user.name) }
+                </Link<Route>>
+                { for props.user.tags.iter().map(|tag| html! {
+                    <TagChip tag={tag.clone()} on_click={props.on_tag_click.clone()} />
+                })}
+            </span>
+            if columns.is_visible(Column::Email) {
+                <span role="cell" class="flex items-center gap-2 min-w-0 font-medium text-gray-900 dark:text-gray-100">
+                    <span class="truncate">{ format!("{}", props.user.email) }</span>
+                    <CopyButton
+                        text={props.user.email.clone()}
+                        label={user_action_label(&t, "a11y-copy-email", &props.user)}
+                    />
+                    <a
+                        href={mailto_href(&props.user.email)}
+                        aria-label={user_action_label(&t, "a11y-mail-user", &props.user)}
+                        title={user_action_label(&t, "a11y-mail-user", &props.user)}
+                        class={classes!("text-gray-500", "hover:text-gray-800", "dark:text-gray-400", "dark:hover:text-gray-100", FOCUS_RING)}
+                    >
+                        { "✉" }
+                    </a>
+                </span>
+            }
+            if columns.is_visible(Column::LastSeen) {
+                <span role="cell" class="text-gray-600 dark:text-gray-400">
+                    { format_last_seen(&t, props.user.last_login_at.as_deref(), (js_sys::Date::now() / 1000.0) as i64) }
+                </span>
+            }
+            if columns.is_visible(Column::Status) {
+                <span role="cell" class={classes!("text-xs", "font-semibold", "px-2", "py-1", "rounded-full", "text-center", status_badge_class(props.user.status))}>
+                    { t.t(status_label_id(props.user.status)) }
+                </span>
+            }
+            <span role="cell">
+                <button
+                    type="button"
+                    onclick={on_set_status}
+                    aria-label={user_action_label(&t, status_action_id(next_status).1, &props.user)}
+                    class={classes!("w-full", "bg-gray-500", "hover:bg-gray-700", "text-white", "py-1", "px-2", "rounded", FOCUS_RING)}
+                >
+                    { t.t(status_action_id(next_status).0) }
+                </button>
+            </span>
+            <span role="cell">
+                <button
+                    type="button"
+                    onclick={on_delete}
+                    aria-label={user_action_label(&t, "a11y-delete-user", &props.user)}
+                    class={classes!("w-full", "bg-red-500", "hover:bg-red-700", "text-white", "py-1", "px-2", "rounded", FOCUS_RING)}
+                >
+                    { t.t("action-delete") }
+                </button>
+            </span>
+            <span role="cell">
+                <button
+                    type="button"
+                    onclick={on_edit}
+                    aria-label={user_action_label(&t, "a11y-edit-user", &props.user)}
+                    class={classes!("w-full", "bg-yellow-500", "hover:bg-yellow-700", "text-white", "py-1", "px-2", "rounded", FOCUS_RING)}
+                >
+                    { t.t("action-edit") }
+                </button>
+            </span>
+        </li>
+    }
+}
+
+// Message ids of the button switching a user to `status`: its text and its accessible name
+pub fn status_action_id(status: UserStatus) -> (&'static str, &'static str) {
+    match status {
+        UserStatus::Suspended => ("action-suspend", "a11y-suspend-user"),
+        UserStatus::Active => ("action-activate", "a11y-activate-user"),
+    }
+}
+
+// Accessible name of a row action, naming the user it applies to
+pub fn user_action_label(t: &Translator, id: &str, user: &User) -> String {
+    t.t_with(id, &[("name", user.name.as_str().into())])
+}
+
+// Badge colours of a user status
+pub fn status_badge_class(status: UserStatus) -> &'static str {
+    match status {
+        UserStatus::Active => "bg-green-100 text-green-800",
+        UserStatus::Suspended => "bg-red-100 text-red-800",
+    }
+}
+
+// Message id of a status label
+pub fn status_label_id(status: UserStatus) -> &'static str {
+    match status {
+        UserStatus::Active => "status-active",
+        UserStatus::Suspended => "status-suspended",
+    }
+}
+
+// Gravatar image of an email; Gravatar draws an identicon for emails without one
+pub fn gravatar_url(email: &str, size: u32) -> String {
+    let hash = hex::encode(Sha256::digest(email.trim().to_lowercase().as_bytes()));
+    format!("https://www.gravatar.com/avatar/{}?s={}&d=identicon", hash, size)
+}
+
+// Up to two initials of a name, shown when the avatar image cannot load
+pub fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+// Props for Avatar component
+#[derive(Properties, PartialEq)]
+pub struct AvatarProps {
+    pub email: String,
+    pub name: String,
+    // Width and height in CSS pixels
+    #[prop_or(32)]
+    pub size: u32,
 }
 
-#[function_component(UserForm)]
-pub fn user_form(props: &UserFormProps) -> Html {
-    let on_name_input = {
-        let on_name_change = props.on_name_change.clone();
-        Callback::from(move |e: InputEvent| {
-            let input = e.target_dyn_into::<HtmlInputElement>().unwrap();
-            on_name_change.emit(input.value());
-        })
+// Round Gravatar picture of a user, falling back to initials when offline
+#[function_component(Avatar)]
+pub fn avatar(props: &AvatarProps) -> Html {
+    let failed = use_state(|| false);
+    {
+        let failed = failed.clone();
+        use_effect_with(props.email.clone(), move |_| failed.set(false));
+    }
+    let onerror = {
+        let failed = failed.clone();
+        Callback::from(move |_: Event| failed.set(true))
     };
+    let style = format!("width: {0}px; height: {0}px", props.size);
 
-    let on_email_input = {
-        let on_email_change = props.on_email_change.clone();
-        Callback::from(move |e: InputEvent| {
-            let input = e.target_dyn_into::<HtmlInputElement>().unwrap();
-            on_email_change.emit(input.value());
-        })
-    };
+    // Decorative: the user's name is always shown next to it
+    if *failed {
+        return html! {
+            <span aria-hidden="true" style={style} class="inline-flex items-center justify-center rounded-full bg-gray-300 text-gray-700 dark:bg-gray-600 dark:text-gray-100 text-xs font-bold">
+                { initials(&props.name) }
+            </span>
+        };
+    }
+    html! {
+        <img
+            src={gravatar_url(&props.email, props.size * 2)}
+            alt=""
+            width={props.size.to_string()}
+            height={props.size.to_string()}
+            loading="lazy"
+            onerror={onerror}
+            class="rounded-full bg-gray-200 dark:bg-gray-700"
+        />
+    }
+}
 
-    let on_password_input = {
-        let on_password_change = props.on_password_change.clone();
-        Callback::from(move |e: InputEvent| {
-            let input = e.target_dyn_into::<HtmlInputElement>().unwrap();
-            on_password_change.emit(input.value());
-        })
+// Message id describing an audit-log event; None for events this UI does not know
+pub fn activity_event_id(event: &str) -> Option<&'static str> {
+    match event {
+        "user.created" => Some("activity-created"),
+        "user.updated" => Some("activity-updated"),
+        "user.deleted" => Some("activity-deleted"),
+        "user.anonymized" => Some("activity-anonymized"),
+        "user.password_changed" => Some("activity-password-changed"),
+        _ => None,
+    }
+}
+
+// Props for ActivityTimeline component
+#[derive(Properties, PartialEq)]
+pub struct ActivityTimelineProps {
+    // Newest first, as returned by the backend
+    pub entries: Vec<ActivityEntry>,
+}
+
+// A user's recent audit-log entries
+#[function_component(ActivityTimeline)]
+pub fn activity_timeline(props: &ActivityTimelineProps) -> Html {
+    let t = use_translation();
+    if props.entries.is_empty() {
+        return html! {
+            <p class="text-gray-500 dark:text-gray-400">{ t.t("activity-empty") }</p>
+        };
+    }
+    let now = (js_sys::Date::now() / 1000.0) as i64;
+
+    html! {
+        <ol class="border-l-2 border-gray-200 dark:border-gray-700 ml-2">
+            { for props.entries.iter().map(|entry| html! {
+                <li class="ml-4 mb-3">
+                    <p class="font-medium text-gray-900 dark:text-gray-100">
+                        { activity_event_id(&entry.event).map(|id| t.t(id)).unwrap_or_else(|| entry.event.clone()) }
+                    </p>
+                    <time datetime={entry.occurred_at.clone()} title={entry.occurred_at.clone()} class="text-sm text-gray-600 dark:text-gray-400">
+                        { format_last_seen(&t, Some(&entry.occurred_at), now) }
+                    </time>
+                </li>
+            })}
+        </ol>
+    }
+}
+
+// Global function the CAPTCHA script calls once it has loaded
+const CAPTCHA_ONLOAD: &str = "onCaptchaLoaded";
+
+// Source of the provider's script, set up for explicit rendering
+pub fn captcha_script_src(provider: CaptchaProvider) -> Option<String> {
+    provider
+        .script_url()
+        .map(|url| format!("{}?render=explicit&onload={}", url, CAPTCHA_ONLOAD))
+}
+
+// Render a widget into `container` if the provider's script is ready
+fn render_captcha(provider: CaptchaProvider, container: &web_sys::Element, options: &js_sys::Object) -> bool {
+    let (Some(window), Some(global)) = (web_sys::window(), provider.js_global()) else {
+        return false;
+    };
+    let Ok(api) = js_sys::Reflect::get(&window, &global.into()) else {
+        return false;
     };
+    js_sys::Reflect::get(&api, &"render".into())
+        .ok()
+        .and_then(|render| render.dyn_into::<js_sys::Function>().ok())
+        .is_some_and(|render| render.call2(&api, container, options).is_ok())
+}
 
-    let on_submit = {
-        let callback = props.on_submit.clone();
-        Callback::from(move |_| callback.emit(()))
+// Add the provider's script to the page once
+fn load_captcha_script(src: &str) {
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
     };
+    if document.query_selector(&format!("script[src='{}']", src)).ok().flatten().is_some() {
+        return;
+    }
+    if let (Ok(script), Some(head)) = (document.create_element("script"), document.head()) {
+        let _ = script.set_attribute("src", src);
+        let _ = script.set_attribute("async", "");
+        let _ = head.append_child(&script);
+    }
+}
+
+// Props for CaptchaWidget component
+#[derive(Properties, PartialEq)]
+pub struct CaptchaWidgetProps {
+    pub settings: CaptchaSettings,
+    // The token once the challenge is solved, None when it expires
+    pub on_token: Callback<Option<String>>,
+}
 
+// hCaptcha, reCAPTCHA or Turnstile challenge; renders nothing while CAPTCHA is off.
+// Give it a new `key` to get a fresh challenge, since tokens are single use
+#[function_component(CaptchaWidget)]
+pub fn captcha_widget(props: &CaptchaWidgetProps) -> Html {
+    let container = use_node_ref();
+    let settings = props.settings.clone();
+    let enabled = settings.provider.script_url().is_some() && settings.site_key.is_some();
+
+    {
+        let container = container.clone();
+        let on_token = props.on_token.clone();
+        use_effect_with(settings, move |settings| {
+            let provider = settings.provider;
+            let solved = {
+                let on_token = on_token.clone();
+                Closure::<dyn Fn(String)>::new(move |token| on_token.emit(Some(token)))
+            };
+            let expired = Closure::<dyn Fn()>::new(move || on_token.emit(None));
+            let options = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&options, &"sitekey".into(), &settings.site_key.clone().unwrap_or_default().into());
+            let _ = js_sys::Reflect::set(&options, &"callback".into(), solved.as_ref());
+            let _ = js_sys::Reflect::set(&options, &"expired-callback".into(), expired.as_ref());
+
+            let render: Rc<dyn Fn() -> bool> = Rc::new(move || {
+                container
+                    .cast::<web_sys::Element>()
+                    .is_some_and(|element| render_captcha(provider, &element, &options))
+            });
+            // The script is only loaded by the first widget; later ones render right away
+            let onload = (settings.site_key.is_some() && !render()).then(|| {
+                let onload = Closure::<dyn Fn()>::new(move || {
+                    render();
+                });
+                if let (Some(window), Some(src)) = (web_sys::window(), captcha_script_src(provider)) {
+                    let _ = js_sys::Reflect::set(&window, &CAPTCHA_ONLOAD.into(), onload.as_ref());
+                    load_captcha_script(&src);
+                }
+                onload
+            });
+            move || drop((solved, expired, onload))
+        });
+    }
+
+    if !enabled {
+        return Html::default();
+    }
+    html! { <div ref={container} class="mt-2 mb-4"></div> }
+}
+
+// Navigation bar shown on every page
+#[function_component(NavBar)]
+pub fn nav_bar() -> Html {
+    let t = use_translation();
     html! {
-        <div class="mb-4">
-            <input
-                placeholder="Name"
-                value={props.name.clone()}
-                oninput={on_name_input}
-                class="border rounded px-4 py-2 mr-2"
-            />
-            <input
-                placeholder="Email"
-                value={props.email.clone()}
-                oninput={on_email_input}
-                class="border rounded px-4 py-2 mr-2"
-            />
-            <input
-                type="password"
-                placeholder="Password"
-                value={props.password.clone()}
-                oninput={on_password_input}
-                class="border rounded px-4 py-2 mr-2"
-            />
-            <button
-                onclick={on_submit}
-                class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded"
-            >
-                { if props.is_editing { "Update User" } else { "Create User" } }
-            </button>
-            if !props.message.is_empty() {
-                <p class="text-green-500 mt-2">{ &props.message }</p>
-            }
-        </div>
+        <nav aria-label={t.t("a11y-main-nav")} class="flex items-center gap-4 mb-6 border-b dark:border-gray-700 pb-2 print:hidden">
+            <Link<Route> to={Route::Users} classes="text-blue-600 dark:text-blue-400 hover:underline">
+                { t.t("nav-users") }
+            </Link<Route>>
+            <Link<Route> to={Route::Dashboard} classes="text-blue-600 dark:text-blue-400 hover:underline">
+                { t.t("nav-dashboard") }
+            </Link<Route>>
+            <Link<Route> to={Route::Signup} classes="text-blue-600 dark:text-blue-400 hover:underline">
+                { t.t("nav-signup") }
+            </Link<Route>>
+            <LanguageSwitcher />
+            <ThemeToggle />
+            <DesktopNotifications />
+            <ConnectivityDot />
+        </nav>
     }
 }
 
-// Props for UserList component
+// Props for StatCard component
 #[derive(Properties, PartialEq)]
-pub struct UserListProps {
-    pub users: Vec<User>,
-    pub on_delete: Callback<i32>,
-    pub on_edit: Callback<i32>,
+pub struct StatCardProps {
+    pub label: String,
+    pub value: i64,
 }
 
-#[function_component(UserList)]
-pub fn user_list(props: &UserListProps) -> Html {
+#[function_component(StatCard)]
+pub fn stat_card(props: &StatCardProps) -> Html {
     html! {
-        <div class="p-6">
-            <h2 class="text-2xl font-bold text-gray-700 mb-2">{ "User List" }</h2>
-            <div class="grid grid-cols-[50px_1fr_1fr_100px_100px] gap-4 px-4 py-2 bg-gray-100 font-bold text-gray-700 border-b">
-              <div>{ "ID" }</div>
-              <div>{ "Name" }</div>
-              <div>{ "Email" }</div>
-              <div>{ "" }</div>
-              <div>{ "" }</div>
-            </div>
-            <ul class="divide-y divide-gray-200">
-                { for props.users.iter().map(|user| {
-                    html! { <UserListItem key={user.id} user={user.clone()} on_delete={props.on_delete.clone()} on_edit={props.on_edit.clone()} /> }
-                })}
-            </ul>
+        <div class="border rounded p-4 bg-white shadow-sm dark:bg-gray-800 dark:border-gray-700">
+            <p class="text-sm text-gray-500 dark:text-gray-400">{ &props.label }</p>
+            <p class="text-3xl font-bold text-gray-900 dark:text-gray-100">{ props.value }</p>
         </div>
     }
 }
 
-// Props for UserListItem component
+// Props for BarChart component
 #[derive(Properties, PartialEq)]
-pub struct UserListItemProps {
-    pub user: User,
-    pub on_delete: Callback<i32>,
-    pub on_edit: Callback<i32>,
+pub struct BarChartProps {
+    pub title: String,
+    pub data: Vec<(String, i64)>,
 }
 
-#[function_component(UserListItem)]
-pub fn user_list_item(props: &UserListItemProps) -> Html {
-    let user_id = props.user.id;
-    let on_delete = {
-        let callback = props.on_delete.clone();
-        Callback::from(move |_| callback.emit(user_id))
-    };
+// Chart drawing area in SVG units
+pub const CHART_WIDTH: f64 = 600.0;
+pub const CHART_HEIGHT: f64 = 150.0;
 
-    let on_edit = {
-        let callback = props.on_edit.clone();
-        Callback::from(move |_| callback.emit(user_id))
-    };
+// Height of each bar, scaled so the largest value fills the chart
+pub fn bar_heights(values: &[i64], max_height: f64) -> Vec<f64> {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|value| {
+            if max <= 0 {
+                0.0
+            } else {
+                (*value as f64 / max as f64) * max_height
+            }
+        })
+        .collect()
+}
+
+#[function_component(BarChart)]
+pub fn bar_chart(props: &BarChartProps) -> Html {
+    let values: Vec<i64> = props.data.iter().map(|(_, value)| *value).collect();
+    let heights = bar_heights(&values, CHART_HEIGHT);
+    let slot = CHART_WIDTH / props.data.len().max(1) as f64;
 
     html! {
-        <li class="grid grid-cols-[50px_1fr_1fr_100px_100px] gap-4 px-4 py-2 hover:bg-gray-50 items-center">
-            <span class="font-medium text-gray-900">
-                { format!("{}", props.user.id) }
-            </span>
-            <span class="font-medium text-gray-900">
-                { format!("{}", props.user.name) }
-            </span> <span class="font-medium text-gray-900">
-                { format!("{}", props.user.email) }
-            </span>
-            <button
-                onclick={on_delete}
-                class=" bg-red-500 hover:bg-red-700 text-white py-1 px-2 rounded"
-            >
-                { "Delete" }
-            </button>
-            <button
-                onclick={on_edit}
-                class=" bg-yellow-500 hover:bg-yellow-700 text-white  py-1 px-2 rounded"
+        <div class="mb-6">
+            <h2 class="text-xl font-bold text-gray-700 dark:text-gray-200 mb-2">{ &props.title }</h2>
+            <svg
+                role="img"
+                aria-label={props.title.clone()}
+                viewBox={format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT + 20.0)}
+                class="w-full border rounded bg-gray-50 dark:bg-gray-800 dark:border-gray-700"
             >
-                { "Edit" }
-            </button>
-        </li>
+                { for props.data.iter().zip(heights).enumerate().map(|(index, ((label, value), height))| {
+                    let x = index as f64 * slot;
+                    html! {
+                        <g>
+                            <rect
+                                x={format!("{:.1}", x + slot * 0.1)}
+                                y={format!("{:.1}", CHART_HEIGHT - height)}
+                                width={format!("{:.1}", slot * 0.8)}
+                                height={format!("{:.1}", height)}
+                                class="fill-blue-500"
+                            >
+                                <title>{ format!("{}: {}", label, value) }</title>
+                            </rect>
+                            <text
+                                x={format!("{:.1}", x + slot / 2.0)}
+                                y={format!("{:.1}", CHART_HEIGHT + 14.0)}
+                                font-size="8"
+                                text-anchor="middle"
+                                class="fill-gray-600 dark:fill-gray-300"
+                            >
+                                { label }
+                            </text>
+                        </g>
+                    }
+                })}
+            </svg>
+        </div>
     }
 }
 
@@ -189,6 +2500,7 @@ pub fn button(props: &ButtonProps) -> Html {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::i18n::Locale;
 
     #[test]
     fn test_user_form_props_creation() {
@@ -201,7 +2513,18 @@ mod tests {
             on_email_change: Callback::noop(),
             on_password_change: Callback::noop(),
             on_submit: Callback::noop(),
-            message: "Success".to_string(),
+            notification: Some(Notification::success("Success")),
+            on_dismiss: Callback::noop(),
+            errors: FieldErrors::default(),
+            submit_label: None,
+            phone: String::new(),
+            address: Address::default(),
+            username: String::new(),
+            username_locked: false,
+            on_username_change: None,
+            on_phone_change: None,
+            on_address_change: None,
+            on_history: None,
         };
 
         assert_eq!(props1.name, "John");
@@ -216,12 +2539,23 @@ mod tests {
             id: 1,
             name: "John".to_string(),
             email: "john@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
         }];
 
         let props1 = UserListProps {
             users: users.clone(),
             on_delete: Callback::noop(),
             on_edit: Callback::noop(),
+            on_set_status: Callback::noop(),
+            selected: Vec::new(),
+            on_toggle_select: None,
         };
 
         assert_eq!(props1.users.len(), 1);
@@ -233,12 +2567,25 @@ mod tests {
             id: 1,
             name: "John".to_string(),
             email: "john@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
         };
 
         let props = UserListItemProps {
             user: user.clone(),
             on_delete: Callback::noop(),
             on_edit: Callback::noop(),
+            on_set_status: Callback::noop(),
+            selected: false,
+            on_toggle_select: None,
+            columns: ColumnSettings::default(),
+            on_tag_click: Callback::noop(),
         };
 
         assert_eq!(props.user.id, 1);
@@ -246,6 +2593,40 @@ mod tests {
         assert_eq!(props.user.email, "john@example.com");
     }
 
+    #[test]
+    fn test_password_strength_meter_props() {
+        let props = PasswordStrengthMeterProps {
+            password: "password123".to_string(),
+        };
+
+        assert_eq!(password_strength(&props.password), PasswordStrength::Fair);
+    }
+
+    #[test]
+    fn test_strength_bar_classes() {
+        assert_eq!(strength_bar_classes(PasswordStrength::Weak), ("w-1/3", "bg-red-500"));
+        assert_eq!(strength_bar_classes(PasswordStrength::Fair), ("w-2/3", "bg-yellow-500"));
+        assert_eq!(strength_bar_classes(PasswordStrength::Strong), ("w-full", "bg-green-500"));
+    }
+
+    #[test]
+    fn test_bar_heights_scale_to_max() {
+        assert_eq!(bar_heights(&[1, 2, 4], 100.0), vec![25.0, 50.0, 100.0]);
+        assert_eq!(bar_heights(&[0, 0], 100.0), vec![0.0, 0.0]);
+        assert!(bar_heights(&[], 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_stat_card_props() {
+        let props = StatCardProps {
+            label: "Total users".to_string(),
+            value: 42,
+        };
+
+        assert_eq!(props.label, "Total users");
+        assert_eq!(props.value, 42);
+    }
+
     #[test]
     fn test_button_props() {
         let props = ButtonProps {
@@ -268,4 +2649,440 @@ mod tests {
 
         assert_eq!(props.class, "");
     }
+
+    #[test]
+    fn test_status_badge_class() {
+        assert!(status_badge_class(UserStatus::Active).contains("green"));
+        assert!(status_badge_class(UserStatus::Suspended).contains("red"));
+    }
+
+    #[test]
+    fn test_parse_utc_timestamp() {
+        assert_eq!(parse_utc_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_utc_timestamp("2026-10-17T12:00:00Z"), Some(1_792_238_400));
+        assert_eq!(parse_utc_timestamp("2026-10-17"), None);
+        assert_eq!(parse_utc_timestamp("2026-10-17T12:00:00+02:00"), None);
+    }
+
+    #[test]
+    fn test_format_last_seen() {
+        let t = Translator::new(Locale::En);
+        let now = parse_utc_timestamp("2026-10-17T12:00:00Z").unwrap();
+        assert_eq!(format_last_seen(&t, None, now), "Never");
+        assert_eq!(format_last_seen(&t, Some("2026-10-17T11:59:30Z"), now), "Just now");
+        assert_eq!(format_last_seen(&t, Some("2026-10-17T11:59:00Z"), now), "1 minute ago");
+        assert_eq!(format_last_seen(&t, Some("2026-10-17T09:00:00Z"), now), "3 hours ago");
+        assert_eq!(format_last_seen(&t, Some("2026-10-15T12:00:00Z"), now), "2 days ago");
+        assert_eq!(format_last_seen(&t, Some("2026-01-02T12:00:00Z"), now), "2026-01-02");
+    }
+
+    #[test]
+    fn test_format_last_seen_translated() {
+        let t = Translator::new(Locale::Es);
+        let now = parse_utc_timestamp("2026-10-17T12:00:00Z").unwrap();
+        assert_eq!(format_last_seen(&t, None, now), "Nunca");
+        assert_eq!(format_last_seen(&t, Some("2026-10-17T09:00:00Z"), now), "hace 3 horas");
+    }
+
+    #[test]
+    fn test_status_labels_are_translated() {
+        let t = Translator::new(Locale::PtBr);
+        assert_eq!(t.t(status_label_id(UserStatus::Active)), "Ativo");
+        assert_eq!(t.t(status_label_id(UserStatus::Suspended)), "Suspenso");
+    }
+
+    #[test]
+    fn test_sort_by_last_seen() {
+        let user = |id: i32, last_login_at: Option<&str>| User {
+            id,
+            name: format!("User {}", id),
+            email: format!("user{}@example.com", id),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: last_login_at.map(str::to_string),
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+        let users = vec![
+            user(1, Some("2026-10-16T08:00:00Z")),
+            user(2, None),
+            user(3, Some("2026-10-17T08:00:00Z")),
+        ];
+        let ids = |order| -> Vec<i32> { sort_by_last_seen(&users, order).iter().map(|u| u.id).collect() };
+
+        assert_eq!(ids(LastSeenSort::None), vec![1, 2, 3]);
+        assert_eq!(ids(LastSeenSort::MostRecent), vec![3, 1, 2]);
+        assert_eq!(ids(LastSeenSort::LeastRecent), vec![1, 3, 2]);
+        assert_eq!(LastSeenSort::LeastRecent.next(), LastSeenSort::None);
+    }
+
+    #[test]
+    fn test_filter_and_count_tags() {
+        let user = |id: i32, tags: &[&str]| User {
+            id,
+            name: format!("User {}", id),
+            email: format!("user{}@example.com", id),
+            username: None,
+            status: UserStatus::Active,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+        let users = vec![user(1, &["pioneer", "vip"]), user(2, &["pioneer"]), user(3, &[])];
+        let ids = |tag| -> Vec<i32> { filter_by_tag(&users, tag).iter().map(|u| u.id).collect() };
+
+        assert_eq!(ids(None), vec![1, 2, 3]);
+        assert_eq!(ids(Some("pioneer")), vec![1, 2]);
+        assert_eq!(ids(Some("vip")), vec![1]);
+        assert!(ids(Some("beta")).is_empty());
+        assert_eq!(
+            count_tags(&users),
+            vec![("pioneer".to_string(), 2), ("vip".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_apply_view() {
+        let user = |id: i32, name: &str, status: UserStatus, tags: &[&str], last_login_at: Option<&str>| User {
+            id,
+            name: name.to_string(),
+            email: format!("user{}@example.com", id),
+            username: (id == 3).then(|| "ada-lovelace".to_string()),
+            status,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            last_login_at: last_login_at.map(str::to_string),
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+        let users = vec![
+            user(1, "John Doe", UserStatus::Active, &["vip"], Some("2026-10-16T08:00:00Z")),
+            user(2, "Jane Doe", UserStatus::Suspended, &["vip"], None),
+            user(3, "Ada", UserStatus::Active, &[], Some("2026-10-17T08:00:00Z")),
+        ];
+        let ids = |view: ListView| -> Vec<i32> { apply_view(&users, &view).iter().map(|u| u.id).collect() };
+
+        assert_eq!(ids(ListView::default()), vec![1, 2, 3]);
+        assert_eq!(ids(ListView { search: " DOE ".to_string(), ..ListView::default() }), vec![1, 2]);
+        assert_eq!(ids(ListView { search: "lovelace".to_string(), ..ListView::default() }), vec![3]);
+        assert_eq!(ids(ListView { search: "user2@".to_string(), ..ListView::default() }), vec![2]);
+        assert_eq!(
+            ids(ListView {
+                sort: LastSeenSort::MostRecent,
+                status: Some(UserStatus::Active),
+                ..ListView::default()
+            }),
+            vec![3, 1]
+        );
+        assert_eq!(
+            ids(ListView {
+                tag: Some("vip".to_string()),
+                status: Some(UserStatus::Suspended),
+                ..ListView::default()
+            }),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_view_name_error() {
+        let t = Translator::new(Locale::En);
+        let views: Vec<SavedView> = (0..MAX_SAVED_VIEWS)
+            .map(|i| SavedView { name: format!("View {}", i), view: ListView::default() })
+            .collect();
+        assert_eq!(view_name_error(&t, &[], "VIPs"), None);
+        assert_eq!(view_name_error(&t, &[], "  ").as_deref(), Some("Give the view a name"));
+        assert_eq!(
+            view_name_error(&t, &[], &"v".repeat(41)).as_deref(),
+            Some("View names can be at most 40 characters")
+        );
+        assert_eq!(view_name_error(&t, &views, "VIPs").as_deref(), Some("At most 20 views can be saved"));
+        // Replacing a saved view is allowed at the limit
+        assert_eq!(view_name_error(&t, &views, "View 3"), None);
+    }
+
+    #[test]
+    fn test_tags_error() {
+        let t = Translator::new(Locale::En);
+        assert_eq!(tags_error(&t, "vip, pioneer"), None);
+        assert_eq!(tags_error(&t, ""), None);
+        assert_eq!(
+            tags_error(&t, "vip!").as_deref(),
+            Some("Tags may only use lowercase letters, digits and single dashes between them")
+        );
+        assert_eq!(tags_error(&t, &"a".repeat(25)).as_deref(), Some("Tags must be at most 24 characters"));
+        let many: Vec<String> = (0..11).map(|i| format!("tag-{}", i)).collect();
+        assert_eq!(tags_error(&t, &many.join(",")).as_deref(), Some("A user can have at most 10 tags"));
+    }
+
+    #[test]
+    fn test_list_grid_adds_checkbox_column() {
+        let columns = ColumnSettings::default();
+        assert!(columns.grid_template(true).starts_with("30px 50px"));
+        assert!(columns.grid_template(false).starts_with("50px"));
+    }
+
+    #[test]
+    fn test_text_field_props_defaults() {
+        let props = yew::props!(TextFieldProps {
+            id: "user-name",
+            label: "Name",
+            value: String::new(),
+            oninput: Callback::noop(),
+        });
+
+        assert_eq!(props.input_type, "text");
+        assert!(!props.required);
+        assert_eq!(field_description_id(&props), None);
+    }
+
+    #[test]
+    fn test_field_description_prefers_error() {
+        let mut props = yew::props!(TextFieldProps {
+            id: "user-password",
+            label: "Password",
+            value: String::new(),
+            oninput: Callback::noop(),
+            help: Some("At least 6 characters".to_string()),
+        });
+        assert_eq!(field_description_id(&props).as_deref(), Some("user-password-help"));
+
+        props.error = Some("Too short".to_string());
+        assert_eq!(field_description_id(&props).as_deref(), Some("user-password-error"));
+    }
+
+    #[test]
+    fn test_email_field_error() {
+        let t = Translator::new(Locale::En);
+        assert_eq!(email_field_error(&t, ""), None);
+        assert_eq!(email_field_error(&t, "john@example.com"), None);
+        assert_eq!(email_field_error(&t, "john").as_deref(), Some("Invalid email format"));
+    }
+
+    #[test]
+    fn test_username_field_feedback() {
+        let t = Translator::new(Locale::En);
+        let state = |username: &str, username_locked: bool| UserFormState {
+            username: username.to_string(),
+            username_locked,
+            ..UserFormState::new()
+        };
+
+        let (error, help) = username_field_feedback(&t, &state("", false), None);
+        assert_eq!(error, None);
+        assert!(help.is_some());
+
+        let (error, _) = username_field_feedback(&t, &state("ab", false), None);
+        assert_eq!(error.as_deref(), Some("Username must be at least 3 characters"));
+
+        let (error, _) = username_field_feedback(&t, &state("ada", false), Some(true));
+        assert_eq!(error.as_deref(), Some("Username ada is already taken"));
+
+        let (error, help) = username_field_feedback(&t, &state("ada", false), Some(false));
+        assert_eq!(error, None);
+        assert_eq!(help.as_deref(), Some("ada is available"));
+
+        // A locked username is never re-checked or reported
+        let (error, help) = username_field_feedback(&t, &state("ada", true), Some(true));
+        assert_eq!(error, None);
+        assert_eq!(help.as_deref(), Some("Usernames cannot be changed"));
+    }
+
+    #[test]
+    fn test_empty_state_props_defaults() {
+        let props = yew::props!(EmptyStateProps {
+            message: "No users yet".to_string(),
+        });
+
+        assert_eq!(props.icon, "∅");
+        assert!(props.action_label.is_none());
+        assert!(props.on_action.is_none());
+    }
+
+    #[test]
+    fn test_error_state_props() {
+        let props = ErrorStateProps {
+            message: "Failed to fetch users".to_string(),
+            on_retry: Callback::noop(),
+        };
+
+        assert_eq!(props.message, "Failed to fetch users");
+    }
+
+    #[test]
+    fn test_row_action_labels_name_the_user() {
+        let t = Translator::new(Locale::En);
+        let user = User {
+            id: 1,
+            name: "John".to_string(),
+            email: "john@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+
+        assert_eq!(user_action_label(&t, "a11y-delete-user", &user), "Delete John");
+        let (text, label) = status_action_id(user.status.toggled());
+        assert_eq!(t.t(text), "Suspend");
+        assert_eq!(user_action_label(&t, label, &user), "Suspend John");
+    }
+
+    #[test]
+    fn test_aria_sort_values() {
+        assert_eq!(LastSeenSort::None.aria_sort(), "none");
+        assert_eq!(LastSeenSort::MostRecent.aria_sort(), "descending");
+        assert_eq!(LastSeenSort::LeastRecent.aria_sort(), "ascending");
+    }
+
+    #[test]
+    fn test_strength_level() {
+        assert_eq!(strength_level(PasswordStrength::Weak), 1);
+        assert_eq!(strength_level(PasswordStrength::Strong), 3);
+    }
+
+    #[test]
+    fn test_notification_classes_by_level() {
+        assert!(notification_classes(NotificationLevel::Success).contains("green"));
+        assert!(notification_classes(NotificationLevel::Warning).contains("yellow"));
+        assert!(notification_classes(NotificationLevel::Error).contains("red"));
+    }
+
+    #[test]
+    fn test_activity_events_are_translated() {
+        let t = Translator::new(Locale::PtBr);
+        assert_eq!(activity_event_id("user.updated"), Some("activity-updated"));
+        assert_eq!(activity_event_id("user.unknown"), None);
+        for event in ["user.created", "user.updated", "user.deleted", "user.anonymized", "user.password_changed"] {
+            let id = activity_event_id(event).unwrap();
+            assert_ne!(t.t(id), id);
+        }
+    }
+
+    #[test]
+    fn test_gravatar_url_hashes_normalized_email() {
+        let expected = "https://www.gravatar.com/avatar/973dfe463ec85785f5f95af5ba3906eedb2d931c24e69824a89ea65dba4e813b?s=64&d=identicon";
+        assert_eq!(gravatar_url("test@example.com", 64), expected);
+        assert_eq!(gravatar_url(" Test@Example.com ", 64), expected);
+    }
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("john doe"), "JD");
+        assert_eq!(initials("Maria da Silva"), "MD");
+        assert_eq!(initials("Ana"), "A");
+        assert_eq!(initials(""), "");
+    }
+
+    #[test]
+    fn test_captcha_script_renders_explicitly() {
+        assert_eq!(captcha_script_src(CaptchaProvider::None), None);
+        assert_eq!(
+            captcha_script_src(CaptchaProvider::Turnstile).as_deref(),
+            Some("https://challenges.cloudflare.com/turnstile/v0/api.js?render=explicit&onload=onCaptchaLoaded")
+        );
+        assert_eq!(CaptchaProvider::ReCaptcha.js_global(), Some("grecaptcha"));
+    }
+
+    #[test]
+    fn test_wizard_step_errors_are_translated() {
+        let mut state = UserFormState::new();
+        state.name = "John".to_string();
+        state.email = "john@example.com".to_string();
+        state.step = SignupStep::Password;
+        state.password = "123".to_string();
+
+        let errors = wizard_step_errors(&Translator::new(Locale::En), &state);
+        assert_eq!(errors.get("password").as_deref(), Some("Password must be at least 6 characters"));
+        let errors = wizard_step_errors(&Translator::new(Locale::Es), &state);
+        assert_eq!(errors.get("password").as_deref(), Some("La contraseña debe tener al menos 6 caracteres"));
+
+        state.password = "password123".to_string();
+        assert!(wizard_step_errors(&Translator::new(Locale::En), &state).is_empty());
+    }
+
+    #[test]
+    fn test_mailto_href() {
+        assert_eq!(mailto_href("john@example.com"), "mailto:john@example.com");
+        assert_eq!(mailto_href(" a+b?c@example.com "), "mailto:a+b%3Fc@example.com");
+        assert_eq!(mailto_href("100%#@example.com"), "mailto:100%25%23@example.com");
+    }
+
+    #[test]
+    fn test_copy_and_mail_labels_name_the_user() {
+        let t = Translator::new(Locale::PtBr);
+        let user = User {
+            id: 1,
+            name: "Ana".to_string(),
+            email: "ana@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+        assert_eq!(user_action_label(&t, "a11y-copy-email", &user), "Copiar o e-mail de Ana");
+        assert_eq!(user_action_label(&t, "a11y-mail-user", &user), "Enviar um e-mail para Ana");
+        assert_eq!(t.t("copied"), "Copiado!");
+    }
+
+    #[test]
+    fn test_print_cell_text() {
+        let t = Translator::new(Locale::En);
+        let user = User {
+            id: 7,
+            name: "John".to_string(),
+            email: "john@example.com".to_string(),
+            username: None,
+            status: UserStatus::Suspended,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+        assert_eq!(print_cell_text(&t, &user, Column::Id, 0), "7");
+        assert_eq!(print_cell_text(&t, &user, Column::Email, 0), "john@example.com");
+        assert_eq!(print_cell_text(&t, &user, Column::Status, 0), "Suspended");
+        assert_eq!(print_cell_text(&t, &user, Column::LastSeen, 0), t.t("last-seen-never"));
+    }
+
+    #[test]
+    fn test_format_address() {
+        let address = Address {
+            street: "Av. Paulista, 1000".to_string(),
+            complement: Some("Apt 12".to_string()),
+            city: "São Paulo".to_string(),
+            region: Some("SP".to_string()),
+            postal_code: "01310-100".to_string(),
+            country: "BR".to_string(),
+        };
+        assert_eq!(
+            format_address(&address),
+            "Av. Paulista, 1000, Apt 12\n01310-100 São Paulo, SP\nBR"
+        );
+
+        let address = Address {
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+            country: "US".to_string(),
+            ..Address::default()
+        };
+        assert_eq!(format_address(&address), "1 Main St\nSpringfield\nUS");
+    }
 }
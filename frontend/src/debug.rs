@@ -0,0 +1,239 @@
+// Debug Module - Single Responsibility Principle
+// Developer panel behind the `debug_panel` feature flag: lists the latest
+// calls of HttpUserApiClient with their status, latency and a preview of the
+// payloads, clears the client caches and switches the app to the demo store
+// for the rest of the tab's session. Calls are only recorded while it is open
+
+use crate::components::FOCUS_RING;
+use crate::demo;
+use crate::flags::{use_feature_flags, FeatureFlags, DEBUG_PANEL};
+use crate::i18n::use_translation;
+use crate::local_cache;
+use gloo::events::EventListener;
+use gloo::storage::{SessionStorage, Storage};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use yew::prelude::*;
+
+// Calls kept for the panel; older ones are dropped
+pub const MAX_RECORDED_CALLS: usize = 50;
+// Characters of a request or response body shown in the panel
+pub const PREVIEW_LENGTH: usize = 200;
+
+// Dispatched on `window` whenever a call is recorded or the list cleared
+const API_CALLS_CHANGED_EVENT: &str = "api-calls-changed";
+// Session storage, so a forgotten switch ends with the tab
+const MOCK_CLIENT_STORAGE_KEY: &str = "debug-mock-client";
+
+thread_local! {
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+    // Newest first
+    static CALLS: RefCell<VecDeque<ApiCall>> = const { RefCell::new(VecDeque::new()) };
+}
+
+// One request sent by HttpUserApiClient
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiCall {
+    pub method: String,
+    pub url: String,
+    // None when no response arrived
+    pub status: Option<u16>,
+    pub latency_ms: u32,
+    pub request: Option<String>,
+    pub response: Option<String>,
+}
+
+// Start of `body`, cut at PREVIEW_LENGTH characters
+pub fn preview(body: &str) -> String {
+    match body.char_indices().nth(PREVIEW_LENGTH) {
+        Some((end, _)) => format!("{}…", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+// Add `call` in front of `calls`, dropping the oldest beyond MAX_RECORDED_CALLS
+pub fn push_call(calls: &mut VecDeque<ApiCall>, call: ApiCall) {
+    calls.push_front(call);
+    calls.truncate(MAX_RECORDED_CALLS);
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.with(Cell::get)
+}
+
+fn set_recording(recording: bool) {
+    RECORDING.with(|cell| cell.set(recording));
+}
+
+pub fn record(call: ApiCall) {
+    CALLS.with(|calls| push_call(&mut calls.borrow_mut(), call));
+    dispatch_changed();
+}
+
+pub fn recorded_calls() -> Vec<ApiCall> {
+    CALLS.with(|calls| calls.borrow().iter().cloned().collect())
+}
+
+pub fn clear_calls() {
+    CALLS.with(|calls| calls.borrow_mut().clear());
+    dispatch_changed();
+}
+
+fn dispatch_changed() {
+    if let (Some(window), Ok(event)) = (web_sys::window(), web_sys::Event::new(API_CALLS_CHANGED_EVENT)) {
+        let _ = window.dispatch_event(&event);
+    }
+}
+
+// Whether regular builds answer from the demo store instead of the backend
+pub fn mock_client_enabled() -> bool {
+    SessionStorage::get(MOCK_CLIENT_STORAGE_KEY).unwrap_or(false)
+}
+
+fn reload() {
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().reload();
+    }
+}
+
+// Drop the cached user list and feature flags; the reload also empties the
+// copies held in memory
+fn clear_caches() {
+    local_cache::clear_user_list();
+    FeatureFlags::clear_cached();
+    reload();
+}
+
+// Every client is built on page load, so the switch takes effect on reload
+fn set_mock_client(enabled: bool) {
+    if enabled {
+        let _ = SessionStorage::set(MOCK_CLIENT_STORAGE_KEY, true);
+    } else {
+        SessionStorage::delete(MOCK_CLIENT_STORAGE_KEY);
+    }
+    reload();
+}
+
+#[function_component(DebugPanel)]
+pub fn debug_panel() -> Html {
+    let flags = use_feature_flags();
+    if flags.is_enabled(DEBUG_PANEL) {
+        html! { <DebugPanelContent /> }
+    } else {
+        html! {}
+    }
+}
+
+#[function_component(DebugPanelContent)]
+fn debug_panel_content() -> Html {
+    let t = use_translation();
+    let calls = use_state(recorded_calls);
+
+    {
+        let calls = calls.clone();
+        use_effect_with((), move |_| {
+            set_recording(true);
+            let listener = web_sys::window().map(|window| {
+                EventListener::new(&window, API_CALLS_CHANGED_EVENT, move |_| calls.set(recorded_calls()))
+            });
+            move || {
+                set_recording(false);
+                drop(listener);
+            }
+        });
+    }
+
+    let mock = mock_client_enabled();
+    let button = classes!("border", "rounded", "px-2", "py-1", "dark:border-gray-600", FOCUS_RING);
+    let row = |call: &ApiCall| {
+        let status = call.status.map(|status| status.to_string()).unwrap_or_else(|| "—".to_string());
+        let payload = [call.request.as_deref(), call.response.as_deref()].into_iter().flatten().collect::<Vec<_>>();
+        html! {
+            <tr class="align-top">
+                <td class="py-1 pr-4 font-mono">{ &call.method }</td>
+                <td class="py-1 pr-4 font-mono break-all">{ &call.url }</td>
+                <td class="py-1 pr-4">{ status }</td>
+                <td class="py-1 pr-4">{ t.t_with("debug-latency", &[("ms", call.latency_ms.into())]) }</td>
+                <td class="py-1 font-mono text-xs break-all">
+                    { for payload.into_iter().map(|text| html! { <div>{ text }</div> }) }
+                </td>
+            </tr>
+        }
+    };
+
+    html! {
+        <details class="fixed bottom-0 right-0 z-20 m-2 max-h-[50vh] w-[40rem] max-w-full overflow-auto p-2 border rounded shadow bg-white text-sm dark:bg-gray-800 dark:border-gray-700">
+            <summary class={classes!("cursor-pointer", "font-semibold", FOCUS_RING)}>
+                { t.t_with("debug-panel", &[("count", calls.len().into())]) }
+            </summary>
+            <div class="my-2 flex flex-wrap gap-2">
+                <button type="button" class={button.clone()} onclick={Callback::from(|_: MouseEvent| clear_calls())}>
+                    { t.t("debug-clear-calls") }
+                </button>
+                <button type="button" class={button.clone()} onclick={Callback::from(|_: MouseEvent| clear_caches())}>
+                    { t.t("debug-clear-caches") }
+                </button>
+                // Demo builds have no backend to switch back to
+                if !demo::ENABLED {
+                    <button type="button" class={button} onclick={Callback::from(move |_: MouseEvent| set_mock_client(!mock))}>
+                        { t.t(if mock { "debug-use-backend" } else { "debug-use-mock" }) }
+                    </button>
+                }
+            </div>
+            if calls.is_empty() {
+                <p class="text-gray-500 dark:text-gray-400">{ t.t("debug-no-calls") }</p>
+            } else {
+                <table class="w-full border-collapse">
+                    <thead>
+                        <tr class="text-left">
+                            <th class="pr-4">{ t.t("debug-method") }</th>
+                            <th class="pr-4">{ t.t("debug-url") }</th>
+                            <th class="pr-4">{ t.t("debug-status") }</th>
+                            <th class="pr-4">{ t.t("debug-latency-header") }</th>
+                            <th>{ t.t("debug-payload") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for calls.iter().map(row) }
+                    </tbody>
+                </table>
+            }
+        </details>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(url: &str) -> ApiCall {
+        ApiCall {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            status: Some(200),
+            latency_ms: 12,
+            request: None,
+            response: None,
+        }
+    }
+
+    #[test]
+    fn test_preview() {
+        assert_eq!(preview("{\"tags\":[]}"), "{\"tags\":[]}");
+        let long = "é".repeat(PREVIEW_LENGTH + 1);
+        let cut = preview(&long);
+        assert_eq!(cut.chars().count(), PREVIEW_LENGTH + 1);
+        assert!(cut.ends_with('…'));
+    }
+
+    #[test]
+    fn test_push_call_keeps_the_newest() {
+        let mut calls = VecDeque::new();
+        for index in 0..MAX_RECORDED_CALLS + 2 {
+            push_call(&mut calls, call(&format!("/users/{}", index)));
+        }
+        assert_eq!(calls.len(), MAX_RECORDED_CALLS);
+        assert_eq!(calls[0].url, format!("/users/{}", MAX_RECORDED_CALLS + 1));
+        assert_eq!(calls[MAX_RECORDED_CALLS - 1].url, "/users/2");
+    }
+}
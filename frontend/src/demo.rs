@@ -0,0 +1,653 @@
+// Demo Module - Single Responsibility Principle
+// In-browser stand-in for the backend, used instead of HttpUserApiClient when
+// the frontend is built with the `demo` feature. Users live in localStorage,
+// seeded with sample users on the first visit, so the UI can be hosted as
+// static files (e.g. on GitHub Pages) and shown without a server
+
+use crate::api::{
+    ActivityEntry, ApiError, ApiResult, BulkUpdateRequest, BulkUpdateResult, CaptchaSettings, CreateUserRequest, DailySignups,
+    SubmitResult, UpdateUserRequest, User, UserApiClient, UserStats, UserStatus,
+};
+use crate::debug;
+use crate::i18n::use_translation;
+use crate::preferences::UserPreferences;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use shared::validation::{normalize_tags, validate_tags};
+use std::collections::BTreeMap;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::js_sys;
+use yew::prelude::*;
+
+const DEMO_STORAGE_KEY: &str = "demo-data";
+
+// Whether this build talks to the demo store instead of the backend
+pub const ENABLED: bool = cfg!(feature = "demo");
+
+const SAMPLE_USERS: [(&str, &str); 8] = [
+    ("Ada Lovelace", "ada@example.com"),
+    ("Alan Turing", "alan@example.com"),
+    ("Grace Hopper", "grace@example.com"),
+    ("Linus Torvalds", "linus@example.com"),
+    ("Margaret Hamilton", "margaret@example.com"),
+    ("Dennis Ritchie", "dennis@example.com"),
+    ("Barbara Liskov", "barbara@example.com"),
+    ("Ken Thompson", "ken@example.com"),
+];
+
+// Tags of the sample users, assigned in turn
+const SAMPLE_TAGS: [&[&str]; 3] = [&["pioneer", "vip"], &["pioneer"], &[]];
+
+// Everything the fake backend knows, saved as one localStorage entry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DemoData {
+    next_id: i32,
+    users: Vec<User>,
+    // Audit log per user id, oldest first
+    activity: BTreeMap<i32, Vec<ActivityEntry>>,
+    preferences: BTreeMap<i32, UserPreferences>,
+}
+
+impl DemoData {
+    // Store holding the sample users, created at `now`
+    pub fn seeded(now: &str) -> Self {
+        let mut data = Self {
+            next_id: 1,
+            users: Vec::new(),
+            activity: BTreeMap::new(),
+            preferences: BTreeMap::new(),
+        };
+        for (index, (name, email)) in SAMPLE_USERS.iter().enumerate() {
+            let id = data.insert(name, email, now);
+            // Profile URLs work out of the box, e.g. `/users/@ada`
+            data.users[index].username = email.split('@').next().map(str::to_string);
+            // Enough tags for the filter bar to have something to narrow
+            data.users[index].tags = SAMPLE_TAGS[index % SAMPLE_TAGS.len()].iter().map(|tag| tag.to_string()).collect();
+            // A few users have logged in, one is locked out
+            if index % 3 == 0 {
+                data.users[index].last_login_at = Some(now.to_string());
+            }
+            if index == SAMPLE_USERS.len() - 1 {
+                data.users[index].status = UserStatus::Suspended;
+                data.record(id, "user.suspended", now);
+            }
+        }
+        data
+    }
+
+    // Data of the previous visits, or the sample users on the first one
+    pub fn load() -> Self {
+        LocalStorage::get(DEMO_STORAGE_KEY).unwrap_or_else(|_| Self::seeded(&now_utc()))
+    }
+
+    // Losing a write only loses that change of the demo
+    pub fn store(&self) {
+        let _ = LocalStorage::set(DEMO_STORAGE_KEY, self);
+    }
+
+    // Start over from the sample users on the next load
+    pub fn reset() {
+        LocalStorage::delete(DEMO_STORAGE_KEY);
+    }
+
+    pub fn users(&self) -> Vec<User> {
+        self.users.clone()
+    }
+
+    pub fn user(&self, id: i32) -> ApiResult<User> {
+        self.users
+            .iter()
+            .find(|user| user.id == id)
+            .cloned()
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    pub fn user_by_username(&self, username: &str) -> ApiResult<User> {
+        self.users
+            .iter()
+            .find(|user| user.username.as_deref() == Some(username))
+            .cloned()
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    // Whether a user other than `id` has the username
+    pub fn username_taken(&self, username: &str, id: Option<i32>) -> bool {
+        self.users
+            .iter()
+            .any(|user| Some(user.id) != id && user.username.as_deref() == Some(username))
+    }
+
+    pub fn set_tags(&mut self, id: i32, tags: Vec<String>, now: &str) -> Result<(), ApiError> {
+        let tags = normalize_tags(&tags);
+        if let Err(error) = validate_tags(&tags) {
+            return Err(ApiError {
+                message: error.to_string(),
+                field: Some("tags".to_string()),
+            });
+        }
+        let user = self
+            .users
+            .iter_mut()
+            .find(|user| user.id == id)
+            .ok_or_else(|| ApiError::general("User not found"))?;
+        user.tags = tags;
+        user.updated_at = Some(now.to_string());
+        self.record(id, "user.updated", now);
+        Ok(())
+    }
+
+    // Applies `request` to each of its users like the backend does: a missing
+    // user, or one that would end up with too many tags, gets its own error
+    // and is left untouched
+    pub fn bulk_update(&mut self, request: &BulkUpdateRequest, now: &str) -> Vec<BulkUpdateResult> {
+        let mut ids: Vec<i32> = Vec::new();
+        for id in &request.ids {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+        ids.into_iter()
+            .map(|id| match self.bulk_update_user(id, request, now) {
+                Ok(user) => BulkUpdateResult {
+                    id,
+                    user: Some(user),
+                    message: None,
+                },
+                Err(error) => BulkUpdateResult {
+                    id,
+                    user: None,
+                    message: Some(error.message),
+                },
+            })
+            .collect()
+    }
+
+    fn bulk_update_user(&mut self, id: i32, request: &BulkUpdateRequest, now: &str) -> Result<User, ApiError> {
+        let user = self.user(id).map_err(ApiError::general)?;
+        let tags = if request.add_tags.is_empty() && request.remove_tags.is_empty() {
+            None
+        } else {
+            let remove = normalize_tags(&request.remove_tags);
+            let mut tags = user.tags.clone();
+            tags.extend(normalize_tags(&request.add_tags));
+            tags.retain(|tag| !remove.contains(tag));
+            let tags = normalize_tags(&tags);
+            validate_tags(&tags).map_err(|error| ApiError::general(error.to_string()))?;
+            Some(tags)
+        };
+        if let Some(status) = request.status {
+            self.set_status(id, status, now).map_err(ApiError::general)?;
+        }
+        if let Some(tags) = tags {
+            self.set_tags(id, tags, now)?;
+        }
+        self.user(id).map_err(ApiError::general)
+    }
+
+    // CSV of the users with `ids` (all of them when empty), in the backend's export format
+    pub fn export_csv(&self, ids: &[i32]) -> String {
+        let mut csv = String::from("id,name,email,status,last_login_at\n");
+        for user in self.users.iter().filter(|user| ids.is_empty() || ids.contains(&user.id)) {
+            let status = match user.status {
+                UserStatus::Active => "active",
+                UserStatus::Suspended => "suspended",
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                user.id,
+                csv_field(&user.name),
+                csv_field(&user.email),
+                status,
+                user.last_login_at.as_deref().unwrap_or_default()
+            ));
+        }
+        csv
+    }
+
+    pub fn activity(&self, id: i32) -> Vec<ActivityEntry> {
+        self.activity.get(&id).cloned().unwrap_or_default()
+    }
+
+    pub fn email_exists(&self, email: &str) -> bool {
+        self.users.iter().any(|user| user.email.eq_ignore_ascii_case(email))
+    }
+
+    pub fn create(&mut self, request: &CreateUserRequest, now: &str) -> SubmitResult {
+        if self.email_exists(&request.email) {
+            return Err(email_taken(&request.email));
+        }
+        let taken = request.username.as_deref().filter(|username| self.username_taken(username, None));
+        if let Some(username) = taken {
+            return Err(username_taken(username));
+        }
+        let id = self.insert(&request.name, &request.email, now);
+        if let Some(user) = self.users.iter_mut().find(|user| user.id == id) {
+            user.username = request.username.clone();
+            user.phone = request.phone.clone();
+            user.address = request.address.clone();
+        }
+        Ok(())
+    }
+
+    pub fn update(&mut self, request: &UpdateUserRequest, now: &str) -> SubmitResult {
+        if self
+            .users
+            .iter()
+            .any(|user| user.id != request.id && user.email.eq_ignore_ascii_case(&request.email))
+        {
+            return Err(email_taken(&request.email));
+        }
+        let taken = request.username.as_deref().filter(|username| self.username_taken(username, Some(request.id)));
+        if let Some(username) = taken {
+            return Err(username_taken(username));
+        }
+        let user = self
+            .users
+            .iter_mut()
+            .find(|user| user.id == request.id)
+            .ok_or_else(|| ApiError::general("User not found"))?;
+        // Like the backend, a username is set once and never changed
+        match (&user.username, &request.username) {
+            (Some(current), Some(requested)) if current != requested => {
+                return Err(ApiError {
+                    message: "Username cannot be changed".to_string(),
+                    field: Some("username".to_string()),
+                });
+            }
+            (None, Some(_)) => user.username = request.username.clone(),
+            _ => {}
+        }
+        user.name = request.name.clone();
+        user.email = request.email.clone();
+        user.phone = request.phone.clone();
+        user.address = request.address.clone();
+        user.updated_at = Some(now.to_string());
+        self.record(request.id, "user.updated", now);
+        Ok(())
+    }
+
+    pub fn delete(&mut self, id: i32) -> ApiResult<()> {
+        let count = self.users.len();
+        self.users.retain(|user| user.id != id);
+        if self.users.len() == count {
+            return Err("Failed to delete user".to_string());
+        }
+        self.activity.remove(&id);
+        self.preferences.remove(&id);
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, id: i32, status: UserStatus, now: &str) -> ApiResult<()> {
+        let user = self
+            .users
+            .iter_mut()
+            .find(|user| user.id == id)
+            .ok_or_else(|| format!("Failed to {} user", status.action()))?;
+        user.status = status;
+        user.updated_at = Some(now.to_string());
+        let event = match status {
+            UserStatus::Active => "user.activated",
+            UserStatus::Suspended => "user.suspended",
+        };
+        self.record(id, event, now);
+        Ok(())
+    }
+
+    pub fn preferences(&self, user_id: i32) -> UserPreferences {
+        self.preferences.get(&user_id).cloned().unwrap_or_default()
+    }
+
+    pub fn save_preferences(&mut self, user_id: i32, preferences: UserPreferences) {
+        self.preferences.insert(user_id, preferences);
+    }
+
+    // Every demo user counts as verified; signups are read from the audit log
+    pub fn stats(&self) -> UserStats {
+        let mut per_day: BTreeMap<String, i64> = BTreeMap::new();
+        for entry in self.activity.values().flatten().filter(|entry| entry.event == "user.created") {
+            *per_day.entry(entry.occurred_at.chars().take(10).collect()).or_default() += 1;
+        }
+        UserStats {
+            total: self.users.len() as i64,
+            verified: self.users.len() as i64,
+            unverified: 0,
+            signups_per_day: per_day
+                .into_iter()
+                .map(|(day, count)| DailySignups { day, count })
+                .collect(),
+        }
+    }
+
+    fn insert(&mut self, name: &str, email: &str, now: &str) -> i32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.users.push(User {
+            id,
+            name: name.to_string(),
+            email: email.to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: Some(now.to_string()),
+            updated_at: Some(now.to_string()),
+            phone: None,
+            address: None,
+        });
+        self.record(id, "user.created", now);
+        id
+    }
+
+    fn record(&mut self, id: i32, event: &str, now: &str) {
+        self.activity.entry(id).or_default().push(ActivityEntry {
+            event: event.to_string(),
+            occurred_at: now.to_string(),
+        });
+    }
+}
+
+// Quoted like the backend's export, formula-like values included
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn email_taken(email: &str) -> ApiError {
+    ApiError {
+        message: format!("A user with email {} already exists", email),
+        field: Some("email".to_string()),
+    }
+}
+
+fn username_taken(username: &str) -> ApiError {
+    ApiError {
+        message: format!("Username {} is already taken", username),
+        field: Some("username".to_string()),
+    }
+}
+
+// Current time as `YYYY-MM-DDTHH:MM:SSZ`, the format the backend sends
+fn now_utc() -> String {
+    let iso = String::from(js_sys::Date::new_0().to_iso_string());
+    format!("{}Z", &iso[..19])
+}
+
+// Run `operation` on the stored data and save it; the result arrives
+// asynchronously, like a response from the backend would
+fn with_data<T: 'static>(callback: Callback<T>, operation: impl FnOnce(&mut DemoData, &str) -> T) {
+    let mut data = DemoData::load();
+    let result = operation(&mut data, &now_utc());
+    data.store();
+    spawn_local(async move { callback.emit(result) });
+}
+
+// API client answering from the demo store in localStorage
+#[derive(Clone, Default)]
+pub struct DemoUserApiClient;
+
+impl UserApiClient for DemoUserApiClient {
+    fn fetch_users(&self, callback: Callback<ApiResult<Vec<User>>>) {
+        with_data(callback, |data, _| Ok(data.users()));
+    }
+
+    fn fetch_user(&self, id: i32, callback: Callback<ApiResult<User>>) {
+        with_data(callback, |data, _| data.user(id));
+    }
+
+    fn fetch_user_by_username(&self, username: String, callback: Callback<ApiResult<User>>) {
+        with_data(callback, |data, _| data.user_by_username(&username));
+    }
+
+    fn fetch_activity(&self, id: i32, callback: Callback<ApiResult<Vec<ActivityEntry>>>) {
+        with_data(callback, |data, _| Ok(data.activity(id)));
+    }
+
+    fn fetch_preferences(&self, user_id: i32, callback: Callback<ApiResult<UserPreferences>>) {
+        with_data(callback, |data, _| Ok(data.preferences(user_id)));
+    }
+
+    fn save_preferences(&self, user_id: i32, preferences: UserPreferences, callback: Callback<SubmitResult>) {
+        with_data(callback, |data, _| {
+            data.save_preferences(user_id, preferences);
+            Ok(())
+        });
+    }
+
+    fn create_user(&self, request: CreateUserRequest, callback: Callback<SubmitResult>) {
+        with_data(callback, |data, now| data.create(&request, now));
+    }
+
+    fn sign_up(&self, request: CreateUserRequest, callback: Callback<SubmitResult>) {
+        with_data(callback, |data, now| data.create(&request, now));
+    }
+
+    fn email_exists(&self, email: String, callback: Callback<ApiResult<bool>>) {
+        with_data(callback, |data, _| Ok(data.email_exists(&email)));
+    }
+
+    fn username_available(&self, username: String, callback: Callback<ApiResult<bool>>) {
+        with_data(callback, |data, _| Ok(!data.username_taken(&username, None)));
+    }
+
+    // No emails are sent in the demo, so any link counts as confirmed
+    fn verify_email(&self, _token: String, callback: Callback<ApiResult<()>>) {
+        with_data(callback, |_, _| Ok(()));
+    }
+
+    fn fetch_captcha_settings(&self, callback: Callback<ApiResult<CaptchaSettings>>) {
+        with_data(callback, |_, _| Ok(CaptchaSettings::default()));
+    }
+
+    fn update_user(&self, request: UpdateUserRequest, callback: Callback<SubmitResult>) {
+        with_data(callback, |data, now| data.update(&request, now));
+    }
+
+    fn delete_user(&self, id: i32, callback: Callback<ApiResult<()>>) {
+        with_data(callback, |data, _| data.delete(id));
+    }
+
+    fn set_user_status(&self, id: i32, status: UserStatus, callback: Callback<ApiResult<()>>) {
+        with_data(callback, |data, now| data.set_status(id, status, now));
+    }
+
+    fn set_user_tags(&self, id: i32, tags: Vec<String>, callback: Callback<SubmitResult>) {
+        with_data(callback, |data, now| data.set_tags(id, tags, now));
+    }
+
+    fn bulk_update_users(
+        &self,
+        request: BulkUpdateRequest,
+        callback: Callback<Result<Vec<BulkUpdateResult>, ApiError>>,
+    ) {
+        with_data(callback, |data, now| Ok(data.bulk_update(&request, now)));
+    }
+
+    fn export_users(&self, ids: Vec<i32>, callback: Callback<ApiResult<String>>) {
+        with_data(callback, |data, _| Ok(data.export_csv(&ids)));
+    }
+
+    fn fetch_stats(&self, callback: Callback<ApiResult<UserStats>>) {
+        with_data(callback, |data, _| Ok(data.stats()));
+    }
+}
+
+// Tells visitors of a demo build that their changes stay in the browser;
+// renders nothing in regular builds
+#[function_component(DemoBanner)]
+pub fn demo_banner() -> Html {
+    let t = use_translation();
+    // Also shown while the debug panel switched a regular build to the demo store
+    if !ENABLED && !debug::mock_client_enabled() {
+        return html! {};
+    }
+    let on_reset = Callback::from(|_: MouseEvent| {
+        DemoData::reset();
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().reload();
+        }
+    });
+    html! {
+        <div role="status" class="mb-4 flex items-center justify-between gap-4 rounded bg-amber-100 px-4 py-2 text-amber-900 dark:bg-amber-900 dark:text-amber-100">
+            <span>{ t.t("demo-banner") }</span>
+            <button type="button" class="underline" onclick={on_reset}>{ t.t("demo-reset") }</button>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: &str = "2026-10-17T12:00:00Z";
+
+    fn request(name: &str, email: &str) -> CreateUserRequest {
+        CreateUserRequest {
+            name: name.to_string(),
+            email: email.to_string(),
+            password: "password123".to_string(),
+            username: None,
+            captcha_token: None,
+            phone: None,
+            address: None,
+        }
+    }
+
+    #[test]
+    fn test_seeded_data() {
+        let data = DemoData::seeded(NOW);
+        assert_eq!(data.users().len(), SAMPLE_USERS.len());
+        assert_eq!(data.user(1).unwrap().name, "Ada Lovelace");
+        assert!(data.users().iter().any(|user| user.status == UserStatus::Suspended));
+        assert_eq!(data.stats().signups_per_day, vec![DailySignups {
+            day: "2026-10-17".to_string(),
+            count: SAMPLE_USERS.len() as i64,
+        }]);
+    }
+
+    #[test]
+    fn test_create_and_update() {
+        let mut data = DemoData::seeded(NOW);
+        data.create(&request("Jane Doe", "jane@example.com"), NOW).unwrap();
+        let jane = data.users().into_iter().find(|user| user.email == "jane@example.com").unwrap();
+        assert_eq!(jane.id, SAMPLE_USERS.len() as i32 + 1);
+        assert_eq!(jane.created_at.as_deref(), Some(NOW));
+
+        let err = data.create(&request("Jane Again", "JANE@example.com"), NOW).unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("email"));
+
+        let update = UpdateUserRequest {
+            id: jane.id,
+            name: "Jane Smith".to_string(),
+            email: "ada@example.com".to_string(),
+            password: String::new(),
+            username: None,
+            phone: None,
+            address: None,
+        };
+        assert!(data.update(&update, NOW).is_err());
+        let update = UpdateUserRequest { email: "jane@example.com".to_string(), ..update };
+        data.update(&update, "2026-10-18T09:30:00Z").unwrap();
+        let jane = data.user(jane.id).unwrap();
+        assert_eq!(jane.name, "Jane Smith");
+        assert_eq!(jane.created_at.as_deref(), Some(NOW));
+        assert_eq!(jane.updated_at.as_deref(), Some("2026-10-18T09:30:00Z"));
+        let events: Vec<String> = data.activity(jane.id).into_iter().map(|entry| entry.event).collect();
+        assert_eq!(events, vec!["user.created", "user.updated"]);
+    }
+
+    #[test]
+    fn test_usernames() {
+        let mut data = DemoData::seeded(NOW);
+        assert_eq!(data.user_by_username("ada").unwrap().id, 1);
+        assert!(data.user_by_username("nobody").is_err());
+
+        let taken = CreateUserRequest {
+            username: Some("ada".to_string()),
+            ..request("Ada Again", "ada2@example.com")
+        };
+        assert_eq!(data.create(&taken, NOW).unwrap_err().field.as_deref(), Some("username"));
+
+        let update = UpdateUserRequest {
+            id: 1,
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            password: String::new(),
+            username: Some("countess".to_string()),
+            phone: None,
+            address: None,
+        };
+        assert_eq!(data.update(&update, NOW).unwrap_err().message, "Username cannot be changed");
+        data.update(&UpdateUserRequest { username: None, ..update }, NOW).unwrap();
+        assert_eq!(data.user(1).unwrap().username.as_deref(), Some("ada"));
+    }
+
+    #[test]
+    fn test_set_tags() {
+        let mut data = DemoData::seeded(NOW);
+        data.set_tags(1, vec!["VIP".to_string(), "beta".to_string()], NOW).unwrap();
+        assert_eq!(data.user(1).unwrap().tags, vec!["beta".to_string(), "vip".to_string()]);
+        let error = data.set_tags(1, vec!["not valid".to_string()], NOW).unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("tags"));
+        assert!(data.set_tags(99, Vec::new(), NOW).is_err());
+    }
+
+    #[test]
+    fn test_bulk_update() {
+        let mut data = DemoData::seeded(NOW);
+        let request = BulkUpdateRequest {
+            ids: vec![1, 2, 99, 1],
+            status: Some(UserStatus::Suspended),
+            add_tags: vec!["Beta".to_string()],
+            remove_tags: vec!["vip".to_string()],
+        };
+        let results = data.bulk_update(&request, NOW);
+        assert_eq!(results.iter().map(|result| result.id).collect::<Vec<_>>(), vec![1, 2, 99]);
+        let ada = data.user(1).unwrap();
+        assert_eq!(ada.status, UserStatus::Suspended);
+        assert_eq!(ada.tags, vec!["beta".to_string(), "pioneer".to_string()]);
+        assert_eq!(results[0].user.as_ref(), Some(&ada));
+        assert!(results[2].user.is_none());
+        assert!(results[2].message.is_some());
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let mut data = DemoData::seeded(NOW);
+        data.users[0].name = "Lovelace, Ada".to_string();
+        let csv = data.export_csv(&[1]);
+        assert_eq!(
+            csv,
+            format!("id,name,email,status,last_login_at\n1,\"Lovelace, Ada\",ada@example.com,active,{}\n", NOW)
+        );
+        assert_eq!(data.export_csv(&[]).lines().count(), SAMPLE_USERS.len() + 1);
+        assert_eq!(csv_field("=1+1"), "'=1+1");
+    }
+
+    #[test]
+    fn test_status_and_delete() {
+        let mut data = DemoData::seeded(NOW);
+        data.set_status(1, UserStatus::Suspended, NOW).unwrap();
+        assert_eq!(data.user(1).unwrap().status, UserStatus::Suspended);
+        assert!(data.set_status(99, UserStatus::Active, NOW).is_err());
+
+        data.delete(1).unwrap();
+        assert!(data.user(1).is_err());
+        assert!(data.activity(1).is_empty());
+        assert!(data.delete(1).is_err());
+    }
+
+    #[test]
+    fn test_storage_format_round_trips() {
+        let data = DemoData::seeded(NOW);
+        let json = serde_json::to_string(&data).unwrap();
+        assert_eq!(serde_json::from_str::<DemoData>(&json).unwrap(), data);
+    }
+}
@@ -0,0 +1,141 @@
+// Desktop notifications Module - Single Responsibility Principle
+// Turns live user events (server-sent events) into Web Notifications while
+// the tab is in the background, so admins notice new signups without
+// watching the page. The browser is only asked for permission after a click
+// on the enable button, and events are only listened to once it is granted
+
+use crate::api::{api_url, User};
+use crate::components::FOCUS_RING;
+use crate::demo;
+use crate::i18n::{use_translation, Translator};
+use gloo::events::EventListener;
+use serde::Deserialize;
+use shared::routes;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{js_sys, EventSource, MessageEvent, Notification, NotificationOptions, NotificationPermission};
+use yew::prelude::*;
+
+const USER_CREATED_EVENT: &str = "user.created";
+
+// Body of a `user.created` server-sent event
+#[derive(Deserialize)]
+struct CreatedEvent {
+    user: User,
+}
+
+// The new user announced by a `user.created` event
+pub fn created_user(data: &str) -> Option<User> {
+    serde_json::from_str::<CreatedEvent>(data).ok().map(|event| event.user)
+}
+
+// Notify only when allowed and the user is not looking at the page anyway
+pub fn should_notify(permission: Option<NotificationPermission>, page_hidden: bool) -> bool {
+    page_hidden && permission == Some(NotificationPermission::Granted)
+}
+
+// Permission of this site; None when the browser has no Notification API
+fn permission() -> Option<NotificationPermission> {
+    let window = web_sys::window()?;
+    js_sys::Reflect::has(&window, &"Notification".into())
+        .ok()?
+        .then(Notification::permission)
+}
+
+fn page_hidden() -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| document.hidden())
+}
+
+fn show_new_user(t: &Translator, user: &User) {
+    let options = NotificationOptions::new();
+    options.set_body(&format!("{} <{}>", user.name, user.email));
+    // The tag replaces a notification about the same user instead of stacking
+    options.set_tag(&format!("user-created-{}", user.id));
+    let _ = Notification::new_with_options(&t.t("desktop-notification-new-user"), &options);
+}
+
+// Listens for new users while notifications are allowed; renders the enable
+// button until the browser has been asked
+#[function_component(DesktopNotifications)]
+pub fn desktop_notifications() -> Html {
+    let t = use_translation();
+    let permission = use_state(permission);
+
+    {
+        let t = t.clone();
+        use_effect_with(*permission, move |permission| {
+            // Demo builds have no server sending events
+            let source = if *permission == Some(NotificationPermission::Granted) && !demo::ENABLED {
+                EventSource::new(&api_url(routes::EVENTS)).ok()
+            } else {
+                None
+            };
+            let listener = source.as_ref().map(|source| {
+                EventListener::new(source, USER_CREATED_EVENT, move |event| {
+                    let Some(data) = event.dyn_ref::<MessageEvent>().and_then(|event| event.data().as_string()) else {
+                        return;
+                    };
+                    if let Some(user) = created_user(&data).filter(|_| should_notify(self::permission(), page_hidden())) {
+                        show_new_user(&t, &user);
+                    }
+                })
+            });
+            move || {
+                drop(listener);
+                if let Some(source) = source {
+                    source.close();
+                }
+            }
+        });
+    }
+
+    if *permission != Some(NotificationPermission::Default) {
+        return Html::default();
+    }
+    let enable = {
+        let permission = permission.clone();
+        Callback::from(move |_| {
+            let permission = permission.clone();
+            spawn_local(async move {
+                if let Ok(promise) = Notification::request_permission() {
+                    let _ = JsFuture::from(promise).await;
+                }
+                permission.set(self::permission());
+            });
+        })
+    };
+
+    html! {
+        <button
+            type="button"
+            onclick={enable}
+            class={classes!("border", "rounded", "px-2", "py-1", "text-gray-700", "hover:bg-gray-100", "dark:text-gray-200", "dark:border-gray-600", "dark:hover:bg-gray-700", FOCUS_RING)}
+        >
+            { t.t("desktop-notifications-enable") }
+        </button>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_created_user_from_event_body() {
+        let data = r#"{"type":"created","user":{"id":7,"name":"Jane","email":"jane@example.com","status":"active","last_login_at":null}}"#;
+        let user = created_user(data).unwrap();
+        assert_eq!(user.id, 7);
+        assert_eq!(user.email, "jane@example.com");
+        assert!(created_user(r#"{"type":"deleted","id":7}"#).is_none());
+    }
+
+    #[test]
+    fn test_notify_only_in_background_with_permission() {
+        assert!(should_notify(Some(NotificationPermission::Granted), true));
+        assert!(!should_notify(Some(NotificationPermission::Granted), false));
+        assert!(!should_notify(Some(NotificationPermission::Default), true));
+        assert!(!should_notify(None, true));
+    }
+}
@@ -0,0 +1,47 @@
+// Downloads Module - Single Responsibility Principle
+// Hands text produced by the app, e.g. a CSV export, to the browser as a file
+// download through a temporary object URL
+
+use gloo::file::{Blob, ObjectUrl};
+use gloo::timers::callback::Timeout;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+pub const CSV_MIME: &str = "text/csv";
+
+// Name of the file a CSV export is saved as
+pub fn export_file_name(selected: usize) -> String {
+    match selected {
+        0 => "users.csv".to_string(),
+        count => format!("users-selected-{}.csv", count),
+    }
+}
+
+// Offer `contents` as a file named `file_name`
+pub fn save_file(file_name: &str, mime: &str, contents: &str) {
+    let url = ObjectUrl::from(Blob::new_with_options(contents, Some(mime)));
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+    let Ok(link) = document.create_element("a") else {
+        return;
+    };
+    let _ = link.set_attribute("href", &url);
+    let _ = link.set_attribute("download", file_name);
+    if let Ok(link) = link.dyn_into::<HtmlElement>() {
+        link.click();
+    }
+    // Revoked once the browser has picked the download up
+    Timeout::new(1_000, move || drop(url)).forget();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_file_name() {
+        assert_eq!(export_file_name(0), "users.csv");
+        assert_eq!(export_file_name(3), "users-selected-3.csv");
+    }
+}
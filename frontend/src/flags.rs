@@ -0,0 +1,161 @@
+// Feature Flags Module - Single Responsibility Principle
+// Fetches the backend's feature flags once on startup and shares them through
+// a context, so dark-launched UI is switched on per deployment. The last
+// flags received are kept in localStorage and used until the fetch completes
+
+use crate::api::{api_url, ApiResult};
+use crate::demo;
+use gloo::net::http::Request;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use shared::routes;
+use std::collections::BTreeMap;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+// Flags known to the frontend
+pub const BULK_DELETE: &str = "bulk_delete";
+pub const DEBUG_PANEL: &str = "debug_panel";
+pub const ORG_MANAGEMENT: &str = "org_management";
+
+const FLAGS_STORAGE_KEY: &str = "feature_flags";
+
+// Flag states by name; flags the backend does not report are disabled
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct FeatureFlags(BTreeMap<String, bool>);
+
+impl FeatureFlags {
+    pub fn new(flags: BTreeMap<String, bool>) -> Self {
+        Self(flags)
+    }
+
+    pub fn is_enabled(&self, flag: &str) -> bool {
+        self.0.get(flag).copied().unwrap_or(false)
+    }
+
+    // Flags from the previous visit, if any
+    pub fn cached() -> Option<Self> {
+        LocalStorage::get(FLAGS_STORAGE_KEY).ok()
+    }
+
+    pub fn store(&self) {
+        // Losing the cache only delays the flags until the next fetch
+        let _ = LocalStorage::set(FLAGS_STORAGE_KEY, self);
+    }
+
+    pub fn clear_cached() {
+        LocalStorage::delete(FLAGS_STORAGE_KEY);
+    }
+}
+
+pub fn fetch_feature_flags(callback: Callback<ApiResult<FeatureFlags>>) {
+    // Demo builds show the features the demo store supports
+    if demo::ENABLED {
+        callback.emit(Ok(FeatureFlags::new(BTreeMap::from([(BULK_DELETE.to_string(), true)]))));
+        return;
+    }
+    let url = api_url(routes::FLAGS);
+    spawn_local(async move {
+        match Request::get(&url).send().await {
+            Ok(resp) if resp.ok() => match resp.json::<FeatureFlags>().await {
+                Ok(flags) => callback.emit(Ok(flags)),
+                Err(_) => callback.emit(Err("Failed to parse feature flags".to_string())),
+            },
+            Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+            Err(_) => callback.emit(Err("Failed to fetch feature flags".to_string())),
+        }
+    });
+}
+
+// Props for FeatureFlagsProvider component
+#[derive(Properties, PartialEq)]
+pub struct FeatureFlagsProviderProps {
+    pub children: Html,
+}
+
+// Provides the feature flags to every component below it
+#[function_component(FeatureFlagsProvider)]
+pub fn feature_flags_provider(props: &FeatureFlagsProviderProps) -> Html {
+    let flags = use_state(|| FeatureFlags::cached().unwrap_or_default());
+
+    {
+        let flags = flags.clone();
+        use_effect_with((), move |_| {
+            fetch_feature_flags(Callback::from(move |result: ApiResult<FeatureFlags>| {
+                // On failure the cached flags stay in use
+                if let Ok(fetched) = result {
+                    fetched.store();
+                    flags.set(fetched);
+                }
+            }));
+            || ()
+        });
+    }
+
+    html! {
+        <ContextProvider<FeatureFlags> context={(*flags).clone()}>
+            { props.children.clone() }
+        </ContextProvider<FeatureFlags>>
+    }
+}
+
+// Current feature flags; all disabled outside a FeatureFlagsProvider
+#[hook]
+pub fn use_feature_flags() -> FeatureFlags {
+    use_context::<FeatureFlags>().unwrap_or_default()
+}
+
+// Props for FeatureGate component
+#[derive(Properties, PartialEq)]
+pub struct FeatureGateProps {
+    pub flag: AttrValue,
+    #[prop_or_default]
+    pub children: Html,
+    // Rendered instead of the children while the flag is disabled
+    #[prop_or_default]
+    pub fallback: Html,
+}
+
+#[function_component(FeatureGate)]
+pub fn feature_gate(props: &FeatureGateProps) -> Html {
+    let flags = use_feature_flags();
+    if flags.is_enabled(&props.flag) {
+        props.children.clone()
+    } else {
+        props.fallback.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feature_flags_default_to_disabled() {
+        let flags = FeatureFlags::default();
+        assert!(!flags.is_enabled(BULK_DELETE));
+    }
+
+    #[test]
+    fn test_feature_flags_from_backend_response() {
+        let flags: FeatureFlags =
+            serde_json::from_str(r#"{"bulk_delete":true,"org_management":false}"#).unwrap();
+        assert!(flags.is_enabled(BULK_DELETE));
+        assert!(!flags.is_enabled(ORG_MANAGEMENT));
+        assert!(!flags.is_enabled("unknown"));
+
+        let json = serde_json::to_string(&flags).unwrap();
+        assert_eq!(json, r#"{"bulk_delete":true,"org_management":false}"#);
+    }
+
+    #[test]
+    fn test_feature_gate_props() {
+        let props = FeatureGateProps {
+            flag: AttrValue::from(BULK_DELETE),
+            children: Html::default(),
+            fallback: Html::default(),
+        };
+        assert_eq!(props.flag, "bulk_delete");
+    }
+}
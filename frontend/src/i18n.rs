@@ -0,0 +1,279 @@
+// Internationalization Module - Single Responsibility Principle
+// Renders UI text from Fluent bundles in the chosen locale. The locale is
+// shared through a context, switched from the navigation bar and kept in
+// localStorage; first visits follow the browser's language
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use unic_langid::LanguageIdentifier;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+const LOCALE_STORAGE_KEY: &str = "locale";
+
+// Locales with a translation bundle
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en")]
+    En,
+    #[serde(rename = "pt-BR")]
+    PtBr,
+    #[serde(rename = "es")]
+    Es,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::PtBr, Locale::Es];
+
+    // BCP 47 language tag
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::PtBr => "pt-BR",
+            Locale::Es => "es",
+        }
+    }
+
+    // Name of the locale in its own language, for the language switcher
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::PtBr => "Português (Brasil)",
+            Locale::Es => "Español",
+        }
+    }
+
+    // Best locale for a language tag: an exact match, then the same language
+    pub fn negotiate(tag: &str) -> Option<Locale> {
+        let language = |tag: &str| tag.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(tag))
+            .or_else(|| {
+                Self::ALL
+                    .into_iter()
+                    .find(|locale| language(locale.tag()) == language(tag))
+            })
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en/main.ftl"),
+            Locale::PtBr => include_str!("../locales/pt-BR/main.ftl"),
+            Locale::Es => include_str!("../locales/es/main.ftl"),
+        }
+    }
+
+    // Locale chosen on a previous visit, else the browser's language
+    pub fn preferred() -> Locale {
+        LocalStorage::get(LOCALE_STORAGE_KEY)
+            .ok()
+            .or_else(|| {
+                let language = web_sys::window()?.navigator().language()?;
+                Locale::negotiate(&language)
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn store(&self) {
+        // Losing the choice only falls back to the browser's language
+        let _ = LocalStorage::set(LOCALE_STORAGE_KEY, self);
+    }
+}
+
+// Formats messages of one locale
+#[derive(Clone)]
+pub struct Translator {
+    locale: Locale,
+    bundle: Rc<FluentBundle<FluentResource>>,
+}
+
+impl Translator {
+    pub fn new(locale: Locale) -> Self {
+        let resource = FluentResource::try_new(locale.source().to_string())
+            .unwrap_or_else(|(_, errors)| panic!("Invalid {} messages: {:?}", locale.tag(), errors));
+        let language: LanguageIdentifier = locale.tag().parse().expect("valid language tag");
+        let mut bundle = FluentBundle::new(vec![language]);
+        // Keep rendered text free of Unicode isolation marks
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .unwrap_or_else(|errors| panic!("Duplicate {} messages: {:?}", locale.tag(), errors));
+
+        Translator {
+            locale,
+            bundle: Rc::new(bundle),
+        }
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    // Text of a message without variables
+    pub fn t(&self, id: &str) -> String {
+        self.t_with(id, &[])
+    }
+
+    // Text of a message; missing messages render as their id
+    pub fn t_with(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let Some(pattern) = self.bundle.get_message(id).and_then(|message| message.value()) else {
+            return id.to_string();
+        };
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let mut errors = Vec::new();
+        self.bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .into_owned()
+    }
+}
+
+impl Default for Translator {
+    fn default() -> Self {
+        Self::new(Locale::default())
+    }
+}
+
+impl PartialEq for Translator {
+    fn eq(&self, other: &Self) -> bool {
+        self.locale == other.locale
+    }
+}
+
+// Shared by I18nProvider: the active translator and a way to switch locale
+#[derive(Clone, PartialEq)]
+pub struct I18nContext {
+    pub translator: Translator,
+    pub set_locale: Callback<Locale>,
+}
+
+// Props for I18nProvider component
+#[derive(Properties, PartialEq)]
+pub struct I18nProviderProps {
+    pub children: Html,
+}
+
+// Provides the active locale to every component below it
+#[function_component(I18nProvider)]
+pub fn i18n_provider(props: &I18nProviderProps) -> Html {
+    let translator = use_state(|| Translator::new(Locale::preferred()));
+    let set_locale = {
+        let translator = translator.clone();
+        Callback::from(move |locale: Locale| {
+            locale.store();
+            translator.set(Translator::new(locale));
+        })
+    };
+    let context = I18nContext {
+        translator: (*translator).clone(),
+        set_locale,
+    };
+
+    html! {
+        <ContextProvider<I18nContext> context={context}>
+            { props.children.clone() }
+        </ContextProvider<I18nContext>>
+    }
+}
+
+// Translator of the active locale; English outside an I18nProvider
+#[hook]
+pub fn use_translation() -> Translator {
+    use_context::<I18nContext>()
+        .map(|context| context.translator)
+        .unwrap_or_default()
+}
+
+// Select switching the UI language
+#[function_component(LanguageSwitcher)]
+pub fn language_switcher() -> Html {
+    let Some(context) = use_context::<I18nContext>() else {
+        return Html::default();
+    };
+    let current = context.translator.locale();
+    let onchange = {
+        let set_locale = context.set_locale.clone();
+        Callback::from(move |e: Event| {
+            let select = e.target_dyn_into::<HtmlSelectElement>().unwrap();
+            if let Some(locale) = Locale::negotiate(&select.value()) {
+                set_locale.emit(locale);
+            }
+        })
+    };
+
+    html! {
+        <select
+            aria-label={context.translator.t("language")}
+            onchange={onchange}
+            class="ml-auto border rounded px-2 py-1 text-gray-700 dark:bg-gray-800 dark:text-gray-200 dark:border-gray-600"
+        >
+            { for Locale::ALL.iter().map(|locale| html! {
+                <option value={locale.tag()} selected={*locale == current}>{ locale.label() }</option>
+            })}
+        </select>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_negotiation() {
+        assert_eq!(Locale::negotiate("en-US"), Some(Locale::En));
+        assert_eq!(Locale::negotiate("pt-BR"), Some(Locale::PtBr));
+        assert_eq!(Locale::negotiate("pt-PT"), Some(Locale::PtBr));
+        assert_eq!(Locale::negotiate("es"), Some(Locale::Es));
+        assert_eq!(Locale::negotiate("de-DE"), None);
+    }
+
+    #[test]
+    fn test_locale_round_trips_through_storage_format() {
+        for locale in Locale::ALL {
+            let json = serde_json::to_string(&locale).unwrap();
+            assert_eq!(json, format!("\"{}\"", locale.tag()));
+            assert_eq!(serde_json::from_str::<Locale>(&json).unwrap(), locale);
+        }
+    }
+
+    #[test]
+    fn test_translate_with_plurals() {
+        let en = Translator::new(Locale::En);
+        assert_eq!(en.t("user-create"), "Create User");
+        assert_eq!(en.t_with("last-seen-minutes", &[("count", 1.into())]), "1 minute ago");
+        assert_eq!(en.t_with("last-seen-minutes", &[("count", 5.into())]), "5 minutes ago");
+
+        let pt = Translator::new(Locale::PtBr);
+        assert_eq!(pt.t("user-create"), "Criar usuário");
+        assert_eq!(pt.t_with("last-seen-days", &[("count", 2.into())]), "há 2 dias");
+    }
+
+    #[test]
+    fn test_missing_message_renders_id() {
+        assert_eq!(Translator::default().t("no-such-message"), "no-such-message");
+    }
+
+    #[test]
+    fn test_every_locale_has_every_message() {
+        let reference = FluentResource::try_new(Locale::En.source().to_string()).unwrap();
+        let ids: Vec<&str> = reference
+            .entries()
+            .filter_map(|entry| match entry {
+                fluent_syntax::ast::Entry::Message(message) => Some(message.id.name),
+                _ => None,
+            })
+            .collect();
+        for locale in Locale::ALL {
+            let translator = Translator::new(locale);
+            for id in &ids {
+                assert!(translator.bundle.has_message(id), "{} lacks {}", locale.tag(), id);
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// List State Module - Single Responsibility Principle
+// What the user list shows (search, sort, filters, page and saved view),
+// mirrored in the query string of the users page so a list can be shared by
+// its URL and the back button steps through earlier states
+
+use crate::api::UserStatus;
+use crate::components::LastSeenSort;
+use crate::preferences::{find_view, ListView, SavedView};
+use serde::{Deserialize, Serialize};
+
+// Users shown per page of the list
+pub const USERS_PER_PAGE: usize = 25;
+// Pause after the last change before the address bar follows, so typing a
+// search adds one history entry rather than one per key
+pub const LIST_QUERY_DEBOUNCE_MS: u32 = 300;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListState {
+    pub view: ListView,
+    // Page shown, from 0
+    pub page: usize,
+    // Saved view the list was switched to; kept while its filters are edited
+    // so they can be saved over it
+    pub saved_view: Option<String>,
+}
+
+impl ListState {
+    // `saved` from its first page
+    pub fn of_saved(saved: &SavedView) -> Self {
+        Self {
+            view: saved.view.clone(),
+            page: 0,
+            saved_view: Some(saved.name.clone()),
+        }
+    }
+
+    // This state with other search text, sort or filters, back on the first page
+    pub fn with_view(&self, view: ListView) -> Self {
+        Self {
+            view,
+            page: 0,
+            saved_view: self.saved_view.clone(),
+        }
+    }
+
+    pub fn with_page(&self, page: usize) -> Self {
+        Self { page, ..self.clone() }
+    }
+
+    pub fn with_saved_view(&self, saved_view: Option<String>) -> Self {
+        Self { saved_view, ..self.clone() }
+    }
+}
+
+// Pages `count` users fill; an empty list still has one
+pub fn page_count(count: usize) -> usize {
+    count.div_ceil(USERS_PER_PAGE).max(1)
+}
+
+// Items of `page` (from 0); pages past the end show the last one
+pub fn page_of<T: Clone>(items: &[T], page: usize) -> Vec<T> {
+    let page = page.min(page_count(items.len()) - 1);
+    items.iter().skip(page * USERS_PER_PAGE).take(USERS_PER_PAGE).cloned().collect()
+}
+
+// Query string of the users page, e.g. `?q=doe&sort=most_recent&page=2`;
+// defaults are left out and pages count from 1
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct UserListQuery {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub q: String,
+    #[serde(default, skip_serializing_if = "LastSeenSort::is_none")]
+    pub sort: LastSeenSort,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<UserStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view: Option<String>,
+}
+
+impl UserListQuery {
+    pub fn of(state: &ListState) -> Self {
+        Self {
+            q: state.view.search.clone(),
+            sort: state.view.sort,
+            tag: state.view.tag.clone(),
+            status: state.view.status,
+            page: (state.page > 0).then_some(state.page + 1),
+            view: state.saved_view.clone(),
+        }
+    }
+
+    // The list state this query describes. A bare `?view=<name>` shows that
+    // saved view; names that are not saved are ignored
+    pub fn to_state(&self, saved: &[SavedView]) -> ListState {
+        let saved = self.view.as_deref().and_then(|name| find_view(saved, name));
+        let view = ListView {
+            search: self.q.clone(),
+            sort: self.sort,
+            tag: self.tag.clone(),
+            status: self.status,
+        };
+        match saved {
+            Some(saved) if view == ListView::default() && self.page.is_none() => ListState::of_saved(saved),
+            _ => ListState {
+                view,
+                page: self.page.unwrap_or(1).saturating_sub(1),
+                saved_view: saved.map(|saved| saved.name.clone()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved() -> Vec<SavedView> {
+        vec![SavedView {
+            name: "VIPs".to_string(),
+            view: ListView {
+                tag: Some("vip".to_string()),
+                ..ListView::default()
+            },
+        }]
+    }
+
+    #[test]
+    fn test_query_round_trip() {
+        let state = ListState {
+            view: ListView {
+                search: "doe".to_string(),
+                sort: LastSeenSort::LeastRecent,
+                tag: Some("vip".to_string()),
+                status: Some(UserStatus::Suspended),
+            },
+            page: 2,
+            saved_view: Some("VIPs".to_string()),
+        };
+        let query = UserListQuery::of(&state);
+        assert_eq!(query.page, Some(3));
+        assert_eq!(query.to_state(&saved()), state);
+
+        // The first page and unset filters are left out of the URL
+        assert_eq!(UserListQuery::of(&ListState::default()), UserListQuery::default());
+        assert_eq!(UserListQuery::default().to_state(&saved()), ListState::default());
+    }
+
+    #[test]
+    fn test_query_with_saved_view() {
+        // A bare view name shows the saved view
+        let query = UserListQuery { view: Some("VIPs".to_string()), ..UserListQuery::default() };
+        assert_eq!(query.to_state(&saved()), ListState::of_saved(&saved()[0]));
+
+        // Filters in the URL win over the saved ones
+        let query = UserListQuery { q: "ada".to_string(), ..query };
+        let state = query.to_state(&saved());
+        assert_eq!(state.view.search, "ada");
+        assert_eq!(state.view.tag, None);
+        assert_eq!(state.saved_view.as_deref(), Some("VIPs"));
+
+        // Unknown views are dropped
+        let query = UserListQuery { view: Some("Gone".to_string()), ..UserListQuery::default() };
+        assert_eq!(query.to_state(&saved()), ListState::default());
+    }
+
+    #[test]
+    fn test_editing_filters_goes_back_to_the_first_page() {
+        let state = ListState::of_saved(&saved()[0]).with_page(3);
+        let edited = state.with_view(ListView { search: "ada".to_string(), ..state.view.clone() });
+        assert_eq!(edited.page, 0);
+        assert_eq!(edited.saved_view.as_deref(), Some("VIPs"));
+    }
+
+    #[test]
+    fn test_pages() {
+        assert_eq!(page_count(0), 1);
+        assert_eq!(page_count(USERS_PER_PAGE), 1);
+        assert_eq!(page_count(USERS_PER_PAGE + 1), 2);
+
+        let items: Vec<usize> = (0..USERS_PER_PAGE + 5).collect();
+        assert_eq!(page_of(&items, 0).len(), USERS_PER_PAGE);
+        assert_eq!(page_of(&items, 1), (USERS_PER_PAGE..USERS_PER_PAGE + 5).collect::<Vec<_>>());
+        // Past the end shows the last page
+        assert_eq!(page_of(&items, 9), page_of(&items, 1));
+    }
+}
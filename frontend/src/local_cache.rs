@@ -0,0 +1,51 @@
+// Local cache Module - Single Responsibility Principle
+// Keeps the last user list fetched from the server in localStorage, so the
+// users page renders it instantly on the next visit while the fresh list
+// loads; its ETag lets the server answer 304 when nothing changed since
+
+use crate::api::CachedUserList;
+use gloo::storage::{LocalStorage, Storage};
+
+const USER_LIST_STORAGE_KEY: &str = "cached-user-list";
+
+// User list saved on a previous visit, if any
+pub fn load_user_list() -> Option<CachedUserList> {
+    LocalStorage::get(USER_LIST_STORAGE_KEY).ok()
+}
+
+// Losing the cache only costs a slower first render
+pub fn store_user_list(list: &CachedUserList) {
+    let _ = LocalStorage::set(USER_LIST_STORAGE_KEY, list);
+}
+
+pub fn clear_user_list() {
+    LocalStorage::delete(USER_LIST_STORAGE_KEY);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{User, UserStatus};
+
+    #[test]
+    fn test_saved_list_round_trips_through_storage_format() {
+        let list = CachedUserList {
+            etag: "W/\"2-abc\"".to_string(),
+            users: vec![User {
+                id: 7,
+                name: "Jane".to_string(),
+                email: "jane@example.com".to_string(),
+                username: None,
+                status: UserStatus::Suspended,
+                tags: Vec::new(),
+                last_login_at: Some("2026-10-17T12:00:00Z".to_string()),
+                created_at: None,
+                updated_at: None,
+                phone: None,
+                address: None,
+            }],
+        };
+        let json = serde_json::to_string(&list).unwrap();
+        assert_eq!(serde_json::from_str::<CachedUserList>(&json).unwrap(), list);
+    }
+}
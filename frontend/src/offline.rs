@@ -0,0 +1,384 @@
+// Offline Module - Single Responsibility Principle
+// Makes the app usable without a connection: registers the service worker
+// (sw.js) that caches the app bundle and the last user list, queues the
+// changes made while offline or while the backend is unreachable in
+// localStorage and replays them in order once it answers again.
+// OfflineBanner tells the user about both
+
+use crate::api;
+use crate::i18n::use_translation;
+use crate::reporting;
+use gloo::events::EventListener;
+use gloo::net::http::{Method, Request};
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::js_sys;
+use yew::prelude::*;
+
+const SERVICE_WORKER_URL: &str = "/sw.js";
+const QUEUE_STORAGE_KEY: &str = "pending-mutations";
+// Dispatched on window whenever the queue changes
+const QUEUE_CHANGED_EVENT: &str = "pending-mutations-changed";
+// Dispatched on window whenever the heartbeat finds the backend gone or back
+pub const CONNECTIVITY_CHANGED_EVENT: &str = "connectivity-changed";
+
+thread_local! {
+    // Set while replay_pending runs, so a mutation is never sent twice
+    static REPLAYING: Cell<bool> = const { Cell::new(false) };
+    // Outcome of the last heartbeat; assumed reachable until one fails
+    static BACKEND_REACHABLE: Cell<bool> = const { Cell::new(true) };
+}
+
+// Whether changes can reach the backend right now
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    Online,
+    // The browser has a connection but the backend does not answer
+    Unreachable,
+    // The browser has no connection at all
+    Offline,
+}
+
+impl Connectivity {
+    pub fn of(browser_online: bool, backend_reachable: bool) -> Self {
+        match (browser_online, backend_reachable) {
+            (false, _) => Connectivity::Offline,
+            (true, false) => Connectivity::Unreachable,
+            (true, true) => Connectivity::Online,
+        }
+    }
+
+    // Message id describing the state
+    pub fn label_id(&self) -> &'static str {
+        match self {
+            Connectivity::Online => "connectivity-online",
+            Connectivity::Unreachable => "connectivity-unreachable",
+            Connectivity::Offline => "connectivity-offline",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MutationMethod {
+    Post,
+    Put,
+    Delete,
+}
+
+impl MutationMethod {
+    fn method(&self) -> Method {
+        match self {
+            MutationMethod::Post => Method::POST,
+            MutationMethod::Put => Method::PUT,
+            MutationMethod::Delete => Method::DELETE,
+        }
+    }
+}
+
+// Request made while offline, sent again when the connection returns
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingMutation {
+    pub method: MutationMethod,
+    pub url: String,
+    // JSON body, if the request has one
+    pub body: Option<String>,
+    // Replayed with the admin token of the session; the token itself is never queued
+    #[serde(default)]
+    pub admin: bool,
+}
+
+// What happened to a replayed mutation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplayOutcome {
+    // The server answered (accepted or rejected); the mutation is done
+    Answered,
+    // Still no connection; it and everything after it stay queued
+    Unreachable,
+}
+
+// Mutations still queued after replaying `queue` in order; replay stops at
+// the first unreachable one so later changes never overtake earlier ones
+pub fn remaining_after(queue: &[PendingMutation], outcomes: &[ReplayOutcome]) -> Vec<PendingMutation> {
+    let done = outcomes
+        .iter()
+        .take_while(|outcome| **outcome == ReplayOutcome::Answered)
+        .count();
+    queue[done..].to_vec()
+}
+
+fn browser_online() -> bool {
+    web_sys::window().is_none_or(|window| window.navigator().on_line())
+}
+
+pub fn connectivity() -> Connectivity {
+    Connectivity::of(browser_online(), BACKEND_REACHABLE.get())
+}
+
+// Whether changes are sent right away rather than queued
+pub fn is_online() -> bool {
+    connectivity() == Connectivity::Online
+}
+
+// Record the outcome of a heartbeat; the queue is replayed once the backend
+// answers again
+pub fn set_backend_reachable(reachable: bool) {
+    if BACKEND_REACHABLE.replace(reachable) == reachable {
+        return;
+    }
+    dispatch(CONNECTIVITY_CHANGED_EVENT);
+    if is_online() && !pending_mutations().is_empty() {
+        spawn_local(replay_pending());
+    }
+}
+
+fn dispatch(event: &str) {
+    if let (Some(window), Ok(event)) = (web_sys::window(), web_sys::Event::new(event)) {
+        let _ = window.dispatch_event(&event);
+    }
+}
+
+// Register sw.js where the browser supports service workers; call once on startup
+pub fn register_service_worker() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let navigator = window.navigator();
+    // serviceWorker is missing on plain-HTTP origins other than localhost
+    if !js_sys::Reflect::has(&navigator, &"serviceWorker".into()).unwrap_or(false) {
+        return;
+    }
+    let promise = navigator.service_worker().register(SERVICE_WORKER_URL);
+    spawn_local(async move {
+        if let Err(e) = JsFuture::from(promise).await {
+            web_sys::console::warn_2(&"Service worker not registered:".into(), &e);
+        }
+    });
+}
+
+pub fn pending_mutations() -> Vec<PendingMutation> {
+    LocalStorage::get(QUEUE_STORAGE_KEY).unwrap_or_default()
+}
+
+fn store_pending(queue: &[PendingMutation]) {
+    if queue.is_empty() {
+        LocalStorage::delete(QUEUE_STORAGE_KEY);
+    } else {
+        let _ = LocalStorage::set(QUEUE_STORAGE_KEY, queue);
+    }
+    dispatch(QUEUE_CHANGED_EVENT);
+}
+
+// Queue a mutation made offline
+pub fn queue_mutation(mutation: PendingMutation) {
+    let mut queue = pending_mutations();
+    queue.push(mutation);
+    store_pending(&queue);
+}
+
+// Queue the mutation instead of sending it when the browser is offline or
+// the backend unreachable; true when it was queued
+pub fn queue_if_offline(method: MutationMethod, url: &str, body: Option<String>) -> bool {
+    queue_unless_online(PendingMutation {
+        method,
+        url: url.to_string(),
+        body,
+        admin: false,
+    })
+}
+
+// Like queue_if_offline, for an endpoint that requires the admin token
+pub fn queue_admin_if_offline(method: MutationMethod, url: &str, body: Option<String>) -> bool {
+    queue_unless_online(PendingMutation {
+        method,
+        url: url.to_string(),
+        body,
+        admin: true,
+    })
+}
+
+fn queue_unless_online(mutation: PendingMutation) -> bool {
+    if is_online() {
+        return false;
+    }
+    queue_mutation(mutation);
+    true
+}
+
+async fn replay(mutation: &PendingMutation) -> ReplayOutcome {
+    let mut request = Request::new(&mutation.url).method(mutation.method.method());
+    if let Some(body) = &mutation.body {
+        request = request
+            .header("Content-Type", "application/json")
+            .body(body.as_str());
+    }
+    if let Some(token) = api::admin_token().filter(|_| mutation.admin) {
+        request = request.header("Authorization", &api::admin_authorization(&token));
+    }
+    match request.send().await {
+        Ok(response) => {
+            if !response.ok() {
+                // Nobody is waiting for the answer any more, so rejections only reach the logs
+                reporting::report_failed_request(&mutation.url, Some(response.status()), "Offline change rejected");
+            }
+            ReplayOutcome::Answered
+        }
+        Err(_) => ReplayOutcome::Unreachable,
+    }
+}
+
+// Send the queued mutations in order
+pub async fn replay_pending() {
+    if REPLAYING.replace(true) {
+        return;
+    }
+    let queue = pending_mutations();
+    let mut outcomes = Vec::new();
+    for mutation in &queue {
+        let outcome = replay(mutation).await;
+        outcomes.push(outcome);
+        if outcome == ReplayOutcome::Unreachable {
+            break;
+        }
+    }
+    // Mutations queued during the replay are kept behind the remaining ones
+    let added = pending_mutations().get(queue.len()..).unwrap_or_default().to_vec();
+    let mut remaining = remaining_after(&queue, &outcomes);
+    remaining.extend(added);
+    store_pending(&remaining);
+    REPLAYING.set(false);
+}
+
+// Connectivity, followed through the browser's online and offline events
+// and the heartbeat's reports
+#[hook]
+pub fn use_connectivity() -> Connectivity {
+    let state = use_state(connectivity);
+    {
+        let state = state.clone();
+        use_effect_with((), move |_| {
+            let listeners = web_sys::window().map(|window| {
+                ["online", "offline", CONNECTIVITY_CHANGED_EVENT].map(|event| {
+                    let state = state.clone();
+                    EventListener::new(&window, event, move |_| state.set(connectivity()))
+                })
+            });
+            move || drop(listeners)
+        });
+    }
+    *state
+}
+
+// Shows that the app is offline or the backend unreachable, and how many
+// changes wait to be sent; replays them when the connection returns
+#[function_component(OfflineBanner)]
+pub fn offline_banner() -> Html {
+    let t = use_translation();
+    let connectivity = use_connectivity();
+    let pending = use_state(|| pending_mutations().len());
+
+    {
+        let pending = pending.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window();
+            if is_online() && !pending_mutations().is_empty() {
+                spawn_local(replay_pending());
+            }
+            let listeners = window.map(|window| {
+                let on_online = EventListener::new(&window, "online", move |_| {
+                    if is_online() {
+                        spawn_local(replay_pending());
+                    }
+                });
+                let on_queue = EventListener::new(&window, QUEUE_CHANGED_EVENT, move |_| {
+                    pending.set(pending_mutations().len())
+                });
+                (on_online, on_queue)
+            });
+            move || drop(listeners)
+        });
+    }
+
+    let message = match (connectivity, *pending) {
+        (Connectivity::Online, 0) => return Html::default(),
+        (Connectivity::Online, count) => t.t_with("offline-syncing", &[("count", count.into())]),
+        (Connectivity::Unreachable, count) => t.t_with("offline-unreachable", &[("count", count.into())]),
+        (Connectivity::Offline, count) => t.t_with("offline-banner", &[("count", count.into())]),
+    };
+    html! {
+        <div role="status" class="print:hidden mb-4 p-3 rounded border border-yellow-300 bg-yellow-50 text-yellow-800 dark:bg-gray-800 dark:border-yellow-700 dark:text-yellow-300">
+            { message }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{Locale, Translator};
+
+    fn mutation(url: &str) -> PendingMutation {
+        PendingMutation {
+            method: MutationMethod::Delete,
+            url: url.to_string(),
+            body: None,
+            admin: false,
+        }
+    }
+
+    #[test]
+    fn test_replay_stops_at_first_unreachable_mutation() {
+        let queue = vec![mutation("/users/1"), mutation("/users/2"), mutation("/users/3")];
+
+        let remaining = remaining_after(&queue, &[ReplayOutcome::Answered, ReplayOutcome::Unreachable]);
+        assert_eq!(remaining, vec![mutation("/users/2"), mutation("/users/3")]);
+
+        let outcomes = [ReplayOutcome::Answered; 3];
+        assert!(remaining_after(&queue, &outcomes).is_empty());
+        assert_eq!(remaining_after(&queue, &[]), queue);
+    }
+
+    #[test]
+    fn test_queue_storage_format() {
+        let queued = PendingMutation {
+            method: MutationMethod::Put,
+            url: "http://127.0.0.1:8000/api/v1/users/7".to_string(),
+            body: Some(r#"{"id":7}"#.to_string()),
+            admin: false,
+        };
+        let json = serde_json::to_string(&queued).unwrap();
+        assert_eq!(serde_json::from_str::<PendingMutation>(&json).unwrap(), queued);
+
+        // Queued before admin mutations were flagged
+        let old = r#"{"method":"Post","url":"http://127.0.0.1:8000/api/v1/users","body":null}"#;
+        assert!(!serde_json::from_str::<PendingMutation>(old).unwrap().admin);
+    }
+
+    #[test]
+    fn test_banner_counts_pending_changes() {
+        let t = Translator::new(Locale::En);
+        assert_eq!(
+            t.t_with("offline-banner", &[("count", 0.into())]),
+            "You are offline. Showing the last saved data."
+        );
+        assert_eq!(
+            t.t_with("offline-banner", &[("count", 2.into())]),
+            "You are offline. 2 changes will be sent when the connection returns."
+        );
+        assert_eq!(t.t_with("offline-syncing", &[("count", 1.into())]), "Back online, sending 1 offline change…");
+        assert_eq!(
+            t.t_with("offline-unreachable", &[("count", 1.into())]),
+            "The server cannot be reached. 1 change will be sent when it is back."
+        );
+    }
+
+    #[test]
+    fn test_connectivity() {
+        assert_eq!(Connectivity::of(true, true), Connectivity::Online);
+        assert_eq!(Connectivity::of(true, false), Connectivity::Unreachable);
+        // Without a connection the heartbeat's last answer does not matter
+        assert_eq!(Connectivity::of(false, true), Connectivity::Offline);
+        assert_eq!(Connectivity::of(false, false), Connectivity::Offline);
+    }
+}
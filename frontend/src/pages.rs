@@ -0,0 +1,1382 @@
+// Pages Module - Single Responsibility Principle
+// Each routed page composes components and wires them to the service layer
+
+use crate::api::{
+    ActivityEntry, Address, ApiError, ApiResult, BulkUpdateRequest, BulkUpdateResult, CaptchaSettings, SubmitResult, User, UserStats, UserStatus,
+};
+use crate::components::{
+    format_address, format_last_seen, status_badge_class, status_label_id, ActivityTimeline, AdminTokenField, Avatar, BarChart,
+    BulkActionsToolbar,
+    wizard_step_errors, Button, CaptchaWidget, EmptyState, ErrorState, NotificationBanner, SignupWizard,
+    StatCard, TagChip, TagEditor, UserForm, UserList, FOCUS_RING,
+};
+use crate::download::{export_file_name, save_file, CSV_MIME};
+use crate::flags::{FeatureGate, BULK_DELETE};
+use crate::i18n::{use_translation, Translator};
+use crate::local_cache;
+use crate::router::{Route, UserRef};
+use crate::service::{DefaultUserService, UserService};
+use crate::state::{
+    split_submit_error, use_user_form_state, FieldErrors, HistoryStep, Notification, SignupStep, UserFormState,
+    USER_FORM_FIELDS,
+};
+use gloo::timers::callback::Interval;
+use serde::Deserialize;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{js_sys, HtmlInputElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+// The detail page refetches its user this often; unchanged users cost a 304
+const USER_POLL_MS: u32 = 30_000;
+
+#[function_component(UsersPage)]
+pub fn users_page() -> Html {
+    // State management
+    let form_state = use_user_form_state();
+    let notification = use_state(|| None::<Notification>);
+    // Rejections of the last submission on a form field
+    let field_errors = use_state(FieldErrors::default);
+    let users = use_state(Vec::new);
+    // Outcome of the last fetch: `None` until the list was loaded once
+    let list_state = use_state(|| None::<Result<(), String>>);
+    // Set while the users shown come from the last visit, until a fetch succeeds
+    let from_cache = use_state(|| false);
+    // Users ticked for a bulk action
+    let selected = use_state(Vec::<i32>::new);
+    // Set while a bulk update is in flight
+    let bulk_busy = use_state(|| false);
+    let t = use_translation();
+
+    // Service layer - created once per component so the API client's list cache survives re-renders
+    let service = use_memo((), |_| DefaultUserService::default());
+
+    // Fetch users handler
+    let fetch_users = {
+        let users = users.clone();
+        let list_state = list_state.clone();
+        let from_cache = from_cache.clone();
+        let service = service.clone();
+
+        Callback::from(move |_| {
+            let users = users.clone();
+            let list_state = list_state.clone();
+            let from_cache = from_cache.clone();
+            let service = service.clone();
+
+            service.fetch_users(Callback::from(
+                move |result: ApiResult<Vec<User>>| match result {
+                    Ok(fetched_users) => {
+                        users.set(fetched_users);
+                        list_state.set(Some(Ok(())));
+                        from_cache.set(false);
+                    }
+                    Err(err) => list_state.set(Some(Err(err))),
+                },
+            ));
+        })
+    };
+
+    // Show the list saved on the last visit right away, then fetch the fresh one
+    {
+        let users = users.clone();
+        let list_state = list_state.clone();
+        let from_cache = from_cache.clone();
+        let fetch_users = fetch_users.clone();
+        use_effect_with((), move |_| {
+            if let Some(saved) = local_cache::load_user_list() {
+                users.set(saved.users);
+                list_state.set(Some(Ok(())));
+                from_cache.set(true);
+            }
+            fetch_users.emit(());
+        });
+    }
+
+    // Create/Update user handler
+    let submit_user = {
+        let form_state = form_state.clone();
+        let notification = notification.clone();
+        let field_errors = field_errors.clone();
+        let t = t.clone();
+        let fetch_users = fetch_users.clone();
+        let service = service.clone();
+
+        Callback::from(move |_| {
+            let current_state = (*form_state).clone();
+            let is_editing = current_state.is_editing();
+            let notification = notification.clone();
+            let field_errors = field_errors.clone();
+            let fetch_users = fetch_users.clone();
+            let form_state = form_state.clone();
+            let service = service.clone();
+            let t = t.clone();
+
+            let callback = Callback::from(move |result: SubmitResult| {
+                match result {
+                    Ok(_) => {
+                        let success_msg = if is_editing { "user-updated" } else { "user-created" };
+                        notification.set(Some(Notification::success(t.t(success_msg))));
+                        field_errors.set(FieldErrors::default());
+
+                        // Reset form and refresh list
+                        form_state.set(UserFormState::new());
+                        fetch_users.emit(());
+                    }
+                    Err(err) => {
+                        let (errors, general) = split_submit_error(err);
+                        notification.set(general.map(Notification::error));
+                        field_errors.set(errors);
+                    }
+                }
+            });
+
+            if is_editing {
+                service.update_user(&current_state, callback);
+            } else {
+                service.create_user(&current_state, callback);
+            }
+        })
+    };
+
+    // Delete user handler
+    let delete_user = {
+        let notification = notification.clone();
+        let fetch_users = fetch_users.clone();
+        let service = service.clone();
+        let t = t.clone();
+
+        Callback::from(move |id: i32| {
+            let notification = notification.clone();
+            let fetch_users = fetch_users.clone();
+            let service = service.clone();
+            let t = t.clone();
+
+            service.delete_user(
+                id,
+                Callback::from(move |result: ApiResult<()>| match result {
+                    Ok(_) => {
+                        notification.set(Some(Notification::success(t.t("user-deleted"))));
+                        fetch_users.emit(());
+                    }
+                    Err(err) => notification.set(Some(Notification::error(err))),
+                }),
+            );
+        })
+    };
+
+    // Suspend/activate handler
+    let set_user_status = {
+        let notification = notification.clone();
+        let fetch_users = fetch_users.clone();
+        let service = service.clone();
+        let t = t.clone();
+
+        Callback::from(move |(id, status): (i32, UserStatus)| {
+            let notification = notification.clone();
+            let fetch_users = fetch_users.clone();
+            let t = t.clone();
+
+            service.set_user_status(
+                id,
+                status,
+                Callback::from(move |result: ApiResult<()>| match result {
+                    Ok(_) => {
+                        let text = t.t_with("user-status-changed", &[("status", status.label().to_lowercase().into())]);
+                        notification.set(Some(Notification::success(text)));
+                        fetch_users.emit(());
+                    }
+                    Err(err) => notification.set(Some(Notification::error(err))),
+                }),
+            );
+        })
+    };
+
+    let toggle_selected = {
+        let selected = selected.clone();
+        Callback::from(move |id: i32| {
+            let mut ids = (*selected).clone();
+            match ids.iter().position(|selected_id| *selected_id == id) {
+                Some(index) => {
+                    ids.remove(index);
+                }
+                None => ids.push(id),
+            }
+            selected.set(ids);
+        })
+    };
+
+    // Bulk delete handler - deletes the selected users, then refreshes the list once
+    let delete_selected = {
+        let selected = selected.clone();
+        let notification = notification.clone();
+        let fetch_users = fetch_users.clone();
+        let service = service.clone();
+        let t = t.clone();
+
+        Callback::from(move |_| {
+            let ids = (*selected).clone();
+            if ids.is_empty() {
+                return;
+            }
+            selected.set(Vec::new());
+            let remaining = Rc::new(Cell::new(ids.len()));
+            let failed = Rc::new(Cell::new(0));
+            let total = ids.len();
+
+            for id in ids {
+                let remaining = remaining.clone();
+                let failed = failed.clone();
+                let notification = notification.clone();
+                let fetch_users = fetch_users.clone();
+                let t = t.clone();
+
+                service.delete_user(
+                    id,
+                    Callback::from(move |result: ApiResult<()>| {
+                        if result.is_err() {
+                            failed.set(failed.get() + 1);
+                        }
+                        remaining.set(remaining.get() - 1);
+                        if remaining.get() == 0 {
+                            notification.set(Some(bulk_delete_notification(&t, total, failed.get())));
+                            fetch_users.emit(());
+                        }
+                    }),
+                );
+            }
+        })
+    };
+
+    // Bulk update handler - applies one change to the selected users, keeping
+    // the selection so further changes can follow
+    let bulk_update = {
+        let selected = selected.clone();
+        let bulk_busy = bulk_busy.clone();
+        let notification = notification.clone();
+        let fetch_users = fetch_users.clone();
+        let service = service.clone();
+        let t = t.clone();
+
+        Callback::from(move |request: BulkUpdateRequest| {
+            let request = BulkUpdateRequest {
+                ids: (*selected).clone(),
+                ..request
+            };
+            let bulk_busy = bulk_busy.clone();
+            let notification = notification.clone();
+            let fetch_users = fetch_users.clone();
+            let t = t.clone();
+            bulk_busy.set(true);
+
+            service.bulk_update_users(
+                request,
+                Callback::from(move |result: Result<Vec<BulkUpdateResult>, ApiError>| {
+                    bulk_busy.set(false);
+                    match result {
+                        Ok(results) => {
+                            notification.set(Some(bulk_update_notification(&t, &results)));
+                            fetch_users.emit(());
+                        }
+                        Err(err) => notification.set(Some(Notification::error(err.message))),
+                    }
+                }),
+            );
+        })
+    };
+    let bulk_set_status = {
+        let bulk_update = bulk_update.clone();
+        Callback::from(move |status: UserStatus| {
+            bulk_update.emit(BulkUpdateRequest {
+                status: Some(status),
+                ..Default::default()
+            })
+        })
+    };
+    let bulk_add_tag = {
+        let bulk_update = bulk_update.clone();
+        Callback::from(move |tag: String| {
+            bulk_update.emit(BulkUpdateRequest {
+                add_tags: vec![tag],
+                ..Default::default()
+            })
+        })
+    };
+    let bulk_remove_tag = Callback::from(move |tag: String| {
+        bulk_update.emit(BulkUpdateRequest {
+            remove_tags: vec![tag],
+            ..Default::default()
+        })
+    });
+    // Export handler - downloads the selected users as CSV, or every user
+    // when nothing is selected
+    let export_users = {
+        let notification = notification.clone();
+        let service = service.clone();
+        Callback::from(move |ids: Vec<i32>| {
+            let notification = notification.clone();
+            let selected = ids.len();
+            service.export_users(
+                &ids,
+                Callback::from(move |result: ApiResult<String>| match result {
+                    Ok(csv) => save_file(&export_file_name(selected), CSV_MIME, &csv),
+                    Err(err) => notification.set(Some(Notification::error(err))),
+                }),
+            );
+        })
+    };
+    let export_all = {
+        let export_users = export_users.clone();
+        Callback::from(move |_| export_users.emit(Vec::new()))
+    };
+    let export_selected = {
+        let selected = selected.clone();
+        Callback::from(move |_| export_users.emit((*selected).clone()))
+    };
+
+    let clear_selection = {
+        let selected = selected.clone();
+        Callback::from(move |_| selected.set(Vec::new()))
+    };
+
+    // Edit user handler
+    let edit_user = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        let users = users.clone();
+
+        Callback::from(move |id: i32| {
+            if let Some(user) = users.iter().find(|u| u.id == id) {
+                field_errors.set(FieldErrors::default());
+                let mut new_state = (*form_state).clone();
+                // Note: Password is not included for security reasons - user must enter new password
+                new_state.set_for_editing(id, user.name.clone(), user.email.clone(), String::new());
+                new_state.set_username(user.username.clone());
+                new_state.set_contact(user.phone.clone(), user.address.clone());
+                form_state.set(new_state);
+            }
+        })
+    };
+
+    // Form input handlers
+    let on_name_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |name: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.name = name);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "name");
+        })
+    };
+
+    let on_email_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |email: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.email = email);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "email");
+        })
+    };
+
+    let on_password_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |password: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.password = password);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "password");
+        })
+    };
+
+    let on_username_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |username: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.username = username);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "username");
+        })
+    };
+
+    let on_phone_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |phone: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.phone = phone);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "phone");
+        })
+    };
+
+    let on_address_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |address: Address| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.address = address);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "address");
+        })
+    };
+
+    let dismiss_notification = {
+        let notification = notification.clone();
+        Callback::from(move |_| notification.set(None))
+    };
+
+    // Moves the focus to the form so the first user can be entered
+    let focus_form = Callback::from(|_| {
+        let name_input = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("user-name"))
+            .and_then(|element| element.dyn_into::<HtmlInputElement>().ok());
+        if let Some(input) = name_input {
+            let _ = input.focus();
+        }
+    });
+
+    // The cached list stays on screen when the refresh fails
+    let cache_notice = from_cache.then(|| {
+        let id = match &*list_state {
+            Some(Err(_)) => "users-cached-stale",
+            _ => "users-cached",
+        };
+        html! {
+            <p role="status" class="mb-2 text-sm text-yellow-700 dark:text-yellow-300">{ t.t(id) }</p>
+        }
+    });
+
+    let user_list = match &*list_state {
+        Some(Err(err)) if !*from_cache => html! {
+            <ErrorState message={err.clone()} on_retry={fetch_users.clone()} />
+        },
+        Some(_) if users.is_empty() => html! {
+            <EmptyState
+                icon="👥"
+                message={t.t("users-empty")}
+                action_label={t.t("users-empty-action")}
+                on_action={focus_form}
+            />
+        },
+        _ => html! {
+            <UserList
+                users={(*users).clone()}
+                on_delete={delete_user}
+                on_edit={edit_user}
+                on_set_status={set_user_status}
+                selected={(*selected).clone()}
+                on_toggle_select={toggle_selected}
+            />
+        },
+    };
+
+    // Render UI
+    html! {
+        <div>
+            <h1 class="text-4xl font-bold text-blue-500 mb-4">{ t.t("users-title") }</h1>
+
+            // Printing keeps only the title and the list
+            <div class="print:hidden">
+                <AdminTokenField />
+                <UserForm
+                    name={form_state.name.clone()}
+                    email={form_state.email.clone()}
+                    password={form_state.password.clone()}
+                    is_editing={form_state.is_editing()}
+                    on_name_change={on_name_change}
+                    on_email_change={on_email_change}
+                    on_password_change={on_password_change}
+                    phone={form_state.phone.clone()}
+                    address={form_state.address.clone()}
+                    username={form_state.username.clone()}
+                    username_locked={form_state.username_locked}
+                    on_username_change={on_username_change}
+                    on_phone_change={on_phone_change}
+                    on_address_change={on_address_change}
+                    on_history={form_history(&form_state)}
+                    on_submit={submit_user}
+                    notification={(*notification).clone()}
+                    on_dismiss={dismiss_notification}
+                    errors={(*field_errors).clone()}
+                />
+
+                <Button
+                    text={t.t("users-fetch")}
+                    onclick={fetch_users}
+                    class="bg-gray-500 hover:bg-gray-700 text-white font-bold py-2 px-4 rounded mb-4"
+                />
+                <Button
+                    text={t.t("users-export")}
+                    onclick={export_all}
+                    class="bg-gray-500 hover:bg-gray-700 text-white font-bold py-2 px-4 rounded mb-4 ml-2"
+                />
+
+                <BulkActionsToolbar
+                    count={selected.len()}
+                    on_set_status={bulk_set_status}
+                    on_add_tag={bulk_add_tag}
+                    on_remove_tag={bulk_remove_tag}
+                    on_clear={clear_selection}
+                    busy={*bulk_busy}
+                >
+                    <Button
+                        text={t.t_with("export-selected", &[("count", selected.len().into())])}
+                        onclick={export_selected}
+                        class="bg-gray-600 hover:bg-gray-700 text-white py-1 px-3 rounded"
+                    />
+                    <FeatureGate flag={BULK_DELETE}>
+                        <Button
+                            text={t.t_with("delete-selected", &[("count", selected.len().into())])}
+                            onclick={delete_selected}
+                            class="bg-red-500 hover:bg-red-700 text-white font-bold py-1 px-3 rounded"
+                        />
+                    </FeatureGate>
+                </BulkActionsToolbar>
+
+                { for cache_notice }
+            </div>
+            { user_list }
+        </div>
+    }
+}
+
+// Props for UserDetailPage component
+#[derive(Properties, PartialEq)]
+pub struct UserDetailPageProps {
+    pub id: i32,
+}
+
+// Profile of one user with their recent activity, editable and deletable in place
+#[function_component(UserDetailPage)]
+pub fn user_detail_page(props: &UserDetailPageProps) -> Html {
+    // Compared, so a poll answered from the cache does not re-render
+    let user = use_state_eq(|| None::<User>);
+    let activity = use_state(Vec::<ActivityEntry>::new);
+    let error = use_state(|| None::<String>);
+    let notification = use_state(|| None::<Notification>);
+    // Filled while the inline edit form is open
+    let form_state = use_user_form_state();
+    let field_errors = use_state(FieldErrors::default);
+    let service = use_memo((), |_| DefaultUserService::default());
+    let navigator = use_navigator();
+    let t = use_translation();
+    let id = props.id;
+
+    // Loads the profile, then its activity
+    let load = {
+        let user = user.clone();
+        let activity = activity.clone();
+        let error = error.clone();
+        let service = service.clone();
+
+        Callback::from(move |_| {
+            let user = user.clone();
+            let activity = activity.clone();
+            let error = error.clone();
+            let activity_service = service.clone();
+
+            service.fetch_user(
+                id,
+                Callback::from(move |result: ApiResult<User>| match result {
+                    Ok(fetched) => {
+                        user.set(Some(fetched));
+                        error.set(None);
+                        let error = error.clone();
+                        let activity = activity.clone();
+                        activity_service.fetch_activity(
+                            id,
+                            Callback::from(move |result: ApiResult<Vec<ActivityEntry>>| match result {
+                                Ok(entries) => activity.set(entries),
+                                Err(err) => error.set(Some(err)),
+                            }),
+                        );
+                    }
+                    Err(err) => error.set(Some(err)),
+                }),
+            );
+        })
+    };
+
+    // Only the profile is polled; a failed poll keeps what is shown
+    let refresh = {
+        let user = user.clone();
+        let service = service.clone();
+        Callback::from(move |_| {
+            let user = user.clone();
+            service.fetch_user(
+                id,
+                Callback::from(move |result: ApiResult<User>| {
+                    if let Ok(fetched) = result {
+                        user.set(Some(fetched));
+                    }
+                }),
+            );
+        })
+    };
+
+    {
+        let load = load.clone();
+        use_effect_with(id, move |_| {
+            load.emit(());
+            let poll = Interval::new(USER_POLL_MS, move || refresh.emit(()));
+            move || drop(poll)
+        });
+    }
+
+    let start_edit = {
+        let user = user.clone();
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |_| {
+            if let Some(user) = &*user {
+                field_errors.set(FieldErrors::default());
+                let mut new_state = UserFormState::new();
+                new_state.set_for_editing(user.id, user.name.clone(), user.email.clone(), String::new());
+                new_state.set_username(user.username.clone());
+                new_state.set_contact(user.phone.clone(), user.address.clone());
+                form_state.set(new_state);
+            }
+        })
+    };
+
+    let cancel_edit = {
+        let form_state = form_state.clone();
+        Callback::from(move |_| form_state.set(UserFormState::new()))
+    };
+
+    let submit_edit = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        let notification = notification.clone();
+        let service = service.clone();
+        let load = load.clone();
+        let t = t.clone();
+
+        Callback::from(move |_| {
+            let form_state = form_state.clone();
+            let field_errors = field_errors.clone();
+            let notification = notification.clone();
+            let load = load.clone();
+            let t = t.clone();
+
+            service.update_user(
+                &form_state.clone(),
+                Callback::from(move |result: SubmitResult| match result {
+                    Ok(_) => {
+                        notification.set(Some(Notification::success(t.t("user-updated"))));
+                        field_errors.set(FieldErrors::default());
+                        form_state.set(UserFormState::new());
+                        load.emit(());
+                    }
+                    Err(err) => {
+                        let (errors, general) = split_submit_error(err);
+                        notification.set(general.map(Notification::error));
+                        field_errors.set(errors);
+                    }
+                }),
+            );
+        })
+    };
+
+    // Deleting leaves a page with nothing to show, so go back to the list
+    let delete_user = {
+        let notification = notification.clone();
+        let service = service.clone();
+
+        Callback::from(move |_| {
+            let notification = notification.clone();
+            let navigator = navigator.clone();
+            service.delete_user(
+                id,
+                Callback::from(move |result: ApiResult<()>| match result {
+                    Ok(_) => {
+                        if let Some(navigator) = &navigator {
+                            navigator.push(&Route::Users);
+                        }
+                    }
+                    Err(err) => notification.set(Some(Notification::error(err))),
+                }),
+            );
+        })
+    };
+
+    let on_name_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |name: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.name = name);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "name");
+        })
+    };
+
+    let on_email_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |email: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.email = email);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "email");
+        })
+    };
+
+    let on_password_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |password: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.password = password);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "password");
+        })
+    };
+
+    let on_username_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |username: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.username = username);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "username");
+        })
+    };
+
+    let on_phone_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |phone: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.phone = phone);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "phone");
+        })
+    };
+
+    let on_address_change = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |address: Address| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| state.address = address);
+            form_state.set(new_state);
+            clear_field_error(&field_errors, "address");
+        })
+    };
+
+    // Tags are saved on their own, apart from the edit form
+    let saving_tags = use_state(|| false);
+    let save_tags = {
+        let saving_tags = saving_tags.clone();
+        let notification = notification.clone();
+        let service = service.clone();
+        let load = load.clone();
+        let t = t.clone();
+
+        Callback::from(move |input: String| {
+            let saving_tags = saving_tags.clone();
+            let notification = notification.clone();
+            let load = load.clone();
+            let t = t.clone();
+            saving_tags.set(true);
+
+            service.set_user_tags(
+                id,
+                &input,
+                Callback::from(move |result: SubmitResult| {
+                    saving_tags.set(false);
+                    match result {
+                        Ok(_) => {
+                            notification.set(Some(Notification::success(t.t("tags-saved"))));
+                            load.emit(());
+                        }
+                        Err(err) => notification.set(Some(Notification::error(err.message))),
+                    }
+                }),
+            );
+        })
+    };
+
+    let dismiss_notification = {
+        let notification = notification.clone();
+        Callback::from(move |_| notification.set(None))
+    };
+
+    let back_link = html! {
+        <Link<Route> to={Route::Users} classes={classes!("text-blue-600", "dark:text-blue-400", "hover:underline", FOCUS_RING)}>
+            { t.t("user-detail-back") }
+        </Link<Route>>
+    };
+
+    let Some(profile) = &*user else {
+        return html! {
+            <div>
+                { back_link }
+                if let Some(err) = &*error {
+                    <ErrorState message={err.clone()} on_retry={load} />
+                } else {
+                    <p role="status" class="text-gray-500 dark:text-gray-400">{ t.t("user-detail-loading") }</p>
+                }
+            </div>
+        };
+    };
+    let now = (js_sys::Date::now() / 1000.0) as i64;
+
+    html! {
+        <div>
+            { back_link }
+            <div class="flex items-center gap-4 mt-2 mb-4">
+                <Avatar email={profile.email.clone()} name={profile.name.clone()} size={64} />
+                <h1 class="text-4xl font-bold text-blue-500">{ profile.name.clone() }</h1>
+            </div>
+
+            <dl class="grid grid-cols-[160px_1fr] gap-2 mb-4 text-gray-900 dark:text-gray-100">
+                <dt class="font-bold">{ t.t("column-id") }</dt>
+                <dd>{ profile.id }</dd>
+                <dt class="font-bold">{ t.t("field-email") }</dt>
+                <dd>{ profile.email.clone() }</dd>
+                if let Some(username) = &profile.username {
+                    <dt class="font-bold">{ t.t("field-username") }</dt>
+                    <dd>
+                        <Link<Route>
+                            to={Route::UserDetail { user: UserRef::Username(username.clone()) }}
+                            classes={classes!("text-blue-600", "dark:text-blue-400", "hover:underline", FOCUS_RING)}
+                        >
+                            { format!("@{}", username) }
+                        </Link<Route>>
+                    </dd>
+                }
+                <dt class="font-bold">{ t.t("column-status") }</dt>
+                <dd>
+                    <span class={classes!("text-xs", "font-semibold", "px-2", "py-1", "rounded-full", status_badge_class(profile.status))}>
+                        { t.t(status_label_id(profile.status)) }
+                    </span>
+                </dd>
+                <dt class="font-bold">{ t.t("column-last-seen") }</dt>
+                <dd title={profile.last_login_at.clone()}>
+                    { format_last_seen(&t, profile.last_login_at.as_deref(), now) }
+                </dd>
+                if let Some(created_at) = &profile.created_at {
+                    <dt class="font-bold">{ t.t("field-created-at") }</dt>
+                    <dd><time datetime={created_at.clone()} title={created_at.clone()}>{ format_last_seen(&t, Some(created_at), now) }</time></dd>
+                }
+                if let Some(updated_at) = &profile.updated_at {
+                    <dt class="font-bold">{ t.t("field-updated-at") }</dt>
+                    <dd><time datetime={updated_at.clone()} title={updated_at.clone()}>{ format_last_seen(&t, Some(updated_at), now) }</time></dd>
+                }
+                if let Some(phone) = &profile.phone {
+                    <dt class="font-bold">{ t.t("field-phone") }</dt>
+                    <dd><a href={format!("tel:{}", phone)} class="text-blue-600 dark:text-blue-400 hover:underline">{ phone.clone() }</a></dd>
+                }
+                if let Some(address) = &profile.address {
+                    <dt class="font-bold">{ t.t("field-address") }</dt>
+                    <dd class="whitespace-pre-line">{ format_address(address) }</dd>
+                }
+                if !profile.tags.is_empty() {
+                    <dt class="font-bold">{ t.t("field-tags") }</dt>
+                    <dd class="flex flex-wrap gap-1">
+                        { for profile.tags.iter().map(|tag| html! { <TagChip tag={tag.clone()} /> }) }
+                    </dd>
+                }
+            </dl>
+            <AdminTokenField />
+            <TagEditor tags={profile.tags.clone()} on_save={save_tags} saving={*saving_tags} />
+
+            if form_state.is_editing() {
+                <UserForm
+                    name={form_state.name.clone()}
+                    email={form_state.email.clone()}
+                    password={form_state.password.clone()}
+                    is_editing=true
+                    on_name_change={on_name_change}
+                    on_email_change={on_email_change}
+                    on_password_change={on_password_change}
+                    phone={form_state.phone.clone()}
+                    address={form_state.address.clone()}
+                    username={form_state.username.clone()}
+                    username_locked={form_state.username_locked}
+                    on_username_change={on_username_change}
+                    on_phone_change={on_phone_change}
+                    on_address_change={on_address_change}
+                    on_history={form_history(&form_state)}
+                    on_submit={submit_edit}
+                    errors={(*field_errors).clone()}
+                />
+                <Button
+                    text={t.t("action-cancel")}
+                    onclick={cancel_edit}
+                    class="bg-gray-500 hover:bg-gray-700 text-white font-bold py-2 px-4 rounded mb-4"
+                />
+            } else {
+                <div class="flex gap-2 mb-4">
+                    <Button
+                        text={t.t("action-edit")}
+                        onclick={start_edit}
+                        class="bg-yellow-500 hover:bg-yellow-700 text-white font-bold py-2 px-4 rounded"
+                    />
+                    <Button
+                        text={t.t("action-delete")}
+                        onclick={delete_user}
+                        class="bg-red-500 hover:bg-red-700 text-white font-bold py-2 px-4 rounded"
+                    />
+                </div>
+            }
+            <NotificationBanner notification={(*notification).clone()} on_dismiss={dismiss_notification} />
+
+            <h2 class="text-2xl font-bold text-gray-700 dark:text-gray-200 mt-6 mb-2">{ t.t("activity-title") }</h2>
+            if let Some(err) = &*error {
+                <p role="alert" class="text-red-500 mb-4">{ err.clone() }</p>
+            }
+            <ActivityTimeline entries={(*activity).clone()} />
+        </div>
+    }
+}
+
+// Props for ProfilePage component
+#[derive(Properties, PartialEq)]
+pub struct ProfilePageProps {
+    pub username: String,
+}
+
+// Profile reached through `/users/@username`: resolves the username, then
+// shows the same page as `/users/:id`
+#[function_component(ProfilePage)]
+pub fn profile_page(props: &ProfilePageProps) -> Html {
+    let resolved = use_state(|| None::<ApiResult<i32>>);
+    let t = use_translation();
+
+    {
+        let resolved = resolved.clone();
+        use_effect_with(props.username.clone(), move |username| {
+            resolved.set(None);
+            DefaultUserService::default().fetch_user_by_username(
+                username,
+                Callback::from(move |result: ApiResult<User>| resolved.set(Some(result.map(|user| user.id)))),
+            );
+        });
+    }
+
+    match &*resolved {
+        Some(Ok(id)) => html! { <UserDetailPage id={*id} /> },
+        Some(Err(err)) => html! {
+            <div>
+                <Link<Route> to={Route::Users} classes={classes!("text-blue-600", "dark:text-blue-400", "hover:underline", FOCUS_RING)}>
+                    { t.t("user-detail-back") }
+                </Link<Route>>
+                <p role="alert" class="text-red-500 mt-2">{ err.clone() }</p>
+            </div>
+        },
+        None => html! {
+            <p role="status" class="text-gray-500 dark:text-gray-400">{ t.t("user-detail-loading") }</p>
+        },
+    }
+}
+
+// Self-service registration as a wizard (account details, password,
+// confirmation); the new user gets an email with a confirmation link
+#[function_component(SignupPage)]
+pub fn signup_page() -> Html {
+    let form_state = use_user_form_state();
+    let notification = use_state(|| None::<Notification>);
+    let field_errors = use_state(FieldErrors::default);
+    let captcha = use_state(CaptchaSettings::default);
+    let captcha_token = use_state(|| None::<String>);
+    // Bumped after each submission to remount the widget, since tokens are single use
+    let captcha_key = use_state(|| 0u32);
+    let service = use_memo((), |_| DefaultUserService::default());
+    let t = use_translation();
+
+    {
+        let captcha = captcha.clone();
+        let service = service.clone();
+        use_effect_with((), move |_| {
+            // Without settings the form is sent without a token and the backend decides
+            service.fetch_captcha_settings(Callback::from(move |result: ApiResult<CaptchaSettings>| {
+                if let Ok(settings) = result {
+                    captcha.set(settings);
+                }
+            }));
+            || ()
+        });
+    }
+
+    let on_captcha_token = {
+        let captcha_token = captcha_token.clone();
+        Callback::from(move |token: Option<String>| captcha_token.set(token))
+    };
+
+    let submit = {
+        let form_state = form_state.clone();
+        let notification = notification.clone();
+        let field_errors = field_errors.clone();
+        let captcha_token = captcha_token.clone();
+        let captcha_key = captcha_key.clone();
+        let service = service.clone();
+        let t = t.clone();
+
+        Callback::from(move |_| {
+            let form_state = form_state.clone();
+            let notification = notification.clone();
+            let field_errors = field_errors.clone();
+            let t = t.clone();
+            let current_state = (*form_state).clone();
+            let token = (*captcha_token).clone();
+            captcha_token.set(None);
+            captcha_key.set(*captcha_key + 1);
+
+            service.sign_up(
+                &current_state,
+                token,
+                Callback::from(move |result: SubmitResult| match result {
+                    Ok(_) => {
+                        notification.set(Some(Notification::success(t.t("signup-success"))));
+                        field_errors.set(FieldErrors::default());
+                        form_state.set(UserFormState::new());
+                    }
+                    Err(err) => {
+                        let (errors, general) = split_submit_error(err);
+                        // Go back to the step of the field the backend rejected
+                        let step = USER_FORM_FIELDS
+                            .iter()
+                            .find(|field| errors.get(field).is_some())
+                            .and_then(|field| SignupStep::of_field(field));
+                        if let Some(step) = step {
+                            form_state.set(UserFormState { step, ..(*form_state).clone() });
+                        }
+                        notification.set(general.map(Notification::error));
+                        field_errors.set(errors);
+                    }
+                }),
+            );
+        })
+    };
+
+    let on_next = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        let t = t.clone();
+        Callback::from(move |_| {
+            let mut new_state = (*form_state).clone();
+            if new_state.next_step() {
+                form_state.set(new_state);
+                field_errors.set(FieldErrors::default());
+            } else {
+                field_errors.set(wizard_step_errors(&t, &new_state));
+            }
+        })
+    };
+
+    let on_back = {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |_| {
+            let mut new_state = (*form_state).clone();
+            new_state.previous_step();
+            form_state.set(new_state);
+            field_errors.set(FieldErrors::default());
+        })
+    };
+
+    // Form input handlers
+    let on_change = |apply: fn(&mut UserFormState, String), field: &'static str| {
+        let form_state = form_state.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |value: String| {
+            let mut new_state = (*form_state).clone();
+            new_state.edit(|state| apply(state, value));
+            form_state.set(new_state);
+            clear_field_error(&field_errors, field);
+        })
+    };
+
+    let dismiss_notification = {
+        let notification = notification.clone();
+        Callback::from(move |_| notification.set(None))
+    };
+
+    html! {
+        <div>
+            <h1 class="text-4xl font-bold text-blue-500 mb-4">{ t.t("signup-title") }</h1>
+            <SignupWizard
+                state={(*form_state).clone()}
+                on_name_change={on_change(|state, name| state.name = name, "name")}
+                on_email_change={on_change(|state, email| state.email = email, "email")}
+                on_password_change={on_change(|state, password| state.password = password, "password")}
+                on_back={on_back}
+                on_next={on_next}
+                on_submit={submit}
+                notification={(*notification).clone()}
+                on_dismiss={dismiss_notification}
+                errors={(*field_errors).clone()}
+                on_history={form_history(&form_state)}
+            >
+                <CaptchaWidget
+                    key={*captcha_key}
+                    settings={(*captcha).clone()}
+                    on_token={on_captcha_token}
+                />
+            </SignupWizard>
+        </div>
+    }
+}
+
+// Query string of the link in verification emails
+#[derive(Deserialize, Default)]
+struct VerifyEmailQuery {
+    #[serde(default)]
+    token: String,
+}
+
+// Target of the link in verification emails; confirms the address on load
+#[function_component(VerifyEmailPage)]
+pub fn verify_email_page() -> Html {
+    let location = use_location();
+    let token = location
+        .and_then(|location| location.query::<VerifyEmailQuery>().ok())
+        .unwrap_or_default()
+        .token;
+    // `None` while the request is in flight
+    let outcome = use_state(|| None::<ApiResult<()>>);
+    let service = use_memo((), |_| DefaultUserService::default());
+    let t = use_translation();
+
+    {
+        let outcome = outcome.clone();
+        use_effect_with(token, move |token| {
+            service.verify_email(token, Callback::from(move |result| outcome.set(Some(result))));
+            || ()
+        });
+    }
+
+    html! {
+        <div>
+            <h1 class="text-4xl font-bold text-blue-500 mb-4">{ t.t("verify-email-title") }</h1>
+            {
+                match &*outcome {
+                    None => html! { <p class="text-gray-600 dark:text-gray-400">{ t.t("verify-email-pending") }</p> },
+                    Some(Ok(())) => html! {
+                        <p role="status" class="text-green-700 dark:text-green-400">{ t.t("verify-email-success") }</p>
+                    },
+                    Some(Err(err)) => html! { <p role="alert" class="text-red-500">{ err.clone() }</p> },
+                }
+            }
+        </div>
+    }
+}
+
+#[function_component(DashboardPage)]
+pub fn dashboard_page() -> Html {
+    let stats = use_state(|| None::<UserStats>);
+    let error = use_state(String::new);
+    let service = use_memo((), |_| DefaultUserService::default());
+    let t = use_translation();
+
+    {
+        let stats = stats.clone();
+        let error = error.clone();
+        let service = service.clone();
+        use_effect_with((), move |_| {
+            service.fetch_stats(Callback::from(move |result: ApiResult<UserStats>| {
+                match result {
+                    Ok(fetched) => stats.set(Some(fetched)),
+                    Err(err) => error.set(err),
+                }
+            }));
+            || ()
+        });
+    }
+
+    html! {
+        <div>
+            <h1 class="text-4xl font-bold text-blue-500 mb-4">{ t.t("dashboard-title") }</h1>
+            if !error.is_empty() {
+                <p role="alert" class="text-red-500 mb-4">{ &*error }</p>
+            }
+            if let Some(stats) = &*stats {
+                <div class="grid grid-cols-3 gap-4 mb-6">
+                    <StatCard label={t.t("stats-total")} value={stats.total} />
+                    <StatCard label={t.t("stats-verified")} value={stats.verified} />
+                    <StatCard label={t.t("stats-unverified")} value={stats.unverified} />
+                </div>
+                <BarChart
+                    title={t.t("chart-signups")}
+                    data={signup_chart_data(stats)}
+                />
+                <BarChart
+                    title={t.t("chart-verification")}
+                    data={vec![
+                        (t.t("stats-verified"), stats.verified),
+                        (t.t("stats-unverified"), stats.unverified),
+                    ]}
+                />
+            } else if error.is_empty() {
+                <p role="status" class="text-gray-500 dark:text-gray-400">{ t.t("stats-loading") }</p>
+            }
+        </div>
+    }
+}
+
+// Undoes or redoes the last edit of a form, for Ctrl+Z and Ctrl+Shift+Z
+fn form_history(form_state: &UseStateHandle<UserFormState>) -> Callback<HistoryStep> {
+    let form_state = form_state.clone();
+    Callback::from(move |step: HistoryStep| {
+        let mut new_state = (*form_state).clone();
+        if new_state.step_history(step) {
+            form_state.set(new_state);
+        }
+    })
+}
+
+// Drops the error shown on a field once the user edits it
+fn clear_field_error(field_errors: &UseStateHandle<FieldErrors>, field: &str) {
+    if field_errors.get(field).is_some() {
+        let mut errors = (**field_errors).clone();
+        errors.clear(field);
+        field_errors.set(errors);
+    }
+}
+
+// Outcome of a bulk delete of `total` users: a warning when some failed, an error when all did
+pub fn bulk_delete_notification(t: &Translator, total: usize, failed: usize) -> Notification {
+    let failures = || t.t_with("bulk-delete-failed", &[("failed", failed.into()), ("total", total.into())]);
+    match failed {
+        0 => Notification::success(t.t_with("bulk-deleted", &[("total", total.into())])),
+        _ if failed == total => Notification::error(failures()),
+        _ => Notification::warning(failures()),
+    }
+}
+
+// Outcome of a bulk update: a warning naming the first failure when some
+// users were skipped, an error when all of them were
+pub fn bulk_update_notification(t: &Translator, results: &[BulkUpdateResult]) -> Notification {
+    let total = results.len();
+    let failures: Vec<&BulkUpdateResult> = results.iter().filter(|result| result.user.is_none()).collect();
+    let Some(first) = failures.first() else {
+        return Notification::success(t.t_with("bulk-updated", &[("total", total.into())]));
+    };
+    let text = t.t_with(
+        "bulk-update-failed",
+        &[
+            ("failed", failures.len().into()),
+            ("total", total.into()),
+            ("reason", first.message.clone().unwrap_or_default().into()),
+        ],
+    );
+    if failures.len() == total {
+        Notification::error(text)
+    } else {
+        Notification::warning(text)
+    }
+}
+
+// Chart labels use the `MM-DD` part of each day to keep the axis readable
+pub fn signup_chart_data(stats: &UserStats) -> Vec<(String, i64)> {
+    stats
+        .signups_per_day
+        .iter()
+        .map(|entry| {
+            let label = entry.day.get(5..).unwrap_or(&entry.day).to_string();
+            (label, entry.count)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::DailySignups;
+    use crate::i18n::Locale;
+    use crate::state::NotificationLevel;
+
+    #[test]
+    fn test_signup_chart_data_labels() {
+        let stats = UserStats {
+            total: 3,
+            verified: 1,
+            unverified: 2,
+            signups_per_day: vec![
+                DailySignups {
+                    day: "2026-10-16".to_string(),
+                    count: 1,
+                },
+                DailySignups {
+                    day: "2026-10-17".to_string(),
+                    count: 2,
+                },
+            ],
+        };
+
+        assert_eq!(
+            signup_chart_data(&stats),
+            vec![("10-16".to_string(), 1), ("10-17".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_bulk_delete_notification() {
+        let t = Translator::new(Locale::En);
+        assert_eq!(
+            bulk_delete_notification(&t, 3, 0),
+            Notification::success("3 users deleted successfully")
+        );
+        assert_eq!(
+            bulk_delete_notification(&t, 3, 1),
+            Notification::warning("1 of 3 users could not be deleted")
+        );
+        assert_eq!(
+            bulk_delete_notification(&t, 2, 2),
+            Notification::error("2 of 2 users could not be deleted")
+        );
+        assert_eq!(bulk_delete_notification(&t, 1, 0).text, "1 user deleted successfully");
+
+        let pt = Translator::new(Locale::PtBr);
+        assert_eq!(bulk_delete_notification(&pt, 3, 0).text, "3 usuários excluídos com sucesso");
+    }
+
+    #[test]
+    fn test_bulk_update_notification() {
+        let t = Translator::new(Locale::En);
+        let updated = |id: i32| BulkUpdateResult {
+            id,
+            user: Some(User {
+                id,
+                name: "Ada".to_string(),
+                email: "ada@example.com".to_string(),
+                username: None,
+                status: UserStatus::Suspended,
+                tags: Vec::new(),
+                last_login_at: None,
+                created_at: None,
+                updated_at: None,
+                phone: None,
+                address: None,
+            }),
+            message: None,
+        };
+        let skipped = |id: i32| BulkUpdateResult {
+            id,
+            user: None,
+            message: Some(format!("User with id {} not found", id)),
+        };
+
+        assert_eq!(
+            bulk_update_notification(&t, &[updated(1), updated(2)]),
+            Notification::success("2 users updated successfully")
+        );
+        assert_eq!(
+            bulk_update_notification(&t, &[updated(1), skipped(9)]),
+            Notification::warning("1 of 2 users could not be updated: User with id 9 not found")
+        );
+        assert_eq!(bulk_update_notification(&t, &[skipped(9)]).level, NotificationLevel::Error);
+    }
+}
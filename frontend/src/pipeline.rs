@@ -0,0 +1,187 @@
+// Pipeline Module - Single Responsibility Principle
+// Interceptor chain HttpUserApiClient sends its requests through. Each layer
+// gets the request and the rest of the chain, an async
+// `fn(ApiRequest) -> Response`, so it can change the request, look at the
+// response, retry or answer by itself. Cross-cutting concerns such as failure
+// reports or the debug panel's recording are layers instead of code repeated
+// in every client method
+
+use gloo::net::http::Method;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+pub type LocalBoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+// The rest of the chain from one layer on, ending with the transport
+pub type Next<Res> = Rc<dyn Fn(ApiRequest) -> LocalBoxFuture<Res>>;
+
+// One interceptor; calling `next` hands the request on
+pub type Layer<Res> = Rc<dyn Fn(ApiRequest, Next<Res>) -> LocalBoxFuture<Res>>;
+
+// A request before it is handed to the browser
+#[derive(Clone, Debug)]
+pub struct ApiRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl ApiRequest {
+    pub fn new(method: Method, url: &str) -> Self {
+        Self {
+            method,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    // `body` sent as JSON
+    pub fn json(self, body: String) -> Self {
+        let mut request = self.header("Content-Type", "application/json");
+        request.body = Some(body);
+        request
+    }
+
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// Layers in the order a request passes them, then the transport. Generic over
+// the response so the chain can be tested without a browser
+pub struct Pipeline<Res> {
+    layers: Vec<Layer<Res>>,
+    transport: Next<Res>,
+}
+
+impl<Res> Clone for Pipeline<Res> {
+    fn clone(&self) -> Self {
+        Self {
+            layers: self.layers.clone(),
+            transport: self.transport.clone(),
+        }
+    }
+}
+
+impl<Res: 'static> Pipeline<Res> {
+    pub fn new(transport: impl Fn(ApiRequest) -> LocalBoxFuture<Res> + 'static) -> Self {
+        Self {
+            layers: Vec::new(),
+            transport: Rc::new(transport),
+        }
+    }
+
+    // Add `layer` inside the layers added before, i.e. closer to the transport
+    pub fn layer(mut self, layer: impl Fn(ApiRequest, Next<Res>) -> LocalBoxFuture<Res> + 'static) -> Self {
+        self.layers.push(Rc::new(layer));
+        self
+    }
+
+    pub fn send(&self, request: ApiRequest) -> LocalBoxFuture<Res> {
+        let chain = self.layers.iter().rev().fold(self.transport.clone(), |next, layer| {
+            let layer = layer.clone();
+            Rc::new(move |request| layer(request, next.clone())) as Next<Res>
+        });
+        chain(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::task::{Context, Poll, Waker};
+
+    // The futures in these tests never wait, so one poll finishes them
+    fn ready<T>(mut future: LocalBoxFuture<T>) -> T {
+        match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(value) => value,
+            Poll::Pending => panic!("future did not complete"),
+        }
+    }
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    fn traced(log: &Log, name: &'static str) -> impl Fn(ApiRequest, Next<String>) -> LocalBoxFuture<String> + use<> {
+        let log = log.clone();
+        move |request, next| {
+            let log = log.clone();
+            Box::pin(async move {
+                log.borrow_mut().push(format!("{} request", name));
+                let response = next(request).await;
+                log.borrow_mut().push(format!("{} response", name));
+                response
+            })
+        }
+    }
+
+    fn echo(log: &Log) -> impl Fn(ApiRequest) -> LocalBoxFuture<String> + use<> {
+        let log = log.clone();
+        move |request| {
+            log.borrow_mut().push("transport".to_string());
+            let answer = format!("{} {}", request.method, request.url);
+            Box::pin(async move { answer })
+        }
+    }
+
+    #[test]
+    fn test_layers_run_in_the_order_they_were_added() {
+        let log = Log::default();
+        let pipeline = Pipeline::new(echo(&log)).layer(traced(&log, "outer")).layer(traced(&log, "inner"));
+
+        let response = ready(pipeline.send(ApiRequest::new(Method::GET, "/users")));
+        assert_eq!(response, "GET /users");
+        assert_eq!(
+            *log.borrow(),
+            ["outer request", "inner request", "transport", "inner response", "outer response"]
+        );
+    }
+
+    #[test]
+    fn test_layers_see_the_changes_of_earlier_layers() {
+        let pipeline = Pipeline::new(|request: ApiRequest| {
+            let token = request.header_value("authorization").unwrap_or("none").to_string();
+            Box::pin(async move { token }) as LocalBoxFuture<String>
+        })
+        .layer(|request: ApiRequest, next: Next<String>| next(request.header("Authorization", "Bearer abc")))
+        .layer(|request: ApiRequest, next: Next<String>| {
+            let seen = request.header_value("Authorization").map(str::to_string);
+            Box::pin(async move { format!("{:?} {}", seen, next(request).await) })
+        });
+
+        assert_eq!(
+            ready(pipeline.send(ApiRequest::new(Method::GET, "/users"))),
+            "Some(\"Bearer abc\") Bearer abc"
+        );
+    }
+
+    #[test]
+    fn test_a_layer_can_answer_without_the_transport() {
+        let log = Log::default();
+        let pipeline = Pipeline::new(echo(&log))
+            .layer(traced(&log, "outer"))
+            .layer(|_: ApiRequest, _: Next<String>| Box::pin(async { "cached".to_string() }) as LocalBoxFuture<String>)
+            .layer(traced(&log, "inner"));
+
+        assert_eq!(ready(pipeline.send(ApiRequest::new(Method::GET, "/users"))), "cached");
+        assert_eq!(*log.borrow(), ["outer request", "outer response"]);
+    }
+
+    #[test]
+    fn test_json_request() {
+        let request = ApiRequest::new(Method::PUT, "/users/1/tags").json("{\"tags\":[]}".to_string());
+        assert_eq!(request.header_value("content-type"), Some("application/json"));
+        assert_eq!(request.body.as_deref(), Some("{\"tags\":[]}"));
+    }
+}
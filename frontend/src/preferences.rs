@@ -0,0 +1,378 @@
+// Preferences Module - Single Responsibility Principle
+// Layout choices of the user list (hidden and widened columns) and its saved
+// views, kept in localStorage so they survive reloads, and the UserPreferences
+// document the backend stores per user so theme, locale, page size, columns
+// and views roam across devices. Saved values win over the local defaults when merged
+
+use crate::api::UserStatus;
+use crate::components::LastSeenSort;
+use crate::i18n::Locale;
+use crate::theme::Theme;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const COLUMN_SETTINGS_STORAGE_KEY: &str = "column-settings";
+const SAVED_VIEWS_STORAGE_KEY: &str = "saved-views";
+
+// Most views a user may save, and longest name of one; the backend enforces the same
+pub const MAX_SAVED_VIEWS: usize = 20;
+pub const MAX_VIEW_NAME_LENGTH: usize = 40;
+
+// Data columns of the user list; the name column links to the profile and is always shown
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Id,
+    Name,
+    Email,
+    LastSeen,
+    Status,
+}
+
+impl Column {
+    // Columns the user may hide
+    pub const HIDEABLE: [Column; 4] = [Column::Id, Column::Email, Column::LastSeen, Column::Status];
+    // Columns the user may widen
+    pub const WIDENABLE: [Column; 2] = [Column::Name, Column::Email];
+
+    // Message id of the column header
+    pub fn label_id(&self) -> &'static str {
+        match self {
+            Column::Id => "column-id",
+            Column::Name => "field-name",
+            Column::Email => "field-email",
+            Column::LastSeen => "column-last-seen",
+            Column::Status => "column-status",
+        }
+    }
+
+    // Width of the column in the list grid
+    fn track(&self, wide: bool) -> &'static str {
+        match (self, wide) {
+            (Column::Id, _) => "50px",
+            (Column::Name | Column::Email, false) => "minmax(0,1fr)",
+            (Column::Name | Column::Email, true) => "minmax(0,2fr)",
+            (Column::LastSeen, _) => "130px",
+            (Column::Status, _) => "110px",
+        }
+    }
+}
+
+// Hidden and widened columns of the user list
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ColumnSettings {
+    #[serde(default)]
+    pub hidden: Vec<Column>,
+    #[serde(default)]
+    pub wide: Vec<Column>,
+}
+
+impl ColumnSettings {
+    // Settings saved on a previous visit, else every column at its normal width
+    pub fn load() -> Self {
+        LocalStorage::get(COLUMN_SETTINGS_STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn store(&self) {
+        // Losing the settings only brings back the default layout
+        let _ = LocalStorage::set(COLUMN_SETTINGS_STORAGE_KEY, self);
+    }
+
+    pub fn is_visible(&self, column: Column) -> bool {
+        !self.hidden.contains(&column)
+    }
+
+    pub fn is_wide(&self, column: Column) -> bool {
+        self.wide.contains(&column)
+    }
+
+    // Columns shown, in display order
+    pub fn visible_columns(&self) -> Vec<Column> {
+        [Column::Id, Column::Name, Column::Email, Column::LastSeen, Column::Status]
+            .into_iter()
+            .filter(|column| self.is_visible(*column))
+            .collect()
+    }
+
+    pub fn toggle_visible(&self, column: Column) -> Self {
+        let mut settings = self.clone();
+        if Column::HIDEABLE.contains(&column) {
+            toggle(&mut settings.hidden, column);
+        }
+        settings
+    }
+
+    pub fn toggle_wide(&self, column: Column) -> Self {
+        let mut settings = self.clone();
+        if Column::WIDENABLE.contains(&column) {
+            toggle(&mut settings.wide, column);
+        }
+        settings
+    }
+
+    // CSS `grid-template-columns` of the list rows: the optional checkbox,
+    // the visible columns and the three action buttons
+    pub fn grid_template(&self, selectable: bool) -> String {
+        let mut tracks = Vec::new();
+        if selectable {
+            tracks.push("30px");
+        }
+        tracks.extend(
+            self.visible_columns()
+                .into_iter()
+                .map(|column| column.track(self.is_wide(column))),
+        );
+        tracks.extend(["100px"; 3]);
+        tracks.join(" ")
+    }
+}
+
+// What the user list shows: users matching the search text, tag and status,
+// in the given order. Unset filters are left out of the stored JSON
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ListView {
+    // Matched case-insensitively against name, email and username
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub search: String,
+    #[serde(default, skip_serializing_if = "LastSeenSort::is_none")]
+    pub sort: LastSeenSort,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<UserStatus>,
+}
+
+// A ListView the user saved under a name to switch back to it later
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SavedView {
+    pub name: String,
+    #[serde(flatten)]
+    pub view: ListView,
+}
+
+impl SavedView {
+    // Views saved on a previous visit, in the order they were first saved
+    pub fn load_all() -> Vec<SavedView> {
+        LocalStorage::get(SAVED_VIEWS_STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn store_all(views: &[SavedView]) {
+        // Losing the views only empties the views menu
+        let _ = LocalStorage::set(SAVED_VIEWS_STORAGE_KEY, views);
+    }
+}
+
+// The saved view called `name`, if any
+pub fn find_view<'a>(views: &'a [SavedView], name: &str) -> Option<&'a SavedView> {
+    views.iter().find(|view| view.name == name)
+}
+
+// `views` with `view` saved: a view of the same name is replaced in place,
+// otherwise it is appended
+pub fn with_view(views: &[SavedView], view: SavedView) -> Vec<SavedView> {
+    let mut views = views.to_vec();
+    match views.iter_mut().find(|saved| saved.name == view.name) {
+        Some(saved) => *saved = view,
+        None => views.push(view),
+    }
+    views
+}
+
+pub fn without_view(views: &[SavedView], name: &str) -> Vec<SavedView> {
+    views.iter().filter(|view| view.name != name).cloned().collect()
+}
+
+// Preferences saved on the backend (`/users/<id>/preferences`); a missing
+// value means the user never changed it on any device
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct UserPreferences {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+    // Users per page, 1 to 100
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<ColumnSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub views: Option<Vec<SavedView>>,
+}
+
+impl UserPreferences {
+    // Preferences of this browser, to be merged with the saved ones
+    pub fn local() -> Self {
+        Self {
+            theme: Some(Theme::stored()),
+            locale: Some(Locale::preferred()),
+            page_size: None,
+            columns: Some(ColumnSettings::load()),
+            views: Some(SavedView::load_all()),
+        }
+    }
+
+    // These preferences with the gaps filled from `defaults`
+    pub fn merged_over(&self, defaults: &UserPreferences) -> Self {
+        Self {
+            theme: self.theme.or(defaults.theme),
+            locale: self.locale.or(defaults.locale),
+            page_size: self.page_size.or(defaults.page_size),
+            columns: self.columns.clone().or_else(|| defaults.columns.clone()),
+            views: self.views.clone().or_else(|| defaults.views.clone()),
+        }
+    }
+
+    // Keep the preferences in this browser; the providers pick them up on the next load
+    pub fn store_locally(&self) {
+        if let Some(theme) = self.theme {
+            theme.store();
+        }
+        if let Some(locale) = self.locale {
+            locale.store();
+        }
+        if let Some(columns) = &self.columns {
+            columns.store();
+        }
+        if let Some(views) = &self.views {
+            SavedView::store_all(views);
+        }
+    }
+}
+
+fn toggle(columns: &mut Vec<Column>, column: Column) {
+    match columns.iter().position(|c| *c == column) {
+        Some(index) => {
+            columns.remove(index);
+        }
+        None => columns.push(column),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hide_and_widen_columns() {
+        let settings = ColumnSettings::default()
+            .toggle_visible(Column::Id)
+            .toggle_wide(Column::Email);
+        assert!(!settings.is_visible(Column::Id));
+        assert!(settings.is_wide(Column::Email));
+        assert_eq!(
+            settings.visible_columns(),
+            vec![Column::Name, Column::Email, Column::LastSeen, Column::Status]
+        );
+
+        // Toggling again restores the column
+        assert!(settings.toggle_visible(Column::Id).is_visible(Column::Id));
+        // The name column cannot be hidden, nor the ID column widened
+        assert!(settings.toggle_visible(Column::Name).is_visible(Column::Name));
+        assert!(!settings.toggle_wide(Column::Id).is_wide(Column::Id));
+    }
+
+    #[test]
+    fn test_grid_template_follows_settings() {
+        let settings = ColumnSettings::default();
+        assert_eq!(
+            settings.grid_template(false),
+            "50px minmax(0,1fr) minmax(0,1fr) 130px 110px 100px 100px 100px"
+        );
+        assert!(settings.grid_template(true).starts_with("30px 50px"));
+
+        let settings = settings.toggle_visible(Column::Id).toggle_wide(Column::Email);
+        assert_eq!(
+            settings.grid_template(false),
+            "minmax(0,1fr) minmax(0,2fr) 130px 110px 100px 100px 100px"
+        );
+    }
+
+    #[test]
+    fn test_settings_storage_format() {
+        let settings = ColumnSettings::default().toggle_visible(Column::LastSeen);
+        let json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(json, r#"{"hidden":["last_seen"],"wide":[]}"#);
+        assert_eq!(serde_json::from_str::<ColumnSettings>("{}").unwrap(), ColumnSettings::default());
+    }
+
+    #[test]
+    fn test_saved_preferences_win_over_local_defaults() {
+        let saved = UserPreferences {
+            theme: Some(Theme::Dark),
+            page_size: Some(50),
+            ..UserPreferences::default()
+        };
+        let local = UserPreferences {
+            theme: Some(Theme::Light),
+            locale: Some(Locale::Es),
+            page_size: None,
+            columns: Some(ColumnSettings::default().toggle_visible(Column::Id)),
+            views: None,
+        };
+        let merged = saved.merged_over(&local);
+        assert_eq!(merged.theme, Some(Theme::Dark));
+        assert_eq!(merged.locale, Some(Locale::Es));
+        assert_eq!(merged.page_size, Some(50));
+        assert_eq!(merged.columns, local.columns);
+    }
+
+    #[test]
+    fn test_preferences_match_the_backend_format() {
+        let preferences = UserPreferences {
+            locale: Some(Locale::PtBr),
+            columns: Some(ColumnSettings::default().toggle_wide(Column::Email)),
+            ..UserPreferences::default()
+        };
+        let json = serde_json::to_string(&preferences).unwrap();
+        assert_eq!(json, r#"{"locale":"pt-BR","columns":{"hidden":[],"wide":["email"]}}"#);
+        let saved: UserPreferences = serde_json::from_str(r#"{"theme":"system","page_size":25}"#).unwrap();
+        assert_eq!(saved.theme, Some(Theme::System));
+        assert_eq!(saved.page_size, Some(25));
+    }
+
+    fn view(name: &str, tag: &str) -> SavedView {
+        SavedView {
+            name: name.to_string(),
+            view: ListView { tag: Some(tag.to_string()), ..ListView::default() },
+        }
+    }
+
+    #[test]
+    fn test_save_and_delete_views() {
+        let views = with_view(&with_view(&[], view("VIPs", "vip")), view("Staff", "staff"));
+        assert_eq!(views.iter().map(|view| view.name.as_str()).collect::<Vec<_>>(), vec!["VIPs", "Staff"]);
+
+        // Saving under an existing name replaces the view where it was
+        let views = with_view(&views, view("VIPs", "gold"));
+        assert_eq!(views.len(), 2);
+        assert_eq!(find_view(&views, "VIPs").unwrap().view.tag.as_deref(), Some("gold"));
+        assert_eq!(views[0].name, "VIPs");
+
+        let views = without_view(&views, "VIPs");
+        assert!(find_view(&views, "VIPs").is_none());
+        assert_eq!(views.len(), 1);
+    }
+
+    #[test]
+    fn test_views_match_the_backend_format() {
+        let saved = SavedView {
+            name: "Suspended staff".to_string(),
+            view: ListView {
+                search: "doe".to_string(),
+                sort: LastSeenSort::MostRecent,
+                tag: Some("staff".to_string()),
+                status: Some(UserStatus::Suspended),
+            },
+        };
+        let json = serde_json::to_string(&saved).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"Suspended staff","search":"doe","sort":"most_recent","tag":"staff","status":"suspended"}"#
+        );
+        assert_eq!(serde_json::from_str::<SavedView>(&json).unwrap(), saved);
+
+        // Unset filters are left out
+        let json = serde_json::to_string(&view("VIPs", "vip")).unwrap();
+        assert_eq!(json, r#"{"name":"VIPs","tag":"vip"}"#);
+    }
+}
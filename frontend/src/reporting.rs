@@ -0,0 +1,135 @@
+// Error reporting Module - Single Responsibility Principle
+// Sends panics and failed API requests to the backend (`POST /client-errors`)
+// with the route, user agent and app version, so crashes users never
+// mention still reach the logs. Reports go out with navigator.sendBeacon:
+// the browser sends them even when the WASM app traps right after a panic
+
+use crate::api::api_url;
+use crate::demo;
+use serde::Serialize;
+use shared::routes;
+
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Longer messages are cut so reports stay under the backend's size limit
+const MAX_MESSAGE_CHARS: usize = 4_000;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientErrorKind {
+    Panic,
+    Request,
+}
+
+// Body of `POST /client-errors`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ClientErrorReport {
+    pub kind: ClientErrorKind,
+    pub message: String,
+    pub route: Option<String>,
+    pub user_agent: Option<String>,
+    pub app_version: String,
+    // Failed requests only
+    pub url: Option<String>,
+    pub status: Option<u16>,
+}
+
+impl ClientErrorReport {
+    // Report about the page the user is on
+    pub fn new(kind: ClientErrorKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: truncate_message(message),
+            route: current_route(),
+            user_agent: user_agent(),
+            app_version: APP_VERSION.to_string(),
+            url: None,
+            status: None,
+        }
+    }
+}
+
+pub fn truncate_message(message: &str) -> String {
+    message.chars().take(MAX_MESSAGE_CHARS).collect()
+}
+
+// Only server errors and network failures are worth reporting; 4xx answers
+// are expected outcomes (validation, not found) the UI already handles
+pub fn is_reportable_status(status: u16) -> bool {
+    status >= 500
+}
+
+fn current_route() -> Option<String> {
+    web_sys::window()?.location().pathname().ok()
+}
+
+fn user_agent() -> Option<String> {
+    web_sys::window()?.navigator().user_agent().ok()
+}
+
+// Best effort: a report that cannot be sent is dropped
+pub fn send_report(report: &ClientErrorReport) {
+    // Demo builds have no backend to receive reports
+    if demo::ENABLED {
+        return;
+    }
+    let (Some(window), Ok(body)) = (web_sys::window(), serde_json::to_string(report)) else {
+        return;
+    };
+    let url = api_url(routes::CLIENT_ERRORS);
+    let _ = window.navigator().send_beacon_with_opt_str(&url, Some(&body));
+}
+
+pub fn report_failed_request(url: &str, status: Option<u16>, message: &str) {
+    let mut report = ClientErrorReport::new(ClientErrorKind::Request, message);
+    report.url = Some(url.to_string());
+    report.status = status;
+    send_report(&report);
+}
+
+// Log panics to the console, show the ErrorBoundary fallback and report
+// them; call before rendering the app
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let message = info.to_string();
+        web_sys::console::error_1(&message.clone().into());
+        crate::boundary::show_fallback();
+        send_report(&ClientErrorReport::new(ClientErrorKind::Panic, &message));
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_format() {
+        let report = ClientErrorReport {
+            kind: ClientErrorKind::Request,
+            message: "Server returned an error".to_string(),
+            route: Some("/users".to_string()),
+            user_agent: None,
+            app_version: APP_VERSION.to_string(),
+            url: Some("http://127.0.0.1:8000/api/v1/users".to_string()),
+            status: Some(500),
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["kind"], "request");
+        assert_eq!(json["status"], 500);
+        assert_eq!(json["app_version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_long_messages_are_truncated() {
+        assert_eq!(truncate_message("boom"), "boom");
+        assert_eq!(truncate_message(&"é".repeat(5_000)).chars().count(), MAX_MESSAGE_CHARS);
+    }
+
+    #[test]
+    fn test_only_server_errors_are_reported() {
+        assert!(is_reportable_status(500));
+        assert!(is_reportable_status(503));
+        assert!(!is_reportable_status(404));
+        assert!(!is_reportable_status(422));
+    }
+}
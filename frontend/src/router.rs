@@ -0,0 +1,133 @@
+// Router Module - Single Responsibility Principle
+// Maps URLs to pages
+
+use crate::api::User;
+use crate::i18n::use_translation;
+use crate::pages::{DashboardPage, ProfilePage, SignupPage, UserDetailPage, UsersPage, VerifyEmailPage};
+use std::fmt;
+use std::str::FromStr;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+// A user in a URL: `/users/7` by id, or `/users/@ada` by username
+#[derive(Clone, Debug, PartialEq)]
+pub enum UserRef {
+    Id(i32),
+    Username(String),
+}
+
+impl UserRef {
+    // Link to a user's profile, by username when they have one
+    pub fn of(user: &User) -> Self {
+        match &user.username {
+            Some(username) => UserRef::Username(username.clone()),
+            None => UserRef::Id(user.id),
+        }
+    }
+}
+
+impl FromStr for UserRef {
+    type Err = String;
+
+    fn from_str(segment: &str) -> Result<Self, Self::Err> {
+        match segment.strip_prefix('@') {
+            Some(username) if !username.is_empty() => Ok(UserRef::Username(username.to_string())),
+            Some(_) => Err("Empty username".to_string()),
+            None => segment.parse().map(UserRef::Id).map_err(|e| format!("Invalid user id: {}", e)),
+        }
+    }
+}
+
+impl fmt::Display for UserRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserRef::Id(id) => write!(f, "{}", id),
+            UserRef::Username(username) => write!(f, "@{}", username),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+pub enum Route {
+    #[at("/")]
+    Users,
+    #[at("/users/:user")]
+    UserDetail { user: UserRef },
+    #[at("/dashboard")]
+    Dashboard,
+    #[at("/signup")]
+    Signup,
+    #[at("/verify-email")]
+    VerifyEmail,
+    #[not_found]
+    #[at("/404")]
+    NotFound,
+}
+
+pub fn switch(route: Route) -> Html {
+    match route {
+        Route::Users => html! { <UsersPage /> },
+        Route::UserDetail { user: UserRef::Id(id) } => html! { <UserDetailPage id={id} /> },
+        Route::UserDetail { user: UserRef::Username(username) } => html! { <ProfilePage username={username} /> },
+        Route::Dashboard => html! { <DashboardPage /> },
+        Route::Signup => html! { <SignupPage /> },
+        Route::VerifyEmail => html! { <VerifyEmailPage /> },
+        Route::NotFound => html! { <NotFoundPage /> },
+    }
+}
+
+#[function_component(NotFoundPage)]
+fn not_found_page() -> Html {
+    let t = use_translation();
+    html! {
+        <h1 class="text-2xl font-bold text-gray-700 dark:text-gray-200">{ t.t("page-not-found") }</h1>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_paths() {
+        assert_eq!(Route::Users.to_path(), "/");
+        assert_eq!(Route::Dashboard.to_path(), "/dashboard");
+        assert_eq!(Route::recognize("/dashboard"), Some(Route::Dashboard));
+        assert_eq!(Route::UserDetail { user: UserRef::Id(7) }.to_path(), "/users/7");
+        assert_eq!(Route::recognize("/users/7"), Some(Route::UserDetail { user: UserRef::Id(7) }));
+        assert_eq!(
+            Route::recognize("/users/@ada-lovelace"),
+            Some(Route::UserDetail { user: UserRef::Username("ada-lovelace".to_string()) })
+        );
+        assert_eq!(Route::recognize("/users/ada"), Some(Route::NotFound));
+        assert_eq!(Route::recognize("/signup"), Some(Route::Signup));
+        assert_eq!(Route::recognize("/verify-email"), Some(Route::VerifyEmail));
+        assert_eq!(Route::recognize("/unknown"), Some(Route::NotFound));
+    }
+
+    #[test]
+    fn test_user_ref() {
+        assert_eq!("7".parse(), Ok(UserRef::Id(7)));
+        assert_eq!("@ada".parse(), Ok(UserRef::Username("ada".to_string())));
+        assert!("@".parse::<UserRef>().is_err());
+        assert!("ada".parse::<UserRef>().is_err());
+        assert_eq!(UserRef::Username("ada".to_string()).to_string(), "@ada");
+
+        let mut user = User {
+            id: 3,
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            username: None,
+            status: Default::default(),
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
+        };
+        assert_eq!(UserRef::of(&user), UserRef::Id(3));
+        user.username = Some("ada".to_string());
+        assert_eq!(UserRef::of(&user), UserRef::Username("ada".to_string()));
+    }
+}
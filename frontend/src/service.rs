@@ -2,17 +2,49 @@
 // Business logic layer that coordinates between API and UI
 
 use crate::api::{
-    ApiResult, CreateUserRequest, HttpUserApiClient, UpdateUserRequest, User, UserApiClient,
+    ActivityEntry, ApiError, ApiResult, BulkUpdateRequest, BulkUpdateResult, CaptchaSettings, CreateUserRequest,
+    HttpUserApiClient, SubmitResult, UpdateUserRequest, User, UserApiClient, UserStats, UserStatus,
+};
+use crate::debug;
+use crate::demo::DemoUserApiClient;
+use crate::preferences::UserPreferences;
+use crate::state::{parse_tags, tags_error_id, UserFormState};
+use shared::validation::{
+    normalize_email, normalize_tags, normalize_username, validate_email, validate_tag, validate_username,
 };
-use crate::state::UserFormState;
 use yew::prelude::*;
 
 // Service trait for user operations
 pub trait UserService {
     fn fetch_users(&self, callback: Callback<ApiResult<Vec<User>>>);
-    fn create_user(&self, state: &UserFormState, callback: Callback<ApiResult<()>>);
-    fn update_user(&self, state: &UserFormState, callback: Callback<ApiResult<()>>);
+    fn fetch_user(&self, id: i32, callback: Callback<ApiResult<User>>);
+    fn fetch_user_by_username(&self, username: &str, callback: Callback<ApiResult<User>>);
+    fn fetch_activity(&self, id: i32, callback: Callback<ApiResult<Vec<ActivityEntry>>>);
+    // Saved preferences of a user merged over this browser's, which they replace locally
+    fn sync_preferences(&self, user_id: i32, callback: Callback<ApiResult<UserPreferences>>);
+    fn save_preferences(&self, user_id: i32, preferences: UserPreferences, callback: Callback<SubmitResult>);
+    fn create_user(&self, state: &UserFormState, callback: Callback<SubmitResult>);
+    fn sign_up(&self, state: &UserFormState, captcha_token: Option<String>, callback: Callback<SubmitResult>);
+    fn verify_email(&self, token: &str, callback: Callback<ApiResult<()>>);
+    // Whether the email is registered already; invalid emails are never checked
+    fn email_taken(&self, email: &str, callback: Callback<ApiResult<bool>>);
+    // Whether another user has the username already; invalid usernames are never checked
+    fn username_taken(&self, username: &str, callback: Callback<ApiResult<bool>>);
+    fn fetch_captcha_settings(&self, callback: Callback<ApiResult<CaptchaSettings>>);
+    fn update_user(&self, state: &UserFormState, callback: Callback<SubmitResult>);
     fn delete_user(&self, id: i32, callback: Callback<ApiResult<()>>);
+    fn set_user_status(&self, id: i32, status: UserStatus, callback: Callback<ApiResult<()>>);
+    // Replace the tags of a user with the comma-separated tags of `input`
+    fn set_user_tags(&self, id: i32, input: &str, callback: Callback<SubmitResult>);
+    // Apply one change to the selected users; tags are normalized first
+    fn bulk_update_users(
+        &self,
+        request: BulkUpdateRequest,
+        callback: Callback<Result<Vec<BulkUpdateResult>, ApiError>>,
+    );
+    // CSV of the selected users, or of every user when none is selected
+    fn export_users(&self, ids: &[i32], callback: Callback<ApiResult<String>>);
+    fn fetch_stats(&self, callback: Callback<ApiResult<UserStats>>);
 }
 
 // Implementation of UserService
@@ -32,24 +64,111 @@ impl<T: UserApiClient> UserService for UserServiceImpl<T> {
         self.api_client.fetch_users(callback);
     }
 
-    fn create_user(&self, state: &UserFormState, callback: Callback<ApiResult<()>>) {
+    fn fetch_user(&self, id: i32, callback: Callback<ApiResult<User>>) {
+        self.api_client.fetch_user(id, callback);
+    }
+
+    fn fetch_user_by_username(&self, username: &str, callback: Callback<ApiResult<User>>) {
+        self.api_client.fetch_user_by_username(normalize_username(username), callback);
+    }
+
+    fn fetch_activity(&self, id: i32, callback: Callback<ApiResult<Vec<ActivityEntry>>>) {
+        self.api_client.fetch_activity(id, callback);
+    }
+
+    fn sync_preferences(&self, user_id: i32, callback: Callback<ApiResult<UserPreferences>>) {
+        self.api_client.fetch_preferences(
+            user_id,
+            Callback::from(move |result: ApiResult<UserPreferences>| {
+                let merged = result.map(|saved| saved.merged_over(&UserPreferences::local()));
+                if let Ok(preferences) = &merged {
+                    preferences.store_locally();
+                }
+                callback.emit(merged);
+            }),
+        );
+    }
+
+    fn save_preferences(&self, user_id: i32, preferences: UserPreferences, callback: Callback<SubmitResult>) {
+        preferences.store_locally();
+        self.api_client.save_preferences(user_id, preferences, callback);
+    }
+
+    fn create_user(&self, state: &UserFormState, callback: Callback<SubmitResult>) {
         if !state.is_valid() {
-            callback.emit(Err("Invalid form data".to_string()));
+            callback.emit(Err(ApiError::general("Invalid form data")));
             return;
         }
 
         let request = CreateUserRequest {
             name: state.name.clone(),
-            email: state.email.clone(),
+            email: normalize_email(&state.email),
             password: state.password.clone(),
+            username: state.username_request(),
+            captcha_token: None,
+            phone: state.phone_request(),
+            address: state.address_request(),
         };
 
         self.api_client.create_user(request, callback);
     }
 
-    fn update_user(&self, state: &UserFormState, callback: Callback<ApiResult<()>>) {
+    fn sign_up(&self, state: &UserFormState, captcha_token: Option<String>, callback: Callback<SubmitResult>) {
         if !state.is_valid() {
-            callback.emit(Err("Invalid form data".to_string()));
+            callback.emit(Err(ApiError::general("Invalid form data")));
+            return;
+        }
+
+        let request = CreateUserRequest {
+            name: state.name.clone(),
+            email: normalize_email(&state.email),
+            password: state.password.clone(),
+            username: None,
+            captcha_token,
+            phone: None,
+            address: None,
+        };
+
+        self.api_client.sign_up(request, callback);
+    }
+
+    fn verify_email(&self, token: &str, callback: Callback<ApiResult<()>>) {
+        let token = token.trim();
+        if token.is_empty() {
+            callback.emit(Err("Missing verification token".to_string()));
+            return;
+        }
+        self.api_client.verify_email(token.to_string(), callback);
+    }
+
+    fn email_taken(&self, email: &str, callback: Callback<ApiResult<bool>>) {
+        let email = normalize_email(email);
+        if validate_email(&email).is_err() {
+            callback.emit(Ok(false));
+            return;
+        }
+        self.api_client.email_exists(email, callback);
+    }
+
+    fn username_taken(&self, username: &str, callback: Callback<ApiResult<bool>>) {
+        let username = normalize_username(username);
+        if validate_username(&username).is_err() {
+            callback.emit(Ok(false));
+            return;
+        }
+        self.api_client.username_available(
+            username,
+            Callback::from(move |result: ApiResult<bool>| callback.emit(result.map(|available| !available))),
+        );
+    }
+
+    fn fetch_captcha_settings(&self, callback: Callback<ApiResult<CaptchaSettings>>) {
+        self.api_client.fetch_captcha_settings(callback);
+    }
+
+    fn update_user(&self, state: &UserFormState, callback: Callback<SubmitResult>) {
+        if !state.is_valid() {
+            callback.emit(Err(ApiError::general("Invalid form data")));
             return;
         }
 
@@ -57,27 +176,177 @@ impl<T: UserApiClient> UserService for UserServiceImpl<T> {
             let request = UpdateUserRequest {
                 id,
                 name: state.name.clone(),
-                email: state.email.clone(),
+                email: normalize_email(&state.email),
                 password: state.password.clone(),
+                username: state.username_request(),
+                phone: state.phone_request(),
+                address: state.address_request(),
             };
 
             self.api_client.update_user(request, callback);
         } else {
-            callback.emit(Err("No user selected for editing".to_string()));
+            callback.emit(Err(ApiError::general("No user selected for editing")));
         }
     }
 
     fn delete_user(&self, id: i32, callback: Callback<ApiResult<()>>) {
         self.api_client.delete_user(id, callback);
     }
+
+    fn set_user_status(&self, id: i32, status: UserStatus, callback: Callback<ApiResult<()>>) {
+        self.api_client.set_user_status(id, status, callback);
+    }
+
+    fn set_user_tags(&self, id: i32, input: &str, callback: Callback<SubmitResult>) {
+        if tags_error_id(input).is_some() {
+            callback.emit(Err(ApiError::general("Invalid tags")));
+            return;
+        }
+        self.api_client.set_user_tags(id, parse_tags(input), callback);
+    }
+
+    fn bulk_update_users(
+        &self,
+        request: BulkUpdateRequest,
+        callback: Callback<Result<Vec<BulkUpdateResult>, ApiError>>,
+    ) {
+        let request = BulkUpdateRequest {
+            add_tags: normalize_tags(&request.add_tags),
+            remove_tags: normalize_tags(&request.remove_tags),
+            ..request
+        };
+        if request.ids.is_empty() {
+            callback.emit(Err(ApiError::general("No users selected")));
+            return;
+        }
+        if request.add_tags.iter().chain(&request.remove_tags).any(|tag| validate_tag(tag).is_err()) {
+            callback.emit(Err(ApiError::general("Invalid tags")));
+            return;
+        }
+        self.api_client.bulk_update_users(request, callback);
+    }
+
+    fn export_users(&self, ids: &[i32], callback: Callback<ApiResult<String>>) {
+        self.api_client.export_users(ids.to_vec(), callback);
+    }
+
+    fn fetch_stats(&self, callback: Callback<ApiResult<UserStats>>) {
+        self.api_client.fetch_stats(callback);
+    }
 }
 
-// Default service implementation using HttpUserApiClient
-pub type DefaultUserService = UserServiceImpl<HttpUserApiClient>;
+// API client of regular builds: HttpUserApiClient, or the demo store while
+// the debug panel's mock switch is on
+#[derive(Clone, Default)]
+pub struct SwitchableUserApiClient {
+    http: HttpUserApiClient,
+    mock: DemoUserApiClient,
+}
+
+impl SwitchableUserApiClient {
+    // Checked on every call, as the switch only changes with a reload
+    fn client(&self) -> &dyn UserApiClient {
+        if debug::mock_client_enabled() {
+            &self.mock
+        } else {
+            &self.http
+        }
+    }
+}
+
+impl UserApiClient for SwitchableUserApiClient {
+    fn fetch_users(&self, callback: Callback<ApiResult<Vec<User>>>) {
+        self.client().fetch_users(callback);
+    }
+
+    fn fetch_user(&self, id: i32, callback: Callback<ApiResult<User>>) {
+        self.client().fetch_user(id, callback);
+    }
+
+    fn fetch_user_by_username(&self, username: String, callback: Callback<ApiResult<User>>) {
+        self.client().fetch_user_by_username(username, callback);
+    }
+
+    fn fetch_activity(&self, id: i32, callback: Callback<ApiResult<Vec<ActivityEntry>>>) {
+        self.client().fetch_activity(id, callback);
+    }
+
+    fn fetch_preferences(&self, user_id: i32, callback: Callback<ApiResult<UserPreferences>>) {
+        self.client().fetch_preferences(user_id, callback);
+    }
+
+    fn save_preferences(&self, user_id: i32, preferences: UserPreferences, callback: Callback<SubmitResult>) {
+        self.client().save_preferences(user_id, preferences, callback);
+    }
+
+    fn create_user(&self, request: CreateUserRequest, callback: Callback<SubmitResult>) {
+        self.client().create_user(request, callback);
+    }
+
+    fn sign_up(&self, request: CreateUserRequest, callback: Callback<SubmitResult>) {
+        self.client().sign_up(request, callback);
+    }
+
+    fn email_exists(&self, email: String, callback: Callback<ApiResult<bool>>) {
+        self.client().email_exists(email, callback);
+    }
+
+    fn username_available(&self, username: String, callback: Callback<ApiResult<bool>>) {
+        self.client().username_available(username, callback);
+    }
+
+    fn verify_email(&self, token: String, callback: Callback<ApiResult<()>>) {
+        self.client().verify_email(token, callback);
+    }
+
+    fn fetch_captcha_settings(&self, callback: Callback<ApiResult<CaptchaSettings>>) {
+        self.client().fetch_captcha_settings(callback);
+    }
+
+    fn update_user(&self, request: UpdateUserRequest, callback: Callback<SubmitResult>) {
+        self.client().update_user(request, callback);
+    }
+
+    fn delete_user(&self, id: i32, callback: Callback<ApiResult<()>>) {
+        self.client().delete_user(id, callback);
+    }
+
+    fn set_user_status(&self, id: i32, status: UserStatus, callback: Callback<ApiResult<()>>) {
+        self.client().set_user_status(id, status, callback);
+    }
+
+    fn set_user_tags(&self, id: i32, tags: Vec<String>, callback: Callback<SubmitResult>) {
+        self.client().set_user_tags(id, tags, callback);
+    }
+
+    fn bulk_update_users(
+        &self,
+        request: BulkUpdateRequest,
+        callback: Callback<Result<Vec<BulkUpdateResult>, ApiError>>,
+    ) {
+        self.client().bulk_update_users(request, callback);
+    }
+
+    fn export_users(&self, ids: Vec<i32>, callback: Callback<ApiResult<String>>) {
+        self.client().export_users(ids, callback);
+    }
+
+    fn fetch_stats(&self, callback: Callback<ApiResult<UserStats>>) {
+        self.client().fetch_stats(callback);
+    }
+}
+
+// Default service implementation, talking to the backend
+#[cfg(not(feature = "demo"))]
+pub type DefaultUserService = UserServiceImpl<SwitchableUserApiClient>;
+
+// Demo builds answer from localStorage instead of the backend
+#[cfg(feature = "demo")]
+pub type DefaultUserService = UserServiceImpl<crate::demo::DemoUserApiClient>;
 
 impl Default for DefaultUserService {
     fn default() -> Self {
-        Self::new(HttpUserApiClient::new())
+        Self::new(Default::default())
     }
 }
 
@@ -98,25 +367,136 @@ mod tests {
                     id: 1,
                     name: "Test User".to_string(),
                     email: "test@example.com".to_string(),
+                    username: None,
+                    status: UserStatus::Active,
+                    tags: Vec::new(),
+                    last_login_at: None,
+                    created_at: None,
+                    updated_at: None,
+                    phone: None,
+                    address: None,
                 }]));
             } else {
                 callback.emit(Err("Failed to fetch".to_string()));
             }
         }
 
-        fn create_user(&self, _request: CreateUserRequest, callback: Callback<ApiResult<()>>) {
+        fn fetch_user(&self, id: i32, callback: Callback<ApiResult<User>>) {
+            if self.should_succeed {
+                callback.emit(Ok(User {
+                    id,
+                    name: "Test User".to_string(),
+                    email: "test@example.com".to_string(),
+                    username: None,
+                    status: UserStatus::Active,
+                    tags: Vec::new(),
+                    last_login_at: None,
+                    created_at: None,
+                    updated_at: None,
+                    phone: None,
+                    address: None,
+                }));
+            } else {
+                callback.emit(Err("User not found".to_string()));
+            }
+        }
+
+        fn fetch_user_by_username(&self, username: String, callback: Callback<ApiResult<User>>) {
+            if self.should_succeed {
+                callback.emit(Ok(User {
+                    id: 3,
+                    name: "Test User".to_string(),
+                    email: "test@example.com".to_string(),
+                    username: Some(username),
+                    status: UserStatus::Active,
+                    tags: Vec::new(),
+                    last_login_at: None,
+                    created_at: None,
+                    updated_at: None,
+                    phone: None,
+                    address: None,
+                }));
+            } else {
+                callback.emit(Err("User not found".to_string()));
+            }
+        }
+
+        fn fetch_activity(&self, _id: i32, callback: Callback<ApiResult<Vec<ActivityEntry>>>) {
+            if self.should_succeed {
+                callback.emit(Ok(vec![ActivityEntry {
+                    event: "user.created".to_string(),
+                    occurred_at: "2026-10-17T12:00:00Z".to_string(),
+                }]));
+            } else {
+                callback.emit(Err("Failed to fetch activity".to_string()));
+            }
+        }
+
+        fn fetch_preferences(&self, _user_id: i32, callback: Callback<ApiResult<UserPreferences>>) {
+            if self.should_succeed {
+                callback.emit(Ok(UserPreferences::default()));
+            } else {
+                callback.emit(Err("Failed to fetch preferences".to_string()));
+            }
+        }
+
+        fn save_preferences(&self, _user_id: i32, _preferences: UserPreferences, callback: Callback<SubmitResult>) {
+            if self.should_succeed {
+                callback.emit(Ok(()));
+            } else {
+                callback.emit(Err(ApiError::general("Failed to save preferences")));
+            }
+        }
+
+        fn create_user(&self, _request: CreateUserRequest, callback: Callback<SubmitResult>) {
+            if self.should_succeed {
+                callback.emit(Ok(()));
+            } else {
+                callback.emit(Err(ApiError::general("Failed to create")));
+            }
+        }
+
+        fn sign_up(&self, _request: CreateUserRequest, callback: Callback<SubmitResult>) {
+            if self.should_succeed {
+                callback.emit(Ok(()));
+            } else {
+                callback.emit(Err(ApiError::general("Failed to sign up")));
+            }
+        }
+
+        fn email_exists(&self, email: String, callback: Callback<ApiResult<bool>>) {
+            if self.should_succeed {
+                callback.emit(Ok(email == "taken@example.com"));
+            } else {
+                callback.emit(Err("Failed to check email".to_string()));
+            }
+        }
+
+        fn username_available(&self, username: String, callback: Callback<ApiResult<bool>>) {
+            if self.should_succeed {
+                callback.emit(Ok(username != "taken"));
+            } else {
+                callback.emit(Err("Failed to check username".to_string()));
+            }
+        }
+
+        fn verify_email(&self, _token: String, callback: Callback<ApiResult<()>>) {
             if self.should_succeed {
                 callback.emit(Ok(()));
             } else {
-                callback.emit(Err("Failed to create".to_string()));
+                callback.emit(Err("Invalid or expired verification token".to_string()));
             }
         }
 
-        fn update_user(&self, _request: UpdateUserRequest, callback: Callback<ApiResult<()>>) {
+        fn fetch_captcha_settings(&self, callback: Callback<ApiResult<CaptchaSettings>>) {
+            callback.emit(Ok(CaptchaSettings::default()));
+        }
+
+        fn update_user(&self, _request: UpdateUserRequest, callback: Callback<SubmitResult>) {
             if self.should_succeed {
                 callback.emit(Ok(()));
             } else {
-                callback.emit(Err("Failed to update".to_string()));
+                callback.emit(Err(ApiError::general("Failed to update")));
             }
         }
 
@@ -127,6 +507,63 @@ mod tests {
                 callback.emit(Err("Failed to delete".to_string()));
             }
         }
+
+        fn set_user_status(&self, _id: i32, _status: UserStatus, callback: Callback<ApiResult<()>>) {
+            if self.should_succeed {
+                callback.emit(Ok(()));
+            } else {
+                callback.emit(Err("Failed to change status".to_string()));
+            }
+        }
+
+        fn set_user_tags(&self, _id: i32, _tags: Vec<String>, callback: Callback<SubmitResult>) {
+            if self.should_succeed {
+                callback.emit(Ok(()));
+            } else {
+                callback.emit(Err(ApiError::general("Failed to save tags")));
+            }
+        }
+
+        fn bulk_update_users(
+            &self,
+            request: BulkUpdateRequest,
+            callback: Callback<Result<Vec<BulkUpdateResult>, ApiError>>,
+        ) {
+            if self.should_succeed {
+                callback.emit(Ok(request
+                    .ids
+                    .into_iter()
+                    .map(|id| BulkUpdateResult {
+                        id,
+                        user: None,
+                        message: (!request.add_tags.is_empty()).then(|| request.add_tags.join(",")),
+                    })
+                    .collect()));
+            } else {
+                callback.emit(Err(ApiError::general("Failed to update users")));
+            }
+        }
+
+        fn export_users(&self, ids: Vec<i32>, callback: Callback<ApiResult<String>>) {
+            if self.should_succeed {
+                callback.emit(Ok(format!("exported {:?}", ids)));
+            } else {
+                callback.emit(Err("Failed to export users".to_string()));
+            }
+        }
+
+        fn fetch_stats(&self, callback: Callback<ApiResult<UserStats>>) {
+            if self.should_succeed {
+                callback.emit(Ok(UserStats {
+                    total: 1,
+                    verified: 0,
+                    unverified: 1,
+                    signups_per_day: Vec::new(),
+                }));
+            } else {
+                callback.emit(Err("Failed to fetch stats".to_string()));
+            }
+        }
     }
 
     #[test]
@@ -207,6 +644,10 @@ mod tests {
             name: state.name.clone(),
             email: state.email.clone(),
             password: state.password.clone(),
+            username: None,
+            captcha_token: None,
+            phone: None,
+            address: None,
         };
 
         assert_eq!(request.name, "John");
@@ -229,6 +670,9 @@ mod tests {
                 name: state.name.clone(),
                 email: state.email.clone(),
                 password: state.password.clone(),
+                username: None,
+                phone: None,
+                address: None,
             };
 
             assert_eq!(request.id, 5);
@@ -239,4 +683,136 @@ mod tests {
             panic!("Expected editing_id to be Some");
         }
     }
+
+    #[test]
+    fn test_fetch_user_and_activity_delegate_to_client() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let service = UserServiceImpl::new(MockUserApiClient {
+            should_succeed: true,
+        });
+        let user = Rc::new(RefCell::new(None));
+        let activity = Rc::new(RefCell::new(None));
+        {
+            let user = user.clone();
+            service.fetch_user(7, Callback::from(move |result| *user.borrow_mut() = Some(result)));
+        }
+        {
+            let activity = activity.clone();
+            service.fetch_activity(7, Callback::from(move |result| *activity.borrow_mut() = Some(result)));
+        }
+
+        assert_eq!(user.borrow().clone().unwrap().unwrap().id, 7);
+        assert_eq!(activity.borrow().clone().unwrap().unwrap()[0].event, "user.created");
+    }
+
+    #[test]
+    fn test_sign_up_and_verify_email_check_input_first() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let service = UserServiceImpl::new(MockUserApiClient {
+            should_succeed: true,
+        });
+        let signed_up = Rc::new(RefCell::new(None));
+        {
+            let signed_up = signed_up.clone();
+            service.sign_up(&UserFormState::new(), None, Callback::from(move |result| *signed_up.borrow_mut() = Some(result)));
+        }
+        assert!(signed_up.borrow().clone().unwrap().is_err());
+
+        let verified = Rc::new(RefCell::new(Vec::new()));
+        for token in ["", "abc123"] {
+            let verified = verified.clone();
+            service.verify_email(token, Callback::from(move |result| verified.borrow_mut().push(result)));
+        }
+        assert_eq!(
+            *verified.borrow(),
+            vec![Err("Missing verification token".to_string()), Ok(())]
+        );
+    }
+
+    #[test]
+    fn test_email_taken_skips_invalid_emails() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let service = UserServiceImpl::new(MockUserApiClient {
+            should_succeed: true,
+        });
+        let answers = Rc::new(RefCell::new(Vec::new()));
+        for email in [" Taken@Example.com", "free@example.com", "taken@"] {
+            let answers = answers.clone();
+            service.email_taken(email, Callback::from(move |result| answers.borrow_mut().push(result)));
+        }
+        assert_eq!(*answers.borrow(), vec![Ok(true), Ok(false), Ok(false)]);
+    }
+
+    #[test]
+    fn test_username_taken_skips_invalid_usernames() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let service = UserServiceImpl::new(MockUserApiClient {
+            should_succeed: true,
+        });
+        let answers = Rc::new(RefCell::new(Vec::new()));
+        for username in ["@Taken", "free", "ab"] {
+            let answers = answers.clone();
+            service.username_taken(username, Callback::from(move |result| answers.borrow_mut().push(result)));
+        }
+        assert_eq!(*answers.borrow(), vec![Ok(true), Ok(false), Ok(false)]);
+
+        let user = Rc::new(RefCell::new(None));
+        {
+            let user = user.clone();
+            service.fetch_user_by_username("@Ana", Callback::from(move |result| *user.borrow_mut() = Some(result)));
+        }
+        assert_eq!(user.borrow().clone().unwrap().unwrap().username.as_deref(), Some("ana"));
+    }
+
+    #[test]
+    fn test_set_user_tags_validates_before_saving() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let service = UserServiceImpl::new(MockUserApiClient {
+            should_succeed: true,
+        });
+        let results = Rc::new(RefCell::new(Vec::new()));
+        for input in ["VIP, beta", "not valid"] {
+            let results = results.clone();
+            service.set_user_tags(1, input, Callback::from(move |result| results.borrow_mut().push(result)));
+        }
+        assert_eq!(*results.borrow(), vec![Ok(()), Err(ApiError::general("Invalid tags"))]);
+    }
+
+    #[test]
+    fn test_bulk_update_users_normalizes_tags() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let service = UserServiceImpl::new(MockUserApiClient {
+            should_succeed: true,
+        });
+        let results = Rc::new(RefCell::new(Vec::new()));
+        let request = |ids: Vec<i32>, tag: &str| BulkUpdateRequest {
+            ids,
+            add_tags: vec![tag.to_string()],
+            ..Default::default()
+        };
+        for request in [request(vec![1, 2], " VIP "), request(vec![1], "not valid"), request(Vec::new(), "vip")] {
+            let results = results.clone();
+            service.bulk_update_users(request, Callback::from(move |result| results.borrow_mut().push(result)));
+        }
+
+        let results = results.borrow();
+        // The mock echoes the tags it received as the message
+        let updated = results[0].as_ref().unwrap();
+        assert_eq!(updated.len(), 2);
+        assert_eq!(updated[0].message.as_deref(), Some("vip"));
+        assert_eq!(results[1], Err(ApiError::general("Invalid tags")));
+        assert_eq!(results[2], Err(ApiError::general("No users selected")));
+    }
 }
@@ -1,14 +1,196 @@
 // User State Module - Single Responsibility Principle
-// Manages user form state and validation
-
+// Manages user form state and validation, including the steps of the
+// signup wizard and the undo history of form edits
+
+use crate::api::{Address, ApiError};
+use shared::password::{validate_password, PasswordError};
+use shared::validation::{
+    normalize_phone, normalize_tags, normalize_username, validate_address, validate_email,
+    validate_phone, validate_tags, validate_username, AddressError, EmailError, TagError,
+    UsernameError, MAX_USERNAME_LENGTH,
+};
+use std::collections::BTreeMap;
 use yew::prelude::*;
 
+// Steps of the signup wizard, in order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SignupStep {
+    #[default]
+    Account,
+    Password,
+    Confirm,
+}
+
+impl SignupStep {
+    pub const ALL: [SignupStep; 3] = [SignupStep::Account, SignupStep::Password, SignupStep::Confirm];
+
+    // Position of the step, from 0
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|step| step == self).unwrap_or_default()
+    }
+
+    pub fn next(&self) -> Option<Self> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+
+    pub fn previous(&self) -> Option<Self> {
+        self.index().checked_sub(1).map(|index| Self::ALL[index])
+    }
+
+    // Message id of the step's title
+    pub fn label_id(&self) -> &'static str {
+        match self {
+            SignupStep::Account => "signup-step-account",
+            SignupStep::Password => "signup-step-password",
+            SignupStep::Confirm => "signup-step-confirm",
+        }
+    }
+
+    // Form fields entered on the step
+    pub fn fields(&self) -> &'static [&'static str] {
+        match self {
+            SignupStep::Account => &["name", "email"],
+            SignupStep::Password => &["password"],
+            SignupStep::Confirm => &[],
+        }
+    }
+
+    // Step where a field is entered, so errors the backend reports can be shown there
+    pub fn of_field(field: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|step| step.fields().contains(&field))
+    }
+}
+
+// Input mask of the username field: lowercase letters, digits and dashes, at
+// most 30 of them; uppercase letters are lowered rather than dropped
+pub fn mask_username(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| c.to_ascii_lowercase())
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '-')
+        .take(MAX_USERNAME_LENGTH)
+        .collect()
+}
+
+// Tags typed in the tag editor, separated by commas: `VIP, beta` becomes
+// `["beta", "vip"]`, normalized like the backend stores them
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let tags: Vec<String> = input.split(',').map(str::to_string).collect();
+    normalize_tags(&tags)
+}
+
+// Tags as the tag editor shows them
+pub fn format_tags(tags: &[String]) -> String {
+    tags.join(", ")
+}
+
+// Message id of the tag editor's error, if its input is invalid
+pub fn tags_error_id(input: &str) -> Option<&'static str> {
+    validate_tags(&parse_tags(input)).err().map(|error| match error {
+        TagError::Empty | TagError::InvalidFormat => "tag-invalid",
+        TagError::TooLong => "tag-too-long",
+        TagError::TooMany => "tags-too-many",
+    })
+}
+
+// Longest phone number E.164 allows, in digits
+const MAX_PHONE_DIGITS: usize = 15;
+
+// Input mask of the phone field: a leading `+` followed by at most 15 digits,
+// so whatever is typed or pasted stays in the E.164 shape the backend expects
+pub fn mask_phone(input: &str) -> String {
+    let digits: String = input
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .take(MAX_PHONE_DIGITS)
+        .collect();
+    if digits.is_empty() {
+        String::new()
+    } else {
+        format!("+{}", digits)
+    }
+}
+
+// Input mask of the country field: two uppercase letters
+pub fn mask_country(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .take(2)
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+// Message id of an address validation error
+fn address_error_id(error: AddressError) -> &'static str {
+    match error {
+        AddressError::StreetEmpty => "address-street-empty",
+        AddressError::CityEmpty => "address-city-empty",
+        AddressError::InvalidPostalCode => "address-postal-code-invalid",
+        AddressError::InvalidCountry => "address-country-invalid",
+        AddressError::TooLong => "address-too-long",
+    }
+}
+
+// Most edits of a form that can be undone; older ones are forgotten
+pub const MAX_FORM_HISTORY: usize = 100;
+
+// Values of the editable fields, as kept in the undo history
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FormValues {
+    pub name: String,
+    pub email: String,
+    pub password: String,
+    pub username: String,
+    pub phone: String,
+    pub address: Address,
+}
+
+// Direction of a history shortcut
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryStep {
+    Undo,
+    Redo,
+}
+
+impl HistoryStep {
+    // Step of a key press: Ctrl+Z (Cmd+Z on macOS) undoes, Ctrl+Shift+Z and Ctrl+Y redo
+    pub fn from_key(key: &str, ctrl_or_meta: bool, shift: bool) -> Option<Self> {
+        if !ctrl_or_meta {
+            return None;
+        }
+        match (key.to_ascii_lowercase().as_str(), shift) {
+            ("z", false) => Some(HistoryStep::Undo),
+            ("z", true) | ("y", false) => Some(HistoryStep::Redo),
+            _ => None,
+        }
+    }
+}
+
+// Values before each edit, most recent last, and the values undone since
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FormHistory {
+    undo: Vec<FormValues>,
+    redo: Vec<FormValues>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct UserFormState {
     pub name: String,
     pub email: String,
     pub password: String,
+    // Optional; empty when the user has none
+    pub username: String,
+    // Set when the edited user already has a username, which never changes
+    pub username_locked: bool,
+    // Optional; empty when the user has no phone
+    pub phone: String,
+    // Optional; left blank when every field is empty
+    pub address: Address,
     pub editing_id: Option<i32>,
+    // Current step of the signup wizard; other forms show every field at once
+    pub step: SignupStep,
+    pub history: FormHistory,
 }
 
 impl Default for UserFormState {
@@ -23,7 +205,13 @@ impl UserFormState {
             name: String::new(),
             email: String::new(),
             password: String::new(),
+            username: String::new(),
+            username_locked: false,
+            phone: String::new(),
+            address: Address::default(),
             editing_id: None,
+            step: SignupStep::default(),
+            history: FormHistory::default(),
         }
     }
 
@@ -37,7 +225,82 @@ impl UserFormState {
             name,
             email,
             password,
+            username: String::new(),
+            username_locked: false,
+            phone: String::new(),
+            address: Address::default(),
             editing_id,
+            step: SignupStep::default(),
+            history: FormHistory::default(),
+        }
+    }
+
+    pub fn values(&self) -> FormValues {
+        FormValues {
+            name: self.name.clone(),
+            email: self.email.clone(),
+            password: self.password.clone(),
+            username: self.username.clone(),
+            phone: self.phone.clone(),
+            address: self.address.clone(),
+        }
+    }
+
+    fn restore(&mut self, values: FormValues) {
+        self.name = values.name;
+        self.email = values.email;
+        self.password = values.password;
+        self.username = values.username;
+        self.phone = values.phone;
+        self.address = values.address;
+    }
+
+    // Apply a user's edit, recording the previous values so it can be undone.
+    // A new edit drops whatever was undone before it
+    pub fn edit(&mut self, change: impl FnOnce(&mut Self)) {
+        let before = self.values();
+        change(self);
+        if self.values() != before {
+            if self.history.undo.len() == MAX_FORM_HISTORY {
+                self.history.undo.remove(0);
+            }
+            self.history.undo.push(before);
+            self.history.redo.clear();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.history.redo.is_empty()
+    }
+
+    // Go back to the values before the last edit; false when there is none
+    pub fn undo(&mut self) -> bool {
+        let Some(values) = self.history.undo.pop() else {
+            return false;
+        };
+        self.history.redo.push(self.values());
+        self.restore(values);
+        true
+    }
+
+    // Reapply the last undone edit; false when there is none
+    pub fn redo(&mut self) -> bool {
+        let Some(values) = self.history.redo.pop() else {
+            return false;
+        };
+        self.history.undo.push(self.values());
+        self.restore(values);
+        true
+    }
+
+    pub fn step_history(&mut self, step: HistoryStep) -> bool {
+        match step {
+            HistoryStep::Undo => self.undo(),
+            HistoryStep::Redo => self.redo(),
         }
     }
 
@@ -48,19 +311,141 @@ impl UserFormState {
     pub fn is_valid(&self) -> bool {
         !self.name.trim().is_empty()
             && !self.email.trim().is_empty()
-            && !self.password.trim().is_empty()
+            && validate_password(&self.password).is_ok()
             && self.is_valid_email()
+            && self.username_error_id().is_none()
+            && self.phone_error_id().is_none()
+            && self.address_error_id().is_none()
     }
 
+    // Uses the same rules as the backend so both sides agree on what is valid
     pub fn is_valid_email(&self) -> bool {
-        self.email.contains('@') && self.email.len() > 3
+        validate_email(&self.email).is_ok()
+    }
+
+    pub fn email_error(&self) -> Option<String> {
+        validate_email(&self.email).err().map(|e| e.to_string())
+    }
+
+    // Message id of the username error; an empty username is valid, as it is optional
+    pub fn username_error_id(&self) -> Option<&'static str> {
+        let username = self.username_request()?;
+        validate_username(&username).err().map(|error| match error {
+            UsernameError::TooShort => "username-too-short",
+            UsernameError::TooLong => "username-too-long",
+            UsernameError::InvalidFormat => "username-invalid",
+        })
+    }
+
+    // Username as sent to the backend, None when left empty
+    pub fn username_request(&self) -> Option<String> {
+        Some(normalize_username(&self.username)).filter(|username| !username.is_empty())
+    }
+
+    // Message id of the phone error; an empty phone is valid, as it is optional
+    pub fn phone_error_id(&self) -> Option<&'static str> {
+        match self.phone_request() {
+            Some(phone) => validate_phone(&phone).err().map(|_| "phone-invalid"),
+            None => None,
+        }
+    }
+
+    // Message id of the address error; a blank address is valid, as it is optional
+    pub fn address_error_id(&self) -> Option<&'static str> {
+        let address = self.address_request()?;
+        validate_address(
+            &address.street,
+            address.complement.as_deref(),
+            &address.city,
+            address.region.as_deref(),
+            &address.postal_code,
+            &address.country,
+        )
+        .err()
+        .map(address_error_id)
+    }
+
+    // Phone as sent to the backend, None when left empty
+    pub fn phone_request(&self) -> Option<String> {
+        Some(normalize_phone(&self.phone)).filter(|phone| !phone.is_empty())
+    }
+
+    // Address as sent to the backend: trimmed, None when every field is empty
+    pub fn address_request(&self) -> Option<Address> {
+        let optional = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let address = Address {
+            street: self.address.street.trim().to_string(),
+            complement: optional(&self.address.complement),
+            city: self.address.city.trim().to_string(),
+            region: optional(&self.address.region),
+            postal_code: self.address.postal_code.trim().to_string(),
+            country: self.address.country.trim().to_string(),
+        };
+        (address != Address::default()).then_some(address)
     }
 
     pub fn reset(&mut self) {
         self.name.clear();
         self.email.clear();
         self.password.clear();
+        self.username.clear();
+        self.username_locked = false;
+        self.phone.clear();
+        self.address = Address::default();
         self.editing_id = None;
+        self.step = SignupStep::default();
+        self.clear_history();
+    }
+
+    fn clear_history(&mut self) {
+        self.history = FormHistory::default();
+    }
+
+    // Message ids of the errors blocking the current step, by field, with the
+    // same rules as the backend
+    pub fn step_errors(&self) -> Vec<(&'static str, &'static str)> {
+        let mut errors = Vec::new();
+        for field in self.step.fields() {
+            let error = match *field {
+                "name" => self.name.trim().is_empty().then_some("name-empty"),
+                "email" => validate_email(&self.email).err().map(|error| match error {
+                    EmailError::Empty => "email-empty",
+                    _ => "email-invalid",
+                }),
+                "password" => validate_password(&self.password).err().map(|error| match error {
+                    PasswordError::Empty => "password-empty",
+                    _ => "password-too-short",
+                }),
+                _ => None,
+            };
+            if let Some(message_id) = error {
+                errors.push((*field, message_id));
+            }
+        }
+        errors
+    }
+
+    // Move to the next step when the current one is valid; true when it moved
+    pub fn next_step(&mut self) -> bool {
+        match self.step.next() {
+            Some(next) if self.step_errors().is_empty() => {
+                self.step = next;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn previous_step(&mut self) {
+        if let Some(previous) = self.step.previous() {
+            self.step = previous;
+        }
     }
 
     pub fn set_for_editing(&mut self, id: i32, name: String, email: String, password: String) {
@@ -68,6 +453,93 @@ impl UserFormState {
         self.email = email;
         self.password = password;
         self.editing_id = Some(id);
+        // Undo never brings back what was typed for another user
+        self.clear_history();
+    }
+
+    // Fill the username of a stored user, locked once it has one
+    pub fn set_username(&mut self, username: Option<String>) {
+        self.username_locked = username.is_some();
+        self.username = username.unwrap_or_default();
+    }
+
+    // Fill the optional contact fields from a stored user
+    pub fn set_contact(&mut self, phone: Option<String>, address: Option<Address>) {
+        self.phone = phone.unwrap_or_default();
+        self.address = address.unwrap_or_default();
+    }
+}
+
+// Severity of a notification, which sets its colour and lifetime
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NotificationLevel {
+    Success,
+    Warning,
+    Error,
+}
+
+// Feedback shown to the user after an action
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub text: String,
+}
+
+impl Notification {
+    pub fn success(text: impl Into<String>) -> Self {
+        Self { level: NotificationLevel::Success, text: text.into() }
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self { level: NotificationLevel::Warning, text: text.into() }
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self { level: NotificationLevel::Error, text: text.into() }
+    }
+
+    // Successes go away on their own; warnings and errors stay until dismissed
+    pub fn auto_dismisses(&self) -> bool {
+        self.level == NotificationLevel::Success
+    }
+}
+
+// Inputs of the user form that can show an error
+pub const USER_FORM_FIELDS: [&str; 6] = ["name", "email", "password", "username", "phone", "address"];
+
+// Errors reported by the backend, by form field
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldErrors(BTreeMap<String, String>);
+
+impl FieldErrors {
+    pub fn get(&self, field: &str) -> Option<String> {
+        self.0.get(field).cloned()
+    }
+
+    pub fn set(&mut self, field: &str, message: String) {
+        self.0.insert(field.to_string(), message);
+    }
+
+    // Drops the error of a field once it is edited
+    pub fn clear(&mut self, field: &str) {
+        self.0.remove(field);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+// Splits a rejected submission into an error shown on its form field, or a
+// general error when the backend did not name one of the form's fields
+pub fn split_submit_error(error: ApiError) -> (FieldErrors, Option<String>) {
+    let mut errors = FieldErrors::default();
+    match error.field.as_deref() {
+        Some(field) if USER_FORM_FIELDS.contains(&field) => {
+            errors.set(field, error.message);
+            (errors, None)
+        }
+        _ => (errors, Some(error.message)),
     }
 }
 
@@ -160,6 +632,42 @@ mod tests {
         assert!(state.is_valid_email());
     }
 
+    #[test]
+    fn test_is_valid_email_rejects_malformed_addresses() {
+        let mut state = UserFormState::new();
+
+        state.email = "a@b@c".to_string();
+        assert!(!state.is_valid_email());
+
+        state.email = "@@".to_string();
+        assert!(!state.is_valid_email());
+    }
+
+    #[test]
+    fn test_is_valid_requires_backend_password_policy() {
+        let mut state = UserFormState::new();
+        state.name = "John".to_string();
+        state.email = "john@example.com".to_string();
+
+        state.password = "12345".to_string();
+        assert!(!state.is_valid());
+
+        state.password = "123456".to_string();
+        assert!(state.is_valid());
+    }
+
+    #[test]
+    fn test_email_error_matches_backend_messages() {
+        let mut state = UserFormState::new();
+        assert_eq!(state.email_error(), Some("Email cannot be empty".to_string()));
+
+        state.email = "invalid".to_string();
+        assert_eq!(state.email_error(), Some("Invalid email format".to_string()));
+
+        state.email = "john@example.com".to_string();
+        assert_eq!(state.email_error(), None);
+    }
+
     #[test]
     fn test_reset() {
         let mut state = UserFormState::with_values(
@@ -228,4 +736,261 @@ mod tests {
         let cloned = state.clone();
         assert_eq!(state, cloned);
     }
+
+    #[test]
+    fn test_split_submit_error_on_form_field() {
+        let error = ApiError {
+            message: "Invalid email format".to_string(),
+            field: Some("email".to_string()),
+        };
+        let (errors, general) = split_submit_error(error);
+        assert_eq!(errors.get("email").as_deref(), Some("Invalid email format"));
+        assert_eq!(general, None);
+    }
+
+    #[test]
+    fn test_split_submit_error_falls_back_to_general() {
+        let (errors, general) = split_submit_error(ApiError::general("Request failed"));
+        assert!(errors.is_empty());
+        assert_eq!(general.as_deref(), Some("Request failed"));
+
+        let unknown_field = ApiError {
+            message: "Invalid value".to_string(),
+            field: Some("tags[1]".to_string()),
+        };
+        let (errors, general) = split_submit_error(unknown_field);
+        assert!(errors.is_empty());
+        assert_eq!(general.as_deref(), Some("Invalid value"));
+    }
+
+    #[test]
+    fn test_field_errors_clear() {
+        let mut errors = FieldErrors::default();
+        errors.set("name", "Name cannot be empty".to_string());
+        errors.clear("name");
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_only_success_notifications_auto_dismiss() {
+        assert!(Notification::success("User created successfully").auto_dismisses());
+        assert!(!Notification::warning("1 of 3 users could not be deleted").auto_dismisses());
+        assert!(!Notification::error("Request failed").auto_dismisses());
+        assert_eq!(Notification::error("Request failed").level, NotificationLevel::Error);
+    }
+
+    #[test]
+    fn test_signup_steps_in_order() {
+        assert_eq!(SignupStep::Account.next(), Some(SignupStep::Password));
+        assert_eq!(SignupStep::Confirm.next(), None);
+        assert_eq!(SignupStep::Account.previous(), None);
+        assert_eq!(SignupStep::Confirm.previous(), Some(SignupStep::Password));
+        assert_eq!(SignupStep::Confirm.index(), 2);
+        assert_eq!(SignupStep::of_field("email"), Some(SignupStep::Account));
+        assert_eq!(SignupStep::of_field("password"), Some(SignupStep::Password));
+        assert_eq!(SignupStep::of_field("captcha_token"), None);
+    }
+
+    #[test]
+    fn test_wizard_validates_each_step() {
+        let mut state = UserFormState::new();
+        assert_eq!(state.step_errors(), vec![("name", "name-empty"), ("email", "email-empty")]);
+        assert!(!state.next_step());
+        assert_eq!(state.step, SignupStep::Account);
+
+        state.name = "John".to_string();
+        state.email = "john@".to_string();
+        assert_eq!(state.step_errors(), vec![("email", "email-invalid")]);
+        state.email = "john@example.com".to_string();
+        assert!(state.next_step());
+        assert_eq!(state.step, SignupStep::Password);
+
+        // Only the password is checked on its step
+        state.password = "12345".to_string();
+        assert_eq!(state.step_errors(), vec![("password", "password-too-short")]);
+        state.password = "123456".to_string();
+        assert!(state.next_step());
+        assert_eq!(state.step, SignupStep::Confirm);
+        assert!(!state.next_step());
+
+        state.previous_step();
+        assert_eq!(state.step, SignupStep::Password);
+        state.reset();
+        assert_eq!(state.step, SignupStep::Account);
+    }
+
+    #[test]
+    fn test_mask_phone_keeps_international_digits() {
+        assert_eq!(mask_phone("+55 (11) 91234-5678"), "+5511912345678");
+        assert_eq!(mask_phone("abc"), "");
+        assert_eq!(mask_phone("+1234567890123456789"), "+123456789012345");
+    }
+
+    #[test]
+    fn test_mask_country() {
+        assert_eq!(mask_country("br"), "BR");
+        assert_eq!(mask_country("u.s.a"), "US");
+    }
+
+    #[test]
+    fn test_contact_fields_are_optional() {
+        let state = UserFormState::with_values(
+            "John".to_string(),
+            "john@example.com".to_string(),
+            "Password123!".to_string(),
+            None,
+        );
+        assert_eq!(state.phone_request(), None);
+        assert_eq!(state.address_request(), None);
+        assert_eq!(state.phone_error_id(), None);
+        assert_eq!(state.address_error_id(), None);
+    }
+
+    #[test]
+    fn test_contact_fields_validation() {
+        let mut state = UserFormState {
+            phone: "+0123".to_string(),
+            ..UserFormState::default()
+        };
+        assert_eq!(state.phone_error_id(), Some("phone-invalid"));
+        state.phone = "+55 11 91234-5678".to_string();
+        assert_eq!(state.phone_error_id(), None);
+        assert_eq!(state.phone_request().as_deref(), Some("+5511912345678"));
+
+        state.address.street = "Av. Paulista, 1000".to_string();
+        assert_eq!(state.address_error_id(), Some("address-city-empty"));
+        state.address.city = "São Paulo".to_string();
+        state.address.country = "BRA".to_string();
+        assert_eq!(state.address_error_id(), Some("address-country-invalid"));
+        state.address.country = "BR".to_string();
+        state.address.complement = Some("  ".to_string());
+        assert_eq!(state.address_error_id(), None);
+        let address = state.address_request().unwrap();
+        assert_eq!(address.complement, None);
+        assert_eq!(address.city, "São Paulo");
+    }
+
+    #[test]
+    fn test_set_contact_and_reset() {
+        let mut state = UserFormState::default();
+        let address = Address {
+            street: "Rua A".to_string(),
+            city: "Recife".to_string(),
+            country: "BR".to_string(),
+            ..Address::default()
+        };
+        state.set_contact(Some("+5581912345678".to_string()), Some(address.clone()));
+        assert_eq!(state.phone, "+5581912345678");
+        assert_eq!(state.address, address);
+        state.reset();
+        assert_eq!(state.phone, "");
+        assert_eq!(state.address, Address::default());
+    }
+
+    #[test]
+    fn test_parse_and_format_tags() {
+        assert_eq!(parse_tags("VIP, beta,, vip "), vec!["beta".to_string(), "vip".to_string()]);
+        assert!(parse_tags(" ").is_empty());
+        assert_eq!(format_tags(&parse_tags("vip,beta")), "beta, vip");
+    }
+
+    #[test]
+    fn test_tags_error_id() {
+        assert_eq!(tags_error_id(""), None);
+        assert_eq!(tags_error_id("vip, early-adopter"), None);
+        assert_eq!(tags_error_id("not valid"), Some("tag-invalid"));
+        assert_eq!(tags_error_id(&"x".repeat(25)), Some("tag-too-long"));
+        let many: Vec<String> = (0..11).map(|i| format!("t{}", i)).collect();
+        assert_eq!(tags_error_id(&many.join(",")), Some("tags-too-many"));
+    }
+
+    #[test]
+    fn test_mask_username() {
+        assert_eq!(mask_username("Ana Silva_2"), "anasilva2");
+        assert_eq!(mask_username("ana-silva"), "ana-silva");
+        assert_eq!(mask_username(&"x".repeat(40)).len(), 30);
+    }
+
+    #[test]
+    fn test_username_is_optional_and_validated() {
+        let mut state = UserFormState::default();
+        assert_eq!(state.username_request(), None);
+        assert_eq!(state.username_error_id(), None);
+
+        state.username = "ab".to_string();
+        assert_eq!(state.username_error_id(), Some("username-too-short"));
+        state.username = "ana-".to_string();
+        assert_eq!(state.username_error_id(), Some("username-invalid"));
+        state.username = "@ana-silva".to_string();
+        assert_eq!(state.username_error_id(), None);
+        assert_eq!(state.username_request().as_deref(), Some("ana-silva"));
+    }
+
+    #[test]
+    fn test_set_username_locks_existing_usernames() {
+        let mut state = UserFormState::default();
+        state.set_username(None);
+        assert!(!state.username_locked);
+        state.set_username(Some("ana".to_string()));
+        assert!(state.username_locked);
+        assert_eq!(state.username, "ana");
+        state.reset();
+        assert!(!state.username_locked);
+        assert_eq!(state.username, "");
+    }
+
+    #[test]
+    fn test_undo_and_redo_edits() {
+        let mut state = UserFormState::default();
+        assert!(!state.undo());
+        state.edit(|state| state.name = "Jane".to_string());
+        state.edit(|state| state.email = "pasted over".to_string());
+        // Edits that change nothing are not recorded
+        state.edit(|state| state.email = "pasted over".to_string());
+
+        assert!(state.undo());
+        assert_eq!(state.email, "");
+        assert_eq!(state.name, "Jane");
+        assert!(state.can_redo());
+        assert!(state.redo());
+        assert_eq!(state.email, "pasted over");
+        assert!(!state.redo());
+
+        assert!(state.undo());
+        assert!(state.undo());
+        assert_eq!(state.values(), FormValues::default());
+        assert!(!state.can_undo());
+
+        // A new edit drops the undone ones
+        assert!(state.redo());
+        state.edit(|state| state.phone = "+55".to_string());
+        assert!(!state.can_redo());
+    }
+
+    #[test]
+    fn test_history_is_capped_and_cleared() {
+        let mut state = UserFormState::default();
+        for length in 1..=MAX_FORM_HISTORY + 10 {
+            state.edit(|state| state.name = "a".repeat(length));
+        }
+        while state.undo() {}
+        // The oldest edits were forgotten
+        assert_eq!(state.name, "a".repeat(10));
+
+        state.edit(|state| state.name = "Jane".to_string());
+        state.set_for_editing(1, "John".to_string(), "john@example.com".to_string(), String::new());
+        assert!(!state.can_undo());
+        state.edit(|state| state.name = "Johnny".to_string());
+        state.reset();
+        assert!(!state.can_undo());
+    }
+
+    #[test]
+    fn test_history_shortcuts() {
+        assert_eq!(HistoryStep::from_key("z", true, false), Some(HistoryStep::Undo));
+        assert_eq!(HistoryStep::from_key("Z", true, true), Some(HistoryStep::Redo));
+        assert_eq!(HistoryStep::from_key("y", true, false), Some(HistoryStep::Redo));
+        assert_eq!(HistoryStep::from_key("z", false, false), None);
+        assert_eq!(HistoryStep::from_key("a", true, false), None);
+    }
 }
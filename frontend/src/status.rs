@@ -0,0 +1,153 @@
+// Service Status Module - Single Responsibility Principle
+// Polls the backend's `GET /status` so users learn the API went read-only for
+// maintenance before their next save fails. MaintenanceBanner shows the
+// operator's message, or a default one, for as long as maintenance lasts.
+// A heartbeat on `GET /healthz` tells whether the backend can be reached at
+// all; ConnectivityDot shows the outcome and changes are queued while it fails
+
+use crate::api::{api_url, ApiResult, SERVER_URL};
+use crate::demo;
+use crate::i18n::use_translation;
+use crate::offline::{self, use_connectivity, Connectivity};
+use gloo::events::EventListener;
+use gloo::net::http::Request;
+use gloo::timers::callback::Interval;
+use serde::Deserialize;
+use shared::routes;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+// Maintenance is switched at runtime, so the status is fetched again regularly
+const STATUS_POLL_MS: u32 = 60_000;
+// Time between two pings of `/healthz`
+const HEARTBEAT_MS: u32 = 15_000;
+
+// Body of `GET /status`
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ServiceStatus {
+    pub maintenance: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+pub fn fetch_service_status(callback: Callback<ApiResult<ServiceStatus>>) {
+    // The demo store is never read-only
+    if demo::ENABLED {
+        callback.emit(Ok(ServiceStatus::default()));
+        return;
+    }
+    let url = api_url(routes::STATUS);
+    spawn_local(async move {
+        match Request::get(&url).send().await {
+            Ok(resp) if resp.ok() => match resp.json::<ServiceStatus>().await {
+                Ok(status) => callback.emit(Ok(status)),
+                Err(_) => callback.emit(Err("Failed to parse service status".to_string())),
+            },
+            Ok(_) => callback.emit(Err("Server returned an error".to_string())),
+            Err(_) => callback.emit(Err("Failed to fetch service status".to_string())),
+        }
+    });
+}
+
+// `/healthz` sits at the root of the backend, outside the versioned API
+pub fn health_url() -> String {
+    format!("{}{}", SERVER_URL, routes::HEALTHZ)
+}
+
+// Ping the backend and report whether it answered
+pub fn send_heartbeat() {
+    // The demo store is always there, and without a connection the browser's
+    // own events already tell that nothing can be reached
+    if demo::ENABLED || !web_sys::window().is_none_or(|window| window.navigator().on_line()) {
+        return;
+    }
+    let url = health_url();
+    spawn_local(async move {
+        let reachable = matches!(Request::get(&url).send().await, Ok(resp) if resp.ok());
+        offline::set_backend_reachable(reachable);
+    });
+}
+
+// Status dot of the navigation bar: green while the backend answers, amber
+// while it cannot be reached and grey while the browser is offline. Runs the
+// heartbeat, and pings right away when the connection returns
+#[function_component(ConnectivityDot)]
+pub fn connectivity_dot() -> Html {
+    let t = use_translation();
+    let connectivity = use_connectivity();
+
+    use_effect_with((), move |_| {
+        send_heartbeat();
+        let poll = Interval::new(HEARTBEAT_MS, send_heartbeat);
+        let on_online = web_sys::window().map(|window| EventListener::new(&window, "online", |_| send_heartbeat()));
+        move || drop((poll, on_online))
+    });
+
+    let color = match connectivity {
+        Connectivity::Online => "bg-green-500",
+        Connectivity::Unreachable => "bg-amber-500",
+        Connectivity::Offline => "bg-gray-400",
+    };
+    let label = t.t(connectivity.label_id());
+    html! {
+        <span role="status" title={label.clone()} class="flex items-center">
+            <span aria-hidden="true" class={classes!("inline-block", "w-2.5", "h-2.5", "rounded-full", color)}></span>
+            <span class="sr-only">{ label }</span>
+        </span>
+    }
+}
+
+#[function_component(MaintenanceBanner)]
+pub fn maintenance_banner() -> Html {
+    let t = use_translation();
+    let status = use_state(ServiceStatus::default);
+
+    {
+        let status = status.clone();
+        use_effect_with((), move |_| {
+            // A failed fetch keeps the last known status
+            let on_status = Callback::from(move |result: ApiResult<ServiceStatus>| {
+                if let Ok(fetched) = result {
+                    status.set(fetched);
+                }
+            });
+            fetch_service_status(on_status.clone());
+            let poll = Interval::new(STATUS_POLL_MS, move || fetch_service_status(on_status.clone()));
+            move || drop(poll)
+        });
+    }
+
+    if !status.maintenance {
+        return html! {};
+    }
+    let message = status
+        .message
+        .clone()
+        .unwrap_or_else(|| t.t("maintenance-banner"));
+    html! {
+        <div role="status" class="mb-4 rounded bg-amber-100 px-4 py-2 text-amber-900 dark:bg-amber-900 dark:text-amber-100">
+            { message }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_status_from_backend_response() {
+        let status: ServiceStatus =
+            serde_json::from_str(r#"{"maintenance":true,"message":"Back at 14:00 UTC"}"#).unwrap();
+        assert!(status.maintenance);
+        assert_eq!(status.message.as_deref(), Some("Back at 14:00 UTC"));
+
+        let status: ServiceStatus = serde_json::from_str(r#"{"maintenance":false}"#).unwrap();
+        assert_eq!(status, ServiceStatus::default());
+    }
+
+    #[test]
+    fn test_health_url() {
+        assert_eq!(health_url(), "http://127.0.0.1:8000/healthz");
+    }
+}
@@ -0,0 +1,209 @@
+// Theme Module - Single Responsibility Principle
+// Switches between light and dark mode. Tailwind's `dark:` variants are
+// enabled by the `dark` class on the root element, which the ThemeProvider
+// sets from the chosen theme; "system" follows the OS preference, live.
+// The choice is kept in localStorage
+
+use crate::i18n::use_translation;
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+const THEME_STORAGE_KEY: &str = "theme";
+const DARK_SCHEME_QUERY: &str = "(prefers-color-scheme: dark)";
+const DARK_CLASS: &str = "dark";
+
+// Theme chosen by the user
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+impl Theme {
+    // Theme selected by the toggle after this one
+    pub fn next(&self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::System,
+            Theme::System => Theme::Light,
+        }
+    }
+
+    // Whether pages render dark, given the OS preference
+    pub fn is_dark(&self, system_prefers_dark: bool) -> bool {
+        match self {
+            Theme::Light => false,
+            Theme::Dark => true,
+            Theme::System => system_prefers_dark,
+        }
+    }
+
+    // Message id of the theme's name
+    pub fn label_id(&self) -> &'static str {
+        match self {
+            Theme::Light => "theme-light",
+            Theme::Dark => "theme-dark",
+            Theme::System => "theme-system",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Theme::Light => "☀",
+            Theme::Dark => "☾",
+            Theme::System => "◐",
+        }
+    }
+
+    // Theme chosen on a previous visit
+    pub fn stored() -> Self {
+        LocalStorage::get(THEME_STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn store(&self) {
+        // Losing the choice only falls back to the system theme
+        let _ = LocalStorage::set(THEME_STORAGE_KEY, self);
+    }
+}
+
+fn dark_scheme_query() -> Option<web_sys::MediaQueryList> {
+    web_sys::window()?.match_media(DARK_SCHEME_QUERY).ok()?
+}
+
+// Add or remove the `dark` class on the root element
+fn apply_dark_class(dark: bool) {
+    let Some(root) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.document_element())
+    else {
+        return;
+    };
+    let classes = root.class_list();
+    let _ = if dark {
+        classes.add_1(DARK_CLASS)
+    } else {
+        classes.remove_1(DARK_CLASS)
+    };
+}
+
+// Shared by ThemeProvider: the chosen theme, whether it renders dark, and a way to change it
+#[derive(Clone, PartialEq)]
+pub struct ThemeContext {
+    pub theme: Theme,
+    pub dark: bool,
+    pub set_theme: Callback<Theme>,
+}
+
+// Props for ThemeProvider component
+#[derive(Properties, PartialEq)]
+pub struct ThemeProviderProps {
+    pub children: Html,
+}
+
+// Provides the theme to every component below it and styles the page with it
+#[function_component(ThemeProvider)]
+pub fn theme_provider(props: &ThemeProviderProps) -> Html {
+    let theme = use_state(Theme::stored);
+    let system_prefers_dark =
+        use_state(|| dark_scheme_query().is_some_and(|query| query.matches()));
+
+    // Track OS preference changes while mounted
+    {
+        let system_prefers_dark = system_prefers_dark.clone();
+        use_effect_with((), move |_| {
+            let listener = dark_scheme_query().map(|query| {
+                let target = query.clone();
+                EventListener::new(&query, "change", move |_| {
+                    system_prefers_dark.set(target.matches());
+                })
+            });
+            move || drop(listener)
+        });
+    }
+
+    let dark = theme.is_dark(*system_prefers_dark);
+    use_effect_with(dark, |dark| apply_dark_class(*dark));
+
+    let set_theme = {
+        let theme = theme.clone();
+        Callback::from(move |chosen: Theme| {
+            chosen.store();
+            theme.set(chosen);
+        })
+    };
+    let context = ThemeContext {
+        theme: *theme,
+        dark,
+        set_theme,
+    };
+
+    html! {
+        <ContextProvider<ThemeContext> context={context}>
+            { props.children.clone() }
+        </ContextProvider<ThemeContext>>
+    }
+}
+
+// Theme context; none outside a ThemeProvider
+#[hook]
+pub fn use_theme() -> Option<ThemeContext> {
+    use_context::<ThemeContext>()
+}
+
+// Button cycling through light, dark and system themes
+#[function_component(ThemeToggle)]
+pub fn theme_toggle() -> Html {
+    let t = use_translation();
+    let Some(context) = use_theme() else {
+        return Html::default();
+    };
+    let onclick = {
+        let set_theme = context.set_theme.clone();
+        let next = context.theme.next();
+        Callback::from(move |_| set_theme.emit(next))
+    };
+    let label = t.t(context.theme.label_id());
+
+    html! {
+        <button
+            type="button"
+            onclick={onclick}
+            title={label.clone()}
+            class="border rounded px-2 py-1 text-gray-700 hover:bg-gray-100 dark:text-gray-200 dark:border-gray-600 dark:hover:bg-gray-700"
+        >
+            { format!("{} {}", context.theme.icon(), label) }
+        </button>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_cycles_through_modes() {
+        assert_eq!(Theme::Light.next(), Theme::Dark);
+        assert_eq!(Theme::Dark.next(), Theme::System);
+        assert_eq!(Theme::System.next(), Theme::Light);
+    }
+
+    #[test]
+    fn test_system_theme_follows_preference() {
+        assert!(!Theme::Light.is_dark(true));
+        assert!(Theme::Dark.is_dark(false));
+        assert!(Theme::System.is_dark(true));
+        assert!(!Theme::System.is_dark(false));
+    }
+
+    #[test]
+    fn test_theme_storage_format() {
+        assert_eq!(serde_json::to_string(&Theme::Dark).unwrap(), "\"dark\"");
+        assert_eq!(serde_json::from_str::<Theme>("\"system\"").unwrap(), Theme::System);
+        assert_eq!(Theme::default(), Theme::System);
+    }
+}
@@ -0,0 +1,208 @@
+// API worker Module - Single Responsibility Principle
+// Fetches and decodes the user list in a dedicated web worker, so parsing
+// thousands of rows never blocks the UI thread. The worker only does the
+// transport: HttpUserApiClient keeps the list cache, the error messages and
+// the reporting, and talks to the worker through one shared bridge
+
+use crate::api::{User, WireFormat};
+use gloo::worker::{Bridge, Bridged, Private, Worker, WorkerLink};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{js_sys, Headers, RequestInit, WorkerGlobalScope};
+use yew::Callback;
+
+// Script of the worker, built by Trunk from src/bin/api_worker.rs
+const WORKER_SCRIPT: &str = "api_worker.js";
+
+// Request sent to the worker
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FetchUsers {
+    // Matches the reply to its callback
+    pub id: u32,
+    pub url: String,
+    pub format: WireFormat,
+    // ETag of the cached list, sent as If-None-Match
+    pub etag: Option<String>,
+}
+
+// Outcome of a user list request
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum UsersResponse {
+    Users { etag: Option<String>, users: Vec<User> },
+    // 304: the cached list is still current
+    NotModified,
+    // Any other non-2xx status
+    Rejected { status: u16 },
+    // The body could not be decoded
+    Malformed,
+    // No answer at all, e.g. offline
+    Unreachable { error: String },
+}
+
+impl UsersResponse {
+    // HTTP status the server answered with, if it answered
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            UsersResponse::Users { .. } | UsersResponse::Malformed => Some(200),
+            UsersResponse::NotModified => Some(304),
+            UsersResponse::Rejected { status } => Some(*status),
+            UsersResponse::Unreachable { .. } => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FetchUsersReply {
+    pub id: u32,
+    pub response: UsersResponse,
+}
+
+pub struct ApiWorker {
+    link: WorkerLink<Self>,
+}
+
+impl Worker for ApiWorker {
+    type Reach = Private<Self>;
+    type Message = ();
+    type Input = FetchUsers;
+    type Output = FetchUsersReply;
+
+    fn create(link: WorkerLink<Self>) -> Self {
+        Self { link }
+    }
+
+    fn update(&mut self, _msg: Self::Message) {}
+
+    fn handle_input(&mut self, request: Self::Input, who: gloo::worker::HandlerId) {
+        let link = self.link.clone();
+        spawn_local(async move {
+            let response = send(&request)
+                .await
+                .unwrap_or_else(|e| UsersResponse::Unreachable { error: format!("{:?}", e) });
+            link.respond(who, FetchUsersReply { id: request.id, response });
+        });
+    }
+
+    fn name_of_resource() -> &'static str {
+        WORKER_SCRIPT
+    }
+}
+
+// Runs inside the worker, where there is no window: fetch goes through the worker scope
+async fn send(request: &FetchUsers) -> Result<UsersResponse, JsValue> {
+    let headers = Headers::new()?;
+    headers.set("Accept", request.format.accept_header())?;
+    if let Some(etag) = &request.etag {
+        headers.set("If-None-Match", etag)?;
+    }
+    let init = RequestInit::new();
+    init.set_method("GET");
+    init.set_headers(&headers);
+    let fetch_request = web_sys::Request::new_with_str_and_init(&request.url, &init)?;
+
+    let scope: WorkerGlobalScope = js_sys::global().unchecked_into();
+    let response: web_sys::Response = JsFuture::from(scope.fetch_with_request(&fetch_request))
+        .await?
+        .unchecked_into();
+    if response.status() == 304 {
+        return Ok(UsersResponse::NotModified);
+    }
+    if !response.ok() {
+        return Ok(UsersResponse::Rejected { status: response.status() });
+    }
+
+    let etag = response.headers().get("ETag")?;
+    let body = JsFuture::from(response.array_buffer()?).await?;
+    let body = js_sys::Uint8Array::new(&body).to_vec();
+    Ok(match request.format.decode_users(&body) {
+        Ok(users) => UsersResponse::Users { etag, users },
+        Err(_) => UsersResponse::Malformed,
+    })
+}
+
+// Main-thread side of the worker, spawned on first use
+struct ApiWorkerBridge {
+    bridge: Box<dyn Bridge<ApiWorker>>,
+    pending: Rc<RefCell<HashMap<u32, Callback<UsersResponse>>>>,
+    next_id: u32,
+}
+
+impl ApiWorkerBridge {
+    fn spawn() -> Self {
+        let pending: Rc<RefCell<HashMap<u32, Callback<UsersResponse>>>> = Rc::default();
+        let replies = pending.clone();
+        let bridge = ApiWorker::bridge(Rc::new(move |reply: FetchUsersReply| {
+            // Released before emitting, since the callback may start another request
+            let callback = replies.borrow_mut().remove(&reply.id);
+            if let Some(callback) = callback {
+                callback.emit(reply.response);
+            }
+        }));
+        Self {
+            bridge,
+            pending,
+            next_id: 0,
+        }
+    }
+}
+
+thread_local! {
+    static BRIDGE: RefCell<Option<ApiWorkerBridge>> = const { RefCell::new(None) };
+}
+
+// Fetch the user list in the worker; `callback` runs on the UI thread
+pub fn fetch_users(url: String, format: WireFormat, etag: Option<String>, callback: Callback<UsersResponse>) {
+    BRIDGE.with(|bridge| {
+        let mut bridge = bridge.borrow_mut();
+        let worker = bridge.get_or_insert_with(ApiWorkerBridge::spawn);
+        worker.next_id = worker.next_id.wrapping_add(1);
+        let id = worker.next_id;
+        worker.pending.borrow_mut().insert(id, callback);
+        worker.bridge.send(FetchUsers { id, url, format, etag });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::UserStatus;
+
+    #[test]
+    fn test_messages_survive_the_worker_boundary() {
+        // gloo-worker passes messages as bincode
+        let reply = FetchUsersReply {
+            id: 3,
+            response: UsersResponse::Users {
+                etag: Some("W/\"1-abc\"".to_string()),
+                users: vec![User {
+                    id: 1,
+                    name: "Jane".to_string(),
+                    email: "jane@example.com".to_string(),
+                    username: None,
+                    status: UserStatus::Active,
+                    tags: Vec::new(),
+                    last_login_at: None,
+                    created_at: None,
+                    updated_at: None,
+                    phone: None,
+                    address: None,
+                }],
+            },
+        };
+        let bytes = bincode::serialize(&reply).unwrap();
+        assert_eq!(bincode::deserialize::<FetchUsersReply>(&bytes).unwrap(), reply);
+
+        let request = FetchUsers {
+            id: 4,
+            url: "http://127.0.0.1:8000/api/v1/users".to_string(),
+            format: WireFormat::MessagePack,
+            etag: None,
+        };
+        let bytes = bincode::serialize(&request).unwrap();
+        assert_eq!(bincode::deserialize::<FetchUsers>(&bytes).unwrap(), request);
+    }
+}
@@ -0,0 +1,78 @@
+// Service worker - offline cache
+// Serves the app shell (index.html, the WASM bundle and its JS glue) from the
+// cache when the network is unavailable, and keeps the last user list
+// returned by the API for offline viewing. Registered by the Rust entry
+// point (offline.rs); bump CACHE_VERSION to drop caches of older releases
+
+const CACHE_VERSION = "v1";
+const SHELL_CACHE = `shell-${CACHE_VERSION}`;
+const API_CACHE = `api-${CACHE_VERSION}`;
+const SHELL_URLS = ["/", "/index.html", "/manifest.webmanifest", "/icon.svg"];
+// Only the user list is kept; other API responses always need the network
+const CACHED_API_PATH = /\/api\/v1\/users$/;
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(caches.open(SHELL_CACHE).then((cache) => cache.addAll(SHELL_URLS)));
+  self.skipWaiting();
+});
+
+self.addEventListener("activate", (event) => {
+  const current = [SHELL_CACHE, API_CACHE];
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) => Promise.all(keys.filter((key) => !current.includes(key)).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim())
+  );
+});
+
+// Network first, falling back to the cached copy
+async function networkFirst(request, cacheName) {
+  const cache = await caches.open(cacheName);
+  try {
+    const response = await fetch(request);
+    if (response.ok) {
+      await cache.put(request, response.clone());
+    }
+    return response;
+  } catch (error) {
+    const cached = await cache.match(request, { ignoreVary: true });
+    if (cached) {
+      return cached;
+    }
+    throw error;
+  }
+}
+
+// Cache first; hashed bundle files never change once built
+async function cacheFirst(request) {
+  const cached = await caches.match(request);
+  if (cached) {
+    return cached;
+  }
+  const response = await fetch(request);
+  if (response.ok || response.type === "opaque") {
+    const cache = await caches.open(SHELL_CACHE);
+    await cache.put(request, response.clone());
+  }
+  return response;
+}
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  if (request.method !== "GET") {
+    return;
+  }
+  const url = new URL(request.url);
+
+  if (CACHED_API_PATH.test(url.pathname)) {
+    // Conditional requests would be answered with an empty 304 offline
+    const plain = new Request(request.url, { headers: { Accept: request.headers.get("Accept") || "application/json" } });
+    event.respondWith(networkFirst(plain, API_CACHE));
+  } else if (request.mode === "navigate") {
+    // Every route is rendered by the same index.html
+    event.respondWith(networkFirst(request, SHELL_CACHE).catch(() => caches.match("/index.html")));
+  } else if (url.origin === self.location.origin || url.hostname === "cdn.tailwindcss.com") {
+    event.respondWith(cacheFirst(request));
+  }
+});
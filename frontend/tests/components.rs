@@ -0,0 +1,264 @@
+// Component tests for the frontend application
+// Mount components in a headless browser, fire DOM events at them and check
+// the callbacks they emit and the markup they render. Run with
+// `wasm-pack test --headless --firefox` (or --chrome); natively this file is empty
+
+#![cfg(target_arch = "wasm32")]
+
+use frontend::api::{User, UserStatus};
+use frontend::components::{UserForm, UserFormProps, UserList, UserListProps};
+use frontend::router::{switch, Route};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{Element, Event, EventInit, HtmlElement, HtmlInputElement};
+use yew::prelude::*;
+use yew::AppHandle;
+use yew_router::history::{AnyHistory, MemoryHistory};
+use yew_router::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// Fresh element under <body> for one test
+fn test_root() -> Element {
+    let document = gloo::utils::document();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+    root
+}
+
+fn mount<C: BaseComponent>(props: C::Properties) -> (Element, AppHandle<C>) {
+    let root = test_root();
+    let handle = yew::Renderer::<C>::with_root_and_props(root.clone(), props).render();
+    (root, handle)
+}
+
+// Let yew run the pending renders and effects
+async fn settle() {
+    yew::platform::time::sleep(Duration::from_millis(10)).await;
+}
+
+fn find(root: &Element, selector: &str) -> Element {
+    root.query_selector(selector)
+        .unwrap()
+        .unwrap_or_else(|| panic!("no element matches {}", selector))
+}
+
+// Set the value of an input and fire `input`, as typing does
+fn type_into(root: &Element, selector: &str, value: &str) {
+    let input: HtmlInputElement = find(root, selector).unchecked_into();
+    input.set_value(value);
+    // Yew listens on the root, so the event has to bubble
+    let init = EventInit::new();
+    init.set_bubbles(true);
+    let event = Event::new_with_event_init_dict("input", &init).unwrap();
+    input.dispatch_event(&event).unwrap();
+}
+
+fn click(root: &Element, selector: &str) {
+    find(root, selector).unchecked_into::<HtmlElement>().click();
+}
+
+// Button whose text is `label`
+fn click_button(root: &Element, label: &str) {
+    let buttons = root.query_selector_all("button").unwrap();
+    let button = (0..buttons.length())
+        .filter_map(|index| buttons.get(index))
+        .map(|node| node.unchecked_into::<HtmlElement>())
+        .find(|button| button.text_content().unwrap_or_default().trim() == label)
+        .unwrap_or_else(|| panic!("no button {}", label));
+    button.click();
+}
+
+fn text(root: &Element) -> String {
+    root.text_content().unwrap_or_default()
+}
+
+// Callback recording every value it receives
+fn recorder<T: 'static>() -> (Callback<T>, Rc<RefCell<Vec<T>>>) {
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let sink = received.clone();
+    (Callback::from(move |value| sink.borrow_mut().push(value)), received)
+}
+
+fn user(id: i32, name: &str, email: &str) -> User {
+    User {
+        id,
+        name: name.to_string(),
+        email: email.to_string(),
+        username: None,
+        status: UserStatus::Active,
+        tags: Vec::new(),
+        last_login_at: None,
+        created_at: None,
+        updated_at: None,
+        phone: None,
+        address: None,
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_user_form_emits_input_and_submit() {
+    let (on_name_change, names) = recorder();
+    let (on_email_change, emails) = recorder();
+    let (on_submit, submits) = recorder();
+    let (root, _handle) = mount::<UserForm>(UserFormProps {
+        name: String::new(),
+        email: String::new(),
+        password: String::new(),
+        is_editing: false,
+        on_name_change,
+        on_email_change,
+        on_password_change: Callback::noop(),
+        on_submit,
+        notification: None,
+        on_dismiss: Callback::noop(),
+        errors: Default::default(),
+        submit_label: None,
+    });
+    settle().await;
+
+    type_into(&root, "#user-name", "Jane Doe");
+    type_into(&root, "#user-email", "jane@example.com");
+    click_button(&root, "Create User");
+    settle().await;
+
+    assert_eq!(*names.borrow(), vec!["Jane Doe".to_string()]);
+    assert_eq!(*emails.borrow(), vec!["jane@example.com".to_string()]);
+    assert_eq!(submits.borrow().len(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn test_user_form_shows_field_errors() {
+    let (root, _handle) = mount::<UserForm>(UserFormProps {
+        name: "Jane".to_string(),
+        email: "not-an-email".to_string(),
+        password: String::new(),
+        is_editing: true,
+        on_name_change: Callback::noop(),
+        on_email_change: Callback::noop(),
+        on_password_change: Callback::noop(),
+        on_submit: Callback::noop(),
+        notification: None,
+        on_dismiss: Callback::noop(),
+        errors: Default::default(),
+        submit_label: None,
+    });
+    settle().await;
+
+    let email = find(&root, "#user-email");
+    assert_eq!(email.get_attribute("aria-invalid").as_deref(), Some("true"));
+    assert!(text(&root).contains("Invalid email format"));
+    assert!(text(&root).contains("Update User"));
+}
+
+#[wasm_bindgen_test]
+async fn test_user_list_renders_rows_and_emits_actions() {
+    let (on_delete, deleted) = recorder();
+    let (on_edit, edited) = recorder();
+    let (on_set_status, status_changes) = recorder();
+    let (root, _handle) = mount::<UserList>(UserListProps {
+        users: vec![user(1, "Jane Doe", "jane@example.com"), user(2, "John Roe", "john@example.com")],
+        on_delete,
+        on_edit,
+        on_set_status,
+        selected: vec![],
+        on_toggle_select: None,
+    });
+    settle().await;
+
+    assert!(text(&root).contains("User List"));
+    assert!(text(&root).contains("jane@example.com"));
+    assert!(text(&root).contains("John Roe"));
+
+    click(&root, "button[aria-label='Delete John Roe']");
+    click(&root, "button[aria-label='Edit Jane Doe']");
+    click(&root, "button[aria-label='Suspend Jane Doe']");
+    settle().await;
+
+    assert_eq!(*deleted.borrow(), vec![2]);
+    assert_eq!(*edited.borrow(), vec![1]);
+    assert_eq!(*status_changes.borrow(), vec![(1, UserStatus::Suspended)]);
+}
+
+#[wasm_bindgen_test]
+async fn test_user_list_selection() {
+    let (on_toggle_select, toggled) = recorder();
+    let (root, _handle) = mount::<UserList>(UserListProps {
+        users: vec![user(1, "Jane Doe", "jane@example.com")],
+        on_delete: Callback::noop(),
+        on_edit: Callback::noop(),
+        on_set_status: Callback::noop(),
+        selected: vec![1],
+        on_toggle_select: Some(on_toggle_select),
+    });
+    settle().await;
+
+    let checkbox: HtmlInputElement = find(&root, "input[aria-label='Select Jane Doe']").unchecked_into();
+    assert!(checkbox.checked());
+    checkbox.click();
+    settle().await;
+    assert_eq!(*toggled.borrow(), vec![1]);
+}
+
+#[derive(Properties, PartialEq)]
+struct RoutedProps {
+    path: &'static str,
+}
+
+// The app's routes, starting at `path` without touching the address bar
+#[function_component(Routed)]
+fn routed(props: &RoutedProps) -> Html {
+    let history = use_memo(props.path, |path| AnyHistory::from(MemoryHistory::with_entries(vec![*path])));
+    html! {
+        <Router history={(*history).clone()}>
+            <Switch<Route> render={switch} />
+        </Router>
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_unknown_route_shows_not_found_page() {
+    let (root, _handle) = mount::<Routed>(RoutedProps { path: "/no-such-page" });
+    settle().await;
+    assert!(text(&root).contains("Page not found"));
+}
+
+#[wasm_bindgen_test]
+async fn test_users_page_shows_form() {
+    let (root, _handle) = mount::<Routed>(RoutedProps { path: "/" });
+    settle().await;
+    // No backend runs during the tests; the page renders whatever the fetch gives
+    assert!(text(&root).contains("User Management"));
+    root.query_selector("#user-name").unwrap().expect("user form");
+}
+
+#[wasm_bindgen_test]
+async fn test_signup_wizard_steps() {
+    let (root, _handle) = mount::<Routed>(RoutedProps { path: "/signup" });
+    settle().await;
+    assert!(text(&root).contains("Account details"));
+
+    // The first step cannot be left with empty fields
+    click_button(&root, "Next");
+    settle().await;
+    assert!(text(&root).contains("Name cannot be empty"));
+    assert!(root.query_selector("#user-password").unwrap().is_none());
+
+    type_into(&root, "#user-name", "Jane Doe");
+    settle().await;
+    type_into(&root, "#user-email", "jane@example.com");
+    settle().await;
+    click_button(&root, "Next");
+    settle().await;
+    root.query_selector("#user-password").unwrap().expect("password step");
+    assert!(!text(&root).contains("Name cannot be empty"));
+
+    // Going back keeps what was typed
+    click_button(&root, "Back");
+    settle().await;
+    let name: HtmlInputElement = find(&root, "#user-name").unchecked_into();
+    assert_eq!(name.value(), "Jane Doe");
+}
@@ -3,7 +3,7 @@
 
 #[cfg(test)]
 mod integration_tests {
-    use frontend::api::{HttpUserApiClient, User};
+    use frontend::api::{HttpUserApiClient, User, UserStatus};
     use frontend::service::DefaultUserService;
     use frontend::state::UserFormState;
 
@@ -13,6 +13,14 @@ mod integration_tests {
             id: 1,
             name: "Test User".to_string(),
             email: "test@example.com".to_string(),
+            username: None,
+            status: UserStatus::Active,
+            tags: Vec::new(),
+            last_login_at: None,
+            created_at: None,
+            updated_at: None,
+            phone: None,
+            address: None,
         };
 
         assert_eq!(user.id, 1);
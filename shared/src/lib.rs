@@ -0,0 +1,15 @@
+// Shared crate - code used by both the backend and the frontend
+// Keeping these rules in one place guarantees both sides agree on them
+
+pub mod password;
+pub mod routes;
+pub mod validation;
+
+pub use password::{
+    generate_password, password_strength, validate_password, PasswordError, PasswordStrength,
+};
+pub use validation::{
+    normalize_email, normalize_phone, normalize_tag, normalize_tags, normalize_username, validate_address,
+    validate_email, validate_phone, validate_tag, validate_tags, validate_username, AddressError,
+    EmailError, PhoneError, TagError, UsernameError,
+};
@@ -0,0 +1,160 @@
+// Password Policy Module - Single Responsibility Principle
+// The backend enforces `validate_password`; the frontend uses the same rules
+// to drive the strength meter so users see the policy before submitting
+
+use std::fmt;
+
+pub const MIN_PASSWORD_LENGTH: usize = 6;
+pub const GENERATED_PASSWORD_LENGTH: usize = 16;
+
+const LOWERCASE: &str = "abcdefghijkmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHJKLMNPQRSTUVWXYZ";
+const DIGITS: &str = "23456789";
+const SYMBOLS: &str = "!@#$%^&*-_=+?";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordError {
+    Empty,
+    TooShort,
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordError::Empty => write!(f, "Password cannot be empty"),
+            PasswordError::TooShort => write!(
+                f,
+                "Password must be at least {} characters",
+                MIN_PASSWORD_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
+/// Validate a password against the minimum policy enforced by the backend
+pub fn validate_password(password: &str) -> Result<(), PasswordError> {
+    if password.trim().is_empty() {
+        return Err(PasswordError::Empty);
+    }
+    if password.len() < MIN_PASSWORD_LENGTH {
+        return Err(PasswordError::TooShort);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PasswordStrength {
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl PasswordStrength {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PasswordStrength::Weak => "Weak",
+            PasswordStrength::Fair => "Fair",
+            PasswordStrength::Strong => "Strong",
+        }
+    }
+}
+
+/// Estimate the strength of a password
+///
+/// Passwords rejected by `validate_password` are always weak. Otherwise one
+/// point is given per character class used (lowercase, uppercase, digit,
+/// symbol) plus one for 10+ and one for 14+ characters.
+pub fn password_strength(password: &str) -> PasswordStrength {
+    if validate_password(password).is_err() {
+        return PasswordStrength::Weak;
+    }
+
+    let classes = [
+        password.chars().any(|c| c.is_ascii_lowercase()),
+        password.chars().any(|c| c.is_ascii_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_ascii_alphanumeric()),
+    ];
+    let length = password.chars().count();
+    let score = classes.iter().filter(|used| **used).count()
+        + usize::from(length >= 10)
+        + usize::from(length >= 14);
+
+    match score {
+        0..=2 => PasswordStrength::Weak,
+        3..=4 => PasswordStrength::Fair,
+        _ => PasswordStrength::Strong,
+    }
+}
+
+/// Build a strong password from a buffer of random bytes
+///
+/// The caller provides the randomness (e.g. `crypto.getRandomValues` in the
+/// browser) so this stays deterministic and testable. The result always has
+/// `GENERATED_PASSWORD_LENGTH` characters and contains every character class.
+pub fn generate_password(random_bytes: &[u8; GENERATED_PASSWORD_LENGTH]) -> String {
+    let classes = [LOWERCASE, UPPERCASE, DIGITS, SYMBOLS];
+    let all: String = classes.concat();
+
+    random_bytes
+        .iter()
+        .enumerate()
+        .map(|(index, byte)| {
+            // The first characters guarantee one of each class, the rest use the full set
+            let charset = classes.get(index).copied().unwrap_or(&all);
+            let chars = charset.as_bytes();
+            chars[*byte as usize % chars.len()] as char
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_password() {
+        assert_eq!(validate_password(""), Err(PasswordError::Empty));
+        assert_eq!(validate_password("    "), Err(PasswordError::Empty));
+        assert_eq!(validate_password("12345"), Err(PasswordError::TooShort));
+        assert_eq!(validate_password("123456"), Ok(()));
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(PasswordError::Empty.to_string(), "Password cannot be empty");
+        assert_eq!(
+            PasswordError::TooShort.to_string(),
+            "Password must be at least 6 characters"
+        );
+    }
+
+    #[test]
+    fn test_password_strength() {
+        assert_eq!(password_strength(""), PasswordStrength::Weak);
+        assert_eq!(password_strength("abc"), PasswordStrength::Weak);
+        assert_eq!(password_strength("abcdef"), PasswordStrength::Weak);
+        assert_eq!(password_strength("password123"), PasswordStrength::Fair);
+        assert_eq!(password_strength("Password123"), PasswordStrength::Fair);
+        assert_eq!(password_strength("Tr0ub4dor&3x"), PasswordStrength::Strong);
+    }
+
+    #[test]
+    fn test_strength_ordering() {
+        assert!(PasswordStrength::Weak < PasswordStrength::Fair);
+        assert!(PasswordStrength::Fair < PasswordStrength::Strong);
+    }
+
+    #[test]
+    fn test_generated_password_is_strong() {
+        for seed in [0u8, 7, 128, 255] {
+            let bytes = [seed; GENERATED_PASSWORD_LENGTH];
+            let password = generate_password(&bytes);
+            assert_eq!(password.len(), GENERATED_PASSWORD_LENGTH);
+            assert_eq!(validate_password(&password), Ok(()));
+            assert_eq!(password_strength(&password), PasswordStrength::Strong);
+        }
+    }
+}
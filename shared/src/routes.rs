@@ -0,0 +1,71 @@
+// Routes Module - Single Responsibility Principle
+// Paths of the backend's endpoints, built in one place so the URLs the
+// frontend requests and the prefixes the backend mounts cannot drift apart.
+// API paths are relative to the version prefix, e.g. `API_V1_PREFIX` followed
+// by `users::by_id(7)` is `/api/v1/users/7`
+
+pub const API_V1_PREFIX: &str = "/api/v1";
+// Deprecated unversioned alias of v1
+pub const LEGACY_API_PREFIX: &str = "/api";
+// Liveness check, at the root of the server rather than below a prefix
+pub const HEALTHZ: &str = "/healthz";
+
+pub const SIGNUP: &str = "/signup";
+pub const SIGNUP_VERIFY: &str = "/signup/verify";
+pub const CAPTCHA: &str = "/captcha";
+pub const CLIENT_ERRORS: &str = "/client-errors";
+pub const USERNAMES_AVAILABLE: &str = "/usernames/available";
+pub const STATS: &str = "/stats";
+pub const EVENTS: &str = "/events";
+pub const FLAGS: &str = "/flags";
+pub const STATUS: &str = "/status";
+
+pub mod users {
+    // List and create
+    pub const ALL: &str = "/users";
+    pub const EXISTS: &str = "/users/exists";
+    pub const EXPORT: &str = "/users/export";
+    pub const BULK: &str = "/users/bulk";
+
+    pub fn by_id(id: i32) -> String {
+        format!("/users/{}", id)
+    }
+
+    // `username` goes into the path as given, so it must be percent-encoded
+    pub fn by_username(username: &str) -> String {
+        format!("/users/@{}", username)
+    }
+
+    pub fn activity(id: i32) -> String {
+        format!("/users/{}/activity", id)
+    }
+
+    pub fn preferences(id: i32) -> String {
+        format!("/users/{}/preferences", id)
+    }
+
+    pub fn tags(id: i32) -> String {
+        format!("/users/{}/tags", id)
+    }
+
+    pub fn suspend(id: i32) -> String {
+        format!("/users/{}/suspend", id)
+    }
+
+    pub fn activate(id: i32) -> String {
+        format!("/users/{}/activate", id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_paths() {
+        assert_eq!(users::by_id(7), "/users/7");
+        assert_eq!(users::by_username("ada"), "/users/@ada");
+        assert_eq!(users::activity(7), "/users/7/activity");
+        assert_eq!(format!("{}{}", API_V1_PREFIX, users::suspend(7)), "/api/v1/users/7/suspend");
+    }
+}
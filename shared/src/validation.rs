@@ -0,0 +1,566 @@
+// Validation Module - Single Responsibility Principle
+// Email, username, tag, phone and address rules shared by `User::validate` (backend) and
+// `UserFormState` (frontend)
+
+use std::fmt;
+
+const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_LOCAL_PART_LENGTH: usize = 64;
+const MAX_DOMAIN_LABEL_LENGTH: usize = 63;
+
+// Special characters allowed in the local part of an address (RFC 5322 `atext`)
+const LOCAL_PART_SPECIAL_CHARS: &str = "!#$%&'*+/=?^_`{|}~-";
+
+// E.164 numbers have at most 15 digits, country code included; shorter
+// than 7 digits nothing is dialable anywhere
+const MIN_PHONE_DIGITS: usize = 7;
+const MAX_PHONE_DIGITS: usize = 15;
+// Separators people type in phone numbers, dropped by `normalize_phone`
+const PHONE_SEPARATORS: &str = " -().";
+
+pub const MIN_USERNAME_LENGTH: usize = 3;
+pub const MAX_USERNAME_LENGTH: usize = 30;
+
+pub const MAX_TAG_LENGTH: usize = 24;
+pub const MAX_TAGS: usize = 10;
+
+pub const MAX_ADDRESS_FIELD_LENGTH: usize = 200;
+const MAX_POSTAL_CODE_LENGTH: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailError {
+    Empty,
+    InvalidFormat,
+}
+
+impl fmt::Display for EmailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmailError::Empty => write!(f, "Email cannot be empty"),
+            EmailError::InvalidFormat => write!(f, "Invalid email format"),
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsernameError {
+    TooShort,
+    TooLong,
+    InvalidFormat,
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsernameError::TooShort => write!(
+                f,
+                "Username must be at least {} characters",
+                MIN_USERNAME_LENGTH
+            ),
+            UsernameError::TooLong => write!(
+                f,
+                "Username must be at most {} characters",
+                MAX_USERNAME_LENGTH
+            ),
+            UsernameError::InvalidFormat => write!(
+                f,
+                "Username may only contain lowercase letters, digits and single dashes between them"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UsernameError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagError {
+    Empty,
+    TooLong,
+    InvalidFormat,
+    TooMany,
+}
+
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagError::Empty => write!(f, "Tag cannot be empty"),
+            TagError::TooLong => write!(f, "Tags must be at most {} characters", MAX_TAG_LENGTH),
+            TagError::InvalidFormat => write!(
+                f,
+                "Tags may only contain lowercase letters, digits and single dashes between them"
+            ),
+            TagError::TooMany => write!(f, "A user can have at most {} tags", MAX_TAGS),
+        }
+    }
+}
+
+impl std::error::Error for TagError {}
+
+// Lowercase ASCII letters and digits, optionally joined by single dashes
+fn is_slug(value: &str) -> bool {
+    value
+        .split('-')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneError {
+    InvalidFormat,
+}
+
+impl fmt::Display for PhoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhoneError::InvalidFormat => {
+                write!(f, "Phone number must be in international format, e.g. +5511912345678")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PhoneError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressError {
+    StreetEmpty,
+    CityEmpty,
+    InvalidPostalCode,
+    InvalidCountry,
+    TooLong,
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressError::StreetEmpty => write!(f, "Street cannot be empty"),
+            AddressError::CityEmpty => write!(f, "City cannot be empty"),
+            AddressError::InvalidPostalCode => write!(f, "Invalid postal code"),
+            AddressError::InvalidCountry => {
+                write!(f, "Country must be a two-letter ISO 3166 code, e.g. BR")
+            }
+            AddressError::TooLong => write!(
+                f,
+                "Address fields must be at most {} characters",
+                MAX_ADDRESS_FIELD_LENGTH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+/// Normalize an email for storage and comparison: trimmed and lowercased.
+/// Only ASCII letters are lowercased: valid addresses are ASCII, and full
+/// Unicode lowercasing would turn e.g. the Kelvin sign into `k`, so an
+/// address rejected as typed would become valid once normalized
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_ascii_lowercase()
+}
+
+/// Validate an email address
+///
+/// Accepts the dot-atom form of RFC 5322 (`local@domain.tld`), which covers
+/// real-world addresses while rejecting inputs like `a@b@c` or `@@`.
+/// Quoted local parts and IP-literal domains are intentionally not supported.
+pub fn validate_email(email: &str) -> Result<(), EmailError> {
+    let email = email.trim();
+    if email.is_empty() {
+        return Err(EmailError::Empty);
+    }
+    if email.len() > MAX_EMAIL_LENGTH {
+        return Err(EmailError::InvalidFormat);
+    }
+
+    let (local, domain) = email.split_once('@').ok_or(EmailError::InvalidFormat)?;
+    if is_valid_local_part(local) && is_valid_domain(domain) {
+        Ok(())
+    } else {
+        Err(EmailError::InvalidFormat)
+    }
+}
+
+/// Normalize a username for storage and lookup: trimmed, lowercased and
+/// without the `@` of profile URLs, so `@Ana-Silva` becomes `ana-silva`
+pub fn normalize_username(username: &str) -> String {
+    let username = username.trim();
+    username
+        .strip_prefix('@')
+        .unwrap_or(username)
+        .to_ascii_lowercase()
+}
+
+/// Validate a username: 3 to 30 lowercase ASCII letters, digits and dashes,
+/// where a dash can neither start or end it nor follow another dash. Usernames
+/// appear in URLs, so anything else is rejected rather than escaped
+pub fn validate_username(username: &str) -> Result<(), UsernameError> {
+    let length = username.chars().count();
+    if length < MIN_USERNAME_LENGTH {
+        return Err(UsernameError::TooShort);
+    }
+    if length > MAX_USERNAME_LENGTH {
+        return Err(UsernameError::TooLong);
+    }
+    if is_slug(username) {
+        Ok(())
+    } else {
+        Err(UsernameError::InvalidFormat)
+    }
+}
+
+/// Normalize a tag for storage and filtering: trimmed and lowercased
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_ascii_lowercase()
+}
+
+/// Normalize tags for storage: each like `normalize_tag`, blanks dropped, sorted
+/// and without duplicates, so `["VIP", " beta", "vip"]` becomes `["beta", "vip"]`
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|tag| normalize_tag(tag))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Validate one tag: up to 24 lowercase ASCII letters, digits and single
+/// dashes between them, like usernames, since tags appear in filter URLs
+pub fn validate_tag(tag: &str) -> Result<(), TagError> {
+    if tag.is_empty() {
+        return Err(TagError::Empty);
+    }
+    if tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(TagError::TooLong);
+    }
+    if is_slug(tag) {
+        Ok(())
+    } else {
+        Err(TagError::InvalidFormat)
+    }
+}
+
+/// Validate the tags of a user: at most 10, each valid
+pub fn validate_tags(tags: &[String]) -> Result<(), TagError> {
+    if tags.len() > MAX_TAGS {
+        return Err(TagError::TooMany);
+    }
+    tags.iter().try_for_each(|tag| validate_tag(tag))
+}
+
+/// Normalize a phone number for storage: separators such as spaces, dashes,
+/// dots and parentheses are dropped, so `+55 (11) 91234-5678` becomes
+/// `+5511912345678`
+pub fn normalize_phone(phone: &str) -> String {
+    phone
+        .trim()
+        .chars()
+        .filter(|c| !PHONE_SEPARATORS.contains(*c))
+        .collect()
+}
+
+/// Validate a phone number in E.164 form: `+`, a country code not starting
+/// with 0 and at most 15 digits in all. Separators are accepted, as they are
+/// dropped by `normalize_phone`
+pub fn validate_phone(phone: &str) -> Result<(), PhoneError> {
+    let phone = normalize_phone(phone);
+    let digits = phone.strip_prefix('+').ok_or(PhoneError::InvalidFormat)?;
+    let valid = (MIN_PHONE_DIGITS..=MAX_PHONE_DIGITS).contains(&digits.len())
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && !digits.starts_with('0');
+    if valid {
+        Ok(())
+    } else {
+        Err(PhoneError::InvalidFormat)
+    }
+}
+
+/// Validate the parts of a postal address. Complement and region are
+/// optional and the postal code may be empty, as some countries have none;
+/// the country is an ISO 3166-1 alpha-2 code
+pub fn validate_address(
+    street: &str,
+    complement: Option<&str>,
+    city: &str,
+    region: Option<&str>,
+    postal_code: &str,
+    country: &str,
+) -> Result<(), AddressError> {
+    if street.trim().is_empty() {
+        return Err(AddressError::StreetEmpty);
+    }
+    if city.trim().is_empty() {
+        return Err(AddressError::CityEmpty);
+    }
+    if [Some(street), complement, Some(city), region]
+        .into_iter()
+        .flatten()
+        .any(|field| field.trim().chars().count() > MAX_ADDRESS_FIELD_LENGTH)
+    {
+        return Err(AddressError::TooLong);
+    }
+
+    let postal_code = postal_code.trim();
+    let valid_postal_code = postal_code.len() <= MAX_POSTAL_CODE_LENGTH
+        && postal_code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-');
+    if !valid_postal_code {
+        return Err(AddressError::InvalidPostalCode);
+    }
+
+    let country = country.trim();
+    if country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(AddressError::InvalidCountry);
+    }
+    Ok(())
+}
+
+fn is_valid_local_part(local: &str) -> bool {
+    !local.is_empty()
+        && local.len() <= MAX_LOCAL_PART_LENGTH
+        && local
+            .split('.')
+            .all(|atom| !atom.is_empty() && atom.chars().all(is_local_part_char))
+}
+
+fn is_local_part_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || LOCAL_PART_SPECIAL_CHARS.contains(c)
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 || !labels.iter().all(|label| is_valid_domain_label(label)) {
+        return false;
+    }
+
+    // The top-level domain must not be purely numeric (e.g. `user@1.2`)
+    labels
+        .last()
+        .is_some_and(|tld| tld.chars().any(|c| c.is_ascii_alphabetic()))
+}
+
+fn is_valid_domain_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= MAX_DOMAIN_LABEL_LENGTH
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_emails() {
+        for email in [
+            "john@example.com",
+            "john.doe@example.com",
+            "john+tag@mail.example.co.uk",
+            "o'brien@example.ie",
+            "user_name-1@sub-domain.example.org",
+            "  padded@example.com  ",
+        ] {
+            assert_eq!(validate_email(email), Ok(()), "{} should be valid", email);
+        }
+    }
+
+    #[test]
+    fn test_empty_email() {
+        assert_eq!(validate_email(""), Err(EmailError::Empty));
+        assert_eq!(validate_email("   "), Err(EmailError::Empty));
+    }
+
+    #[test]
+    fn test_invalid_emails() {
+        for email in [
+            "invalid",
+            "a@b",
+            "a@b@c",
+            "@@",
+            "@example.com",
+            "john@",
+            "john@.com",
+            "john@example.",
+            "john@example..com",
+            ".john@example.com",
+            "john.@example.com",
+            "jo..hn@example.com",
+            "john doe@example.com",
+            "john@exa mple.com",
+            "john@-example.com",
+            "john@example-.com",
+            "john@1.2",
+        ] {
+            assert_eq!(
+                validate_email(email),
+                Err(EmailError::InvalidFormat),
+                "{} should be invalid",
+                email
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_long_email() {
+        let local = "a".repeat(65);
+        assert!(validate_email(&format!("{}@example.com", local)).is_err());
+
+        let domain = format!("{}.com", "a".repeat(250));
+        assert!(validate_email(&format!("john@{}", domain)).is_err());
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(EmailError::Empty.to_string(), "Email cannot be empty");
+        assert_eq!(EmailError::InvalidFormat.to_string(), "Invalid email format");
+    }
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(normalize_email("  John.Doe@Example.COM "), "john.doe@example.com");
+        assert_eq!(normalize_email("already@lower.com"), "already@lower.com");
+    }
+
+    #[test]
+    fn test_normalize_email_keeps_the_verdict() {
+        // U+212A KELVIN SIGN lowercases to an ASCII `k`
+        let kelvin = "\u{212A}im@example.com";
+        assert_eq!(validate_email(kelvin), Err(EmailError::InvalidFormat));
+        assert_eq!(validate_email(&normalize_email(kelvin)), Err(EmailError::InvalidFormat));
+    }
+
+    #[test]
+    fn test_valid_phones() {
+        for phone in [
+            "+5511912345678",
+            "+14155552671",
+            "+44 20 7946 0958",
+            "+55 (11) 91234-5678",
+            " +351.912.345.678 ",
+        ] {
+            assert_eq!(validate_phone(phone), Ok(()), "{} should be valid", phone);
+        }
+    }
+
+    #[test]
+    fn test_invalid_phones() {
+        for phone in [
+            "",
+            "5511912345678",
+            "+",
+            "+0511912345678",
+            "+123456",
+            "+1234567890123456",
+            "+55 11 9123a5678",
+            "++5511912345678",
+        ] {
+            assert_eq!(
+                validate_phone(phone),
+                Err(PhoneError::InvalidFormat),
+                "{} should be invalid",
+                phone
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_phone() {
+        assert_eq!(normalize_phone(" +55 (11) 91234-5678 "), "+5511912345678");
+        assert_eq!(normalize_phone("+14155552671"), "+14155552671");
+    }
+
+    #[test]
+    fn test_validate_address() {
+        assert_eq!(
+            validate_address("Av. Paulista, 1000", Some("Apto 12"), "São Paulo", Some("SP"), "01310-100", "BR"),
+            Ok(())
+        );
+        assert_eq!(validate_address("1 Main St", None, "Dublin", None, "", "IE"), Ok(()));
+        assert_eq!(validate_address(" ", None, "Dublin", None, "", "IE"), Err(AddressError::StreetEmpty));
+        assert_eq!(validate_address("1 Main St", None, "", None, "", "IE"), Err(AddressError::CityEmpty));
+        assert_eq!(
+            validate_address("1 Main St", None, "Dublin", None, "D02#X285", "IE"),
+            Err(AddressError::InvalidPostalCode)
+        );
+        assert_eq!(
+            validate_address("1 Main St", None, "Dublin", None, "01234567890", "IE"),
+            Err(AddressError::InvalidPostalCode)
+        );
+        for country in ["", "ie", "IRL", "I1"] {
+            assert_eq!(
+                validate_address("1 Main St", None, "Dublin", None, "", country),
+                Err(AddressError::InvalidCountry)
+            );
+        }
+        assert_eq!(
+            validate_address(&"a".repeat(201), None, "Dublin", None, "", "IE"),
+            Err(AddressError::TooLong)
+        );
+        let long = "a".repeat(MAX_ADDRESS_FIELD_LENGTH + 1);
+        assert_eq!(
+            validate_address("1 Main St", Some(&long), "Dublin", None, "", "IE"),
+            Err(AddressError::TooLong)
+        );
+        assert_eq!(
+            validate_address("1 Main St", None, "Dublin", Some(&long), "", "IE"),
+            Err(AddressError::TooLong)
+        );
+        let longest = "a".repeat(MAX_ADDRESS_FIELD_LENGTH);
+        assert_eq!(
+            validate_address("1 Main St", Some(&longest), "Dublin", Some(&longest), "", "IE"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_normalize_username() {
+        assert_eq!(normalize_username("  @Ana-Silva "), "ana-silva");
+        assert_eq!(normalize_username("john42"), "john42");
+    }
+
+    #[test]
+    fn test_valid_usernames() {
+        for username in ["ana", "ana-silva", "john42", "a-b-c", "007", &"x".repeat(30)] {
+            assert_eq!(validate_username(username), Ok(()), "{} should be valid", username);
+        }
+    }
+
+    #[test]
+    fn test_invalid_usernames() {
+        assert_eq!(validate_username("ab"), Err(UsernameError::TooShort));
+        assert_eq!(validate_username(&"x".repeat(31)), Err(UsernameError::TooLong));
+        for username in ["-ana", "ana-", "ana--silva", "Ana", "ana_silva", "ana.silva", "ana silva", "anã"] {
+            assert_eq!(
+                validate_username(username),
+                Err(UsernameError::InvalidFormat),
+                "{} should be invalid",
+                username
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = ["VIP", " beta", "vip", "  "].map(String::from);
+        assert_eq!(normalize_tags(&tags), vec!["beta".to_string(), "vip".to_string()]);
+        assert_eq!(normalize_tag(" Early-Adopter "), "early-adopter");
+    }
+
+    #[test]
+    fn test_validate_tags() {
+        assert_eq!(validate_tags(&[]), Ok(()));
+        assert_eq!(validate_tags(&["vip".to_string(), "early-adopter".to_string()]), Ok(()));
+        assert_eq!(validate_tag(""), Err(TagError::Empty));
+        assert_eq!(validate_tag(&"x".repeat(25)), Err(TagError::TooLong));
+        for tag in ["-vip", "vip-", "v--ip", "VIP", "v ip", "v_ip"] {
+            assert_eq!(validate_tag(tag), Err(TagError::InvalidFormat), "{} should be invalid", tag);
+        }
+        let tags: Vec<String> = (0..11).map(|i| format!("tag{}", i)).collect();
+        assert_eq!(validate_tags(&tags), Err(TagError::TooMany));
+    }
+}